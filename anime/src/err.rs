@@ -1,4 +1,5 @@
 use std::io;
+use std::path::PathBuf;
 use std::result;
 use std::string;
 use thiserror::Error;
@@ -20,11 +21,46 @@ pub enum Error {
     JsonDecode(#[from] serde_json::Error),
 
     #[error("http error: {0}")]
-    Http(#[from] attohttpc::Error),
+    Http(#[from] reqwest::Error),
+
+    #[error("failed to generate random bytes: {0}")]
+    Random(#[from] getrandom::Error),
+
+    #[error("invalid proxy URL \"{url}\": {source}")]
+    InvalidProxyUrl {
+        url: String,
+        source: url::ParseError,
+    },
+
+    #[error("unsupported proxy scheme: {0}\nonly http, https, socks5, and socks5h proxies are supported")]
+    UnsupportedProxyScheme(String),
+
+    #[error("invalid API URL \"{url}\": {source}")]
+    InvalidApiUrl {
+        url: String,
+        source: url::ParseError,
+    },
+
+    #[error("unsupported API URL scheme: {0}\nonly http and https URLs are supported")]
+    UnsupportedApiScheme(String),
 
     #[error("failed to parse episode: {filename}")]
     EpisodeParseFailed { filename: String },
 
+    #[error("external episode parser {} failed to parse {filename}: {reason}", .program.display())]
+    ExternalParserFailed {
+        program: PathBuf,
+        filename: String,
+        reason: String,
+    },
+
+    #[error("failed to link files:\nfrom: {}\nto: {}\nreason: {source}", .from.display(), .to.display())]
+    LinkFailed {
+        from: PathBuf,
+        to: PathBuf,
+        source: io::Error,
+    },
+
     #[error("found different episode titles:\nexpecting: {expecting}\nfound: {found}")]
     MultipleTitles { expecting: String, found: String },
 
@@ -34,24 +70,48 @@ pub enum Error {
     #[error("bad AniList response ({code}): {message}")]
     BadAniListResponse { code: u16, message: String },
 
+    #[error("bad MyAnimeList response ({code}): {message}")]
+    BadMalResponse { code: u16, message: String },
+
+    #[error("bad Kitsu response ({code}): {message}")]
+    BadKitsuResponse { code: u16, message: String },
+
     #[error("must be authorized to make this request")]
     NeedAuthentication,
 
+    #[error("invalid redirect URL \"{url}\": {source}\nthe whole address you were redirected to must be entered")]
+    InvalidRedirectUrl {
+        url: String,
+        source: url::ParseError,
+    },
+
+    #[error("the redirect URL is from a different authorization request\nopen the auth URL again and enter the address you're redirected to")]
+    AuthStateMismatch,
+
+    #[error("the redirect URL has no authorization code, so access may have been denied")]
+    MissingAuthCode,
+
     #[error("requested series is not an anime")]
     NotAnAnime,
+
+    #[error("this service can't list every entry of a user's list")]
+    ListEntriesUnsupported,
 }
 
 impl Error {
     #[must_use]
     pub fn is_http_code(&self, http_code: u16) -> bool {
-        use attohttpc::ErrorKind;
-
         match self {
-            Error::BadAniListResponse { code, .. } if http_code == *code => true,
-            Error::Http(source) => match source.kind() {
-                ErrorKind::StatusCode(status) => status.as_u16() == http_code,
-                _ => false,
-            },
+            Error::BadAniListResponse { code, .. }
+            | Error::BadMalResponse { code, .. }
+            | Error::BadKitsuResponse { code, .. }
+                if http_code == *code =>
+            {
+                true
+            }
+            Error::Http(source) => {
+                matches!(source.status(), Some(status) if status.as_u16() == http_code)
+            }
             _ => false,
         }
     }
@@ -19,8 +19,25 @@ pub mod remote;
 
 pub use err::{Error, Result};
 
+use std::fmt;
+
+#[cfg(feature = "diesel-support")]
+use {
+    diesel::{
+        deserialize::{self, FromSql},
+        serialize::{self, Output, ToSql},
+        sql_types::SmallInt,
+    },
+    std::io::Write,
+};
+
 /// Represents the type of a series.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "diesel-support",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "SmallInt"
+)]
 pub enum SeriesKind {
     /// TV episodes.
     Season,
@@ -44,6 +61,52 @@ impl Into<&'static str> for SeriesKind {
     }
 }
 
+impl fmt::Display for SeriesKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value: &'static str = (*self).into();
+        write!(f, "{}", value)
+    }
+}
+
+#[cfg(feature = "diesel-support")]
+impl<DB> FromSql<SmallInt, DB> for SeriesKind
+where
+    DB: diesel::backend::Backend,
+    i16: FromSql<SmallInt, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> deserialize::Result<Self> {
+        match i16::from_sql(bytes)? {
+            1 => Ok(SeriesKind::Season),
+            2 => Ok(SeriesKind::Movie),
+            3 => Ok(SeriesKind::Special),
+            4 => Ok(SeriesKind::OVA),
+            5 => Ok(SeriesKind::ONA),
+            6 => Ok(SeriesKind::Music),
+            other => Err(format!("invalid series kind: {}", other).into()),
+        }
+    }
+}
+
+#[cfg(feature = "diesel-support")]
+impl<DB> ToSql<SmallInt, DB> for SeriesKind
+where
+    DB: diesel::backend::Backend,
+    i16: ToSql<SmallInt, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> serialize::Result {
+        let value = match self {
+            SeriesKind::Season => 1,
+            SeriesKind::Movie => 2,
+            SeriesKind::Special => 3,
+            SeriesKind::OVA => 4,
+            SeriesKind::ONA => 5,
+            SeriesKind::Music => 6,
+        };
+
+        value.to_sql(out)
+    }
+}
+
 /// Find the best matching item in `items` via `matcher` and return it if the maximum confidence is greater than `min_confidence`.
 ///
 /// `min_confidence` should be a value between 0.0 and 1.0.
@@ -36,3 +36,126 @@ where
 
     Some(parsed)
 }
+
+/// A title parsed from a folder name, along with the season of the series the folder is for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeasonTitle {
+    /// The title without any season or part markers.
+    pub title: String,
+    /// The season the folder is for, starting from 1.
+    ///
+    /// Each part after the first counts as a season of its own, as they usually have their own entry on anime services.
+    pub season: u32,
+}
+
+/// Parse the title of a folder, along with the season it's for when its name has a marker like `S2`, `Season 2`,
+/// `2nd Season`, or `Part 2`.
+///
+/// When the name doesn't have a marker, the season will be 1.
+pub fn parse_season_title<S>(dir: S) -> Option<SeasonTitle>
+where
+    S: AsRef<Path>,
+{
+    let title = parse_title(dir)?;
+    let words = title.split_whitespace().collect::<Vec<_>>();
+
+    let mut season = None;
+    let mut part = None;
+    let mut remaining = Vec::with_capacity(words.len());
+    let mut index = 0;
+
+    while index < words.len() {
+        let word = words[index];
+        let next = words.get(index + 1).copied();
+
+        if let Some(num) = season_abbreviation(word) {
+            season = Some(num);
+            index += 1;
+            continue;
+        }
+
+        let next_num = next.and_then(number);
+
+        if word.eq_ignore_ascii_case("season") && next_num.is_some() {
+            season = next_num;
+            index += 2;
+            continue;
+        }
+
+        if matches!(next, Some(next) if next.eq_ignore_ascii_case("season")) {
+            if let Some(num) = ordinal(word) {
+                season = Some(num);
+                index += 2;
+                continue;
+            }
+        }
+
+        if (word.eq_ignore_ascii_case("part") || word.eq_ignore_ascii_case("cour"))
+            && next_num.is_some()
+        {
+            part = next_num;
+            index += 2;
+            continue;
+        }
+
+        remaining.push(word);
+        index += 1;
+    }
+
+    let stripped = remaining
+        .join(" ")
+        .trim_matches(|ch: char| ch == '-' || ch == ':' || ch.is_whitespace())
+        .to_string();
+
+    // A folder named after nothing but its season should still be searched for by that name
+    let title = if stripped.is_empty() { title } else { stripped };
+    let season = season.unwrap_or(1) + part.unwrap_or(1) - 1;
+
+    Some(SeasonTitle { title, season })
+}
+
+/// Parses markers like `S2` and `S02`.
+fn season_abbreviation(word: &str) -> Option<u32> {
+    let digits = word.strip_prefix(|ch| ch == 'S' || ch == 's')?;
+
+    if digits.is_empty() || !digits.bytes().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+
+    digits.parse().ok().filter(|&num| num > 0)
+}
+
+/// Parses numbers written with digits or as roman numerals, like `2` or `II`.
+fn number(word: &str) -> Option<u32> {
+    const NUMERALS: [&str; 6] = ["I", "II", "III", "IV", "V", "VI"];
+
+    if let Ok(num) = word.parse() {
+        return Some(num).filter(|&num| num > 0);
+    }
+
+    NUMERALS
+        .iter()
+        .position(|numeral| numeral.eq_ignore_ascii_case(word))
+        .map(|index| index as u32 + 1)
+}
+
+/// Parses ordinals like `2nd` or `Second`.
+fn ordinal(word: &str) -> Option<u32> {
+    const WORDS: [&str; 6] = ["first", "second", "third", "fourth", "fifth", "sixth"];
+
+    if let Some(index) = WORDS
+        .iter()
+        .position(|ordinal| ordinal.eq_ignore_ascii_case(word))
+    {
+        return Some(index as u32 + 1);
+    }
+
+    let digits = word.trim_end_matches(|ch: char| ch.is_ascii_alphabetic());
+    let suffix = word[digits.len()..].to_ascii_lowercase();
+
+    if !matches!(suffix.as_str(), "st" | "nd" | "rd" | "th") {
+        return None;
+    }
+
+    digits.parse().ok().filter(|&num| num > 0)
+}
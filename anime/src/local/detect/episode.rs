@@ -1,21 +1,58 @@
 use super::common::{whitespace, INVALID_TITLE_CHARS};
+use crate::local::ParsedEpisode;
+use crate::SeriesKind;
 use nom::branch::alt;
-use nom::bytes::complete::take_while;
-use nom::character::complete::char;
-use nom::combinator::{map, recognize};
+use nom::bytes::complete::{tag_no_case, take_while};
+use nom::character::complete::{char, one_of};
+use nom::combinator::{map, opt, recognize};
 use nom::multi::separated_list1;
 use nom::sequence::tuple;
 use nom::IResult;
 
 const SEPARATOR_CHAR: u8 = b'-';
 
+/// The episodes contained in an episode file.
+#[derive(Copy, Clone)]
+struct EpisodeSpan {
+    first: u32,
+    last: u32,
+    /// The decimal part of fractional episodes, such as `5` for `12.5`.
+    fraction: Option<u8>,
+}
+
+impl EpisodeSpan {
+    fn new(first: u32, last: Option<u32>, fraction: Option<u8>) -> Self {
+        Self {
+            first,
+            last: last.unwrap_or(first),
+            fraction,
+        }
+    }
+
+    /// Returns false if the span ends before it starts, or is a range of fractional episodes.
+    fn is_valid(&self) -> bool {
+        self.first <= self.last && (self.fraction.is_none() || self.first == self.last)
+    }
+
+    fn into_parsed(self, title: String, category: SeriesKind) -> ParsedEpisode {
+        ParsedEpisode::new(Some(title), self.first, category)
+            .with_span(self.last)
+            .with_fraction(self.fraction)
+    }
+}
+
+/// Parses the single digit after the decimal point of fractional episodes.
+fn fraction_digit(input: &str) -> IResult<&str, u8> {
+    map(one_of("0123456789"), |ch| ch as u8 - b'0')(input)
+}
+
 /// Variant of the default parser that looks for episodes fitting a `<title> - <episode>` format.
 ///
 /// ### Implementation Note
 ///
 /// Internally, this variant reverses the supplied string before and after parsing, as it makes it much easier to parse titles correctly.
 pub mod title_and_episode {
-    use super::{reverse, separator_opt, title, whitespace};
+    use super::{reverse, separator_opt, title, whitespace, EpisodeSpan};
     use crate::local::detect::common::replace_whitespace;
     use crate::local::ParsedEpisode;
     use crate::SeriesKind;
@@ -28,17 +65,16 @@ pub mod title_and_episode {
     pub fn parse(input: &str) -> Option<ParsedEpisode> {
         let input = input.chars().rev().collect::<String>();
 
-        let (_, (_, _, (title, episode, category))) =
+        let (_, (_, _, (title, span, category))) =
             tuple((reverse::tags, whitespace, title_and_episode))(&input).ok()?;
 
         let title = title.chars().rev().collect::<String>();
         let cleaned = replace_whitespace(title);
 
-        let episode = ParsedEpisode::new(Some(cleaned), episode, category);
-        Some(episode)
+        Some(span.into_parsed(cleaned, category))
     }
 
-    fn title_and_episode(input: &str) -> IResult<&str, (&str, u32, SeriesKind)> {
+    fn title_and_episode(input: &str) -> IResult<&str, (&str, EpisodeSpan, SeriesKind)> {
         // Categories can be specified before or after the actual episode
         let ep_with_category = alt((
             map(
@@ -50,7 +86,9 @@ pub mod title_and_episode {
                 |(ep, _, cat)| (ep, cat),
             ),
             // If we only have a category, we should assume that there's only one episode
-            map(reverse::category, |cat| (1, cat)),
+            map(reverse::category, |cat| {
+                (EpisodeSpan::new(1, None, None), cat)
+            }),
         ));
 
         let title_with_category = map(
@@ -84,7 +122,7 @@ pub mod title_and_episode {
 ///
 /// Internally, this variant reverses the supplied string before and after parsing, as it makes it much easier to parse titles correctly.
 pub mod title_episode_desc {
-    use super::{reverse, separator_opt, title, whitespace};
+    use super::{reverse, separator_opt, title, whitespace, EpisodeSpan};
     use crate::local::detect::common::replace_whitespace;
     use crate::local::ParsedEpisode;
     use crate::SeriesKind;
@@ -98,17 +136,16 @@ pub mod title_episode_desc {
     pub fn parse(input: &str) -> Option<ParsedEpisode> {
         let input = input.chars().rev().collect::<String>();
 
-        let (_, (_, _, (title, episode))) =
+        let (_, (_, _, (title, span))) =
             tuple((reverse::tags, whitespace, title_and_episode))(&input).ok()?;
 
         let title = title.chars().rev().collect::<String>();
         let cleaned = replace_whitespace(title);
 
-        let episode = ParsedEpisode::new(Some(cleaned), episode, SeriesKind::Season);
-        Some(episode)
+        Some(span.into_parsed(cleaned, SeriesKind::Season))
     }
 
-    fn title_and_episode(input: &str) -> IResult<&str, (&str, u32)> {
+    fn title_and_episode(input: &str) -> IResult<&str, (&str, EpisodeSpan)> {
         let until_digit = take_till(|c: char| is_digit(c as u8));
         let title_episode = tuple((until_digit, reverse::episode, separator_opt, title));
 
@@ -120,43 +157,76 @@ pub mod title_episode_desc {
 ///
 /// All episodes in this format are assumed to be season episodes.
 pub mod episode_and_title {
-    use super::{separator_opt, title, whitespace};
+    use super::{fraction_digit, separator_opt, title, whitespace, EpisodeSpan};
     use crate::local::detect::common::{replace_whitespace, tags};
     use crate::local::ParsedEpisode;
     use crate::SeriesKind;
     use nom::character::complete::{char, digit1, one_of};
-    use nom::combinator::{map, map_res, opt};
-    use nom::sequence::{separated_pair, tuple};
+    use nom::combinator::{map, map_res, not, opt, verify};
+    use nom::sequence::{preceded, separated_pair, terminated, tuple};
     use nom::IResult;
 
     #[must_use]
     pub fn parse(input: &str) -> Option<ParsedEpisode> {
-        let (_, (_, _, (episode, title))) =
+        let (_, (_, _, (span, title))) =
             tuple((tags, whitespace, episode_and_title))(input).ok()?;
 
         let title = replace_whitespace(title);
-        let episode = ParsedEpisode::new(Some(title), episode, SeriesKind::Season);
+        Some(span.into_parsed(title, SeriesKind::Season))
+    }
 
-        Some(episode)
+    fn episode_and_title(input: &str) -> IResult<&str, (EpisodeSpan, &str)> {
+        separated_pair(episode_span, separator_opt, title)(input)
     }
 
-    fn episode_and_title(input: &str) -> IResult<&str, (u32, &str)> {
-        separated_pair(episode, separator_opt, title)(input)
+    pub(super) fn episode(input: &str) -> IResult<&str, u32> {
+        map(episode_span, |span| span.first)(input)
     }
 
-    fn episode(input: &str) -> IResult<&str, u32> {
-        let ep = map_res(digit1, str::parse);
+    /// Parses an episode, a range of episodes such as `01-02`, or a fractional episode such as `12.5`.
+    fn episode_span(input: &str) -> IResult<&str, EpisodeSpan> {
+        let ep = || map_res(digit1, str::parse::<u32>);
 
         let season_marker = tuple((char('S'), digit1));
         let ep_marker = tuple((opt(season_marker), char('E')));
         let version_suffix = map(tuple((one_of("vV"), digit1)), |_| ());
+        let span_end = preceded(tuple((one_of("-~"), opt(char('E')))), ep());
+        let fraction = terminated(preceded(char('.'), fraction_digit), not(digit1));
+
+        let parsed_episode = tuple((
+            opt(ep_marker),
+            ep(),
+            opt(fraction),
+            opt(span_end),
+            opt(version_suffix),
+        ));
 
-        let parsed_episode = tuple((opt(ep_marker), ep, opt(version_suffix)));
+        let span = map(parsed_episode, |(_, first, fraction, last, _)| {
+            EpisodeSpan::new(first, last, fraction)
+        });
 
-        map(parsed_episode, |(_, ep, _)| ep)(input)
+        verify(span, EpisodeSpan::is_valid)(input)
     }
 }
 
+/// Looks for an episode number at the very start of `input`.
+///
+/// Leading separators and an `Ep` / `Episode` prefix are skipped before the episode is parsed.
+#[must_use]
+pub fn leading_episode(input: &str) -> Option<u32> {
+    let prefix = alt((tag_no_case("episode"), tag_no_case("ep")));
+
+    let (_, (_, _, _, episode)) = tuple((
+        separator_opt,
+        opt(prefix),
+        whitespace,
+        episode_and_title::episode,
+    ))(input)
+    .ok()?;
+
+    Some(episode)
+}
+
 fn title(input: &str) -> IResult<&str, &str> {
     use nom::error::{Error, ErrorKind, ParseError};
     use nom::Err;
@@ -191,14 +261,14 @@ fn separator_opt(input: &str) -> IResult<&str, ()> {
 }
 
 mod reverse {
-    use super::whitespace;
+    use super::{fraction_digit, whitespace, EpisodeSpan};
     use crate::SeriesKind;
     use nom::branch::alt;
     use nom::bytes::complete::{is_not, tag_no_case};
     use nom::character::complete::{char, digit1, one_of};
-    use nom::combinator::{map, map_res, opt};
+    use nom::combinator::{map, map_res, opt, verify};
     use nom::multi::many0;
-    use nom::sequence::{delimited, tuple};
+    use nom::sequence::{delimited, preceded, terminated, tuple};
     use nom::IResult;
 
     macro_rules! maybe_plural {
@@ -228,11 +298,16 @@ mod reverse {
         delimited(char(']'), is_not("["), char('['))(input)
     }
 
-    pub fn episode(input: &str) -> IResult<&str, u32> {
-        let ep = map_res(digit1, |s: &str| {
-            let rev = s.chars().rev().collect::<String>();
-            rev.parse::<u32>()
-        });
+    /// Parses an episode, a range of episodes such as `01-02`, or a fractional episode such as `12.5`.
+    ///
+    /// Since the input is reversed, the last episode of a range and the fractional part of an episode are parsed first.
+    pub fn episode(input: &str) -> IResult<&str, EpisodeSpan> {
+        let ep = || {
+            map_res(digit1, |s: &str| {
+                let rev = s.chars().rev().collect::<String>();
+                rev.parse::<u32>()
+            })
+        };
 
         // These look for one of the following formats:
         // S<season>E<episode>
@@ -254,9 +329,30 @@ mod reverse {
             alt((season_marker, episode_prefix, e_prefix))
         };
 
-        let parsed_episode = tuple((opt(file_version), ep, opt(prefix)));
+        let span_start = preceded(tuple((opt(one_of("Ee")), one_of("-~"))), ep());
+        let fraction = terminated(fraction_digit, char('.'));
+
+        let parsed_episode = tuple((
+            opt(file_version),
+            opt(fraction),
+            ep(),
+            opt(span_start),
+            opt(prefix),
+        ));
+
+        let span = map(
+            parsed_episode,
+            |(_, fraction, last, first, _)| match first {
+                Some(first) => EpisodeSpan {
+                    first,
+                    last,
+                    fraction,
+                },
+                None => EpisodeSpan::new(last, None, fraction),
+            },
+        );
 
-        map(parsed_episode, |(_, ep, _)| ep)(input)
+        verify(span, EpisodeSpan::is_valid)(input)
     }
 
     pub fn file_version(input: &str) -> IResult<&str, ()> {
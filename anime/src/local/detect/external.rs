@@ -0,0 +1,163 @@
+//! Runs a user-provided executable to parse filenames that none of the built-in parsers can handle.
+//!
+//! The executable receives the filename on stdin and should print the episode number and category on stdout,
+//! separated by a tab, such as `12\tSeason`. The category can be left out for regular episodes.
+//!
+//! To limit what the executable can do, it's started without any arguments or environment variables
+//! from the temporary directory, and is killed if it doesn't finish within [`TIMEOUT`].
+
+use super::ParsedEpisode;
+use crate::err::{Error, Result};
+use crate::SeriesKind;
+use std::env;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long the executable has to parse a single filename before it's killed.
+pub const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often to check if the executable has finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// The most output that will be read from the executable, in bytes.
+const MAX_OUTPUT_LEN: u64 = 256;
+
+/// Parse `filename` by running the executable at `program`.
+pub fn parse(program: &Path, filename: &str) -> Result<ParsedEpisode> {
+    let failed = |reason: String| Error::ExternalParserFailed {
+        program: program.into(),
+        filename: filename.into(),
+        reason,
+    };
+
+    let mut child = Command::new(program)
+        .env_clear()
+        .current_dir(env::temp_dir())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| failed(err.to_string()))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The executable is free to exit without reading the filename, so a closed pipe isn't an error
+        stdin.write_all(filename.as_bytes()).ok();
+        stdin.write_all(b"\n").ok();
+    }
+
+    let deadline = Instant::now() + TIMEOUT;
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if Instant::now() >= deadline {
+            child.kill().ok();
+            child.wait().ok();
+
+            return Err(failed(format!(
+                "timed out after {} seconds",
+                TIMEOUT.as_secs()
+            )));
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    if !status.success() {
+        return Err(failed(format!("exited with {}", status)));
+    }
+
+    let mut output = String::new();
+
+    if let Some(stdout) = child.stdout.take() {
+        stdout
+            .take(MAX_OUTPUT_LEN)
+            .read_to_string(&mut output)
+            .map_err(|err| failed(err.to_string()))?;
+    }
+
+    let (episode, category) = parse_output(&output)
+        .ok_or_else(|| failed(format!("unexpected output: {}", output.trim())))?;
+
+    Ok(ParsedEpisode::new(None, episode, category))
+}
+
+/// Parse the episode number and category printed by an external parser.
+fn parse_output(output: &str) -> Option<(u32, SeriesKind)> {
+    let line = output.lines().next()?;
+    let mut fields = line.split('\t');
+
+    let episode = fields.next()?.trim().parse().ok()?;
+
+    let category = match fields.next().map(str::trim) {
+        None | Some("") => SeriesKind::Season,
+        Some(category) => parse_category(category)?,
+    };
+
+    Some((episode, category))
+}
+
+fn parse_category(category: &str) -> Option<SeriesKind> {
+    match category.to_ascii_lowercase().as_ref() {
+        "season" | "episode" => Some(SeriesKind::Season),
+        "movie" => Some(SeriesKind::Movie),
+        "special" => Some(SeriesKind::Special),
+        "ova" => Some(SeriesKind::OVA),
+        "ona" => Some(SeriesKind::ONA),
+        "music" => Some(SeriesKind::Music),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_parsing() {
+        assert_eq!(parse_output("12\tSeason\n"), Some((12, SeriesKind::Season)));
+        assert_eq!(parse_output("3\tova"), Some((3, SeriesKind::OVA)));
+        assert_eq!(parse_output("7\n"), Some((7, SeriesKind::Season)));
+        assert_eq!(
+            parse_output("1\tSpecial\nextra"),
+            Some((1, SeriesKind::Special))
+        );
+        assert_eq!(parse_output("1\tunknown"), None);
+        assert_eq!(parse_output("one\tSeason"), None);
+        assert_eq!(parse_output(""), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_executable() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = env::temp_dir().join("anime_external_parser");
+        fs::create_dir_all(&dir).unwrap();
+
+        let script = dir.join("parser.sh");
+        fs::write(
+            &script,
+            "#!/bin/sh\nread name\ncase \"$name\" in\n  *OVA*) printf '2\\tOVA\\n' ;;\n  *) exit 1 ;;\nesac\n",
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let parsed = parse(&script, "Series Title OVA Two.mkv");
+        let failed = parse(&script, "Series Title.mkv");
+
+        fs::remove_dir_all(&dir).ok();
+
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.episode, 2);
+        assert_eq!(parsed.category, SeriesKind::OVA);
+
+        assert!(matches!(failed, Err(Error::ExternalParserFailed { .. })));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod dir;
 pub mod episode;
+pub mod external;
+pub mod tokenizer;
 
 mod common;
 
@@ -7,7 +9,8 @@ use crate::err::{Error, Result};
 use crate::SeriesKind;
 use smallvec::{smallvec, SmallVec};
 use std::borrow::Cow;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, RangeInclusive};
+use std::path::PathBuf;
 use std::str;
 
 #[cfg(feature = "diesel-support")]
@@ -35,6 +38,11 @@ use {
 /// `<tags> <episode> <title> <tags>`
 ///
 /// The default parser also accounts for different types of whitespace characters, such as '.' and '_'.
+///
+/// For release naming schemes that the default parser struggles with, [`EpisodeParser::Tokenizer`] can be used instead.
+/// See the [`tokenizer`] module for more info.
+///
+/// Filenames that none of the built-in parsers can handle can be parsed by an executable with [`EpisodeParser::External`].
 #[derive(Clone, Debug)]
 #[cfg_attr(
     feature = "diesel-support",
@@ -44,9 +52,18 @@ use {
 pub enum EpisodeParser {
     Default,
     Custom(CustomPattern),
+    /// Splits filenames into tokens to find the title, episode, file version, release group, and resolution.
+    Tokenizer,
+    /// Runs the executable at the path to parse each filename. See the [`external`] module for more info.
+    External(PathBuf),
 }
 
 impl EpisodeParser {
+    /// The text used to select and store the tokenizer parser in place of a custom pattern.
+    pub const TOKENIZER_KEYWORD: &'static str = "@tokenizer";
+    /// The text placed before the path of the executable to select and store an external parser in place of a custom pattern.
+    pub const EXTERNAL_PREFIX: &'static str = "@external:";
+
     /// Create a new [`EpisodeParser::Custom`] with the specified custom pattern.
     ///
     /// # Example
@@ -92,6 +109,12 @@ impl EpisodeParser {
         match self {
             Self::Default => Self::parse_with_default(filename),
             Self::Custom(pattern) => Self::parse_with_pattern(pattern, filename),
+            Self::Tokenizer => {
+                tokenizer::parse(&filename).ok_or_else(|| Error::EpisodeParseFailed {
+                    filename: filename.into(),
+                })
+            }
+            Self::External(program) => external::parse(program, &filename),
         }
     }
 
@@ -143,9 +166,10 @@ impl Default for EpisodeParser {
 impl PartialEq for EpisodeParser {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (Self::Default, Self::Default) => true,
-            (Self::Default, Self::Custom(_)) | (Self::Custom(_), Self::Default) => false,
+            (Self::Default, Self::Default) | (Self::Tokenizer, Self::Tokenizer) => true,
             (Self::Custom(pat1), Self::Custom(pat2)) => pat1 == pat2,
+            (Self::External(path1), Self::External(path2)) => path1 == path2,
+            _ => false,
         }
     }
 }
@@ -171,6 +195,15 @@ where
     fn from_sql(bytes: Option<&DB::RawValue>) -> deserialize::Result<Self> {
         if bytes.is_some() {
             let pattern = CustomPattern::from_sql(bytes)?;
+
+            if pattern.inner() == Self::TOKENIZER_KEYWORD {
+                return Ok(Self::Tokenizer);
+            }
+
+            if let Some(program) = pattern.inner().strip_prefix(Self::EXTERNAL_PREFIX) {
+                return Ok(Self::External(program.into()));
+            }
+
             Ok(Self::Custom(pattern))
         } else {
             Ok(Self::default())
@@ -190,6 +223,11 @@ where
         match self {
             Self::Default => Ok(IsNull::Yes),
             Self::Custom(pattern) => pattern.to_sql(out),
+            Self::Tokenizer => ToSql::<Text, DB>::to_sql(Self::TOKENIZER_KEYWORD, out),
+            Self::External(program) => {
+                let value = format!("{}{}", Self::EXTERNAL_PREFIX, program.display());
+                ToSql::<Text, DB>::to_sql(value.as_str(), out)
+            }
         }
     }
 }
@@ -202,6 +240,10 @@ where
 /// * `*` is a wildcard and will match everything up to the next character in the pattern.
 /// * `#` is an episode marker and will only match digits. Everything after this character is ignored.
 ///
+/// If a pattern does not contain an episode marker, it will be treated as a prefix instead.
+/// Once the entire pattern has been matched, the episode number will be parsed from whatever directly follows it,
+/// skipping any separators and `Ep` / `Episode` prefixes along the way.
+///
 /// Both pattern characters can be escaped by having at least two of them next to each other, like so:
 /// * `**`
 /// * `##`
@@ -213,6 +255,9 @@ where
 ///
 /// let pattern = CustomPattern::new("[*] Series Title - EP#");
 /// assert_eq!(pattern.detect_episode("[Test Tag] Series Title - ep12"), Some(12));
+///
+/// let pattern = CustomPattern::new("[*] Series Title");
+/// assert_eq!(pattern.detect_episode("[Test Tag] Series Title - 12 [1080p]"), Some(12));
 /// ```
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(
@@ -258,7 +303,8 @@ impl CustomPattern {
 
     /// Executes the current pattern to find an episode number in the specified `value`.
     ///
-    /// This will always return `None` if the current pattern does not have a `#` character to mark the location of episodes.
+    /// If the current pattern does not have a `#` character to mark the location of episodes,
+    /// the episode will be parsed from the text directly after the matched pattern.
     pub fn detect_episode<S>(&self, value: S) -> Option<u32>
     where
         S: AsRef<str>,
@@ -267,7 +313,14 @@ impl CustomPattern {
         let mut pattern_chars = self.0.chars().peekable();
         let mut cur_pattern_char = pattern_chars.next();
 
-        while let Some(value_ch) = value_chars.next() {
+        loop {
+            let remaining = value_chars.as_str();
+
+            let value_ch = match value_chars.next() {
+                Some(ch) => ch,
+                None => break,
+            };
+
             match cur_pattern_char {
                 Some(Self::WILDCARD) => match pattern_chars.peek() {
                     Some(&Self::EPISODE_MARKER) if value_ch.is_ascii_digit() => {
@@ -295,7 +348,9 @@ impl CustomPattern {
                 Some(ch) if ch.eq_ignore_ascii_case(&value_ch) => {
                     cur_pattern_char = pattern_chars.next()
                 }
-                Some(_) | None => break,
+                Some(_) => break,
+                // We've matched the entire pattern without finding an episode marker, so fall back to looking right after it
+                None => return episode::leading_episode(remaining),
             }
         }
 
@@ -309,6 +364,27 @@ impl CustomPattern {
         self.0.contains(Self::EPISODE_MARKER)
     }
 
+    /// Returns true if the current pattern is able to detect episodes.
+    ///
+    /// This is the case when the pattern either contains an episode marker, or can be used as a prefix
+    /// (i.e. it is not empty and does not end with a wildcard).
+    #[must_use]
+    pub fn can_detect_episodes(&self) -> bool {
+        if self.has_episode_marker() {
+            return true;
+        }
+
+        let trailing_wildcards = self
+            .0
+            .chars()
+            .rev()
+            .take_while(|&ch| ch == Self::WILDCARD)
+            .count();
+
+        // An even number of trailing wildcards means they're all escaped
+        !self.0.is_empty() && trailing_wildcards % 2 == 0
+    }
+
     /// Returns a reference to the pattern string.
     #[inline(always)]
     #[must_use]
@@ -366,8 +442,30 @@ pub struct ParsedEpisode {
     /// The parsed title of the episode file.
     pub title: Option<String>,
     /// The parsed episode number of the episode file.
+    ///
+    /// For files that contain multiple episodes, this is the first episode.
     pub episode: u32,
+    /// The last episode contained in the episode file.
+    ///
+    /// This is the same as `episode` unless the file contains multiple episodes, such as `01-02`.
+    pub last_episode: u32,
+    /// The decimal part of fractional episode numbers, such as `5` for recap episodes numbered `12.5`.
+    ///
+    /// Fractional episodes never contain multiple episodes.
+    pub fraction: Option<u8>,
     pub category: SeriesKind,
+    /// The file version, such as `2` for `v2` releases.
+    ///
+    /// This is only detected by [`EpisodeParser::Tokenizer`].
+    pub version: Option<u32>,
+    /// The group that released the episode file.
+    ///
+    /// This is only detected by [`EpisodeParser::Tokenizer`].
+    pub release_group: Option<String>,
+    /// The video resolution, such as `1080p`.
+    ///
+    /// This is only detected by [`EpisodeParser::Tokenizer`].
+    pub resolution: Option<String>,
 }
 
 impl ParsedEpisode {
@@ -376,9 +474,33 @@ impl ParsedEpisode {
         Self {
             title,
             episode,
+            last_episode: episode,
+            fraction: None,
             category,
+            version: None,
+            release_group: None,
+            resolution: None,
         }
     }
+
+    #[inline(always)]
+    fn with_span(mut self, last_episode: u32) -> Self {
+        self.last_episode = last_episode.max(self.episode);
+        self
+    }
+
+    #[inline(always)]
+    fn with_fraction(mut self, fraction: Option<u8>) -> Self {
+        self.fraction = fraction;
+        self
+    }
+
+    /// Returns every episode contained in the episode file.
+    #[inline(always)]
+    #[must_use]
+    pub fn episodes(&self) -> RangeInclusive<u32> {
+        self.episode..=self.last_episode
+    }
 }
 
 #[cfg(test)]
@@ -568,6 +690,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn multi_episode_detection() {
+        let formats = vec![
+            ("Series Title - 01-02.mkv", 1..=2),
+            ("[Header 1] Series Title - 03~05 [1080p].mkv", 3..=5),
+            ("[Header 1] Series Title - S01E07-E08.mkv", 7..=8),
+            ("[Header 1] 09-10 - Series Title.mkv", 9..=10),
+            ("Series Title - 11v2.mkv", 11..=11),
+        ];
+
+        let parser = EpisodeParser::default();
+
+        for (format, expected) in formats {
+            let parsed = parser
+                .parse(format)
+                .unwrap_or_else(|err| panic!("failed to parse {}: {}", format, err));
+
+            assert_eq!(parsed.title.as_deref(), Some("Series Title"), "{}", format);
+            assert_eq!(parsed.episodes(), expected, "{}", format);
+        }
+    }
+
+    #[test]
+    fn fractional_episode_detection() {
+        let formats = vec![
+            ("Series Title - 12.5.mkv", 12, Some(5)),
+            ("[Header 1] Series Title - 06.5v2 [1080p].mkv", 6, Some(5)),
+            ("[Header 1] Series Title - E12.5 [1080p].mkv", 12, Some(5)),
+            ("[Header 1] 12.5 - Series Title.mkv", 12, Some(5)),
+            ("Series Title - 12.mkv", 12, None),
+        ];
+
+        let parser = EpisodeParser::default();
+
+        for (format, episode, fraction) in formats {
+            let parsed = parser
+                .parse(format)
+                .unwrap_or_else(|err| panic!("failed to parse {}: {}", format, err));
+
+            assert_eq!(parsed.title.as_deref(), Some("Series Title"), "{}", format);
+            assert_eq!(parsed.episode, episode, "{}", format);
+            assert_eq!(parsed.fraction, fraction, "{}", format);
+        }
+
+        assert!(parser.parse("Series Title - 12.5-13.mkv").is_err());
+    }
+
     #[test]
     fn ambiguous_episode_format_detection() {
         let formats = vec![
@@ -614,6 +783,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn season_title_detection() {
+        let titles = [
+            ("Series Title", "Series Title", 1),
+            ("[Tag] Series Title S2 (01-12)", "Series Title", 2),
+            ("Series.Title.S02", "Series Title", 2),
+            ("Series Title - Season 3", "Series Title", 3),
+            ("Series Title 2nd Season", "Series Title", 2),
+            ("Series Title Second Season [Tag]", "Series Title", 2),
+            ("Series Title Part 2", "Series Title", 2),
+            ("Series Title Part II", "Series Title", 2),
+            ("Series Title Season 2 Part 2", "Series Title", 3),
+            ("Series Title Season", "Series Title Season", 1),
+            ("Series Title S", "Series Title S", 1),
+            ("Season 2", "Season 2", 2),
+        ];
+
+        for (dir, title, season) in titles {
+            let expected = dir::SeasonTitle {
+                title: title.into(),
+                season,
+            };
+
+            assert_eq!(
+                dir::parse_season_title(dir),
+                Some(expected),
+                "season title mismatch: {}",
+                dir
+            );
+        }
+    }
+
     #[test]
     fn custom_pattern_detection() {
         let pairs = vec![
@@ -663,6 +864,22 @@ mod tests {
             ("Series Title", "Series Title", None),
             ("Series Title #", "Series Title", None),
             ("*", "Test 12", None),
+            ("Series Title", "Series Title - 12.mkv", Some(12)),
+            (
+                "[*] Series Title",
+                "[Tag] Series Title - 12 [1080p].mkv",
+                Some(12),
+            ),
+            (
+                "[*] Series Title -",
+                "[Tag] Series Title - E12v2.mkv",
+                Some(12),
+            ),
+            ("*Title", "Series Title.Ep.12.mkv", Some(12)),
+            ("*Title", "Series Title Episode 3", Some(3)),
+            ("[*] Series Title", "[Tag] Series Title - S01E07", Some(7)),
+            ("Series Title", "Series Title - Description.mkv", None),
+            ("Series Title", "Series Title", None),
         ];
 
         for (format, value, expected) in pairs {
@@ -676,4 +893,28 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn custom_pattern_can_detect_episodes() {
+        let pairs = vec![
+            ("", false),
+            ("*", false),
+            ("[*] Series Title *", false),
+            ("[*] Series Title", true),
+            ("[*] Series Title**", true),
+            ("[*] Series Title - #", true),
+            ("*#", true),
+        ];
+
+        for (format, expected) in pairs {
+            let pattern = CustomPattern::new(format);
+
+            assert_eq!(
+                pattern.can_detect_episodes(),
+                expected,
+                "pattern: {}",
+                format
+            );
+        }
+    }
 }
@@ -0,0 +1,632 @@
+//! A parser that splits filenames into tokens and classifies each one, in a similar fashion to anitomy.
+//!
+//! This copes with release naming schemes that the default parser struggles with, such as
+//! filenames with CRC32 hashes, resolution / codec tags, file versions, and multi-episode spans.
+
+use crate::local::ParsedEpisode;
+use crate::SeriesKind;
+
+/// Words that only describe the encoding or source of an episode file.
+const METADATA_KEYWORDS: [&str; 27] = [
+    "aac",
+    "ac3",
+    "av1",
+    "avc",
+    "bd",
+    "bdrip",
+    "bluray",
+    "dts",
+    "dual",
+    "dual-audio",
+    "dvd",
+    "flac",
+    "h264",
+    "h265",
+    "hdr",
+    "hevc",
+    "hi10",
+    "hi10p",
+    "mp3",
+    "opus",
+    "raw",
+    "web",
+    "web-dl",
+    "webrip",
+    "x264",
+    "x265",
+    "10bit",
+];
+
+/// Words that indicate the next word is an episode number.
+const EPISODE_PREFIXES: [&str; 4] = ["e", "ep", "ep.", "episode"];
+
+/// An episode number found in a filename.
+struct EpisodeNumber {
+    first: u32,
+    /// The last episode of a multi-episode span, or `first` otherwise.
+    last: u32,
+    /// The decimal part of fractional episodes, such as `5` for `12.5`.
+    fraction: Option<u8>,
+    version: Option<u32>,
+}
+
+impl EpisodeNumber {
+    fn new(first: u32, last: u32, version: Option<u32>) -> Self {
+        Self {
+            first,
+            last,
+            fraction: None,
+            version,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    /// Text surrounded by brackets, parenthesis, or braces.
+    Enclosed(&'a str),
+    /// A single word outside of any enclosing characters.
+    Word(&'a str),
+    /// A lone dash, which usually separates the title from the episode.
+    Dash,
+}
+
+/// Parse `filename` by splitting it into tokens.
+///
+/// Returns None if no episode number could be found.
+#[must_use]
+pub fn parse(filename: &str) -> Option<ParsedEpisode> {
+    let tokens = tokenize(strip_extension(filename));
+    let mut parsed = ParsedEpisode::new(None, 0, SeriesKind::Season);
+
+    if let Some(Token::Enclosed(group)) = tokens.first() {
+        if !is_metadata(group) {
+            parsed.release_group = Some(group.trim().to_string());
+        }
+    }
+
+    for token in &tokens {
+        let words = match token {
+            Token::Enclosed(text) => text.split(is_delimiter).collect(),
+            Token::Word(word) => vec![*word],
+            Token::Dash => continue,
+        };
+
+        for word in words {
+            if let Some(resolution) = parse_resolution(word) {
+                parsed.resolution.get_or_insert(resolution);
+            } else if let Some(version) = parse_version(word) {
+                parsed.version.get_or_insert(version);
+            }
+        }
+    }
+
+    // Everything we care about in the title / episode section is outside of enclosing characters
+    let words = tokens
+        .iter()
+        .enumerate()
+        .filter_map(|(index, token)| match token {
+            Token::Word(word) if !is_metadata(word) => Some((index, *word)),
+            Token::Dash => Some((index, "-")),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let (episode_pos, episode) = find_episode(&words)?;
+
+    parsed.episode = episode.first;
+    parsed.last_episode = episode.last;
+    parsed.fraction = episode.fraction;
+
+    if episode.version.is_some() {
+        parsed.version = episode.version;
+    }
+
+    let title_words = if episode_pos == 0 {
+        // The <episode> - <title> format, where the title ends at the next dash or enclosed token
+        let after = &words[1..];
+        let start = after.iter().take_while(|(_, word)| *word == "-").count();
+
+        adjacent_words(&after[start..])
+            .into_iter()
+            .take_while(|&word| word != "-")
+            .collect()
+    } else {
+        // Anything separated from the episode by an enclosed token is unlikely to be part of the title
+        let start = (1..=episode_pos)
+            .rev()
+            .take_while(|&pos| words[pos - 1].0 + 1 == words[pos].0)
+            .last()
+            .map_or(episode_pos, |pos| pos - 1);
+
+        words[start..episode_pos]
+            .iter()
+            .map(|&(_, word)| word)
+            .collect()
+    };
+
+    let (title_words, category) = split_category(title_words, &words, episode_pos);
+    let title = trim_title(&title_words).join(" ");
+
+    parsed.category = category;
+
+    if !title.is_empty() {
+        parsed.title = Some(title);
+    }
+
+    Some(parsed)
+}
+
+/// Returns the words at the start of `words` that directly follow each other without any enclosed tokens in between.
+fn adjacent_words<'a>(words: &[(usize, &'a str)]) -> Vec<&'a str> {
+    let mut adjacent = Vec::with_capacity(words.len());
+
+    for (pos, &(index, word)) in words.iter().enumerate() {
+        if pos > 0 && words[pos - 1].0 + 1 != index {
+            break;
+        }
+
+        adjacent.push(word);
+    }
+
+    adjacent
+}
+
+/// Removes dashes and episode prefixes from either end of `title`.
+fn trim_title<'a, 'b>(mut title: &'b [&'a str]) -> &'b [&'a str] {
+    let is_trimmable = |word: &str| {
+        word == "-"
+            || EPISODE_PREFIXES
+                .iter()
+                .any(|prefix| word.eq_ignore_ascii_case(prefix))
+    };
+
+    while let Some((first, rest)) = title.split_first() {
+        if *first != "-" {
+            break;
+        }
+
+        title = rest;
+    }
+
+    while let Some((last, rest)) = title.split_last() {
+        if !is_trimmable(last) {
+            break;
+        }
+
+        title = rest;
+    }
+
+    title
+}
+
+/// Returns the position in `words` of the episode number, along with the episode itself.
+fn find_episode(words: &[(usize, &str)]) -> Option<(usize, EpisodeNumber)> {
+    let mut fallback = None;
+
+    for (pos, &(_, word)) in words.iter().enumerate() {
+        let prev = pos.checked_sub(1).map(|prev| words[prev].1);
+
+        if let Some(episode) = parse_marked_episode(word) {
+            return Some((pos, episode));
+        }
+
+        let episode = match parse_episode_number(word) {
+            Some(episode) => episode,
+            None => continue,
+        };
+
+        let is_marked = prev.map_or(false, |prev| {
+            prev == "-"
+                || EPISODE_PREFIXES
+                    .iter()
+                    .any(|p| prev.eq_ignore_ascii_case(p))
+        });
+
+        if is_marked {
+            return Some((pos, episode));
+        }
+
+        // Unmarked numbers at the very start are only used when nothing better can be found
+        if pos > 0 || fallback.is_none() {
+            fallback = Some((pos, episode));
+        }
+    }
+
+    fallback
+}
+
+/// Parses episodes that are explicitly marked, such as `S01E12`, `E12`, and `Ep12`.
+fn parse_marked_episode(word: &str) -> Option<EpisodeNumber> {
+    let lower = word.to_ascii_lowercase();
+
+    let rest = if let Some(rest) = lower.strip_prefix('s') {
+        let season_len = rest.chars().take_while(char::is_ascii_digit).count();
+
+        if season_len == 0 {
+            return None;
+        }
+
+        rest[season_len..].strip_prefix('e')?
+    } else {
+        lower
+            .strip_prefix("episode")
+            .or_else(|| lower.strip_prefix("ep"))
+            .or_else(|| lower.strip_prefix('e'))?
+    };
+
+    parse_episode_number(rest)
+}
+
+/// Parses plain episode numbers, with an optional file version (`12v2`), multi-episode span (`01-02`),
+/// or fractional part (`12.5`).
+fn parse_episode_number(word: &str) -> Option<EpisodeNumber> {
+    let (first, rest) = split_number(word)?;
+
+    if rest.is_empty() {
+        return Some(EpisodeNumber::new(first, first, None));
+    }
+
+    if let Some(version) = parse_version(rest) {
+        return Some(EpisodeNumber::new(first, first, Some(version)));
+    }
+
+    if let Some((fraction, rest)) = split_fraction(rest) {
+        let version = match rest {
+            "" => None,
+            rest => Some(parse_version(rest)?),
+        };
+
+        let mut episode = EpisodeNumber::new(first, first, version);
+        episode.fraction = Some(fraction);
+        return Some(episode);
+    }
+
+    // Multi-episode spans
+    let span_end = rest
+        .strip_prefix('-')
+        .or_else(|| rest.strip_prefix('~'))?
+        .trim_start_matches(['e', 'E']);
+
+    let (last, rest) = split_number(span_end)?;
+
+    if last < first {
+        return None;
+    }
+
+    if rest.is_empty() {
+        Some(EpisodeNumber::new(first, last, None))
+    } else {
+        parse_version(rest).map(|version| EpisodeNumber::new(first, last, Some(version)))
+    }
+}
+
+/// Splits the number at the start of `word` from the rest of it.
+fn split_number(word: &str) -> Option<(u32, &str)> {
+    let digits = word.chars().take_while(char::is_ascii_digit).count();
+
+    if digits == 0 || digits > 4 {
+        return None;
+    }
+
+    let number = word[..digits].parse().ok()?;
+    Some((number, &word[digits..]))
+}
+
+/// Splits a single digit fractional part, such as `.5`, from the start of `word`.
+fn split_fraction(word: &str) -> Option<(u8, &str)> {
+    let rest = word.strip_prefix('.')?;
+    let digit = rest.chars().next()?.to_digit(10)?;
+    let rest = &rest[1..];
+
+    if rest.starts_with(|ch: char| ch.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((digit as u8, rest))
+}
+
+/// Parses a file version, such as `v2`.
+fn parse_version(word: &str) -> Option<u32> {
+    let version = word.strip_prefix(['v', 'V'])?;
+
+    if version.is_empty() || !version.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+
+    version.parse().ok()
+}
+
+/// Parses a video resolution, such as `1080p` or `1920x1080`.
+fn parse_resolution(word: &str) -> Option<String> {
+    let lower = word.to_ascii_lowercase();
+
+    let is_resolution = match lower.strip_suffix('p') {
+        Some(height) => {
+            (3..=4).contains(&height.len()) && height.chars().all(|ch| ch.is_ascii_digit())
+        }
+        None => match lower.split_once('x') {
+            Some((width, height)) => {
+                !width.is_empty()
+                    && !height.is_empty()
+                    && width
+                        .chars()
+                        .chain(height.chars())
+                        .all(|ch| ch.is_ascii_digit())
+            }
+            None => false,
+        },
+    };
+
+    if is_resolution {
+        Some(lower)
+    } else {
+        None
+    }
+}
+
+/// Returns true if `word` is a CRC32 hash.
+fn is_hash(word: &str) -> bool {
+    word.len() == 8 && word.chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+/// Returns true if every word in `text` describes the encoding / source of the file rather than its content.
+fn is_metadata(text: &str) -> bool {
+    let mut words = text.split(is_delimiter).filter(|word| !word.is_empty());
+
+    words.all(|word| {
+        is_hash(word)
+            || parse_resolution(word).is_some()
+            || parse_version(word).is_some()
+            || METADATA_KEYWORDS
+                .iter()
+                .any(|keyword| word.eq_ignore_ascii_case(keyword))
+    })
+}
+
+/// Removes a category marker (such as `OVA`) from the end of the title, or finds one directly after the episode.
+fn split_category<'a>(
+    mut title: Vec<&'a str>,
+    words: &[(usize, &'a str)],
+    episode_pos: usize,
+) -> (Vec<&'a str>, SeriesKind) {
+    let title_len = trim_title(&title).len();
+
+    if let Some(category) = title_len
+        .checked_sub(1)
+        .and_then(|last| parse_category(title[last]))
+    {
+        title.truncate(title_len - 1);
+        return (title, category);
+    }
+
+    let after_episode = words.get(episode_pos + 1).map(|&(_, word)| word);
+
+    match after_episode.and_then(parse_category) {
+        Some(category) => (title, category),
+        None => (title, SeriesKind::Season),
+    }
+}
+
+fn parse_category(word: &str) -> Option<SeriesKind> {
+    let lower = word.to_ascii_lowercase();
+    let singular = lower.strip_suffix('s').unwrap_or(&lower);
+
+    match singular {
+        "ova" => Some(SeriesKind::OVA),
+        "ona" => Some(SeriesKind::ONA),
+        "special" => Some(SeriesKind::Special),
+        "movie" => Some(SeriesKind::Movie),
+        _ => None,
+    }
+}
+
+fn is_delimiter(ch: char) -> bool {
+    ch == ' ' || ch == '_' || ch == ','
+}
+
+/// Removes the file extension from `filename`, if it has one.
+fn strip_extension(filename: &str) -> &str {
+    match filename.rsplit_once('.') {
+        Some((name, ext))
+            if (1..=4).contains(&ext.len())
+                && ext.chars().all(|ch| ch.is_ascii_alphanumeric())
+                && !ext.chars().all(|ch| ch.is_ascii_digit()) =>
+        {
+            name
+        }
+        _ => filename,
+    }
+}
+
+fn tokenize(filename: &str) -> Vec<Token<'_>> {
+    // Dots are only treated as spaces when the filename has no real spaces, as they're otherwise likely part of the title
+    let dots_are_spaces = !filename.contains(' ');
+
+    let mut tokens = Vec::new();
+    let mut rest = filename;
+
+    while !rest.is_empty() {
+        let closing = match rest.chars().next() {
+            Some('[') => Some(']'),
+            Some('(') => Some(')'),
+            Some('{') => Some('}'),
+            _ => None,
+        };
+
+        if let Some(closing) = closing {
+            if let Some(end) = rest.find(closing) {
+                tokens.push(Token::Enclosed(&rest[1..end]));
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+
+        let end = rest[1..]
+            .find(['[', '(', '{'])
+            .map_or(rest.len(), |end| end + 1);
+
+        let words = rest[..end]
+            .split(|ch| is_delimiter(ch) || (dots_are_spaces && ch == '.'))
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                if word == "-" || word == "~" {
+                    Token::Dash
+                } else {
+                    Token::Word(word)
+                }
+            });
+
+        tokens.extend(words);
+        rest = &rest[end..];
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenizer_detection() {
+        let formats = vec![
+            (
+                "[Group] Series Title - 12 [1080p][ABCD1234].mkv",
+                Some("Series Title"),
+                12,
+                Some("Group"),
+                Some("1080p"),
+                None,
+            ),
+            (
+                "[Group] Series Title - 12v2 (BD 1920x1080 HEVC FLAC) [0F1E2D3C].mkv",
+                Some("Series Title"),
+                12,
+                Some("Group"),
+                Some("1920x1080"),
+                Some(2),
+            ),
+            (
+                "Series.Title.S01E12.1080p.WEB.x264-GROUP.mkv",
+                Some("Series Title"),
+                12,
+                None,
+                Some("1080p"),
+                None,
+            ),
+            (
+                "[Group] Series Title 2 - 12-13 [720p].mkv",
+                Some("Series Title 2"),
+                12,
+                Some("Group"),
+                Some("720p"),
+                None,
+            ),
+            (
+                "[Group] 86 - 03 [1080p].mkv",
+                Some("86"),
+                3,
+                Some("Group"),
+                Some("1080p"),
+                None,
+            ),
+            (
+                "[Group] Series Title Ep 12 [v3][480p].mkv",
+                Some("Series Title"),
+                12,
+                Some("Group"),
+                Some("480p"),
+                Some(3),
+            ),
+            (
+                "12 - Episode Description.mkv",
+                Some("Episode Description"),
+                12,
+                None,
+                None,
+                None,
+            ),
+            (
+                "[1080p] Series_Title_-_12_[Group].mkv",
+                Some("Series Title"),
+                12,
+                None,
+                Some("1080p"),
+                None,
+            ),
+        ];
+
+        for (filename, title, episode, group, resolution, version) in formats {
+            let parsed = parse(filename).unwrap_or_else(|| panic!("failed to parse {}", filename));
+
+            assert_eq!(
+                parsed.title.as_deref(),
+                title,
+                "title mismatch: {}",
+                filename
+            );
+            assert_eq!(parsed.episode, episode, "episode mismatch: {}", filename);
+            assert_eq!(
+                parsed.release_group.as_deref(),
+                group,
+                "release group mismatch: {}",
+                filename
+            );
+            assert_eq!(
+                parsed.resolution.as_deref(),
+                resolution,
+                "resolution mismatch: {}",
+                filename
+            );
+            assert_eq!(parsed.version, version, "version mismatch: {}", filename);
+            assert_eq!(parsed.category, SeriesKind::Season, "{}", filename);
+        }
+    }
+
+    #[test]
+    fn tokenizer_span_detection() {
+        let parsed = parse("[Group] Series Title - 01-02v2 [1080p].mkv").unwrap();
+        assert_eq!(parsed.episodes(), 1..=2);
+        assert_eq!(parsed.version, Some(2));
+
+        let parsed = parse("Series.Title.S01E03-E05.1080p.mkv").unwrap();
+        assert_eq!(parsed.episodes(), 3..=5);
+
+        let parsed = parse("[Group] Series Title - 04 [1080p].mkv").unwrap();
+        assert_eq!(parsed.episodes(), 4..=4);
+    }
+
+    #[test]
+    fn tokenizer_fraction_detection() {
+        let parsed = parse("[Group] Series Title - 12.5 [1080p].mkv").unwrap();
+        assert_eq!(parsed.title.as_deref(), Some("Series Title"));
+        assert_eq!(parsed.episode, 12);
+        assert_eq!(parsed.fraction, Some(5));
+
+        let parsed = parse("[Group] Series Title - 06.5v2 [1080p].mkv").unwrap();
+        assert_eq!(parsed.episode, 6);
+        assert_eq!(parsed.fraction, Some(5));
+        assert_eq!(parsed.version, Some(2));
+
+        let parsed = parse("[Group] Series Title - 12 [1080p].mkv").unwrap();
+        assert_eq!(parsed.fraction, None);
+    }
+
+    #[test]
+    fn tokenizer_category_detection() {
+        let parsed = parse("[Group] Series Title OVA - 02 [1080p].mkv").unwrap();
+        assert_eq!(parsed.title.as_deref(), Some("Series Title"));
+        assert_eq!(parsed.category, SeriesKind::OVA);
+        assert_eq!(parsed.episode, 2);
+
+        let parsed = parse("[Group] Series Title - 02 Special [1080p].mkv").unwrap();
+        assert_eq!(parsed.title.as_deref(), Some("Series Title"));
+        assert_eq!(parsed.category, SeriesKind::Special);
+    }
+
+    #[test]
+    fn tokenizer_rejects_files_without_episodes() {
+        assert!(parse("[Group] Series Title [1080p][ABCD1234].mkv").is_none());
+    }
+}
@@ -1,19 +1,27 @@
 pub mod detect;
+pub mod split;
 
 pub use detect::{EpisodeParser, ParsedEpisode};
 
 use crate::err::{Error, Result};
 use crate::SeriesKind;
 use std::cmp::{Ord, Ordering, PartialOrd};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::ops::{Deref, DerefMut};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// An episode on disk.
+///
+/// A single file can contain multiple episodes, in which case `number` is the first episode and `last_number` is the last.
+///
+/// Fractional episodes (such as recaps numbered `12.5`) have their decimal part stored in `fraction`.
+/// They are sorted after the whole episode they share a number with, but are never treated as that episode.
 #[derive(Debug)]
 pub struct Episode {
     pub number: u32,
+    pub last_number: u32,
+    pub fraction: Option<u8>,
     pub filename: String,
 }
 
@@ -21,13 +29,60 @@ impl Episode {
     #[inline(always)]
     #[must_use]
     pub fn new(number: u32, filename: String) -> Self {
-        Self { number, filename }
+        Self::with_span(number, number, filename)
+    }
+
+    /// Create a new `Episode` for a file that contains every episode from `number` to `last_number`.
+    #[inline(always)]
+    #[must_use]
+    pub fn with_span(number: u32, last_number: u32, filename: String) -> Self {
+        Self {
+            number,
+            last_number: last_number.max(number),
+            fraction: None,
+            filename,
+        }
+    }
+
+    /// Create a new `Episode` for a fractional episode, such as `12.5` when `number` is 12 and `fraction` is 5.
+    #[inline(always)]
+    #[must_use]
+    pub fn fractional(number: u32, fraction: u8, filename: String) -> Self {
+        Self {
+            number,
+            last_number: number,
+            fraction: Some(fraction),
+            filename,
+        }
+    }
+
+    /// Returns true if the episode has a fractional number, such as `12.5`.
+    #[inline(always)]
+    #[must_use]
+    pub fn is_fractional(&self) -> bool {
+        self.fraction.is_some()
+    }
+
+    /// Returns the number of episodes the file contains.
+    #[inline(always)]
+    #[must_use]
+    pub fn span_len(&self) -> u32 {
+        self.last_number - self.number + 1
+    }
+
+    /// Returns true if the file contains the episode with the specified `number`.
+    ///
+    /// Fractional episodes never contain a whole episode.
+    #[inline(always)]
+    #[must_use]
+    pub fn contains(&self, number: u32) -> bool {
+        !self.is_fractional() && (self.number..=self.last_number).contains(&number)
     }
 }
 
 impl Ord for Episode {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.number.cmp(&other.number)
+        (self.number, self.fraction).cmp(&(other.number, other.fraction))
     }
 }
 
@@ -39,7 +94,7 @@ impl PartialOrd for Episode {
 
 impl PartialEq for Episode {
     fn eq(&self, other: &Self) -> bool {
-        self.number == other.number
+        self.number == other.number && self.fraction == other.fraction
     }
 }
 
@@ -83,12 +138,29 @@ impl SortedEpisodes {
         self.0.push(episode);
     }
 
-    /// Returns a reference to the episode with the specified `number`.
+    /// Returns a reference to the episode file that contains the episode with the specified `number`.
+    ///
+    /// Fractional episodes are never returned.
     #[inline]
     #[must_use]
     pub fn find(&self, episode_num: u32) -> Option<&Episode> {
         self.0
-            .binary_search_by_key(&episode_num, |ep| ep.number)
+            .binary_search_by(|ep| {
+                if ep.is_fractional() {
+                    // Fractional episodes sort after the whole episode they share a number with
+                    if ep.number < episode_num {
+                        Ordering::Less
+                    } else {
+                        Ordering::Greater
+                    }
+                } else if ep.last_number < episode_num {
+                    Ordering::Less
+                } else if ep.number > episode_num {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
             .ok()
             .map(|index| &self.0[index])
     }
@@ -96,7 +168,53 @@ impl SortedEpisodes {
     #[inline]
     #[must_use]
     pub fn highest_episode_number(&self) -> u32 {
-        self.0.last().map_or(0, |ep| ep.number)
+        self.whole().last().map_or(0, |ep| ep.last_number)
+    }
+
+    /// Returns every episode that does not have a fractional number.
+    #[inline]
+    pub fn whole(&self) -> impl DoubleEndedIterator<Item = &Episode> {
+        self.0.iter().filter(|ep| !ep.is_fractional())
+    }
+
+    /// Returns every episode that has a fractional number, such as `12.5`.
+    #[inline]
+    pub fn fractional(&self) -> impl DoubleEndedIterator<Item = &Episode> {
+        self.0.iter().filter(|ep| ep.is_fractional())
+    }
+
+    /// Returns true if the first episode appears to be a creditless opening or ending that was numbered as a regular episode.
+    ///
+    /// To avoid false positives, this will only return true when there is exactly one more whole episode than `total_episodes`.
+    #[must_use]
+    pub fn has_numbered_opening(&self, total_episodes: u32) -> bool {
+        if self.whole().count() != total_episodes as usize + 1 {
+            return false;
+        }
+
+        self.whole()
+            .next()
+            .map_or(false, |episode| is_opening_or_ending(&episode.filename))
+    }
+
+    /// Shifts the number of every episode by `offset`.
+    ///
+    /// Episodes that end up with a number below 1 will be removed.
+    /// Files that contain multiple episodes will only keep the episodes that are still 1 or above.
+    pub fn apply_offset(&mut self, offset: i32) {
+        if offset == 0 {
+            return;
+        }
+
+        self.0
+            .retain(|episode| i64::from(episode.last_number) + i64::from(offset) >= 1);
+
+        let shift = |number: u32| (i64::from(number) + i64::from(offset)).max(1) as u32;
+
+        for episode in &mut self.0 {
+            episode.number = shift(episode.number);
+            episode.last_number = shift(episode.last_number);
+        }
     }
 
     fn sort(&mut self) {
@@ -113,6 +231,52 @@ impl Deref for SortedEpisodes {
     }
 }
 
+/// Returns true if `filename` looks like a creditless opening or ending.
+///
+/// This looks for words like `NCOP`, `NCED1`, `OP2`, or `ED`, followed by a number for the latter two.
+/// Only whole words are checked, as plain words like `Opening` and `Ending` can be part of episode titles.
+fn is_opening_or_ending(filename: &str) -> bool {
+    filename
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .any(|word| {
+            let word = word.to_ascii_lowercase();
+
+            let (marker, number) = match word.find(|ch: char| ch.is_ascii_digit()) {
+                Some(index) => word.split_at(index),
+                None => (word.as_str(), ""),
+            };
+
+            if !number.chars().all(|ch| ch.is_ascii_digit()) {
+                return false;
+            }
+
+            match marker {
+                "ncop" | "nced" => true,
+                "op" | "ed" => !number.is_empty(),
+                _ => false,
+            }
+        })
+}
+
+/// Returns the episode category indicated by the name of a directory, such as `Specials` or `OVA`.
+///
+/// Directories for seasons (such as `Season 2`) don't indicate a category, as they can contain other kinds of episodes.
+fn dir_name_category(name: &str) -> Option<SeriesKind> {
+    name.split(|ch: char| !ch.is_ascii_alphanumeric())
+        .find_map(|word| {
+            let word = word.to_ascii_lowercase();
+            let singular = word.strip_suffix('s').unwrap_or(&word);
+
+            match singular {
+                "special" | "sp" | "extra" => Some(SeriesKind::Special),
+                "ova" | "oad" => Some(SeriesKind::OVA),
+                "ona" => Some(SeriesKind::ONA),
+                "movie" | "film" => Some(SeriesKind::Movie),
+                _ => None,
+            }
+        })
+}
+
 pub type EpisodeMap = HashMap<SeriesKind, SortedEpisodes>;
 
 /// A list of episodes on disk.
@@ -145,6 +309,13 @@ impl CategorizedEpisodes {
         self.0.into_iter().next().map(|(_, episodes)| episodes)
     }
 
+    /// Consumes the struct and returns the episodes belonging to the specified `category`, if any are present.
+    #[inline]
+    #[must_use]
+    pub fn take_category(mut self, category: SeriesKind) -> Option<SortedEpisodes> {
+        self.0.remove(&category)
+    }
+
     /// Consumes the struct and returns seasonal episodes, or, if there's only one episode category, those episodes.
     #[inline]
     #[must_use]
@@ -162,72 +333,138 @@ impl CategorizedEpisodes {
     }
 
     /// Find the first matching series episodes in `dir` with the specified `parser`.
+    ///
+    /// Only files directly inside of `dir` are parsed. See [`CategorizedEpisodes::parse_recursive`] to include subdirectories.
     pub fn parse<P>(dir: P, parser: &EpisodeParser) -> Result<Self>
     where
         P: AsRef<Path>,
     {
-        let mut last_title: Option<String> = None;
-        let mut episodes = HashMap::with_capacity(1);
+        Self::parse_with(dir.as_ref(), parser, false, &HashSet::new())
+    }
 
-        Self::parse_eps_in_dir_with(dir, parser, |parsed, filename| {
-            if let Some(series_name) = parsed.title {
-                match &mut last_title {
-                    Some(last_title) => {
-                        if *last_title != series_name {
-                            return Err(Error::MultipleTitles {
-                                expecting: last_title.clone(),
-                                found: series_name,
-                            });
-                        }
-                    }
-                    None => last_title = Some(series_name),
-                }
-            }
+    /// Find the first matching series episodes in `dir` and all of its subdirectories with the specified `parser`.
+    ///
+    /// Subdirectories with a name that indicates an episode category (such as `Specials`, `OVA`, or `Movies`)
+    /// will have all of their episodes placed in that category. Episodes in any other subdirectory keep the category they were parsed with.
+    ///
+    /// Unlike the files directly inside of `dir`, files in subdirectories that can't be parsed are skipped,
+    /// and their titles don't need to match the rest of the episodes.
+    /// The filename of every episode in a subdirectory will be relative to `dir`.
+    pub fn parse_recursive<P>(dir: P, parser: &EpisodeParser) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::parse_with(dir.as_ref(), parser, true, &HashSet::new())
+    }
+
+    /// Find the series episodes in `dir` like [`CategorizedEpisodes::parse`], or like [`CategorizedEpisodes::parse_recursive`] when `recursive` is true,
+    /// but treat every title in `titles` as the same series.
+    ///
+    /// This allows the files directly inside of `dir` to be named with different titles for the same series, such as a romaji title and an abbreviation.
+    /// Titles are compared case-insensitively.
+    pub fn parse_with_titles<P, S>(
+        dir: P,
+        parser: &EpisodeParser,
+        recursive: bool,
+        titles: &[S],
+    ) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        let titles = titles
+            .iter()
+            .map(|title| title.as_ref().to_lowercase())
+            .collect();
 
-            let cat_epsisodes = episodes
-                .entry(parsed.category)
-                .or_insert_with(|| SortedEpisodes::with_capacity(1));
+        Self::parse_with(dir.as_ref(), parser, recursive, &titles)
+    }
 
-            let episode = Episode::new(parsed.episode, filename);
-            cat_epsisodes.push(episode);
+    fn parse_with(
+        dir: &Path,
+        parser: &EpisodeParser,
+        recursive: bool,
+        titles: &HashSet<String>,
+    ) -> Result<Self> {
+        let mut last_title: Option<String> = None;
+        let mut episodes = HashMap::with_capacity(1);
 
-            Ok(())
-        })?;
+        // Subdirectories left to parse, relative to `dir`, along with the category their name indicates
+        let mut pending_dirs = vec![(PathBuf::new(), None)];
 
-        Self::sort_all(&mut episodes);
+        while let Some((subdir, dir_category)) = pending_dirs.pop() {
+            let is_top_level = subdir.as_os_str().is_empty();
 
-        Ok(Self(episodes))
-    }
+            for entry in fs::read_dir(dir.join(&subdir))? {
+                let entry = entry?;
+                let entry_type = entry.file_type()?;
 
-    fn parse_eps_in_dir_with<P, F>(dir: P, parser: &EpisodeParser, mut inserter: F) -> Result<()>
-    where
-        P: AsRef<Path>,
-        F: FnMut(ParsedEpisode, String) -> Result<()>,
-    {
-        let dir = dir.as_ref();
-        let entries = fs::read_dir(dir)?;
+                let filename = entry.file_name();
+                let filename = filename.to_string_lossy();
 
-        for entry in entries {
-            let entry = entry?;
-            let entry_type = entry.file_type()?;
+                if entry_type.is_dir() {
+                    if recursive {
+                        let category = dir_name_category(&filename).or(dir_category);
+                        pending_dirs.push((subdir.join(filename.as_ref()), category));
+                    }
 
-            if entry_type.is_dir() {
-                continue;
-            }
+                    continue;
+                }
 
-            let filename = entry.file_name();
-            let filename = filename.to_string_lossy();
+                // The .part extension is commonly used to indicate that a file is incomplete
+                if filename.ends_with(".part") {
+                    continue;
+                }
 
-            // The .part extension is commonly used to indicate that a file is incomplete
-            if filename.ends_with(".part") {
-                continue;
-            }
+                let parsed = match parser.parse(filename.as_ref()) {
+                    Ok(parsed) => parsed,
+                    Err(_) if !is_top_level => continue,
+                    Err(err) => return Err(err),
+                };
+
+                if let (Some(series_name), true) = (&parsed.title, is_top_level) {
+                    match &last_title {
+                        Some(last_title) => {
+                            let same_series = last_title == series_name
+                                || (titles.contains(&last_title.to_lowercase())
+                                    && titles.contains(&series_name.to_lowercase()));
+
+                            if !same_series {
+                                return Err(Error::MultipleTitles {
+                                    expecting: last_title.clone(),
+                                    found: series_name.clone(),
+                                });
+                            }
+                        }
+                        None => last_title = Some(series_name.clone()),
+                    }
+                }
 
-            let episode = parser.parse(filename.as_ref())?;
-            inserter(episode, filename.into_owned())?;
+                let filename = if is_top_level {
+                    filename.into_owned()
+                } else {
+                    subdir
+                        .join(filename.as_ref())
+                        .to_string_lossy()
+                        .into_owned()
+                };
+
+                let cat_epsisodes = episodes
+                    .entry(dir_category.unwrap_or(parsed.category))
+                    .or_insert_with(|| SortedEpisodes::with_capacity(1));
+
+                let episode = match parsed.fraction {
+                    Some(fraction) => Episode::fractional(parsed.episode, fraction, filename),
+                    None => Episode::with_span(parsed.episode, parsed.last_episode, filename),
+                };
+
+                cat_epsisodes.push(episode);
+            }
         }
 
-        Ok(())
+        Self::sort_all(&mut episodes);
+
+        Ok(Self(episodes))
     }
 
     fn sort_all(episode_cats: &mut EpisodeMap) {
@@ -250,3 +487,296 @@ impl DerefMut for CategorizedEpisodes {
         &mut self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn create_fixture(name: &str, filenames: &[&str]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("anime_fixture_{}", name));
+
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        fs::create_dir_all(&dir).unwrap();
+
+        for filename in filenames {
+            File::create(dir.join(filename)).unwrap();
+        }
+
+        dir
+    }
+
+    #[test]
+    fn numbered_opening_detection() {
+        let mut filenames = vec![String::from("[Group] Series Title - 01 NCOP.mkv")];
+        filenames.extend((2..=13).map(|ep| format!("[Group] Series Title - {:02}.mkv", ep)));
+
+        let filenames = filenames.iter().map(String::as_str).collect::<Vec<_>>();
+        let dir = create_fixture("numbered_opening", &filenames);
+
+        let mut episodes = CategorizedEpisodes::parse(&dir, &EpisodeParser::default())
+            .unwrap()
+            .take_season_episodes_or_present()
+            .unwrap();
+
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(episodes.has_numbered_opening(12));
+        assert!(!episodes.has_numbered_opening(13));
+        assert!(!episodes.has_numbered_opening(11));
+
+        episodes.apply_offset(-1);
+
+        assert_eq!(episodes.len(), 12);
+        assert_eq!(episodes[0].number, 1);
+        assert_eq!(episodes[0].filename, "[Group] Series Title - 02.mkv");
+        assert_eq!(episodes.highest_episode_number(), 12);
+    }
+
+    #[test]
+    fn opening_and_ending_markers() {
+        let openings = [
+            "[Group] Series Title - 01 NCOP.mkv",
+            "[Group] Series Title - NCED2 [1080p].mkv",
+            "[Group] Series Title - 01 (OP1).mkv",
+            "Series_Title_ED02.mkv",
+        ];
+
+        for filename in &openings {
+            assert!(is_opening_or_ending(filename), "{}", filename);
+        }
+
+        let episodes = [
+            "[Group] Series Title - 01 - The Opening Act.mkv",
+            "[Group] Series Title - 01 - A Happy Ending.mkv",
+            "[Group] Series Title - 01 - Top Speed.mkv",
+            "[Group] Series Title - 01 - OP.mkv",
+            "[Group] Series Title - 01 - Edited.mkv",
+            "[Group] Series Title - 01 - ED2X.mkv",
+        ];
+
+        for filename in &episodes {
+            assert!(!is_opening_or_ending(filename), "{}", filename);
+        }
+    }
+
+    #[test]
+    fn regular_episodes_have_no_numbered_opening() {
+        let filenames = (1..=13)
+            .map(|ep| format!("[Group] Series Title - {:02}.mkv", ep))
+            .collect::<Vec<_>>();
+
+        let filenames = filenames.iter().map(String::as_str).collect::<Vec<_>>();
+        let dir = create_fixture("no_numbered_opening", &filenames);
+
+        let episodes = CategorizedEpisodes::parse(&dir, &EpisodeParser::default())
+            .unwrap()
+            .take_season_episodes_or_present()
+            .unwrap();
+
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(!episodes.has_numbered_opening(12));
+    }
+
+    #[test]
+    fn take_active_category() {
+        let dir = create_fixture(
+            "multiple_categories",
+            &[
+                "[Group] Series Title - 01.mkv",
+                "[Group] Series Title - 02.mkv",
+                "[Group] Series Title - 03.mkv",
+                "[Group] Series Title OVA - 01.mkv",
+                "[Group] Series Title OVA - 02.mkv",
+                "[Group] Series Title Special - 01.mkv",
+            ],
+        );
+
+        let parse = || CategorizedEpisodes::parse(&dir, &EpisodeParser::default()).unwrap();
+
+        let season = parse().take_category(SeriesKind::Season).unwrap();
+        let ovas = parse().take_category(SeriesKind::OVA).unwrap();
+        let specials = parse().take_category(SeriesKind::Special).unwrap();
+        let movies = parse().take_category(SeriesKind::Movie);
+        let default = parse().take_season_episodes_or_present().unwrap();
+
+        let only_extras = {
+            let mut episodes = parse();
+            episodes.remove(&SeriesKind::Season);
+            episodes.take_season_episodes_or_present()
+        };
+
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(season.len(), 3);
+        assert_eq!(ovas.len(), 2);
+        assert_eq!(ovas[1].filename, "[Group] Series Title OVA - 02.mkv");
+        assert_eq!(specials.len(), 1);
+        assert!(movies.is_none());
+        assert_eq!(default.len(), 3);
+        assert!(only_extras.is_none());
+    }
+
+    #[test]
+    fn multi_episode_files() {
+        let dir = create_fixture(
+            "multi_episode",
+            &[
+                "[Group] Series Title - 01-02.mkv",
+                "[Group] Series Title - 03.mkv",
+                "[Group] Series Title - 04-06.mkv",
+            ],
+        );
+
+        let mut episodes = CategorizedEpisodes::parse(&dir, &EpisodeParser::default())
+            .unwrap()
+            .take_season_episodes_or_present()
+            .unwrap();
+
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(episodes.len(), 3);
+        assert_eq!(episodes.highest_episode_number(), 6);
+        assert_eq!(episodes.find(2).unwrap().span_len(), 2);
+        assert_eq!(episodes.find(3).unwrap().number, 3);
+        assert_eq!(
+            episodes.find(5).unwrap().filename,
+            "[Group] Series Title - 04-06.mkv"
+        );
+        assert!(episodes.find(7).is_none());
+
+        episodes.apply_offset(-1);
+
+        assert_eq!(episodes[0].number, 1);
+        assert_eq!(episodes[0].last_number, 1);
+        assert_eq!(episodes.highest_episode_number(), 5);
+    }
+
+    #[test]
+    fn fractional_episode_files() {
+        let dir = create_fixture(
+            "fractional_episode",
+            &[
+                "[Group] Series Title - 11.mkv",
+                "[Group] Series Title - 12.mkv",
+                "[Group] Series Title - 12.5.mkv",
+                "[Group] Series Title - 13.mkv",
+            ],
+        );
+
+        let episodes = CategorizedEpisodes::parse(&dir, &EpisodeParser::default())
+            .unwrap()
+            .take_season_episodes_or_present()
+            .unwrap();
+
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(episodes.len(), 4);
+        assert_eq!(episodes.fractional().count(), 1);
+        assert_eq!(episodes[2].fraction, Some(5));
+        assert_eq!(
+            episodes.find(12).unwrap().filename,
+            "[Group] Series Title - 12.mkv"
+        );
+        assert_eq!(
+            episodes.find(13).unwrap().filename,
+            "[Group] Series Title - 13.mkv"
+        );
+        assert_eq!(episodes.highest_episode_number(), 13);
+        assert!(!episodes.has_numbered_opening(3));
+    }
+
+    #[test]
+    fn recursive_episode_files() {
+        let dir = create_fixture(
+            "recursive",
+            &[
+                "[Group] Series Title - 01.mkv",
+                "[Group] Series Title - 02.mkv",
+            ],
+        );
+
+        for subdir in &["Specials", "Season 2", "Extras/OVA"] {
+            fs::create_dir_all(dir.join(subdir)).unwrap();
+        }
+
+        File::create(dir.join("Specials").join("[Group] Series Title - 01.mkv")).unwrap();
+        File::create(dir.join("Specials").join("cover.jpg")).unwrap();
+        File::create(dir.join("Season 2").join("[Group] Another Title - 03.mkv")).unwrap();
+        File::create(dir.join("Extras/OVA").join("[Group] Series Title - 01.mkv")).unwrap();
+
+        let flat = CategorizedEpisodes::parse(&dir, &EpisodeParser::default()).unwrap();
+        let recursive =
+            CategorizedEpisodes::parse_recursive(&dir, &EpisodeParser::default()).unwrap();
+
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(!flat.has_multiple_categories());
+
+        let season = &recursive[&SeriesKind::Season];
+        assert_eq!(season.len(), 3);
+        assert_eq!(
+            season.find(3).unwrap().filename,
+            "Season 2/[Group] Another Title - 03.mkv"
+        );
+
+        let specials = &recursive[&SeriesKind::Special];
+        assert_eq!(specials.len(), 1);
+        assert_eq!(
+            specials[0].filename,
+            "Specials/[Group] Series Title - 01.mkv"
+        );
+
+        // The closest directory name takes priority
+        assert_eq!(recursive[&SeriesKind::OVA].len(), 1);
+    }
+
+    #[test]
+    fn synonym_titles_are_the_same_series() {
+        let dir = create_fixture(
+            "synonym_titles",
+            &[
+                "[Group] Sousou no Frieren - 01.mkv",
+                "[Group] Frieren S2 - 02.mkv",
+            ],
+        );
+
+        let parser = EpisodeParser::default();
+        let without_titles = CategorizedEpisodes::parse(&dir, &parser);
+        let with_titles = CategorizedEpisodes::parse_with_titles(
+            &dir,
+            &parser,
+            false,
+            &["sousou no frieren", "Frieren S2"],
+        );
+        let unrelated_titles =
+            CategorizedEpisodes::parse_with_titles(&dir, &parser, false, &["Frieren S2"]);
+
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(matches!(without_titles, Err(Error::MultipleTitles { .. })));
+        assert_eq!(with_titles.unwrap()[&SeriesKind::Season].len(), 2);
+        assert!(matches!(
+            unrelated_titles,
+            Err(Error::MultipleTitles { .. })
+        ));
+    }
+
+    #[test]
+    fn dir_name_categories() {
+        assert_eq!(dir_name_category("Specials"), Some(SeriesKind::Special));
+        assert_eq!(
+            dir_name_category("[Group] Series Title OVAs"),
+            Some(SeriesKind::OVA)
+        );
+        assert_eq!(dir_name_category("Movie"), Some(SeriesKind::Movie));
+        assert_eq!(dir_name_category("Season 2"), None);
+        assert_eq!(dir_name_category("Spiral"), None);
+    }
+}
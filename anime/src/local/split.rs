@@ -0,0 +1,378 @@
+use super::SortedEpisodes;
+use crate::err::{Error, Result};
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How the episode files of a split series are placed into its new folder.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LinkMethod {
+    /// Create a symbolic link to each episode file.
+    ///
+    /// This is only supported on unix platforms.
+    Symlink,
+    /// Create a hard link to each episode file, which requires both folders to be on the same filesystem.
+    Hardlink,
+}
+
+impl LinkMethod {
+    fn link(self, from: &Path, to: &Path) -> io::Result<()> {
+        match self {
+            #[cfg(unix)]
+            Self::Symlink => std::os::unix::fs::symlink(from, to),
+            #[cfg(not(unix))]
+            Self::Symlink => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "symbolic links are only supported on unix",
+            )),
+            Self::Hardlink => fs::hard_link(from, to),
+        }
+    }
+}
+
+impl Default for LinkMethod {
+    fn default() -> Self {
+        Self::Symlink
+    }
+}
+
+/// A single episode file to link into the folder of a split series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SplitAction {
+    /// The name of the episode file in the folder being split.
+    pub old_name: String,
+    /// The name of the link in the folder of the split series.
+    pub new_name: String,
+}
+
+impl SplitAction {
+    pub fn new<S, N>(old_name: S, new_name: N) -> Self
+    where
+        S: Into<String>,
+        N: Into<String>,
+    {
+        Self {
+            old_name: old_name.into(),
+            new_name: new_name.into(),
+        }
+    }
+
+    /// Returns the actions needed to link the episodes of a season named `title` with `season_episodes` episodes out of `episodes`,
+    /// where the season starts after `offset` episodes.
+    ///
+    /// The new links are named after `title` and numbered from the start of the season.
+    #[must_use]
+    pub fn from_merged_season(
+        title: &str,
+        season_episodes: u32,
+        episodes: &SortedEpisodes,
+        offset: u32,
+    ) -> Vec<Self> {
+        let mut actions = Vec::new();
+
+        let sequel_start = 1 + offset;
+        let sequel_end = offset + season_episodes;
+
+        for real_ep_num in sequel_start..=sequel_end {
+            let episode = match episodes.find(real_ep_num) {
+                // Files with multiple episodes only need to be linked once
+                Some(episode) if episode.number == real_ep_num || real_ep_num == sequel_start => {
+                    episode
+                }
+                Some(_) | None => continue,
+            };
+
+            let extension = PathBuf::from(&episode.filename).extension().map_or_else(
+                || Cow::Borrowed(""),
+                |e| format!(".{}", e.to_string_lossy()).into(),
+            );
+
+            let last_ep_num = episode.last_number.min(sequel_end);
+
+            let new_filename = if last_ep_num > real_ep_num {
+                format!(
+                    "{} - {:02}-{:02}{}",
+                    title,
+                    real_ep_num - offset,
+                    last_ep_num - offset,
+                    extension
+                )
+            } else {
+                format!("{} - {:02}{}", title, real_ep_num - offset, extension)
+            };
+
+            actions.push(Self::new(&episode.filename, new_filename));
+        }
+
+        actions
+    }
+}
+
+/// The episodes of a season that don't line up with the files it would be split from.
+///
+/// Splitting a season with a report that isn't clean leaves holes in its folder, or leaves files behind.
+/// Every episode is numbered from the start of the season.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SplitReport {
+    /// The episodes of the season that have no file.
+    pub missing: Vec<u32>,
+    /// The episodes that fall within the season but won't be linked, such as recaps numbered `12.5` or episodes past its end.
+    pub extra: Vec<String>,
+}
+
+impl SplitReport {
+    /// Check the episodes of a season with `season_episodes` episodes that starts after `offset` episodes in `episodes`.
+    ///
+    /// When `is_last` is true, whole episodes past the end of the season are counted as extra, since no later season will take them.
+    /// Nothing is reported as missing when the number of episodes in the season isn't known yet.
+    #[must_use]
+    pub fn from_merged_season(
+        season_episodes: u32,
+        episodes: &SortedEpisodes,
+        offset: u32,
+        is_last: bool,
+    ) -> Self {
+        let sequel_end = offset + season_episodes;
+
+        let missing = (1 + offset..=sequel_end)
+            .filter(|&num| episodes.find(num).is_none())
+            .map(|num| num - offset)
+            .collect();
+
+        let fractional = episodes
+            .fractional()
+            .filter(|ep| ep.number > offset && (season_episodes == 0 || ep.number <= sequel_end))
+            .map(|ep| format!("{}.{}", ep.number - offset, ep.fraction.unwrap_or(0)));
+
+        let trailing = episodes
+            .whole()
+            .filter(|_| is_last && season_episodes > 0)
+            .flat_map(|ep| ep.number..=ep.last_number)
+            .filter(|&num| num > sequel_end)
+            .map(|num| (num - offset).to_string());
+
+        Self {
+            missing,
+            extra: fractional.chain(trailing).collect(),
+        }
+    }
+
+    /// Returns true if every episode of the season has a file, and every file in the season belongs to it.
+    #[inline(always)]
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+impl fmt::Display for SplitReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "ok");
+        }
+
+        if !self.missing.is_empty() {
+            let missing = self
+                .missing
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+
+            write!(f, "missing {}", missing.join(", "))?;
+
+            if !self.extra.is_empty() {
+                write!(f, "; ")?;
+            }
+        }
+
+        if !self.extra.is_empty() {
+            write!(f, "extra {}", self.extra.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Every episode file to link from the folder of one series into the folder of another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SplitPlan<'a> {
+    /// The folder the episode files are in.
+    pub base_dir: PathBuf,
+    /// The folder the links are created in.
+    pub out_dir: PathBuf,
+    pub actions: Cow<'a, [SplitAction]>,
+}
+
+impl<'a> SplitPlan<'a> {
+    pub fn new<B, O, A>(base_dir: B, out_dir: O, actions: A) -> Self
+    where
+        B: Into<PathBuf>,
+        O: Into<PathBuf>,
+        A: Into<Cow<'a, [SplitAction]>>,
+    {
+        Self {
+            base_dir: base_dir.into(),
+            out_dir: out_dir.into(),
+            actions: actions.into(),
+        }
+    }
+
+    /// Create every link in the plan with `method`, creating the output folder if needed.
+    ///
+    /// Links that already exist are left alone, so a plan can safely be executed more than once.
+    pub fn execute(&self, method: LinkMethod) -> Result<()> {
+        if self.actions.is_empty() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.out_dir)?;
+
+        for action in self.actions.iter() {
+            let from = self.base_dir.join(&action.old_name);
+            let to = self.out_dir.join(&action.new_name);
+
+            match method.link(&from, &to) {
+                Ok(()) => (),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => (),
+                Err(source) => return Err(Error::LinkFailed { from, to, source }),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove every symbolic link in the output folder that points into the base folder.
+    ///
+    /// This should be done before executing a plan that links episodes differently than a previous one.
+    /// Hard links can't be told apart from regular files, so they are never removed.
+    pub fn remove_links(&self) -> Result<()> {
+        for entry in fs::read_dir(&self.out_dir)? {
+            let path = entry?.path();
+
+            let is_episode_link =
+                fs::read_link(&path).map_or(false, |target| target.starts_with(&self.base_dir));
+
+            if is_episode_link {
+                fs::remove_file(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local::Episode;
+    use std::env;
+    use std::fs::File;
+
+    fn merged_episodes(count: u32) -> SortedEpisodes {
+        SortedEpisodes::with_episodes(
+            (1..=count)
+                .map(|num| Episode::new(num, format!("Merged - {:02}.mkv", num)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn merged_season_actions() {
+        let episodes = merged_episodes(37);
+
+        let old_actions = SplitAction::from_merged_season("Season 2", 12, &episodes, 12);
+        let new_actions = SplitAction::from_merged_season("Season 2", 12, &episodes, 13);
+
+        assert_eq!(old_actions[0].old_name, "Merged - 13.mkv");
+        assert_eq!(new_actions[0].old_name, "Merged - 14.mkv");
+        assert_eq!(new_actions[0].new_name, "Season 2 - 01.mkv");
+        assert_eq!(new_actions.len(), 12);
+        assert_eq!(new_actions[11].old_name, "Merged - 25.mkv");
+    }
+
+    #[test]
+    fn multi_episode_files_are_linked_once() {
+        let episodes = SortedEpisodes::with_episodes(vec![
+            Episode::with_span(1, 2, "Merged - 01-02.mkv".into()),
+            Episode::with_span(3, 4, "Merged - 03-04.mkv".into()),
+        ]);
+
+        let actions = SplitAction::from_merged_season("Season 2", 2, &episodes, 2);
+
+        assert_eq!(
+            actions,
+            vec![SplitAction::new(
+                "Merged - 03-04.mkv",
+                "Season 2 - 01-02.mkv"
+            )]
+        );
+    }
+
+    #[test]
+    fn report_lists_missing_and_extra_episodes() {
+        let mut episodes = merged_episodes(26).take();
+        episodes.retain(|ep| ep.number != 17);
+        episodes.push(Episode::fractional(18, 5, "Merged - 18.5.mkv".into()));
+
+        let episodes = SortedEpisodes::with_episodes(episodes);
+
+        let report = SplitReport::from_merged_season(12, &episodes, 12, false);
+        assert_eq!(report.missing, vec![5]);
+        assert_eq!(report.extra, vec!["6.5"]);
+        assert_eq!(report.to_string(), "missing 5; extra 6.5");
+
+        // Episodes 25 and 26 only count as extra when there's no season after this one
+        let report = SplitReport::from_merged_season(12, &episodes, 12, true);
+        assert_eq!(report.extra, vec!["6.5", "13", "14"]);
+
+        let report = SplitReport::from_merged_season(12, &merged_episodes(24), 12, true);
+        assert!(report.is_clean());
+        assert_eq!(report.to_string(), "ok");
+    }
+
+    #[test]
+    fn execute_and_remove_links() {
+        let dir = env::temp_dir().join(format!("anime_split_{}", std::process::id()));
+        let base_dir = dir.join("Merged");
+        let out_dir = dir.join("Season 2");
+
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        fs::create_dir_all(&base_dir).unwrap();
+
+        for num in 1..=4 {
+            File::create(base_dir.join(format!("Merged - {:02}.mkv", num))).unwrap();
+        }
+
+        let actions = SplitAction::from_merged_season("Season 2", 2, &merged_episodes(4), 2);
+        let plan = SplitPlan::new(&base_dir, &out_dir, actions);
+
+        plan.execute(LinkMethod::Hardlink).unwrap();
+        // Executing the same plan again should leave the existing links alone
+        plan.execute(LinkMethod::Hardlink).unwrap();
+
+        assert!(out_dir.join("Season 2 - 01.mkv").exists());
+        assert!(out_dir.join("Season 2 - 02.mkv").exists());
+
+        // Hard links look like regular files, so they should never be removed
+        plan.remove_links().unwrap();
+        assert!(out_dir.join("Season 2 - 01.mkv").exists());
+
+        #[cfg(unix)]
+        {
+            let symlinked = SplitPlan::new(&base_dir, dir.join("Symlinked"), plan.actions.clone());
+
+            symlinked.execute(LinkMethod::Symlink).unwrap();
+            assert!(symlinked.out_dir.join("Season 2 - 02.mkv").exists());
+
+            symlinked.remove_links().unwrap();
+            assert_eq!(fs::read_dir(&symlinked.out_dir).unwrap().count(), 0);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
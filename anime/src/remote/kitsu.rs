@@ -0,0 +1,861 @@
+use super::{
+    parse_timestamp, AccessToken, AiringStatus, IssuedToken, RemoteService, ScoreParser, Sequel,
+    SeriesDate, SeriesEntry, SeriesID, SeriesInfo, SeriesKind, SeriesTitle, Status,
+};
+use crate::err::{Error, Result};
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use reqwest::RequestBuilder;
+use serde_derive::{Deserialize, Serialize};
+use serde_json as json;
+use serde_json::json;
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::result;
+use tracing::Instrument;
+use url::{form_urlencoded, Url};
+
+/// The URL to the API endpoint.
+pub const API_URL: &str = "https://kitsu.io/api/edge";
+
+/// The URL to the endpoint used to retrieve access tokens.
+const TOKEN_URL: &str = "https://kitsu.io/api/oauth/token";
+
+/// The relationships to include with series info so sequels can be found.
+const INFO_INCLUDE: &str = "mediaRelationships.destination";
+
+/// The maximum number of results to return when searching for a series by name.
+const SEARCH_LIMIT: u32 = 10;
+
+const JSON_API_CONTENT_TYPE: &str = "application/vnd.api+json";
+
+/// Retrieve an access token for the user with the specified `username` (or email) and `password`.
+///
+/// Kitsu does not offer a way to authorize applications through the browser, so the token
+/// has to be retrieved with the user's credentials instead. The credentials themselves do not need to be stored.
+pub async fn login(client: &Client, username: &str, password: &str) -> Result<IssuedToken> {
+    let body = form_urlencoded::Serializer::new(String::new())
+        .append_pair("grant_type", "password")
+        .append_pair("username", username)
+        .append_pair("password", password)
+        .finish();
+
+    let request = client
+        .http
+        .post(TOKEN_URL)
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(body);
+
+    let json = send_request(client, request, None).await?;
+    let response: TokenResponse = json::from_value(json)?;
+
+    Ok(IssuedToken::new(response.access_token, response.expires_in))
+}
+
+/// A connection to the Kitsu API.
+#[derive(Debug)]
+pub enum Kitsu {
+    /// A Kitsu connection with authentication.
+    ///
+    /// This mode will allow you to update the specified user's list.
+    /// To get a user's token, the `login` function can be used.
+    Authenticated(Auth),
+    /// A Kitsu connection without any authentication.
+    ///
+    /// In this mode, you can only search for series info by name and by ID.
+    /// Trying to make any other request will return a `NeedAuthentication` error.
+    Unauthenticated(Client),
+}
+
+impl Kitsu {
+    fn client(&self) -> &Client {
+        match &self {
+            Self::Authenticated(auth) => &auth.client,
+            Self::Unauthenticated(client) => client,
+        }
+    }
+
+    fn auth(&self) -> Result<&Auth> {
+        match &self {
+            Self::Authenticated(auth) => Ok(auth),
+            Self::Unauthenticated(_) => Err(Error::NeedAuthentication),
+        }
+    }
+
+    fn rating_system(&self) -> RatingSystem {
+        match &self {
+            Self::Authenticated(auth) => auth.user.rating_system,
+            Self::Unauthenticated(_) => RatingSystem::default(),
+        }
+    }
+
+    async fn get<T>(&self, path: &str, params: &[(&str, &str)]) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let client = self.client();
+        let token = self.auth().ok().map(|auth| &auth.token);
+        let request = client
+            .http
+            .get(format!("{}{}", API_URL, path))
+            .query(params);
+
+        let json = send_request(client, request, token).await?;
+
+        json::from_value(json).map_err(Into::into)
+    }
+
+    /// Find the authenticated user's library entry for the series with the specified `id`.
+    async fn library_entry(&self, id: SeriesID) -> Result<Option<LibraryEntry>> {
+        let auth = self.auth()?;
+        let user_id = auth.user.id.to_string();
+        let anime_id = id.to_string();
+
+        let mut doc: Document<Vec<LibraryEntry>> = self
+            .get(
+                "/library-entries",
+                &[
+                    ("filter[userId]", &user_id),
+                    ("filter[animeId]", &anime_id),
+                    ("filter[kind]", "anime"),
+                ],
+            )
+            .await?;
+
+        Ok(doc.data.pop())
+    }
+}
+
+#[async_trait]
+impl RemoteService for Kitsu {
+    async fn search_info_by_name(&self, name: &str) -> Result<Vec<SeriesInfo>> {
+        let limit = SEARCH_LIMIT.to_string();
+
+        let doc: Document<Vec<AnimeResource>> = self
+            .get(
+                "/anime",
+                &[
+                    ("filter[text]", name),
+                    ("page[limit]", &limit),
+                    ("include", INFO_INCLUDE),
+                ],
+            )
+            .await?;
+
+        let entries = doc
+            .data
+            .iter()
+            .filter_map(|anime| anime.to_series_info(&doc.included).ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    async fn search_info_by_id(&self, id: SeriesID) -> Result<SeriesInfo> {
+        let doc: Document<AnimeResource> = self
+            .get(&format!("/anime/{}", id), &[("include", INFO_INCLUDE)])
+            .await?;
+
+        doc.data
+            .to_series_info(&doc.included)
+            .map_err(|_| Error::NotAnAnime)
+    }
+
+    async fn get_list_entry(&self, id: SeriesID) -> Result<Option<SeriesEntry>> {
+        let entry = self.library_entry(id).await?;
+        Ok(entry.map(|entry| entry.attributes.into_series_entry(id)))
+    }
+
+    async fn update_list_entry(&self, entry: &SeriesEntry) -> Result<()> {
+        let auth = self.auth()?;
+
+        let rating = entry.score.map(rating_twenty);
+
+        let attributes = json!({
+            "status": LibraryStatus::from(entry.status),
+            "progress": entry.watched_eps,
+            "reconsuming": entry.status == Status::Rewatching,
+            "reconsumeCount": entry.times_rewatched,
+            "ratingTwenty": rating,
+            "startedAt": entry.start_date.map(SeriesDate::to_ymd_string),
+            "finishedAt": entry.end_date.map(SeriesDate::to_ymd_string),
+        });
+
+        // Existing entries have to be updated by their own ID, while new ones must be linked to the user and series
+        let request = match self.library_entry(entry.id).await? {
+            Some(existing) => {
+                let body = json!({
+                    "data": {
+                        "type": "libraryEntries",
+                        "id": &existing.id,
+                        "attributes": attributes,
+                    }
+                });
+
+                auth.client
+                    .http
+                    .patch(format!("{}/library-entries/{}", API_URL, existing.id))
+                    .header(CONTENT_TYPE, JSON_API_CONTENT_TYPE)
+                    .body(body.to_string())
+            }
+            None => {
+                let body = json!({
+                    "data": {
+                        "type": "libraryEntries",
+                        "attributes": attributes,
+                        "relationships": {
+                            "anime": { "data": { "type": "anime", "id": entry.id.to_string() } },
+                            "user": { "data": { "type": "users", "id": auth.user.id.to_string() } },
+                        },
+                    }
+                });
+
+                auth.client
+                    .http
+                    .post(format!("{}/library-entries", API_URL))
+                    .header(CONTENT_TYPE, JSON_API_CONTENT_TYPE)
+                    .body(body.to_string())
+            }
+        };
+
+        send_request(&auth.client, request, Some(&auth.token)).await?;
+        Ok(())
+    }
+
+    fn has_list(&self) -> bool {
+        matches!(self, Self::Authenticated(_))
+    }
+}
+
+impl ScoreParser for Kitsu {
+    fn parse_score(&self, score: &str) -> Option<u8> {
+        self.rating_system().points_value(score)
+    }
+
+    fn score_to_str(&self, score: u8) -> Cow<str> {
+        let rating = rating_twenty(score);
+
+        match self.rating_system() {
+            RatingSystem::Advanced => format!("{:.1}", f32::from(rating) / 2.0).into(),
+            RatingSystem::Regular => format!("{:.1}", f32::from(rating) / 4.0).into(),
+            RatingSystem::Simple => match rating {
+                0..=5 => "awful".into(),
+                6..=11 => "meh".into(),
+                12..=17 => "good".into(),
+                _ => "great".into(),
+            },
+        }
+    }
+}
+
+/// Settings used when sending requests to Kitsu.
+#[derive(Clone, Debug, Default)]
+pub struct Client {
+    proxy: Option<Url>,
+    http: reqwest::Client,
+}
+
+impl Client {
+    /// Create a new `Client` that connects to Kitsu directly.
+    #[inline(always)]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new `Client` that sends all requests through the specified `proxy` URL.
+    ///
+    /// HTTP, HTTPS, and SOCKS5 proxies are supported, where `socks5h` also resolves hostnames through the proxy.
+    pub fn with_proxy<S>(proxy: S) -> Result<Self>
+    where
+        S: AsRef<str>,
+    {
+        let (proxy, http) = super::proxied_http_client(proxy.as_ref())?;
+
+        Ok(Self {
+            proxy: Some(proxy),
+            http,
+        })
+    }
+
+    /// Returns true if requests will be sent through a proxy.
+    #[inline(always)]
+    #[must_use]
+    pub fn uses_proxy(&self) -> bool {
+        self.proxy.is_some()
+    }
+}
+
+/// An authenticated user.
+#[derive(Debug)]
+pub struct Auth {
+    /// The Kitsu user's account information.
+    pub user: User,
+    token: AccessToken,
+    client: Client,
+}
+
+impl Auth {
+    #[inline(always)]
+    #[must_use]
+    pub fn new(user: User, token: AccessToken, client: Client) -> Self {
+        Self {
+            user,
+            token,
+            client,
+        }
+    }
+
+    /// Retrieve the current authorization from Kitsu using the specified `token`.
+    ///
+    /// The returned `Auth` will use `client` to make all further requests.
+    pub async fn retrieve(token: AccessToken, client: Client) -> Result<Self> {
+        let request = client
+            .http
+            .get(format!("{}/users", API_URL))
+            .query(&[("filter[self]", "true")]);
+
+        let json = send_request(&client, request, Some(&token)).await?;
+        let mut doc: Document<Vec<UserResource>> = json::from_value(json)?;
+
+        let user = doc.data.pop().ok_or(Error::NeedAuthentication)?;
+        let user = User {
+            id: user.id.parse().map_err(|_| Error::NeedAuthentication)?,
+            name: user.attributes.name,
+            rating_system: user.attributes.rating_system,
+        };
+
+        Ok(Self::new(user, token, client))
+    }
+}
+
+/// A Kitsu user.
+#[derive(Debug)]
+pub struct User {
+    /// The user's account ID.
+    pub id: u32,
+    /// The user's account name.
+    pub name: String,
+    /// The user's preferred rating system.
+    pub rating_system: RatingSystem,
+}
+
+/// Kitsu rating systems.
+///
+/// Kitsu stores every rating in a range between 2 - 20, which each system displays differently.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RatingSystem {
+    /// Range between 1.0 - 10.0, in increments of 0.5.
+    Advanced,
+    /// Range between 0.5 - 5.0 stars, in increments of 0.5.
+    Regular,
+    /// Represented by one of four words. Value ranges for each word are shown below:
+    ///
+    /// | Range    | Word  |
+    /// | -------- | ----- |
+    /// | 0 - 27   | awful |
+    /// | 28 - 57  | meh   |
+    /// | 58 - 87  | good  |
+    /// | 88 - 100 | great |
+    Simple,
+}
+
+impl RatingSystem {
+    fn points_value(self, score: &str) -> Option<u8> {
+        let rating = match self {
+            Self::Advanced => {
+                let score = score.parse::<f32>().ok()?;
+                (score * 2.0).round()
+            }
+            Self::Regular => {
+                let score = score.parse::<f32>().ok()?;
+                (score * 4.0).round()
+            }
+            Self::Simple => match score {
+                "awful" => 2.0,
+                "meh" => 8.0,
+                "good" => 14.0,
+                "great" => 20.0,
+                _ => return None,
+            },
+        };
+
+        if !(0.0..=20.0).contains(&rating) {
+            return None;
+        }
+
+        Some(rating as u8 * 5)
+    }
+}
+
+impl Default for RatingSystem {
+    fn default() -> Self {
+        Self::Advanced
+    }
+}
+
+/// Map a score between 0 - 100 to Kitsu's range of 2 - 20.
+fn rating_twenty(score: u8) -> u8 {
+    ((u16::from(score) + 2) / 5).clamp(2, 20) as u8
+}
+
+async fn send_request(
+    client: &Client,
+    mut request: RequestBuilder,
+    token: Option<&AccessToken>,
+) -> Result<json::Value> {
+    request = request
+        .timeout(super::REQUEST_TIMEOUT)
+        .header(ACCEPT, JSON_API_CONTENT_TYPE);
+
+    if let Some(token) = token {
+        request = request.bearer_auth(token.decode()?);
+    }
+
+    let request = request.build()?;
+
+    if cfg!(debug_assertions) && cfg!(feature = "print-requests-debug") {
+        println!("DEBUG: Kitsu request: {}", request.url());
+    }
+
+    let span =
+        tracing::debug_span!("kitsu_request", method = %request.method(), url = %request.url());
+    let response = client
+        .http
+        .execute(request)
+        .instrument(span.clone())
+        .await?;
+
+    if !response.status().is_success() {
+        let code = response.status().as_u16();
+        let json: json::Value = response.json().await.unwrap_or_default();
+
+        // Regular API errors follow the JSON:API format, while OAuth errors do not
+        let message = json["errors"][0]["detail"]
+            .as_str()
+            .or_else(|| json["error_description"].as_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        span.in_scope(|| tracing::warn!(code, %message, "request failed"));
+        return Err(Error::BadKitsuResponse { code, message });
+    }
+
+    response.json().await.map_err(Into::into)
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+/// A JSON:API document.
+#[derive(Debug, Deserialize)]
+struct Document<T> {
+    data: T,
+    #[serde(default)]
+    included: Vec<Included>,
+}
+
+/// A resource that was included with a document.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Included {
+    Anime {
+        id: String,
+        attributes: IncludedAnimeAttributes,
+    },
+    MediaRelationships {
+        id: String,
+        attributes: MediaRelationshipAttributes,
+        relationships: MediaRelationshipLinks,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserResource {
+    id: String,
+    attributes: UserAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserAttributes {
+    name: String,
+    #[serde(default)]
+    rating_system: RatingSystem,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnimeResource {
+    id: String,
+    attributes: AnimeAttributes,
+    #[serde(default)]
+    relationships: AnimeRelationships,
+}
+
+impl AnimeResource {
+    fn sequels(&self, included: &[Included]) -> Vec<Sequel> {
+        let relations = match &self.relationships.media_relationships {
+            Some(relations) => relations,
+            None => return Vec::new(),
+        };
+
+        relations
+            .data
+            .iter()
+            .filter_map(|link| {
+                let (role, destination) = included.iter().find_map(|item| match item {
+                    Included::MediaRelationships {
+                        id,
+                        attributes,
+                        relationships,
+                    } if *id == link.id => Some((attributes.role, &relationships.destination.data)),
+                    _ => None,
+                })?;
+
+                // It doesn't make sense to consider this relation a sequel
+                // if its an alternative, source, or character relation
+                if !role.is_sequential() || destination.kind != "anime" {
+                    return None;
+                }
+
+                let subtype = included.iter().find_map(|item| match item {
+                    Included::Anime { id, attributes } if *id == destination.id => {
+                        attributes.subtype
+                    }
+                    _ => None,
+                })?;
+
+                let kind = subtype.try_into().ok()?;
+                let id = destination.id.parse().ok()?;
+
+                Some(Sequel::new(kind, id))
+            })
+            .collect()
+    }
+
+    fn to_series_info(&self, included: &[Included]) -> result::Result<SeriesInfo, ()> {
+        let attrs = &self.attributes;
+        let kind = attrs.subtype.try_into()?;
+        let id = self.id.parse().map_err(|_| ())?;
+
+        let romaji = attrs
+            .titles
+            .en_jp
+            .clone()
+            .unwrap_or_else(|| attrs.canonical_title.clone());
+
+        Ok(SeriesInfo {
+            id,
+            title: SeriesTitle {
+                romaji,
+                preferred: attrs.canonical_title.clone(),
+                english: attrs.titles.en.clone(),
+                native: attrs.titles.ja_jp.clone(),
+                synonyms: attrs.abbreviated_titles.clone().unwrap_or_default(),
+            },
+            episodes: attrs.episode_count.unwrap_or(1),
+            episode_length: attrs.episode_length.unwrap_or(24),
+            kind,
+            sequels: self.sequels(included),
+            airing: attrs.status.map_or(AiringStatus::Unknown, Into::into),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnimeAttributes {
+    canonical_title: String,
+    #[serde(default)]
+    titles: AnimeTitles,
+    /// Kitsu sends null rather than an empty list for series without any.
+    #[serde(default)]
+    abbreviated_titles: Option<Vec<String>>,
+    episode_count: Option<u32>,
+    /// The length of an episode in minutes.
+    episode_length: Option<u32>,
+    subtype: AnimeSubtype,
+    #[serde(default)]
+    status: Option<AnimeStatus>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AnimeTitles {
+    en: Option<String>,
+    en_jp: Option<String>,
+    ja_jp: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IncludedAnimeAttributes {
+    subtype: Option<AnimeSubtype>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnimeRelationships {
+    media_relationships: Option<ResourceLinks>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResourceLinks {
+    #[serde(default)]
+    data: Vec<ResourceLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResourceLink {
+    #[serde(rename = "type")]
+    kind: String,
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaRelationshipAttributes {
+    role: MediaRelationshipRole,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaRelationshipLinks {
+    destination: MediaRelationshipDestination,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaRelationshipDestination {
+    data: ResourceLink,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum MediaRelationshipRole {
+    Sequel,
+    SideStory,
+    Other,
+    #[serde(other)]
+    Unknown,
+}
+
+impl MediaRelationshipRole {
+    /// Returns true if the relation is considered to be some kind of sequel. Ex: a second season, OVA, ONA, movie, etc
+    fn is_sequential(self) -> bool {
+        match self {
+            Self::Sequel | Self::SideStory | Self::Other => true,
+            Self::Unknown => false,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+enum AnimeSubtype {
+    TV,
+    #[serde(rename = "movie")]
+    Movie,
+    #[serde(rename = "special")]
+    Special,
+    OVA,
+    ONA,
+    #[serde(rename = "music")]
+    Music,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum AnimeStatus {
+    Finished,
+    Current,
+    Upcoming,
+    Unreleased,
+    #[serde(other)]
+    Other,
+}
+
+impl Into<AiringStatus> for AnimeStatus {
+    fn into(self) -> AiringStatus {
+        match self {
+            Self::Finished => AiringStatus::Finished,
+            Self::Current => AiringStatus::Airing,
+            Self::Upcoming | Self::Unreleased => AiringStatus::NotYetAired,
+            Self::Other => AiringStatus::Unknown,
+        }
+    }
+}
+
+impl TryInto<SeriesKind> for AnimeSubtype {
+    type Error = ();
+
+    fn try_into(self) -> result::Result<SeriesKind, Self::Error> {
+        match self {
+            Self::TV => Ok(SeriesKind::Season),
+            Self::Movie => Ok(SeriesKind::Movie),
+            Self::Special => Ok(SeriesKind::Special),
+            Self::OVA => Ok(SeriesKind::OVA),
+            Self::ONA => Ok(SeriesKind::ONA),
+            Self::Music => Ok(SeriesKind::Music),
+            Self::Other => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LibraryEntry {
+    id: String,
+    attributes: LibraryEntryAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibraryEntryAttributes {
+    status: LibraryStatus,
+    progress: u32,
+    #[serde(default)]
+    reconsuming: bool,
+    #[serde(default)]
+    reconsume_count: u32,
+    rating_twenty: Option<u8>,
+    started_at: Option<String>,
+    finished_at: Option<String>,
+    updated_at: Option<String>,
+}
+
+impl LibraryEntryAttributes {
+    fn into_series_entry(self, id: u32) -> SeriesEntry {
+        let score = self.rating_twenty.map(|rating| rating.min(20) * 5);
+
+        // Kitsu represents rewatching as a flag on top of the regular status
+        let status = if self.reconsuming {
+            Status::Rewatching
+        } else {
+            self.status.into()
+        };
+
+        SeriesEntry {
+            id,
+            watched_eps: self.progress,
+            score,
+            status,
+            times_rewatched: self.reconsume_count,
+            start_date: self.started_at.as_deref().and_then(SeriesDate::parse_ymd),
+            end_date: self.finished_at.as_deref().and_then(SeriesDate::parse_ymd),
+            updated_at: self.updated_at.as_deref().and_then(parse_timestamp),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum LibraryStatus {
+    Current,
+    Completed,
+    OnHold,
+    Dropped,
+    Planned,
+}
+
+impl Into<Status> for LibraryStatus {
+    fn into(self) -> Status {
+        match self {
+            Self::Current => Status::Watching,
+            Self::Completed => Status::Completed,
+            Self::OnHold => Status::OnHold,
+            Self::Dropped => Status::Dropped,
+            Self::Planned => Status::PlanToWatch,
+        }
+    }
+}
+
+impl From<Status> for LibraryStatus {
+    fn from(status: Status) -> Self {
+        match status {
+            // Series being rewatched are marked as current and have a reconsuming flag set
+            Status::Watching | Status::Rewatching => Self::Current,
+            Status::Completed => Self::Completed,
+            Status::OnHold => Self::OnHold,
+            Status::Dropped => Self::Dropped,
+            Status::PlanToWatch => Self::Planned,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anime_document_to_series_info() {
+        let doc: Document<AnimeResource> = json::from_value(json!({
+            "data": {
+                "id": "3936",
+                "type": "anime",
+                "attributes": {
+                    "canonicalTitle": "Fullmetal Alchemist: Brotherhood",
+                    "titles": { "en_jp": "Hagane no Renkinjutsushi: Fullmetal Alchemist" },
+                    "episodeCount": 64,
+                    "episodeLength": 24,
+                    "subtype": "TV",
+                    "status": "finished",
+                },
+                "relationships": {
+                    "mediaRelationships": {
+                        "data": [
+                            { "type": "mediaRelationships", "id": "1" },
+                            { "type": "mediaRelationships", "id": "2" },
+                        ]
+                    }
+                }
+            },
+            "included": [
+                {
+                    "id": "1",
+                    "type": "mediaRelationships",
+                    "attributes": { "role": "side_story" },
+                    "relationships": { "destination": { "data": { "type": "anime", "id": "4792" } } },
+                },
+                {
+                    "id": "2",
+                    "type": "mediaRelationships",
+                    "attributes": { "role": "alternative_setting" },
+                    "relationships": { "destination": { "data": { "type": "anime", "id": "100" } } },
+                },
+                { "id": "4792", "type": "anime", "attributes": { "subtype": "OVA" } },
+                { "id": "100", "type": "anime", "attributes": { "subtype": "TV" } },
+                { "id": "7", "type": "categories", "attributes": {} },
+            ],
+        }))
+        .unwrap();
+
+        let info = doc.data.to_series_info(&doc.included).unwrap();
+
+        assert_eq!(info.id, 3936);
+        assert_eq!(info.episodes, 64);
+        assert_eq!(info.kind, SeriesKind::Season);
+        assert_eq!(info.airing, AiringStatus::Finished);
+        assert_eq!(
+            info.title.romaji,
+            "Hagane no Renkinjutsushi: Fullmetal Alchemist"
+        );
+        assert_eq!(info.sequels.len(), 1);
+        assert_eq!(info.sequels[0].id, 4792);
+        assert_eq!(info.sequels[0].kind, SeriesKind::OVA);
+    }
+
+    #[test]
+    fn rating_system_scores() {
+        assert_eq!(RatingSystem::Advanced.points_value("7.5"), Some(75));
+        assert_eq!(RatingSystem::Regular.points_value("3.5"), Some(70));
+        assert_eq!(RatingSystem::Simple.points_value("good"), Some(70));
+        assert_eq!(RatingSystem::Advanced.points_value("11"), None);
+
+        assert_eq!(rating_twenty(0), 2);
+        assert_eq!(rating_twenty(73), 15);
+        assert_eq!(rating_twenty(100), 20);
+    }
+}
@@ -0,0 +1,952 @@
+#![allow(clippy::doc_markdown)]
+
+use super::{
+    parse_timestamp, AccessToken, AiringStatus, IssuedToken, Relation, RelationKind, RemoteService,
+    ScoreParser, Sequel, SeriesDate, SeriesEntry, SeriesID, SeriesInfo, SeriesKind, SeriesTitle,
+    Status,
+};
+use crate::err::{Error, Result};
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::RequestBuilder;
+use serde_derive::Deserialize;
+use serde_json as json;
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::result;
+use tracing::Instrument;
+use url::{form_urlencoded, Url};
+
+/// The URL to the API endpoint.
+pub const API_URL: &str = "https://api.myanimelist.net/v2";
+
+/// The URL to the OAuth2 endpoints used to authorize users.
+const OAUTH_URL: &str = "https://myanimelist.net/v1/oauth2";
+
+/// The fields to request when retrieving series info.
+const INFO_FIELDS: &str =
+    "id,title,alternative_titles,num_episodes,average_episode_duration,media_type,status";
+
+/// The fields to request when retrieving the info of a single series along with its relations.
+///
+/// Relations are left out of searches, as every result would otherwise need them to be looked up.
+const INFO_WITH_RELATIONS_FIELDS: &str =
+    "id,title,alternative_titles,num_episodes,average_episode_duration,media_type,status,related_anime{media_type}";
+
+/// The fields to request when retrieving a user's list entry.
+const LIST_STATUS_FIELDS: &str = "my_list_status{status,score,num_episodes_watched,is_rewatching,num_times_rewatched,start_date,finish_date,updated_at}";
+
+/// The maximum number of results to return when searching for a series by name.
+const SEARCH_LIMIT: u32 = 10;
+
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// Returns the URL that the user needs to go to in order to authenticate their account
+/// so the API can make changes to it.
+///
+/// `client_id` is the ID of the application you wish to use the API with, and `redirect_uri` is the
+/// redirect URL registered for it. Both can be found in the `API` section of your account settings.
+///
+/// After authorizing the application, the user will be redirected to `redirect_uri` with `code` and `state`
+/// parameters. The URL they end up on should be passed to [`code_from_redirect`] along with `state`
+/// to get the code that can be passed to [`exchange_code`].
+#[must_use]
+pub fn auth_url(
+    client_id: &str,
+    redirect_uri: &str,
+    verifier: &CodeVerifier,
+    state: &AuthState,
+) -> String {
+    let query = form_urlencoded::Serializer::new(String::new())
+        .append_pair("response_type", "code")
+        .append_pair("client_id", client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("state", state.as_str())
+        .append_pair("code_challenge", verifier.as_str())
+        .append_pair("code_challenge_method", "plain")
+        .finish();
+
+    format!("{}/authorize?{}", OAUTH_URL, query)
+}
+
+/// Returns the authorization code from the `redirected` URL the user was sent to after visiting the URL provided by [`auth_url`].
+///
+/// `state` must be the same one that was used to create the authorization URL, and the one in `redirected` must match it,
+/// as otherwise the code may have been issued for a request this application didn't make.
+pub fn code_from_redirect(redirected: &str, state: &AuthState) -> Result<String> {
+    let redirected = redirected.trim();
+
+    let url = Url::parse(redirected).map_err(|source| Error::InvalidRedirectUrl {
+        url: redirected.into(),
+        source,
+    })?;
+
+    let mut code = None;
+    let mut returned_state = None;
+
+    for (name, value) in url.query_pairs() {
+        match name.as_ref() {
+            "code" => code = Some(value.into_owned()),
+            "state" => returned_state = Some(value),
+            _ => (),
+        }
+    }
+
+    if returned_state.as_deref() != Some(state.as_str()) {
+        return Err(Error::AuthStateMismatch);
+    }
+
+    code.ok_or(Error::MissingAuthCode)
+}
+
+/// Exchange the authorization `code` the user received from the URL provided by [`auth_url`]
+/// for an access token.
+///
+/// `redirect_uri` and `verifier` must be the same ones that were used to create the authorization URL.
+pub async fn exchange_code(
+    client: &Client,
+    code: &str,
+    redirect_uri: &str,
+    verifier: &CodeVerifier,
+) -> Result<IssuedToken> {
+    let body = form_urlencoded::Serializer::new(String::new())
+        .append_pair("client_id", &client.client_id)
+        .append_pair("grant_type", "authorization_code")
+        .append_pair("code", code.trim())
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("code_verifier", verifier.as_str())
+        .finish();
+
+    let request = client
+        .http
+        .post(format!("{}/token", OAUTH_URL))
+        .header(CONTENT_TYPE, FORM_CONTENT_TYPE)
+        .body(body);
+
+    let json = send_request(client, request, None).await?;
+    let response: TokenResponse = json::from_value(json)?;
+
+    Ok(IssuedToken::new(response.access_token, response.expires_in))
+}
+
+/// A PKCE code verifier that links an authorization request to its token request.
+///
+/// MyAnimeList only supports the `plain` challenge method, so the verifier is also used as the code challenge.
+#[derive(Clone, Debug)]
+pub struct CodeVerifier(String);
+
+impl CodeVerifier {
+    /// The number of random bytes needed to produce a verifier of the maximum allowed length (128 characters).
+    const NUM_BYTES: usize = 96;
+
+    /// Generate a new random code verifier with the operating system's random number generator.
+    pub fn generate() -> Result<Self> {
+        let mut bytes = [0; Self::NUM_BYTES];
+        getrandom::getrandom(&mut bytes)?;
+
+        Ok(Self(base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD)))
+    }
+
+    #[inline(always)]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A random value that links an authorization request to the redirect the user is sent to afterwards.
+///
+/// Checking it with [`code_from_redirect`] prevents a code from a request that another site started from being used.
+#[derive(Clone, Debug)]
+pub struct AuthState(String);
+
+impl AuthState {
+    /// The number of random bytes in the state.
+    const NUM_BYTES: usize = 32;
+
+    /// Generate a new random state with the operating system's random number generator.
+    pub fn generate() -> Result<Self> {
+        let mut bytes = [0; Self::NUM_BYTES];
+        getrandom::getrandom(&mut bytes)?;
+
+        Ok(Self(base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD)))
+    }
+
+    #[inline(always)]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A connection to the MyAnimeList API.
+#[derive(Debug)]
+pub enum Mal {
+    /// A MyAnimeList connection with authentication.
+    ///
+    /// This mode will allow you to update the specified user's list.
+    /// To get a user's token, they will need to visit the URL provided by
+    /// the `auth_url` function and provide you the code they receive, which can be
+    /// exchanged for a token with the `exchange_code` function.
+    Authenticated(Auth),
+    /// A MyAnimeList connection without any authentication.
+    ///
+    /// In this mode, you can only search for series info by name and by ID.
+    /// Trying to make any other request will return a `NeedAuthentication` error.
+    Unauthenticated(Client),
+}
+
+impl Mal {
+    fn client(&self) -> &Client {
+        match &self {
+            Self::Authenticated(auth) => &auth.client,
+            Self::Unauthenticated(client) => client,
+        }
+    }
+
+    fn auth_token(&self) -> Result<&AccessToken> {
+        match &self {
+            Self::Authenticated(auth) => Ok(&auth.token),
+            Self::Unauthenticated(_) => Err(Error::NeedAuthentication),
+        }
+    }
+
+    async fn get<T>(&self, path: &str, params: &[(&str, &str)]) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let client = self.client();
+        let request = client
+            .http
+            .get(format!("{}{}", API_URL, path))
+            .query(params);
+
+        let json = send_request(client, request, self.auth_token().ok()).await?;
+        json::from_value(json).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl RemoteService for Mal {
+    async fn search_info_by_name(&self, name: &str) -> Result<Vec<SeriesInfo>> {
+        let limit = SEARCH_LIMIT.to_string();
+
+        let results: SearchResults = self
+            .get(
+                "/anime",
+                &[("q", name), ("limit", &limit), ("fields", INFO_FIELDS)],
+            )
+            .await?;
+
+        let entries = results
+            .data
+            .into_iter()
+            .filter_map(|result| result.node.try_into().ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    async fn search_info_by_id(&self, id: SeriesID) -> Result<SeriesInfo> {
+        let info: Anime = self
+            .get(
+                &format!("/anime/{}", id),
+                &[("fields", INFO_WITH_RELATIONS_FIELDS)],
+            )
+            .await?;
+        info.try_into().map_err(|_| Error::NotAnAnime)
+    }
+
+    async fn search_relations(&self, id: SeriesID) -> Result<Vec<Relation>> {
+        let info: Anime = self
+            .get(
+                &format!("/anime/{}", id),
+                &[("fields", INFO_WITH_RELATIONS_FIELDS)],
+            )
+            .await?;
+
+        let relations = info
+            .related_anime
+            .into_iter()
+            .filter_map(|related| {
+                let kind = related.relation_type.kind()?;
+                Some(Relation::new(kind, related.node.id))
+            })
+            .collect();
+
+        Ok(relations)
+    }
+
+    async fn get_list_entry(&self, id: SeriesID) -> Result<Option<SeriesEntry>> {
+        self.auth_token()?;
+
+        let query: Result<AnimeEntry> = self
+            .get(&format!("/anime/{}", id), &[("fields", LIST_STATUS_FIELDS)])
+            .await;
+
+        match query {
+            Ok(anime) => Ok(anime
+                .my_list_status
+                .map(|status| status.into_series_entry(id))),
+            Err(ref err) if err.is_http_code(404) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn update_list_entry(&self, entry: &SeriesEntry) -> Result<()> {
+        let token = self.auth_token()?;
+
+        let body = {
+            let mut body = form_urlencoded::Serializer::new(String::new());
+
+            body.append_pair("status", ListStatusKind::from(entry.status).as_str())
+                .append_pair(
+                    "is_rewatching",
+                    &(entry.status == Status::Rewatching).to_string(),
+                )
+                .append_pair("score", &to_mal_score(entry.score.unwrap_or(0)).to_string())
+                .append_pair("num_watched_episodes", &entry.watched_eps.to_string())
+                .append_pair("num_times_rewatched", &entry.times_rewatched.to_string());
+
+            if let Some(date) = entry.start_date {
+                body.append_pair("start_date", &date.to_ymd_string());
+            }
+
+            if let Some(date) = entry.end_date {
+                body.append_pair("finish_date", &date.to_ymd_string());
+            }
+
+            body.finish()
+        };
+
+        let client = self.client();
+        let request = client
+            .http
+            .patch(format!("{}/anime/{}/my_list_status", API_URL, entry.id))
+            .header(CONTENT_TYPE, FORM_CONTENT_TYPE)
+            .body(body);
+
+        send_request(client, request, Some(token)).await?;
+        Ok(())
+    }
+
+    fn has_list(&self) -> bool {
+        matches!(self, Self::Authenticated(_))
+    }
+}
+
+impl ScoreParser for Mal {
+    fn parse_score(&self, score: &str) -> Option<u8> {
+        let score = score.parse::<u8>().ok()?;
+        (score <= 10).then(|| score * 10)
+    }
+
+    fn score_to_str(&self, score: u8) -> Cow<str> {
+        to_mal_score(score).to_string().into()
+    }
+}
+
+/// Settings used when sending requests to MyAnimeList.
+#[derive(Clone, Debug)]
+pub struct Client {
+    client_id: String,
+    proxy: Option<Url>,
+    http: reqwest::Client,
+}
+
+impl Client {
+    /// Create a new `Client` that connects to MyAnimeList directly.
+    ///
+    /// `client_id` is the ID of the application you wish to use the API with.
+    /// It can be retrieved from the `API` section of your account settings.
+    #[must_use]
+    pub fn new<S>(client_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            client_id: client_id.into(),
+            proxy: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Create a new `Client` that sends all requests through the specified `proxy` URL.
+    ///
+    /// HTTP, HTTPS, and SOCKS5 proxies are supported, where `socks5h` also resolves hostnames through the proxy.
+    pub fn with_proxy<S, P>(client_id: S, proxy: P) -> Result<Self>
+    where
+        S: Into<String>,
+        P: AsRef<str>,
+    {
+        let (proxy, http) = super::proxied_http_client(proxy.as_ref())?;
+
+        Ok(Self {
+            client_id: client_id.into(),
+            proxy: Some(proxy),
+            http,
+        })
+    }
+
+    /// Returns true if requests will be sent through a proxy.
+    #[inline(always)]
+    #[must_use]
+    pub fn uses_proxy(&self) -> bool {
+        self.proxy.is_some()
+    }
+}
+
+/// An authenticated user.
+#[derive(Debug)]
+pub struct Auth {
+    /// The MyAnimeList user's account information.
+    pub user: User,
+    token: AccessToken,
+    client: Client,
+}
+
+impl Auth {
+    #[inline(always)]
+    #[must_use]
+    pub fn new(user: User, token: AccessToken, client: Client) -> Self {
+        Self {
+            user,
+            token,
+            client,
+        }
+    }
+
+    /// Retrieve the current authorization from MyAnimeList using the specified `token`.
+    ///
+    /// The returned `Auth` will use `client` to make all further requests.
+    pub async fn retrieve(token: AccessToken, client: Client) -> Result<Self> {
+        let request = client.http.get(format!("{}/users/@me", API_URL));
+        let json = send_request(&client, request, Some(&token)).await?;
+        let user = json::from_value(json)?;
+
+        Ok(Self::new(user, token, client))
+    }
+}
+
+/// A MyAnimeList user.
+#[derive(Debug, Deserialize)]
+pub struct User {
+    /// The user's account ID.
+    pub id: u32,
+    /// The user's account name.
+    pub name: String,
+}
+
+async fn send_request(
+    client: &Client,
+    mut request: RequestBuilder,
+    token: Option<&AccessToken>,
+) -> Result<json::Value> {
+    request = request.timeout(super::REQUEST_TIMEOUT);
+
+    // Requests that aren't made on behalf of a user only need to identify the application
+    request = match token {
+        Some(token) => request.bearer_auth(token.decode()?),
+        None => request.header("X-MAL-CLIENT-ID", client.client_id.as_str()),
+    };
+
+    let request = request.build()?;
+
+    if cfg!(debug_assertions) && cfg!(feature = "print-requests-debug") {
+        println!("DEBUG: MyAnimeList request: {}", request.url());
+    }
+
+    let span =
+        tracing::debug_span!("mal_request", method = %request.method(), url = %request.url());
+    let response = client
+        .http
+        .execute(request)
+        .instrument(span.clone())
+        .await?;
+
+    if !response.status().is_success() {
+        let code = response.status().as_u16();
+        let json: json::Value = response.json().await.unwrap_or_default();
+
+        let message = json["message"]
+            .as_str()
+            .filter(|msg| !msg.is_empty())
+            .or_else(|| json["error"].as_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        span.in_scope(|| tracing::warn!(code, %message, "request failed"));
+        return Err(Error::BadMalResponse { code, message });
+    }
+
+    response.json().await.map_err(Into::into)
+}
+
+/// Map a score between 0 - 100 to MyAnimeList's range of 0 - 10.
+fn to_mal_score(score: u8) -> u8 {
+    ((u16::from(score) + 5) / 10).min(10) as u8
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResults {
+    data: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    node: Anime,
+}
+
+#[derive(Debug, Deserialize)]
+struct Anime {
+    id: u32,
+    title: String,
+    #[serde(default)]
+    alternative_titles: AlternativeTitles,
+    num_episodes: Option<u32>,
+    /// The length of an episode in seconds.
+    average_episode_duration: Option<u32>,
+    media_type: MediaType,
+    status: Option<AnimeStatus>,
+    #[serde(default)]
+    related_anime: Vec<RelatedAnime>,
+}
+
+impl Anime {
+    fn sequels(&self) -> Vec<Sequel> {
+        self.related_anime
+            .iter()
+            .filter_map(|related| related.try_into().ok())
+            .collect()
+    }
+}
+
+impl TryInto<SeriesInfo> for Anime {
+    type Error = ();
+
+    fn try_into(self) -> result::Result<SeriesInfo, Self::Error> {
+        let kind = self.media_type.try_into()?;
+        let sequels = self.sequels();
+
+        // MyAnimeList uses 0 to indicate an unknown episode count and duration
+        let episodes = self.num_episodes.filter(|&eps| eps > 0).unwrap_or(1);
+
+        let episode_length = self
+            .average_episode_duration
+            .map(|secs| (secs + 30) / 60)
+            .filter(|&mins| mins > 0)
+            .unwrap_or(24);
+
+        Ok(SeriesInfo {
+            id: self.id,
+            title: SeriesTitle {
+                romaji: self.title.clone(),
+                preferred: self.title,
+                english: non_empty(self.alternative_titles.en),
+                native: non_empty(self.alternative_titles.ja),
+                synonyms: self.alternative_titles.synonyms,
+            },
+            episodes,
+            episode_length,
+            kind,
+            sequels,
+            airing: self.status.map_or(AiringStatus::Unknown, Into::into),
+        })
+    }
+}
+
+/// The other titles of a series.
+///
+/// MyAnimeList sends an empty string for titles a series doesn't have.
+#[derive(Debug, Default, Deserialize)]
+struct AlternativeTitles {
+    #[serde(default)]
+    synonyms: Vec<String>,
+    en: Option<String>,
+    ja: Option<String>,
+}
+
+fn non_empty(title: Option<String>) -> Option<String> {
+    title.filter(|title| !title.is_empty())
+}
+
+#[derive(Debug, Deserialize)]
+struct RelatedAnime {
+    node: RelatedNode,
+    relation_type: RelationType,
+}
+
+impl TryInto<Sequel> for &RelatedAnime {
+    type Error = ();
+
+    fn try_into(self) -> result::Result<Sequel, Self::Error> {
+        if !self.relation_type.is_sequential() {
+            return Err(());
+        }
+
+        let kind = match self.node.media_type {
+            Some(media_type) => media_type.try_into()?,
+            None => return Err(()),
+        };
+
+        Ok(Sequel::new(kind, self.node.id))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RelatedNode {
+    id: u32,
+    media_type: Option<MediaType>,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RelationType {
+    Sequel,
+    Prequel,
+    SideStory,
+    SpinOff,
+    AlternativeSetting,
+    AlternativeVersion,
+    ParentStory,
+    FullStory,
+    Summary,
+    Character,
+    Other,
+    #[serde(other)]
+    Unknown,
+}
+
+impl RelationType {
+    /// Returns true if the relation is a direct continuation of the series.
+    ///
+    /// MyAnimeList uses `other` for anything from music videos to crossovers, so only actual sequels count.
+    fn is_sequential(self) -> bool {
+        matches!(self, Self::Sequel)
+    }
+
+    /// Returns the kind of relation this is, or None if it's one that doesn't link two anime together, such as a shared character.
+    fn kind(self) -> Option<RelationKind> {
+        match self {
+            Self::Sequel => Some(RelationKind::Sequel),
+            Self::Prequel => Some(RelationKind::Prequel),
+            Self::SideStory => Some(RelationKind::SideStory),
+            Self::SpinOff => Some(RelationKind::SpinOff),
+            Self::AlternativeSetting | Self::AlternativeVersion => Some(RelationKind::Alternative),
+            Self::ParentStory | Self::FullStory => Some(RelationKind::Parent),
+            Self::Summary => Some(RelationKind::Summary),
+            Self::Other => Some(RelationKind::Other),
+            Self::Character | Self::Unknown => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum MediaType {
+    TV,
+    Movie,
+    #[serde(alias = "tv_special")]
+    Special,
+    OVA,
+    ONA,
+    Music,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AnimeStatus {
+    FinishedAiring,
+    CurrentlyAiring,
+    NotYetAired,
+    #[serde(other)]
+    Other,
+}
+
+impl Into<AiringStatus> for AnimeStatus {
+    fn into(self) -> AiringStatus {
+        match self {
+            Self::FinishedAiring => AiringStatus::Finished,
+            Self::CurrentlyAiring => AiringStatus::Airing,
+            Self::NotYetAired => AiringStatus::NotYetAired,
+            Self::Other => AiringStatus::Unknown,
+        }
+    }
+}
+
+impl TryInto<SeriesKind> for MediaType {
+    type Error = ();
+
+    fn try_into(self) -> result::Result<SeriesKind, Self::Error> {
+        match self {
+            Self::TV => Ok(SeriesKind::Season),
+            Self::Movie => Ok(SeriesKind::Movie),
+            Self::Special => Ok(SeriesKind::Special),
+            Self::OVA => Ok(SeriesKind::OVA),
+            Self::ONA => Ok(SeriesKind::ONA),
+            Self::Music => Ok(SeriesKind::Music),
+            Self::Other => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AnimeEntry {
+    my_list_status: Option<ListStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListStatus {
+    status: ListStatusKind,
+    score: u8,
+    num_episodes_watched: u32,
+    #[serde(default)]
+    is_rewatching: bool,
+    #[serde(default)]
+    num_times_rewatched: u32,
+    start_date: Option<String>,
+    finish_date: Option<String>,
+    updated_at: Option<String>,
+}
+
+impl ListStatus {
+    fn into_series_entry(self, id: u32) -> SeriesEntry {
+        let score = (self.score > 0).then(|| self.score.min(10) * 10);
+
+        // MyAnimeList represents rewatching as a flag on top of the regular status
+        let status = if self.is_rewatching {
+            Status::Rewatching
+        } else {
+            self.status.into()
+        };
+
+        SeriesEntry {
+            id,
+            watched_eps: self.num_episodes_watched,
+            score,
+            status,
+            times_rewatched: self.num_times_rewatched,
+            // Dates on MyAnimeList can be partial (like `2021-04`), which will fail to parse
+            start_date: self.start_date.as_deref().and_then(SeriesDate::parse_ymd),
+            end_date: self.finish_date.as_deref().and_then(SeriesDate::parse_ymd),
+            updated_at: self.updated_at.as_deref().and_then(parse_timestamp),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum ListStatusKind {
+    Watching,
+    Completed,
+    OnHold,
+    Dropped,
+    PlanToWatch,
+}
+
+impl ListStatusKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Watching => "watching",
+            Self::Completed => "completed",
+            Self::OnHold => "on_hold",
+            Self::Dropped => "dropped",
+            Self::PlanToWatch => "plan_to_watch",
+        }
+    }
+}
+
+impl Into<Status> for ListStatusKind {
+    fn into(self) -> Status {
+        match self {
+            Self::Watching => Status::Watching,
+            Self::Completed => Status::Completed,
+            Self::OnHold => Status::OnHold,
+            Self::Dropped => Status::Dropped,
+            Self::PlanToWatch => Status::PlanToWatch,
+        }
+    }
+}
+
+impl From<Status> for ListStatusKind {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Watching => Self::Watching,
+            // Series being rewatched keep their completed status and have a rewatching flag set
+            Status::Completed | Status::Rewatching => Self::Completed,
+            Status::OnHold => Self::OnHold,
+            Status::Dropped => Self::Dropped,
+            Status::PlanToWatch => Self::PlanToWatch,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_status_to_series_entry() {
+        let status: ListStatus = json::from_value(json::json!({
+            "status": "completed",
+            "score": 8,
+            "num_episodes_watched": 12,
+            "is_rewatching": true,
+            "num_times_rewatched": 1,
+            "start_date": "2021-04-05",
+            "finish_date": "2021-06",
+        }))
+        .unwrap();
+
+        let entry = status.into_series_entry(1);
+
+        assert_eq!(entry.status, Status::Rewatching);
+        assert_eq!(entry.score, Some(80));
+        assert_eq!(entry.watched_eps, 12);
+        assert_eq!(entry.times_rewatched, 1);
+        let start_date = entry.start_date.unwrap();
+        assert_eq!(
+            (start_date.year, start_date.month, start_date.day),
+            (2021, 4, 5)
+        );
+        assert!(entry.end_date.is_none());
+
+        assert_eq!(
+            ListStatusKind::from(entry.status),
+            ListStatusKind::Completed
+        );
+    }
+
+    #[test]
+    fn anime_to_series_info() {
+        let anime: Anime = json::from_value(json::json!({
+            "id": 5114,
+            "title": "Fullmetal Alchemist: Brotherhood",
+            "num_episodes": 64,
+            "average_episode_duration": 1440,
+            "media_type": "tv",
+            "status": "finished_airing",
+            "related_anime": [
+                { "node": { "id": 6421, "media_type": "special" }, "relation_type": "side_story" },
+                { "node": { "id": 121, "media_type": "tv" }, "relation_type": "alternative_version" },
+                { "node": { "id": 9135, "media_type": "movie" }, "relation_type": "sequel" },
+                { "node": { "id": 34596, "media_type": "music" }, "relation_type": "other" },
+            ],
+        }))
+        .unwrap();
+
+        let info: SeriesInfo = anime.try_into().unwrap();
+
+        assert_eq!(info.id, 5114);
+        assert_eq!(info.episodes, 64);
+        assert_eq!(info.episode_length, 24);
+        assert_eq!(info.kind, SeriesKind::Season);
+        assert_eq!(info.airing, AiringStatus::Finished);
+        assert_eq!(info.sequels.len(), 1);
+        assert_eq!(info.sequels[0].id, 9135);
+        assert_eq!(info.sequels[0].kind, SeriesKind::Movie);
+    }
+
+    #[test]
+    fn auth_url_includes_redirect_and_state() {
+        let verifier = CodeVerifier::generate().unwrap();
+        let state = AuthState::generate().unwrap();
+        let url = auth_url("id", "http://localhost:8080/callback", &verifier, &state);
+        let url = Url::parse(&url).unwrap();
+
+        let param = |name| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        assert_eq!(
+            param("redirect_uri").as_deref(),
+            Some("http://localhost:8080/callback")
+        );
+        assert_eq!(param("state").as_deref(), Some(state.as_str()));
+        assert_eq!(param("code_challenge").as_deref(), Some(verifier.as_str()));
+    }
+
+    #[test]
+    fn redirect_state_is_verified() {
+        let state = AuthState::generate().unwrap();
+
+        let redirected = format!(
+            "http://localhost:8080/callback?code=abc123&state={}",
+            state.as_str()
+        );
+
+        assert_eq!(code_from_redirect(&redirected, &state).unwrap(), "abc123");
+
+        let forged = "http://localhost:8080/callback?code=abc123&state=forged";
+        assert!(matches!(
+            code_from_redirect(forged, &state),
+            Err(Error::AuthStateMismatch)
+        ));
+
+        let missing_state = "http://localhost:8080/callback?code=abc123";
+        assert!(matches!(
+            code_from_redirect(missing_state, &state),
+            Err(Error::AuthStateMismatch)
+        ));
+
+        let denied = format!(
+            "http://localhost:8080/callback?error=access_denied&state={}",
+            state.as_str()
+        );
+
+        assert!(matches!(
+            code_from_redirect(&denied, &state),
+            Err(Error::MissingAuthCode)
+        ));
+
+        assert!(matches!(
+            code_from_redirect("abc123", &state),
+            Err(Error::InvalidRedirectUrl { .. })
+        ));
+    }
+
+    #[test]
+    fn only_sequels_are_sequential() {
+        let kinds = [
+            ("sequel", true, Some(RelationKind::Sequel)),
+            ("side_story", false, Some(RelationKind::SideStory)),
+            (
+                "alternative_setting",
+                false,
+                Some(RelationKind::Alternative),
+            ),
+            ("full_story", false, Some(RelationKind::Parent)),
+            ("other", false, Some(RelationKind::Other)),
+            ("character", false, None),
+            ("adaptation", false, None),
+        ];
+
+        for (name, sequential, kind) in kinds {
+            let relation: RelationType = json::from_value(json::json!(name)).unwrap();
+
+            assert_eq!(relation.is_sequential(), sequential, "{}", name);
+            assert_eq!(relation.kind(), kind, "{}", name);
+        }
+    }
+
+    #[test]
+    fn score_conversion() {
+        let mal = Mal::Unauthenticated(Client::new("id"));
+
+        assert_eq!(mal.parse_score("7"), Some(70));
+        assert_eq!(mal.parse_score("11"), None);
+        assert_eq!(mal.score_to_str(75), "8");
+        assert_eq!(mal.score_to_str(100), "10");
+    }
+}
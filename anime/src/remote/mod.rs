@@ -1,14 +1,25 @@
 pub mod anilist;
+pub mod kitsu;
+pub mod mal;
+pub mod multi;
 pub mod offline;
 
-use crate::err::Result;
+use crate::err::{Error, Result};
 use crate::SeriesKind;
 use anilist::AniList;
+use async_trait::async_trait;
 use enum_dispatch::enum_dispatch;
+use kitsu::Kitsu;
+use mal::Mal;
+use multi::MultiRemote;
 use offline::Offline;
 use serde_derive::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::fmt;
+use std::iter;
+use std::ops::Range;
+use std::time::Duration;
+use url::Url;
 
 #[cfg(feature = "diesel-support")]
 use {
@@ -28,6 +39,9 @@ pub type SeriesID = u32;
 #[derive(Debug)]
 pub enum Remote {
     AniList,
+    Kitsu,
+    Mal,
+    MultiRemote,
     Offline,
 }
 
@@ -39,26 +53,117 @@ impl Remote {
     }
 }
 
+/// How long to wait for a remote service to respond before giving up on a request.
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Parse and validate the specified `proxy` URL.
+///
+/// HTTP, HTTPS, and SOCKS5 proxies are supported, where `socks5h` also resolves hostnames through the proxy.
+pub(crate) fn parse_proxy(proxy: &str) -> Result<Url> {
+    let url = Url::parse(proxy).map_err(|source| Error::InvalidProxyUrl {
+        url: proxy.into(),
+        source,
+    })?;
+
+    match url.scheme() {
+        "http" | "https" | "socks5" | "socks5h" => (),
+        scheme => return Err(Error::UnsupportedProxyScheme(scheme.into())),
+    }
+
+    if url.host_str().is_none() {
+        return Err(Error::InvalidProxyUrl {
+            url: proxy.into(),
+            source: url::ParseError::EmptyHost,
+        });
+    }
+
+    Ok(url)
+}
+
+/// Build an HTTP client that sends all requests through the specified `proxy` URL.
+///
+/// HTTP, HTTPS, and SOCKS5 proxies are supported, where `socks5h` also resolves hostnames through the proxy.
+pub fn proxied_http_client(proxy: &str) -> Result<(Url, reqwest::Client)> {
+    let url = parse_proxy(proxy)?;
+
+    let client = reqwest::Client::builder()
+        .proxy(reqwest::Proxy::all(url.as_str())?)
+        .build()?;
+
+    Ok((url, client))
+}
+
 /// Core functionality to interact with an anime tracking service.
+///
+/// Every request is asynchronous, so a Tokio runtime must be running to use any service that connects to the internet.
+#[async_trait]
 #[enum_dispatch(Remote)]
-pub trait RemoteService: ScoreParser {
+pub trait RemoteService: ScoreParser + Send + Sync {
     /// Search for an anime's information by title and return all of the matches.
-    fn search_info_by_name(&self, name: &str) -> Result<Vec<SeriesInfo>>;
+    async fn search_info_by_name(&self, name: &str) -> Result<Vec<SeriesInfo>>;
 
     /// Get an anime's information by its ID.
     /// Note that the ID will differ from service to service.
-    fn search_info_by_id(&self, id: SeriesID) -> Result<SeriesInfo>;
+    async fn search_info_by_id(&self, id: SeriesID) -> Result<SeriesInfo>;
+
+    /// Get the information of every anime with an ID in `ids`.
+    ///
+    /// Anime the service doesn't return are left out of the results.
+    /// By default, each anime is looked up with its own request and the first one to fail is returned as an error.
+    async fn search_info_by_ids(&self, ids: &[SeriesID]) -> Result<Vec<SeriesInfo>> {
+        let mut infos = Vec::with_capacity(ids.len());
+
+        for &id in ids {
+            infos.push(self.search_info_by_id(id).await?);
+        }
+
+        Ok(infos)
+    }
+
+    /// Get every anime related to the one with the specified `id`, such as its prequels, sequels, and side stories.
+    ///
+    /// By default, only the sequels in the anime's information are returned, as most services don't report any other relations.
+    async fn search_relations(&self, id: SeriesID) -> Result<Vec<Relation>> {
+        let info = self.search_info_by_id(id).await?;
+
+        let relations = info
+            .sequels
+            .into_iter()
+            .map(|sequel| Relation::new(RelationKind::Sequel, sequel.id))
+            .collect();
+
+        Ok(relations)
+    }
 
     /// Retrieve the anime list entry from the currently authenticated user.
     ///
     /// `id` is the ID of the anime, which differs from service to service.
-    fn get_list_entry(&self, id: SeriesID) -> Result<Option<SeriesEntry>>;
+    async fn get_list_entry(&self, id: SeriesID) -> Result<Option<SeriesEntry>>;
+
+    /// Retrieve every entry in the currently authenticated user's anime list, along with the information of its anime.
+    ///
+    /// Returns an error by default, as not every service can list a user's entries.
+    async fn list_entries(&self) -> Result<Vec<(SeriesInfo, SeriesEntry)>> {
+        Err(Error::ListEntriesUnsupported)
+    }
 
     /// Upload `entry` to the currently authenticated user's anime list.
     ///
     /// Please ensure that the `SeriesEntry` you are using comes from the current service
     /// in use, or you may overwrite a completely different list entry.
-    fn update_list_entry(&self, entry: &SeriesEntry) -> Result<()>;
+    async fn update_list_entry(&self, entry: &SeriesEntry) -> Result<()>;
+
+    /// Get every episode of the anime with an ID in `ids` that aired between the `since` and `until` Unix timestamps.
+    ///
+    /// Returns an empty list by default, as not every service provides an airing schedule.
+    async fn aired_episodes(
+        &self,
+        _ids: &[SeriesID],
+        _since: i64,
+        _until: i64,
+    ) -> Result<Vec<AiredEpisode>> {
+        Ok(Vec::new())
+    }
 
     /// Indicates whether or not this service is meant to be used without an internet connection.
     ///
@@ -66,6 +171,13 @@ pub trait RemoteService: ScoreParser {
     fn is_offline(&self) -> bool {
         false
     }
+
+    /// Indicates whether or not this service has a user's anime list that entries can be retrieved from and uploaded to.
+    ///
+    /// Returns true by default.
+    fn has_list(&self) -> bool {
+        true
+    }
 }
 
 /// Functionality to deal with scores from an anime tracking service.
@@ -104,6 +216,8 @@ pub struct SeriesInfo {
     pub kind: SeriesKind,
     /// An ID pointing to the sequel of this series.
     pub sequels: Vec<Sequel>,
+    /// Whether the series has finished airing.
+    pub airing: AiringStatus,
 }
 
 impl SeriesInfo {
@@ -121,11 +235,56 @@ impl SeriesInfo {
         name.make_ascii_lowercase();
 
         crate::closest_match(items, min_confidence, |info| {
-            let title = info.title.romaji.to_ascii_lowercase();
-            Some(strsim::jaro_winkler(&title, &name) as f32)
+            Some(info.title_confidence(&name, false))
+        })
+    }
+
+    /// Like [`SeriesInfo::closest_match`], but also treats `name` as a possible acronym of each title and synonym.
+    ///
+    /// This allows names like "KnY" to match "Kimetsu no Yaiba", and "SnK S2" to match "Shingeki no Kyojin Season 2". An acronym match only boosts the confidence
+    /// of an item, so a title that is closer to `name` as a whole will still be preferred.
+    #[inline]
+    pub fn closest_match_with_acronyms<'a, I, S>(
+        name: S,
+        min_confidence: f32,
+        items: I,
+    ) -> Option<(usize, Cow<'a, Self>)>
+    where
+        I: Iterator<Item = Cow<'a, Self>>,
+        S: Into<String>,
+    {
+        let mut name = name.into();
+        name.make_ascii_lowercase();
+
+        crate::closest_match(items, min_confidence, |info| {
+            Some(info.title_confidence(&name, true))
         })
     }
 
+    /// Returns how closely the lowercase `name` matches the closest title of the series, from 0.0 to 1.0.
+    ///
+    /// Every title is considered, including the English and native titles and synonyms.
+    /// If `match_acronyms` is set, `name` being an acronym of a title is also treated as a strong match.
+    #[must_use]
+    pub fn title_confidence(&self, name: &str, match_acronyms: bool) -> f32 {
+        /// The confidence given to a title that `name` is an acronym of.
+        const ACRONYM_CONFIDENCE: f32 = 0.95;
+
+        self.title
+            .all()
+            .map(|title| {
+                let title = title.to_lowercase();
+                let score = strsim::jaro_winkler(&title, name) as f32;
+
+                if match_acronyms && is_acronym_of(name, &title) {
+                    score.max(ACRONYM_CONFIDENCE)
+                } else {
+                    score
+                }
+            })
+            .fold(0.0, f32::max)
+    }
+
     /// Returns the first sequel that is the same kind as the current series.
     ///
     /// This can be used to follow sequel trails of seasons.
@@ -155,6 +314,119 @@ impl<'a> Into<Cow<'a, SeriesInfo>> for &'a SeriesInfo {
     }
 }
 
+/// Returns true if `name` is an acronym formed from the first letter of each word in `title`.
+///
+/// Both the acronym of every word and the acronym of only the significant words (skipping particles like "no")
+/// are checked. Both `name` and `title` are expected to be lowercase.
+///
+/// `name` may also end with a season, as in "snk s2" or "snk season 2". In that case, the acronym must match
+/// `title` without its own season, and both seasons must be the same. A title without a season is treated as
+/// the first one.
+///
+/// To avoid false positives, the acronym in `name` must be a single short word and at least 2 letters long.
+fn is_acronym_of(name: &str, title: &str) -> bool {
+    if is_plain_acronym_of(name, title) {
+        return true;
+    }
+
+    let (name, season) = match split_season(name) {
+        (name, Some(season)) => (name, season),
+        (_, None) => return false,
+    };
+
+    let (title, title_season) = split_season(title);
+    title_season.unwrap_or(1) == season && is_plain_acronym_of(name, title)
+}
+
+/// Returns true if `name` is an acronym of the whole `title`, without considering seasons.
+fn is_plain_acronym_of(name: &str, title: &str) -> bool {
+    /// Words that are commonly left out of acronyms.
+    const MINOR_WORDS: [&str; 12] = [
+        "a", "an", "the", "of", "to", "no", "wa", "ga", "wo", "ni", "de", "e",
+    ];
+
+    if name.len() < 2 || name.len() > 8 || !name.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+        return false;
+    }
+
+    let words = title
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+
+    let full = acronym(words.iter().copied());
+    let significant = acronym(
+        words
+            .iter()
+            .copied()
+            .filter(|word| !MINOR_WORDS.contains(word)),
+    );
+
+    [full, significant]
+        .iter()
+        .any(|acronym| acronym.len() >= 2 && acronym == name)
+}
+
+/// Join the first letter of each word in `words` together.
+///
+/// Numbers are kept whole, as in "Steins;Gate 0" => "sg0".
+fn acronym<'a, I>(words: I) -> String
+where
+    I: Iterator<Item = &'a str>,
+{
+    words
+        .map(|word| {
+            if word.chars().all(|ch| ch.is_ascii_digit()) {
+                word
+            } else {
+                word.char_indices()
+                    .nth(1)
+                    .map_or(word, |(end, _)| &word[..end])
+            }
+        })
+        .collect()
+}
+
+/// Split a trailing season off of `text`, as in "snk s2", "snk season 2", "snk 2nd season", or "snk 2".
+///
+/// Returns `text` unchanged with no season if it doesn't end with one.
+fn split_season(text: &str) -> (&str, Option<u32>) {
+    fn parse_ordinal(word: &str) -> Option<u32> {
+        ["st", "nd", "rd", "th"]
+            .iter()
+            .find_map(|suffix| word.strip_suffix(suffix))
+            .and_then(|num| num.parse().ok())
+    }
+
+    let text = text.trim();
+
+    let (rest, last) = match text.rsplit_once(char::is_whitespace) {
+        Some((rest, last)) => (rest.trim_end(), last),
+        None => return (text, None),
+    };
+
+    let split = if last == "season" {
+        rest.rsplit_once(char::is_whitespace)
+            .and_then(|(rest, ordinal)| Some((rest.trim_end(), parse_ordinal(ordinal)?)))
+    } else if let Ok(season) = last.parse() {
+        let rest = match rest.rsplit_once(char::is_whitespace) {
+            Some((before, "season")) => before.trim_end(),
+            _ => rest,
+        };
+
+        Some((rest, season))
+    } else {
+        last.strip_prefix('s')
+            .and_then(|num| num.parse().ok())
+            .map(|season| (rest, season))
+    };
+
+    match split {
+        Some((rest, season)) if !rest.is_empty() => (rest, Some(season)),
+        _ => (text, None),
+    }
+}
+
 /// A sequel to a series.
 #[derive(Clone, Debug)]
 pub struct Sequel {
@@ -172,17 +444,80 @@ impl Sequel {
     }
 }
 
+/// An anime related to another one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Relation {
+    /// How the anime is related.
+    pub kind: RelationKind,
+    /// The series ID of the related anime.
+    pub id: SeriesID,
+}
+
+impl Relation {
+    #[inline(always)]
+    #[must_use]
+    pub fn new(kind: RelationKind, id: SeriesID) -> Self {
+        Self { kind, id }
+    }
+}
+
+/// The ways an anime can be related to another one.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RelationKind {
+    Prequel,
+    Sequel,
+    SideStory,
+    SpinOff,
+    Alternative,
+    Parent,
+    Summary,
+    Other,
+}
+
+impl RelationKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Prequel => "Prequel",
+            Self::Sequel => "Sequel",
+            Self::SideStory => "Side Story",
+            Self::SpinOff => "Spin-off",
+            Self::Alternative => "Alternative",
+            Self::Parent => "Parent",
+            Self::Summary => "Summary",
+            Self::Other => "Other",
+        }
+    }
+}
+
 /// Various title formats for an anime series.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct SeriesTitle {
     /// The title in romaji.
     pub romaji: String,
     /// The title in the user's preferred format.
     pub preferred: String,
+    /// The official English title, if the series has one.
+    pub english: Option<String>,
+    /// The title in the native language of the series, if known.
+    pub native: Option<String>,
+    /// Alternative titles and abbreviations the series is known by.
+    pub synonyms: Vec<String>,
+}
+
+impl SeriesTitle {
+    /// Returns every title of the series, starting with the romaji title.
+    pub fn all(&self) -> impl Iterator<Item = &str> {
+        iter::once(self.romaji.as_str())
+            .chain(iter::once(self.preferred.as_str()))
+            .chain(self.english.as_deref())
+            .chain(self.native.as_deref())
+            .chain(self.synonyms.iter().map(String::as_str))
+    }
 }
 
 /// A list entry for an anime series.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SeriesEntry {
     /// The ID of the anime.
     pub id: u32,
@@ -198,6 +533,10 @@ pub struct SeriesEntry {
     pub start_date: Option<SeriesDate>,
     /// The date the user finished watching the series.
     pub end_date: Option<SeriesDate>,
+    /// When the entry was last changed on the service, in seconds since the Unix epoch.
+    ///
+    /// This is only known for services that report it.
+    pub updated_at: Option<i64>,
 }
 
 impl SeriesEntry {
@@ -213,6 +552,7 @@ impl SeriesEntry {
             times_rewatched: 0,
             start_date: None,
             end_date: None,
+            updated_at: None,
         }
     }
 }
@@ -298,7 +638,95 @@ where
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+/// An episode that has aired according to a service's airing schedule.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AiredEpisode {
+    /// The ID of the anime the episode belongs to.
+    pub id: SeriesID,
+    pub episode: u32,
+    /// When the episode aired, as a Unix timestamp.
+    pub aired_at: i64,
+}
+
+/// The airing status of an anime series.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "diesel-support",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "SmallInt"
+)]
+pub enum AiringStatus {
+    Finished,
+    Airing,
+    NotYetAired,
+    Cancelled,
+    /// The remote service did not say.
+    Unknown,
+}
+
+impl Default for AiringStatus {
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+impl Into<&'static str> for AiringStatus {
+    fn into(self) -> &'static str {
+        match self {
+            Self::Finished => "Finished",
+            Self::Airing => "Airing",
+            Self::NotYetAired => "Not Yet Aired",
+            Self::Cancelled => "Cancelled",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for AiringStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value: &'static str = (*self).into();
+        write!(f, "{}", value)
+    }
+}
+
+#[cfg(feature = "diesel-support")]
+impl<DB> FromSql<SmallInt, DB> for AiringStatus
+where
+    DB: diesel::backend::Backend,
+    i16: FromSql<SmallInt, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> deserialize::Result<Self> {
+        match i16::from_sql(bytes)? {
+            1 => Ok(AiringStatus::Finished),
+            2 => Ok(AiringStatus::Airing),
+            3 => Ok(AiringStatus::NotYetAired),
+            4 => Ok(AiringStatus::Cancelled),
+            5 => Ok(AiringStatus::Unknown),
+            other => Err(format!("invalid airing status: {}", other).into()),
+        }
+    }
+}
+
+#[cfg(feature = "diesel-support")]
+impl<DB> ToSql<SmallInt, DB> for AiringStatus
+where
+    DB: diesel::backend::Backend,
+    i16: ToSql<SmallInt, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> serialize::Result {
+        let value = match self {
+            AiringStatus::Finished => 1,
+            AiringStatus::Airing => 2,
+            AiringStatus::NotYetAired => 3,
+            AiringStatus::Cancelled => 4,
+            AiringStatus::Unknown => 5,
+        };
+
+        value.to_sql(out)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(
     feature = "diesel-support",
     derive(AsExpression, FromSqlRow),
@@ -317,6 +745,78 @@ impl SeriesDate {
     pub fn from_ymd(year: u16, month: u8, day: u8) -> Self {
         Self { year, month, day }
     }
+
+    /// Parse a date that starts with a full `YYYY-MM-DD` date, such as `2021-04-05` or `2021-04-05T00:00:00.000Z`.
+    ///
+    /// Returns None if the date is missing its year, month, or day.
+    #[must_use]
+    pub fn parse_ymd(date: &str) -> Option<Self> {
+        let mut parts = date.get(..10)?.splitn(3, '-');
+
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+
+        Some(Self::from_ymd(year, month, day))
+    }
+
+    /// Format the date as `YYYY-MM-DD`.
+    #[must_use]
+    pub fn to_ymd_string(self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Parse an RFC 3339 timestamp, like `2021-04-05T09:30:00+09:00` or `2021-04-05T00:30:00.000Z`, into seconds since the Unix epoch.
+///
+/// Fractions of a second are ignored.
+fn parse_timestamp(time: &str) -> Option<i64> {
+    let date = SeriesDate::parse_ymd(time)?;
+    let clock = time.get(10..)?.strip_prefix('T')?;
+
+    let field = |range: Range<usize>| -> Option<i64> { clock.get(range)?.parse().ok() };
+
+    if clock.get(2..3)? != ":" || clock.get(5..6)? != ":" {
+        return None;
+    }
+
+    let secs_of_day = field(0..2)? * 3600 + field(3..5)? * 60 + field(6..8)?;
+
+    let offset = clock
+        .get(8..)?
+        .trim_start_matches(|ch: char| ch == '.' || ch.is_ascii_digit());
+
+    let offset_secs = match offset {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match offset.get(..1)? {
+                "+" => 1,
+                "-" => -1,
+                _ => return None,
+            };
+
+            let hours: i64 = offset.get(1..3)?.parse().ok()?;
+            let mins: i64 = offset.get(4..6)?.parse().ok()?;
+
+            sign * (hours * 3600 + mins * 60)
+        }
+    };
+
+    let days = days_since_epoch(date.year.into(), date.month.into(), date.day.into());
+
+    Some(days * 86400 + secs_of_day - offset_secs)
+}
+
+/// Returns the number of days between the Unix epoch and the given date in the proleptic Gregorian calendar.
+fn days_since_epoch(year: i64, month: i64, day: i64) -> i64 {
+    // Counting years from March puts the leap day at the end of the year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
 }
 
 #[cfg(feature = "diesel-support")]
@@ -430,3 +930,178 @@ impl fmt::Debug for AccessToken {
         write!(f, "AccessToken {{}}")
     }
 }
+
+/// An access token that was just issued by a remote service.
+#[derive(Debug)]
+pub struct IssuedToken {
+    pub token: AccessToken,
+    /// How long the token can be used for, if the remote service said.
+    pub expires_in: Option<Duration>,
+}
+
+impl IssuedToken {
+    fn new<S>(token: S, expires_in_secs: Option<u64>) -> Self
+    where
+        S: AsRef<[u8]>,
+    {
+        Self {
+            token: AccessToken::encode(token),
+            expires_in: expires_in_secs.map(Duration::from_secs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series_info(romaji: &str) -> SeriesInfo {
+        SeriesInfo {
+            id: 1,
+            title: SeriesTitle {
+                romaji: romaji.into(),
+                preferred: romaji.into(),
+                ..SeriesTitle::default()
+            },
+            episodes: 12,
+            episode_length: 24,
+            kind: SeriesKind::Season,
+            sequels: Vec::new(),
+            airing: AiringStatus::Finished,
+        }
+    }
+
+    #[test]
+    fn acronym_detection() {
+        let tests = [
+            ("kny", "kimetsu no yaiba", true),
+            ("ky", "kimetsu no yaiba", true),
+            ("snk", "shingeki no kyojin", true),
+            ("sg0", "steins;gate 0", true),
+            ("opm", "one punch man", true),
+            ("kny", "kimi no na wa.", false),
+            ("k", "kimetsu", false),
+            ("kny kny", "kimetsu no yaiba kimetsu no yaiba", false),
+            ("kimetsu", "kimetsu no yaiba", false),
+            ("snk s2", "shingeki no kyojin season 2", true),
+            ("snk s2", "shingeki no kyojin 2nd season", true),
+            ("snk season 2", "shingeki no kyojin 2", true),
+            ("snk 2", "shingeki no kyojin s2", true),
+            ("snk s1", "shingeki no kyojin", true),
+            ("snk s2", "shingeki no kyojin", false),
+            ("snk s3", "shingeki no kyojin season 2", false),
+            ("s2", "shingeki no kyojin season 2", false),
+        ];
+
+        for (name, title, expected) in &tests {
+            assert_eq!(
+                is_acronym_of(name, title),
+                *expected,
+                "acronym mismatch: {} => {}",
+                name,
+                title
+            );
+        }
+    }
+
+    #[test]
+    fn acronym_matches_full_title() {
+        let items = [
+            series_info("Kimi no Na wa."),
+            series_info("Kimetsu no Yaiba"),
+            series_info("Shingeki no Kyojin"),
+        ];
+
+        let found =
+            SeriesInfo::closest_match_with_acronyms("KnY", 0.85, items.iter().map(Cow::Borrowed));
+
+        assert_eq!(found.map(|(index, _)| index), Some(1));
+
+        let found =
+            SeriesInfo::closest_match_with_acronyms("SnK", 0.85, items.iter().map(Cow::Borrowed));
+
+        assert_eq!(found.map(|(index, _)| index), Some(2));
+
+        // Acronyms shouldn't match unless explicitly asked for
+        let found = SeriesInfo::closest_match("KnY", 0.85, items.iter().map(Cow::Borrowed));
+        assert!(found.is_none());
+    }
+
+    #[test]
+    fn acronym_does_not_override_closer_title() {
+        let items = [series_info("Opm"), series_info("One Punch Man")];
+
+        let found =
+            SeriesInfo::closest_match_with_acronyms("opm", 0.85, items.iter().map(Cow::Borrowed));
+
+        assert_eq!(found.map(|(index, _)| index), Some(0));
+    }
+
+    #[test]
+    fn acronym_with_season_matches_every_title() {
+        let mut first = series_info("Shingeki no Kyojin");
+        first.title.english = Some("Attack on Titan".into());
+
+        let mut second = series_info("Shingeki no Kyojin 2");
+        second.title.english = Some("Attack on Titan Season 2".into());
+
+        let mut third = series_info("Shingeki no Kyojin 3");
+        third.title.synonyms = vec!["Attack on Titan Season 3".into()];
+
+        let items = [first, second, third];
+
+        let tests = [
+            ("SnK S2", 1),
+            ("SnK Season 3", 2),
+            ("AoT S2", 1),
+            ("AoT 3", 2),
+        ];
+
+        for (name, expected) in &tests {
+            let found = SeriesInfo::closest_match_with_acronyms(
+                *name,
+                0.85,
+                items.iter().map(Cow::Borrowed),
+            );
+
+            assert_eq!(found.map(|(index, _)| index), Some(*expected), "{}", name);
+        }
+    }
+
+    #[test]
+    fn english_titles_and_synonyms_match() {
+        let mut attack = series_info("Shingeki no Kyojin");
+        attack.title.english = Some("Attack on Titan".into());
+
+        let mut railgun = series_info("Toaru Kagaku no Railgun");
+        railgun.title.synonyms = vec!["Railgun".into()];
+
+        let items = [series_info("Kimetsu no Yaiba"), attack, railgun];
+
+        let found =
+            SeriesInfo::closest_match("Attack on Titan", 0.85, items.iter().map(Cow::Borrowed));
+
+        assert_eq!(found.map(|(index, _)| index), Some(1));
+
+        let found = SeriesInfo::closest_match("railgun", 0.85, items.iter().map(Cow::Borrowed));
+        assert_eq!(found.map(|(index, _)| index), Some(2));
+    }
+
+    #[test]
+    fn timestamps_are_parsed() {
+        let tests = [
+            ("1970-01-01T00:00:00Z", Some(0)),
+            ("2017-11-11T19:51:22+00:00", Some(1_510_429_882)),
+            ("2017-08-11T20:48:10.812Z", Some(1_502_484_490)),
+            ("2021-04-05T09:30:00+09:00", Some(1_617_582_600)),
+            ("2000-02-29T23:59:59-05:00", Some(951_886_799)),
+            ("2021-04", None),
+            ("2021-04-05", None),
+            ("2021-04-05T09:30:00", None),
+        ];
+
+        for (time, expected) in &tests {
+            assert_eq!(parse_timestamp(time), *expected, "{}", time);
+        }
+    }
+}
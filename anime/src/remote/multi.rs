@@ -0,0 +1,267 @@
+use super::{
+    AiredEpisode, Relation, Remote, RemoteService, ScoreParser, SeriesEntry, SeriesID, SeriesInfo,
+};
+use crate::err::Result;
+use async_trait::async_trait;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A remote service that sends list updates to several services at once.
+///
+/// The primary service is used for everything other than list entries, so all series IDs
+/// and scores are from the primary service. Since series IDs differ between services, the matching series
+/// on each linked service is found by searching for the series' title from the primary service.
+#[derive(Debug)]
+pub struct MultiRemote {
+    primary: Box<Remote>,
+    linked: Vec<LinkedRemote>,
+}
+
+impl MultiRemote {
+    /// The minimum confidence needed for a series on a linked service to be considered the same as
+    /// a series on the primary service.
+    const MIN_CONFIDENCE: f32 = 0.85;
+
+    #[must_use]
+    pub fn new(primary: Remote, linked: Vec<Remote>) -> Self {
+        Self {
+            primary: Box::new(primary),
+            linked: linked.into_iter().map(LinkedRemote::new).collect(),
+        }
+    }
+
+    /// Returns the service that all series IDs and scores come from.
+    #[inline(always)]
+    #[must_use]
+    pub fn primary(&self) -> &Remote {
+        &self.primary
+    }
+
+    /// Returns an iterator over the primary service, followed by all linked services.
+    pub fn remotes(&self) -> impl Iterator<Item = &Remote> {
+        std::iter::once(self.primary.as_ref())
+            .chain(self.linked.iter().map(|linked| &linked.remote))
+    }
+
+    /// Find the ID of the series on the `linked` service that matches the series with the specified `id`
+    /// from the primary service.
+    ///
+    /// Returns None if no matching series could be found.
+    async fn linked_id(&self, linked: &LinkedRemote, id: SeriesID) -> Result<Option<SeriesID>> {
+        let cached = linked.lock_ids().get(&id).copied();
+
+        if let Some(linked_id) = cached {
+            return Ok(linked_id);
+        }
+
+        let info = self.primary.search_info_by_id(id).await?;
+        let results = linked
+            .remote
+            .search_info_by_name(&info.title.romaji)
+            .await?;
+
+        let found = results
+            .into_iter()
+            .filter(|result| result.kind == info.kind);
+        let linked_id = SeriesInfo::closest_match(
+            &info.title.romaji,
+            Self::MIN_CONFIDENCE,
+            found.map(Into::into),
+        )
+        .map(|(_, found)| found.id);
+
+        linked.lock_ids().insert(id, linked_id);
+        Ok(linked_id)
+    }
+}
+
+#[async_trait]
+impl RemoteService for MultiRemote {
+    async fn search_info_by_name(&self, name: &str) -> Result<Vec<SeriesInfo>> {
+        self.primary.search_info_by_name(name).await
+    }
+
+    async fn search_info_by_id(&self, id: SeriesID) -> Result<SeriesInfo> {
+        self.primary.search_info_by_id(id).await
+    }
+
+    async fn search_info_by_ids(&self, ids: &[SeriesID]) -> Result<Vec<SeriesInfo>> {
+        self.primary.search_info_by_ids(ids).await
+    }
+
+    async fn search_relations(&self, id: SeriesID) -> Result<Vec<Relation>> {
+        self.primary.search_relations(id).await
+    }
+
+    async fn list_entries(&self) -> Result<Vec<(SeriesInfo, SeriesEntry)>> {
+        self.primary.list_entries().await
+    }
+
+    async fn get_list_entry(&self, id: SeriesID) -> Result<Option<SeriesEntry>> {
+        let mut newest = self.primary.get_list_entry(id).await?;
+
+        for linked in &self.linked {
+            let linked_id = match self.linked_id(linked, id).await? {
+                Some(linked_id) => linked_id,
+                None => continue,
+            };
+
+            let entry = match linked.remote.get_list_entry(linked_id).await? {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            let is_newer = newest
+                .as_ref()
+                .map_or(true, |newest| is_newer(&entry, newest));
+
+            if is_newer {
+                newest = Some(SeriesEntry { id, ..entry });
+            }
+        }
+
+        Ok(newest)
+    }
+
+    async fn update_list_entry(&self, entry: &SeriesEntry) -> Result<()> {
+        let mut result = self.primary.update_list_entry(entry).await;
+
+        // Every service should still be updated if one of them fails
+        for linked in &self.linked {
+            let linked_result = match self.linked_id(linked, entry.id).await {
+                Ok(Some(linked_id)) => {
+                    let linked_entry = SeriesEntry {
+                        id: linked_id,
+                        ..entry.clone()
+                    };
+
+                    linked.remote.update_list_entry(&linked_entry).await
+                }
+                Ok(None) => Ok(()),
+                Err(err) => Err(err),
+            };
+
+            if result.is_ok() {
+                result = linked_result;
+            }
+        }
+
+        result
+    }
+
+    async fn aired_episodes(
+        &self,
+        ids: &[SeriesID],
+        since: i64,
+        until: i64,
+    ) -> Result<Vec<AiredEpisode>> {
+        self.primary.aired_episodes(ids, since, until).await
+    }
+
+    fn is_offline(&self) -> bool {
+        self.primary.is_offline()
+    }
+
+    fn has_list(&self) -> bool {
+        self.primary.has_list()
+    }
+}
+
+impl ScoreParser for MultiRemote {
+    fn parse_score(&self, score: &str) -> Option<u8> {
+        self.primary.parse_score(score)
+    }
+
+    fn score_to_str(&self, score: u8) -> Cow<str> {
+        self.primary.score_to_str(score)
+    }
+}
+
+#[derive(Debug)]
+struct LinkedRemote {
+    remote: Remote,
+    /// Series IDs from the primary service mapped to their ID on this service.
+    ids: Mutex<HashMap<SeriesID, Option<SeriesID>>>,
+}
+
+impl LinkedRemote {
+    fn new(remote: Remote) -> Self {
+        Self {
+            remote,
+            ids: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock_ids(&self) -> std::sync::MutexGuard<HashMap<SeriesID, Option<SeriesID>>> {
+        // The map is always left in a valid state, so it can still be used if a thread panicked while holding it
+        self.ids
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Returns true if `entry` was changed more recently than `other`.
+///
+/// Entries are compared by when they were last updated on their service. When either of them doesn't report that,
+/// the entry that is the furthest along is assumed to be the newest, since progress is rarely undone.
+fn is_newer(entry: &SeriesEntry, other: &SeriesEntry) -> bool {
+    match (entry.updated_at, other.updated_at) {
+        (Some(updated), Some(other_updated)) => updated > other_updated,
+        _ => progress_of(entry) > progress_of(other),
+    }
+}
+
+/// Returns a value that increases as more of the series in `entry` is watched.
+fn progress_of(entry: &SeriesEntry) -> (u32, u32) {
+    (entry.times_rewatched, entry.watched_eps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote::offline::Offline;
+
+    #[test]
+    fn furthest_entry_wins_without_update_times() {
+        let mut old = SeriesEntry::new(1);
+        old.watched_eps = 3;
+
+        let mut new = SeriesEntry::new(1);
+        new.watched_eps = 5;
+
+        let mut rewatch = SeriesEntry::new(1);
+        rewatch.watched_eps = 1;
+        rewatch.times_rewatched = 1;
+
+        assert!(is_newer(&new, &old));
+        assert!(is_newer(&rewatch, &new));
+        assert!(!is_newer(&old, &new));
+    }
+
+    #[test]
+    fn most_recently_updated_entry_wins() {
+        let mut rewound = SeriesEntry::new(1);
+        rewound.watched_eps = 2;
+        rewound.updated_at = Some(200);
+
+        let mut stale = SeriesEntry::new(1);
+        stale.watched_eps = 5;
+        stale.updated_at = Some(100);
+
+        assert!(is_newer(&rewound, &stale));
+        assert!(!is_newer(&stale, &rewound));
+
+        // An entry without an update time can only be compared by progress
+        stale.updated_at = None;
+        assert!(is_newer(&stale, &rewound));
+    }
+
+    #[test]
+    fn remotes_starts_with_primary() {
+        let multi = MultiRemote::new(Offline::new().into(), vec![Offline::new().into()]);
+
+        assert_eq!(multi.remotes().count(), 2);
+        assert!(multi.is_offline());
+    }
+}
@@ -1,5 +1,10 @@
 use super::{RemoteService, ScoreParser, SeriesEntry, SeriesID, SeriesInfo};
 use crate::err::{self, Result};
+use async_trait::async_trait;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 
 /// A remote service that will not connect to the internet.
 ///
@@ -7,39 +12,173 @@ use crate::err::{self, Result};
 /// for your application by letting you seamlessly switch between online and offline
 /// modes.
 ///
-/// Note that the `search_info_by_name` and `search_info_by_id` methods will always
-/// return an error with the variant `NeedExistingSeriesData`. All other methods simply
+/// Series info can only be found for series that were given to the service with
+/// `Offline::with_cache`. The `search_info_by_name` and `search_info_by_id` methods will return
+/// an error with the variant `NeedExistingSeriesData` for everything else. All other methods simply
 /// do nothing.
-#[derive(Debug, Default)]
-pub struct Offline;
+///
+/// Scores are parsed and shown as a number between 0 - 100 unless a parser is given with `Offline::with_score_parser`.
+#[derive(Default)]
+pub struct Offline {
+    cache: HashMap<SeriesID, SeriesInfo>,
+    score_parser: Option<Arc<dyn ScoreParser + Send + Sync>>,
+}
 
 impl Offline {
+    /// The minimum similarity a cached title needs to have with a search to be returned from `search_info_by_name`.
+    const MIN_NAME_SIMILARITY: f64 = 0.7;
+
     #[must_use]
     pub fn new() -> Offline {
-        Offline {}
+        Offline::default()
+    }
+
+    /// Create a new `Offline` service that can look up series info from `cache`.
+    #[must_use]
+    pub fn with_cache<I>(cache: I) -> Offline
+    where
+        I: IntoIterator<Item = SeriesInfo>,
+    {
+        Offline {
+            cache: cache.into_iter().map(|info| (info.id, info)).collect(),
+            score_parser: None,
+        }
+    }
+
+    /// Parse and show scores with `parser`, such as the score format of the user of the service being replaced.
+    #[must_use]
+    pub fn with_score_parser<P>(mut self, parser: P) -> Offline
+    where
+        P: ScoreParser + Send + Sync + 'static,
+    {
+        self.score_parser = Some(Arc::new(parser));
+        self
     }
 }
 
+impl fmt::Debug for Offline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Offline")
+            .field("cache", &self.cache)
+            .field("has_score_parser", &self.score_parser.is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
 impl RemoteService for Offline {
-    fn search_info_by_name(&self, _: &str) -> Result<Vec<SeriesInfo>> {
-        Err(err::Error::NeedExistingSeriesData)
+    async fn search_info_by_name(&self, name: &str) -> Result<Vec<SeriesInfo>> {
+        let name = name.to_lowercase();
+
+        let is_similar = |title: &str| {
+            let title = title.to_lowercase();
+            title.contains(&name)
+                || strsim::jaro_winkler(&title, &name) >= Self::MIN_NAME_SIMILARITY
+        };
+
+        let results = self
+            .cache
+            .values()
+            .filter(|info| info.title.all().any(is_similar))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if results.is_empty() {
+            return Err(err::Error::NeedExistingSeriesData);
+        }
+
+        Ok(results)
+    }
+
+    async fn search_info_by_id(&self, id: SeriesID) -> Result<SeriesInfo> {
+        self.cache
+            .get(&id)
+            .cloned()
+            .ok_or(err::Error::NeedExistingSeriesData)
     }
 
-    fn search_info_by_id(&self, _: SeriesID) -> Result<SeriesInfo> {
-        Err(err::Error::NeedExistingSeriesData)
+    async fn search_info_by_ids(&self, ids: &[SeriesID]) -> Result<Vec<SeriesInfo>> {
+        let infos = ids
+            .iter()
+            .filter_map(|id| self.cache.get(id))
+            .cloned()
+            .collect();
+
+        Ok(infos)
     }
 
-    fn get_list_entry(&self, _: SeriesID) -> Result<Option<SeriesEntry>> {
+    async fn get_list_entry(&self, _: SeriesID) -> Result<Option<SeriesEntry>> {
         Ok(None)
     }
 
-    fn update_list_entry(&self, _: &SeriesEntry) -> Result<()> {
+    async fn update_list_entry(&self, _: &SeriesEntry) -> Result<()> {
         Ok(())
     }
 
     fn is_offline(&self) -> bool {
         true
     }
+
+    fn has_list(&self) -> bool {
+        false
+    }
+}
+
+impl ScoreParser for Offline {
+    fn parse_score(&self, score: &str) -> Option<u8> {
+        match &self.score_parser {
+            Some(parser) => parser.parse_score(score),
+            None => score
+                .parse()
+                .ok()
+                .and_then(|score| (score <= 100).then(|| score)),
+        }
+    }
+
+    fn score_to_str(&self, score: u8) -> Cow<str> {
+        match &self.score_parser {
+            Some(parser) => parser.score_to_str(score),
+            None => Cow::Owned(score.to_string()),
+        }
+    }
 }
 
-impl ScoreParser for Offline {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote::{AiringStatus, SeriesTitle};
+    use crate::SeriesKind;
+
+    fn info(id: SeriesID, title: &str) -> SeriesInfo {
+        SeriesInfo {
+            id,
+            title: SeriesTitle {
+                romaji: title.into(),
+                preferred: title.into(),
+                ..SeriesTitle::default()
+            },
+            episodes: 12,
+            episode_length: 24,
+            kind: SeriesKind::Season,
+            sequels: Vec::new(),
+            airing: AiringStatus::Finished,
+        }
+    }
+
+    #[tokio::test]
+    async fn search_cached_info() {
+        let offline = Offline::with_cache(vec![
+            info(1, "Kono Subarashii Sekai ni Shukufuku wo!"),
+            info(2, "Made in Abyss"),
+        ]);
+
+        assert_eq!(offline.search_info_by_id(2).await.unwrap().id, 2);
+        assert!(offline.search_info_by_id(3).await.is_err());
+
+        let results = offline.search_info_by_name("made in abyss").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, 2);
+
+        assert!(offline.search_info_by_name("Steins;Gate").await.is_err());
+    }
+}
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS series_aliases (
+    alias TEXT NOT NULL PRIMARY KEY,
+    series_id INTEGER NOT NULL,
+    FOREIGN KEY(series_id) REFERENCES series_configs(id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+ALTER TABLE series_configs ADD COLUMN local_rewatch SMALLINT;
\ No newline at end of file
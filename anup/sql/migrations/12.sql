@@ -0,0 +1,3 @@
+ALTER TABLE series_configs ADD COLUMN sub_lang TEXT;
+ALTER TABLE series_configs ADD COLUMN audio_lang TEXT;
+ALTER TABLE series_configs ADD COLUMN sub_file_pattern TEXT;
\ No newline at end of file
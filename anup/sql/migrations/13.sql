@@ -0,0 +1 @@
+ALTER TABLE series_configs ADD COLUMN stream_url_template TEXT;
\ No newline at end of file
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS entry_changes (
+    id INTEGER NOT NULL PRIMARY KEY,
+    series_id INTEGER NOT NULL,
+    field TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    changed_at BIGINT NOT NULL,
+    FOREIGN KEY(series_id) REFERENCES series_configs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS entry_changes_series_id ON entry_changes(series_id);
\ No newline at end of file
@@ -0,0 +1,6 @@
+ALTER TABLE series_info ADD COLUMN title_english TEXT;
+ALTER TABLE series_info ADD COLUMN title_native TEXT;
+
+ALTER TABLE cached_info ADD COLUMN title_english TEXT;
+ALTER TABLE cached_info ADD COLUMN title_native TEXT;
+ALTER TABLE cached_info ADD COLUMN title_synonyms TEXT NOT NULL DEFAULT '';
\ No newline at end of file
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS series_synonyms (
+    series_id INTEGER NOT NULL,
+    synonym TEXT NOT NULL,
+    PRIMARY KEY(series_id, synonym),
+    FOREIGN KEY(series_id) REFERENCES series_configs(id) ON DELETE CASCADE
+);
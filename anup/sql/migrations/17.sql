@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS series_list_view (
+    id INTEGER NOT NULL PRIMARY KEY CHECK (id = 1),
+    sort TEXT NOT NULL,
+    status_filter SMALLINT
+);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS series_tags (
+    series_id INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY(series_id, tag),
+    FOREIGN KEY(series_id) REFERENCES series_configs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS series_tags_tag ON series_tags(tag);
+
+ALTER TABLE series_list_view ADD COLUMN tag_filter TEXT;
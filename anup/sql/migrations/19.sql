@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS merged_seasons (
+    series_id INTEGER NOT NULL,
+    season_id INTEGER NOT NULL,
+    episode_offset SMALLINT NOT NULL,
+    episodes SMALLINT NOT NULL,
+    PRIMARY KEY(series_id, season_id),
+    FOREIGN KEY(series_id) REFERENCES series_configs(id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+ALTER TABLE series_configs ADD COLUMN episode_offset SMALLINT NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE series_configs ADD COLUMN episode_length_mins SMALLINT;
\ No newline at end of file
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS watch_history (
+    id INTEGER NOT NULL PRIMARY KEY,
+    series_id INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    episode SMALLINT NOT NULL,
+    started_at BIGINT NOT NULL,
+    finished_at BIGINT NOT NULL,
+    exit_code INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS watch_history_finished_at ON watch_history(finished_at);
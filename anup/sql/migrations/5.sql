@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS episode_progress (
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    position_secs INTEGER NOT NULL,
+    watched_secs INTEGER NOT NULL,
+    PRIMARY KEY(series_id, episode),
+    FOREIGN KEY(series_id) REFERENCES series_configs(id) ON DELETE CASCADE
+);
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS cached_info (
+    id INTEGER NOT NULL PRIMARY KEY,
+    title_preferred TEXT NOT NULL,
+    title_romaji TEXT NOT NULL,
+    episodes INTEGER NOT NULL,
+    episode_length_mins INTEGER NOT NULL,
+    kind SMALLINT NOT NULL,
+    airing SMALLINT NOT NULL,
+    fetched_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS cached_sequels (
+    series_id INTEGER NOT NULL,
+    sequel_id INTEGER NOT NULL,
+    kind SMALLINT NOT NULL,
+    PRIMARY KEY(series_id, sequel_id),
+    FOREIGN KEY(series_id) REFERENCES cached_info(id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+ALTER TABLE series_configs ADD COLUMN absolute_numbering BOOL NOT NULL DEFAULT 0;
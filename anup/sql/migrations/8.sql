@@ -0,0 +1 @@
+ALTER TABLE series_configs ADD COLUMN feed_url TEXT;
\ No newline at end of file
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS cached_airing (
+    series_id INTEGER NOT NULL PRIMARY KEY,
+    latest_episode INTEGER NOT NULL,
+    aired_at BIGINT NOT NULL,
+    fetched_at BIGINT NOT NULL
+);
\ No newline at end of file
@@ -1,5 +1,3 @@
-PRAGMA user_version = 1;
-
 CREATE TABLE IF NOT EXISTS series_configs (
     id INTEGER NOT NULL PRIMARY KEY,
     nickname TEXT NOT NULL UNIQUE,
@@ -0,0 +1,354 @@
+use crate::config::Config;
+use crate::database::{self, Database};
+use crate::file::{SaveDir, SerializedFile};
+use anyhow::{anyhow, Context, Result};
+use chrono::Local;
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The name the database is stored under in a backup.
+const DATABASE_NAME: &str = "data.sqlite";
+
+/// The name backups made before a schema migration start with.
+const MIGRATION_PREFIX: &str = "pre-migration";
+
+/// How many backups made before schema migrations are kept before the oldest are removed.
+const MIGRATION_BACKUPS_KEPT: usize = 5;
+
+/// Returns the directory backups are saved to by default, creating it if it doesn't exist.
+pub fn backup_dir() -> Result<PathBuf> {
+    let mut dir = SaveDir::LocalData.validated_dir_path()?.to_path_buf();
+    dir.push("backups");
+
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("failed to create backup directory at {}", dir.display()))?;
+
+    Ok(dir)
+}
+
+/// Returns a file name for a new backup that starts with `prefix` and ends with the current time.
+pub fn timestamped_name(prefix: &str) -> String {
+    format!("{}-{}.tar.gz", prefix, Local::now().format("%Y%m%d-%H%M%S"))
+}
+
+/// Write a compressed snapshot of the database `conn` is connected to, along with the config, to `path`.
+pub fn create(conn: &SqliteConnection, path: &Path) -> Result<()> {
+    let snapshot = backup_dir()?.join(".snapshot.sqlite");
+
+    if snapshot.exists() {
+        fs::remove_file(&snapshot).context("failed to remove stale database snapshot")?;
+    }
+
+    // VACUUM INTO gives us a consistent copy of the database without having to close the connection
+    diesel::sql_query("VACUUM INTO ?")
+        .bind::<Text, _>(snapshot.to_string_lossy())
+        .execute(conn)
+        .context("failed to take a snapshot of the database")?;
+
+    let config_path = Config::validated_save_path()?;
+    let mut files = vec![(snapshot.as_path(), DATABASE_NAME)];
+
+    if config_path.exists() {
+        files.push((&config_path, config_name(&config_path)?));
+    }
+
+    let result = write_archive(path, &files);
+    fs::remove_file(&snapshot).ok();
+
+    if result.is_err() {
+        fs::remove_file(path).ok();
+    }
+
+    result.with_context(|| format!("failed to write backup to {}", path.display()))
+}
+
+/// Take a backup of the database before it is migrated from schema `version`.
+///
+/// Only the most recent [`MIGRATION_BACKUPS_KEPT`] backups made this way are kept.
+pub fn before_migration(conn: &SqliteConnection, version: usize) -> Result<()> {
+    let dir = backup_dir()?;
+    let prefix = format!("{}-v{:02}", MIGRATION_PREFIX, version);
+
+    create(conn, &dir.join(timestamped_name(&prefix)))?;
+    rotate(&dir, MIGRATION_PREFIX, MIGRATION_BACKUPS_KEPT)
+}
+
+/// Replace the database and config with the ones stored in the backup at `path`.
+///
+/// The backup is fully verified before anything is replaced, and the current database and config are backed up first.
+/// Returns the path of the backup made of the current data.
+pub fn restore(path: &Path) -> Result<Option<PathBuf>> {
+    let dir = backup_dir()?;
+    let staging = dir.join(".restore");
+
+    if staging.exists() {
+        fs::remove_dir_all(&staging).context("failed to remove stale restore directory")?;
+    }
+
+    fs::create_dir_all(&staging).context("failed to create restore directory")?;
+
+    let result = restore_from_staging(path, &staging, &dir);
+    fs::remove_dir_all(&staging).ok();
+    result
+}
+
+fn restore_from_staging(path: &Path, staging: &Path, dir: &Path) -> Result<Option<PathBuf>> {
+    let config_path = Config::validated_save_path()?;
+    let config_name = config_name(&config_path)?;
+
+    extract_archive(path, staging, &[DATABASE_NAME, config_name])
+        .with_context(|| format!("failed to read backup at {}", path.display()))?;
+
+    let staged_db = staging.join(DATABASE_NAME);
+
+    if !staged_db.exists() {
+        return Err(anyhow!("backup at {} has no database", path.display()));
+    }
+
+    verify_database(&staged_db)?;
+
+    let db_path = Database::validated_path()?;
+
+    let previous = if db_path.exists() {
+        let conn = SqliteConnection::establish(&db_path.to_string_lossy())
+            .context("failed to open the current database")?;
+
+        let previous = dir.join(timestamped_name("pre-restore"));
+
+        create(&conn, &previous).context("failed to back up the current database")?;
+        Some(previous)
+    } else {
+        None
+    };
+
+    // A leftover write-ahead log belongs to the old database and would corrupt the restored one
+    for suffix in &["-wal", "-shm"] {
+        let mut leftover = db_path.clone().into_os_string();
+        leftover.push(suffix);
+        let leftover = PathBuf::from(leftover);
+
+        if leftover.exists() {
+            fs::remove_file(&leftover)
+                .with_context(|| format!("failed to remove {}", leftover.display()))?;
+        }
+    }
+
+    fs::copy(&staged_db, &db_path).context("failed to replace the database")?;
+
+    let staged_config = staging.join(config_name);
+
+    if staged_config.exists() {
+        fs::copy(&staged_config, &config_path).context("failed to replace the config")?;
+    }
+
+    Ok(previous)
+}
+
+fn config_name(path: &Path) -> Result<&str> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("config path has no file name"))
+}
+
+/// Write a gzipped tarball to `path` containing each file under its given name.
+fn write_archive(path: &Path, files: &[(&Path, &str)]) -> Result<()> {
+    let file = File::create(path)?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    for (file, name) in files {
+        archive
+            .append_path_with_name(file, name)
+            .with_context(|| format!("failed to add {} to backup", name))?;
+    }
+
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Extract every file in the gzipped tarball at `path` whose name is in `allowed` to `dir`.
+///
+/// The whole archive is read so that its checksum is always verified.
+fn extract_archive(path: &Path, dir: &Path, allowed: &[&str]) -> Result<()> {
+    let file = File::open(path)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+
+        let name = match entry_path.to_str() {
+            Some(name) if allowed.contains(&name) => name,
+            _ => continue,
+        };
+
+        entry.unpack(dir.join(name))?;
+    }
+
+    // The gzip checksum is only checked once the decoder reaches the end of the stream
+    io::copy(&mut archive.into_inner(), &mut io::sink())
+        .context("backup is corrupt or incomplete")?;
+
+    Ok(())
+}
+
+/// Check that the database at `path` is intact and can be used by this version of the program.
+fn verify_database(path: &Path) -> Result<()> {
+    #[derive(QueryableByName)]
+    struct IntegrityCheck {
+        #[sql_type = "Text"]
+        integrity_check: String,
+    }
+
+    let conn = SqliteConnection::establish(&path.to_string_lossy())
+        .context("failed to open the backed up database")?;
+
+    let problems = diesel::sql_query("PRAGMA integrity_check")
+        .load::<IntegrityCheck>(&conn)
+        .context("failed to check the integrity of the backed up database")?
+        .into_iter()
+        .map(|check| check.integrity_check)
+        .filter(|check| check != "ok")
+        .collect::<Vec<_>>();
+
+    if !problems.is_empty() {
+        return Err(anyhow!(
+            "backed up database is corrupt:\n{}",
+            problems.join("\n")
+        ));
+    }
+
+    let version = database::schema_version(&conn)?;
+
+    if version > database::SCHEMA_VERSION {
+        return Err(anyhow!(
+            "backup was made by a newer version of the program (schema version {}, expected at most {})",
+            version,
+            database::SCHEMA_VERSION
+        ));
+    }
+
+    Ok(())
+}
+
+/// Remove the oldest backups in `dir` that start with `prefix` until only `keep` of them remain.
+fn rotate(dir: &Path, prefix: &str, keep: usize) -> Result<()> {
+    let mut backups = fs::read_dir(dir)
+        .context("failed to read backup directory")?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            matches!(path.file_name().and_then(|name| name.to_str()), Some(name) if name.starts_with(prefix))
+        })
+        .collect::<Vec<_>>();
+
+    if backups.len() <= keep {
+        return Ok(());
+    }
+
+    // Backup names end with their creation time, and older schema versions sort first
+    backups.sort_unstable();
+
+    for backup in &backups[..backups.len() - keep] {
+        fs::remove_file(backup)
+            .with_context(|| format!("failed to remove old backup {}", backup.display()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::connection::SimpleConnection;
+    use std::io::{Seek, SeekFrom, Write};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("anup-{}-{}", name, std::process::id()));
+
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn archive_roundtrip_detects_corruption() {
+        let dir = temp_dir("backup-roundtrip");
+        let db_path = dir.join("source.sqlite");
+
+        let conn = SqliteConnection::establish(&db_path.to_string_lossy()).unwrap();
+        conn.batch_execute(
+            "CREATE TABLE series (id INTEGER PRIMARY KEY); INSERT INTO series VALUES (1);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let archive = dir.join("backup.tar.gz");
+        write_archive(&archive, &[(&db_path, DATABASE_NAME)]).unwrap();
+
+        let extracted = dir.join("extracted");
+        fs::create_dir_all(&extracted).unwrap();
+        extract_archive(&archive, &extracted, &[DATABASE_NAME]).unwrap();
+        verify_database(&extracted.join(DATABASE_NAME)).unwrap();
+
+        let len = fs::metadata(&archive).unwrap().len();
+        let mut file = fs::OpenOptions::new().write(true).open(&archive).unwrap();
+        file.seek(SeekFrom::Start(len / 2)).unwrap();
+        file.write_all(&[0xFF; 8]).unwrap();
+        drop(file);
+
+        let corrupted = dir.join("corrupted");
+        fs::create_dir_all(&corrupted).unwrap();
+
+        let result = extract_archive(&archive, &corrupted, &[DATABASE_NAME])
+            .and_then(|()| verify_database(&corrupted.join(DATABASE_NAME)));
+
+        assert!(result.is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn rotate_keeps_newest_backups() {
+        let dir = temp_dir("backup-rotate");
+
+        let names = [
+            "pre-migration-v02-20220101-000000.tar.gz",
+            "pre-migration-v03-20220102-000000.tar.gz",
+            "pre-migration-v03-20220103-000000.tar.gz",
+            "pre-migration-v04-20220104-000000.tar.gz",
+            "backup-20220101-000000.tar.gz",
+        ];
+
+        for name in &names {
+            File::create(dir.join(name)).unwrap();
+        }
+
+        rotate(&dir, MIGRATION_PREFIX, 2).unwrap();
+
+        let mut remaining = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+
+        remaining.sort_unstable();
+
+        assert_eq!(
+            remaining,
+            vec![
+                "backup-20220101-000000.tar.gz",
+                "pre-migration-v03-20220103-000000.tar.gz",
+                "pre-migration-v04-20220104-000000.tar.gz",
+            ]
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::{anyhow, Context, Result};
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// Programs that set the clipboard to what's written to their standard input, in the order they're tried.
+const PROGRAMS: [(&str, &[&str]); 4] = [
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("pbcopy", &[]),
+];
+
+/// Set the contents of the clipboard to `text`.
+///
+/// The first clipboard program that's installed and works is used. When none do, the terminal is asked to set the
+/// clipboard instead, which most terminals support and also works over SSH.
+pub fn copy(text: &str) -> Result<()> {
+    for (program, args) in PROGRAMS {
+        if copy_with(program, args, text).is_ok() {
+            return Ok(());
+        }
+    }
+
+    copy_with_terminal(text).context("asking the terminal to set the clipboard")
+}
+
+fn copy_with(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let status = child.wait()?;
+
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", program, status));
+    }
+
+    Ok(())
+}
+
+/// Set the clipboard with the OSC 52 escape sequence.
+fn copy_with_terminal(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64::encode(text))?;
+    stdout.flush()
+}
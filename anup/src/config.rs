@@ -1,23 +1,47 @@
 use crate::{
-    file::{FileFormat, SaveDir, SerializedFile},
+    file::{self, FileFormat, SaveDir, SerializedFile},
     key::Key,
 };
-use crossterm::event::KeyCode;
+use anime::remote::{self, anilist, kitsu, mal};
+use anyhow::{anyhow, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use serde::ser::Serializer;
 use serde::{
     de::{self, Deserializer, Visitor},
     Deserialize, Serialize,
 };
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
 use std::ops::Mul;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::result;
+use tracing::level_filters::LevelFilter;
+use tui::style::Color;
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
     pub series_dir: PathBuf,
     pub reset_dates_on_rewatch: bool,
+    pub confirm_start_from_plan: bool,
+    pub match_title_acronyms: bool,
+    pub match_confidence: Percentage,
+    pub title_language: TitleLanguage,
     pub episode: EpisodeConfig,
+    pub network: NetworkConfig,
+    pub anilist: AniListConfig,
+    pub mal: MalConfig,
     pub tui: TuiConfig,
+    pub offline: OfflineConfig,
+    pub notifications: NotificationConfig,
+    pub goals: GoalConfig,
+    pub feeds: FeedConfig,
+    pub downloads: DownloadConfig,
+    pub media_server: MediaServerConfig,
+    pub hooks: HookConfig,
+    pub filenames: FilenameConfig,
+    pub log: LogConfig,
+    pub chapter_skip: ChapterSkipConfig,
 }
 
 impl Config {
@@ -30,6 +54,70 @@ impl Config {
             ..Self::default()
         }
     }
+
+    /// Create a new AniList client that uses the configured proxy and API URL, if any.
+    pub fn anilist_client(&self) -> Result<anilist::Client> {
+        let mut client = match &self.network.proxy {
+            Some(proxy) => {
+                anilist::Client::with_proxy(proxy).context("invalid network proxy in config")?
+            }
+            None => anilist::Client::new(),
+        };
+
+        if let Some(api_url) = &self.anilist.api_url {
+            client
+                .set_api_url(api_url)
+                .context("invalid AniList API URL in config")?;
+        }
+
+        Ok(client)
+    }
+
+    /// Create a new Kitsu client that uses the configured proxy, if any.
+    pub fn kitsu_client(&self) -> Result<kitsu::Client> {
+        match &self.network.proxy {
+            Some(proxy) => {
+                kitsu::Client::with_proxy(proxy).context("invalid network proxy in config")
+            }
+            None => Ok(kitsu::Client::new()),
+        }
+    }
+
+    /// Returns the configured MyAnimeList client ID, which is required to use MyAnimeList.
+    pub fn mal_client_id(&self) -> Result<&str> {
+        self.mal.client_id.as_deref().ok_or_else(|| {
+            anyhow!("the client_id field in the mal section of your config must be set to use MyAnimeList")
+        })
+    }
+
+    /// Returns the configured MyAnimeList redirect URL, which is required to add MyAnimeList users.
+    pub fn mal_redirect_uri(&self) -> Result<&str> {
+        self.mal.redirect_uri.as_deref().ok_or_else(|| {
+            anyhow!("the redirect_uri field in the mal section of your config must be set to the redirect URL of your MyAnimeList client")
+        })
+    }
+
+    /// Create a new MyAnimeList client that uses the configured client ID and proxy, if any.
+    pub fn mal_client(&self) -> Result<mal::Client> {
+        let client_id = self.mal_client_id()?;
+
+        match &self.network.proxy {
+            Some(proxy) => {
+                mal::Client::with_proxy(client_id, proxy).context("invalid network proxy in config")
+            }
+            None => Ok(mal::Client::new(client_id)),
+        }
+    }
+
+    /// Create a new HTTP client for requests that don't go to the remote service, such as feeds and covers, that uses the configured proxy, if any.
+    pub fn http_client(&self) -> Result<reqwest::Client> {
+        match &self.network.proxy {
+            Some(proxy) => remote::proxied_http_client(proxy)
+                .map(|(_, client)| client)
+                .context("invalid network proxy in config"),
+            None => Ok(reqwest::Client::new()),
+        }
+    }
 }
 
 impl Default for Config {
@@ -41,8 +129,25 @@ impl Default for Config {
         Self {
             series_dir,
             reset_dates_on_rewatch: false,
+            confirm_start_from_plan: false,
+            match_title_acronyms: false,
+            match_confidence: Percentage::new(85.0),
+            title_language: TitleLanguage::Preferred,
             episode: EpisodeConfig::default(),
+            network: NetworkConfig::default(),
+            anilist: AniListConfig::default(),
+            mal: MalConfig::default(),
             tui: TuiConfig::default(),
+            offline: OfflineConfig::default(),
+            notifications: NotificationConfig::default(),
+            goals: GoalConfig::default(),
+            feeds: FeedConfig::default(),
+            downloads: DownloadConfig::default(),
+            media_server: MediaServerConfig::default(),
+            hooks: HookConfig::default(),
+            filenames: FilenameConfig::default(),
+            log: LogConfig::default(),
+            chapter_skip: ChapterSkipConfig::default(),
         }
     }
 }
@@ -59,14 +164,53 @@ impl SerializedFile for Config {
     fn format() -> FileFormat {
         FileFormat::Config
     }
+
+    /// Profiles without a config file of their own use the one from the default profile.
+    fn validated_save_path() -> Result<PathBuf> {
+        let path_in = |dir: &Path| {
+            dir.join(Self::filename())
+                .with_extension(Self::format().extension())
+        };
+
+        if file::profile().is_none() {
+            return Ok(path_in(Self::save_dir().validated_dir_path()?));
+        }
+
+        let profile_path = path_in(Self::save_dir().dir_path());
+
+        if profile_path.exists() {
+            return Ok(profile_path);
+        }
+
+        Ok(path_in(Self::save_dir().validated_shared_dir_path()?))
+    }
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct EpisodeConfig {
     #[serde(rename = "percent_watched_to_progress")]
     pub pcnt_must_watch: Percentage,
     pub player: String,
     pub player_args: Vec<String>,
+    pub player_inherit_stdio: bool,
+    /// Players to offer trying in order when the player exits with an error right after it starts.
+    pub fallback_players: Vec<String>,
+    pub count_on_crash_if_watched: bool,
+    pub verify_files: bool,
+    pub recursive_scan: bool,
+    pub watch_folders: bool,
+    pub scan_on_startup: bool,
+    pub track_mpv_position: bool,
+    /// How many minutes mpv can be left paused before it exits for the episode to still count, where 0 means no limit.
+    pub max_idle_mins: u32,
+    pub resume_playback: bool,
+    pub local_rewatch: bool,
+    pub rename_template: String,
+    /// The program and arguments to stream episodes with, with the URL of the episode appended.
+    ///
+    /// When empty, the URL is opened in the default browser.
+    pub stream_command: Vec<String>,
 }
 
 impl Default for EpisodeConfig {
@@ -75,6 +219,295 @@ impl Default for EpisodeConfig {
             pcnt_must_watch: Percentage::new(50.0),
             player: String::from("mpv"),
             player_args: Vec::new(),
+            player_inherit_stdio: false,
+            fallback_players: Vec::new(),
+            count_on_crash_if_watched: false,
+            verify_files: false,
+            recursive_scan: false,
+            watch_folders: true,
+            scan_on_startup: true,
+            track_mpv_position: true,
+            max_idle_mins: 15,
+            resume_playback: true,
+            local_rewatch: false,
+            rename_template: String::from("{title} - {episode:02}{ext}"),
+            stream_command: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub proxy: Option<String>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AniListConfig {
+    pub api_url: Option<String>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MalConfig {
+    pub client_id: Option<String>,
+    /// The redirect URL registered for the client, which MyAnimeList sends users to after they authorize it.
+    pub redirect_uri: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OfflineConfig {
+    pub cache_max_age_days: u32,
+}
+
+impl Default for OfflineConfig {
+    fn default() -> Self {
+        Self {
+            cache_max_age_days: 7,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Whether newly aired episodes of watching and planned series should be checked for.
+    pub airing: bool,
+    /// Whether newly aired episodes should also be shown as a desktop notification.
+    pub desktop: bool,
+    pub check_interval_mins: u32,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            airing: true,
+            desktop: false,
+            check_interval_mins: 30,
+        }
+    }
+}
+
+/// How much the user wants to watch each week, which runs from Monday to Sunday.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GoalConfig {
+    pub weekly_episodes: Option<u32>,
+    pub weekly_minutes: Option<u32>,
+    /// Whether a summary of the previous week should be logged once a new week starts.
+    pub weekly_summary: bool,
+}
+
+impl Default for GoalConfig {
+    fn default() -> Self {
+        Self {
+            weekly_episodes: None,
+            weekly_minutes: None,
+            weekly_summary: true,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FeedConfig {
+    pub check_interval_mins: u32,
+    /// The program and arguments to run with the link of each new release appended, such as a torrent client.
+    pub command: Vec<String>,
+}
+
+impl Default for FeedConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_mins: 60,
+            command: Vec::new(),
+        }
+    }
+}
+
+/// Commands to run when episodes are played and completed.
+///
+/// Each command is a program followed by its arguments, and is told about the episode through environment variables.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HookConfig {
+    pub before_play: Vec<String>,
+    pub after_play: Vec<String>,
+    pub on_episode_completed: Vec<String>,
+    pub on_series_completed: Vec<String>,
+}
+
+/// How characters that can't be used in filenames on every platform are replaced in the names of generated files and folders.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FilenameConfig {
+    /// What every invalid character without its own replacement is replaced with.
+    pub replacement: String,
+    /// Replacements for specific characters, keyed by the character to replace.
+    pub replacements: BTreeMap<String, String>,
+}
+
+impl Default for FilenameConfig {
+    fn default() -> Self {
+        let replacements = [(":", " -"), ("/", " "), ("\\", " ")]
+            .iter()
+            .map(|&(from, to)| (from.into(), to.into()))
+            .collect();
+
+        Self {
+            replacement: String::new(),
+            replacements,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// The most detailed level of messages to write to the log file.
+    pub level: LogLevel,
+    /// How many days of log files to keep before the oldest one is removed.
+    pub max_files: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::Info,
+            max_files: 7,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ChapterSkipConfig {
+    /// The mpv key that skips past the opening or ending being played, or an empty string to not bind one.
+    pub key: String,
+    /// Whether openings and endings should be skipped as soon as they start playing.
+    pub auto_skip: bool,
+    /// The words that mark a chapter as an opening or ending when found in its title.
+    pub chapters: Vec<String>,
+}
+
+impl Default for ChapterSkipConfig {
+    fn default() -> Self {
+        let chapters = ["OP", "Opening", "ED", "Ending", "Intro", "Outro", "Credits"]
+            .iter()
+            .map(|&name| name.into())
+            .collect();
+
+        Self {
+            key: String::from("TAB"),
+            auto_skip: false,
+            chapters,
+        }
+    }
+}
+
+/// Which title of a series to show.
+///
+/// Series without a title in the chosen language show their preferred title instead.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TitleLanguage {
+    /// The title in the format chosen on the remote service.
+    Preferred,
+    Romaji,
+    English,
+    Native,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Off => Self::OFF,
+            LogLevel::Error => Self::ERROR,
+            LogLevel::Warn => Self::WARN,
+            LogLevel::Info => Self::INFO,
+            LogLevel::Debug => Self::DEBUG,
+            LogLevel::Trace => Self::TRACE,
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DownloadConfig {
+    /// The torrent client that new releases found in series feeds should be sent to.
+    pub client: Option<DownloadClient>,
+    /// The address of the client's web API. The client's default local address is used when this isn't set.
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum DownloadClient {
+    QBittorrent,
+    Transmission,
+}
+
+impl DownloadClient {
+    pub fn default_url(self) -> &'static str {
+        match self {
+            Self::QBittorrent => "http://127.0.0.1:8080",
+            Self::Transmission => "http://127.0.0.1:9091/transmission/rpc",
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MediaServerConfig {
+    /// The media server that played episodes should be synced from.
+    pub server: Option<MediaServer>,
+    /// The address of the server. The server's default local address is used when this isn't set.
+    pub url: Option<String>,
+    /// The API key for Jellyfin, or the `X-Plex-Token` for Plex.
+    pub token: Option<String>,
+    /// The ID of the Jellyfin user whose played episodes should be synced. Plex doesn't need this.
+    pub user_id: Option<String>,
+    /// Where the server sees `series_dir`, if it has it mounted at a different path.
+    pub series_dir: Option<PathBuf>,
+    pub check_interval_mins: u32,
+}
+
+impl Default for MediaServerConfig {
+    fn default() -> Self {
+        Self {
+            server: None,
+            url: None,
+            token: None,
+            user_id: None,
+            series_dir: None,
+            check_interval_mins: 5,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum MediaServer {
+    Jellyfin,
+    Plex,
+}
+
+impl MediaServer {
+    pub fn default_url(self) -> &'static str {
+        match self {
+            Self::Jellyfin => "http://127.0.0.1:8096",
+            Self::Plex => "http://127.0.0.1:32400",
         }
     }
 }
@@ -156,20 +589,273 @@ impl Mul<Percentage> for f32 {
     }
 }
 
-#[derive(Default, Deserialize, Serialize)]
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct TuiConfig {
     pub keys: TuiKeys,
+    pub theme: ThemeConfig,
+    pub cover_art: CoverArt,
+}
+
+/// How the cover art of the selected series is drawn in the info panel.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum CoverArt {
+    /// Use the image protocol the terminal is detected to support, if any.
+    Auto,
+    Off,
+    Kitty,
+    ITerm,
+    Sixel,
+}
+
+impl Default for CoverArt {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+/// The colors to draw the TUI with.
+///
+/// Each color is taken from `preset`, unless it's overridden by its own field.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    pub selection: Option<ThemeColor>,
+    pub focus: Option<ThemeColor>,
+    pub border: Option<ThemeColor>,
+    pub inactive: Option<ThemeColor>,
+    pub error: Option<ThemeColor>,
+    pub warning: Option<ThemeColor>,
+    pub info: Option<ThemeColor>,
+    pub watching: Option<ThemeColor>,
+    pub completed: Option<ThemeColor>,
+    pub on_hold: Option<ThemeColor>,
+    pub dropped: Option<ThemeColor>,
+    pub plan_to_watch: Option<ThemeColor>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum ThemePreset {
+    Default,
+    Solarized,
+    HighContrast,
+}
+
+impl Default for ThemePreset {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+/// A terminal color, written as either its name (such as `lightblue`), an index from 0-255, or a `#rrggbb` hex code.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ThemeColor(pub Color);
+
+impl ThemeColor {
+    const NAMED: [(&'static str, Color); 17] = [
+        ("reset", Color::Reset),
+        ("black", Color::Black),
+        ("red", Color::Red),
+        ("green", Color::Green),
+        ("yellow", Color::Yellow),
+        ("blue", Color::Blue),
+        ("magenta", Color::Magenta),
+        ("cyan", Color::Cyan),
+        ("gray", Color::Gray),
+        ("darkgray", Color::DarkGray),
+        ("lightred", Color::LightRed),
+        ("lightgreen", Color::LightGreen),
+        ("lightyellow", Color::LightYellow),
+        ("lightblue", Color::LightBlue),
+        ("lightmagenta", Color::LightMagenta),
+        ("lightcyan", Color::LightCyan),
+        ("white", Color::White),
+    ];
+}
+
+impl TryFrom<&str> for ThemeColor {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let value = value.trim().to_ascii_lowercase();
+
+        if let Some(hex) = value.strip_prefix('#') {
+            let rgb = u32::from_str_radix(hex, 16)
+                .ok()
+                .filter(|_| hex.len() == 6)
+                .ok_or_else(|| anyhow!("invalid hex color: #{}", hex))?;
+
+            let [_, r, g, b] = rgb.to_be_bytes();
+            return Ok(Self(Color::Rgb(r, g, b)));
+        }
+
+        if let Ok(index) = value.parse() {
+            return Ok(Self(Color::Indexed(index)));
+        }
+
+        Self::NAMED
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|&(_, color)| Self(color))
+            .ok_or_else(|| anyhow!("unknown color: {}", value))
+    }
+}
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use std::fmt;
+
+        struct ColorVisitor;
+
+        impl<'de> Visitor<'de> for ColorVisitor {
+            type Value = ThemeColor;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a color name, index, or hex code")
+            }
+
+            fn visit_str<E>(self, value: &str) -> result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                value.try_into().map_err(E::custom)
+            }
+        }
+
+        de.deserialize_str(ColorVisitor)
+    }
+}
+
+impl Serialize for ThemeColor {
+    fn serialize<S>(&self, ser: S) -> result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            Color::Rgb(r, g, b) => ser.serialize_str(&format!("#{:02x}{:02x}{:02x}", r, g, b)),
+            Color::Indexed(index) => ser.serialize_str(&index.to_string()),
+            color => {
+                let name = Self::NAMED
+                    .iter()
+                    .find(|(_, named)| *named == color)
+                    .map_or("reset", |(name, _)| name);
+
+                ser.serialize_str(name)
+            }
+        }
+    }
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Copy, Deserialize, Serialize)]
+#[serde(default)]
 pub struct TuiKeys {
     pub play_next_episode: Key,
+    pub add_series: Key,
+    pub browse_series: Key,
+    pub update_series: Key,
+    pub delete_series: Key,
+    pub split_series: Key,
+    pub relations_panel: Key,
+    pub seasonal_panel: Key,
+    pub recommendations_panel: Key,
+    pub episodes_panel: Key,
+    pub user_panel: Key,
+    pub history_panel: Key,
+    pub log_panel: Key,
+    pub relink_series: Key,
+    pub stats_panel: Key,
+    pub downloads_panel: Key,
+    pub search: Key,
+    pub mark_series: Key,
+    pub undo: Key,
+    pub command_prompt: Key,
+    pub list_up: Key,
+    pub list_down: Key,
+    pub quit: Key,
+}
+
+impl TuiKeys {
+    /// Returns every binding along with the name of its field.
+    fn bindings(&self) -> [(&'static str, Key); 23] {
+        [
+            ("play_next_episode", self.play_next_episode),
+            ("add_series", self.add_series),
+            ("browse_series", self.browse_series),
+            ("update_series", self.update_series),
+            ("delete_series", self.delete_series),
+            ("split_series", self.split_series),
+            ("relations_panel", self.relations_panel),
+            ("seasonal_panel", self.seasonal_panel),
+            ("recommendations_panel", self.recommendations_panel),
+            ("episodes_panel", self.episodes_panel),
+            ("user_panel", self.user_panel),
+            ("history_panel", self.history_panel),
+            ("log_panel", self.log_panel),
+            ("relink_series", self.relink_series),
+            ("stats_panel", self.stats_panel),
+            ("downloads_panel", self.downloads_panel),
+            ("search", self.search),
+            ("mark_series", self.mark_series),
+            ("undo", self.undo),
+            ("command_prompt", self.command_prompt),
+            ("list_up", self.list_up),
+            ("list_down", self.list_down),
+            ("quit", self.quit),
+        ]
+    }
+
+    /// Returns a message describing each key that is bound to more than one action.
+    pub fn conflicts(&self) -> Vec<String> {
+        let bindings = self.bindings();
+        let mut conflicts = Vec::new();
+
+        for (i, (name, key)) in bindings.iter().enumerate() {
+            for (other_name, other_key) in &bindings[i + 1..] {
+                if key == other_key {
+                    conflicts.push(format!(
+                        "the {} and {} keybindings both use {}",
+                        name, other_name, key
+                    ));
+                }
+            }
+        }
+
+        conflicts
+    }
 }
 
 impl Default for TuiKeys {
     fn default() -> TuiKeys {
+        let shift = |ch| Key::new(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::SHIFT));
+
         TuiKeys {
             play_next_episode: Key::from_code(KeyCode::Enter),
+            add_series: Key::from_code(KeyCode::Char('a')),
+            browse_series: Key::from_code(KeyCode::Char('b')),
+            update_series: Key::from_code(KeyCode::Char('e')),
+            delete_series: shift('d'),
+            split_series: Key::from_code(KeyCode::Char('s')),
+            relations_panel: Key::from_code(KeyCode::Char('r')),
+            seasonal_panel: shift('b'),
+            recommendations_panel: shift('r'),
+            episodes_panel: Key::from_code(KeyCode::Char('l')),
+            user_panel: Key::from_code(KeyCode::Char('u')),
+            history_panel: shift('h'),
+            log_panel: shift('l'),
+            relink_series: Key::from_code(KeyCode::Char('y')),
+            stats_panel: shift('s'),
+            downloads_panel: Key::from_code(KeyCode::Char('t')),
+            search: Key::from_code(KeyCode::Char('/')),
+            mark_series: Key::from_code(KeyCode::Char(' ')),
+            undo: Key::from_code(KeyCode::Char('z')),
+            command_prompt: Key::from_code(KeyCode::Char(':')),
+            list_up: Key::from_code(KeyCode::Up),
+            list_down: Key::from_code(KeyCode::Down),
+            quit: Key::from_code(KeyCode::Char('q')),
         }
     }
 }
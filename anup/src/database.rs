@@ -1,14 +1,17 @@
+use crate::backup;
 use crate::file::SaveDir;
 use anyhow::{Context, Result};
 use diesel::connection::SimpleConnection;
 use diesel::deserialize::{self, FromSql};
 use diesel::prelude::*;
 use diesel::serialize::{self, Output, ToSql};
-use diesel::sql_types::{Nullable, Text};
+use diesel::sql_types::{Integer, Nullable, Text};
 use smallvec::SmallVec;
+use std::fmt;
 use std::io::Write;
 use std::ops::Deref;
 use std::path::PathBuf;
+use std::result;
 
 pub mod schema {
     table! {
@@ -18,6 +21,16 @@ pub mod schema {
             path -> Text,
             episode_parser -> Nullable<Text>,
             player_args -> Nullable<Text>,
+            episode_offset -> SmallInt,
+            episode_category -> Nullable<SmallInt>,
+            absolute_numbering -> Bool,
+            feed_url -> Nullable<Text>,
+            local_rewatch -> Nullable<SmallInt>,
+            sub_lang -> Nullable<Text>,
+            audio_lang -> Nullable<Text>,
+            sub_file_pattern -> Nullable<Text>,
+            stream_url_template -> Nullable<Text>,
+            episode_length_mins -> Nullable<SmallInt>,
         }
     }
 
@@ -28,6 +41,8 @@ pub mod schema {
             title_romaji -> Text,
             episodes -> SmallInt,
             episode_length_mins -> SmallInt,
+            title_english -> Nullable<Text>,
+            title_native -> Nullable<Text>,
         }
     }
 
@@ -43,6 +58,185 @@ pub mod schema {
             needs_sync -> Bool,
         }
     }
+
+    table! {
+        series_aliases (alias) {
+            alias -> Text,
+            series_id -> Integer,
+        }
+    }
+
+    table! {
+        series_synonyms (series_id, synonym) {
+            series_id -> Integer,
+            synonym -> Text,
+        }
+    }
+
+    table! {
+        series_tags (series_id, tag) {
+            series_id -> Integer,
+            tag -> Text,
+        }
+    }
+
+    table! {
+        merged_seasons (series_id, season_id) {
+            series_id -> Integer,
+            season_id -> Integer,
+            episode_offset -> SmallInt,
+            episodes -> SmallInt,
+        }
+    }
+
+    table! {
+        series_list_view {
+            id -> Integer,
+            sort -> Text,
+            status_filter -> Nullable<SmallInt>,
+            tag_filter -> Nullable<Text>,
+        }
+    }
+
+    table! {
+        entry_changes {
+            id -> Integer,
+            series_id -> Integer,
+            field -> Text,
+            old_value -> Nullable<Text>,
+            new_value -> Nullable<Text>,
+            changed_at -> BigInt,
+        }
+    }
+
+    table! {
+        watch_history {
+            id -> Integer,
+            series_id -> Integer,
+            title -> Text,
+            episode -> SmallInt,
+            started_at -> BigInt,
+            finished_at -> BigInt,
+            exit_code -> Nullable<Integer>,
+        }
+    }
+
+    table! {
+        episode_progress (series_id, episode) {
+            series_id -> Integer,
+            episode -> SmallInt,
+            position_secs -> Integer,
+            watched_secs -> Integer,
+        }
+    }
+
+    table! {
+        cached_info {
+            id -> Integer,
+            title_preferred -> Text,
+            title_romaji -> Text,
+            episodes -> Integer,
+            episode_length_mins -> Integer,
+            kind -> SmallInt,
+            airing -> SmallInt,
+            fetched_at -> BigInt,
+            title_english -> Nullable<Text>,
+            title_native -> Nullable<Text>,
+            title_synonyms -> Text,
+        }
+    }
+
+    table! {
+        cached_sequels (series_id, sequel_id) {
+            series_id -> Integer,
+            sequel_id -> Integer,
+            kind -> SmallInt,
+        }
+    }
+
+    table! {
+        cached_airing (series_id) {
+            series_id -> Integer,
+            latest_episode -> Integer,
+            aired_at -> BigInt,
+            fetched_at -> BigInt,
+        }
+    }
+}
+
+/// Schema migrations to apply to the database, in order.
+///
+/// The migration at index `n` upgrades the database from version `n + 1` to version `n + 2`.
+const MIGRATIONS: [&str; 19] = [
+    include_str!("../sql/migrations/2.sql"),
+    include_str!("../sql/migrations/3.sql"),
+    include_str!("../sql/migrations/4.sql"),
+    include_str!("../sql/migrations/5.sql"),
+    include_str!("../sql/migrations/6.sql"),
+    include_str!("../sql/migrations/7.sql"),
+    include_str!("../sql/migrations/8.sql"),
+    include_str!("../sql/migrations/9.sql"),
+    include_str!("../sql/migrations/10.sql"),
+    include_str!("../sql/migrations/11.sql"),
+    include_str!("../sql/migrations/12.sql"),
+    include_str!("../sql/migrations/13.sql"),
+    include_str!("../sql/migrations/14.sql"),
+    include_str!("../sql/migrations/15.sql"),
+    include_str!("../sql/migrations/16.sql"),
+    include_str!("../sql/migrations/17.sql"),
+    include_str!("../sql/migrations/18.sql"),
+    include_str!("../sql/migrations/19.sql"),
+    include_str!("../sql/migrations/20.sql"),
+];
+
+/// The schema version of a fully migrated database.
+pub const SCHEMA_VERSION: usize = MIGRATIONS.len() + 1;
+
+/// Returns the schema version of the database `conn` is connected to.
+pub fn schema_version(conn: &SqliteConnection) -> Result<usize> {
+    #[derive(QueryableByName)]
+    struct UserVersion {
+        #[sql_type = "Integer"]
+        user_version: i32,
+    }
+
+    // Databases created before migrations were introduced are considered to be at version 1
+    let version = diesel::sql_query("PRAGMA user_version")
+        .get_result::<UserVersion>(conn)
+        .context("getting schema version")?
+        .user_version
+        .max(1) as usize;
+
+    Ok(version)
+}
+
+/// How many more times a transaction is attempted when another process still has the database locked after the busy timeout.
+const MAX_BUSY_RETRIES: u32 = 3;
+
+/// Errors that can tell if they were caused by another connection holding a lock on the database.
+pub trait BusyError {
+    fn is_busy(&self) -> bool;
+}
+
+impl BusyError for diesel::result::Error {
+    fn is_busy(&self) -> bool {
+        match self {
+            diesel::result::Error::DatabaseError(_, info) => is_busy_message(info.message()),
+            _ => false,
+        }
+    }
+}
+
+impl BusyError for anyhow::Error {
+    fn is_busy(&self) -> bool {
+        self.downcast_ref::<diesel::result::Error>()
+            .map_or(false, BusyError::is_busy)
+    }
+}
+
+/// Returns true if `message` is the error SQLite reports when the database is locked by another connection.
+fn is_busy_message(message: &str) -> bool {
+    message.contains("database is locked") || message.contains("database table is locked")
 }
 
 pub struct Database(SqliteConnection);
@@ -50,17 +244,54 @@ pub struct Database(SqliteConnection);
 impl Database {
     pub fn open() -> Result<Self> {
         let path = Self::validated_path().context("getting path")?;
+        let existed = path.exists();
         let conn = SqliteConnection::establish(&path.to_string_lossy())?;
 
+        Self::init(conn, existed)
+    }
+
+    /// Open a new database that only exists in memory, for tests that need to read and write rows.
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        Self::init(conn, false)
+    }
+
+    fn init(conn: SqliteConnection, existed: bool) -> Result<Self> {
         conn.batch_execute(include_str!("../sql/pragmas.sql"))
             .context("executing pragmas")?;
 
         conn.batch_execute(include_str!("../sql/schema.sql"))
             .context("executing schema")?;
 
+        Self::migrate(&conn, existed).context("migrating schema")?;
+
         Ok(Self(conn))
     }
 
+    fn migrate(conn: &SqliteConnection, existed: bool) -> Result<()> {
+        let current = schema_version(conn)?;
+
+        // There's nothing worth saving in a database we just created
+        if existed && current < SCHEMA_VERSION {
+            backup::before_migration(conn, current)
+                .context("backing up database before migrating")?;
+        }
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(current - 1) {
+            let version = index + 2;
+            tracing::info!(version, "migrating database");
+
+            conn.transaction::<_, diesel::result::Error, _>(|| {
+                conn.batch_execute(migration)?;
+                conn.batch_execute(&format!("PRAGMA user_version = {};", version))
+            })
+            .with_context(|| format!("applying migration to version {}", version))?;
+        }
+
+        Ok(())
+    }
+
     pub fn validated_path() -> Result<PathBuf> {
         let mut path = SaveDir::LocalData.validated_dir_path()?.to_path_buf();
         path.push("data.sqlite");
@@ -71,6 +302,38 @@ impl Database {
     pub fn conn(&self) -> &SqliteConnection {
         &self.0
     }
+
+    /// Run `func` in a transaction that's rolled back if it fails.
+    ///
+    /// The transaction locks the database for writing as soon as it starts, so it waits for other processes
+    /// using the database instead of failing partway through. If the database is still locked after the busy timeout,
+    /// the whole transaction is attempted again up to [`MAX_BUSY_RETRIES`] times.
+    ///
+    /// `name` describes what the transaction does, and is used to tell transactions apart in the log.
+    pub fn transaction<T, E, F>(&self, name: &str, mut func: F) -> result::Result<T, E>
+    where
+        F: FnMut() -> result::Result<T, E>,
+        E: From<diesel::result::Error> + BusyError + fmt::Display,
+    {
+        let _span = tracing::debug_span!("transaction", name).entered();
+        let mut retries = 0;
+
+        loop {
+            let result = self.0.immediate_transaction(&mut func);
+
+            match &result {
+                Err(err) if err.is_busy() && retries < MAX_BUSY_RETRIES => {
+                    retries += 1;
+                    tracing::warn!(retries, "database is locked, retrying transaction");
+                }
+                Err(err) => {
+                    tracing::warn!(%err, "transaction rolled back");
+                    return result;
+                }
+                Ok(_) => return result,
+            }
+        }
+    }
 }
 
 impl Drop for Database {
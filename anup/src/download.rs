@@ -0,0 +1,379 @@
+use crate::config::{DownloadClient, DownloadConfig};
+use anyhow::{anyhow, Context, Result};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, COOKIE, SET_COOKIE};
+use reqwest::{Response, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::form_urlencoded;
+
+/// How long to wait for the torrent client to respond before giving up on a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The tag given to torrents added to qBittorrent so only ours are tracked.
+const QBITTORRENT_TAG: &str = "anup";
+
+/// A torrent in the download client's queue.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Torrent {
+    pub name: String,
+    /// How much of the torrent has been downloaded, from 0.0 to 1.0.
+    pub progress: f32,
+    /// The directory the torrent is being downloaded to.
+    pub dir: PathBuf,
+}
+
+impl Torrent {
+    #[inline(always)]
+    pub fn is_complete(&self) -> bool {
+        self.progress >= 1.0
+    }
+}
+
+/// A torrent being downloaded to the folder of a series.
+#[derive(Debug, PartialEq)]
+pub struct SeriesDownload {
+    pub nickname: String,
+    pub torrent: Torrent,
+}
+
+/// A connection to the web API of the torrent client set in the config.
+pub struct Downloader {
+    http: reqwest::Client,
+    client: DownloadClient,
+    url: String,
+    username: Option<String>,
+    password: Option<String>,
+    /// The qBittorrent login cookie or Transmission session ID, once one has been obtained.
+    session: Option<String>,
+}
+
+impl Downloader {
+    /// Returns a new downloader for the client in `config` that connects to it with `http`, or None if no client is set.
+    pub fn from_config(config: &DownloadConfig, http: &reqwest::Client) -> Option<Self> {
+        let client = config.client?;
+
+        let url = config
+            .url
+            .as_deref()
+            .unwrap_or_else(|| client.default_url())
+            .trim_end_matches('/')
+            .to_string();
+
+        Some(Self {
+            http: http.clone(),
+            client,
+            url,
+            username: config.username.clone(),
+            password: config.password.clone(),
+            session: None,
+        })
+    }
+
+    /// Start downloading the torrent or magnet `link` to `dir`.
+    pub async fn add(&mut self, link: &str, dir: &Path) -> Result<()> {
+        let dir = dir.to_string_lossy();
+
+        match self.client {
+            DownloadClient::QBittorrent => {
+                let form = form_urlencoded::Serializer::new(String::new())
+                    .append_pair("urls", link)
+                    .append_pair("savepath", &dir)
+                    .append_pair("tags", QBITTORRENT_TAG)
+                    .finish();
+
+                self.qbittorrent_request("torrents/add", &form).await?;
+            }
+            DownloadClient::Transmission => {
+                let arguments = json!({
+                    "filename": link,
+                    "download-dir": dir,
+                });
+
+                self.transmission_request("torrent-add", &arguments).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns every torrent we've added that the client still has.
+    ///
+    /// Transmission has no way to tag torrents, so every torrent it has is returned.
+    pub async fn torrents(&mut self) -> Result<Vec<Torrent>> {
+        match self.client {
+            DownloadClient::QBittorrent => {
+                let form = form_urlencoded::Serializer::new(String::new())
+                    .append_pair("tag", QBITTORRENT_TAG)
+                    .finish();
+
+                let torrents: Vec<QBittorrentTorrent> = self
+                    .qbittorrent_request("torrents/info", &form)
+                    .await?
+                    .json()
+                    .await
+                    .context("failed to parse torrents from qBittorrent")?;
+
+                Ok(torrents.into_iter().map(Into::into).collect())
+            }
+            DownloadClient::Transmission => {
+                let arguments = json!({
+                    "fields": ["name", "percentDone", "downloadDir"],
+                });
+
+                let response = self.transmission_request("torrent-get", &arguments).await?;
+
+                let torrents: TransmissionTorrents = serde_json::from_value(response)
+                    .context("failed to parse torrents from Transmission")?;
+
+                Ok(torrents.torrents.into_iter().map(Into::into).collect())
+            }
+        }
+    }
+
+    async fn qbittorrent_request(&mut self, endpoint: &str, form: &str) -> Result<Response> {
+        // qBittorrent responds with 403 Forbidden when we aren't logged in or our cookie has expired
+        for _ in 0..2 {
+            if self.session.is_none() && self.username.is_some() {
+                self.qbittorrent_login().await?;
+            }
+
+            let mut request = self
+                .http
+                .post(format!("{}/api/v2/{}", self.url, endpoint))
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .timeout(REQUEST_TIMEOUT);
+
+            if let Some(session) = &self.session {
+                request = request.header(COOKIE, session.as_str());
+            }
+
+            let response = request
+                .body(form.to_string())
+                .send()
+                .await
+                .context("failed to connect to qBittorrent")?;
+
+            if response.status() == StatusCode::FORBIDDEN && self.username.is_some() {
+                self.session = None;
+                continue;
+            }
+
+            return response
+                .error_for_status()
+                .with_context(|| anyhow!("qBittorrent request to {} failed", endpoint));
+        }
+
+        Err(anyhow!("failed to log in to qBittorrent"))
+    }
+
+    async fn qbittorrent_login(&mut self) -> Result<()> {
+        let form = form_urlencoded::Serializer::new(String::new())
+            .append_pair("username", self.username.as_deref().unwrap_or_default())
+            .append_pair("password", self.password.as_deref().unwrap_or_default())
+            .finish();
+
+        let response = self
+            .http
+            .post(format!("{}/api/v2/auth/login", self.url))
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .timeout(REQUEST_TIMEOUT)
+            .body(form)
+            .send()
+            .await
+            .and_then(Response::error_for_status)
+            .context("failed to connect to qBittorrent")?;
+
+        let cookie = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(qbittorrent_session_cookie)
+            .ok_or_else(|| anyhow!("qBittorrent rejected the configured username / password"))?;
+
+        self.session = Some(cookie.to_string());
+        Ok(())
+    }
+
+    async fn transmission_request(&mut self, method: &str, arguments: &Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct RpcResponse {
+            result: String,
+            #[serde(default)]
+            arguments: Value,
+        }
+
+        const SESSION_HEADER: &str = "X-Transmission-Session-Id";
+
+        let body = json!({
+            "method": method,
+            "arguments": arguments,
+        });
+
+        // Transmission responds with 409 Conflict and a new session ID when ours is missing or has expired
+        for _ in 0..2 {
+            let mut request = self.http.post(&self.url).timeout(REQUEST_TIMEOUT);
+
+            if let Some(session) = &self.session {
+                request = request.header(SESSION_HEADER, session.as_str());
+            }
+
+            if let Some(username) = &self.username {
+                let credentials = format!(
+                    "{}:{}",
+                    username,
+                    self.password.as_deref().unwrap_or_default()
+                );
+
+                request = request.header(
+                    AUTHORIZATION,
+                    format!("Basic {}", base64::encode(credentials)),
+                );
+            }
+
+            let response = request
+                .json(&body)
+                .send()
+                .await
+                .context("failed to connect to Transmission")?;
+
+            if response.status() == StatusCode::CONFLICT {
+                self.session = response
+                    .headers()
+                    .get(SESSION_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+
+                continue;
+            }
+
+            let response: RpcResponse =
+                async { response.error_for_status()?.json().await }
+                    .await
+                    .with_context(|| anyhow!("Transmission request {} failed", method))?;
+
+            if response.result != "success" {
+                return Err(anyhow!(
+                    "Transmission request {} failed: {}",
+                    method,
+                    response.result
+                ));
+            }
+
+            return Ok(response.arguments);
+        }
+
+        Err(anyhow!("failed to get a session ID from Transmission"))
+    }
+}
+
+/// Returns the `SID=...` pair from a `Set-Cookie` header sent by qBittorrent, if it has one.
+fn qbittorrent_session_cookie(header: &str) -> Option<&str> {
+    header
+        .split(';')
+        .map(str::trim)
+        .find(|pair| pair.starts_with("SID="))
+}
+
+#[derive(Deserialize)]
+struct QBittorrentTorrent {
+    name: String,
+    progress: f32,
+    save_path: PathBuf,
+}
+
+impl From<QBittorrentTorrent> for Torrent {
+    fn from(torrent: QBittorrentTorrent) -> Self {
+        Self {
+            name: torrent.name,
+            progress: torrent.progress,
+            dir: torrent.save_path,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TransmissionTorrents {
+    torrents: Vec<TransmissionTorrent>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransmissionTorrent {
+    name: String,
+    percent_done: f32,
+    download_dir: PathBuf,
+}
+
+impl From<TransmissionTorrent> for Torrent {
+    fn from(torrent: TransmissionTorrent) -> Self {
+        Self {
+            name: torrent.name,
+            progress: torrent.percent_done,
+            dir: torrent.download_dir,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_client_torrents() {
+        let qbittorrent = r#"[{
+            "name": "[Group] Series Title - 05 [1080p].mkv",
+            "progress": 0.5,
+            "save_path": "/home/user/anime/series",
+            "state": "downloading"
+        }]"#;
+
+        let transmission = r#"{
+            "torrents": [{
+                "name": "[Group] Series Title - 06 [1080p].mkv",
+                "percentDone": 1,
+                "downloadDir": "/home/user/anime/series"
+            }]
+        }"#;
+
+        let qbittorrent: Vec<QBittorrentTorrent> = serde_json::from_str(qbittorrent).unwrap();
+        let transmission: TransmissionTorrents = serde_json::from_str(transmission).unwrap();
+
+        let torrents = qbittorrent
+            .into_iter()
+            .map(Torrent::from)
+            .chain(transmission.torrents.into_iter().map(Torrent::from))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            torrents,
+            vec![
+                Torrent {
+                    name: "[Group] Series Title - 05 [1080p].mkv".into(),
+                    progress: 0.5,
+                    dir: "/home/user/anime/series".into(),
+                },
+                Torrent {
+                    name: "[Group] Series Title - 06 [1080p].mkv".into(),
+                    progress: 1.0,
+                    dir: "/home/user/anime/series".into(),
+                },
+            ]
+        );
+
+        assert!(!torrents[0].is_complete());
+        assert!(torrents[1].is_complete());
+    }
+
+    #[test]
+    fn extract_qbittorrent_session_cookie() {
+        assert_eq!(
+            qbittorrent_session_cookie("SID=abc123; HttpOnly; SameSite=Strict; path=/"),
+            Some("SID=abc123")
+        );
+
+        assert_eq!(qbittorrent_session_cookie("path=/"), None);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::err;
-use anyhow::{Context, Result};
-use once_cell::sync::Lazy;
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::{Lazy, OnceCell};
 use ron::ser::PrettyConfig;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -102,6 +102,61 @@ impl FileFormat {
     }
 }
 
+/// The name of the directory every profile other than the default one is saved in.
+const PROFILES_DIR: &str = "profiles";
+
+/// The profile that files are saved under, where None is the default profile.
+static PROFILE: OnceCell<Option<String>> = OnceCell::new();
+
+/// Set the profile that files are saved under, where None is the default profile.
+///
+/// This needs to be called before any file is loaded, as the path of each save directory is only computed once.
+pub fn set_profile(name: Option<String>) -> Result<()> {
+    if let Some(name) = &name {
+        validate_profile_name(name)?;
+    }
+
+    PROFILE
+        .set(name)
+        .map_err(|_| anyhow!("the profile has already been set"))
+}
+
+/// Returns the name of the profile files are saved under, or None if it's the default profile.
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().and_then(Option::as_deref)
+}
+
+fn validate_profile_name(name: &str) -> Result<()> {
+    let is_valid_char = |ch: char| ch.is_alphanumeric() || ch == '-' || ch == '_';
+
+    if name.is_empty() || !name.chars().all(is_valid_char) {
+        return Err(anyhow!(
+            "profile names can only contain letters, numbers, dashes, and underscores"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the name of every profile that has been used, other than the default one.
+pub fn profiles() -> Result<Vec<String>> {
+    let dir = SaveDir::LocalData.shared_dir_path().join(PROFILES_DIR);
+
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut names = subdirectories(&dir)
+        .context("reading profiles")?
+        .into_iter()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| validate_profile_name(name).is_ok())
+        .collect::<Vec<_>>();
+
+    names.sort_unstable();
+    Ok(names)
+}
+
 #[derive(Copy, Clone)]
 pub enum SaveDir {
     Config,
@@ -109,7 +164,8 @@ pub enum SaveDir {
 }
 
 impl SaveDir {
-    pub fn dir_path(self) -> &'static Path {
+    /// Returns the directory shared by every profile, which is also the directory of the default profile.
+    pub fn shared_dir_path(self) -> &'static Path {
         static CONFIG_PATH: Lazy<PathBuf> = Lazy::new(|| {
             let mut dir = dirs_next::config_dir().unwrap_or_else(|| PathBuf::from("~/.config/"));
             dir.push(env!("CARGO_PKG_NAME"));
@@ -129,15 +185,41 @@ impl SaveDir {
         }
     }
 
-    pub fn validated_dir_path(self) -> Result<&'static Path> {
-        let dir = self.dir_path();
+    /// Returns the directory of the current profile.
+    pub fn dir_path(self) -> &'static Path {
+        fn profile_dir(dir: SaveDir) -> PathBuf {
+            let shared = dir.shared_dir_path();
+
+            match profile() {
+                Some(name) => shared.join(PROFILES_DIR).join(name),
+                None => shared.to_path_buf(),
+            }
+        }
 
-        if !dir.exists() {
-            fs::create_dir_all(dir).context("creating directory")?;
+        static CONFIG_PATH: Lazy<PathBuf> = Lazy::new(|| profile_dir(SaveDir::Config));
+        static LOCAL_DATA_PATH: Lazy<PathBuf> = Lazy::new(|| profile_dir(SaveDir::LocalData));
+
+        match self {
+            SaveDir::Config => CONFIG_PATH.as_ref(),
+            SaveDir::LocalData => LOCAL_DATA_PATH.as_ref(),
         }
+    }
 
-        Ok(dir)
+    pub fn validated_dir_path(self) -> Result<&'static Path> {
+        validate_dir(self.dir_path())
+    }
+
+    pub fn validated_shared_dir_path(self) -> Result<&'static Path> {
+        validate_dir(self.shared_dir_path())
+    }
+}
+
+fn validate_dir(dir: &'static Path) -> Result<&'static Path> {
+    if !dir.exists() {
+        fs::create_dir_all(dir).context("creating directory")?;
     }
+
+    Ok(dir)
 }
 
 pub fn subdirectories<D>(dir: D) -> Result<Vec<DirEntry>>
@@ -201,3 +283,17 @@ where
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_name_validation() {
+        assert!(validate_profile_name("roommate").is_ok());
+        assert!(validate_profile_name("my-profile_2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../escape").is_err());
+        assert!(validate_profile_name("with space").is_err());
+    }
+}
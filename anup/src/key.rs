@@ -7,11 +7,12 @@ use serde::{
 use smallvec::SmallVec;
 use std::{
     convert::{TryFrom, TryInto},
+    fmt,
     ops::Deref,
     result,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy)]
 pub struct Key(KeyEvent);
 
 impl Key {
@@ -26,6 +27,36 @@ impl Key {
     pub fn ctrl_pressed(&self) -> bool {
         self.0.modifiers.contains(KeyModifiers::CONTROL)
     }
+
+    /// Returns the key code and modifiers of the key in a form that can be compared with other keys.
+    ///
+    /// Terminals report uppercase letters along with the shift modifier, while keys parsed from
+    /// the config are always lowercase. Letters are lowercased with the shift modifier added to
+    /// account for this, and the shift modifier is ignored for every other character since it
+    /// depends on the keyboard layout.
+    fn normalized(self) -> (KeyCode, KeyModifiers) {
+        let KeyEvent { code, modifiers } = self.0;
+
+        match code {
+            KeyCode::Char(ch) if ch.is_ascii_alphabetic() => {
+                let modifiers = if ch.is_ascii_uppercase() {
+                    modifiers | KeyModifiers::SHIFT
+                } else {
+                    modifiers
+                };
+
+                (KeyCode::Char(ch.to_ascii_lowercase()), modifiers)
+            }
+            KeyCode::Char(_) => (code, modifiers - KeyModifiers::SHIFT),
+            _ => (code, modifiers),
+        }
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
 }
 
 impl Deref for Key {
@@ -72,6 +103,7 @@ impl TryFrom<&str> for Key {
             "insert" => KeyCode::Insert,
             "unknown" => KeyCode::Null,
             "escape" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
             key if key.len() == 1 && key.is_ascii() => {
                 let bytes = key.as_bytes();
                 KeyCode::Char(bytes[0] as char)
@@ -127,25 +159,42 @@ impl Serialize for Key {
     where
         S: Serializer,
     {
-        match self.0.code {
-            KeyCode::Backspace => se.serialize_str("backspace"),
-            KeyCode::Enter => se.serialize_str("enter"),
-            KeyCode::Left => se.serialize_str("left"),
-            KeyCode::Right => se.serialize_str("right"),
-            KeyCode::Up => se.serialize_str("up"),
-            KeyCode::Down => se.serialize_str("down"),
-            KeyCode::Home => se.serialize_str("home"),
-            KeyCode::End => se.serialize_str("end"),
-            KeyCode::PageUp => se.serialize_str("pageup"),
-            KeyCode::PageDown => se.serialize_str("pagedown"),
-            KeyCode::Tab => se.serialize_str("tab"),
-            KeyCode::BackTab => se.serialize_str("backtab"),
-            KeyCode::Delete => se.serialize_str("delete"),
-            KeyCode::Insert => se.serialize_str("insert"),
-            KeyCode::F(key) => se.serialize_str(&format!("f{}", key)),
-            KeyCode::Char(key) => se.serialize_char(key),
-            KeyCode::Null => se.serialize_str("unknown"),
-            KeyCode::Esc => se.serialize_str("escape"),
+        se.serialize_str(&self.to_string())
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (code, modifiers) = self.normalized();
+
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("ctrl+")?;
+        } else if modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("shift+")?;
+        } else if modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("alt+")?;
+        }
+
+        match code {
+            KeyCode::Backspace => f.write_str("backspace"),
+            KeyCode::Enter => f.write_str("enter"),
+            KeyCode::Left => f.write_str("left"),
+            KeyCode::Right => f.write_str("right"),
+            KeyCode::Up => f.write_str("up"),
+            KeyCode::Down => f.write_str("down"),
+            KeyCode::Home => f.write_str("home"),
+            KeyCode::End => f.write_str("end"),
+            KeyCode::PageUp => f.write_str("pageup"),
+            KeyCode::PageDown => f.write_str("pagedown"),
+            KeyCode::Tab => f.write_str("tab"),
+            KeyCode::BackTab => f.write_str("backtab"),
+            KeyCode::Delete => f.write_str("delete"),
+            KeyCode::Insert => f.write_str("insert"),
+            KeyCode::F(key) => write!(f, "f{}", key),
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(key) => write!(f, "{}", key),
+            KeyCode::Null => f.write_str("unknown"),
+            KeyCode::Esc => f.write_str("escape"),
         }
     }
 }
@@ -175,9 +224,33 @@ mod tests {
         test_key!("alt+tab", KeyCode::Tab => KeyModifiers::ALT);
         test_key!("ctrl + backspace", KeyCode::Backspace => KeyModifiers::CONTROL);
         test_key!("  shift +  f12", KeyCode::F(12) => KeyModifiers::SHIFT);
+        test_key!("space", KeyCode::Char(' ') => KeyModifiers::NONE);
         test_key!("f1", KeyCode::F(1) => KeyModifiers::NONE);
     }
 
+    #[test]
+    fn terminal_keys_match_parsed_keys() {
+        let parsed: Key = "shift+d".try_into().unwrap();
+        let pressed = Key::new(KeyEvent::new(KeyCode::Char('D'), KeyModifiers::SHIFT));
+        assert_eq!(parsed, pressed);
+
+        let parsed: Key = ":".try_into().unwrap();
+        let pressed = Key::new(KeyEvent::new(KeyCode::Char(':'), KeyModifiers::SHIFT));
+        assert_eq!(parsed, pressed);
+
+        let parsed: Key = "d".try_into().unwrap();
+        let pressed = Key::new(KeyEvent::new(KeyCode::Char('D'), KeyModifiers::SHIFT));
+        assert_ne!(parsed, pressed);
+    }
+
+    #[test]
+    fn key_display_round_trip() {
+        for key in &["shift+d", "ctrl+b", "enter", "f5", "/", "alt+tab", "space"] {
+            let parsed: Key = (*key).try_into().unwrap();
+            assert_eq!(parsed.to_string(), *key);
+        }
+    }
+
     #[test]
     #[should_panic]
     fn invalid_keys() {
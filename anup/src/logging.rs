@@ -0,0 +1,198 @@
+use crate::config::LogConfig;
+use crate::file::SaveDir;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use parking_lot::{Mutex, MutexGuard};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{filter, fmt as format, Layer};
+
+/// The target of events that should be shown in the status log of the TUI.
+pub const STATUS_TARGET: &str = "anup::status";
+
+const LOG_DIR: &str = "logs";
+
+/// The maximum number of events kept for the status log before the oldest ones are removed.
+const MAX_STATUS_EVENTS: usize = 200;
+
+/// Keeps logging to a file until dropped, which also writes out any messages that haven't been yet.
+pub struct Logger {
+    pub status: StatusEvents,
+    _guard: WorkerGuard,
+}
+
+/// Start writing every event up to the configured level to a log file in the local data directory, and collect events
+/// targeting [`STATUS_TARGET`] for the status log of the TUI.
+///
+/// A new log file is started every day, and the oldest one is removed once there are more than the configured amount.
+pub fn init(config: &LogConfig) -> Result<Logger> {
+    let dir = SaveDir::LocalData
+        .validated_shared_dir_path()?
+        .join(LOG_DIR);
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(env!("CARGO_PKG_NAME"))
+        .filename_suffix("log")
+        .max_log_files(config.max_files.max(1))
+        .build(&dir)
+        .with_context(|| format!("failed to create log file in {}", dir.display()))?;
+
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let file_layer = format::layer()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_filter(LevelFilter::from(config.level));
+
+    let status = StatusEvents::default();
+
+    let status_layer = StatusLayer(status.clone())
+        .with_filter(filter::filter_fn(|meta| meta.target() == STATUS_TARGET));
+
+    tracing_subscriber::registry()
+        .with(file_layer)
+        .with(status_layer)
+        .try_init()
+        .context("failed to start logging")?;
+
+    Ok(Logger {
+        status,
+        _guard: guard,
+    })
+}
+
+/// An event that was sent to the status log.
+pub struct StatusEvent {
+    pub level: Level,
+    /// Whether the event adds context to the error before it.
+    pub is_context: bool,
+    pub message: String,
+    pub time: DateTime<Local>,
+}
+
+/// The events that have been sent to the status log, from oldest to newest.
+#[derive(Clone, Default)]
+pub struct StatusEvents(Arc<Mutex<VecDeque<StatusEvent>>>);
+
+impl StatusEvents {
+    pub fn lock(&self) -> MutexGuard<'_, VecDeque<StatusEvent>> {
+        self.0.lock()
+    }
+
+    fn push(&self, event: StatusEvent) {
+        let mut events = self.lock();
+
+        while events.len() >= MAX_STATUS_EVENTS {
+            events.pop_front();
+        }
+
+        events.push_back(event);
+    }
+}
+
+struct StatusLayer(StatusEvents);
+
+impl<S> Layer<S> for StatusLayer
+where
+    S: Subscriber,
+{
+    fn on_event(&self, event: &Event, _: LayerContext<S>) {
+        let mut visitor = StatusVisitor::default();
+        event.record(&mut visitor);
+
+        self.0.push(StatusEvent {
+            level: *event.metadata().level(),
+            is_context: visitor.is_context,
+            message: visitor.message,
+            time: Local::now(),
+        });
+    }
+}
+
+#[derive(Default)]
+struct StatusVisitor {
+    message: String,
+    is_context: bool,
+}
+
+impl Visit for StatusVisitor {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if field.name() == "context" {
+            self.is_context = value;
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_events_are_collected() {
+        let status = StatusEvents::default();
+
+        let subscriber = tracing_subscriber::registry().with(
+            StatusLayer(status.clone())
+                .with_filter(filter::filter_fn(|meta| meta.target() == STATUS_TARGET)),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::error!(target: STATUS_TARGET, "failed to {}", "sync");
+            tracing::error!(target: STATUS_TARGET, context = true, "connection refused");
+            tracing::info!("not for the status log");
+            tracing::info!(target: STATUS_TARGET, "synced");
+        });
+
+        let events = status.lock();
+        assert_eq!(events.len(), 3);
+
+        assert_eq!(events[0].level, Level::ERROR);
+        assert_eq!(events[0].message, "failed to sync");
+        assert!(!events[0].is_context);
+
+        assert_eq!(events[1].message, "connection refused");
+        assert!(events[1].is_context);
+
+        assert_eq!(events[2].level, Level::INFO);
+        assert_eq!(events[2].message, "synced");
+    }
+
+    #[test]
+    fn old_status_events_are_removed() {
+        let status = StatusEvents::default();
+
+        for i in 0..MAX_STATUS_EVENTS + 5 {
+            status.push(StatusEvent {
+                level: Level::INFO,
+                is_context: false,
+                message: i.to_string(),
+                time: Local::now(),
+            });
+        }
+
+        let events = status.lock();
+        assert_eq!(events.len(), MAX_STATUS_EVENTS);
+        assert_eq!(events[0].message, "5");
+    }
+}
@@ -13,13 +13,23 @@
 #[macro_use]
 extern crate diesel;
 
+mod backup;
+mod clipboard;
 mod config;
 mod database;
+mod download;
 mod err;
 mod file;
 mod key;
+mod logging;
+mod media_server;
+mod mpv;
 mod remote;
+mod sanitize;
+mod scrobble;
 mod series;
+#[cfg(feature = "keyring")]
+mod token_store;
 mod tui;
 mod user;
 mod util;
@@ -27,22 +37,45 @@ mod util;
 use crate::config::Config;
 use crate::database::Database;
 use crate::file::SerializedFile;
+use crate::series::batch::{self, FolderMatch};
+use crate::series::cache::{self, Refresh};
+use crate::series::changes::{EntryChange, EntryMerge};
+use crate::series::clean::{CleanAction, MissingSeries, Resolved};
 use crate::series::config::SeriesConfig;
-use crate::series::entry::SeriesEntry;
-use crate::series::info::SeriesInfo;
-use crate::series::{LastWatched, LoadedSeries, Series};
-use crate::user::Users;
-use anime::remote::Remote;
+use crate::series::doctor::{self, Issue};
+use crate::series::entry::{self, ProgressChange, SeriesEntry};
+use crate::series::export::{ExportFormat, ExportedEntry};
+use crate::series::history::WatchRecord;
+use crate::series::hook::Hook;
+use crate::series::import;
+use crate::series::info::{MatchOptions, SeriesInfo};
+use crate::series::listing::{ListFormat, ListedSeries};
+use crate::series::rename::{RenamePlan, Template};
+use crate::series::suggest;
+use crate::series::{
+    episode_counts_as_watched, LastWatched, LoadedSeries, Series, SeriesData, SeriesParams,
+    SeriesPath, WatchStart,
+};
+use crate::user::{UserLinks, Users};
+use anime::local::EpisodeParser;
+use anime::remote::anilist::AniList;
+use anime::remote::{Remote, RemoteService, ScoreParser, SeriesID, Status};
 use anyhow::{anyhow, Context, Result};
 use argh::FromArgs;
 use chrono::Utc;
+use std::borrow::Cow;
+use std::fs;
+use std::path::PathBuf;
+use std::result;
 
 const ANILIST_CLIENT_ID: u32 = 427;
 
 #[derive(FromArgs)]
 /// Play, manage, and sync anime from the terminal.
+///
+/// Running the program without a subcommand opens the TUI.
 pub struct Args {
-    /// the nickname of the series to watch
+    /// the nickname of the series to select when the TUI opens
     #[argh(positional)]
     pub series: Option<String>,
 
@@ -50,13 +83,227 @@ pub struct Args {
     #[argh(switch, short = 'o')]
     pub offline: bool,
 
-    /// play a single episode from the last played series
+    /// the profile to use, which has its own database, users, and config
+    #[argh(option)]
+    pub profile: Option<String>,
+
+    #[argh(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum Command {
+    Watch(WatchArgs),
+    Sync(SyncArgs),
+    List(ListArgs),
+    Add(AddArgs),
+    Rm(RmArgs),
+    Set(SetArgs),
+    Rename(RenameArgs),
+    Doctor(DoctorArgs),
+    Clean(CleanArgs),
+    History(HistoryArgs),
+    Prefetch(PrefetchArgs),
+    Next(NextArgs),
+    Export(ExportArgs),
+    Import(ImportArgs),
+    Backup(BackupArgs),
+    Restore(RestoreArgs),
+    Serve(ServeArgs),
+}
+
+#[derive(FromArgs)]
+/// Play the next episode of a series.
+#[argh(subcommand, name = "watch")]
+pub struct WatchArgs {
+    /// the nickname of the series to watch, or the last watched series if not specified
+    #[argh(positional)]
+    series: Option<String>,
+}
+
+#[derive(FromArgs)]
+/// Syncronize changes made while offline.
+#[argh(subcommand, name = "sync")]
+pub struct SyncArgs {}
+
+#[derive(FromArgs)]
+/// Print every series that has been added.
+#[argh(subcommand, name = "list")]
+pub struct ListArgs {
+    /// only print series with this status: watching, completed, hold, drop, plan, or rewatch
+    #[argh(option, from_str_fn(parse_status_arg))]
+    status: Option<Status>,
+
+    /// the format to print in: text, json, or tsv
+    #[argh(option, default = "ListFormat::default()")]
+    format: ListFormat,
+}
+
+#[derive(FromArgs)]
+/// Add a series by its ID on the remote service, or every new folder in the series directory.
+#[argh(subcommand, name = "add")]
+pub struct AddArgs {
+    /// the ID of the series on the remote service
+    #[argh(positional)]
+    id: Option<SeriesID>,
+
+    /// the nickname to give the series, which is generated from its title if not specified
+    #[argh(option)]
+    nickname: Option<String>,
+
+    /// the folder the episodes of the series are in, which is the closest matching folder in the series directory if not specified
+    #[argh(option)]
+    path: Option<PathBuf>,
+
+    /// match every folder in the series directory that hasn't been added yet against the remote service and print the results
+    #[argh(switch)]
+    all: bool,
+
+    /// with --all, add every folder that was confidently matched
     #[argh(switch)]
-    pub play_one: bool,
+    confirm: bool,
+}
+
+#[derive(FromArgs)]
+/// Remove a series from the program.
+#[argh(subcommand, name = "rm")]
+pub struct RmArgs {
+    /// the nickname of the series to remove
+    #[argh(positional)]
+    series: String,
+}
+
+#[derive(FromArgs)]
+/// Change the list entry or folder of a series.
+#[argh(subcommand, name = "set")]
+pub struct SetArgs {
+    /// the nickname of the series to change
+    #[argh(positional)]
+    series: String,
+
+    /// the new status: watching, completed, hold, drop, plan, or rewatch
+    #[argh(option, from_str_fn(parse_status_arg))]
+    status: Option<Status>,
+
+    /// the new score, in the scoring system of the remote service, where 0 removes the score
+    #[argh(option)]
+    score: Option<String>,
+
+    /// the new number of watched episodes, or a change to it like +1 or -1
+    #[argh(option)]
+    progress: Option<ProgressChange>,
 
-    /// syncronize changes made while offline
+    /// the new folder the episodes of the series are in
+    #[argh(option)]
+    path: Option<PathBuf>,
+}
+
+#[derive(FromArgs)]
+/// Rename the episode files of a series with a template.
+#[argh(subcommand, name = "rename")]
+pub struct RenameArgs {
+    /// the nickname of the series to rename the episodes of
+    #[argh(positional)]
+    series: String,
+
+    /// the template to rename episodes with, which is the one in the config if not specified
+    #[argh(option)]
+    template: Option<Template>,
+
+    /// the season number to use in the template
+    #[argh(option, default = "1")]
+    season: u32,
+
+    /// rename the files instead of only printing what they would be renamed to
+    #[argh(switch)]
+    apply: bool,
+}
+
+#[derive(FromArgs)]
+/// Check every series and the database for problems.
+#[argh(subcommand, name = "doctor")]
+pub struct DoctorArgs {
+    /// apply every fix that can be done safely
     #[argh(switch)]
-    pub sync: bool,
+    fix: bool,
+}
+
+#[derive(FromArgs)]
+/// Print every series whose folder no longer exists.
+#[argh(subcommand, name = "clean")]
+pub struct CleanArgs {
+    /// what to do with each series: delete, repath to the closest matching folder, or keep without a folder
+    #[argh(option)]
+    action: Option<CleanAction>,
+}
+
+#[derive(FromArgs)]
+/// Print the most recently watched episodes.
+#[argh(subcommand, name = "history")]
+pub struct HistoryArgs {}
+
+#[derive(FromArgs)]
+/// Download the info of every series so it can be browsed offline.
+#[argh(subcommand, name = "prefetch")]
+pub struct PrefetchArgs {}
+
+#[derive(FromArgs)]
+/// Export every series in your list to a file.
+#[argh(subcommand, name = "export")]
+pub struct ExportArgs {
+    /// the format to export in: mal-xml, anilist-json, or csv
+    #[argh(option)]
+    format: ExportFormat,
+
+    /// the file to write the exported list to
+    #[argh(positional)]
+    path: PathBuf,
+}
+
+#[derive(FromArgs)]
+/// Add every series from a MyAnimeList XML or AniList JSON export to the program.
+#[argh(subcommand, name = "import")]
+pub struct ImportArgs {
+    /// the export file to read the list from
+    #[argh(positional)]
+    path: PathBuf,
+}
+
+#[derive(FromArgs)]
+/// Save a compressed snapshot of the database and config.
+#[argh(subcommand, name = "backup")]
+pub struct BackupArgs {
+    /// the file or directory to save the backup to
+    #[argh(positional)]
+    path: Option<PathBuf>,
+}
+
+#[derive(FromArgs)]
+/// Replace the database and config with the ones in a backup.
+#[argh(subcommand, name = "restore")]
+pub struct RestoreArgs {
+    /// the backup to restore
+    #[argh(positional)]
+    path: PathBuf,
+}
+
+#[derive(FromArgs)]
+/// Listen for scrobble events from media players and update the progress of the series they belong to.
+#[argh(subcommand, name = "serve")]
+pub struct ServeArgs {
+    /// the address to listen on
+    #[argh(option, default = "String::from(\"127.0.0.1:8419\")")]
+    address: String,
+}
+
+#[derive(FromArgs)]
+/// Suggest which series being watched to watch next.
+#[argh(subcommand, name = "next")]
+pub struct NextArgs {
+    /// the maximum number of suggestions to print
+    #[argh(option, default = "5")]
+    count: usize,
 }
 
 fn main() -> Result<()> {
@@ -71,40 +318,84 @@ fn main() -> Result<()> {
 
 async fn run() -> Result<()> {
     let args: Args = argh::from_env();
+    file::set_profile(args.profile.clone())?;
 
-    if args.play_one {
-        play_episode(&args).await
-    } else if args.sync {
-        sync(&args)
-    } else {
-        tui::run(&args).await
+    // Problems with the config are reported once it's loaded for the command being run
+    let log_config = Config::load().map(|config| config.log).unwrap_or_default();
+
+    let logger = logging::init(&log_config)?;
+
+    let command = match &args.command {
+        Some(command) => command,
+        None => return tui::run(&args, logger).await,
+    };
+
+    match command {
+        Command::Watch(watch) => play_episode(&args, watch).await,
+        Command::Sync(_) => sync(&args).await,
+        Command::List(list) => list_series(list),
+        Command::Add(add) => add_series(&args, add).await,
+        Command::Rm(rm) => remove_series(rm),
+        Command::Set(set) => set_series(&args, set).await,
+        Command::Rename(rename) => rename_episodes(rename),
+        Command::Doctor(doctor) => check_series(&args, doctor).await,
+        Command::Clean(clean) => clean_series(clean),
+        Command::History(_) => print_history(),
+        Command::Prefetch(_) => prefetch(&args).await,
+        Command::Next(next) => suggest_next(&args, next).await,
+        Command::Export(export) => export_list(export),
+        Command::Import(import) => import_list(import),
+        Command::Backup(backup) => backup_data(backup),
+        Command::Restore(restore) => restore_data(restore),
+        Command::Serve(serve) => serve_scrobbles(&args, serve).await,
     }
 }
 
+fn parse_status_arg(value: &str) -> result::Result<Status, String> {
+    entry::parse_status(value).ok_or_else(|| format!("unknown status: {}", value))
+}
+
 /// Initialize a new remote service specified by `args`.
 ///
-/// If there are no users, returns Ok(None).
-fn init_remote(args: &Args) -> Result<Option<Remote>> {
-    use anime::remote::anilist::{AniList, Auth};
-
+/// If there are no users, AniList is used without an account, which can look up series but can't retrieve or update list entries.
+async fn init_remote(args: &Args, config: &Config, db: &Database) -> Result<Remote> {
     if args.offline {
-        Ok(Some(Remote::offline()))
-    } else {
-        let token = match Users::load_or_create()?.take_last_used_token() {
-            Some(token) => token,
-            None => return Ok(None),
-        };
+        return Ok(cache::offline_remote(db));
+    }
+
+    let links = UserLinks::load_or_create()?;
+
+    let (login, linked) = match Users::load_or_create()?.last_used_logins(&links) {
+        Some(logins) => logins,
+        None => return Ok(AniList::Unauthenticated(config.anilist_client()?).into()),
+    };
+
+    let prepare = |login: remote::RemoteLogin| {
+        login.prepare(config).map(|mut prepared| {
+            prepared.set_rate_limit_hook(|wait| {
+                eprintln!("waiting for rate limit ({}s)", wait.as_secs_f32().ceil());
+            });
 
-        let auth = Auth::retrieve(token)?;
-        Ok(Some(AniList::Authenticated(auth).into()))
+            prepared
+        })
+    };
+
+    let linked = linked.into_iter().map(prepare).collect();
+    let (remote, linked_errors) = remote::login_linked(prepare(login)?, linked).await?;
+
+    for err in linked_errors {
+        eprintln!("warning: {:#}", err);
     }
+
+    Ok(remote)
 }
 
-fn sync(args: &Args) -> Result<()> {
+async fn sync(args: &Args) -> Result<()> {
     if args.offline {
         return Err(anyhow!("must be online to run this command"));
     }
 
+    let config = Config::load_or_create()?;
     let db = Database::open().context("failed to open database")?;
     let mut list_entries = SeriesEntry::entries_that_need_sync(&db)?;
 
@@ -112,8 +403,13 @@ fn sync(args: &Args) -> Result<()> {
         return Ok(());
     }
 
-    let remote =
-        init_remote(&args)?.ok_or_else(|| anyhow!("no users found\nadd one in the TUI"))?;
+    let remote = init_remote(&args, &config, &db).await?;
+
+    if !remote.has_list() {
+        return Err(anyhow!(
+            "must be logged in to sync list entries\nadd an account in the TUI"
+        ));
+    }
 
     for entry in &mut list_entries {
         match SeriesInfo::load(&db, entry.id()) {
@@ -125,72 +421,705 @@ fn sync(args: &Args) -> Result<()> {
             ),
         }
 
-        entry.sync_to_remote(&remote)?;
+        let changes = EntryChange::load(&db, entry.id())?;
+        let latest = remote.get_list_entry(entry.id() as u32).await?;
+        let mut merge = EntryMerge::new(entry, latest, &changes);
+
+        for conflict in merge.resolve_latest() {
+            let (kept, value) = if conflict.local_is_latest() {
+                ("local", &conflict.local)
+            } else {
+                ("remote", &conflict.remote)
+            };
+
+            println!(
+                "{} was changed locally and remotely, keeping the {} value of {}",
+                conflict.field.name(),
+                kept,
+                value.as_deref().unwrap_or("nothing")
+            );
+        }
+
+        *entry = merge.into_merged();
+        entry.force_sync_to_remote(&remote).await?;
         entry.save(&db)?;
     }
 
     Ok(())
 }
 
-async fn play_episode(args: &Args) -> Result<()> {
-    use anime::remote::Status;
+async fn prefetch(args: &Args) -> Result<()> {
+    if args.offline {
+        return Err(anyhow!("must be online to run this command"));
+    }
+
+    let config = Config::load_or_create()?;
+    let db = Database::open().context("failed to open database")?;
+
+    let remote = init_remote(&args, &config, &db).await?;
+
+    let result = cache::refresh(|| &db, &remote, Refresh::All).await?;
+
+    for (id, err) in &result.failed {
+        eprintln!(
+            "warning: failed to get info for anime with ID {}: {:#}",
+            id, err
+        );
+    }
+
+    println!("stored info for {} series", result.refreshed);
+    Ok(())
+}
+
+async fn suggest_next(args: &Args, next: &NextArgs) -> Result<()> {
+    const DAY_SECS: i64 = 24 * 60 * 60;
+
+    let config = Config::load_or_create()?;
+    let db = Database::open().context("failed to open database")?;
+
+    let remote = init_remote(&args, &config, &db).await?;
+
+    let series = SeriesConfig::load_all(&db)?
+        .into_iter()
+        .map(|cfg| Series::load_from_config(cfg, &config, &db))
+        .collect::<Vec<_>>();
+
+    let ids = series
+        .iter()
+        .filter_map(LoadedSeries::complete)
+        .map(|series| series.data.info.id as SeriesID)
+        .collect::<Vec<_>>();
+
+    let max_age = chrono::Duration::minutes(config.notifications.check_interval_mins.into());
+    let latest_aired = cache::latest_aired(|| &db, &remote, &ids, max_age).await?;
+    let last_watched = WatchRecord::last_watched_times(&db)?;
+
+    let now = Utc::now().timestamp();
+    let ranked = suggest::rank(&series, &latest_aired, &last_watched, now);
+
+    if ranked.is_empty() {
+        println!("no series being watched has an unwatched episode on disk");
+        return Ok(());
+    }
+
+    for (rank, (index, candidate)) in ranked.iter().take(next.count).enumerate() {
+        let title = series[*index]
+            .info()
+            .map_or("unknown", |info| info.title_preferred.as_str());
+
+        let last_watched = match candidate
+            .last_watched
+            .map(|watched| (now - watched) / DAY_SECS)
+        {
+            Some(0) => "today".into(),
+            Some(days) => format!("{} day(s) ago", days),
+            None => "never".into(),
+        };
+
+        println!(
+            "{}. {} | {} episode(s) behind | last watched {} | {} left",
+            rank + 1,
+            title,
+            candidate.episodes_behind,
+            last_watched,
+            util::hours_mins_str(candidate.remaining_mins.into()),
+        );
+    }
+
+    Ok(())
+}
+
+fn export_list(args: &ExportArgs) -> Result<()> {
+    let db = Database::open().context("failed to open database")?;
+    let entries = ExportedEntry::load_all(&db)?;
+    let exported = args.format.render(&entries)?;
+
+    fs::write(&args.path, exported)
+        .with_context(|| format!("failed to write list to {}", args.path.display()))?;
+
+    println!(
+        "exported {} series to {}",
+        entries.len(),
+        args.path.display()
+    );
+
+    Ok(())
+}
+
+fn backup_data(args: &BackupArgs) -> Result<()> {
+    let db = Database::open().context("failed to open database")?;
+
+    let path = match &args.path {
+        Some(path) if path.is_dir() => path.join(backup::timestamped_name("backup")),
+        Some(path) => path.clone(),
+        None => backup::backup_dir()?.join(backup::timestamped_name("backup")),
+    };
+
+    backup::create(db.conn(), &path)?;
+    println!("backed up to {}", path.display());
+
+    Ok(())
+}
+
+fn restore_data(args: &RestoreArgs) -> Result<()> {
+    let previous = backup::restore(&args.path)?;
+
+    println!("restored {}", args.path.display());
+
+    if let Some(previous) = previous {
+        println!("your previous data was backed up to {}", previous.display());
+    }
+
+    Ok(())
+}
+
+fn import_list(args: &ImportArgs) -> Result<()> {
+    let config = Config::load_or_create()?;
+    let db = Database::open().context("failed to open database")?;
+
+    let contents = fs::read_to_string(&args.path)
+        .with_context(|| format!("failed to read {}", args.path.display()))?;
+
+    let entries = import::parse(&contents)?;
+    let summary = import::import(entries, &config, &db)?;
+
+    for title in &summary.skipped {
+        println!("skipped {} as it has already been added", title);
+    }
+
+    println!("imported {} series", summary.imported);
+
+    if !summary.unmatched.is_empty() {
+        println!(
+            "no episode folder could be found for the following series, so their path will need to be set in the TUI before they can be played:\n{}",
+            summary.unmatched.join("\n")
+        );
+    }
+
+    Ok(())
+}
+
+fn print_history() -> Result<()> {
+    const MAX_RECORDS: i64 = 25;
+
+    let db = Database::open().context("failed to open database")?;
+    let records = WatchRecord::recent(&db, MAX_RECORDS).context("failed to load watch history")?;
+
+    if records.is_empty() {
+        println!("no episodes have been watched yet");
+        return Ok(());
+    }
+
+    // Print the oldest record first so the most recent one ends up closest to the prompt
+    for record in records.iter().rev() {
+        println!(
+            "{} | {} episode {} | watched for {} | {}",
+            record.finished_at_str(),
+            record.title,
+            record.episode,
+            record.watch_time_str(),
+            record.exit_status_str(),
+        );
+    }
+
+    Ok(())
+}
+
+fn list_series(args: &ListArgs) -> Result<()> {
+    let config = Config::load_or_create()?;
+    let db = Database::open().context("failed to open database")?;
+
+    let mut series = SeriesConfig::load_all(&db)?
+        .into_iter()
+        .map(|cfg| SeriesData::load_from_config(&db, Cow::Owned(cfg)))
+        .collect::<diesel::QueryResult<Vec<_>>>()
+        .context("failed to load series")?;
+
+    if let Some(status) = args.status {
+        series.retain(|data| data.entry.status() == status);
+    }
+
+    if series.is_empty() && args.format == ListFormat::Text {
+        println!("no series found");
+        return Ok(());
+    }
+
+    series.sort_unstable_by(|x, y| x.config.nickname.cmp(&y.config.nickname));
+
+    let listed = series
+        .iter()
+        .map(|data| ListedSeries::new(data, &config))
+        .collect::<Vec<_>>();
+
+    println!("{}", args.format.render(&listed)?.trim_end());
+    Ok(())
+}
+
+async fn add_series(args: &Args, add: &AddArgs) -> Result<()> {
+    if args.offline {
+        return Err(anyhow!("must be online to run this command"));
+    }
+
+    let config = Config::load_or_create()?;
+    let db = Database::open().context("failed to open database")?;
+
+    let remote = init_remote(args, &config, &db).await?;
+
+    if add.all {
+        return add_all_series(add, &remote, &config, &db).await;
+    }
+
+    let id = add
+        .id
+        .ok_or_else(|| anyhow!("specify the ID of the series to add, or --all"))?;
+
+    let max_age = chrono::Duration::days(config.offline.cache_max_age_days.into());
+    let info: SeriesInfo = cache::lookup_by_id(|| &db, &remote, id, max_age)
+        .await?
+        .into();
+
+    let nickname = match &add.nickname {
+        Some(nickname) => nickname.clone(),
+        None => series::generate_nickname(&info.title_preferred).ok_or_else(|| {
+            anyhow!(
+                "failed to generate a nickname for {}\nspecify one with --nickname",
+                info.title_preferred
+            )
+        })?,
+    };
+
+    let path = match &add.path {
+        Some(path) => SeriesPath::new(path.as_path(), &config),
+        None => SeriesPath::closest_matching(&info.title_preferred, &config)
+            .unwrap_or_else(|_| SeriesPath::unset()),
+    };
+
+    let params = SeriesParams::new(nickname, path, EpisodeParser::default());
+    let series_config = SeriesConfig::new(info.id, params, &db)?;
+    let data = SeriesData::from_remote(series_config, info, &remote).await?;
+
+    let series = Series::init(data, &config);
+
+    if let LoadedSeries::Partial(_, err) = &series {
+        eprintln!("warning: {}", err);
+    }
+
+    series.save(&db)?;
+    println!("added {}", series.config().nickname);
+    Ok(())
+}
+
+async fn add_all_series(
+    add: &AddArgs,
+    remote: &Remote,
+    config: &Config,
+    db: &Database,
+) -> Result<()> {
+    let sconfigs = SeriesConfig::load_all(db)?;
+    let folders = batch::new_folders(&sconfigs, config)?;
+
+    if folders.is_empty() {
+        println!("no new folders found in {}", config.series_dir.display());
+        return Ok(());
+    }
+
+    let options = MatchOptions::from(config);
+    let mut matches = Vec::with_capacity(folders.len());
+
+    for path in folders {
+        let display = path.display().to_string();
+
+        match FolderMatch::find(path, remote, options).await {
+            Ok(found) => matches.push(found),
+            Err(err) => eprintln!("warning: failed to look up {}: {}", display, err),
+        }
+    }
+
+    for found in &matches {
+        match found.selected() {
+            Some(candidate) => println!(
+                "{}: {} ({:.0}%)",
+                found.path.display(),
+                candidate.info.title_preferred,
+                candidate.confidence * 100.0
+            ),
+            None => {
+                let closest = found
+                    .candidates
+                    .iter()
+                    .take(3)
+                    .map(|candidate| {
+                        format!(
+                            "{} [{}] ({:.0}%)",
+                            candidate.info.title_preferred,
+                            candidate.info.id,
+                            candidate.confidence * 100.0
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                if closest.is_empty() {
+                    println!("{}: no match found", found.path.display());
+                } else {
+                    println!(
+                        "{}: not confident, closest matches are {}",
+                        found.path.display(),
+                        closest.join(", ")
+                    );
+                }
+            }
+        }
+    }
+
+    if !add.confirm {
+        println!("run again with --confirm to add every confident match\nthe others can be added with add <id> --path <folder>");
+        return Ok(());
+    }
+
+    for result in batch::into_configs(matches, db) {
+        let added = async {
+            let (sconfig, info) = result?;
+            let data = SeriesData::from_remote(sconfig, info, remote).await?;
+            let series = Series::init(data, config);
+
+            series.save(db)?;
+            Ok::<_, anyhow::Error>(series.config().nickname.clone())
+        };
+
+        match added.await {
+            Ok(nickname) => println!("added {}", nickname),
+            Err(err) => eprintln!("warning: {}", err),
+        }
+    }
+
+    Ok(())
+}
+
+fn remove_series(args: &RmArgs) -> Result<()> {
+    let db = Database::open().context("failed to open database")?;
+
+    let cfg = SeriesConfig::load_by_name(&db, &args.series)
+        .with_context(|| format!("no series named {} found", args.series))?;
+
+    cfg.delete(&db)?;
+    println!("removed {}", cfg.nickname);
+    Ok(())
+}
+
+async fn set_series(args: &Args, set: &SetArgs) -> Result<()> {
+    let config = Config::load_or_create()?;
+    let db = Database::open().context("failed to open database")?;
+
+    let mut cfg = SeriesConfig::load_by_name(&db, &set.series)
+        .with_context(|| format!("no series named {} found", set.series))?;
+
+    if let Some(path) = &set.path {
+        cfg.path = SeriesPath::new(path.as_path(), &config);
+    }
+
+    let mut data =
+        SeriesData::load_from_config(&db, Cow::Owned(cfg)).context("failed to load series")?;
+
+    let remote = init_remote(args, &config, &db).await?;
+
+    // Relative progress changes should be based on the latest progress, which may have been changed elsewhere
+    data.entry.sync_from_remote(&remote).await?;
+
+    if let Some(progress) = set.progress {
+        data.entry
+            .change_progress(progress, data.info.episodes, &config);
+    }
+
+    // An explicit status takes priority over the one set from the progress
+    if let Some(status) = set.status {
+        data.entry.set_status(status, &config);
+    }
+
+    if let Some(raw_score) = &set.score {
+        let score = match remote.parse_score(raw_score) {
+            Some(0) => None,
+            Some(score) => Some(score.into()),
+            None => return Err(anyhow!("invalid score")),
+        };
+
+        data.entry.set_score(score);
+    }
+
+    // Save before syncing so the change isn't lost if the sync fails
+    data.save(&db)?;
+
+    data.entry.sync_to_remote(&remote).await.with_context(|| {
+        format!(
+            "{} was updated, but could not be synced\nit will be synced the next time you're online",
+            data.config.nickname
+        )
+    })?;
+
+    data.save(&db)?;
+
+    println!(
+        "{} | {} | {}/{}",
+        data.config.nickname,
+        data.entry.status(),
+        data.entry.watched_episodes(),
+        data.info.episodes
+    );
+
+    Ok(())
+}
+
+fn rename_episodes(args: &RenameArgs) -> Result<()> {
+    let config = Config::load_or_create()?;
+    let db = Database::open().context("failed to open database")?;
+
+    let cfg = SeriesConfig::load_by_name(&db, &args.series)
+        .with_context(|| format!("no series named {} found", args.series))?;
+
+    let mut series = Series::load_from_config(cfg, &config, &db).into_complete()?;
+
+    let template = match &args.template {
+        Some(template) => template.clone(),
+        None => config
+            .episode
+            .rename_template
+            .parse()
+            .context("invalid rename template in config")?,
+    };
+
+    let plan = RenamePlan::new(&series, &template, args.season, &config.filenames)?;
+
+    if plan.is_empty() {
+        println!("every episode is already named correctly");
+        return Ok(());
+    }
+
+    for rename in &plan.renames {
+        println!("{} -> {}", rename.old_name, rename.new_name);
+    }
+
+    if !args.apply {
+        println!("run again with --apply to rename these episodes");
+        return Ok(());
+    }
+
+    plan.apply(&mut series, &config, &db)?;
+    println!("renamed {} episodes", plan.renames.len());
+    Ok(())
+}
 
+async fn check_series(args: &Args, doctor_args: &DoctorArgs) -> Result<()> {
+    let config = Config::load_or_create()?;
+    let db = Database::open().context("failed to open database")?;
+    let remote = init_remote(&args, &config, &db).await?;
+
+    if remote.is_offline() {
+        println!("not checking series against the remote service while offline");
+    }
+
+    let mut issues: Vec<Issue> = doctor::check_database(&db)?;
+
+    for sconfig in SeriesConfig::load_all(&db)? {
+        let data = match SeriesData::load_from_config(&db, Cow::Owned(sconfig)) {
+            Ok(data) => data,
+            // Series that fail to load are already reported by the database check
+            Err(_) => continue,
+        };
+
+        issues.extend(doctor::check_local(&data, &config));
+
+        match doctor::check_remote(&data, &remote, &db, &config).await {
+            Ok(remote_issues) => issues.extend(remote_issues),
+            Err(err) => eprintln!(
+                "warning: failed to check {} against the remote service: {}",
+                data.config.nickname, err
+            ),
+        }
+    }
+
+    if issues.is_empty() {
+        println!("no problems found");
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("{}", issue);
+    }
+
+    let fixable = issues.iter().filter_map(|issue| issue.fix.as_ref());
+
+    if !doctor_args.fix {
+        println!(
+            "\n{} problems found, {} can be fixed with --fix",
+            issues.len(),
+            fixable.count()
+        );
+
+        return Ok(());
+    }
+
+    let mut fixed = 0;
+
+    for fix in fixable {
+        match fix.apply(&db) {
+            Ok(()) => fixed += 1,
+            Err(err) => eprintln!("warning: failed to {}: {}", fix, err),
+        }
+    }
+
+    println!("\n{} problems found, {} fixed", issues.len(), fixed);
+    Ok(())
+}
+
+fn clean_series(args: &CleanArgs) -> Result<()> {
+    let config = Config::load_or_create()?;
+    let db = Database::open().context("failed to open database")?;
+
+    let sconfigs = SeriesConfig::load_all(&db)?;
+    let missing = MissingSeries::find_all(&sconfigs, &config);
+
+    if missing.is_empty() {
+        println!("no series with missing folders found");
+        return Ok(());
+    }
+
+    let action = match args.action {
+        Some(action) => action,
+        None => {
+            for series in &missing {
+                match &series.closest {
+                    Some(closest) => println!(
+                        "{}: {} is missing, closest match is {}",
+                        series.config.nickname,
+                        series.config.path.display(),
+                        closest.display()
+                    ),
+                    None => println!(
+                        "{}: {} is missing",
+                        series.config.nickname,
+                        series.config.path.display()
+                    ),
+                }
+            }
+
+            println!("run again with --action delete, repath, or keep to resolve these series");
+            return Ok(());
+        }
+    };
+
+    for series in &missing {
+        match series.resolve(action, &db) {
+            Ok(Resolved::Kept(sconfig)) if sconfig.path.is_set() => {
+                println!("moved {} to {}", sconfig.nickname, sconfig.path.display())
+            }
+            Ok(Resolved::Kept(sconfig)) => println!("removed the folder of {}", sconfig.nickname),
+            Ok(Resolved::Deleted(_)) => println!("removed {}", series.config.nickname),
+            Err(err) => eprintln!("warning: {}: {}", series.config.nickname, err),
+        }
+    }
+
+    Ok(())
+}
+
+async fn serve_scrobbles(args: &Args, serve: &ServeArgs) -> Result<()> {
+    let config = Config::load_or_create()?;
+    let db = Database::open().context("failed to open database")?;
+
+    let remote = init_remote(&args, &config, &db).await?;
+
+    scrobble::serve(&serve.address, &remote, &config, &db).await
+}
+
+async fn play_episode(args: &Args, watch: &WatchArgs) -> Result<()> {
     let config = Config::load_or_create()?;
     let db = Database::open().context("failed to open database")?;
     let mut last_watched = LastWatched::load()?;
 
-    let remote =
-        init_remote(&args)?.ok_or_else(|| anyhow!("no users found\nadd one in the TUI"))?;
+    let remote = init_remote(&args, &config, &db).await?;
 
-    let desired_series = args
+    let desired_series = watch
         .series
         .as_ref()
         .or_else(|| last_watched.get())
         .ok_or_else(|| anyhow!("series name must be specified"))?;
 
     let mut series = {
-        let cfg = SeriesConfig::load_by_name(&db, desired_series).with_context(|| {
-            format!(
-                "{} must be added to the program in the TUI first",
-                desired_series
-            )
-        })?;
+        let cfg = SeriesConfig::load_by_name(&db, desired_series)
+            .with_context(|| format!("{} must be added to the program first", desired_series))?;
 
-        match Series::load_from_config(cfg, &config, &db) {
-            LoadedSeries::Complete(series) => series,
-            LoadedSeries::Partial(_, err) => return Err(err.into()),
-            LoadedSeries::None(_, err) => return Err(err),
-        }
+        Series::load_from_config(cfg, &config, &db).into_complete()?
     };
 
     if last_watched.set(&series.data.config.nickname) {
         last_watched.save()?;
     }
 
-    series.begin_watching(&remote, &config, &db)?;
+    series.data.entry.sync_from_remote(&remote).await?;
+    series.begin_watching(WatchStart::UpdateStatus, &config, &db)?;
+    series.data.entry.sync_to_remote(&remote).await?;
+    series.save(&db)?;
+
+    let next_episode_num = series.next_episode();
+    let run_hook = |hook: Hook, series: &Series| {
+        if let Err(err) = hook.run(series, next_episode_num, &config) {
+            eprintln!("warning: {:#}", err);
+        }
+    };
+
+    run_hook(Hook::BeforePlay, &series);
 
-    let progress_time = series.data.next_watch_progress_time(&config);
-    let next_episode_num = series.data.entry.watched_episodes() + 1;
+    let playback = series.play_episode(next_episode_num, &config, &db)?;
+    let started_at = Utc::now();
 
-    series
-        .play_episode(next_episode_num as u32, &config)?
-        .wait()
+    let result = playback
+        .wait(|_| ())
         .await
         .context("waiting for episode to finish failed")?;
 
-    if Utc::now() >= progress_time {
-        series.episode_completed(&remote, &config, &db)?;
+    run_hook(Hook::AfterPlay, &series);
+
+    let completed = episode_counts_as_watched(&result, &config);
+    series.save_playback_progress(next_episode_num, &result, completed, &config, &db)?;
+
+    if result.reached_progress && result.idle {
+        println!("episode not counted (idle)");
+    }
+
+    if result.crashed {
+        println!(
+            "episode not counted ({} exited with {} right after starting)",
+            config.episode.player, result.status
+        );
+    }
+
+    if completed {
+        series.episode_completed(&config, &db)?;
+        series.data.entry.sync_to_remote(&remote).await?;
+        series.save(&db)?;
+
+        WatchRecord::new(
+            &series.data,
+            next_episode_num as i16,
+            started_at,
+            result.status,
+        )
+        .save(&db)
+        .context("failed to record watch history")?;
+
+        run_hook(Hook::EpisodeCompleted, &series);
 
-        if series.data.entry.status() == Status::Completed {
+        if series.is_completed() {
+            run_hook(Hook::SeriesCompleted, &series);
             println!("{} completed!", series.data.info.title_preferred);
         } else {
             println!(
                 "{}/{} of {} completed",
-                series.data.entry.watched_episodes(),
+                series.watched_episodes(),
                 series.data.info.episodes,
                 series.data.info.title_preferred
             );
         }
+    } else if result.reached_progress {
+        println!("player exited with an error, so the episode was not counted as completed");
     } else {
         println!("did not watch long enough to count episode as completed");
     }
@@ -0,0 +1,297 @@
+use crate::config::{MediaServer, MediaServerConfig};
+use anyhow::{anyhow, Context, Result};
+use reqwest::header::ACCEPT;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long to wait for the media server to respond before giving up on a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Returns the local path of every episode that has been played on the media server in `config`, using `http` to connect to it.
+///
+/// `series_dir` is used to translate paths from the server when it has the series directory mounted somewhere else.
+pub async fn played_episodes(
+    http: &reqwest::Client,
+    config: &MediaServerConfig,
+    series_dir: &Path,
+) -> Result<HashSet<PathBuf>> {
+    let server = match config.server {
+        Some(server) => server,
+        None => return Ok(HashSet::new()),
+    };
+
+    let url = config
+        .url
+        .as_deref()
+        .unwrap_or_else(|| server.default_url())
+        .trim_end_matches('/');
+
+    let token = config.token.as_deref().ok_or_else(|| {
+        anyhow!("the token field in the media_server section of your config must be set")
+    })?;
+
+    let paths = match server {
+        MediaServer::Jellyfin => {
+            let user_id = config.user_id.as_deref().ok_or_else(|| {
+                anyhow!("the user_id field in the media_server section of your config must be set to use Jellyfin")
+            })?;
+
+            jellyfin_played(http, url, token, user_id)
+                .await
+                .context("failed to get played episodes from Jellyfin")?
+        }
+        MediaServer::Plex => plex_played(http, url, token)
+            .await
+            .context("failed to get played episodes from Plex")?,
+    };
+
+    let paths = paths
+        .into_iter()
+        .map(|path| local_path(path, config.series_dir.as_deref(), series_dir))
+        .collect();
+
+    Ok(paths)
+}
+
+/// Translate a `path` from the media server that may be in `server_dir` into one in `local_dir`.
+fn local_path(path: PathBuf, server_dir: Option<&Path>, local_dir: &Path) -> PathBuf {
+    match server_dir.and_then(|server_dir| path.strip_prefix(server_dir).ok()) {
+        Some(relative) => local_dir.join(relative),
+        None => path,
+    }
+}
+
+async fn jellyfin_played(
+    http: &reqwest::Client,
+    url: &str,
+    token: &str,
+    user_id: &str,
+) -> Result<Vec<PathBuf>> {
+    let items: JellyfinItems = http
+        .get(format!("{}/Users/{}/Items", url, user_id))
+        .query(&[
+            ("Recursive", "true"),
+            ("IncludeItemTypes", "Episode,Movie"),
+            ("IsPlayed", "true"),
+            ("Fields", "Path"),
+        ])
+        .header("X-Emby-Token", token)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(items.paths())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct JellyfinItems {
+    items: Vec<JellyfinItem>,
+}
+
+impl JellyfinItems {
+    fn paths(self) -> Vec<PathBuf> {
+        self.items
+            .into_iter()
+            .filter_map(|item| item.path)
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct JellyfinItem {
+    #[serde(default)]
+    path: Option<PathBuf>,
+}
+
+async fn plex_played(http: &reqwest::Client, url: &str, token: &str) -> Result<Vec<PathBuf>> {
+    /// The Plex item type of episodes.
+    const EPISODE_TYPE: &str = "4";
+    /// The Plex item type of movies.
+    const MOVIE_TYPE: &str = "1";
+
+    let sections: PlexSections =
+        plex_request(http, format!("{}/library/sections", url), token, None).await?;
+    let mut paths = Vec::new();
+
+    for section in sections.media_container.directory {
+        let kind = match section.kind.as_str() {
+            "show" => EPISODE_TYPE,
+            "movie" => MOVIE_TYPE,
+            _ => continue,
+        };
+
+        let items: PlexItems = plex_request(
+            http,
+            format!("{}/library/sections/{}/all", url, section.key),
+            token,
+            Some(kind),
+        )
+        .await?;
+
+        paths.extend(items.played_paths());
+    }
+
+    Ok(paths)
+}
+
+async fn plex_request<T>(
+    http: &reqwest::Client,
+    url: String,
+    token: &str,
+    kind: Option<&str>,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let mut request = http
+        .get(url)
+        .header("X-Plex-Token", token)
+        .header(ACCEPT, "application/json")
+        .timeout(REQUEST_TIMEOUT);
+
+    if let Some(kind) = kind {
+        request = request.query(&[("type", kind)]);
+    }
+
+    request
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .map_err(Into::into)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlexSections {
+    media_container: PlexSectionList,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlexSectionList {
+    #[serde(default)]
+    directory: Vec<PlexSection>,
+}
+
+#[derive(Deserialize)]
+struct PlexSection {
+    key: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlexItems {
+    media_container: PlexItemList,
+}
+
+impl PlexItems {
+    fn played_paths(self) -> impl Iterator<Item = PathBuf> {
+        self.media_container
+            .metadata
+            .into_iter()
+            .filter(|item| item.view_count > 0)
+            .flat_map(|item| item.media)
+            .flat_map(|media| media.parts)
+            .map(|part| part.file)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlexItemList {
+    #[serde(default)]
+    metadata: Vec<PlexItem>,
+}
+
+#[derive(Deserialize)]
+struct PlexItem {
+    #[serde(rename = "viewCount", default)]
+    view_count: u32,
+    #[serde(rename = "Media", default)]
+    media: Vec<PlexMedia>,
+}
+
+#[derive(Deserialize)]
+struct PlexMedia {
+    #[serde(rename = "Part", default)]
+    parts: Vec<PlexPart>,
+}
+
+#[derive(Deserialize)]
+struct PlexPart {
+    file: PathBuf,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_played_paths() {
+        let jellyfin = r#"{
+            "Items": [
+                { "Name": "Episode 1", "Path": "/media/anime/Series/01.mkv" },
+                { "Name": "Virtual Episode" }
+            ],
+            "TotalRecordCount": 2
+        }"#;
+
+        let plex = r#"{
+            "MediaContainer": {
+                "size": 2,
+                "Metadata": [
+                    {
+                        "title": "Episode 1",
+                        "viewCount": 1,
+                        "Media": [{ "Part": [{ "file": "/media/anime/Series/01.mkv" }] }]
+                    },
+                    {
+                        "title": "Episode 2",
+                        "Media": [{ "Part": [{ "file": "/media/anime/Series/02.mkv" }] }]
+                    }
+                ]
+            }
+        }"#;
+
+        let expected = vec![PathBuf::from("/media/anime/Series/01.mkv")];
+
+        let jellyfin: JellyfinItems = serde_json::from_str(jellyfin).unwrap();
+        assert_eq!(jellyfin.paths(), expected);
+
+        let plex: PlexItems = serde_json::from_str(plex).unwrap();
+        assert_eq!(plex.played_paths().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn translate_server_paths() {
+        let local_dir = Path::new("/home/user/anime");
+        let server_dir = Some(Path::new("/media/anime"));
+
+        assert_eq!(
+            local_path("/media/anime/Series/01.mkv".into(), server_dir, local_dir),
+            PathBuf::from("/home/user/anime/Series/01.mkv")
+        );
+
+        assert_eq!(
+            local_path("/other/Series/01.mkv".into(), server_dir, local_dir),
+            PathBuf::from("/other/Series/01.mkv")
+        );
+
+        assert_eq!(
+            local_path("/home/user/anime/Series/01.mkv".into(), None, local_dir),
+            PathBuf::from("/home/user/anime/Series/01.mkv")
+        );
+    }
+}
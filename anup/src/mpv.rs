@@ -0,0 +1,197 @@
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::ffi::OsString;
+use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::UnixStream;
+use tokio::time;
+
+/// Returns true if `player` refers to mpv.
+pub fn is_mpv(player: &str) -> bool {
+    Path::new(player)
+        .file_stem()
+        .map_or(false, |name| name == "mpv")
+}
+
+/// The location of the JSON IPC socket used to talk to a single mpv instance.
+///
+/// The socket file is removed when this is dropped.
+pub struct IpcSocket {
+    path: PathBuf,
+}
+
+impl IpcSocket {
+    /// How long to wait for mpv to respond to a single request before giving up on it.
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let mut path = std::env::temp_dir();
+        path.push(format!("anup-mpv-{}-{}.sock", std::process::id(), id));
+
+        Self { path }
+    }
+
+    /// Returns the argument that tells mpv to create its IPC server at this socket.
+    pub fn player_arg(&self) -> OsString {
+        let mut arg = OsString::from("--input-ipc-server=");
+        arg.push(&self.path);
+        arg
+    }
+
+    /// Connect to the socket.
+    ///
+    /// This will fail until mpv has finished starting up, so it may need to be retried.
+    pub async fn connect(&self) -> Result<Connection> {
+        let stream = UnixStream::connect(&self.path)
+            .await
+            .context("connecting to mpv IPC socket")?;
+
+        let (reader, writer) = stream.into_split();
+
+        Ok(Connection {
+            reader: BufReader::new(reader),
+            writer,
+            next_request_id: 0,
+            messages: Vec::new(),
+        })
+    }
+}
+
+impl Drop for IpcSocket {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+pub struct Connection {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+    next_request_id: u64,
+    /// The arguments of every `script-message` mpv has sent us that hasn't been taken yet.
+    messages: Vec<Vec<String>>,
+}
+
+impl Connection {
+    /// Get the current value of the specified mpv `property`.
+    ///
+    /// Returns None if the property is currently unavailable, which happens with properties
+    /// like `playback-time` while a file is still being loaded.
+    pub async fn get_property<T>(&mut self, property: &str) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self.request(json!(["get_property", property])).await?;
+
+        match response.get("error").and_then(Value::as_str) {
+            Some("success") => (),
+            Some("property unavailable") => return Ok(None),
+            Some(err) => return Err(anyhow!("mpv returned an error: {}", err)),
+            None => return Err(anyhow!("mpv response is missing an error status")),
+        }
+
+        let data = response
+            .get("data")
+            .cloned()
+            .ok_or_else(|| anyhow!("mpv response is missing data"))?;
+
+        serde_json::from_value(data)
+            .map(Some)
+            .with_context(|| anyhow!("mpv returned an invalid value for {}", property))
+    }
+
+    /// Run an mpv input `command`, such as `["seek", 90, "absolute"]`.
+    pub async fn command(&mut self, command: Value) -> Result<()> {
+        let response = self.request(command).await?;
+
+        match response.get("error").and_then(Value::as_str) {
+            Some("success") => Ok(()),
+            Some(err) => Err(anyhow!("mpv returned an error: {}", err)),
+            None => Err(anyhow!("mpv response is missing an error status")),
+        }
+    }
+
+    /// Returns the arguments of every `script-message` mpv has sent since the last time this was called.
+    ///
+    /// Messages are only received while waiting for the response to another request.
+    pub fn take_messages(&mut self) -> Vec<Vec<String>> {
+        mem::take(&mut self.messages)
+    }
+
+    async fn request(&mut self, command: Value) -> Result<Value> {
+        time::timeout(IpcSocket::REQUEST_TIMEOUT, self.send_command(command))
+            .await
+            .context("mpv did not respond in time")?
+    }
+
+    async fn send_command(&mut self, command: Value) -> Result<Value> {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+
+        let mut request = json!({ "command": command, "request_id": request_id }).to_string();
+        request.push('\n');
+
+        self.writer
+            .write_all(request.as_bytes())
+            .await
+            .context("sending mpv command")?;
+
+        let mut line = String::new();
+
+        // mpv sends events over the same socket, so anything that isn't the response to our request needs to be skipped
+        loop {
+            line.clear();
+
+            let read = self
+                .reader
+                .read_line(&mut line)
+                .await
+                .context("reading mpv response")?;
+
+            if read == 0 {
+                return Err(anyhow!("mpv closed the IPC connection"));
+            }
+
+            let response: Value = match serde_json::from_str(&line) {
+                Ok(response) => response,
+                Err(_) => continue,
+            };
+
+            if response.get("request_id").and_then(Value::as_u64) == Some(request_id) {
+                return Ok(response);
+            }
+
+            if response.get("event").and_then(Value::as_str) == Some("client-message") {
+                let args = response
+                    .get("args")
+                    .cloned()
+                    .and_then(|args| serde_json::from_value(args).ok());
+
+                if let Some(args) = args {
+                    self.messages.push(args);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_mpv() {
+        assert!(is_mpv("mpv"));
+        assert!(is_mpv("/usr/bin/mpv"));
+        assert!(!is_mpv("vlc"));
+        assert!(!is_mpv("/usr/bin/mpv-wrapper"));
+    }
+}
@@ -1,22 +1,200 @@
-use anime::remote::{AccessToken, Remote};
-use anyhow::{anyhow, Result};
+use crate::config::Config;
+use crate::file::{FileFormat, SaveDir, SerializedFile};
+use crate::user::{RemoteType, UserInfo};
+use anime::remote::multi::MultiRemote;
+use anime::remote::offline::Offline;
+use anime::remote::{anilist, kitsu, mal, AccessToken, Remote};
+use anyhow::{anyhow, Context, Error, Result};
+use serde_derive::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
 
 pub type Username = String;
 
 pub enum RemoteLogin {
     AniList(Username, AccessToken),
+    Kitsu(Username, AccessToken),
+    Mal(Username, AccessToken),
+}
+
+impl RemoteLogin {
+    /// Create a login for the service that `user` is registered on.
+    pub fn new(user: UserInfo, token: AccessToken) -> Self {
+        match user.service {
+            RemoteType::AniList => Self::AniList(user.username, token),
+            RemoteType::Kitsu => Self::Kitsu(user.username, token),
+            RemoteType::Mal => Self::Mal(user.username, token),
+        }
+    }
+
+    pub fn username(&self) -> &str {
+        match self {
+            Self::AniList(username, _) | Self::Kitsu(username, _) | Self::Mal(username, _) => {
+                username
+            }
+        }
+    }
+
+    /// Create the client needed to log in to the remote service with the settings from `config`.
+    pub fn prepare(self, config: &Config) -> Result<PreparedLogin> {
+        let (username, client) = match self {
+            Self::AniList(username, token) => (
+                username,
+                LoginClient::AniList(token, config.anilist_client()?),
+            ),
+            Self::Kitsu(username, token) => {
+                (username, LoginClient::Kitsu(token, config.kitsu_client()?))
+            }
+            Self::Mal(username, token) => (username, LoginClient::Mal(token, config.mal_client()?)),
+        };
+
+        Ok(PreparedLogin { username, client })
+    }
+}
+
+/// A login that has everything it needs to connect to its remote service.
+pub struct PreparedLogin {
+    username: Username,
+    client: LoginClient,
+}
+
+impl PreparedLogin {
+    /// Call `hook` with how long requests have to wait whenever they're held back by the remote service's rate limit.
+    ///
+    /// Only AniList reports its rate limit, so this does nothing for other services.
+    pub fn set_rate_limit_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(Duration) + Send + Sync + 'static,
+    {
+        if let LoginClient::AniList(_, client) = &mut self.client {
+            client.set_rate_limit_hook(hook);
+        }
+    }
+
+    /// Log in to the remote service.
+    pub async fn login(self) -> Result<Remote> {
+        let remote = match self.client {
+            LoginClient::AniList(token, client) => anilist::Auth::retrieve(token, client)
+                .await
+                .map(|auth| anilist::AniList::Authenticated(auth).into()),
+            LoginClient::Kitsu(token, client) => kitsu::Auth::retrieve(token, client)
+                .await
+                .map(|auth| kitsu::Kitsu::Authenticated(auth).into()),
+            LoginClient::Mal(token, client) => mal::Auth::retrieve(token, client)
+                .await
+                .map(|auth| mal::Mal::Authenticated(auth).into()),
+        };
+
+        let username = self.username;
+        remote.with_context(|| anyhow!("failed to log in as {}", username))
+    }
+}
+
+enum LoginClient {
+    AniList(AccessToken, anilist::Client),
+    Kitsu(AccessToken, kitsu::Client),
+    Mal(AccessToken, mal::Client),
+}
+
+/// Log in to the `primary` remote service, along with every `linked` one.
+///
+/// If any linked service can be logged in to, the returned remote will send list updates to all of them.
+/// Linked services that fail to log in are skipped and have their error returned instead.
+pub async fn login_linked(
+    primary: PreparedLogin,
+    linked: Vec<Result<PreparedLogin>>,
+) -> Result<(Remote, Vec<Error>)> {
+    let primary = primary.login().await?;
+
+    let mut remotes = Vec::with_capacity(linked.len());
+    let mut errors = Vec::new();
+
+    if let Err(err) = LastScoreFormat::update(&primary) {
+        errors.push(err.context("failed to save the score format of the user"));
+    }
+
+    for login in linked {
+        let result = match login {
+            Ok(login) => login.login().await,
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(remote) => remotes.push(remote),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    let remote = if remotes.is_empty() {
+        primary
+    } else {
+        MultiRemote::new(primary, remotes).into()
+    };
+
+    Ok((remote, errors))
+}
+
+/// The score format of the last AniList user that was logged in to, so scores can be parsed and shown the same way while offline.
+#[derive(Default, Deserialize, Serialize, PartialEq)]
+pub struct LastScoreFormat(Option<anilist::ScoreFormat>);
+
+impl LastScoreFormat {
+    /// Remember the score format of the user logged in to `remote`.
+    ///
+    /// Users of services other than AniList have nothing to remember, so any previous format is forgotten.
+    pub fn update(remote: &Remote) -> Result<()> {
+        let format = match remote {
+            Remote::AniList(anilist) => Self(Some(anilist.score_format())),
+            _ => Self(None),
+        };
+
+        match Self::load() {
+            Ok(last) if last == format => Ok(()),
+            _ => format.save(),
+        }
+    }
+
+    /// Have `offline` parse and show scores with the remembered format, if there is one.
+    pub fn apply(self, offline: Offline) -> Offline {
+        match self.0 {
+            Some(format) => offline.with_score_parser(format),
+            None => offline,
+        }
+    }
+}
+
+impl SerializedFile for LastScoreFormat {
+    fn filename() -> &'static str {
+        "score_format"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Binary
+    }
 }
 
 pub enum RemoteStatus {
     LoggingIn(Username),
-    LoggedIn(Remote),
+    LoggedIn(Arc<Remote>),
 }
 
 impl RemoteStatus {
     pub fn get_logged_in(&self) -> Result<&Remote> {
         match self {
             Self::LoggingIn(name) => Err(anyhow!("currently logging in as {}", name)),
-            Self::LoggedIn(remote) => Ok(remote),
+            Self::LoggedIn(remote) => Ok(remote.as_ref()),
+        }
+    }
+
+    /// Returns a shared handle to the remote service that can be moved into a background task.
+    pub fn get_logged_in_shared(&self) -> Result<Arc<Remote>> {
+        match self {
+            Self::LoggingIn(name) => Err(anyhow!("currently logging in as {}", name)),
+            Self::LoggedIn(remote) => Ok(Arc::clone(remote)),
         }
     }
 }
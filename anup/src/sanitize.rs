@@ -0,0 +1,130 @@
+use crate::config::FilenameConfig;
+
+/// Characters that can't be used in filenames on Windows, or on filesystems like exFAT and FAT32.
+const INVALID_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Names that Windows reserves for devices, which can't be used as a filename even when followed by an extension.
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+#[inline]
+fn is_invalid(ch: char) -> bool {
+    INVALID_CHARS.contains(&ch) || ch.is_control()
+}
+
+/// Returns `name` in a form that can be used as a filename on every platform.
+///
+/// Characters with an entry in the `replacements` of `config` are replaced with it, and every other invalid character is replaced
+/// with its `replacement`. Repeated spaces are collapsed, trailing dots and spaces are removed as Windows doesn't allow them,
+/// and names reserved by Windows have an underscore added to them.
+pub fn filename(name: &str, config: &FilenameConfig) -> String {
+    let mut replaced = String::with_capacity(name.len());
+    let mut buffer = [0; 4];
+
+    for ch in name.chars() {
+        let replacement = match config.replacements.get(&*ch.encode_utf8(&mut buffer)) {
+            Some(replacement) => replacement.as_str(),
+            None if is_invalid(ch) => config.replacement.as_str(),
+            None => {
+                replaced.push(ch);
+                continue;
+            }
+        };
+
+        // Replacements are configurable, so they can't be trusted to be valid themselves
+        replaced.extend(replacement.chars().filter(|&ch| !is_invalid(ch)));
+    }
+
+    let mut result = String::with_capacity(replaced.len());
+
+    for ch in replaced.chars() {
+        if ch == ' ' && (result.is_empty() || result.ends_with(' ')) {
+            continue;
+        }
+
+        result.push(ch);
+    }
+
+    let trimmed_len = result.trim_end_matches(&[' ', '.'][..]).len();
+    result.truncate(trimmed_len);
+
+    let stem_len = result.find('.').unwrap_or(result.len());
+
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(&result[..stem_len]))
+    {
+        result.insert(stem_len, '_');
+    }
+
+    if result.is_empty() {
+        result.push('_');
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_chars_are_replaced() {
+        let config = FilenameConfig::default();
+
+        assert_eq!(
+            filename("Kaguya-sama: Love is War", &config),
+            "Kaguya-sama - Love is War"
+        );
+
+        assert_eq!(filename("Fate/Zero", &config), "Fate Zero");
+        assert_eq!(filename("Why? <Because>", &config), "Why Because");
+        assert_eq!(
+            filename("Title\twith\ncontrols", &config),
+            "Titlewithcontrols"
+        );
+        assert_eq!(
+            filename("Series Title - 01.mkv", &config),
+            "Series Title - 01.mkv"
+        );
+    }
+
+    #[test]
+    fn custom_replacements() {
+        let mut config = FilenameConfig {
+            replacement: "_".into(),
+            ..FilenameConfig::default()
+        };
+
+        config.replacements.insert("?".into(), "？".into());
+        config.replacements.insert(":".into(), "|".into());
+        config.replacements.insert("!".into(), String::new());
+
+        assert_eq!(filename("Why? Really!", &config), "Why？ Really");
+        assert_eq!(filename("A*B", &config), "A_B");
+        // Invalid characters in a replacement are removed instead of being written out
+        assert_eq!(filename("Re:Zero", &config), "ReZero");
+    }
+
+    #[test]
+    fn trailing_dots_and_spaces_are_removed() {
+        let config = FilenameConfig::default();
+
+        assert_eq!(filename("Title...", &config), "Title");
+        assert_eq!(filename("  Title  . ", &config), "Title");
+        assert_eq!(filename("???", &config), "_");
+    }
+
+    #[test]
+    fn reserved_names() {
+        let config = FilenameConfig::default();
+
+        assert_eq!(filename("CON", &config), "CON_");
+        assert_eq!(filename("con.mkv", &config), "con_.mkv");
+        assert_eq!(filename("Lpt1", &config), "Lpt1_");
+        assert_eq!(filename("COM10", &config), "COM10");
+        assert_eq!(filename("Console", &config), "Console");
+    }
+}
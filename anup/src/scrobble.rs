@@ -0,0 +1,407 @@
+use crate::config::Config;
+use crate::database::Database;
+use crate::series::config::SeriesConfig;
+use crate::series::{SeriesData, WatchStart};
+use anime::remote::Remote;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::str;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time;
+
+/// The path scrobble events are sent to.
+const SCROBBLE_PATH: &str = "/scrobble";
+
+/// The largest request head that will be read, which is far more than any webhook plugin should send.
+const MAX_HEAD_LEN: usize = 16 * 1024;
+
+/// The largest request body that will be read.
+const MAX_BODY_LEN: usize = 64 * 1024;
+
+/// How long a client has to send its request before the connection is dropped.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The minimum similarity a title needs with one of the names of a series to be matched to it.
+const MIN_CONFIDENCE: f32 = 0.85;
+
+/// A playback event sent by a media player, or a webhook plugin of a media server.
+#[derive(Debug, Deserialize)]
+pub struct ScrobbleEvent {
+    /// The ID of the series on the remote service. Takes priority over `title` when set.
+    #[serde(default)]
+    pub id: Option<i32>,
+    /// The title of the series, which is matched against the titles, nickname, and aliases of every added series.
+    #[serde(default)]
+    pub title: Option<String>,
+    pub episode: u32,
+    pub state: ScrobbleState,
+    /// How much of the episode was watched, as a percentage.
+    #[serde(default)]
+    pub progress: Option<f32>,
+}
+
+impl ScrobbleEvent {
+    /// Returns true if the episode of the event should be counted as watched.
+    ///
+    /// Stopped episodes need to have been watched as far as an episode played from anup would need to be.
+    fn counts_as_watched(&self, config: &Config) -> bool {
+        match self.state {
+            ScrobbleState::Finished => true,
+            ScrobbleState::Stop => self.progress.map_or(false, |progress| {
+                progress / 100.0 >= config.episode.pcnt_must_watch.as_multiplier()
+            }),
+            ScrobbleState::Start | ScrobbleState::Pause => false,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrobbleState {
+    Start,
+    Pause,
+    Stop,
+    Finished,
+}
+
+/// Listen for scrobble events on `address` until the program is stopped, applying each one to the series it belongs to.
+///
+/// Requests are handled one at a time, so events for the same series can never race each other.
+pub async fn serve(address: &str, remote: &Remote, config: &Config, db: &Database) -> Result<()> {
+    let listener = TcpListener::bind(address)
+        .await
+        .with_context(|| format!("failed to listen on {}", address))?;
+
+    println!(
+        "listening for scrobble events on http://{}{}",
+        listener.local_addr()?,
+        SCROBBLE_PATH
+    );
+
+    loop {
+        let mut stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                eprintln!("warning: failed to accept connection: {}", err);
+                continue;
+            }
+        };
+
+        if let Err(err) = handle_connection(&mut stream, remote, config, db).await {
+            eprintln!("warning: {:#}", err);
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: &mut TcpStream,
+    remote: &Remote,
+    config: &Config,
+    db: &Database,
+) -> Result<()> {
+    let response = match time::timeout(REQUEST_TIMEOUT, read_request(stream)).await {
+        Ok(Ok(request)) => handle_request(&request, remote, config, db).await,
+        Ok(Err(err)) => Response::new(400, format!("{:#}", err)),
+        Err(_) => Response::new(408, "timed out waiting for the request"),
+    };
+
+    if response.code == 200 {
+        println!("{}", response.message);
+    } else {
+        eprintln!("{}: {}", response.code, response.message);
+    }
+
+    response
+        .write(stream)
+        .await
+        .context("failed to send response")
+}
+
+async fn handle_request(
+    request: &Request,
+    remote: &Remote,
+    config: &Config,
+    db: &Database,
+) -> Response {
+    if request.path != SCROBBLE_PATH {
+        return Response::new(404, format!("nothing is served at {}", request.path));
+    }
+
+    if request.method != "POST" {
+        return Response::new(405, "scrobble events must be sent with POST");
+    }
+
+    let event = match serde_json::from_slice::<ScrobbleEvent>(&request.body) {
+        Ok(event) => event,
+        Err(err) => return Response::new(400, format!("invalid scrobble event: {}", err)),
+    };
+
+    match scrobble(&event, remote, config, db).await {
+        Ok(Some(message)) => Response::new(200, message),
+        Ok(None) => Response::new(404, "no added series matches the event"),
+        Err(err) => Response::new(500, format!("{:#}", err)),
+    }
+}
+
+/// Apply `event` to the series it belongs to, and sync the changes to `remote`.
+///
+/// Returns a message describing what was done, or None if no series matches the event.
+async fn scrobble(
+    event: &ScrobbleEvent,
+    remote: &Remote,
+    config: &Config,
+    db: &Database,
+) -> Result<Option<String>> {
+    let mut series = SeriesConfig::load_all(db)?
+        .into_iter()
+        .map(|cfg| SeriesData::load_from_config(db, Cow::Owned(cfg)))
+        .collect::<diesel::QueryResult<Vec<_>>>()
+        .context("failed to load series")?;
+
+    let data = match find_series(event, &series) {
+        Some(index) => &mut series[index],
+        None => return Ok(None),
+    };
+
+    let title = data.info.title_preferred.clone();
+    let watched = data
+        .config
+        .local_rewatch
+        .unwrap_or_else(|| data.entry.watched_episodes())
+        .max(0) as u32;
+
+    let episode = i16::try_from(event.episode)
+        .map_err(|_| anyhow!("episode {} is out of range", event.episode))?;
+
+    if event.state == ScrobbleState::Start {
+        // Only the next episode is considered, as replaying an old episode of a completed series would start a rewatch
+        if event.episode != watched + 1 || data.config.local_rewatch.is_some() {
+            return Ok(Some(format!("{} episode {} started", title, episode)));
+        }
+
+        data.entry.sync_from_remote(remote).await?;
+        data.entry
+            .begin_watching(WatchStart::UpdateStatus, data.info.episodes, config);
+        data.entry.sync_to_remote(remote).await?;
+        data.save(db)?;
+
+        return Ok(Some(format!(
+            "{} episode {} started, status is now {}",
+            title,
+            episode,
+            data.entry.status()
+        )));
+    }
+
+    if !event.counts_as_watched(config) {
+        return Ok(Some(format!(
+            "{} episode {} was not watched long enough to count",
+            title, episode
+        )));
+    }
+
+    if event.episode <= watched {
+        return Ok(Some(format!(
+            "{} episode {} was already watched",
+            title, episode
+        )));
+    }
+
+    data.entry.sync_from_remote(remote).await?;
+    data.set_progress(episode, config, db)?;
+    data.entry.sync_to_remote(remote).await?;
+    data.save(db)?;
+
+    Ok(Some(format!(
+        "{} episode {} marked as watched",
+        title, episode
+    )))
+}
+
+/// Returns the index of the series in `series` that `event` belongs to.
+///
+/// Series are matched by their ID when the event has one, and by the closest of their names to its title otherwise.
+fn find_series(event: &ScrobbleEvent, series: &[SeriesData]) -> Option<usize> {
+    if let Some(id) = event.id {
+        return series.iter().position(|data| data.config.id == id);
+    }
+
+    let title = event.title.as_deref()?.trim().to_lowercase();
+
+    anime::closest_match(series, MIN_CONFIDENCE, |data| {
+        let names = [
+            &data.info.title_preferred,
+            &data.info.title_romaji,
+            &data.config.nickname,
+        ];
+
+        names
+            .iter()
+            .copied()
+            .chain(&data.aliases)
+            .map(|name| strsim::jaro(&name.to_lowercase(), &title) as f32)
+            .reduce(f32::max)
+    })
+    .map(|(index, _)| index)
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request> {
+    let mut buffer = Vec::with_capacity(1024);
+
+    let head_len = loop {
+        if let Some(pos) = buffer.windows(4).position(|bytes| bytes == b"\r\n\r\n") {
+            break pos;
+        }
+
+        if buffer.len() > MAX_HEAD_LEN {
+            return Err(anyhow!("request headers are too large"));
+        }
+
+        if stream.read_buf(&mut buffer).await? == 0 {
+            return Err(anyhow!("connection closed before the request was sent"));
+        }
+    };
+
+    let head = str::from_utf8(&buffer[..head_len]).context("request is not valid UTF-8")?;
+    let (method, path, content_len) = parse_head(head)?;
+    let (method, path) = (method.to_string(), path.to_string());
+
+    if content_len > MAX_BODY_LEN {
+        return Err(anyhow!("request body is too large"));
+    }
+
+    let mut body = buffer.split_off(head_len + 4);
+    body.truncate(content_len);
+
+    if body.len() < content_len {
+        let start = body.len();
+        body.resize(content_len, 0);
+        stream.read_exact(&mut body[start..]).await?;
+    }
+
+    Ok(Request { method, path, body })
+}
+
+/// Parse the request line and headers of an HTTP request.
+///
+/// Returns the method, the path without its query string, and the length of the body.
+fn parse_head(head: &str) -> Result<(&str, &str, usize)> {
+    let mut lines = head.split("\r\n");
+
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+
+    let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+            (method, target)
+        }
+        _ => return Err(anyhow!("malformed request line: {}", request_line)),
+    };
+
+    let path = target.split('?').next().unwrap_or(target);
+    let mut content_len = 0;
+
+    for line in lines {
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| anyhow!("malformed header: {}", line))?;
+
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            content_len = value
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid content length: {}", value.trim()))?;
+        }
+    }
+
+    Ok((method, path, content_len))
+}
+
+struct Response {
+    code: u16,
+    message: String,
+}
+
+impl Response {
+    fn new<S>(code: u16, message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    async fn write(&self, stream: &mut TcpStream) -> Result<()> {
+        let reason = match self.code {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            _ => "Internal Server Error",
+        };
+
+        let body = serde_json::json!({ "message": self.message }).to_string();
+
+        let response = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.code,
+            reason,
+            body.len(),
+            body
+        );
+
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_head_is_parsed() {
+        let head = "POST /scrobble?source=mpv HTTP/1.1\r\nHost: localhost\r\ncontent-length: 42";
+        let (method, path, content_len) = parse_head(head).unwrap();
+
+        assert_eq!(method, "POST");
+        assert_eq!(path, "/scrobble");
+        assert_eq!(content_len, 42);
+
+        assert!(parse_head("GET /scrobble").is_err());
+        assert!(parse_head("POST /scrobble HTTP/1.1\r\nContent-Length: abc").is_err());
+    }
+
+    #[test]
+    fn stopped_episodes_need_enough_progress() {
+        let config = Config::default();
+
+        let event = |state, progress| ScrobbleEvent {
+            id: None,
+            title: Some("Series Title".into()),
+            episode: 1,
+            state,
+            progress,
+        };
+
+        assert!(event(ScrobbleState::Finished, None).counts_as_watched(&config));
+        assert!(event(ScrobbleState::Stop, Some(95.0)).counts_as_watched(&config));
+        assert!(!event(ScrobbleState::Stop, Some(10.0)).counts_as_watched(&config));
+        assert!(!event(ScrobbleState::Stop, None).counts_as_watched(&config));
+        assert!(!event(ScrobbleState::Start, Some(100.0)).counts_as_watched(&config));
+    }
+}
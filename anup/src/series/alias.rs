@@ -0,0 +1,80 @@
+use crate::database::schema::{series_aliases, series_configs};
+use crate::database::Database;
+use anyhow::{anyhow, Result};
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[table_name = "series_aliases"]
+struct SeriesAlias<'a> {
+    alias: &'a str,
+    series_id: i32,
+}
+
+/// Returns every alias of the series with `series_id`.
+pub fn load(db: &Database, series_id: i32) -> diesel::QueryResult<Vec<String>> {
+    use crate::database::schema::series_aliases::dsl;
+
+    dsl::series_aliases
+        .filter(dsl::series_id.eq(series_id))
+        .select(dsl::alias)
+        .order(dsl::alias)
+        .load(db.conn())
+}
+
+/// Replace every alias of the series with `series_id` with `aliases`.
+pub fn replace(db: &Database, series_id: i32, aliases: &[String]) -> diesel::QueryResult<()> {
+    use crate::database::schema::series_aliases::dsl;
+
+    diesel::delete(dsl::series_aliases.filter(dsl::series_id.eq(series_id))).execute(db.conn())?;
+
+    if aliases.is_empty() {
+        return Ok(());
+    }
+
+    let rows = aliases
+        .iter()
+        .map(|alias| SeriesAlias { alias, series_id })
+        .collect::<Vec<_>>();
+
+    diesel::insert_into(dsl::series_aliases)
+        .values(&rows)
+        .execute(db.conn())?;
+
+    Ok(())
+}
+
+/// Returns the ID of the series that has `alias`, if there is one.
+pub fn series_id(db: &Database, alias: &str) -> diesel::QueryResult<Option<i32>> {
+    use crate::database::schema::series_aliases::dsl;
+
+    dsl::series_aliases
+        .filter(dsl::alias.eq(alias))
+        .select(dsl::series_id)
+        .get_result(db.conn())
+        .optional()
+}
+
+/// Returns `alias` with surrounding whitespace removed if no series already goes by it, either as its nickname or as an alias.
+pub fn validate<'a>(db: &Database, alias: &'a str) -> Result<&'a str> {
+    let alias = alias.trim();
+
+    if alias.is_empty() {
+        return Err(anyhow!("alias can't be empty"));
+    }
+
+    let nickname_taken = series_configs::table
+        .filter(series_configs::nickname.eq(alias))
+        .select(series_configs::nickname)
+        .get_result::<String>(db.conn())
+        .optional()?;
+
+    if let Some(nickname) = nickname_taken {
+        return Err(anyhow!("{} is already the nickname of a series", nickname));
+    }
+
+    if series_id(db, alias)?.is_some() {
+        return Err(anyhow!("{} is already the alias of a series", alias));
+    }
+
+    Ok(alias)
+}
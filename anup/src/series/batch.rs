@@ -0,0 +1,211 @@
+use super::config::SeriesConfig;
+use super::info::{Candidate, InfoResult, InfoSelector, MatchOptions, SeriesInfo};
+use super::{SeriesParams, SeriesPath};
+use crate::config::Config;
+use crate::database::Database;
+use crate::file;
+use anime::local::EpisodeParser;
+use anime::remote::Remote;
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
+
+/// A folder in the series directory that no series has been added for yet.
+pub struct FolderMatch {
+    pub path: SeriesPath,
+    /// The title detected from the name of the folder.
+    pub title: String,
+    /// Every series found for the title, from the closest match to the furthest.
+    pub candidates: Vec<Candidate>,
+    /// The index of the candidate to add the folder as, or None if the folder should be skipped.
+    pub selected: Option<usize>,
+}
+
+impl FolderMatch {
+    /// Detect the title of the folder at `path` and look it up on `remote`.
+    ///
+    /// The closest match is only selected when it's confident enough for `options`, so folders that can't be identified are skipped unless the user picks a series for them.
+    pub async fn find(path: SeriesPath, remote: &Remote, options: MatchOptions) -> Result<Self> {
+        let name = path
+            .inner()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        match InfoSelector::from_path_or_name(&path, name) {
+            InfoSelector::Name(title) => {
+                let candidates = SeriesInfo::search_ranked(title.as_str(), remote, options).await?;
+                Ok(Self::ranked(
+                    path,
+                    title,
+                    candidates,
+                    options.min_confidence,
+                ))
+            }
+            InfoSelector::Season(title, season) => {
+                // Later seasons are found by following sequels from a confident match, so the season found is treated as certain even though its title won't match as closely
+                match SeriesInfo::from_remote_by_season(title.as_str(), season, remote, options)
+                    .await?
+                {
+                    InfoResult::Confident(info) => Ok(Self {
+                        candidates: vec![Candidate {
+                            info,
+                            confidence: 1.0,
+                        }],
+                        selected: Some(0),
+                        path,
+                        title,
+                    }),
+                    InfoResult::Unconfident(candidates) => Ok(Self::ranked(
+                        path,
+                        title,
+                        candidates,
+                        options.min_confidence,
+                    )),
+                }
+            }
+            InfoSelector::ID(_) => unreachable!(),
+        }
+    }
+
+    /// Create a match from `candidates` that are ordered from the closest match to the furthest,
+    /// selecting the first one if its confidence is at least `min_confidence`.
+    fn ranked(
+        path: SeriesPath,
+        title: String,
+        candidates: Vec<Candidate>,
+        min_confidence: f32,
+    ) -> Self {
+        let selected = candidates
+            .first()
+            .filter(|best| best.confidence >= min_confidence)
+            .map(|_| 0);
+
+        Self {
+            path,
+            title,
+            candidates,
+            selected,
+        }
+    }
+
+    #[inline(always)]
+    pub fn selected(&self) -> Option<&Candidate> {
+        self.selected.and_then(|index| self.candidates.get(index))
+    }
+
+    /// Select the next candidate, wrapping around to skipping the folder after the last one.
+    pub fn select_next(&mut self) {
+        self.selected = match self.selected {
+            Some(index) if index + 1 < self.candidates.len() => Some(index + 1),
+            Some(_) => None,
+            None if self.candidates.is_empty() => None,
+            None => Some(0),
+        };
+    }
+
+    /// Select the previous candidate, wrapping around to skipping the folder before the first one.
+    pub fn select_prev(&mut self) {
+        self.selected = match self.selected {
+            Some(0) => None,
+            Some(index) => Some(index - 1),
+            None => self.candidates.len().checked_sub(1),
+        };
+    }
+}
+
+/// Returns every folder in the series directory that isn't used by any series in `sconfigs`.
+pub fn new_folders<'a, I>(sconfigs: I, config: &Config) -> Result<Vec<SeriesPath>>
+where
+    I: IntoIterator<Item = &'a SeriesConfig>,
+{
+    let used = sconfigs
+        .into_iter()
+        .map(|sconfig| sconfig.path.inner())
+        .collect::<HashSet<_>>();
+
+    let mut folders = file::subdirectories(&config.series_dir)?
+        .into_iter()
+        .map(|dir| SeriesPath::new(dir.path(), config))
+        .filter(|path| !used.contains(path.inner()))
+        .collect::<Vec<_>>();
+
+    folders.sort_by(|x, y| x.inner().cmp(y.inner()));
+    Ok(folders)
+}
+
+/// Create the config of every folder in `matches` that has a series selected.
+///
+/// Series that have already been added, or that were selected for an earlier folder, result in an error for that folder.
+pub fn into_configs(
+    matches: Vec<FolderMatch>,
+    db: &Database,
+) -> Vec<Result<(SeriesConfig, SeriesInfo)>> {
+    let mut ids = HashSet::new();
+    let mut nicknames = HashSet::new();
+
+    matches
+        .into_iter()
+        .filter_map(|found| {
+            let index = found.selected?;
+            let info = found.candidates.into_iter().nth(index)?.info;
+
+            if !ids.insert(info.id) {
+                return Some(Err(anyhow!(
+                    "{} was already selected for another folder",
+                    info.title_preferred
+                )));
+            }
+
+            let mut nickname = super::generate_nickname(&info.title_preferred)
+                .unwrap_or_else(|| format!("series_{}", info.id));
+
+            // Different series can generate the same nickname
+            if nicknames.contains(&nickname) || SeriesConfig::load_by_name(db, &nickname).is_ok() {
+                nickname = format!("{}_{}", nickname, info.id);
+            }
+
+            nicknames.insert(nickname.clone());
+
+            let params = SeriesParams::new(nickname, found.path, EpisodeParser::default());
+            Some(SeriesConfig::new(info.id, params, db).map(|sconfig| (sconfig, info)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: i32, confidence: f32) -> Candidate {
+        let info = SeriesInfo {
+            id,
+            title_preferred: format!("series {}", id),
+            title_romaji: format!("series {}", id),
+            episodes: 12,
+            episode_length_mins: 24,
+            title_english: None,
+            title_native: None,
+        };
+
+        Candidate { info, confidence }
+    }
+
+    #[test]
+    fn closest_candidate_is_only_selected_when_confident() {
+        let candidates = vec![candidate(1, 0.9), candidate(2, 0.5)];
+        let found = FolderMatch::ranked(SeriesPath::unset(), "series".into(), candidates, 0.85);
+
+        assert_eq!(found.selected().map(|best| best.info.id), Some(1));
+
+        let candidates = vec![candidate(1, 0.7)];
+        let mut found = FolderMatch::ranked(SeriesPath::unset(), "series".into(), candidates, 0.85);
+
+        assert_eq!(found.selected, None);
+
+        found.select_next();
+        assert_eq!(found.selected, Some(0));
+
+        found.select_next();
+        assert_eq!(found.selected, None);
+    }
+}
@@ -0,0 +1,625 @@
+use super::config::SeriesConfig;
+use super::info::SeriesInfo;
+use crate::database::schema::{cached_airing, cached_info, cached_sequels};
+use crate::database::Database;
+use crate::file::SerializedFile;
+use crate::remote::LastScoreFormat;
+use anime::remote::offline::Offline;
+use anime::remote::{
+    AiredEpisode, AiringStatus, Remote, RemoteService, Sequel, SeriesID, SeriesInfo as RemoteInfo,
+    SeriesTitle,
+};
+use anime::SeriesKind;
+use anyhow::anyhow;
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+
+/// Series info from the remote service, stored so it can still be looked up while offline.
+#[derive(Queryable, Insertable)]
+#[table_name = "cached_info"]
+struct CachedInfo {
+    id: i32,
+    title_preferred: String,
+    title_romaji: String,
+    episodes: i32,
+    episode_length_mins: i32,
+    kind: SeriesKind,
+    airing: AiringStatus,
+    /// The UTC timestamp the info was retrieved from the remote service.
+    fetched_at: i64,
+    title_english: Option<String>,
+    title_native: Option<String>,
+    /// The synonyms of the series' title, separated by newlines.
+    title_synonyms: String,
+}
+
+#[derive(Queryable, Insertable)]
+#[table_name = "cached_sequels"]
+struct CachedSequel {
+    series_id: i32,
+    sequel_id: i32,
+    kind: SeriesKind,
+}
+
+/// The latest episode of a series known to have aired, according to the remote service's airing schedule.
+#[derive(Queryable, Insertable)]
+#[table_name = "cached_airing"]
+struct CachedAiring {
+    series_id: i32,
+    latest_episode: i32,
+    /// The UTC timestamp `latest_episode` aired at.
+    aired_at: i64,
+    /// The UTC timestamp the airing schedule of the series was last checked.
+    fetched_at: i64,
+}
+
+/// Which series should have their cached info refreshed.
+#[derive(Copy, Clone)]
+pub enum Refresh {
+    /// Every tracked series and their sequels.
+    All,
+    /// Series that have never been cached, or were cached longer ago than the specified duration.
+    OlderThan(Duration),
+    /// Series that have never been cached.
+    Uncached,
+}
+
+/// Store `info` in the cache, replacing anything previously cached for the series.
+///
+/// If the series is tracked, its stored info is also updated.
+#[allow(clippy::cast_possible_wrap)]
+pub fn store(db: &Database, info: &RemoteInfo) -> diesel::QueryResult<()> {
+    use crate::database::schema::cached_info::dsl::cached_info;
+    use crate::database::schema::cached_sequels::dsl::{cached_sequels, series_id};
+    use crate::database::schema::series_info::dsl::{id as info_id, series_info};
+
+    let id = info.id as i32;
+
+    let cached = CachedInfo {
+        id,
+        title_preferred: info.title.preferred.clone(),
+        title_romaji: info.title.romaji.clone(),
+        episodes: info.episodes as i32,
+        episode_length_mins: info.episode_length as i32,
+        kind: info.kind,
+        airing: info.airing,
+        fetched_at: Utc::now().timestamp(),
+        title_english: info.title.english.clone(),
+        title_native: info.title.native.clone(),
+        title_synonyms: info.title.synonyms.join("\n"),
+    };
+
+    db.transaction("cache series info", || {
+        diesel::replace_into(cached_info)
+            .values(&cached)
+            .execute(db.conn())?;
+
+        diesel::delete(cached_sequels.filter(series_id.eq(id))).execute(db.conn())?;
+
+        for sequel in &info.sequels {
+            let sequel = CachedSequel {
+                series_id: id,
+                sequel_id: sequel.id as i32,
+                kind: sequel.kind,
+            };
+
+            diesel::insert_into(cached_sequels)
+                .values(&sequel)
+                .execute(db.conn())?;
+        }
+
+        let is_tracked = series_info
+            .filter(info_id.eq(id))
+            .count()
+            .get_result::<i64>(db.conn())?
+            > 0;
+
+        if is_tracked {
+            SeriesInfo::from(info.clone()).save(db)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Load the info of every cached series.
+pub fn load_all(db: &Database) -> diesel::QueryResult<Vec<RemoteInfo>> {
+    use crate::database::schema::cached_info::dsl::cached_info;
+    use crate::database::schema::cached_sequels::dsl::cached_sequels;
+
+    let infos = cached_info.load::<CachedInfo>(db.conn())?;
+    let sequels = cached_sequels.load::<CachedSequel>(db.conn())?;
+
+    Ok(with_sequels(infos, sequels))
+}
+
+/// Load the cached info of every series in `ids` that was fetched at or after the `cutoff` UTC timestamp.
+#[allow(clippy::cast_possible_wrap)]
+fn load_fetched_since(
+    db: &Database,
+    ids: &[SeriesID],
+    cutoff: i64,
+) -> diesel::QueryResult<Vec<RemoteInfo>> {
+    use crate::database::schema::cached_info::dsl::{cached_info, fetched_at, id};
+    use crate::database::schema::cached_sequels::dsl::{cached_sequels, series_id};
+
+    let ids = ids.iter().map(|&sid| sid as i32).collect::<Vec<_>>();
+
+    let infos = cached_info
+        .filter(id.eq_any(&ids))
+        .filter(fetched_at.ge(cutoff))
+        .load::<CachedInfo>(db.conn())?;
+
+    let sequels = cached_sequels
+        .filter(series_id.eq_any(&ids))
+        .load::<CachedSequel>(db.conn())?;
+
+    Ok(with_sequels(infos, sequels))
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn with_sequels(infos: Vec<CachedInfo>, all_sequels: Vec<CachedSequel>) -> Vec<RemoteInfo> {
+    let mut sequels = HashMap::<i32, Vec<Sequel>>::new();
+
+    for sequel in all_sequels {
+        sequels
+            .entry(sequel.series_id)
+            .or_default()
+            .push(Sequel::new(sequel.kind, sequel.sequel_id as SeriesID));
+    }
+
+    infos
+        .into_iter()
+        .map(|info| RemoteInfo {
+            id: info.id as SeriesID,
+            title: SeriesTitle {
+                romaji: info.title_romaji,
+                preferred: info.title_preferred,
+                english: info.title_english,
+                native: info.title_native,
+                synonyms: info.title_synonyms.lines().map(str::to_string).collect(),
+            },
+            episodes: info.episodes as u32,
+            episode_length: info.episode_length_mins as u32,
+            kind: info.kind,
+            sequels: sequels.remove(&info.id).unwrap_or_default(),
+            airing: info.airing,
+        })
+        .collect()
+}
+
+/// Look up the info of every series in `ids`.
+///
+/// Info cached less than `max_age` ago is used as-is, while everything else is fetched from `remote` in as few requests as it allows and stored in the cache.
+/// While offline, cached info is used regardless of its age.
+/// Series that couldn't be found are left out of the results.
+pub async fn lookup<L, D>(
+    lock_db: L,
+    remote: &Remote,
+    ids: &[SeriesID],
+    max_age: Duration,
+) -> anyhow::Result<HashMap<SeriesID, RemoteInfo>>
+where
+    L: Fn() -> D,
+    D: Deref<Target = Database>,
+{
+    let cutoff = if remote.is_offline() {
+        i64::MIN
+    } else {
+        (Utc::now() - max_age).timestamp()
+    };
+
+    let mut found = load_fetched_since(&lock_db(), ids, cutoff)?
+        .into_iter()
+        .map(|info| (info.id, info))
+        .collect::<HashMap<_, _>>();
+
+    let missing = ids
+        .iter()
+        .copied()
+        .filter(|id| !found.contains_key(id))
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() || remote.is_offline() {
+        return Ok(found);
+    }
+
+    let fetched = remote.search_info_by_ids(&missing).await?;
+
+    {
+        let db = lock_db();
+
+        for info in &fetched {
+            store(&db, info)?;
+        }
+    }
+
+    found.extend(fetched.into_iter().map(|info| (info.id, info)));
+    Ok(found)
+}
+
+/// Look up the info of the series with the specified `id` the same way as [`lookup`].
+pub async fn lookup_by_id<L, D>(
+    lock_db: L,
+    remote: &Remote,
+    id: SeriesID,
+    max_age: Duration,
+) -> anyhow::Result<RemoteInfo>
+where
+    L: Fn() -> D,
+    D: Deref<Target = Database>,
+{
+    lookup(lock_db, remote, &[id], max_age)
+        .await?
+        .remove(&id)
+        .ok_or_else(|| anyhow!("no series with ID {} was found", id))
+}
+
+/// Returns the IDs of every tracked series and their known sequels that should be refreshed according to `refresh`.
+///
+/// Every later season of a tracked series is included, so seasons that may have been merged together can be resolved from the cache.
+#[allow(clippy::cast_sign_loss)]
+pub fn ids_needing_refresh(db: &Database, refresh: Refresh) -> diesel::QueryResult<Vec<SeriesID>> {
+    use crate::database::schema::cached_info::dsl::{cached_info, fetched_at, id};
+    use crate::database::schema::cached_sequels::dsl::cached_sequels;
+
+    let tracked = SeriesConfig::load_all(db)?
+        .into_iter()
+        .map(|config| config.id)
+        .collect::<Vec<_>>();
+
+    let mut sequels = HashMap::<i32, Vec<CachedSequel>>::new();
+
+    for sequel in cached_sequels.load::<CachedSequel>(db.conn())? {
+        sequels.entry(sequel.series_id).or_default().push(sequel);
+    }
+
+    let mut ids = tracked.iter().copied().collect::<HashSet<_>>();
+    let mut pending = tracked;
+
+    while let Some(current) = pending.pop() {
+        let current_sequels = match sequels.get(&current) {
+            Some(current_sequels) => current_sequels,
+            None => continue,
+        };
+
+        for sequel in current_sequels {
+            // Only seasons are followed further, as other kinds of sequels can link to entirely different franchises
+            if ids.insert(sequel.sequel_id) && sequel.kind == SeriesKind::Season {
+                pending.push(sequel.sequel_id);
+            }
+        }
+    }
+
+    let mut ids = ids.into_iter().collect::<Vec<_>>();
+    ids.sort_unstable();
+
+    let cutoff = match refresh {
+        Refresh::All => None,
+        Refresh::OlderThan(max_age) => Some((Utc::now() - max_age).timestamp()),
+        Refresh::Uncached => Some(i64::MIN),
+    };
+
+    if let Some(cutoff) = cutoff {
+        let fresh = cached_info
+            .filter(fetched_at.ge(cutoff))
+            .select(id)
+            .load::<i32>(db.conn())?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        ids.retain(|sid| !fresh.contains(sid));
+    }
+
+    Ok(ids.into_iter().map(|sid| sid as SeriesID).collect())
+}
+
+/// Returns the total number of episodes of every season that comes before the series with the specified `id`.
+///
+/// Earlier seasons are found by walking backwards through the cached sequels of each series,
+/// so seasons that have never been cached are not counted.
+#[allow(clippy::cast_sign_loss)]
+pub fn prequel_episodes(db: &Database, id: SeriesID) -> diesel::QueryResult<u32> {
+    use crate::database::schema::cached_info::dsl::{cached_info, episodes, id as info_id};
+    use crate::database::schema::cached_sequels::dsl::{
+        cached_sequels, kind, sequel_id, series_id,
+    };
+
+    let mut current = id as i32;
+    let mut visited = HashSet::new();
+    let mut total = 0;
+
+    while visited.insert(current) {
+        let prequel = cached_sequels
+            .filter(sequel_id.eq(current))
+            .filter(kind.eq(SeriesKind::Season))
+            .select(series_id)
+            .first::<i32>(db.conn())
+            .optional()?;
+
+        let prequel = match prequel {
+            Some(prequel) => prequel,
+            None => break,
+        };
+
+        total += cached_info
+            .filter(info_id.eq(prequel))
+            .select(episodes)
+            .first::<i32>(db.conn())? as u32;
+
+        current = prequel;
+    }
+
+    Ok(total)
+}
+
+/// Returns the ID of the first cached sequel of the series with the specified `id` that is the same kind of series, such as the next season of a TV series.
+///
+/// None is returned if the series or its sequels have never been cached.
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+pub fn direct_sequel(db: &Database, id: SeriesID) -> diesel::QueryResult<Option<SeriesID>> {
+    use crate::database::schema::cached_info::dsl::{
+        cached_info, id as info_id, kind as info_kind,
+    };
+    use crate::database::schema::cached_sequels::dsl::{
+        cached_sequels, kind, sequel_id, series_id,
+    };
+
+    let series_kind = cached_info
+        .filter(info_id.eq(id as i32))
+        .select(info_kind)
+        .first::<SeriesKind>(db.conn())
+        .optional()?;
+
+    let series_kind = match series_kind {
+        Some(series_kind) => series_kind,
+        None => return Ok(None),
+    };
+
+    cached_sequels
+        .filter(series_id.eq(id as i32))
+        .filter(kind.eq(series_kind))
+        .select(sequel_id)
+        .first::<i32>(db.conn())
+        .optional()
+        .map(|sequel| sequel.map(|sequel| sequel as SeriesID))
+}
+
+/// Store the latest episode in `aired` of every series in `ids`, as of the `fetched_at` UTC timestamp.
+///
+/// A series keeps its previously cached episode if it's later than any in `aired`, so a short airing schedule doesn't lose track of earlier checks.
+#[allow(clippy::cast_possible_wrap)]
+pub fn store_aired(
+    db: &Database,
+    ids: &[SeriesID],
+    aired: &[AiredEpisode],
+    fetched_at: i64,
+) -> diesel::QueryResult<()> {
+    use crate::database::schema::cached_airing::dsl::{cached_airing, series_id};
+
+    let sids = ids.iter().map(|&id| id as i32).collect::<Vec<_>>();
+
+    let mut latest = cached_airing
+        .filter(series_id.eq_any(&sids))
+        .load::<CachedAiring>(db.conn())?
+        .into_iter()
+        .map(|cached| (cached.series_id, cached))
+        .collect::<HashMap<_, _>>();
+
+    for episode in aired {
+        let sid = episode.id as i32;
+
+        if !sids.contains(&sid) {
+            continue;
+        }
+
+        let is_later = latest.get(&sid).map_or(true, |cached| {
+            episode.episode as i32 >= cached.latest_episode
+        });
+
+        if is_later {
+            let cached = CachedAiring {
+                series_id: sid,
+                latest_episode: episode.episode as i32,
+                aired_at: episode.aired_at,
+                fetched_at,
+            };
+
+            latest.insert(sid, cached);
+        }
+    }
+
+    for cached in latest.values_mut() {
+        cached.fetched_at = fetched_at;
+    }
+
+    db.transaction("cache aired episodes", || {
+        for cached in latest.values() {
+            diesel::replace_into(cached_airing)
+                .values(cached)
+                .execute(db.conn())?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Load the UTC timestamp the latest cached episode of each series aired at.
+pub fn aired_times(db: &Database) -> diesel::QueryResult<HashMap<i32, i64>> {
+    use crate::database::schema::cached_airing::dsl::{aired_at, cached_airing, series_id};
+
+    let times = cached_airing
+        .select((series_id, aired_at))
+        .load::<(i32, i64)>(db.conn())?
+        .into_iter()
+        .collect();
+
+    Ok(times)
+}
+
+/// Look up the latest aired episode of every series in `ids`.
+///
+/// Airing schedules checked less than `max_age` ago are used as-is, while the rest are checked with `remote` and stored in the cache.
+/// While offline, cached episodes are used regardless of their age.
+/// Series without an episode that aired recently or previously cached are left out of the results.
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+pub async fn latest_aired<L, D>(
+    lock_db: L,
+    remote: &Remote,
+    ids: &[SeriesID],
+    max_age: Duration,
+) -> anyhow::Result<HashMap<SeriesID, u32>>
+where
+    L: Fn() -> D,
+    D: Deref<Target = Database>,
+{
+    use crate::database::schema::cached_airing::dsl::{cached_airing, series_id};
+
+    /// How far back to look through the airing schedule of each series.
+    const LOOKBACK_SECS: i64 = 14 * 24 * 60 * 60;
+
+    let now = Utc::now();
+    let sids = ids.iter().map(|&id| id as i32).collect::<Vec<_>>();
+
+    let load_cached = || {
+        cached_airing
+            .filter(series_id.eq_any(&sids))
+            .load::<CachedAiring>(lock_db().conn())
+    };
+
+    let mut cached = load_cached()?;
+
+    if !remote.is_offline() {
+        let cutoff = (now - max_age).timestamp();
+
+        let stale = ids
+            .iter()
+            .copied()
+            .filter(|&id| {
+                !cached
+                    .iter()
+                    .any(|c| c.series_id == id as i32 && c.fetched_at >= cutoff)
+            })
+            .collect::<Vec<_>>();
+
+        if !stale.is_empty() {
+            let now = now.timestamp();
+            let aired = remote
+                .aired_episodes(&stale, now - LOOKBACK_SECS, now)
+                .await?;
+
+            store_aired(&lock_db(), &stale, &aired, now)?;
+            cached = load_cached()?;
+        }
+    }
+
+    Ok(cached
+        .into_iter()
+        .map(|c| (c.series_id as SeriesID, c.latest_episode as u32))
+        .collect())
+}
+
+/// The result of refreshing the cache.
+#[derive(Default)]
+pub struct RefreshResult {
+    /// The number of series that were successfully refreshed.
+    pub refreshed: usize,
+    /// The series that could not be refreshed.
+    pub failed: Vec<(SeriesID, anyhow::Error)>,
+}
+
+/// Refresh the cached info of every series selected by `refresh` from `remote`.
+///
+/// The database is only accessed through `lock_db` while it's needed, so it isn't held while waiting on the remote service.
+pub async fn refresh<L, D>(
+    lock_db: L,
+    remote: &Remote,
+    refresh: Refresh,
+) -> anyhow::Result<RefreshResult>
+where
+    L: Fn() -> D,
+    D: Deref<Target = Database>,
+{
+    let mut result = RefreshResult::default();
+
+    let ids = ids_needing_refresh(&lock_db(), refresh)?;
+    refresh_ids(&lock_db, remote, ids, &mut result).await;
+
+    // Refreshing can discover sequels that have never been cached, which can have later seasons of their own
+    loop {
+        let failed = result
+            .failed
+            .iter()
+            .map(|(id, _)| *id)
+            .collect::<HashSet<_>>();
+
+        let mut new_ids = ids_needing_refresh(&lock_db(), Refresh::Uncached)?;
+        new_ids.retain(|id| !failed.contains(id));
+
+        if new_ids.is_empty() {
+            break Ok(result);
+        }
+
+        refresh_ids(&lock_db, remote, new_ids, &mut result).await;
+    }
+}
+
+async fn refresh_ids<L, D>(
+    lock_db: L,
+    remote: &Remote,
+    ids: Vec<SeriesID>,
+    result: &mut RefreshResult,
+) where
+    L: Fn() -> D,
+    D: Deref<Target = Database>,
+{
+    if ids.is_empty() {
+        return;
+    }
+
+    let fetched = match remote.search_info_by_ids(&ids).await {
+        Ok(fetched) => fetched,
+        Err(err) => {
+            let err = anyhow::Error::from(err);
+
+            // Every series in the batch failed for the same reason
+            for id in ids {
+                result.failed.push((id, anyhow!("{:#}", err)));
+            }
+
+            return;
+        }
+    };
+
+    let db = lock_db();
+    let mut missing = ids.into_iter().collect::<HashSet<_>>();
+
+    for info in fetched {
+        missing.remove(&info.id);
+
+        match store(&db, &info) {
+            Ok(()) => result.refreshed += 1,
+            Err(err) => result.failed.push((info.id, err.into())),
+        }
+    }
+
+    for id in missing {
+        result
+            .failed
+            .push((id, anyhow!("series not found on the remote service")));
+    }
+}
+
+/// Create an offline remote that can look up the info of every cached series.
+///
+/// Scores are parsed and shown in the format of the last user that was logged in to, if it's known.
+pub fn offline_remote(db: &Database) -> Remote {
+    let offline = load_all(db).map_or_else(|_| Offline::new(), Offline::with_cache);
+
+    match LastScoreFormat::load() {
+        Ok(format) => format.apply(offline).into(),
+        Err(_) => offline.into(),
+    }
+}
@@ -0,0 +1,322 @@
+use super::entry::{EntryField, SeriesEntry};
+use crate::database::schema::entry_changes;
+use crate::database::Database;
+use anime::remote::SeriesEntry as RemoteEntry;
+use chrono::Utc;
+use diesel::prelude::*;
+
+/// A change made to a field of a list entry that hasn't been synced to the remote service yet.
+///
+/// Changes are recorded every time an entry with unsynced changes is saved, and are forgotten once the entry is synced.
+/// This lets changes made offline be merged with ones made on the remote service in the meantime, instead of replacing them.
+#[derive(Debug, Queryable, Insertable)]
+#[table_name = "entry_changes"]
+pub struct EntryChange {
+    pub series_id: i32,
+    /// The [`EntryField::key`] of the field that was changed.
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    /// When the change was made, in seconds since the Unix epoch.
+    pub changed_at: i64,
+}
+
+impl EntryChange {
+    /// Load every recorded change of the entry of the series with `id`, from oldest to newest.
+    pub fn load(db: &Database, id: i32) -> diesel::QueryResult<Vec<Self>> {
+        use crate::database::schema::entry_changes::dsl::{
+            changed_at, entry_changes, field, id as change_id, new_value, old_value, series_id,
+        };
+
+        entry_changes
+            .select((series_id, field, old_value, new_value, changed_at))
+            .filter(series_id.eq(id))
+            .order(change_id.asc())
+            .load(db.conn())
+    }
+
+    /// Forget every recorded change of the entry of the series with `id`.
+    pub fn clear(db: &Database, id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::entry_changes::dsl::{entry_changes, series_id};
+
+        diesel::delete(entry_changes.filter(series_id.eq(id))).execute(db.conn())
+    }
+
+    /// Record each field of `entry` that differs from the saved version of it.
+    ///
+    /// Entries that don't need to be synced have their recorded changes cleared instead, as they've reached the remote service.
+    /// Nothing is recorded for entries that haven't been saved before, since there's nothing to compare them to.
+    pub fn record(entry: &SeriesEntry, db: &Database) -> diesel::QueryResult<()> {
+        use crate::database::schema::entry_changes::dsl::entry_changes;
+
+        if !entry.needs_sync() {
+            Self::clear(db, entry.id())?;
+            return Ok(());
+        }
+
+        let saved = match SeriesEntry::load(db, entry.id()) {
+            Ok(saved) => saved,
+            Err(diesel::NotFound) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        let changed_at = Utc::now().timestamp();
+
+        let changes = EntryField::ALL
+            .iter()
+            .filter_map(|&field| {
+                let old_value = saved.field_value(field);
+                let new_value = entry.field_value(field);
+
+                (old_value != new_value).then(|| Self {
+                    series_id: entry.id(),
+                    field: field.key().into(),
+                    old_value,
+                    new_value,
+                    changed_at,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if !changes.is_empty() {
+            diesel::insert_into(entry_changes)
+                .values(&changes)
+                .execute(db.conn())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A field that was changed both locally and on the remote service since the entry was last synced.
+#[derive(Debug)]
+pub struct Conflict {
+    pub field: EntryField,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+    /// When the field was last changed locally, in seconds since the Unix epoch.
+    pub local_changed_at: i64,
+    /// When the entry was last changed on the remote service, if the service reports it.
+    pub remote_changed_at: Option<i64>,
+}
+
+impl Conflict {
+    /// Returns true if the local value is the one that was changed last.
+    ///
+    /// The local value wins when it's unknown when the remote entry was changed, as that's what syncing has always done.
+    pub fn local_is_latest(&self) -> bool {
+        self.remote_changed_at
+            .map_or(true, |remote| self.local_changed_at >= remote)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Resolution {
+    KeepLocal,
+    KeepRemote,
+}
+
+/// The unsynced changes of a list entry merged with the latest version of the entry on the remote service.
+///
+/// Each field that was only changed locally takes the local value, while every other field takes the remote one.
+/// Fields that were changed on both sides are conflicts, and use the local value until they're resolved.
+pub struct EntryMerge {
+    merged: SeriesEntry,
+    remote: Option<SeriesEntry>,
+    conflicts: Vec<Conflict>,
+}
+
+impl EntryMerge {
+    /// Merge the `changes` made to `local` with the `remote` version of the entry.
+    ///
+    /// The local entry is used as is when there's no remote entry, or when none of its changes were recorded.
+    pub fn new(local: &SeriesEntry, remote: Option<RemoteEntry>, changes: &[EntryChange]) -> Self {
+        let remote_changed_at = remote.as_ref().and_then(|remote| remote.updated_at);
+
+        let remote = match remote {
+            Some(remote) if !changes.is_empty() => SeriesEntry::from(remote),
+            Some(_) | None => {
+                return Self {
+                    merged: local.clone(),
+                    remote: None,
+                    conflicts: Vec::new(),
+                }
+            }
+        };
+
+        let mut merged = remote.clone();
+        let mut conflicts = Vec::new();
+
+        for field in EntryField::ALL {
+            let mut field_changes = changes.iter().filter(|change| change.field == field.key());
+
+            let first = match field_changes.next() {
+                Some(first) => first,
+                None => continue,
+            };
+
+            let last = field_changes.next_back().unwrap_or(first);
+
+            let local_value = local.field_value(field);
+            let remote_value = remote.field_value(field);
+
+            merged.copy_field(field, local);
+
+            // The remote value is left alone if it's the same as it was before the local changes were made
+            if remote_value == first.old_value || remote_value == local_value {
+                continue;
+            }
+
+            conflicts.push(Conflict {
+                field,
+                local: local_value,
+                remote: remote_value,
+                local_changed_at: last.changed_at,
+                remote_changed_at,
+            });
+        }
+
+        merged.set_needs_sync();
+
+        Self {
+            merged,
+            remote: Some(remote),
+            conflicts,
+        }
+    }
+
+    #[inline(always)]
+    pub fn id(&self) -> i32 {
+        self.merged.id()
+    }
+
+    #[inline(always)]
+    pub fn conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+
+    #[inline(always)]
+    pub fn is_resolved(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Settle the conflict over `field` with `resolution`.
+    pub fn resolve(&mut self, field: EntryField, resolution: Resolution) {
+        if let (Resolution::KeepRemote, Some(remote)) = (resolution, &self.remote) {
+            self.merged.copy_field(field, remote);
+        }
+
+        self.conflicts.retain(|conflict| conflict.field != field);
+    }
+
+    /// Settle every conflict in favor of the side that was changed last, and return the conflicts that were settled.
+    pub fn resolve_latest(&mut self) -> Vec<Conflict> {
+        let conflicts = std::mem::take(&mut self.conflicts);
+
+        for conflict in &conflicts {
+            if !conflict.local_is_latest() {
+                if let Some(remote) = &self.remote {
+                    self.merged.copy_field(conflict.field, remote);
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Returns the merged entry, which still needs to be synced.
+    #[inline(always)]
+    pub fn into_merged(self) -> SeriesEntry {
+        self.merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anime::remote::Status;
+
+    fn remote(watched_eps: u32, score: Option<u8>, status: Status) -> RemoteEntry {
+        RemoteEntry {
+            watched_eps,
+            score,
+            status,
+            updated_at: Some(100),
+            ..RemoteEntry::new(1)
+        }
+    }
+
+    fn change(field: EntryField, old: &str, new: &str, changed_at: i64) -> EntryChange {
+        EntryChange {
+            series_id: 1,
+            field: field.key().into(),
+            old_value: Some(old.into()),
+            new_value: Some(new.into()),
+            changed_at,
+        }
+    }
+
+    #[test]
+    fn local_changes_are_merged_with_remote_ones() {
+        // Watched 2 episodes offline, while the score was changed on the remote service
+        let mut local = SeriesEntry::from(remote(3, None, Status::Watching));
+        local.set_watched_episodes(5);
+
+        let changes = [
+            change(EntryField::Progress, "3", "4", 50),
+            change(EntryField::Progress, "4", "5", 60),
+        ];
+
+        let merge = EntryMerge::new(
+            &local,
+            Some(remote(3, Some(80), Status::Watching)),
+            &changes,
+        );
+        assert!(merge.is_resolved());
+
+        let merged = merge.into_merged();
+        assert_eq!(merged.watched_episodes(), 5);
+        assert_eq!(merged.score(), Some(80));
+        assert!(merged.needs_sync());
+    }
+
+    #[test]
+    fn fields_changed_on_both_sides_conflict() {
+        let mut local = SeriesEntry::from(remote(3, None, Status::Watching));
+        local.set_watched_episodes(4);
+
+        let changes = [change(EntryField::Progress, "3", "4", 50)];
+        let latest = remote(6, None, Status::Watching);
+
+        let mut merge = EntryMerge::new(&local, Some(latest.clone()), &changes);
+        assert_eq!(merge.conflicts().len(), 1);
+
+        let conflict = &merge.conflicts()[0];
+        assert_eq!(conflict.field, EntryField::Progress);
+        assert_eq!(conflict.local.as_deref(), Some("4"));
+        assert_eq!(conflict.remote.as_deref(), Some("6"));
+        assert!(!conflict.local_is_latest());
+
+        // The remote entry was changed after the local one
+        merge.resolve_latest();
+        assert!(merge.is_resolved());
+        assert_eq!(merge.into_merged().watched_episodes(), 6);
+
+        let mut merge = EntryMerge::new(&local, Some(latest), &changes);
+        merge.resolve(EntryField::Progress, Resolution::KeepLocal);
+        assert!(merge.is_resolved());
+        assert_eq!(merge.into_merged().watched_episodes(), 4);
+    }
+
+    #[test]
+    fn entries_without_recorded_changes_are_kept() {
+        let mut local = SeriesEntry::from(remote(3, None, Status::Watching));
+        local.set_watched_episodes(4);
+
+        let merge = EntryMerge::new(&local, Some(remote(6, Some(80), Status::Watching)), &[]);
+        let merged = merge.into_merged();
+
+        assert_eq!(merged.watched_episodes(), 4);
+        assert_eq!(merged.score(), None);
+    }
+}
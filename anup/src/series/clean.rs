@@ -0,0 +1,165 @@
+use super::config::SeriesConfig;
+use super::deleted::DeletedRows;
+use super::SeriesPath;
+use crate::config::Config;
+use crate::database::Database;
+use anyhow::{anyhow, Result};
+use std::str::FromStr;
+
+/// A series whose folder was moved or deleted outside of the program.
+pub struct MissingSeries {
+    pub config: SeriesConfig,
+    /// The folder in the series directory that best matches the nickname of the series, if there is one.
+    pub closest: Option<SeriesPath>,
+}
+
+impl MissingSeries {
+    /// Returns every series in `sconfigs` that has a folder set that no longer exists.
+    ///
+    /// Nothing is returned when the series directory itself is missing, as that usually means the drive it's on isn't mounted
+    /// rather than every series being gone.
+    pub fn find_all<'a, I>(sconfigs: I, config: &Config) -> Vec<Self>
+    where
+        I: IntoIterator<Item = &'a SeriesConfig>,
+    {
+        if !config.series_dir.exists() {
+            return Vec::new();
+        }
+
+        sconfigs
+            .into_iter()
+            .filter(|sconfig| {
+                sconfig.path.is_set() && !sconfig.path.exists_base(&config.series_dir)
+            })
+            .map(|sconfig| Self {
+                config: sconfig.clone(),
+                closest: SeriesPath::closest_matching(&sconfig.nickname, config).ok(),
+            })
+            .collect()
+    }
+
+    /// Resolve the missing folder with `action`.
+    ///
+    /// Returns the new config of the series, or the rows deleted along with it if it was deleted.
+    pub fn resolve(&self, action: CleanAction, db: &Database) -> Result<Resolved> {
+        let path = match action {
+            CleanAction::Delete => {
+                let rows = DeletedRows::delete_series(&self.config, db)?;
+                return Ok(Resolved::Deleted(rows));
+            }
+            CleanAction::Repath => self
+                .closest
+                .clone()
+                .ok_or_else(|| anyhow!("no folder found that matches {}", self.config.nickname))?,
+            CleanAction::Keep => SeriesPath::unset(),
+        };
+
+        let mut sconfig = self.config.clone();
+        sconfig.path = path;
+        sconfig.save(db)?;
+
+        Ok(Resolved::Kept(sconfig))
+    }
+}
+
+/// What became of a series whose folder no longer exists after it was resolved.
+pub enum Resolved {
+    /// The series was kept with the config it has now.
+    Kept(SeriesConfig),
+    /// The series was deleted, along with these rows.
+    Deleted(DeletedRows),
+}
+
+/// What to do with a series whose folder no longer exists.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CleanAction {
+    /// Remove the series from the program.
+    Delete,
+    /// Point the series at the folder that best matches its nickname.
+    Repath,
+    /// Keep the series without a folder, like one that's planned to be watched.
+    Keep,
+}
+
+impl FromStr for CleanAction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "delete" => Ok(Self::Delete),
+            "repath" => Ok(Self::Repath),
+            "keep" => Ok(Self::Keep),
+            _ => Err(format!(
+                "unknown action {}, expected delete, repath, or keep",
+                value
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anime::local::EpisodeParser;
+    use std::{env, fs};
+
+    fn sconfig(id: i32, nickname: &str, path: SeriesPath) -> SeriesConfig {
+        SeriesConfig {
+            id,
+            nickname: nickname.into(),
+            path,
+            episode_parser: EpisodeParser::default(),
+            player_args: Default::default(),
+            episode_offset: 0,
+            episode_category: None,
+            absolute_numbering: false,
+            feed_url: None,
+            local_rewatch: None,
+            sub_lang: None,
+            audio_lang: None,
+            sub_file_pattern: None,
+            stream_url_template: None,
+            episode_length_mins: None,
+        }
+    }
+
+    #[test]
+    fn find_missing_series() {
+        let series_dir = env::temp_dir().join(format!("anup_clean_{}", std::process::id()));
+
+        if series_dir.exists() {
+            fs::remove_dir_all(&series_dir).unwrap();
+        }
+
+        fs::create_dir_all(series_dir.join("Moved Series")).unwrap();
+        fs::create_dir_all(series_dir.join("Present Series")).unwrap();
+
+        let config = Config {
+            series_dir: series_dir.clone(),
+            ..Config::default()
+        };
+
+        let sconfigs = [
+            sconfig(
+                1,
+                "moved",
+                SeriesPath::new(series_dir.join("Moved"), &config),
+            ),
+            sconfig(
+                2,
+                "present",
+                SeriesPath::new(series_dir.join("Present Series"), &config),
+            ),
+            sconfig(3, "planned", SeriesPath::unset()),
+        ];
+
+        let missing = MissingSeries::find_all(&sconfigs, &config);
+
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].config.id, 1);
+
+        // An unmounted series directory shouldn't make every series look missing
+        fs::remove_dir_all(&series_dir).unwrap();
+        assert!(MissingSeries::find_all(&sconfigs, &config).is_empty());
+    }
+}
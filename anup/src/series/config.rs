@@ -1,19 +1,40 @@
-use super::{SeriesParams, SeriesPath, UpdateParams};
+use super::{alias, SeriesParams, SeriesPath, UpdateParams};
 use crate::database::schema::series_configs;
 use crate::database::{self, Database};
 use anime::local::EpisodeParser;
 use anime::remote::{Remote, RemoteService};
+use anime::SeriesKind;
 use anyhow::{anyhow, Result};
 use diesel::prelude::*;
 use std::borrow::Cow;
 
-#[derive(Clone, Queryable, Insertable)]
+#[derive(Clone, Queryable, Insertable, AsChangeset)]
+#[changeset_options(treat_none_as_null = "true")]
 pub struct SeriesConfig {
     pub id: i32,
     pub nickname: String,
     pub path: SeriesPath,
     pub episode_parser: EpisodeParser,
     pub player_args: database::PlayerArgs,
+    pub episode_offset: i16,
+    pub episode_category: Option<SeriesKind>,
+    /// Indicates whether the episodes of the series are numbered from the first season of the franchise,
+    /// rather than from the start of the series itself.
+    pub absolute_numbering: bool,
+    /// The URL of an RSS feed to check for new releases of the series.
+    pub feed_url: Option<String>,
+    /// The number of episodes watched in a rewatch that is only tracked locally, or None if there isn't one.
+    pub local_rewatch: Option<i16>,
+    /// The subtitle languages to prefer when playing the series with mpv, like `en,eng`.
+    pub sub_lang: Option<String>,
+    /// The audio languages to prefer when playing the series with mpv, like `ja,jpn`.
+    pub audio_lang: Option<String>,
+    /// The pattern used to find external subtitle files that belong to each episode.
+    pub sub_file_pattern: Option<String>,
+    /// The URL to stream episodes that aren't on disk from, with placeholders for the episode.
+    pub stream_url_template: Option<String>,
+    /// The length of each episode in minutes to use instead of the one from the remote service.
+    pub episode_length_mins: Option<i16>,
 }
 
 impl SeriesConfig {
@@ -28,6 +49,16 @@ impl SeriesConfig {
             path: params.path,
             episode_parser: params.parser,
             player_args: database::PlayerArgs::new(),
+            episode_offset: 0,
+            episode_category: None,
+            absolute_numbering: false,
+            feed_url: None,
+            local_rewatch: None,
+            sub_lang: None,
+            audio_lang: None,
+            sub_file_pattern: None,
+            stream_url_template: None,
+            episode_length_mins: None,
         })
     }
 
@@ -63,10 +94,25 @@ impl SeriesConfig {
         Ok(id_changed)
     }
 
+    /// Save the config to the database.
+    ///
+    /// Existing configs are updated in place, as replacing them would also delete every row that belongs to the series,
+    /// such as its aliases and episode progress.
     pub fn save(&self, db: &Database) -> diesel::QueryResult<usize> {
-        use crate::database::schema::series_configs::dsl::series_configs;
+        use crate::database::schema::series_configs::dsl::{id, nickname, series_configs};
+
+        let updated = diesel::update(series_configs.filter(id.eq(self.id)))
+            .set(self)
+            .execute(db.conn())?;
+
+        if updated > 0 {
+            return Ok(updated);
+        }
+
+        // A series whose ID has changed replaces the config it had under its old ID
+        diesel::delete(series_configs.filter(nickname.eq(&self.nickname))).execute(db.conn())?;
 
-        diesel::replace_into(series_configs)
+        diesel::insert_into(series_configs)
             .values(self)
             .execute(db.conn())
     }
@@ -77,12 +123,25 @@ impl SeriesConfig {
         series_configs.load(db.conn())
     }
 
+    /// Load the config of the series with `name` as either its nickname or one of its aliases.
     pub fn load_by_name(db: &Database, name: &str) -> diesel::QueryResult<Self> {
-        use crate::database::schema::series_configs::dsl::{nickname, series_configs};
+        use crate::database::schema::series_configs::dsl::{id, nickname, series_configs};
 
-        series_configs
+        let by_nickname = series_configs
             .filter(nickname.eq(name))
             .get_result(db.conn())
+            .optional()?;
+
+        if let Some(config) = by_nickname {
+            return Ok(config);
+        }
+
+        match alias::series_id(db, name)? {
+            Some(series_id) => series_configs
+                .filter(id.eq(series_id))
+                .get_result(db.conn()),
+            None => Err(diesel::result::Error::NotFound),
+        }
     }
 
     /// Delete the series configuration from the database.
@@ -104,7 +163,7 @@ impl SeriesConfig {
             .ok()
     }
 
-    fn id_exists(db: &Database, config_id: i32) -> Option<String> {
+    pub fn id_exists(db: &Database, config_id: i32) -> Option<String> {
         use crate::database::schema::series_configs::dsl::{id, nickname, series_configs};
 
         series_configs
@@ -0,0 +1,219 @@
+use super::changes::EntryChange;
+use super::config::SeriesConfig;
+use super::merged::{self, MergedSeason};
+use super::progress::EpisodeProgress;
+use super::{alias, synonym, tag, LoadedSeries};
+use crate::database::schema::entry_changes;
+use crate::database::Database;
+use diesel::prelude::*;
+
+/// A series that was deleted, which can be saved again to undo its deletion.
+pub struct DeletedSeries {
+    pub series: LoadedSeries,
+    pub rows: DeletedRows,
+}
+
+impl DeletedSeries {
+    /// Save the series again along with every row that was deleted with it.
+    pub fn restore(&self, db: &Database) -> diesel::QueryResult<()> {
+        let sconfig = self.series.config();
+        let id = sconfig.id;
+        let rows = &self.rows;
+
+        db.transaction("restore deleted series", || {
+            sconfig.save(db)?;
+            self.series.save(db)?;
+
+            for progress in &rows.progress {
+                progress.save(db)?;
+            }
+
+            alias::replace(db, id, &rows.aliases)?;
+            synonym::replace(db, id, &rows.synonyms)?;
+            tag::replace(db, id, &rows.tags)?;
+            merged::replace(db, id, &rows.merged_seasons)?;
+
+            // Saving the entry above can't record changes for an entry that isn't in the database, so the old ones are all that's there
+            EntryChange::clear(db, id)?;
+
+            diesel::insert_into(entry_changes::table)
+                .values(&rows.changes)
+                .execute(db.conn())?;
+
+            Ok(())
+        })
+    }
+}
+
+/// The rows of a deleted series in every table that's cleared along with its config.
+///
+/// Deleting a series config cascades to each of these tables, so they have to be kept for the series to be restored in full.
+#[derive(Default)]
+pub struct DeletedRows {
+    progress: Vec<EpisodeProgress>,
+    aliases: Vec<String>,
+    synonyms: Vec<String>,
+    tags: Vec<String>,
+    merged_seasons: Vec<MergedSeason>,
+    changes: Vec<EntryChange>,
+}
+
+impl DeletedRows {
+    /// Delete the series with `sconfig` and return every row that was deleted along with it.
+    pub fn delete_series(sconfig: &SeriesConfig, db: &Database) -> diesel::QueryResult<Self> {
+        let id = sconfig.id;
+
+        db.transaction("delete series", || {
+            let rows = Self {
+                progress: EpisodeProgress::load_all(db, id)?,
+                aliases: alias::load(db, id)?,
+                synonyms: synonym::load(db, id)?,
+                tags: tag::load(db, id)?,
+                merged_seasons: merged::load(db, id)?,
+                changes: EntryChange::load(db, id)?,
+            };
+
+            sconfig.delete(db)?;
+            Ok(rows)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::series::SeriesPath;
+    use anime::local::EpisodeParser;
+    use anyhow::anyhow;
+    use diesel::sql_types::Text;
+
+    fn sconfig(id: i32) -> SeriesConfig {
+        SeriesConfig {
+            id,
+            nickname: format!("series{}", id),
+            path: SeriesPath::unset(),
+            episode_parser: EpisodeParser::default(),
+            player_args: Default::default(),
+            episode_offset: 0,
+            episode_category: None,
+            absolute_numbering: false,
+            feed_url: None,
+            local_rewatch: None,
+            sub_lang: None,
+            audio_lang: None,
+            sub_file_pattern: None,
+            stream_url_template: None,
+            episode_length_mins: None,
+        }
+    }
+
+    #[test]
+    fn restored_series_keeps_its_rows() {
+        let db = Database::open_in_memory().unwrap();
+        let sconfig = sconfig(1);
+        sconfig.save(&db).unwrap();
+
+        let aliases = vec!["snk".to_string()];
+        let synonyms = vec!["Attack on Titan".to_string()];
+        let tags = vec!["rewatch".to_string(), "seasonal-2024".to_string()];
+
+        let seasons = vec![MergedSeason {
+            series_id: 1,
+            season_id: 20,
+            episode_offset: 12,
+            episodes: 13,
+        }];
+
+        alias::replace(&db, 1, &aliases).unwrap();
+        synonym::replace(&db, 1, &synonyms).unwrap();
+        tag::replace(&db, 1, &tags).unwrap();
+        merged::replace(&db, 1, &seasons).unwrap();
+
+        diesel::insert_into(entry_changes::table)
+            .values(&EntryChange {
+                series_id: 1,
+                field: "progress".into(),
+                old_value: Some("3".into()),
+                new_value: Some("4".into()),
+                changed_at: 50,
+            })
+            .execute(db.conn())
+            .unwrap();
+
+        EpisodeProgress {
+            series_id: 1,
+            episode: 3,
+            position_secs: 600,
+            watched_secs: 600,
+        }
+        .save(&db)
+        .unwrap();
+
+        let rows = DeletedRows::delete_series(&sconfig, &db).unwrap();
+
+        assert!(alias::load(&db, 1).unwrap().is_empty());
+        assert!(tag::load(&db, 1).unwrap().is_empty());
+        assert!(EpisodeProgress::load(&db, 1, 3).unwrap().is_none());
+        assert!(EntryChange::load(&db, 1).unwrap().is_empty());
+
+        let deleted = DeletedSeries {
+            series: LoadedSeries::None(sconfig, anyhow!("not loaded")),
+            rows,
+        };
+
+        deleted.restore(&db).unwrap();
+
+        assert_eq!(alias::load(&db, 1).unwrap(), aliases);
+        assert_eq!(synonym::load(&db, 1).unwrap(), synonyms);
+        assert_eq!(tag::load(&db, 1).unwrap(), tags);
+        assert_eq!(merged::load(&db, 1).unwrap(), seasons);
+
+        let progress = EpisodeProgress::load(&db, 1, 3).unwrap().unwrap();
+        assert_eq!(progress.position_secs, 600);
+
+        let changes = EntryChange::load(&db, 1).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].new_value.as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn every_cascading_table_is_restored() {
+        /// The tables whose rows are kept in `DeletedRows`.
+        const RESTORED: [&str; 6] = [
+            "episode_progress",
+            "series_aliases",
+            "series_synonyms",
+            "series_tags",
+            "merged_seasons",
+            "entry_changes",
+        ];
+
+        /// The tables that are saved along with the series itself.
+        const SAVED_WITH_SERIES: [&str; 2] = ["series_info", "series_entries"];
+
+        #[derive(QueryableByName)]
+        struct Table {
+            #[sql_type = "Text"]
+            name: String,
+        }
+
+        let db = Database::open_in_memory().unwrap();
+
+        let tables = diesel::sql_query(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND sql LIKE '%REFERENCES series_configs%'",
+        )
+        .load::<Table>(db.conn())
+        .unwrap();
+
+        assert_eq!(tables.len(), RESTORED.len() + SAVED_WITH_SERIES.len());
+
+        for table in tables {
+            assert!(
+                RESTORED.contains(&table.name.as_str())
+                    || SAVED_WITH_SERIES.contains(&table.name.as_str()),
+                "rows in {} are deleted with a series but not restored when its deletion is undone",
+                table.name
+            );
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use super::config::SeriesConfig;
+use super::entry::SeriesEntry;
+use super::info::SeriesInfo;
+use super::{cache, SeriesData, SeriesPath};
+use crate::config::Config;
+use crate::database::schema::{
+    entry_changes, episode_progress, merged_seasons, series_aliases, series_configs,
+    series_entries, series_info, series_synonyms, series_tags,
+};
+use crate::database::Database;
+use anime::local::EpisodeParser;
+use anime::remote::{Remote, RemoteService, SeriesID};
+use anime::SeriesKind;
+use anyhow::{Context, Result};
+use chrono::Duration;
+use diesel::dsl::not;
+use diesel::prelude::*;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// A problem found with a series or the database, along with how to fix it if it can be done safely.
+pub struct Issue {
+    /// The nickname of the series the problem is with, or None if it's with the database as a whole.
+    pub series: Option<String>,
+    pub problem: String,
+    pub fix: Option<Fix>,
+}
+
+impl Issue {
+    fn new<S>(series: &SeriesConfig, problem: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            series: Some(series.nickname.clone()),
+            problem: problem.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.series {
+            Some(series) => write!(f, "{}: {}", series, self.problem)?,
+            None => write!(f, "database: {}", self.problem)?,
+        }
+
+        if let Some(fix) = &self.fix {
+            write!(f, "\n  fix: {}", fix)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A repair for an [`Issue`] that can't lose anything the user would want to keep.
+pub enum Fix {
+    /// Point the series with the contained ID at a different folder.
+    SetPath(i32, SeriesPath),
+    /// Have the series with the contained ID use a different episode parser.
+    SetParser(i32, EpisodeParser),
+    /// Replace a list entry that has no unsynced changes with the one on the remote service.
+    ReplaceEntry(SeriesEntry),
+    /// Replace stored series info with the latest info from the remote service.
+    ReplaceInfo(SeriesInfo),
+    /// Delete every row that belongs to a series that no longer exists.
+    RemoveDangling,
+}
+
+impl Fix {
+    /// Apply the fix to the series it was made for.
+    pub fn apply(&self, db: &Database) -> Result<()> {
+        let update_config = |id: i32, update: &dyn Fn(&mut SeriesConfig)| -> Result<()> {
+            use crate::database::schema::series_configs::dsl;
+
+            let mut config: SeriesConfig = dsl::series_configs
+                .filter(dsl::id.eq(id))
+                .get_result(db.conn())?;
+
+            update(&mut config);
+            config.save(db)?;
+            Ok(())
+        };
+
+        match self {
+            Self::SetPath(id, path) => update_config(*id, &|config| config.path = path.clone()),
+            Self::SetParser(id, parser) => {
+                update_config(*id, &|config| config.episode_parser = parser.clone())
+            }
+            Self::ReplaceEntry(entry) => entry.save(db).map(|_| ()).map_err(Into::into),
+            Self::ReplaceInfo(info) => info.save(db).map(|_| ()).map_err(Into::into),
+            Self::RemoveDangling => remove_dangling_rows(db),
+        }
+    }
+}
+
+impl fmt::Display for Fix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SetPath(_, path) => write!(f, "use the folder at {}", path.inner().display()),
+            Self::SetParser(_, _) => write!(f, "use the default episode parser"),
+            Self::ReplaceEntry(_) => write!(f, "use the list entry from the remote service"),
+            Self::ReplaceInfo(_) => write!(f, "use the series info from the remote service"),
+            Self::RemoveDangling => write!(f, "delete the rows"),
+        }
+    }
+}
+
+/// Check the folder, episodes, and list entry of the series with `data` for problems that can be found without the remote service.
+pub fn check_local(data: &SeriesData, config: &Config) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let sconfig = &data.config;
+
+    let total = data.info.episodes;
+    let watched = data.entry.watched_episodes();
+
+    if total > 0 && watched > total {
+        issues.push(Issue::new(
+            sconfig,
+            format!(
+                "{} episodes are watched, but the series only has {}",
+                watched, total
+            ),
+        ));
+    }
+
+    if !sconfig.path.is_set() {
+        // Series that are planned to be watched usually haven't been downloaded yet
+        if let Some(path) = detect_path(data, config) {
+            issues.push(
+                Issue::new(sconfig, "no folder set").with_fix(Fix::SetPath(sconfig.id, path)),
+            );
+        }
+
+        return issues;
+    }
+
+    let dir = sconfig.path.absolute(config);
+
+    if !dir.exists() {
+        let issue = Issue::new(
+            sconfig,
+            format!("the folder at {} doesn't exist", dir.display()),
+        );
+
+        let issue = match detect_path(data, config) {
+            Some(path) => issue.with_fix(Fix::SetPath(sconfig.id, path)),
+            None => issue,
+        };
+
+        issues.push(issue);
+        return issues;
+    }
+
+    let titles = data.titles();
+
+    let parses_episodes = |parser: &EpisodeParser| {
+        super::parse_episode_dir(&dir, parser, &titles, config).map(|episodes| !episodes.is_empty())
+    };
+
+    let problem = match parses_episodes(&sconfig.episode_parser) {
+        Ok(true) => None,
+        Ok(false) => Some("the episode parser doesn't match any files".into()),
+        Err(err) => Some(format!("the episode parser failed: {}", err)),
+    };
+
+    if let Some(problem) = problem {
+        let issue = Issue::new(sconfig, problem);
+        let default = EpisodeParser::default();
+
+        let issue =
+            if sconfig.episode_parser != default && matches!(parses_episodes(&default), Ok(true)) {
+                issue.with_fix(Fix::SetParser(sconfig.id, default))
+            } else {
+                issue
+            };
+
+        issues.push(issue);
+        return issues;
+    }
+
+    if let Ok(filenames) = episode_filenames(&dir) {
+        for (episode, files) in duplicate_episodes(&filenames, &sconfig.episode_parser) {
+            issues.push(Issue::new(
+                sconfig,
+                format!(
+                    "episode {} is in more than one file: {}",
+                    episode,
+                    files.join(", ")
+                ),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Returns the folder in the series directory that best matches the title of the series with `data`, if there is one.
+fn detect_path(data: &SeriesData, config: &Config) -> Option<SeriesPath> {
+    SeriesPath::closest_to_series(&data.titles(), &data.config.nickname, config)
+}
+
+/// Returns the name of every file directly inside of `dir`.
+fn episode_filenames(dir: &Path) -> Result<Vec<String>> {
+    let mut filenames = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+
+        if entry.file_type()?.is_file() {
+            filenames.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+
+    Ok(filenames)
+}
+
+/// Returns every episode number that more than one of `filenames` parses to with `parser`, along with the files that do.
+///
+/// Only one of the files for each episode is used when scanning a series, so the others are never played.
+fn duplicate_episodes(filenames: &[String], parser: &EpisodeParser) -> Vec<(String, Vec<String>)> {
+    let mut episodes: HashMap<(SeriesKind, u32, Option<u8>), Vec<String>> = HashMap::new();
+
+    for filename in filenames {
+        if filename.ends_with(".part") {
+            continue;
+        }
+
+        let parsed = match parser.parse(filename.as_str()) {
+            Ok(parsed) => parsed,
+            Err(_) => continue,
+        };
+
+        episodes
+            .entry((parsed.category, parsed.episode, parsed.fraction))
+            .or_default()
+            .push(filename.clone());
+    }
+
+    let mut duplicates = episodes
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|((_, episode, fraction), mut files)| {
+            files.sort_unstable();
+            ((episode, fraction), files)
+        })
+        .collect::<Vec<_>>();
+
+    duplicates.sort_unstable();
+
+    duplicates
+        .into_iter()
+        .map(|((episode, fraction), files)| {
+            let episode = match fraction {
+                Some(fraction) => format!("{}.{}", episode, fraction),
+                None => episode.to_string(),
+            };
+
+            (episode, files)
+        })
+        .collect()
+}
+
+/// Check the list entry and info of the series with `data` against the remote service.
+///
+/// Entries with changes that haven't been synced yet are expected to differ, so they aren't checked.
+pub async fn check_remote(
+    data: &SeriesData,
+    remote: &Remote,
+    db: &Database,
+    config: &Config,
+) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+
+    if remote.is_offline() {
+        return Ok(issues);
+    }
+
+    let sconfig = &data.config;
+
+    if !data.entry.needs_sync() {
+        let entry = SeriesEntry::from_remote(remote, &data.info).await?;
+
+        if entry != data.entry {
+            issues.push(
+                Issue::new(
+                    sconfig,
+                    "the list entry is out of date with the remote service",
+                )
+                .with_fix(Fix::ReplaceEntry(entry)),
+            );
+        }
+    }
+
+    let max_age = Duration::days(config.offline.cache_max_age_days.into());
+    let info: SeriesInfo = cache::lookup_by_id(|| db, remote, data.info.id as SeriesID, max_age)
+        .await
+        .context("failed to look up series info")?
+        .into();
+
+    if info.episodes != data.info.episodes || info.title_preferred != data.info.title_preferred {
+        issues.push(
+            Issue::new(sconfig, "the stored series info is out of date")
+                .with_fix(Fix::ReplaceInfo(info)),
+        );
+    }
+
+    Ok(issues)
+}
+
+/// Check for series that are missing their info or list entry, and rows that belong to series that no longer exist.
+pub fn check_database(db: &Database) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+
+    for sconfig in SeriesConfig::load_all(db)? {
+        if let Err(err) = SeriesData::load_from_config(db, Cow::Borrowed(&sconfig)) {
+            issues.push(Issue::new(
+                &sconfig,
+                format!(
+                    "failed to load its info or list entry: {}\nremove and add the series again",
+                    err
+                ),
+            ));
+        }
+    }
+
+    let config_ids = series_configs::table.select(series_configs::id);
+
+    let dangling = [
+        series_info::table
+            .filter(not(series_info::id.eq_any(config_ids)))
+            .count()
+            .get_result::<i64>(db.conn())?,
+        series_entries::table
+            .filter(not(series_entries::id.eq_any(config_ids)))
+            .count()
+            .get_result::<i64>(db.conn())?,
+        episode_progress::table
+            .filter(not(episode_progress::series_id.eq_any(config_ids)))
+            .count()
+            .get_result::<i64>(db.conn())?,
+        series_aliases::table
+            .filter(not(series_aliases::series_id.eq_any(config_ids)))
+            .count()
+            .get_result::<i64>(db.conn())?,
+        series_synonyms::table
+            .filter(not(series_synonyms::series_id.eq_any(config_ids)))
+            .count()
+            .get_result::<i64>(db.conn())?,
+        series_tags::table
+            .filter(not(series_tags::series_id.eq_any(config_ids)))
+            .count()
+            .get_result::<i64>(db.conn())?,
+        merged_seasons::table
+            .filter(not(merged_seasons::series_id.eq_any(config_ids)))
+            .count()
+            .get_result::<i64>(db.conn())?,
+        entry_changes::table
+            .filter(not(entry_changes::series_id.eq_any(config_ids)))
+            .count()
+            .get_result::<i64>(db.conn())?,
+    ]
+    .iter()
+    .sum::<i64>();
+
+    if dangling > 0 {
+        issues.push(Issue {
+            series: None,
+            problem: format!("{} rows belong to series that no longer exist", dangling),
+            fix: Some(Fix::RemoveDangling),
+        });
+    }
+
+    Ok(issues)
+}
+
+fn remove_dangling_rows(db: &Database) -> Result<()> {
+    let config_ids = series_configs::table.select(series_configs::id);
+
+    db.transaction("remove dangling rows", || {
+        diesel::delete(series_info::table.filter(not(series_info::id.eq_any(config_ids))))
+            .execute(db.conn())?;
+
+        diesel::delete(series_entries::table.filter(not(series_entries::id.eq_any(config_ids))))
+            .execute(db.conn())?;
+
+        diesel::delete(
+            episode_progress::table.filter(not(episode_progress::series_id.eq_any(config_ids))),
+        )
+        .execute(db.conn())?;
+
+        diesel::delete(
+            series_aliases::table.filter(not(series_aliases::series_id.eq_any(config_ids))),
+        )
+        .execute(db.conn())?;
+
+        diesel::delete(
+            series_synonyms::table.filter(not(series_synonyms::series_id.eq_any(config_ids))),
+        )
+        .execute(db.conn())?;
+
+        diesel::delete(series_tags::table.filter(not(series_tags::series_id.eq_any(config_ids))))
+            .execute(db.conn())?;
+
+        diesel::delete(
+            merged_seasons::table.filter(not(merged_seasons::series_id.eq_any(config_ids))),
+        )
+        .execute(db.conn())?;
+
+        diesel::delete(
+            entry_changes::table.filter(not(entry_changes::series_id.eq_any(config_ids))),
+        )
+        .execute(db.conn())?;
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_episode_detection() {
+        let filenames = [
+            "[Group A] Series Title - 01.mkv",
+            "[Group B] Series Title - 01.mkv",
+            "[Group A] Series Title - 02.mkv",
+            "[Group A] Series Title - 03.mkv.part",
+            "[Group B] Series Title - 03.mkv.part",
+        ]
+        .iter()
+        .map(|&filename| filename.to_string())
+        .collect::<Vec<_>>();
+
+        let duplicates = duplicate_episodes(&filenames, &EpisodeParser::default());
+
+        assert_eq!(
+            duplicates,
+            vec![(
+                "1".to_string(),
+                vec![
+                    "[Group A] Series Title - 01.mkv".to_string(),
+                    "[Group B] Series Title - 01.mkv".to_string(),
+                ]
+            )]
+        );
+    }
+}
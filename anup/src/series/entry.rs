@@ -1,4 +1,6 @@
+use super::changes::EntryChange;
 use super::info::SeriesInfo;
+use super::WatchStart;
 use crate::config::Config;
 use crate::database::schema::series_entries;
 use crate::database::Database;
@@ -6,8 +8,10 @@ use anime::remote::{Remote, RemoteService, SeriesDate, Status};
 use anyhow::Result;
 use chrono::Local;
 use diesel::prelude::*;
+use std::str::FromStr;
 
-#[derive(Queryable, Insertable)]
+#[derive(Clone, PartialEq, Queryable, Insertable)]
+#[cfg_attr(test, derive(Debug))]
 #[table_name = "series_entries"]
 pub struct SeriesEntry {
     id: i32,
@@ -27,12 +31,23 @@ impl SeriesEntry {
         series_entries.filter(id.eq(entry_id)).get_result(db.conn())
     }
 
+    pub fn load_all(db: &Database) -> diesel::QueryResult<Vec<Self>> {
+        use crate::database::schema::series_entries::dsl::series_entries;
+
+        series_entries.load(db.conn())
+    }
+
+    /// Save the entry, recording each field that changed since it was last saved until the entry has been synced.
     pub fn save(&self, db: &Database) -> diesel::QueryResult<usize> {
         use crate::database::schema::series_entries::dsl::series_entries;
 
-        diesel::replace_into(series_entries)
-            .values(self)
-            .execute(db.conn())
+        db.transaction("save list entry", || {
+            EntryChange::record(self, db)?;
+
+            diesel::replace_into(series_entries)
+                .values(self)
+                .execute(db.conn())
+        })
     }
 
     pub fn entries_that_need_sync(db: &Database) -> diesel::QueryResult<Vec<Self>> {
@@ -41,37 +56,44 @@ impl SeriesEntry {
         series_entries.filter(needs_sync.eq(true)).load(db.conn())
     }
 
-    pub fn from_remote(remote: &Remote, info: &SeriesInfo) -> Result<Self> {
-        match remote.get_list_entry(info.id as u32)? {
+    /// Retrieve the list entry for `info` from `remote`.
+    ///
+    /// Services without a user's list have nothing to retrieve, so a new entry is created instead.
+    pub async fn from_remote(remote: &Remote, info: &SeriesInfo) -> Result<Self> {
+        if !remote.has_list() {
+            return Ok(Self::from(info.id));
+        }
+
+        match remote.get_list_entry(info.id as u32).await? {
             Some(entry) => Ok(Self::from(entry)),
             None => Ok(Self::from(info.id)),
         }
     }
 
-    pub fn force_sync_to_remote(&mut self, remote: &Remote) -> Result<()> {
-        if remote.is_offline() {
+    pub async fn force_sync_to_remote(&mut self, remote: &Remote) -> Result<()> {
+        if !remote.has_list() {
             return Ok(());
         }
 
-        remote.update_list_entry(&self.into())?;
+        remote.update_list_entry(&self.into()).await?;
         self.needs_sync = false;
         Ok(())
     }
 
-    pub fn sync_to_remote(&mut self, remote: &Remote) -> Result<()> {
+    pub async fn sync_to_remote(&mut self, remote: &Remote) -> Result<()> {
         if !self.needs_sync {
             return Ok(());
         }
 
-        self.force_sync_to_remote(remote)
+        self.force_sync_to_remote(remote).await
     }
 
-    pub fn force_sync_from_remote(&mut self, remote: &Remote) -> Result<()> {
-        if remote.is_offline() {
+    pub async fn force_sync_from_remote(&mut self, remote: &Remote) -> Result<()> {
+        if !remote.has_list() {
             return Ok(());
         }
 
-        *self = match remote.get_list_entry(self.id() as u32)? {
+        *self = match remote.get_list_entry(self.id() as u32).await? {
             Some(entry) => Self::from(entry),
             None => Self::from(self.id()),
         };
@@ -79,12 +101,12 @@ impl SeriesEntry {
         Ok(())
     }
 
-    pub fn sync_from_remote(&mut self, remote: &Remote) -> Result<()> {
+    pub async fn sync_from_remote(&mut self, remote: &Remote) -> Result<()> {
         if self.needs_sync {
             return Ok(());
         }
 
-        self.force_sync_from_remote(remote)
+        self.force_sync_from_remote(remote).await
     }
 
     #[inline(always)]
@@ -92,6 +114,136 @@ impl SeriesEntry {
         self.needs_sync
     }
 
+    /// Marks the entry as having changes that haven't reached the remote service yet.
+    #[inline(always)]
+    pub fn set_needs_sync(&mut self) {
+        self.needs_sync = true;
+    }
+
+    /// Replaces the entry with a `previous` version of itself.
+    ///
+    /// The entry will be marked as needing to be synced so the change also reaches the remote service.
+    pub fn restore(&mut self, previous: Self) {
+        *self = previous;
+        self.needs_sync = true;
+    }
+
+    /// Returns true if the user should be asked before this entry is moved out of the plan to watch status.
+    #[inline(always)]
+    pub fn needs_watch_confirmation(&self, config: &Config) -> bool {
+        config.confirm_start_from_plan && self.status == Status::PlanToWatch
+    }
+
+    /// Updates the status and progress of the entry to reflect that it is about to be watched.
+    ///
+    /// Nothing will be changed when `start` is [`WatchStart::KeepStatus`].
+    pub fn begin_watching(&mut self, start: WatchStart, total_episodes: i16, config: &Config) {
+        if start == WatchStart::KeepStatus {
+            return;
+        }
+
+        let last_status = self.status();
+
+        match last_status {
+            Status::Watching | Status::Rewatching => {
+                // There is an edge case where all episodes have been watched, but the status
+                // is still set to watching / rewatching. Here we just start a rewatch
+                if self.watched_episodes() >= total_episodes {
+                    self.set_status(Status::Rewatching, config);
+                    self.set_watched_episodes(0);
+
+                    if last_status == Status::Rewatching {
+                        self.set_times_rewatched(self.times_rewatched() + 1);
+                    }
+                }
+            }
+            Status::Completed => {
+                self.set_status(Status::Rewatching, config);
+                self.set_watched_episodes(0);
+            }
+            Status::PlanToWatch | Status::OnHold => self.set_status(Status::Watching, config),
+            Status::Dropped => {
+                self.set_status(Status::Watching, config);
+                self.set_watched_episodes(0);
+            }
+        }
+    }
+
+    /// Apply `change` to the watched episodes, keeping them within the `total_episodes` of the series.
+    ///
+    /// The status is updated the same way it would be by watching or regressing episodes:
+    /// reaching the last episode completes the series, and going back from a completed series resumes it.
+    pub fn change_progress(
+        &mut self,
+        change: ProgressChange,
+        total_episodes: i16,
+        config: &Config,
+    ) {
+        let mut progress = match change {
+            ProgressChange::To(progress) => progress,
+            ProgressChange::By(delta) => self.watched_episodes.saturating_add(delta),
+        }
+        .max(0);
+
+        if total_episodes > 0 {
+            progress = progress.min(total_episodes);
+        }
+
+        let last_progress = self.watched_episodes;
+        self.set_watched_episodes(progress);
+
+        match self.status() {
+            Status::Completed if progress < total_episodes => {
+                let status = if self.times_rewatched() > 0 {
+                    Status::Rewatching
+                } else {
+                    Status::Watching
+                };
+
+                self.set_status(status, config);
+            }
+            Status::Completed => (),
+            status if total_episodes > 0 && progress >= total_episodes => {
+                // A rewatch is typically only counted once the series is completed again
+                if status == Status::Rewatching {
+                    self.set_times_rewatched(self.times_rewatched() + 1);
+                }
+
+                self.set_status(Status::Completed, config);
+            }
+            Status::PlanToWatch | Status::OnHold if progress > last_progress => {
+                self.set_status(Status::Watching, config);
+            }
+            _ => (),
+        }
+    }
+
+    /// Returns the value of `field` formatted for display, or None if it isn't set.
+    pub fn field_value(&self, field: EntryField) -> Option<String> {
+        match field {
+            EntryField::Progress => Some(self.watched_episodes.to_string()),
+            EntryField::Score => self.score.map(|score| score.to_string()),
+            EntryField::Status => Some(self.status.to_string()),
+            EntryField::TimesRewatched => Some(self.times_rewatched.to_string()),
+            EntryField::StartDate => self.start_date.map(SeriesDate::to_ymd_string),
+            EntryField::EndDate => self.end_date.map(SeriesDate::to_ymd_string),
+        }
+    }
+
+    /// Set `field` to its value in `other`, without changing anything else.
+    pub fn copy_field(&mut self, field: EntryField, other: &Self) {
+        match field {
+            EntryField::Progress => self.watched_episodes = other.watched_episodes,
+            EntryField::Score => self.score = other.score,
+            EntryField::Status => self.status = other.status,
+            EntryField::TimesRewatched => self.times_rewatched = other.times_rewatched,
+            EntryField::StartDate => self.start_date = other.start_date,
+            EntryField::EndDate => self.end_date = other.end_date,
+        }
+
+        self.needs_sync = true;
+    }
+
     pub fn set_status(&mut self, status: Status, config: &Config) {
         match status {
             Status::Watching if self.start_date().is_none() => {
@@ -120,6 +272,100 @@ impl SeriesEntry {
     }
 }
 
+/// Parse a status from its name or abbreviation, such as `watching` or `w`.
+pub fn parse_status(value: &str) -> Option<Status> {
+    match value.to_ascii_lowercase().as_ref() {
+        "w" | "watching" => Some(Status::Watching),
+        "c" | "completed" => Some(Status::Completed),
+        "h" | "hold" => Some(Status::OnHold),
+        "d" | "drop" => Some(Status::Dropped),
+        "p" | "plan" => Some(Status::PlanToWatch),
+        "r" | "rewatch" => Some(Status::Rewatching),
+        _ => None,
+    }
+}
+
+/// A change to the number of watched episodes of an entry.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ProgressChange {
+    /// Set the watched episodes to an exact number.
+    To(i16),
+    /// Add to the watched episodes, or subtract from them if negative.
+    By(i16),
+}
+
+impl FromStr for ProgressChange {
+    type Err = String;
+
+    /// Parse a change from an exact number like `5`, or a relative one like `+1` or `-2`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = |_| format!("invalid episode count: {}", value);
+
+        if value.starts_with('+') || value.starts_with('-') {
+            value.parse().map(Self::By).map_err(invalid)
+        } else {
+            value.parse().map(Self::To).map_err(invalid)
+        }
+    }
+}
+
+/// A field of a list entry that can be changed by the user.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EntryField {
+    Progress,
+    Score,
+    Status,
+    TimesRewatched,
+    StartDate,
+    EndDate,
+}
+
+impl EntryField {
+    pub const ALL: [Self; 6] = [
+        Self::Progress,
+        Self::Score,
+        Self::Status,
+        Self::TimesRewatched,
+        Self::StartDate,
+        Self::EndDate,
+    ];
+
+    /// Returns the name the field is stored under in the database.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Progress => "progress",
+            Self::Score => "score",
+            Self::Status => "status",
+            Self::TimesRewatched => "times_rewatched",
+            Self::StartDate => "start_date",
+            Self::EndDate => "end_date",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Progress => "progress",
+            Self::Score => "score",
+            Self::Status => "status",
+            Self::TimesRewatched => "times rewatched",
+            Self::StartDate => "start date",
+            Self::EndDate => "end date",
+        }
+    }
+}
+
+/// Returns the name [`parse_status`] accepts for `status`, which is meant for scripts rather than display.
+pub fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Watching => "watching",
+        Status::Completed => "completed",
+        Status::OnHold => "hold",
+        Status::Dropped => "drop",
+        Status::PlanToWatch => "plan",
+        Status::Rewatching => "rewatch",
+    }
+}
+
 macro_rules! impl_series_entry_getters_setters {
     ($($field:ident: $field_ty:ty => $setter:tt,)+) => {
         impl SeriesEntry {
@@ -165,6 +411,7 @@ impl Into<anime::remote::SeriesEntry> for &mut SeriesEntry {
             times_rewatched: self.times_rewatched as u32,
             start_date: self.start_date,
             end_date: self.end_date,
+            updated_at: None,
         }
     }
 }
@@ -191,3 +438,53 @@ impl From<i32> for SeriesEntry {
         Self::from(remote_entry)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(status: Status, watched_episodes: i16) -> SeriesEntry {
+        let mut entry = SeriesEntry::from(1);
+        entry.status = status;
+        entry.watched_episodes = watched_episodes;
+        entry
+    }
+
+    #[test]
+    fn parse_progress_change() {
+        assert_eq!("5".parse(), Ok(ProgressChange::To(5)));
+        assert_eq!("+1".parse(), Ok(ProgressChange::By(1)));
+        assert_eq!("-2".parse(), Ok(ProgressChange::By(-2)));
+        assert!("next".parse::<ProgressChange>().is_err());
+    }
+
+    #[test]
+    fn change_progress_updates_status() {
+        let config = Config::default();
+
+        let mut planned = entry(Status::PlanToWatch, 0);
+        planned.change_progress(ProgressChange::By(1), 12, &config);
+        assert_eq!(planned.watched_episodes(), 1);
+        assert_eq!(planned.status(), Status::Watching);
+        assert!(planned.needs_sync());
+
+        let mut watching = entry(Status::Watching, 11);
+        watching.change_progress(ProgressChange::By(3), 12, &config);
+        assert_eq!(watching.watched_episodes(), 12);
+        assert_eq!(watching.status(), Status::Completed);
+
+        let mut rewatching = entry(Status::Rewatching, 5);
+        rewatching.change_progress(ProgressChange::To(12), 12, &config);
+        assert_eq!(rewatching.status(), Status::Completed);
+        assert_eq!(rewatching.times_rewatched(), 1);
+
+        rewatching.change_progress(ProgressChange::By(-1), 12, &config);
+        assert_eq!(rewatching.watched_episodes(), 11);
+        assert_eq!(rewatching.status(), Status::Rewatching);
+
+        let mut unknown_length = entry(Status::Watching, 30);
+        unknown_length.change_progress(ProgressChange::By(-40), 0, &config);
+        assert_eq!(unknown_length.watched_episodes(), 0);
+        assert_eq!(unknown_length.status(), Status::Watching);
+    }
+}
@@ -0,0 +1,279 @@
+use super::entry::SeriesEntry;
+use super::info::SeriesInfo;
+use crate::database::Database;
+use anime::remote::{SeriesDate, Status};
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// A format the list can be exported in.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ExportFormat {
+    /// The XML format MyAnimeList uses for its own exports, which most services can import.
+    MalXml,
+    /// A JSON array of entries in the shape of AniList's `MediaList` objects.
+    AniListJson,
+    Csv,
+}
+
+impl ExportFormat {
+    /// Render `entries` in this format.
+    pub fn render(self, entries: &[ExportedEntry]) -> Result<String> {
+        match self {
+            Self::MalXml => Ok(mal_xml(entries)),
+            Self::AniListJson => anilist_json(entries),
+            Self::Csv => Ok(csv(entries)),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "mal-xml" => Ok(Self::MalXml),
+            "anilist-json" => Ok(Self::AniListJson),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!(
+                "unknown export format {}, expected mal-xml, anilist-json, or csv",
+                value
+            )),
+        }
+    }
+}
+
+/// A list entry along with the series info needed to describe it outside of the program.
+pub struct ExportedEntry {
+    pub entry: SeriesEntry,
+    pub title: String,
+    pub episodes: i16,
+}
+
+impl ExportedEntry {
+    /// Load every list entry in `db` along with its series info.
+    ///
+    /// Entries whose info hasn't been stored are still exported, but without a title or episode count.
+    pub fn load_all(db: &Database) -> Result<Vec<Self>> {
+        let entries = SeriesEntry::load_all(db).context("failed to load list entries")?;
+
+        let exported = entries
+            .into_iter()
+            .map(|entry| {
+                let (title, episodes) = match SeriesInfo::load(db, entry.id()) {
+                    Ok(info) => (info.title_preferred, info.episodes),
+                    Err(diesel::NotFound) => (String::new(), 0),
+                    Err(err) => {
+                        let err = anyhow!(err)
+                            .context(format!("failed to load info of series {}", entry.id()));
+
+                        return Err(err);
+                    }
+                };
+
+                Ok(Self {
+                    entry,
+                    title,
+                    episodes,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(exported)
+    }
+}
+
+fn date_str(date: Option<SeriesDate>) -> Option<String> {
+    date.map(SeriesDate::to_ymd_string)
+}
+
+fn mal_xml(entries: &[ExportedEntry]) -> String {
+    /// MyAnimeList's placeholder for dates that haven't been set.
+    const NO_DATE: &str = "0000-00-00";
+
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" ?>\n<myanimelist>\n\t<myinfo>\n\t\t<user_export_type>1</user_export_type>\n\t</myinfo>\n",
+    );
+
+    for exported in entries {
+        let entry = &exported.entry;
+
+        // MyAnimeList tracks rewatches as a flag on completed series rather than as their own status
+        let status = match entry.status() {
+            Status::Watching => "Watching",
+            Status::Completed | Status::Rewatching => "Completed",
+            Status::OnHold => "On-Hold",
+            Status::Dropped => "Dropped",
+            Status::PlanToWatch => "Plan to Watch",
+        };
+
+        let score = entry
+            .score()
+            .map_or(0, |score| (f32::from(score) / 10.0).round() as i16);
+
+        let rewatching = u8::from(entry.status() == Status::Rewatching);
+
+        writeln!(
+            xml,
+            "\t<anime>
+\t\t<series_animedb_id>{}</series_animedb_id>
+\t\t<series_title><![CDATA[{}]]></series_title>
+\t\t<series_episodes>{}</series_episodes>
+\t\t<my_watched_episodes>{}</my_watched_episodes>
+\t\t<my_start_date>{}</my_start_date>
+\t\t<my_finish_date>{}</my_finish_date>
+\t\t<my_score>{}</my_score>
+\t\t<my_status>{}</my_status>
+\t\t<my_times_watched>{}</my_times_watched>
+\t\t<my_rewatching>{}</my_rewatching>
+\t\t<update_on_import>1</update_on_import>
+\t</anime>",
+            entry.id(),
+            exported.title.replace("]]>", "]]]]><![CDATA[>"),
+            exported.episodes,
+            entry.watched_episodes(),
+            date_str(entry.start_date()).as_deref().unwrap_or(NO_DATE),
+            date_str(entry.end_date()).as_deref().unwrap_or(NO_DATE),
+            score,
+            status,
+            entry.times_rewatched(),
+            rewatching,
+        )
+        .ok();
+    }
+
+    xml.push_str("</myanimelist>\n");
+    xml
+}
+
+fn anilist_json(entries: &[ExportedEntry]) -> Result<String> {
+    fn date_json(date: Option<SeriesDate>) -> Value {
+        match date {
+            Some(date) => json!({ "year": date.year, "month": date.month, "day": date.day }),
+            None => json!({ "year": null, "month": null, "day": null }),
+        }
+    }
+
+    let entries = entries
+        .iter()
+        .map(|exported| {
+            let entry = &exported.entry;
+
+            let status = match entry.status() {
+                Status::Watching => "CURRENT",
+                Status::Completed => "COMPLETED",
+                Status::OnHold => "PAUSED",
+                Status::Dropped => "DROPPED",
+                Status::PlanToWatch => "PLANNING",
+                Status::Rewatching => "REPEATING",
+            };
+
+            json!({
+                "mediaId": entry.id(),
+                "title": exported.title,
+                "status": status,
+                "score": entry.score().unwrap_or(0),
+                "progress": entry.watched_episodes(),
+                "repeat": entry.times_rewatched(),
+                "startedAt": date_json(entry.start_date()),
+                "completedAt": date_json(entry.end_date()),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&entries).context("failed to serialize list entries")
+}
+
+fn csv(entries: &[ExportedEntry]) -> String {
+    fn quoted(value: &str) -> String {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    }
+
+    let mut csv =
+        String::from("id,title,status,score,progress,episodes,rewatches,start_date,end_date\n");
+
+    for exported in entries {
+        let entry = &exported.entry;
+
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{},{}",
+            entry.id(),
+            quoted(&exported.title),
+            entry.status(),
+            entry
+                .score()
+                .map(|score| score.to_string())
+                .unwrap_or_default(),
+            entry.watched_episodes(),
+            exported.episodes,
+            entry.times_rewatched(),
+            date_str(entry.start_date()).unwrap_or_default(),
+            date_str(entry.end_date()).unwrap_or_default(),
+        )
+        .ok();
+    }
+
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_entries() -> Vec<ExportedEntry> {
+        let entry = anime::remote::SeriesEntry {
+            id: 20,
+            watched_eps: 3,
+            score: Some(85),
+            status: Status::Rewatching,
+            times_rewatched: 1,
+            start_date: Some(SeriesDate::from_ymd(2021, 4, 5)),
+            end_date: None,
+            updated_at: None,
+        };
+
+        vec![ExportedEntry {
+            entry: entry.into(),
+            title: "Series \"Title\"".into(),
+            episodes: 12,
+        }]
+    }
+
+    #[test]
+    fn export_mal_xml() {
+        let xml = ExportFormat::MalXml.render(&sample_entries()).unwrap();
+
+        assert!(xml.contains("<series_animedb_id>20</series_animedb_id>"));
+        assert!(xml.contains("<series_title><![CDATA[Series \"Title\"]]></series_title>"));
+        assert!(xml.contains("<my_start_date>2021-04-05</my_start_date>"));
+        assert!(xml.contains("<my_finish_date>0000-00-00</my_finish_date>"));
+        assert!(xml.contains("<my_score>9</my_score>"));
+        assert!(xml.contains("<my_status>Completed</my_status>"));
+        assert!(xml.contains("<my_rewatching>1</my_rewatching>"));
+    }
+
+    #[test]
+    fn export_anilist_json() {
+        let json = ExportFormat::AniListJson.render(&sample_entries()).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value[0]["mediaId"], 20);
+        assert_eq!(value[0]["status"], "REPEATING");
+        assert_eq!(value[0]["score"], 85);
+        assert_eq!(value[0]["startedAt"]["month"], 4);
+        assert!(value[0]["completedAt"]["year"].is_null());
+    }
+
+    #[test]
+    fn export_csv() {
+        let csv = ExportFormat::Csv.render(&sample_entries()).unwrap();
+
+        assert_eq!(
+            csv,
+            "id,title,status,score,progress,episodes,rewatches,start_date,end_date\n\
+             20,\"Series \"\"Title\"\"\",Rewatching,85,3,12,1,2021-04-05,\n"
+        );
+    }
+}
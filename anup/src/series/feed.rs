@@ -0,0 +1,229 @@
+use crate::config::Config;
+use anime::local::EpisodeParser;
+use anyhow::{anyhow, Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::ops::RangeInclusive;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// A single release listed in an RSS feed.
+#[derive(Debug, PartialEq)]
+pub struct FeedItem {
+    pub title: String,
+    /// The torrent or magnet link of the release.
+    pub link: String,
+}
+
+/// Download the RSS feed at `url` with `http` and return every item in it.
+pub async fn fetch(http: &reqwest::Client, url: &str) -> Result<Vec<FeedItem>> {
+    let body = async {
+        http.get(url)
+            .timeout(Duration::from_secs(15))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+    }
+    .await
+    .with_context(|| anyhow!("failed to fetch feed at {}", url))?;
+
+    parse(&body).with_context(|| anyhow!("failed to parse feed at {}", url))
+}
+
+/// Parse every item in the RSS feed contained in `xml`.
+///
+/// The link of an item is taken from its enclosure if it has one, as that's where most feeds put the torrent file.
+pub fn parse(xml: &str) -> Result<Vec<FeedItem>> {
+    #[derive(Default)]
+    struct PartialItem {
+        title: Option<String>,
+        link: Option<String>,
+        enclosure: Option<String>,
+    }
+
+    #[derive(Copy, Clone)]
+    enum Field {
+        Title,
+        Link,
+        Other,
+    }
+
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut items = Vec::new();
+    let mut current: Option<PartialItem> = None;
+    let mut field = Field::Other;
+    let mut buf = Vec::new();
+
+    loop {
+        let text = match reader.read_event(&mut buf)? {
+            Event::Start(tag) => {
+                field = match tag.name() {
+                    b"item" => {
+                        current = Some(PartialItem::default());
+                        Field::Other
+                    }
+                    b"title" => Field::Title,
+                    b"link" => Field::Link,
+                    _ => Field::Other,
+                };
+
+                None
+            }
+            Event::Empty(tag) if tag.name() == b"enclosure" => {
+                if let Some(item) = &mut current {
+                    for attr in tag.attributes() {
+                        let attr = attr?;
+
+                        if attr.key == b"url" {
+                            item.enclosure = Some(attr.unescape_and_decode_value(&reader)?);
+                        }
+                    }
+                }
+
+                None
+            }
+            Event::Text(text) => Some(text.unescape_and_decode(&reader)?),
+            Event::CData(data) => Some(String::from_utf8_lossy(&data.into_inner()).into_owned()),
+            Event::End(tag) => {
+                field = Field::Other;
+
+                if tag.name() == b"item" {
+                    let item = current.take().unwrap_or_default();
+
+                    if let (Some(title), Some(link)) = (item.title, item.enclosure.or(item.link)) {
+                        items.push(FeedItem { title, link });
+                    }
+                }
+
+                None
+            }
+            Event::Eof => break,
+            _ => None,
+        };
+
+        if let (Some(text), Some(item)) = (text, &mut current) {
+            match field {
+                Field::Title => item.title = Some(text),
+                Field::Link => item.link = Some(text),
+                Field::Other => (),
+            }
+        }
+
+        buf.clear();
+    }
+
+    Ok(items)
+}
+
+/// A feed item that `parser` was able to detect episodes in.
+#[derive(Debug, PartialEq)]
+pub struct Release<'a> {
+    pub episodes: RangeInclusive<u32>,
+    pub item: &'a FeedItem,
+}
+
+/// Returns every item in `items` that `parser` can detect episodes from.
+pub fn releases<'a>(items: &'a [FeedItem], parser: &EpisodeParser) -> Vec<Release<'a>> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let parsed = parser.parse(item.title.as_str()).ok()?;
+
+            // Fractional episodes are usually recaps, which aren't worth reporting
+            if parsed.fraction.is_some() {
+                return None;
+            }
+
+            Some(Release {
+                episodes: parsed.episodes(),
+                item,
+            })
+        })
+        .collect()
+}
+
+/// Run the download command from `config` with `link` as its last argument.
+///
+/// Does nothing if no download command is set.
+pub fn hand_off(link: &str, config: &Config) -> Result<()> {
+    let (program, args) = match config.feeds.command.split_first() {
+        Some(command) => command,
+        None => return Ok(()),
+    };
+
+    Command::new(program)
+        .args(args)
+        .arg(link)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| anyhow!("failed to run feed command {}", program))
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0">
+            <channel>
+                <title>Search Results</title>
+                <link>https://example.com/</link>
+                <item>
+                    <title>[Group] Series Title - 05 [1080p].mkv</title>
+                    <link>https://example.com/download/5.torrent</link>
+                </item>
+                <item>
+                    <title><![CDATA[[Group] Series Title - 06 [1080p].mkv]]></title>
+                    <link>https://example.com/view/6</link>
+                    <enclosure url="magnet:?xt=urn:btih:abc&amp;dn=6" type="application/x-bittorrent" />
+                </item>
+                <item>
+                    <title>Series Title Batch</title>
+                    <link>https://example.com/download/batch.torrent</link>
+                </item>
+            </channel>
+        </rss>"#;
+
+    #[test]
+    fn parse_feed_items() {
+        let items = parse(FEED).unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                FeedItem {
+                    title: "[Group] Series Title - 05 [1080p].mkv".into(),
+                    link: "https://example.com/download/5.torrent".into(),
+                },
+                FeedItem {
+                    title: "[Group] Series Title - 06 [1080p].mkv".into(),
+                    link: "magnet:?xt=urn:btih:abc&dn=6".into(),
+                },
+                FeedItem {
+                    title: "Series Title Batch".into(),
+                    link: "https://example.com/download/batch.torrent".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn detect_released_episodes() {
+        let items = parse(FEED).unwrap();
+        let releases = releases(&items, &EpisodeParser::default());
+
+        let episodes = releases
+            .into_iter()
+            .map(|release| release.episodes)
+            .collect::<Vec<_>>();
+
+        assert_eq!(episodes, vec![5..=5, 6..=6]);
+    }
+}
@@ -0,0 +1,297 @@
+use crate::config::GoalConfig;
+use crate::database::Database;
+use crate::file::{FileFormat, SaveDir, SerializedFile};
+use crate::util;
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone};
+use diesel::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// What was recorded in the watch history during a single week.
+#[derive(Debug, Default, PartialEq)]
+pub struct WeekStats {
+    pub episodes: u32,
+    pub watch_time_mins: u32,
+    /// The titles of the series whose last episode was watched during the week, in the order they were finished.
+    pub completed: Vec<String>,
+}
+
+impl WeekStats {
+    /// Load everything watched during the week that starts on `start`, which should be a Monday.
+    pub fn load(db: &Database, start: NaiveDate) -> diesel::QueryResult<Self> {
+        use crate::database::schema::series_info::dsl as info;
+        use crate::database::schema::watch_history::dsl as history;
+
+        let records = history::watch_history
+            .filter(history::finished_at.ge(local_timestamp(start)))
+            .filter(history::finished_at.lt(local_timestamp(start + Duration::days(7))))
+            .order(history::finished_at.asc())
+            .select((
+                history::series_id,
+                history::title,
+                history::episode,
+                history::started_at,
+                history::finished_at,
+            ))
+            .load::<(i32, String, i16, i64, i64)>(db.conn())?
+            .into_iter()
+            .map(|(id, title, episode, started_at, finished_at)| WeekRecord {
+                series_id: id,
+                title,
+                episode,
+                watch_secs: finished_at - started_at,
+            })
+            .collect::<Vec<_>>();
+
+        let episode_counts = info::series_info
+            .select((info::id, info::episodes))
+            .load::<(i32, i16)>(db.conn())?
+            .into_iter()
+            .collect();
+
+        Ok(Self::from_records(records, &episode_counts))
+    }
+
+    /// Total up `records`, which should be in the order they were watched.
+    ///
+    /// A series counts as completed when its last episode according to `episode_counts` was watched.
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    fn from_records<I>(records: I, episode_counts: &HashMap<i32, i16>) -> Self
+    where
+        I: IntoIterator<Item = WeekRecord>,
+    {
+        let mut stats = Self::default();
+        let mut watch_secs = 0;
+        let mut completed = HashSet::new();
+
+        for record in records {
+            stats.episodes += 1;
+            watch_secs += record.watch_secs.max(0) as u64;
+
+            let is_last = matches!(episode_counts.get(&record.series_id), Some(&count) if count > 0 && record.episode >= count);
+
+            if is_last && completed.insert(record.series_id) {
+                stats.completed.push(record.title);
+            }
+        }
+
+        stats.watch_time_mins = (watch_secs / 60) as u32;
+        stats
+    }
+
+    /// Returns the progress made towards the weekly episode goal in `goals`, if one is set.
+    pub fn episode_goal(&self, goals: &GoalConfig) -> Option<GoalProgress> {
+        goals.weekly_episodes.map(|target| GoalProgress {
+            current: self.episodes,
+            target,
+        })
+    }
+
+    /// Returns the progress made towards the weekly watch time goal in `goals`, if one is set.
+    pub fn minutes_goal(&self, goals: &GoalConfig) -> Option<GoalProgress> {
+        goals.weekly_minutes.map(|target| GoalProgress {
+            current: self.watch_time_mins,
+            target,
+        })
+    }
+
+    /// Returns a message describing what was watched last week and whether each goal in `goals` was reached.
+    pub fn summary(&self, goals: &GoalConfig) -> String {
+        let mut msg = format!(
+            "last week you watched {} episode{} ({})",
+            self.episodes,
+            if self.episodes == 1 { "" } else { "s" },
+            util::hours_mins_str(self.watch_time_mins.into())
+        );
+
+        if !self.completed.is_empty() {
+            msg.push_str(" and completed ");
+            msg.push_str(&self.completed.join(", "));
+        }
+
+        if let Some(goal) = self.episode_goal(goals) {
+            let _ = write!(
+                msg,
+                "\nepisode goal: {}/{} {}",
+                goal.current,
+                goal.target,
+                goal.outcome()
+            );
+        }
+
+        if let Some(goal) = self.minutes_goal(goals) {
+            let _ = write!(
+                msg,
+                "\nwatch time goal: {}/{} {}",
+                util::hours_mins_str(goal.current.into()),
+                util::hours_mins_str(goal.target.into()),
+                goal.outcome()
+            );
+        }
+
+        msg
+    }
+}
+
+/// A single episode from the watch history.
+struct WeekRecord {
+    series_id: i32,
+    title: String,
+    episode: i16,
+    watch_secs: i64,
+}
+
+/// How close the current week is to reaching a goal.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GoalProgress {
+    pub current: u32,
+    pub target: u32,
+}
+
+impl GoalProgress {
+    /// Returns how much of the goal has been reached, up to 100.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn percent(self) -> u32 {
+        if self.target == 0 {
+            return 100;
+        }
+
+        (u64::from(self.current) * 100 / u64::from(self.target)).min(100) as u32
+    }
+
+    pub fn is_reached(self) -> bool {
+        self.current >= self.target
+    }
+
+    fn outcome(self) -> &'static str {
+        if self.is_reached() {
+            "(reached)"
+        } else {
+            "(missed)"
+        }
+    }
+}
+
+/// Returns the Monday that starts the week `date` is in.
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday().into())
+}
+
+/// Returns the UTC timestamp of midnight on `date` in local time.
+fn local_timestamp(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+        .map_or(0, |time| time.timestamp())
+}
+
+/// The start of the last week that was summarized, so each week is only summarized once.
+#[derive(Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct LastWeeklySummary(Option<i32>);
+
+impl LastWeeklySummary {
+    /// Returns the start of the week before `today` if it hasn't been summarized yet, and remembers that it has been.
+    ///
+    /// The first week seen is never summarized, so a summary isn't shown as soon as the program starts being used.
+    pub fn take_due(&mut self, today: NaiveDate) -> Option<NaiveDate> {
+        let last_week = week_start(today) - Duration::days(7);
+        let day = last_week.num_days_from_ce();
+
+        match self.0 {
+            Some(summarized) if summarized >= day => None,
+            Some(_) => {
+                self.0 = Some(day);
+                Some(last_week)
+            }
+            None => {
+                self.0 = Some(day);
+                None
+            }
+        }
+    }
+}
+
+impl SerializedFile for LastWeeklySummary {
+    fn filename() -> &'static str {
+        "weekly_summary"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Binary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn record(series_id: i32, title: &str, episode: i16) -> WeekRecord {
+        WeekRecord {
+            series_id,
+            title: title.into(),
+            episode,
+            watch_secs: 24 * 60,
+        }
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        assert_eq!(week_start(date(2022, 3, 16)), date(2022, 3, 14));
+        assert_eq!(week_start(date(2022, 3, 14)), date(2022, 3, 14));
+        assert_eq!(week_start(date(2022, 3, 20)), date(2022, 3, 14));
+    }
+
+    #[test]
+    fn week_totals() {
+        let episode_counts = [(1, 2), (2, 12), (3, 0)].iter().copied().collect();
+
+        let records = vec![
+            record(1, "Finished", 1),
+            record(1, "Finished", 2),
+            // Rewatching the last episode doesn't complete the series again
+            record(1, "Finished", 2),
+            record(2, "Airing", 5),
+            // Series with an unknown number of episodes can't be completed
+            record(3, "Unknown", 1),
+        ];
+
+        let stats = WeekStats::from_records(records, &episode_counts);
+
+        assert_eq!(stats.episodes, 5);
+        assert_eq!(stats.watch_time_mins, 5 * 24);
+        assert_eq!(stats.completed, vec!["Finished".to_string()]);
+
+        let goals = GoalConfig {
+            weekly_episodes: Some(4),
+            weekly_minutes: Some(240),
+            weekly_summary: true,
+        };
+
+        assert_eq!(
+            stats.summary(&goals),
+            "last week you watched 5 episodes (2h 0m) and completed Finished\nepisode goal: 5/4 (reached)\nwatch time goal: 2h 0m/4h 0m (missed)"
+        );
+
+        assert_eq!(stats.minutes_goal(&goals).unwrap().percent(), 50);
+    }
+
+    #[test]
+    fn summary_due_once_a_week() {
+        let mut last = LastWeeklySummary::default();
+
+        // Nothing is summarized the first time
+        assert_eq!(last.take_due(date(2022, 3, 16)), None);
+        assert_eq!(last.take_due(date(2022, 3, 20)), None);
+
+        assert_eq!(last.take_due(date(2022, 3, 21)), Some(date(2022, 3, 14)));
+        assert_eq!(last.take_due(date(2022, 3, 22)), None);
+    }
+}
@@ -0,0 +1,167 @@
+use super::SeriesData;
+use crate::database::schema::watch_history;
+use crate::database::Database;
+use crate::util;
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use diesel::prelude::*;
+use std::collections::HashMap;
+use std::process::ExitStatus;
+
+/// A single episode that was watched to completion.
+#[derive(Debug, Queryable, Insertable)]
+#[table_name = "watch_history"]
+pub struct WatchRecord {
+    pub series_id: i32,
+    /// The preferred title of the series at the time the episode was watched.
+    ///
+    /// This is stored so the record can still be shown after the series has been removed.
+    pub title: String,
+    pub episode: i16,
+    pub started_at: i64,
+    pub finished_at: i64,
+    /// The exit code of the player, or None if it was terminated by a signal.
+    pub exit_code: Option<i32>,
+}
+
+impl WatchRecord {
+    /// Create a record for `episode` of `series`, assuming that it finished being watched now.
+    pub fn new(
+        series: &SeriesData,
+        episode: i16,
+        started_at: DateTime<Utc>,
+        status: ExitStatus,
+    ) -> Self {
+        Self {
+            series_id: series.config.id,
+            title: series.info.title_preferred.clone(),
+            episode,
+            started_at: started_at.timestamp(),
+            finished_at: Utc::now().timestamp(),
+            exit_code: status.code(),
+        }
+    }
+
+    pub fn save(&self, db: &Database) -> diesel::QueryResult<usize> {
+        use crate::database::schema::watch_history::dsl::watch_history;
+
+        diesel::insert_into(watch_history)
+            .values(self)
+            .execute(db.conn())
+    }
+
+    /// Load the `limit` most recently watched episodes, starting with the newest.
+    pub fn recent(db: &Database, limit: i64) -> diesel::QueryResult<Vec<Self>> {
+        use crate::database::schema::watch_history::dsl::{
+            episode, exit_code, finished_at, series_id, started_at, title, watch_history,
+        };
+
+        watch_history
+            .select((
+                series_id,
+                title,
+                episode,
+                started_at,
+                finished_at,
+                exit_code,
+            ))
+            .order(finished_at.desc())
+            .limit(limit)
+            .load(db.conn())
+    }
+
+    /// Load the UTC timestamp an episode of each series in the history was last watched at.
+    pub fn last_watched_times(db: &Database) -> diesel::QueryResult<HashMap<i32, i64>> {
+        use crate::database::schema::watch_history::dsl::{finished_at, series_id, watch_history};
+
+        let records = watch_history
+            .select((series_id, finished_at))
+            .load::<(i32, i64)>(db.conn())?;
+
+        let mut times = HashMap::with_capacity(records.len());
+
+        for (id, finished) in records {
+            let latest = times.entry(id).or_insert(finished);
+            *latest = finished.max(*latest);
+        }
+
+        Ok(times)
+    }
+
+    /// Load the local date every episode of the series with `id` finished on, for episodes watched since `since`.
+    pub fn finished_dates(
+        db: &Database,
+        id: i32,
+        since: DateTime<Utc>,
+    ) -> diesel::QueryResult<Vec<NaiveDate>> {
+        use crate::database::schema::watch_history::dsl::{finished_at, series_id, watch_history};
+
+        let dates = watch_history
+            .filter(series_id.eq(id))
+            .filter(finished_at.ge(since.timestamp()))
+            .select(finished_at)
+            .load::<i64>(db.conn())?
+            .into_iter()
+            .filter_map(|finished| {
+                let time = Local.timestamp_opt(finished, 0).single()?;
+                Some(time.naive_local().date())
+            })
+            .collect();
+
+        Ok(dates)
+    }
+
+    pub fn watch_time(&self) -> Duration {
+        Duration::seconds(self.finished_at - self.started_at)
+    }
+
+    /// Returns the local time the episode finished in a short, human readable format.
+    pub fn finished_at_str(&self) -> String {
+        match Local.timestamp_opt(self.finished_at, 0).single() {
+            Some(time) => time.format("%Y-%m-%d %H:%M").to_string(),
+            None => "unknown".into(),
+        }
+    }
+
+    /// Returns the time spent watching the episode in a short, human readable format.
+    pub fn watch_time_str(&self) -> String {
+        util::hours_mins_str(self.watch_time().num_minutes())
+    }
+
+    /// Returns the exit status of the player in a short, human readable format.
+    pub fn exit_status_str(&self) -> String {
+        match self.exit_code {
+            Some(0) => "ok".into(),
+            Some(code) => format!("exit code {}", code),
+            None => "killed".into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(watch_secs: i64, exit_code: Option<i32>) -> WatchRecord {
+        WatchRecord {
+            series_id: 1,
+            title: "Test".into(),
+            episode: 1,
+            started_at: 0,
+            finished_at: watch_secs,
+            exit_code,
+        }
+    }
+
+    #[test]
+    fn watch_time_formatting() {
+        assert_eq!(record(20 * 60 + 30, Some(0)).watch_time_str(), "20m");
+        assert_eq!(record(84 * 60, Some(0)).watch_time_str(), "1h 24m");
+    }
+
+    #[test]
+    fn exit_status_formatting() {
+        assert_eq!(record(0, Some(0)).exit_status_str(), "ok");
+        assert_eq!(record(0, Some(3)).exit_status_str(), "exit code 3");
+        assert_eq!(record(0, None).exit_status_str(), "killed");
+    }
+}
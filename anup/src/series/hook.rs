@@ -0,0 +1,137 @@
+use super::Series;
+use crate::config::{Config, HookConfig};
+use anyhow::{anyhow, Context, Result};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// A point during playback that a command can be run at.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Hook {
+    BeforePlay,
+    AfterPlay,
+    EpisodeCompleted,
+    SeriesCompleted,
+}
+
+impl Hook {
+    /// Returns the name of the config field the hook is set with.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::BeforePlay => "before_play",
+            Self::AfterPlay => "after_play",
+            Self::EpisodeCompleted => "on_episode_completed",
+            Self::SeriesCompleted => "on_series_completed",
+        }
+    }
+
+    fn command(self, config: &HookConfig) -> &[String] {
+        match self {
+            Self::BeforePlay => &config.before_play,
+            Self::AfterPlay => &config.after_play,
+            Self::EpisodeCompleted => &config.on_episode_completed,
+            Self::SeriesCompleted => &config.on_series_completed,
+        }
+    }
+
+    /// Run the command set for this hook in `config` for `episode` of `series`.
+    ///
+    /// The command is left running in the background. Does nothing if no command is set.
+    pub fn run(self, series: &Series, episode: u32, config: &Config) -> Result<()> {
+        let (program, args) = match self.command(&config.hooks).split_first() {
+            Some(command) => command,
+            None => return Ok(()),
+        };
+
+        Command::new(program)
+            .args(args)
+            .envs(HookEpisode::new(series, episode, config).env_vars(self))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| anyhow!("failed to run {} hook {}", self.name(), program))
+            .map(|_| ())
+    }
+}
+
+/// The episode a hook is being run for.
+struct HookEpisode {
+    id: i32,
+    nickname: String,
+    title: String,
+    episode: u32,
+    episodes: i16,
+    /// The path to the file of the episode, if it's on disk.
+    path: Option<PathBuf>,
+}
+
+impl HookEpisode {
+    fn new(series: &Series, episode: u32, config: &Config) -> Self {
+        Self {
+            id: series.data.config.id,
+            nickname: series.data.config.nickname.clone(),
+            title: series.data.info.title_preferred.clone(),
+            episode,
+            episodes: series.data.info.episodes,
+            path: series.episode_path(episode, config),
+        }
+    }
+
+    /// Returns the environment variables a `hook` command is given.
+    fn env_vars(&self, hook: Hook) -> Vec<(&'static str, String)> {
+        let path = self
+            .path
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        vec![
+            ("ANUP_HOOK", hook.name().into()),
+            ("ANUP_SERIES", self.nickname.clone()),
+            ("ANUP_TITLE", self.title.clone()),
+            ("ANUP_ID", self.id.to_string()),
+            ("ANUP_EPISODE", self.episode.to_string()),
+            ("ANUP_EPISODES", self.episodes.to_string()),
+            ("ANUP_PATH", path),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hook_env_vars() {
+        let episode = HookEpisode {
+            id: 20,
+            nickname: "series".into(),
+            title: "Series Title".into(),
+            episode: 3,
+            episodes: 12,
+            path: Some("/home/user/anime/series/03.mkv".into()),
+        };
+
+        assert_eq!(
+            episode.env_vars(Hook::EpisodeCompleted),
+            vec![
+                ("ANUP_HOOK", "on_episode_completed".to_string()),
+                ("ANUP_SERIES", "series".into()),
+                ("ANUP_TITLE", "Series Title".into()),
+                ("ANUP_ID", "20".into()),
+                ("ANUP_EPISODE", "3".into()),
+                ("ANUP_EPISODES", "12".into()),
+                ("ANUP_PATH", "/home/user/anime/series/03.mkv".into()),
+            ]
+        );
+
+        let episode = HookEpisode {
+            path: None,
+            ..episode
+        };
+
+        let vars = episode.env_vars(Hook::BeforePlay);
+        assert!(vars.contains(&("ANUP_HOOK", "before_play".into())));
+        assert!(vars.contains(&("ANUP_PATH", String::new())));
+    }
+}
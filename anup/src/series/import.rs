@@ -0,0 +1,479 @@
+use super::config::SeriesConfig;
+use super::entry::SeriesEntry;
+use super::info::SeriesInfo;
+use super::{SeriesData, SeriesParams, SeriesPath};
+use crate::config::Config;
+use crate::database::Database;
+use anime::local::EpisodeParser;
+use anime::remote::{SeriesDate, SeriesEntry as RemoteEntry, SeriesInfo as RemoteInfo, Status};
+use anyhow::{anyhow, Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The episode length given to imported series until their info is refreshed from the remote service.
+const DEFAULT_EPISODE_LENGTH_MINS: i16 = 24;
+
+/// A list entry read from an export file.
+#[derive(Debug)]
+pub struct ImportedEntry {
+    pub id: i32,
+    pub title: String,
+    pub episodes: i16,
+    pub watched_episodes: i16,
+    pub score: Option<u8>,
+    pub status: Status,
+    pub times_rewatched: i16,
+    pub start_date: Option<SeriesDate>,
+    pub end_date: Option<SeriesDate>,
+}
+
+impl ImportedEntry {
+    fn to_entry(&self) -> SeriesEntry {
+        let mut entry = SeriesEntry::from(anime::remote::SeriesEntry {
+            id: self.id as u32,
+            watched_eps: self.watched_episodes.max(0) as u32,
+            score: self.score,
+            status: self.status,
+            times_rewatched: self.times_rewatched.max(0) as u32,
+            start_date: self.start_date,
+            end_date: self.end_date,
+            updated_at: None,
+        });
+
+        entry.set_needs_sync();
+        entry
+    }
+}
+
+/// Parse every entry in a MyAnimeList XML export, or an AniList JSON export / `MediaListCollection` query response.
+///
+/// Scores in JSON files are expected to be out of 100.
+pub fn parse(contents: &str) -> Result<Vec<ImportedEntry>> {
+    if contents.trim_start().starts_with('<') {
+        parse_mal_xml(contents).context("failed to parse MyAnimeList XML")
+    } else {
+        parse_anilist_json(contents).context("failed to parse AniList JSON")
+    }
+}
+
+fn parse_mal_xml(xml: &str) -> Result<Vec<ImportedEntry>> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut entries = Vec::new();
+    let mut fields: Option<Vec<(String, String)>> = None;
+    let mut field = None;
+    let mut buf = Vec::new();
+
+    loop {
+        let text = match reader.read_event(&mut buf)? {
+            Event::Start(tag) => {
+                if tag.name() == b"anime" {
+                    fields = Some(Vec::new());
+                } else {
+                    field = Some(String::from_utf8_lossy(tag.name()).into_owned());
+                }
+
+                None
+            }
+            Event::Text(text) => Some(text.unescape_and_decode(&reader)?),
+            Event::CData(data) => Some(String::from_utf8_lossy(&data.into_inner()).into_owned()),
+            Event::End(tag) => {
+                field = None;
+
+                if tag.name() == b"anime" {
+                    if let Some(fields) = fields.take() {
+                        entries.push(mal_entry(&fields)?);
+                    }
+                }
+
+                None
+            }
+            Event::Eof => break,
+            _ => None,
+        };
+
+        if let (Some(text), Some(name), Some(fields)) = (text, &field, &mut fields) {
+            fields.push((name.clone(), text));
+        }
+
+        buf.clear();
+    }
+
+    Ok(entries)
+}
+
+fn mal_entry(fields: &[(String, String)]) -> Result<ImportedEntry> {
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let number = |name: &str| field(name).and_then(|value| value.parse::<i16>().ok());
+
+    // Dates that haven't been set are written as 0000-00-00
+    let date = |name: &str| {
+        field(name)
+            .and_then(SeriesDate::parse_ymd)
+            .filter(|date| date.year > 0)
+    };
+
+    let id = field("series_animedb_id")
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| anyhow!("entry is missing its series_animedb_id"))?;
+
+    let rewatching = number("my_rewatching").unwrap_or(0) > 0;
+
+    // MyAnimeList exports use the status name, but some tools write its number instead
+    let status = match field("my_status").unwrap_or_default() {
+        "Watching" | "1" => Status::Watching,
+        "Completed" | "2" if rewatching => Status::Rewatching,
+        "Completed" | "2" => Status::Completed,
+        "On-Hold" | "3" => Status::OnHold,
+        "Dropped" | "4" => Status::Dropped,
+        "Plan to Watch" | "6" => Status::PlanToWatch,
+        status => return Err(anyhow!("unknown status {} for series {}", status, id)),
+    };
+
+    let score = number("my_score")
+        .filter(|&score| score > 0)
+        .map(|score| (score.min(10) * 10) as u8);
+
+    Ok(ImportedEntry {
+        id,
+        title: field("series_title").unwrap_or_default().to_string(),
+        episodes: number("series_episodes").unwrap_or(0),
+        watched_episodes: number("my_watched_episodes").unwrap_or(0),
+        score,
+        status,
+        times_rewatched: number("my_times_watched").unwrap_or(0),
+        start_date: date("my_start_date"),
+        end_date: date("my_finish_date"),
+    })
+}
+
+fn parse_anilist_json(json: &str) -> Result<Vec<ImportedEntry>> {
+    let value: Value = serde_json::from_str(json)?;
+
+    // A MediaListCollection query response nests its entries inside of each list
+    let collection = value
+        .pointer("/data/MediaListCollection")
+        .or_else(|| value.get("MediaListCollection"))
+        .unwrap_or(&value);
+
+    let entries: Vec<AniListEntry> = match collection.get("lists") {
+        Some(Value::Array(lists)) => lists
+            .iter()
+            .filter_map(|list| list.get("entries"))
+            .map(Vec::<AniListEntry>::deserialize)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect(),
+        _ => Vec::deserialize(collection)?,
+    };
+
+    entries
+        .into_iter()
+        .map(AniListEntry::into_imported)
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AniListEntry {
+    media_id: Option<i32>,
+    media: Option<AniListMedia>,
+    title: Option<String>,
+    status: String,
+    score: Option<f32>,
+    progress: Option<i16>,
+    repeat: Option<i16>,
+    started_at: Option<FuzzyDate>,
+    completed_at: Option<FuzzyDate>,
+}
+
+impl AniListEntry {
+    fn into_imported(self) -> Result<ImportedEntry> {
+        let media_id = self.media.as_ref().map(|media| media.id);
+
+        let id = self
+            .media_id
+            .or(media_id)
+            .ok_or_else(|| anyhow!("entry is missing its media ID"))?;
+
+        let status = match self.status.as_str() {
+            "CURRENT" => Status::Watching,
+            "COMPLETED" => Status::Completed,
+            "PAUSED" => Status::OnHold,
+            "DROPPED" => Status::Dropped,
+            "PLANNING" => Status::PlanToWatch,
+            "REPEATING" => Status::Rewatching,
+            status => return Err(anyhow!("unknown status {} for series {}", status, id)),
+        };
+
+        let (media_title, episodes) = match self.media {
+            Some(media) => (
+                media
+                    .title
+                    .and_then(|title| title.user_preferred.or(title.romaji)),
+                media.episodes,
+            ),
+            None => (None, None),
+        };
+
+        Ok(ImportedEntry {
+            id,
+            title: self.title.or(media_title).unwrap_or_default(),
+            episodes: episodes.unwrap_or(0),
+            watched_episodes: self.progress.unwrap_or(0),
+            score: self
+                .score
+                .filter(|&score| score > 0.0)
+                .map(|score| score.min(100.0).round() as u8),
+            status,
+            times_rewatched: self.repeat.unwrap_or(0),
+            start_date: self.started_at.and_then(FuzzyDate::into_date),
+            end_date: self.completed_at.and_then(FuzzyDate::into_date),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct AniListMedia {
+    id: i32,
+    title: Option<AniListTitle>,
+    episodes: Option<i16>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AniListTitle {
+    user_preferred: Option<String>,
+    romaji: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FuzzyDate {
+    year: Option<u16>,
+    month: Option<u8>,
+    day: Option<u8>,
+}
+
+impl FuzzyDate {
+    fn into_date(self) -> Option<SeriesDate> {
+        Some(SeriesDate::from_ymd(self.year?, self.month?, self.day?))
+    }
+}
+
+/// The outcome of importing a list.
+#[derive(Default)]
+pub struct ImportSummary {
+    pub imported: usize,
+    /// The titles of series that were already in the program.
+    pub skipped: Vec<String>,
+    /// The nicknames of imported series that no folder on disk could be found for.
+    pub unmatched: Vec<String>,
+}
+
+/// Add every imported entry to the program as a series, binding each one to the closest matching folder in the series directory.
+///
+/// Series without a matching folder are still added, but need to have their path set in the TUI before they can be played.
+pub fn import(
+    entries: Vec<ImportedEntry>,
+    config: &Config,
+    db: &Database,
+) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+
+    for imported in entries {
+        let title = if imported.title.is_empty() {
+            format!("series {}", imported.id)
+        } else {
+            imported.title.clone()
+        };
+
+        if SeriesConfig::id_exists(db, imported.id).is_some() {
+            summary.skipped.push(title);
+            continue;
+        }
+
+        let path = SeriesPath::closest_matching(&title, config).ok();
+        let is_unmatched = path.is_none();
+
+        let info = match SeriesInfo::load(db, imported.id) {
+            Ok(info) => info,
+            Err(diesel::NotFound) => SeriesInfo {
+                id: imported.id,
+                title_preferred: title.clone(),
+                title_romaji: title.clone(),
+                episodes: imported.episodes,
+                episode_length_mins: DEFAULT_EPISODE_LENGTH_MINS,
+                title_english: None,
+                title_native: None,
+            },
+            Err(err) => return Err(err.into()),
+        };
+
+        let nickname = add_series(&title, path, info, imported.to_entry(), db)?;
+        summary.imported += 1;
+
+        if is_unmatched {
+            summary.unmatched.push(nickname);
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Add every entry of the user's list on the remote service to the program as a series.
+///
+/// Series are left without a folder, since lists tend to be full of series that were watched long ago and aren't on disk anymore.
+/// Their path can be set in the TUI once they're going to be watched.
+pub fn import_remote(list: Vec<(RemoteInfo, RemoteEntry)>, db: &Database) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+
+    for (info, entry) in list {
+        let info = SeriesInfo::from(info);
+        let title = info.title_preferred.clone();
+
+        if SeriesConfig::id_exists(db, info.id).is_some() {
+            summary.skipped.push(title);
+            continue;
+        }
+
+        add_series(&title, None, info, SeriesEntry::from(entry), db)?;
+        summary.imported += 1;
+    }
+
+    Ok(summary)
+}
+
+/// Add the series described by `info` to the program with a nickname generated from its `title`, and save it.
+///
+/// Returns the nickname the series was given.
+fn add_series(
+    title: &str,
+    path: Option<SeriesPath>,
+    info: SeriesInfo,
+    entry: SeriesEntry,
+    db: &Database,
+) -> Result<String> {
+    let mut nickname =
+        super::generate_nickname(title).unwrap_or_else(|| format!("series_{}", info.id));
+
+    let mut params = SeriesParams::new(
+        nickname.clone(),
+        path.unwrap_or_else(SeriesPath::unset),
+        EpisodeParser::default(),
+    );
+
+    // Different series can generate the same nickname
+    if SeriesConfig::exists(db, info.id, &params).is_some() {
+        nickname = format!("{}_{}", nickname, info.id);
+        params.name.clone_from(&nickname);
+    }
+
+    let data = SeriesData {
+        config: SeriesConfig::new(info.id, params, db)?,
+        entry,
+        info,
+        aliases: Vec::new(),
+        synonyms: Vec::new(),
+        merged_seasons: Vec::new(),
+        tags: Vec::new(),
+        prequel_episodes: 0,
+    };
+
+    data.save(db)
+        .with_context(|| anyhow!("failed to save {}", title))?;
+
+    Ok(nickname)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mal_export() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" ?>
+            <myanimelist>
+                <myinfo><user_export_type>1</user_export_type></myinfo>
+                <anime>
+                    <series_animedb_id>20</series_animedb_id>
+                    <series_title><![CDATA[Series & "Title"]]></series_title>
+                    <series_episodes>12</series_episodes>
+                    <my_watched_episodes>3</my_watched_episodes>
+                    <my_start_date>2021-04-05</my_start_date>
+                    <my_finish_date>0000-00-00</my_finish_date>
+                    <my_score>9</my_score>
+                    <my_status>Completed</my_status>
+                    <my_times_watched>1</my_times_watched>
+                    <my_rewatching>1</my_rewatching>
+                </anime>
+            </myanimelist>"#;
+
+        let entries = parse(xml).unwrap();
+        assert_eq!(entries.len(), 1);
+
+        let entry = &entries[0];
+        assert_eq!(entry.id, 20);
+        assert_eq!(entry.title, "Series & \"Title\"");
+        assert_eq!(entry.episodes, 12);
+        assert_eq!(entry.score, Some(90));
+        assert_eq!(entry.status, Status::Rewatching);
+        assert_eq!(entry.start_date, Some(SeriesDate::from_ymd(2021, 4, 5)));
+        assert_eq!(entry.end_date, None);
+    }
+
+    #[test]
+    fn parse_anilist_exports() {
+        let exported = r#"[{
+            "mediaId": 20,
+            "title": "Series Title",
+            "status": "CURRENT",
+            "score": 85,
+            "progress": 3,
+            "repeat": 0,
+            "startedAt": { "year": 2021, "month": 4, "day": 5 },
+            "completedAt": { "year": null, "month": null, "day": null }
+        }]"#;
+
+        let query = r#"{
+            "data": {
+                "MediaListCollection": {
+                    "lists": [{
+                        "entries": [{
+                            "media": { "id": 20, "title": { "userPreferred": "Series Title" }, "episodes": 12 },
+                            "status": "CURRENT",
+                            "score": 85,
+                            "progress": 3,
+                            "repeat": 0,
+                            "startedAt": { "year": 2021, "month": 4, "day": 5 },
+                            "completedAt": { "year": null, "month": null, "day": null }
+                        }]
+                    }]
+                }
+            }
+        }"#;
+
+        for json in [exported, query] {
+            let entries = parse(json).unwrap();
+            assert_eq!(entries.len(), 1);
+
+            let entry = &entries[0];
+            assert_eq!(entry.id, 20);
+            assert_eq!(entry.title, "Series Title");
+            assert_eq!(entry.score, Some(85));
+            assert_eq!(entry.status, Status::Watching);
+            assert_eq!(entry.watched_episodes, 3);
+            assert_eq!(entry.start_date, Some(SeriesDate::from_ymd(2021, 4, 5)));
+            assert_eq!(entry.end_date, None);
+        }
+    }
+}
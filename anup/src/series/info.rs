@@ -1,12 +1,16 @@
 use super::SeriesPath;
+use crate::config::{Config, TitleLanguage};
 use crate::database::schema::series_info;
 use crate::database::Database;
 use anime::remote::{Remote, RemoteService, SeriesID, SeriesInfo as RemoteInfo};
 use anyhow::Result;
 use diesel::prelude::*;
 use std::borrow::Cow;
+use std::mem;
+use std::result;
 
 #[derive(Clone, Queryable, Insertable)]
+#[cfg_attr(test, derive(Debug))]
 #[table_name = "series_info"]
 pub struct SeriesInfo {
     pub id: i32,
@@ -14,9 +18,23 @@ pub struct SeriesInfo {
     pub title_romaji: String,
     pub episodes: i16,
     pub episode_length_mins: i16,
+    pub title_english: Option<String>,
+    pub title_native: Option<String>,
 }
 
 impl SeriesInfo {
+    /// Returns the title of the series in `language`, or its preferred title if it doesn't have one.
+    pub fn title(&self, language: TitleLanguage) -> &str {
+        let title = match language {
+            TitleLanguage::Preferred => None,
+            TitleLanguage::Romaji => Some(&self.title_romaji),
+            TitleLanguage::English => self.title_english.as_ref(),
+            TitleLanguage::Native => self.title_native.as_ref(),
+        };
+
+        title.unwrap_or(&self.title_preferred)
+    }
+
     pub fn load(db: &Database, info_id: i32) -> diesel::QueryResult<Self> {
         use crate::database::schema::series_info::dsl::{id, series_info};
 
@@ -31,39 +49,184 @@ impl SeriesInfo {
             .execute(db.conn())
     }
 
-    pub fn from_remote(sel: InfoSelector, remote: &Remote) -> Result<InfoResult> {
+    /// Look up the series described by `sel` on the remote service.
+    ///
+    /// When searching by name, the results are only chosen from automatically if the closest one matches well enough for `options`.
+    pub async fn from_remote(
+        sel: InfoSelector,
+        remote: &Remote,
+        options: MatchOptions,
+    ) -> Result<InfoResult> {
         match sel {
-            InfoSelector::ID(id) => Self::from_remote_by_id(id, remote).map(InfoResult::Confident),
-            InfoSelector::Name(name) => Self::from_remote_by_name(name, remote),
+            InfoSelector::ID(id) => Self::from_remote_by_id(id, remote)
+                .await
+                .map(InfoResult::Confident),
+            InfoSelector::Name(name) => Self::from_remote_by_name(name, remote, options).await,
+            InfoSelector::Season(name, season) => {
+                Self::from_remote_by_season(name, season, remote, options).await
+            }
         }
     }
 
-    pub fn from_remote_by_id(id: SeriesID, remote: &Remote) -> Result<Self> {
+    pub async fn from_remote_by_id(id: SeriesID, remote: &Remote) -> Result<Self> {
         remote
             .search_info_by_id(id)
+            .await
             .map(Into::into)
             .map_err(Into::into)
     }
 
-    pub fn from_remote_by_name<S>(name: S, remote: &Remote) -> Result<InfoResult>
+    pub async fn from_remote_by_name<S>(
+        name: S,
+        remote: &Remote,
+        options: MatchOptions,
+    ) -> Result<InfoResult>
+    where
+        S: Into<String>,
+    {
+        match Self::search_remote(name, remote, options).await? {
+            Ok(info) => Ok(InfoResult::Confident(info.into())),
+            Err(candidates) => Ok(InfoResult::Unconfident(candidates)),
+        }
+    }
+
+    /// Look up a later `season` of the series with `name` by following the sequels of the first season.
+    ///
+    /// If the sequels run out before reaching `season`, every season that was found is returned to choose from.
+    pub async fn from_remote_by_season<S>(
+        name: S,
+        season: u32,
+        remote: &Remote,
+        options: MatchOptions,
+    ) -> Result<InfoResult>
     where
         S: Into<String>,
     {
-        const MIN_CONFIDENCE: f32 = 0.85;
+        let name = name.into();
+
+        let mut current = match Self::search_remote(name.as_str(), remote, options).await? {
+            Ok(info) => info,
+            Err(candidates) => return Ok(InfoResult::Unconfident(candidates)),
+        };
+
+        let mut seasons = Vec::new();
+
+        for _ in 1..season {
+            let sequel = match current.direct_sequel() {
+                Some(sequel) => sequel.id,
+                None => {
+                    seasons.push(current);
+
+                    let candidates = seasons
+                        .into_iter()
+                        .map(|info| Candidate::new(info, &name, options))
+                        .collect();
+
+                    return Ok(InfoResult::Unconfident(candidates));
+                }
+            };
+
+            let next = remote.search_info_by_id(sequel).await?;
+            seasons.push(mem::replace(&mut current, next));
+        }
+
+        Ok(InfoResult::Confident(current.into()))
+    }
+
+    /// Search for `name` on the remote service, and return every result from the closest match to the furthest.
+    pub async fn search_ranked<S>(
+        name: S,
+        remote: &Remote,
+        options: MatchOptions,
+    ) -> Result<Vec<Candidate>>
+    where
+        S: Into<String>,
+    {
+        let name = name.into();
+        let results = remote.search_info_by_name(&name).await?;
+
+        Ok(rank(results, &name, options)
+            .into_iter()
+            .map(Candidate::from)
+            .collect())
+    }
 
+    /// Search for `name` on the remote service, and return its closest match.
+    ///
+    /// If no result is close enough, every result is returned instead from the closest match to the furthest.
+    async fn search_remote<S>(
+        name: S,
+        remote: &Remote,
+        options: MatchOptions,
+    ) -> Result<result::Result<RemoteInfo, Vec<Candidate>>>
+    where
+        S: Into<String>,
+    {
         let name = name.into();
-        let mut results = remote.search_info_by_name(&name)?;
-        let found =
-            RemoteInfo::closest_match(name, MIN_CONFIDENCE, results.iter().map(Cow::Borrowed));
-
-        match found {
-            Some((best_match, _)) => {
-                let info = results.swap_remove(best_match).into();
-                Ok(InfoResult::Confident(info))
+        let results = remote.search_info_by_name(&name).await?;
+        let mut ranked = rank(results, &name, options);
+
+        match ranked.first() {
+            Some((_, confidence)) if *confidence >= options.min_confidence => {
+                Ok(Ok(ranked.swap_remove(0).0))
             }
-            None => Ok(InfoResult::Unconfident(
-                results.into_iter().map(Into::into).collect(),
-            )),
+            _ => Ok(Err(ranked.into_iter().map(Candidate::from).collect())),
+        }
+    }
+}
+
+/// Pair each of the `results` with how closely its title matches `name`, from the closest match to the furthest.
+fn rank(results: Vec<RemoteInfo>, name: &str, options: MatchOptions) -> Vec<(RemoteInfo, f32)> {
+    let name = name.to_ascii_lowercase();
+
+    let mut ranked = results
+        .into_iter()
+        .map(|info| {
+            let confidence = info.title_confidence(&name, options.acronyms);
+            (info, confidence)
+        })
+        .collect::<Vec<_>>();
+
+    ranked.sort_by(|(_, x), (_, y)| y.total_cmp(x));
+    ranked
+}
+
+/// How titles are matched when looking up a series by name.
+#[derive(Copy, Clone)]
+pub struct MatchOptions {
+    /// Whether names are also matched against the acronyms of titles.
+    pub acronyms: bool,
+    /// The confidence the closest match needs to be chosen without asking.
+    pub min_confidence: f32,
+}
+
+impl From<&Config> for MatchOptions {
+    fn from(config: &Config) -> Self {
+        Self {
+            acronyms: config.match_title_acronyms,
+            min_confidence: config.match_confidence.as_multiplier(),
+        }
+    }
+}
+
+/// A series found when searching by name, along with how closely its title matches the name.
+pub struct Candidate {
+    pub info: SeriesInfo,
+    pub confidence: f32,
+}
+
+impl Candidate {
+    fn new(info: RemoteInfo, name: &str, options: MatchOptions) -> Self {
+        let confidence = info.title_confidence(&name.to_ascii_lowercase(), options.acronyms);
+        Self::from((info, confidence))
+    }
+}
+
+impl From<(RemoteInfo, f32)> for Candidate {
+    fn from((info, confidence): (RemoteInfo, f32)) -> Self {
+        Self {
+            info: info.into(),
+            confidence,
         }
     }
 }
@@ -77,6 +240,8 @@ impl From<anime::remote::SeriesInfo> for SeriesInfo {
             title_romaji: value.title.romaji,
             episodes: value.episodes as i16,
             episode_length_mins: value.episode_length as i16,
+            title_english: value.title.english,
+            title_native: value.title.native,
         }
     }
 }
@@ -84,22 +249,33 @@ impl From<anime::remote::SeriesInfo> for SeriesInfo {
 #[allow(variant_size_differences)]
 pub enum InfoSelector {
     Name(String),
+    /// A season after the first of the series with the name.
+    Season(String, u32),
     ID(SeriesID),
 }
 
 impl InfoSelector {
+    /// Select the series by the title in the name of the folder at `path`, or by `name` if it doesn't have one.
+    ///
+    /// When the folder name marks the season it's for, like `S2` or `2nd Season`, that season of the series is selected.
     pub fn from_path_or_name<'a, P, S>(path: P, name: S) -> Self
     where
         P: Into<Cow<'a, SeriesPath>>,
         S: Into<String>,
     {
-        use anime::local::detect::dir;
+        use anime::local::detect::dir::{self, SeasonTitle};
         let path = path.into();
-        dir::parse_title(path.inner()).map_or_else(|| Self::Name(name.into()), Self::Name)
+
+        match dir::parse_season_title(path.inner()) {
+            Some(SeasonTitle { title, season }) if season > 1 => Self::Season(title, season),
+            Some(SeasonTitle { title, .. }) => Self::Name(title),
+            None => Self::Name(name.into()),
+        }
     }
 }
 
 pub enum InfoResult {
     Confident(SeriesInfo),
-    Unconfident(Vec<SeriesInfo>),
+    /// Every series found, from the closest match to the furthest.
+    Unconfident(Vec<Candidate>),
 }
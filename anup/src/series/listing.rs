@@ -0,0 +1,235 @@
+use super::entry;
+use super::SeriesData;
+use crate::config::Config;
+use anime::remote::Status;
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// A format the series that have been added can be printed in.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ListFormat {
+    /// One line per series meant to be read by a person.
+    Text,
+    /// A JSON array with an object for each series.
+    Json,
+    /// Tab separated values with a header row.
+    Tsv,
+}
+
+impl ListFormat {
+    /// Render `series` in this format.
+    pub fn render(self, series: &[ListedSeries]) -> Result<String> {
+        match self {
+            Self::Text => Ok(text(series)),
+            Self::Json => json(series),
+            Self::Tsv => Ok(tsv(series)),
+        }
+    }
+}
+
+impl Default for ListFormat {
+    fn default() -> Self {
+        Self::Text
+    }
+}
+
+impl FromStr for ListFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "tsv" => Ok(Self::Tsv),
+            _ => Err(format!(
+                "unknown list format {}, expected text, json, or tsv",
+                value
+            )),
+        }
+    }
+}
+
+/// What is printed about a series that has been added.
+pub struct ListedSeries {
+    pub id: i32,
+    pub nickname: String,
+    pub title: String,
+    pub status: Status,
+    pub watched_episodes: i16,
+    pub episodes: i16,
+    pub score: Option<i16>,
+    /// The absolute path to the folder of the series, or None if it hasn't been set.
+    pub path: Option<PathBuf>,
+}
+
+impl ListedSeries {
+    pub fn new(data: &SeriesData, config: &Config) -> Self {
+        let path = if data.config.path.is_set() {
+            Some(data.config.path.absolute(config).into_owned())
+        } else {
+            None
+        };
+
+        Self {
+            id: data.config.id,
+            nickname: data.config.nickname.clone(),
+            title: data.info.title_preferred.clone(),
+            status: data.entry.status(),
+            watched_episodes: data.entry.watched_episodes(),
+            episodes: data.info.episodes,
+            score: data.entry.score(),
+            path,
+        }
+    }
+}
+
+fn text(series: &[ListedSeries]) -> String {
+    let mut text = String::new();
+
+    for listed in series {
+        let score = listed
+            .score
+            .map_or_else(|| "-".into(), |score| score.to_string());
+
+        let path = listed
+            .path
+            .as_ref()
+            .map_or_else(|| "no folder set".into(), |path| path.display().to_string());
+
+        writeln!(
+            text,
+            "{} | {} | {} | {}/{} | score {} | {}",
+            listed.nickname,
+            listed.title,
+            listed.status,
+            listed.watched_episodes,
+            listed.episodes,
+            score,
+            path,
+        )
+        .ok();
+    }
+
+    text
+}
+
+fn json(series: &[ListedSeries]) -> Result<String> {
+    let series = series
+        .iter()
+        .map(|listed| {
+            json!({
+                "id": listed.id,
+                "nickname": listed.nickname,
+                "title": listed.title,
+                "status": entry::status_name(listed.status),
+                "progress": listed.watched_episodes,
+                "episodes": listed.episodes,
+                "score": listed.score,
+                "path": listed.path,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&series).context("failed to serialize series list")
+}
+
+fn tsv(series: &[ListedSeries]) -> String {
+    // Tabs and newlines are the only characters that can break a row apart, so they're replaced instead of escaped
+    fn field(value: &str) -> String {
+        value.replace(&['\t', '\n', '\r'][..], " ")
+    }
+
+    let mut tsv = String::from("id\tnickname\ttitle\tstatus\tprogress\tepisodes\tscore\tpath\n");
+
+    for listed in series {
+        writeln!(
+            tsv,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            listed.id,
+            field(&listed.nickname),
+            field(&listed.title),
+            entry::status_name(listed.status),
+            listed.watched_episodes,
+            listed.episodes,
+            listed
+                .score
+                .map(|score| score.to_string())
+                .unwrap_or_default(),
+            listed
+                .path
+                .as_ref()
+                .map(|path| field(&path.to_string_lossy()))
+                .unwrap_or_default(),
+        )
+        .ok();
+    }
+
+    tsv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn sample_series() -> Vec<ListedSeries> {
+        vec![
+            ListedSeries {
+                id: 20,
+                nickname: "series".into(),
+                title: "Series\tTitle".into(),
+                status: Status::Watching,
+                watched_episodes: 3,
+                episodes: 12,
+                score: Some(85),
+                path: Some("/anime/Series Title".into()),
+            },
+            ListedSeries {
+                id: 21,
+                nickname: "planned".into(),
+                title: "Planned".into(),
+                status: Status::PlanToWatch,
+                watched_episodes: 0,
+                episodes: 24,
+                score: None,
+                path: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn list_json() {
+        let json = ListFormat::Json.render(&sample_series()).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value[0]["id"], 20);
+        assert_eq!(value[0]["title"], "Series\tTitle");
+        assert_eq!(value[0]["status"], "watching");
+        assert_eq!(value[0]["progress"], 3);
+        assert_eq!(value[0]["score"], 85);
+        assert_eq!(value[0]["path"], "/anime/Series Title");
+        assert!(value[1]["score"].is_null());
+        assert!(value[1]["path"].is_null());
+    }
+
+    #[test]
+    fn list_tsv() {
+        let tsv = ListFormat::Tsv.render(&sample_series()).unwrap();
+        let mut lines = tsv.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some("id\tnickname\ttitle\tstatus\tprogress\tepisodes\tscore\tpath")
+        );
+
+        assert_eq!(
+            lines.next(),
+            Some("20\tseries\tSeries Title\twatching\t3\t12\t85\t/anime/Series Title")
+        );
+
+        assert_eq!(lines.next(), Some("21\tplanned\tPlanned\tplan\t0\t24\t\t"));
+    }
+}
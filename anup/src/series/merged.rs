@@ -0,0 +1,92 @@
+use crate::database::schema::merged_seasons;
+use crate::database::Database;
+use diesel::prelude::*;
+
+/// A season of a series whose folder has several seasons merged into it, with its episodes numbered from the first one.
+#[derive(Clone, Debug, PartialEq, Queryable, Insertable)]
+#[table_name = "merged_seasons"]
+pub struct MergedSeason {
+    /// The ID of the series that watches through the folder.
+    pub series_id: i32,
+    /// The ID of the season on the remote service.
+    pub season_id: i32,
+    /// The number of episodes in the folder that come before the first episode of the season.
+    pub episode_offset: i16,
+    pub episodes: i16,
+}
+
+/// Returns every season merged into the folder of the series with `series_id`, in the order they're watched.
+pub fn load(db: &Database, series_id: i32) -> diesel::QueryResult<Vec<MergedSeason>> {
+    use crate::database::schema::merged_seasons::dsl;
+
+    dsl::merged_seasons
+        .filter(dsl::series_id.eq(series_id))
+        .order(dsl::episode_offset)
+        .load(db.conn())
+}
+
+/// Replace the seasons merged into the folder of the series with `series_id` with `seasons`.
+///
+/// The seasons are stored under `series_id` regardless of the ID they already have, since the ID of the series changes whenever it moves on to the next season.
+pub fn replace(db: &Database, series_id: i32, seasons: &[MergedSeason]) -> diesel::QueryResult<()> {
+    use crate::database::schema::merged_seasons::dsl;
+
+    diesel::delete(dsl::merged_seasons.filter(dsl::series_id.eq(series_id))).execute(db.conn())?;
+
+    if seasons.is_empty() {
+        return Ok(());
+    }
+
+    let rows = seasons
+        .iter()
+        .map(|season| MergedSeason {
+            series_id,
+            ..season.clone()
+        })
+        .collect::<Vec<_>>();
+
+    diesel::insert_into(dsl::merged_seasons)
+        .values(&rows)
+        .execute(db.conn())?;
+
+    Ok(())
+}
+
+/// Returns the season in `seasons` with `season_id`.
+pub fn find(seasons: &[MergedSeason], season_id: i32) -> Option<&MergedSeason> {
+    seasons.iter().find(|season| season.season_id == season_id)
+}
+
+/// Returns the season that comes after the one with `season_id` in `seasons`.
+pub fn next(seasons: &[MergedSeason], season_id: i32) -> Option<&MergedSeason> {
+    let index = seasons
+        .iter()
+        .position(|season| season.season_id == season_id)?;
+
+    seasons.get(index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn season(season_id: i32, episode_offset: i16, episodes: i16) -> MergedSeason {
+        MergedSeason {
+            series_id: 1,
+            season_id,
+            episode_offset,
+            episodes,
+        }
+    }
+
+    #[test]
+    fn next_season() {
+        let seasons = [season(10, 0, 12), season(20, 12, 13), season(30, 25, 12)];
+
+        assert_eq!(next(&seasons, 10).map(|s| s.season_id), Some(20));
+        assert_eq!(next(&seasons, 20).map(|s| s.season_id), Some(30));
+        assert_eq!(next(&seasons, 30), None);
+        assert_eq!(next(&seasons, 40), None);
+        assert_eq!(find(&seasons, 20).map(|s| s.episode_offset), Some(12));
+    }
+}
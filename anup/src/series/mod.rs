@@ -1,87 +1,215 @@
+pub mod alias;
+pub mod batch;
+pub mod cache;
+pub mod changes;
+pub mod clean;
 pub mod config;
+pub mod deleted;
+pub mod doctor;
 pub mod entry;
+pub mod export;
+pub mod feed;
+pub mod goals;
+pub mod history;
+pub mod hook;
+pub mod import;
 pub mod info;
-
-use crate::config::Config;
+pub mod listing;
+pub mod merged;
+pub mod playback;
+#[cfg(feature = "ffprobe")]
+pub mod probe;
+pub mod progress;
+pub mod recommend;
+pub mod rename;
+pub mod stats;
+pub mod stream;
+pub mod subtitle;
+pub mod suggest;
+pub mod synonym;
+pub mod tag;
+
+use crate::config::{Config, FilenameConfig};
 use crate::database::Database;
 use crate::file;
 use crate::file::SaveDir;
+use crate::mpv::{self, IpcSocket};
+use crate::sanitize;
 use crate::try_opt_r;
-use anime::local::{CategorizedEpisodes, EpisodeParser, SortedEpisodes};
+use crate::util;
+use anime::local::{CategorizedEpisodes, Episode, EpisodeParser, SortedEpisodes};
 use anime::remote::{Remote, SeriesID, Status};
+use anime::SeriesKind;
 use anyhow::{anyhow, Context, Error, Result};
 use chrono::{DateTime, Duration, Utc};
 use config::SeriesConfig;
 use diesel::deserialize::{self, FromSql};
-use diesel::prelude::*;
 use diesel::serialize::{self, Output, ToSql};
 use diesel::sql_types::Text;
-use entry::SeriesEntry;
+use entry::{ProgressChange, SeriesEntry};
 use info::SeriesInfo;
+use merged::MergedSeason;
+use playback::{PlaybackHandle, PlaybackResult};
+use progress::EpisodeProgress;
 use smallvec::SmallVec;
 use std::cmp::{Ordering, PartialOrd};
+use std::collections::HashSet;
+use std::ffi::OsString;
 use std::fs;
 use std::io::Write;
+use std::iter;
 use std::mem;
 use std::path::{self, Path, PathBuf};
 use std::result;
 use std::{borrow::Cow, process::Stdio};
 use thiserror::Error;
-use tokio::process::{Child, Command};
+use tokio::process::Command;
 
 #[derive(Debug, Error)]
 pub enum EpisodeScanError {
     #[error("failed to parse episodes at {path}: {source}")]
     EpisodeParseFailed { source: anime::Error, path: PathBuf },
 
+    #[error("the episode folder at {} no longer exists\nit looks like it was moved to {}", .path.display(), .moved_to.display())]
+    FolderMoved { path: PathBuf, moved_to: SeriesPath },
+
     #[error("no episodes found")]
     NoEpisodes,
 
-    #[error("multiple OVA / ONA / special / movie episode categories found without season episodes\nplease isolate each episode set into its own folder")]
+    #[error("no episode folder set\nedit the series to set one once its episodes are downloaded")]
+    NoPath,
+
+    #[error("multiple OVA / ONA / special / movie episode categories found without season episodes\nplease isolate each episode set into its own folder, or pick one with the \"category\" command")]
     SeriesNeedsSplitting,
+
+    #[error("no {0} episodes found")]
+    CategoryNotFound(SeriesKind),
+
+    #[error("episode files cannot be accessed:\n{}", .0.join("\n"))]
+    BrokenEpisodes(Vec<String>),
 }
 
 pub struct SeriesData {
     pub config: SeriesConfig,
     pub info: SeriesInfo,
     pub entry: SeriesEntry,
+    /// Other names the series can be selected by, besides its nickname.
+    pub aliases: Vec<String>,
+    /// Custom titles the series goes by, which are used to find its folder and episodes alongside its official titles.
+    pub synonyms: Vec<String>,
+    /// User-defined labels used to organize series, such as `seasonal-2024`.
+    pub tags: Vec<String>,
+    /// Every season merged into the folder of the series when it's watched as one series, which is moved on to the next of them as each is completed.
+    pub merged_seasons: Vec<MergedSeason>,
+    /// The total number of episodes in the seasons before this one, for series that use absolute episode numbering.
+    pub prequel_episodes: u32,
 }
 
 impl SeriesData {
-    pub fn from_remote(config: SeriesConfig, info: SeriesInfo, remote: &Remote) -> Result<Self> {
-        let entry = SeriesEntry::from_remote(remote, &info)?;
+    pub async fn from_remote(
+        config: SeriesConfig,
+        info: SeriesInfo,
+        remote: &Remote,
+    ) -> Result<Self> {
+        let entry = SeriesEntry::from_remote(remote, &info).await?;
 
         Ok(Self {
             config,
             info,
             entry,
+            aliases: Vec::new(),
+            synonyms: Vec::new(),
+            tags: Vec::new(),
+            merged_seasons: Vec::new(),
+            prequel_episodes: 0,
         })
     }
 
+    #[allow(clippy::cast_sign_loss)]
     pub fn load_from_config(db: &Database, config: Cow<SeriesConfig>) -> diesel::QueryResult<Self> {
         use diesel::result::Error as DieselError;
 
-        db.conn().transaction::<_, DieselError, _>(|| {
-            let info = SeriesInfo::load(db, config.id)?;
-            let entry = SeriesEntry::load(db, config.id)?;
+        let id = config.id;
+
+        let (info, entry, aliases, synonyms, tags, merged_seasons, prequel_episodes) =
+            db.transaction::<_, DieselError, _>("load series", || {
+                Ok((
+                    SeriesInfo::load(db, id)?,
+                    SeriesEntry::load(db, id)?,
+                    alias::load(db, id)?,
+                    synonym::load(db, id)?,
+                    tag::load(db, id)?,
+                    merged::load(db, id)?,
+                    cache::prequel_episodes(db, id as SeriesID)?,
+                ))
+            })?;
 
-            Ok(Self {
-                config: config.into_owned(),
-                info,
-                entry,
-            })
+        Ok(Self {
+            config: config.into_owned(),
+            info,
+            entry,
+            aliases,
+            synonyms,
+            tags,
+            merged_seasons,
+            prequel_episodes,
         })
     }
 
-    pub fn update(&mut self, params: UpdateParams, db: &Database, remote: &Remote) -> Result<()> {
-        let id_changed = self.config.update(params, db, remote)?;
+    /// Returns every title the series goes by, starting with its custom synonyms and followed by each of its official titles.
+    pub fn titles(&self) -> Vec<String> {
+        let official = [
+            Some(&self.info.title_preferred),
+            Some(&self.info.title_romaji),
+            self.info.title_english.as_ref(),
+            self.info.title_native.as_ref(),
+        ];
+
+        let mut titles = self.synonyms.clone();
+
+        for title in official.iter().flatten() {
+            if !titles.contains(title) {
+                titles.push((*title).clone());
+            }
+        }
+
+        titles
+    }
+
+    /// Returns the number to shift every episode of the series by.
+    ///
+    /// For series that use absolute episode numbering or are watched through a folder with merged seasons,
+    /// this also moves the episodes of the series back to the start of its season.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn episode_offset(&self) -> i32 {
+        let offset = i32::from(self.config.episode_offset);
+
+        if let Some(season) = merged::find(&self.merged_seasons, self.info.id) {
+            offset - i32::from(season.episode_offset)
+        } else if self.config.absolute_numbering {
+            offset - self.prequel_episodes as i32
+        } else {
+            offset
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        mut params: UpdateParams,
+        db: &Database,
+        remote: &Remote,
+    ) -> Result<()> {
+        let fetched = params.fetched.take();
 
-        if id_changed {
-            let info = SeriesInfo::from_remote_by_id(self.config.id as SeriesID, remote)
-                .context("getting series info")?;
+        if params.changes_id(self.config.id) && fetched.is_none() {
+            return Err(anyhow!(
+                "the series must be looked up before its ID can be changed"
+            ));
+        }
 
-            let entry = SeriesEntry::from_remote(remote, &info).context("getting series entry")?;
+        let id_changed = self.config.update(params, db, remote)?;
 
+        if let (true, Some((info, entry))) = (id_changed, fetched) {
             self.info = info;
             self.entry = entry;
         }
@@ -89,35 +217,265 @@ impl SeriesData {
         Ok(())
     }
 
-    pub fn force_sync_from_remote(&mut self, remote: &Remote) -> Result<()> {
-        // We don't want to set the new info now in case the entry sync fails
-        let info = SeriesInfo::from_remote_by_id(self.info.id as SeriesID, remote)?;
+    pub fn save(&self, db: &Database) -> diesel::QueryResult<()> {
+        db.transaction("save series", || {
+            self.config.save(db)?;
+            self.info.save(db)?;
+            self.entry.save(db)?;
+            alias::replace(db, self.config.id, &self.aliases)?;
+            synonym::replace(db, self.config.id, &self.synonyms)?;
+            tag::replace(db, self.config.id, &self.tags)?;
+            merged::replace(db, self.config.id, &self.merged_seasons)
+        })
+    }
+
+    /// Set the number of watched episodes to `progress`, updating the status the same way watching or regressing episodes would.
+    pub fn set_progress(&mut self, progress: i16, config: &Config, db: &Database) -> Result<()> {
+        if self.config.local_rewatch.is_some() {
+            return self.set_local_rewatch_progress(progress, db);
+        }
 
-        self.entry.force_sync_from_remote(remote)?;
-        self.info = info;
+        self.entry
+            .change_progress(ProgressChange::To(progress), self.info.episodes, config);
 
+        self.save(db)?;
         Ok(())
     }
 
-    pub fn save(&self, db: &Database) -> diesel::QueryResult<()> {
-        db.conn()
-            .transaction(|| {
-                self.config.save(db)?;
-                self.info.save(db)?;
-                self.entry.save(db)
-            })
-            .map(|_| ())
+    /// Set the number of episodes watched in the local rewatch to `progress`.
+    ///
+    /// Reaching the last episode ends the rewatch, which leaves the series completed like it was before.
+    fn set_local_rewatch_progress(&mut self, progress: i16, db: &Database) -> Result<()> {
+        let progress = progress.max(0);
+
+        self.config.local_rewatch = if progress >= self.info.episodes {
+            None
+        } else {
+            Some(progress)
+        };
+
+        self.save(db)?;
+        Ok(())
+    }
+
+    /// Add `alias` as another name the series can be selected by, and save it.
+    pub fn add_alias(&mut self, alias: &str, db: &Database) -> Result<()> {
+        let alias = alias::validate(db, alias)?;
+
+        self.aliases.push(alias.to_string());
+        self.aliases.sort_unstable();
+
+        alias::replace(db, self.config.id, &self.aliases)?;
+        Ok(())
+    }
+
+    /// Remove `alias` from the names the series can be selected by, and save the change.
+    pub fn remove_alias(&mut self, alias: &str, db: &Database) -> Result<()> {
+        let index = self
+            .aliases
+            .iter()
+            .position(|existing| existing == alias.trim())
+            .ok_or_else(|| anyhow!("{} is not an alias of {}", alias, self.config.nickname))?;
+
+        self.aliases.remove(index);
+        alias::replace(db, self.config.id, &self.aliases)?;
+        Ok(())
     }
 
-    /// Returns the UTC time threshold for an episode should be counted as watched, assuming that the episode was starting to be watched now.
-    pub fn next_watch_progress_time(&self, config: &Config) -> DateTime<Utc> {
+    /// Add `synonym` as a custom title of the series, and save it.
+    pub fn add_synonym(&mut self, synonym: &str, db: &Database) -> Result<()> {
+        let synonym = synonym.trim();
+
+        if synonym.is_empty() {
+            return Err(anyhow!("synonym can't be empty"));
+        }
+
+        if self.synonyms.iter().any(|existing| existing == synonym) {
+            return Err(anyhow!(
+                "{} is already a synonym of {}",
+                synonym,
+                self.config.nickname
+            ));
+        }
+
+        self.synonyms.push(synonym.to_string());
+        self.synonyms.sort_unstable();
+
+        synonym::replace(db, self.config.id, &self.synonyms)?;
+        Ok(())
+    }
+
+    /// Remove `synonym` from the custom titles of the series, and save the change.
+    pub fn remove_synonym(&mut self, synonym: &str, db: &Database) -> Result<()> {
+        let index = self
+            .synonyms
+            .iter()
+            .position(|existing| existing == synonym.trim())
+            .ok_or_else(|| anyhow!("{} is not a synonym of {}", synonym, self.config.nickname))?;
+
+        self.synonyms.remove(index);
+        synonym::replace(db, self.config.id, &self.synonyms)?;
+        Ok(())
+    }
+
+    /// Returns the merged season to move on to, if the series is watched through a folder with merged seasons and its current season has been completed.
+    ///
+    /// Seasons with changes that haven't been synced yet aren't moved on from, as moving on replaces their list entry.
+    pub fn next_merged_season(&self) -> Option<&MergedSeason> {
+        if self.entry.status() != Status::Completed || self.entry.needs_sync() {
+            return None;
+        }
+
+        merged::next(&self.merged_seasons, self.info.id)
+    }
+
+    /// Add `tag` to the series, and save it.
+    pub fn add_tag(&mut self, tag: &str, db: &Database) -> Result<()> {
+        let tag = tag::validate(tag)?;
+
+        if self.tags.iter().any(|existing| existing == tag) {
+            return Err(anyhow!(
+                "{} is already tagged {}",
+                self.config.nickname,
+                tag
+            ));
+        }
+
+        self.tags.push(tag.to_string());
+        self.tags.sort_unstable();
+
+        tag::replace(db, self.config.id, &self.tags)?;
+        Ok(())
+    }
+
+    /// Remove `tag` from the series, and save the change.
+    pub fn remove_tag(&mut self, tag: &str, db: &Database) -> Result<()> {
+        let index = self
+            .tags
+            .iter()
+            .position(|existing| existing == tag.trim())
+            .ok_or_else(|| anyhow!("{} isn't tagged {}", self.config.nickname, tag))?;
+
+        self.tags.remove(index);
+        tag::replace(db, self.config.id, &self.tags)?;
+        Ok(())
+    }
+
+    /// Returns the length of each episode in minutes, preferring the length set for the series over the one from the remote service.
+    pub fn episode_length_mins(&self) -> i16 {
+        self.config
+            .episode_length_mins
+            .unwrap_or(self.info.episode_length_mins)
+    }
+
+    /// Returns the time an episode that lasts `episode_secs` will count as watched, if playback starts now.
+    pub fn next_watch_progress_time(&self, episode_secs: f64, config: &Config) -> DateTime<Utc> {
         let secs_must_watch =
-            (f32::from(self.info.episode_length_mins) * config.episode.pcnt_must_watch) * 60.0;
+            episode_secs * f64::from(config.episode.pcnt_must_watch.as_multiplier());
 
         Utc::now() + Duration::seconds(secs_must_watch as i64)
     }
 }
 
+/// Returns true if an episode that finished playing with `result` should count towards progress.
+///
+/// Enough of the episode must have been watched for it to count, and the player can't have been left paused for too long
+/// before exiting. If the player exited with an error, the episode will only count when the `count_on_crash_if_watched`
+/// config option is set, and never when it crashed right after starting.
+pub fn episode_counts_as_watched(result: &PlaybackResult, config: &Config) -> bool {
+    if !result.reached_progress || result.idle || result.crashed {
+        return false;
+    }
+
+    result.status.success() || config.episode.count_on_crash_if_watched
+}
+
+/// Parse the episodes in `dir` with `parser`, including those in subdirectories if enabled in `config`.
+///
+/// Episodes named with different titles are allowed as long as every title is one of `titles`.
+pub fn parse_episode_dir<P>(
+    dir: P,
+    parser: &EpisodeParser,
+    titles: &[String],
+    config: &Config,
+) -> anime::Result<CategorizedEpisodes>
+where
+    P: AsRef<Path>,
+{
+    CategorizedEpisodes::parse_with_titles(dir, parser, config.episode.recursive_scan, titles)
+}
+
+/// Scan the episodes of the series with `sconfig` and `titles`, shifting every episode number by `offset`.
+///
+/// This only needs the config and title of a series, so it can be done away from the rest of its data, such as on another thread.
+/// If the folder of the series is gone, the folder that best matches the series is looked for in case it was renamed.
+pub fn scan_episodes(
+    sconfig: &SeriesConfig,
+    titles: &[String],
+    offset: i32,
+    config: &Config,
+) -> result::Result<SortedEpisodes, EpisodeScanError> {
+    // Series that can be streamed can still be watched without any episodes on disk
+    let can_stream = sconfig.stream_url_template.is_some();
+
+    if !sconfig.path.is_set() {
+        return if can_stream {
+            Ok(SortedEpisodes::new())
+        } else {
+            Err(EpisodeScanError::NoPath)
+        };
+    }
+
+    let path = sconfig.path.absolute(config);
+
+    // Nothing was moved when the series directory itself is missing, as that usually means the drive it's on isn't mounted
+    if !path.exists() && config.series_dir.exists() {
+        if let Some(moved_to) = SeriesPath::closest_to_series(titles, &sconfig.nickname, config) {
+            return Err(EpisodeScanError::FolderMoved {
+                path: path.into(),
+                moved_to,
+            });
+        }
+    }
+
+    let episodes =
+        parse_episode_dir(&path, &sconfig.episode_parser, titles, config).map_err(|source| {
+            EpisodeScanError::EpisodeParseFailed {
+                source,
+                path: path.clone().into(),
+            }
+        })?;
+
+    if episodes.is_empty() {
+        return if can_stream {
+            Ok(SortedEpisodes::new())
+        } else {
+            Err(EpisodeScanError::NoEpisodes)
+        };
+    }
+
+    let mut episodes = match sconfig.episode_category {
+        Some(category) => episodes
+            .take_category(category)
+            .ok_or(EpisodeScanError::CategoryNotFound(category))?,
+        None => episodes
+            .take_season_episodes_or_present()
+            .ok_or(EpisodeScanError::SeriesNeedsSplitting)?,
+    };
+
+    if config.episode.verify_files {
+        let broken = Series::find_broken_episodes(&path, &episodes);
+
+        if !broken.is_empty() {
+            return Err(EpisodeScanError::BrokenEpisodes(broken));
+        }
+    }
+
+    episodes.apply_offset(offset);
+
+    Ok(episodes)
+}
+
 pub struct Series {
     pub data: SeriesData,
     pub episodes: SortedEpisodes,
@@ -149,34 +507,138 @@ impl Series {
         self.data.update(params, db, remote)?;
 
         self.episodes = match episodes {
-            Some(episodes) => episodes,
+            Some(mut episodes) => {
+                episodes.apply_offset(self.data.episode_offset());
+                episodes
+            }
             None => Self::scan_episodes(&self.data, config)?,
         };
 
         Ok(())
     }
 
-    fn scan_episodes(
-        data: &SeriesData,
+    /// Sets the number to shift every episode of the series by and rescans its episodes.
+    pub fn set_episode_offset(&mut self, offset: i16, config: &Config) -> Result<()> {
+        let old_offset = mem::replace(&mut self.data.config.episode_offset, offset);
+
+        match Self::scan_episodes(&self.data, config) {
+            Ok(episodes) => {
+                self.episodes = episodes;
+                Ok(())
+            }
+            Err(err) => {
+                self.data.config.episode_offset = old_offset;
+                Err(err.into())
+            }
+        }
+    }
+
+    /// Sets whether the series uses absolute episode numbering and rescans its episodes.
+    ///
+    /// `prequel_episodes` should be the total number of episodes in the seasons before the series.
+    pub fn set_absolute_numbering(
+        &mut self,
+        enabled: bool,
+        prequel_episodes: u32,
         config: &Config,
-    ) -> result::Result<SortedEpisodes, EpisodeScanError> {
-        let path = data.config.path.absolute(config);
+    ) -> Result<()> {
+        let old_prequel_episodes = mem::replace(&mut self.data.prequel_episodes, prequel_episodes);
 
-        let episodes =
-            CategorizedEpisodes::parse(&path, &data.config.episode_parser).map_err(|source| {
-                EpisodeScanError::EpisodeParseFailed {
-                    source,
-                    path: path.into(),
-                }
-            })?;
+        let mut sconfig = self.data.config.clone();
+        sconfig.absolute_numbering = enabled;
+
+        self.replace_config(sconfig, config).map_err(|err| {
+            self.data.prequel_episodes = old_prequel_episodes;
+            err
+        })
+    }
 
-        if episodes.is_empty() {
-            return Err(EpisodeScanError::NoEpisodes);
+    /// Sets the seasons merged into the folder of the series and rescans its episodes.
+    ///
+    /// The current seasons will be kept if the rescan fails.
+    pub fn set_merged_seasons(
+        &mut self,
+        seasons: Vec<MergedSeason>,
+        config: &Config,
+    ) -> Result<()> {
+        let current = mem::replace(&mut self.data.merged_seasons, seasons);
+
+        match Self::scan_episodes(&self.data, config) {
+            Ok(episodes) => {
+                self.episodes = episodes;
+                Ok(())
+            }
+            Err(err) => {
+                self.data.merged_seasons = current;
+                Err(err.into())
+            }
         }
+    }
+
+    /// Sets the episode category to watch and rescans the episodes of the series.
+    ///
+    /// When `category` is `None`, season episodes will be used if they are present.
+    pub fn set_episode_category(
+        &mut self,
+        category: Option<SeriesKind>,
+        config: &Config,
+    ) -> Result<()> {
+        let mut sconfig = self.data.config.clone();
+        sconfig.episode_category = category;
+        self.replace_config(sconfig, config)
+    }
+
+    /// Replaces the config of the series and rescans its episodes.
+    ///
+    /// The current config will be kept if the rescan fails.
+    pub fn replace_config(&mut self, sconfig: SeriesConfig, config: &Config) -> Result<()> {
+        let current = mem::replace(&mut self.data.config, sconfig);
+
+        match Self::scan_episodes(&self.data, config) {
+            Ok(episodes) => {
+                self.episodes = episodes;
+                Ok(())
+            }
+            Err(err) => {
+                self.data.config = current;
+                Err(err.into())
+            }
+        }
+    }
+
+    /// Returns the first episode of the series if it appears to be a creditless opening or ending numbered as a regular episode.
+    ///
+    /// When this returns an episode, an episode offset of -1 should make the episode numbers line up with the remote service.
+    pub fn numbered_opening(&self) -> Option<&Episode> {
+        let has_opening = self.data.config.episode_offset == 0
+            && !self.data.config.absolute_numbering
+            && self.data.merged_seasons.is_empty()
+            && self
+                .episodes
+                .has_numbered_opening(self.data.info.episodes as u32);
+
+        if !has_opening {
+            return None;
+        }
+
+        self.episodes.whole().next()
+    }
+
+    #[inline(always)]
+    fn scan_episodes(
+        data: &SeriesData,
+        config: &Config,
+    ) -> result::Result<SortedEpisodes, EpisodeScanError> {
+        scan_episodes(&data.config, &data.titles(), data.episode_offset(), config)
+    }
 
+    /// Returns the filenames of all `episodes` in `dir` that can't be opened, such as broken symlinks.
+    fn find_broken_episodes(dir: &Path, episodes: &SortedEpisodes) -> Vec<String> {
         episodes
-            .take_season_episodes_or_present()
-            .ok_or(EpisodeScanError::SeriesNeedsSplitting)
+            .iter()
+            .filter(|episode| fs::File::open(dir.join(&episode.filename)).is_err())
+            .map(|episode| episode.filename.clone())
+            .collect()
     }
 
     #[inline(always)]
@@ -198,6 +660,24 @@ impl Series {
         Self::init(data, config)
     }
 
+    /// Load the series with `series_config` without scanning its episodes, so they can be scanned later when they're needed.
+    pub fn load_unscanned(series_config: SeriesConfig, db: &Database) -> LoadedSeries {
+        match SeriesData::load_from_config(db, Cow::Borrowed(&series_config)) {
+            Ok(data) => LoadedSeries::Unscanned(data),
+            Err(err) => LoadedSeries::None(series_config, err.into()),
+        }
+    }
+
+    /// Returns how many episodes will be watched by playing the file that contains episode `ep_num`.
+    ///
+    /// This is 1 unless the file contains multiple episodes, in which case it's the number of episodes
+    /// from `ep_num` to the end of the file.
+    pub fn episodes_in_file(&self, ep_num: u32) -> u32 {
+        self.episodes
+            .find(ep_num)
+            .map_or(1, |episode| episode.last_number - ep_num + 1)
+    }
+
     pub fn episode_path(&self, ep_num: u32, config: &Config) -> Option<PathBuf> {
         let episode = self.episodes.find(ep_num)?;
         let mut path = self.data.config.path.absolute(config).into_owned();
@@ -205,95 +685,401 @@ impl Series {
         path.canonicalize().ok()
     }
 
-    pub fn play_episode(&self, episode: u32, config: &Config) -> Result<Child> {
-        let episode_path = self
-            .episode_path(episode, config)
-            .with_context(|| anyhow!("episode {} not found", episode))?;
+    /// Returns the arguments that make mpv use the preferred subtitle and audio tracks of the series, along with any external
+    /// subtitle files that belong to `episode`.
+    fn mpv_track_args(&self, episode: u32, config: &Config) -> Vec<OsString> {
+        let sconfig = &self.data.config;
+        let mut args = Vec::new();
+
+        if let Some(langs) = &sconfig.sub_lang {
+            args.push(format!("--slang={}", langs).into());
+        }
+
+        if let Some(langs) = &sconfig.audio_lang {
+            args.push(format!("--alang={}", langs).into());
+        }
+
+        if let Some(pattern) = &sconfig.sub_file_pattern {
+            let parser = EpisodeParser::custom(pattern.as_str());
+            let dir = sconfig.path.absolute(config);
+            let offset = self.data.episode_offset();
+
+            for path in subtitle::find_external(&dir, episode, &parser, offset) {
+                let mut arg = OsString::from("--sub-file=");
+                arg.push(path);
+                args.push(arg);
+            }
+        }
+
+        args
+    }
+
+    pub fn play_episode(
+        &self,
+        episode: u32,
+        config: &Config,
+        db: &Database,
+    ) -> Result<PlaybackHandle> {
+        self.play_episode_with(episode, &config.episode.player, config, db)
+    }
+
+    /// Play `episode` with `player` instead of the player from `config`, such as one of its fallback players.
+    ///
+    /// The player arguments from `config` and the series are only passed to the player from `config`.
+    pub fn play_episode_with(
+        &self,
+        episode: u32,
+        player: &str,
+        config: &Config,
+        db: &Database,
+    ) -> Result<PlaybackHandle> {
+        let episode_path = match (
+            self.episode_path(episode, config),
+            &self.data.config.stream_url_template,
+        ) {
+            (Some(path), _) => path,
+            (None, Some(template)) => return self.stream_episode(episode, template, config),
+            (None, None) => return Err(anyhow!("episode {} not found", episode)),
+        };
+
+        let is_main_player = player == config.episode.player;
+
+        let mut cmd = Command::new(player);
+        cmd.arg(&episode_path);
+
+        if is_main_player {
+            cmd.args(&config.episode.player_args);
+        }
+
+        let is_mpv = mpv::is_mpv(player);
+
+        if is_mpv {
+            cmd.args(self.mpv_track_args(episode, config));
+        }
+
+        if is_main_player {
+            cmd.args(self.data.config.player_args.as_ref());
+        }
+
+        let ipc = if config.episode.track_mpv_position && is_mpv {
+            let ipc = IpcSocket::new();
+            cmd.arg(ipc.player_arg());
+            Some(ipc)
+        } else {
+            None
+        };
+
+        // Resuming is only possible when we know where the player was last at
+        let progress = if config.episode.resume_playback && ipc.is_some() {
+            EpisodeProgress::load(db, self.data.config.id, episode as i16)
+                .context("loading episode progress")?
+        } else {
+            None
+        };
+
+        if let Some(progress) = &progress {
+            cmd.arg(format!("--start={}", progress.resume_secs()));
+        }
+
+        // Terminal-based players need to be able to take over the terminal we're running in
+        if config.episode.player_inherit_stdio {
+            cmd.stdout(Stdio::inherit());
+            cmd.stderr(Stdio::inherit());
+            cmd.stdin(Stdio::inherit());
+        } else {
+            cmd.stdout(Stdio::null());
+            cmd.stderr(Stdio::null());
+            cmd.stdin(Stdio::null());
+        }
+
+        tracing::info!(
+            series = %self.data.config.nickname,
+            episode,
+            %player,
+            "starting playback"
+        );
+
+        let child = cmd
+            .spawn()
+            .with_context(|| anyhow!("failed to play episode {}", episode))?;
+
+        let episode_secs = self.episode_file_secs(episode, &episode_path);
+
+        let handle = PlaybackHandle::new(
+            child,
+            ipc,
+            self.data.next_watch_progress_time(episode_secs, config),
+            config.episode.pcnt_must_watch.as_multiplier(),
+            episode_secs,
+        )
+        .skip_chapters(&config.chapter_skip)
+        .max_idle(config.episode.max_idle_mins);
+
+        match progress {
+            Some(progress) => Ok(handle.already_watched(f64::from(progress.watched_secs))),
+            None => Ok(handle),
+        }
+    }
+
+    /// Returns how many seconds the file of `episode` at `path` lasts.
+    ///
+    /// The episode length set for the series takes priority over the file itself, which is only probed when built with the
+    /// `ffprobe` feature. Otherwise, the length from the remote service is used for each episode in the file.
+    fn episode_file_secs(&self, episode: u32, path: &Path) -> f64 {
+        let episodes_in_file = f64::from(self.episodes_in_file(episode));
+
+        if let Some(mins) = self.data.config.episode_length_mins {
+            return f64::from(mins) * episodes_in_file * 60.0;
+        }
+
+        #[cfg(feature = "ffprobe")]
+        if let Some(secs) = probe::duration_secs(path) {
+            return secs;
+        }
+
+        #[cfg(not(feature = "ffprobe"))]
+        let _ = path;
+
+        f64::from(self.data.info.episode_length_mins) * episodes_in_file * 60.0
+    }
+
+    /// Open the stream of `episode` from the URL `template` with the stream command from `config`.
+    fn stream_episode(
+        &self,
+        episode: u32,
+        template: &str,
+        config: &Config,
+    ) -> Result<PlaybackHandle> {
+        let url = stream::url(template, self.data.config.id, episode)?;
+
+        let mut cmd = match config.episode.stream_command.split_first() {
+            Some((program, args)) => {
+                let mut cmd = Command::new(program);
+                cmd.args(args);
+                cmd
+            }
+            None => Command::new(util::URL_OPENER),
+        };
 
-        let mut cmd = Command::new(&config.episode.player);
-        cmd.arg(episode_path);
-        cmd.args(&config.episode.player_args);
-        cmd.args(self.data.config.player_args.as_ref());
+        cmd.arg(&url);
         cmd.stdout(Stdio::null());
         cmd.stderr(Stdio::null());
         cmd.stdin(Stdio::null());
 
-        cmd.spawn()
-            .with_context(|| anyhow!("failed to play episode {}", episode))
+        tracing::info!(
+            series = %self.data.config.nickname,
+            episode,
+            %url,
+            "starting stream"
+        );
+
+        let child = cmd
+            .spawn()
+            .with_context(|| anyhow!("failed to stream episode {}", episode))?;
+
+        let episode_secs = f64::from(self.data.episode_length_mins()) * 60.0;
+
+        let handle = PlaybackHandle::new(
+            child,
+            None,
+            self.data.next_watch_progress_time(episode_secs, config),
+            config.episode.pcnt_must_watch.as_multiplier(),
+            episode_secs,
+        );
+
+        Ok(handle.streaming())
     }
 
-    pub fn begin_watching(
-        &mut self,
-        remote: &Remote,
+    /// Remember where playback of `episode` stopped so it can be resumed later.
+    ///
+    /// Any saved position is cleared if the episode was `completed`, or when there is no position worth resuming from.
+    pub fn save_playback_progress(
+        &self,
+        episode: u32,
+        result: &PlaybackResult,
+        completed: bool,
         config: &Config,
         db: &Database,
     ) -> Result<()> {
-        self.data.entry.sync_from_remote(remote)?;
+        // A player that crashed as soon as it started never got anywhere, so the last position is left alone
+        if !config.episode.resume_playback || result.crashed {
+            return Ok(());
+        }
 
-        let entry = &mut self.data.entry;
-        let last_status = entry.status();
-
-        match last_status {
-            Status::Watching | Status::Rewatching => {
-                // There is an edge case where all episodes have been watched, but the status
-                // is still set to watching / rewatching. Here we just start a rewatch
-                if entry.watched_episodes() >= self.data.info.episodes {
-                    entry.set_status(Status::Rewatching, config);
-                    entry.set_watched_episodes(0);
-
-                    if last_status == Status::Rewatching {
-                        entry.set_times_rewatched(entry.times_rewatched() + 1);
-                    }
-                }
-            }
-            Status::Completed => {
-                entry.set_status(Status::Rewatching, config);
-                entry.set_watched_episodes(0);
+        let series_id = self.data.config.id;
+        let episode = episode as i16;
+
+        match result.position {
+            Some((position, watched))
+                if !completed && position as i32 >= EpisodeProgress::MIN_RESUME_SECS =>
+            {
+                let progress = EpisodeProgress {
+                    series_id,
+                    episode,
+                    position_secs: position as i32,
+                    watched_secs: watched as i32,
+                };
+
+                progress.save(db).context("saving episode progress")?;
             }
-            Status::PlanToWatch | Status::OnHold => entry.set_status(Status::Watching, config),
-            Status::Dropped => {
-                entry.set_status(Status::Watching, config);
-                entry.set_watched_episodes(0);
+            _ => {
+                EpisodeProgress::clear(db, series_id, episode)
+                    .context("clearing episode progress")?;
             }
         }
 
-        self.data.entry.sync_to_remote(remote)?;
+        Ok(())
+    }
+
+    /// Returns the number of watched episodes, counted from the start of the local rewatch if one is in progress.
+    pub fn watched_episodes(&self) -> i16 {
+        self.data
+            .config
+            .local_rewatch
+            .unwrap_or_else(|| self.data.entry.watched_episodes())
+    }
+
+    /// Returns the number of the next episode to watch.
+    #[inline(always)]
+    pub fn next_episode(&self) -> u32 {
+        self.watched_episodes().max(0) as u32 + 1
+    }
+
+    /// Returns true if the series is completed and isn't being rewatched locally.
+    pub fn is_completed(&self) -> bool {
+        self.data.entry.status() == Status::Completed && self.data.config.local_rewatch.is_none()
+    }
+
+    /// Start rewatching the series without changing its list entry, so its episodes are only recorded in the local watch history.
+    pub fn begin_local_rewatch(&mut self, db: &Database) -> Result<()> {
+        if self.data.entry.status() != Status::Completed {
+            return Err(anyhow!(
+                "{} must be completed to be rewatched locally",
+                self.data.config.nickname
+            ));
+        }
+
+        self.data.config.local_rewatch = Some(0);
         self.save(db)?;
 
         Ok(())
     }
 
-    pub fn episode_completed(
+    /// Stop the local rewatch of the series, if there is one.
+    pub fn end_local_rewatch(&mut self, db: &Database) -> Result<()> {
+        self.data.config.local_rewatch = None;
+        self.save(db)?;
+        Ok(())
+    }
+
+    pub fn begin_watching(
         &mut self,
-        remote: &Remote,
+        start: WatchStart,
         config: &Config,
         db: &Database,
     ) -> Result<()> {
-        let new_progress = self.data.entry.watched_episodes() + 1;
+        if start == WatchStart::UpdateStatus
+            && config.episode.local_rewatch
+            && self.data.entry.status() == Status::Completed
+            && self.data.config.local_rewatch.is_none()
+        {
+            return self.begin_local_rewatch(db);
+        }
+
+        // The list entry of a series being rewatched locally should stay the same until the rewatch is over
+        if self.data.config.local_rewatch.is_some() {
+            return Ok(());
+        }
+
+        self.data
+            .entry
+            .begin_watching(start, self.data.info.episodes, config);
+
+        self.save(db)?;
+
+        Ok(())
+    }
+
+    /// Mark the next episode as watched.
+    ///
+    /// If the file of the next episode contains multiple episodes, every episode left in it is marked as watched.
+    pub fn episode_completed(&mut self, config: &Config, db: &Database) -> Result<()> {
+        if let Some(watched) = self.data.config.local_rewatch {
+            let new_progress = watched + self.episodes_in_file(watched as u32 + 1) as i16;
+            return self.data.set_local_rewatch_progress(new_progress, db);
+        }
+
+        let watched = self.data.entry.watched_episodes();
+        let new_progress = watched + self.episodes_in_file(watched as u32 + 1) as i16;
 
         if new_progress >= self.data.info.episodes {
             // The watched episode range is inclusive, so it's fine to bump the watched count
-            // if we're at exactly at the last episode
-            if new_progress == self.data.info.episodes {
-                self.data.entry.set_watched_episodes(new_progress);
+            // if we haven't already reached the last episode
+            if watched < self.data.info.episodes {
+                self.data
+                    .entry
+                    .set_watched_episodes(self.data.info.episodes);
             }
 
-            return self.series_complete(remote, config, db);
+            return self.series_complete(config, db);
         }
 
         self.data.entry.set_watched_episodes(new_progress);
-        self.data.entry.sync_to_remote(remote)?;
         self.save(db)?;
 
         Ok(())
     }
 
-    pub fn episode_regressed(
+    /// Mark each episode after the last watched one as watched, for as long as its file is in `played`.
+    ///
+    /// Series that are completed or dropped are left alone, as watching them again would reset their progress.
+    /// Returns the number of files that were marked as watched.
+    pub fn sync_played_episodes(
         &mut self,
-        remote: &Remote,
+        played: &HashSet<PathBuf>,
         config: &Config,
         db: &Database,
-    ) -> Result<()> {
+    ) -> Result<u32> {
+        if !self.data.config.path.is_set()
+            || matches!(
+                self.data.entry.status(),
+                Status::Completed | Status::Dropped
+            )
+        {
+            return Ok(0);
+        }
+
+        let dir = self.data.config.path.absolute(config).into_owned();
+        let mut synced = 0;
+
+        while self.data.entry.status() != Status::Completed {
+            let next = self.data.entry.watched_episodes() as u32 + 1;
+
+            let episode = match self.episodes.find(next) {
+                Some(episode) => episode,
+                None => break,
+            };
+
+            if !played.contains(&dir.join(&episode.filename)) {
+                break;
+            }
+
+            if synced == 0 {
+                self.begin_watching(WatchStart::UpdateStatus, config, db)?;
+            }
+
+            self.episode_completed(config, db)?;
+            synced += 1;
+        }
+
+        Ok(synced)
+    }
+
+    pub fn episode_regressed(&mut self, config: &Config, db: &Database) -> Result<()> {
+        if let Some(watched) = self.data.config.local_rewatch {
+            return self.data.set_local_rewatch_progress(watched - 1, db);
+        }
+
         let entry = &mut self.data.entry;
         entry.set_watched_episodes(entry.watched_episodes().saturating_sub(1));
 
@@ -304,18 +1090,17 @@ impl Series {
         };
 
         entry.set_status(new_status, config);
-        entry.sync_to_remote(remote)?;
         self.save(db)?;
 
         Ok(())
     }
 
-    pub fn series_complete(
-        &mut self,
-        remote: &Remote,
-        config: &Config,
-        db: &Database,
-    ) -> Result<()> {
+    /// Set the number of watched episodes to `progress`, updating the status the same way watching or regressing episodes would.
+    pub fn set_progress(&mut self, progress: i16, config: &Config, db: &Database) -> Result<()> {
+        self.data.set_progress(progress, config, db)
+    }
+
+    pub fn series_complete(&mut self, config: &Config, db: &Database) -> Result<()> {
         let entry = &mut self.data.entry;
 
         // A rewatch is typically only counted once the series is completed again
@@ -324,16 +1109,26 @@ impl Series {
         }
 
         entry.set_status(Status::Completed, config);
-        entry.sync_to_remote(remote)?;
         self.save(db)?;
 
         Ok(())
     }
 }
 
+/// Indicates how the status of a series should be handled when an episode of it is played.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WatchStart {
+    /// Update the status and progress of the series as normal.
+    UpdateStatus,
+    /// Play the episode without touching the status or progress of the series.
+    KeepStatus,
+}
+
 pub enum LoadedSeries {
     Complete(Series),
     Partial(SeriesData, EpisodeScanError),
+    /// The series was loaded without scanning its episodes, which is done once they're needed.
+    Unscanned(SeriesData),
     None(SeriesConfig, Error),
 }
 
@@ -342,14 +1137,44 @@ impl LoadedSeries {
         match self {
             Self::Complete(_) => (),
             Self::Partial(data, _) => *self = Series::load_from_config(&data.config, config, db),
+            Self::Unscanned(data) => {
+                let result = Series::scan_episodes(data, config);
+                self.finish_scan(result);
+            }
             Self::None(cfg, _) => *self = Series::load_from_config(cfg.clone(), config, db),
         }
     }
 
+    #[inline(always)]
+    pub fn is_unscanned(&self) -> bool {
+        matches!(self, Self::Unscanned(_))
+    }
+
+    /// Fill in the episodes of a series that was loaded without scanning them with `result`.
+    ///
+    /// Nothing is changed if the episodes of the series were scanned some other way in the meantime.
+    pub fn finish_scan(&mut self, result: result::Result<SortedEpisodes, EpisodeScanError>) {
+        if !self.is_unscanned() {
+            return;
+        }
+
+        let placeholder = Self::None(self.config().clone(), anyhow!("series is being loaded"));
+
+        let data = match mem::replace(self, placeholder) {
+            Self::Unscanned(data) => data,
+            Self::Complete(_) | Self::Partial(_, _) | Self::None(_, _) => unreachable!(),
+        };
+
+        *self = match result {
+            Ok(episodes) => Self::Complete(Series::with_episodes(data, episodes)),
+            Err(err) => Self::Partial(data, err),
+        };
+    }
+
     pub fn save(&self, db: &Database) -> diesel::QueryResult<()> {
         match self {
             Self::Complete(series) => series.save(db),
-            Self::Partial(data, _) => data.save(db),
+            Self::Partial(data, _) | Self::Unscanned(data) => data.save(db),
             Self::None(_, _) => Ok(()),
         }
     }
@@ -357,7 +1182,7 @@ impl LoadedSeries {
     pub fn config(&self) -> &SeriesConfig {
         match self {
             Self::Complete(series) => &series.data.config,
-            Self::Partial(data, _) => &data.config,
+            Self::Partial(data, _) | Self::Unscanned(data) => &data.config,
             Self::None(cfg, _) => cfg,
         }
     }
@@ -365,15 +1190,53 @@ impl LoadedSeries {
     pub fn info(&self) -> Option<&SeriesInfo> {
         match self {
             Self::Complete(series) => Some(&series.data.info),
-            Self::Partial(data, _) => Some(&data.info),
+            Self::Partial(data, _) | Self::Unscanned(data) => Some(&data.info),
             Self::None(_, _) => None,
         }
     }
 
+    pub fn complete(&self) -> Option<&Series> {
+        match self {
+            Self::Complete(series) => Some(series),
+            Self::Partial(_, _) | Self::Unscanned(_) | Self::None(_, _) => None,
+        }
+    }
+
+    /// Returns the series if its episodes were loaded, or the error that kept them from being loaded.
+    pub fn into_complete(self) -> Result<Series> {
+        match self {
+            Self::Complete(series) => Ok(series),
+            Self::Partial(_, err) => Err(err.into()),
+            Self::Unscanned(data) => Err(anyhow!(
+                "the episodes of {} haven't been scanned",
+                data.config.nickname
+            )),
+            Self::None(_, err) => Err(err),
+        }
+    }
+
     pub fn complete_mut(&mut self) -> Option<&mut Series> {
         match self {
             Self::Complete(series) => Some(series),
-            Self::Partial(_, _) | Self::None(_, _) => None,
+            Self::Partial(_, _) | Self::Unscanned(_) | Self::None(_, _) => None,
+        }
+    }
+
+    /// Returns the data of the series if it was able to be loaded, even if its episodes weren't.
+    pub fn data(&self) -> Option<&SeriesData> {
+        match self {
+            Self::Complete(series) => Some(&series.data),
+            Self::Partial(data, _) | Self::Unscanned(data) => Some(data),
+            Self::None(_, _) => None,
+        }
+    }
+
+    /// Returns the data of the series if it was able to be loaded, even if its episodes weren't.
+    pub fn data_mut(&mut self) -> Option<&mut SeriesData> {
+        match self {
+            Self::Complete(series) => Some(&mut series.data),
+            Self::Partial(data, _) | Self::Unscanned(data) => Some(data),
+            Self::None(_, _) => None,
         }
     }
 
@@ -387,6 +1250,16 @@ impl LoadedSeries {
         self.config().nickname.as_ref()
     }
 
+    /// Returns every alias of the series, or nothing if its data couldn't be loaded.
+    pub fn aliases(&self) -> &[String] {
+        self.data().map_or(&[], |data| &data.aliases)
+    }
+
+    /// Returns true if the series goes by `name`, either as its nickname or as one of its aliases.
+    pub fn has_name(&self, name: &str) -> bool {
+        self.nickname() == name || self.aliases().iter().any(|alias| alias == name)
+    }
+
     #[inline(always)]
     pub fn path(&self) -> &SeriesPath {
         &self.config().path
@@ -397,6 +1270,71 @@ impl LoadedSeries {
         &self.config().episode_parser
     }
 
+    /// Add `synonym` as a custom title of the series, save it, and rescan the episodes of the series.
+    ///
+    /// Like the episode category, this can be applied to series that failed to find their episodes,
+    /// as a synonym can resolve episodes being named with different titles.
+    pub fn add_synonym(&mut self, synonym: &str, config: &Config, db: &Database) -> Result<()> {
+        self.data_mut()
+            .ok_or_else(|| anyhow!("series must be loaded to change its synonyms"))?
+            .add_synonym(synonym, db)?;
+
+        self.rescan(config, db)
+    }
+
+    /// Remove `synonym` from the custom titles of the series, save the change, and rescan the episodes of the series.
+    pub fn remove_synonym(&mut self, synonym: &str, config: &Config, db: &Database) -> Result<()> {
+        self.data_mut()
+            .ok_or_else(|| anyhow!("series must be loaded to change its synonyms"))?
+            .remove_synonym(synonym, db)?;
+
+        self.rescan(config, db)
+    }
+
+    /// Scan the episodes of the series again, after a change that affects how they're found.
+    fn rescan(&mut self, config: &Config, db: &Database) -> Result<()> {
+        match self {
+            Self::Complete(series) => {
+                series.episodes = Series::scan_episodes(&series.data, config)?;
+            }
+            Self::Partial(_, _) | Self::Unscanned(_) | Self::None(_, _) => {
+                self.try_load(config, db)
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the episode category to watch, saves it, and reloads the series.
+    ///
+    /// Unlike most changes, this can also be applied to series that failed to find their episodes,
+    /// as picking a category can resolve a series needing to be split.
+    pub fn set_episode_category(
+        &mut self,
+        category: Option<SeriesKind>,
+        config: &Config,
+        db: &Database,
+    ) -> Result<()> {
+        match self {
+            Self::Complete(series) => {
+                series.set_episode_category(category, config)?;
+                series.save(db)?;
+            }
+            Self::Partial(data, _) | Self::Unscanned(data) => {
+                data.config.episode_category = category;
+                data.config.save(db)?;
+                self.try_load(config, db);
+            }
+            Self::None(_, _) => {
+                return Err(anyhow!(
+                    "series must be loaded to change its episode category"
+                ))
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn update(
         &mut self,
         params: UpdateParams,
@@ -409,13 +1347,15 @@ impl LoadedSeries {
                 series.update(params, config, db, remote)?;
                 series.save(db)?;
             }
-            Self::Partial(data, _) => {
+            Self::Partial(data, _) | Self::Unscanned(data) => {
                 data.update(params, db, remote)?;
                 data.save(db)?;
+                self.try_load(config, db);
             }
             Self::None(cfg, _) => {
                 cfg.update(params, db, remote)?;
                 cfg.save(db)?;
+                self.try_load(config, db);
             }
         }
 
@@ -491,6 +1431,35 @@ pub struct UpdateParams {
     pub path: Option<SeriesPath>,
     pub parser: Option<EpisodeParser>,
     pub episodes: Option<SortedEpisodes>,
+    /// The info and list entry of the series with the new `id`, which must be fetched before the ID can be changed.
+    pub fetched: Option<(SeriesInfo, SeriesEntry)>,
+}
+
+impl UpdateParams {
+    /// Returns true if these parameters would change the ID of the series with `current_id`.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn changes_id(&self, current_id: i32) -> bool {
+        matches!(self.id, Some(id) if id as i32 != current_id)
+    }
+
+    /// Look up the info and list entry of the series with the new ID on `remote`.
+    pub async fn fetch(&mut self, remote: &Remote) -> Result<()> {
+        let id = match self.id {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+
+        let info = SeriesInfo::from_remote_by_id(id, remote)
+            .await
+            .context("getting series info")?;
+
+        let entry = SeriesEntry::from_remote(remote, &info)
+            .await
+            .context("getting series entry")?;
+
+        self.fetched = Some((info, entry));
+        Ok(())
+    }
 }
 
 pub struct LastWatched(Option<String>);
@@ -553,6 +1522,18 @@ impl LastWatched {
 pub struct SeriesPath(PathBuf);
 
 impl SeriesPath {
+    /// Returns a path for series that don't have their episodes on disk yet.
+    #[inline(always)]
+    pub fn unset() -> Self {
+        Self(PathBuf::new())
+    }
+
+    /// Returns true if the path points to a folder, rather than being created with `SeriesPath::unset`.
+    #[inline(always)]
+    pub fn is_set(&self) -> bool {
+        !self.0.as_os_str().is_empty()
+    }
+
     #[inline(always)]
     pub fn new<'a, P>(path: P, config: &Config) -> Self
     where
@@ -570,6 +1551,11 @@ impl SeriesPath {
         Self(path)
     }
 
+    /// Create a path to a folder in the series directory named after `name`, with every character that can't be used in a filename replaced.
+    pub fn with_name(name: &str, filenames: &FilenameConfig) -> Self {
+        Self(sanitize::filename(name, filenames).into())
+    }
+
     #[inline(always)]
     pub fn absolute(&self, config: &Config) -> Cow<Path> {
         self.absolute_base(&config.series_dir)
@@ -612,6 +1598,18 @@ impl SeriesPath {
         )
     }
 
+    /// Returns the existing folder in the series directory that best matches a series with `titles` or `nickname`.
+    ///
+    /// Each title is tried in order before falling back to the nickname.
+    pub fn closest_to_series(titles: &[String], nickname: &str, config: &Config) -> Option<Self> {
+        titles
+            .iter()
+            .map(String::as_str)
+            .chain(iter::once(nickname))
+            .find_map(|name| Self::closest_matching(name, config).ok())
+            .filter(|path| path.absolute(config).exists())
+    }
+
     #[inline(always)]
     pub fn inner(&self) -> &PathBuf {
         &self.0
@@ -778,6 +1776,25 @@ where
     Some(nickname.join(SPACER))
 }
 
+/// Generates a nickname for the `season` of the series nicknamed `base`, such as `base_2` for its second season.
+///
+/// If the nickname is already taken according to `is_taken`, an extra number will be appended to it until a free one is found.
+pub fn season_nickname<F>(base: &str, season: u32, is_taken: F) -> String
+where
+    F: Fn(&str) -> bool,
+{
+    let nickname = format!("{}_{}", base, season);
+
+    if !is_taken(&nickname) {
+        return nickname;
+    }
+
+    (2..)
+        .map(|num| format!("{}_{}", nickname, num))
+        .find(|candidate| !is_taken(candidate))
+        .unwrap_or(nickname)
+}
+
 fn parse_season_number(slice: &str) -> Option<&str> {
     let is_digits = |digits: &[u8]| digits.iter().all(u8::is_ascii_digit);
 
@@ -795,6 +1812,135 @@ fn parse_season_number(slice: &str) -> Option<&str> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anime::local::Episode;
+    use std::env;
+    use std::os::unix::fs::symlink;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    #[test]
+    fn broken_episode_symlink_detection() {
+        let dir = env::temp_dir().join("anup_fixture_broken_symlink");
+
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        fs::create_dir_all(&dir).unwrap();
+
+        fs::File::create(dir.join("Series - 01.mkv")).unwrap();
+        symlink(dir.join("Series - 01.mkv"), dir.join("Series - 02.mkv")).unwrap();
+        symlink(dir.join("missing.mkv"), dir.join("Series - 03.mkv")).unwrap();
+
+        let episodes = CategorizedEpisodes::parse(&dir, &EpisodeParser::default())
+            .unwrap()
+            .take_season_episodes_or_present()
+            .unwrap();
+
+        let broken = Series::find_broken_episodes(&dir, &episodes);
+
+        let missing =
+            SortedEpisodes::with_episodes(vec![Episode::new(4, "Series - 04.mkv".into())]);
+        let broken_missing = Series::find_broken_episodes(&dir, &missing);
+
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(episodes.len(), 3);
+        assert_eq!(broken, vec![String::from("Series - 03.mkv")]);
+        assert_eq!(broken_missing, vec![String::from("Series - 04.mkv")]);
+    }
+
+    #[test]
+    fn crashed_player_counts_when_watched_long_enough() {
+        let mut config = Config::default();
+        // Exit code 1
+        let crashed = ExitStatus::from_raw(1 << 8);
+        let exited = ExitStatus::from_raw(0);
+
+        let counts = |status, watched, config: &Config| {
+            episode_counts_as_watched(&PlaybackResult::new(status, watched), config)
+        };
+
+        assert!(counts(exited, true, &config));
+        assert!(!counts(crashed, true, &config));
+        assert!(!counts(exited, false, &config));
+
+        config.episode.count_on_crash_if_watched = true;
+
+        assert!(counts(crashed, true, &config));
+        assert!(!counts(crashed, false, &config));
+    }
+
+    #[test]
+    fn idle_player_does_not_count() {
+        let config = Config::default();
+
+        let result = PlaybackResult {
+            idle: true,
+            ..PlaybackResult::new(ExitStatus::from_raw(0), true)
+        };
+
+        assert!(!episode_counts_as_watched(&result, &config));
+    }
+
+    #[test]
+    fn player_crash_on_start_never_counts() {
+        let mut config = Config::default();
+        config.episode.count_on_crash_if_watched = true;
+
+        let result = PlaybackResult {
+            crashed: true,
+            ..PlaybackResult::new(ExitStatus::from_raw(1 << 8), true)
+        };
+
+        assert!(!episode_counts_as_watched(&result, &config));
+    }
+
+    #[test]
+    fn declined_watch_start_keeps_plan_to_watch() {
+        let config = Config {
+            confirm_start_from_plan: true,
+            ..Config::default()
+        };
+
+        let mut entry = SeriesEntry::from(1);
+
+        assert!(entry.needs_watch_confirmation(&config));
+
+        entry.begin_watching(WatchStart::KeepStatus, 12, &config);
+
+        assert_eq!(entry.status(), Status::PlanToWatch);
+        assert_eq!(entry.watched_episodes(), 0);
+        assert!(!entry.needs_sync());
+
+        entry.begin_watching(WatchStart::UpdateStatus, 12, &config);
+
+        assert_eq!(entry.status(), Status::Watching);
+        assert!(!entry.needs_watch_confirmation(&config));
+    }
+
+    #[test]
+    fn season_nicknames() {
+        let no_series = |_: &str| false;
+
+        assert_eq!(
+            season_nickname("kaguya_sama", 2, no_series),
+            "kaguya_sama_2"
+        );
+        assert_eq!(
+            season_nickname("kaguya_sama", 3, no_series),
+            "kaguya_sama_3"
+        );
+
+        let existing = ["kaguya_sama_2", "kaguya_sama_2_2"];
+        let is_taken = |nickname: &str| existing.contains(&nickname);
+
+        assert_eq!(
+            season_nickname("kaguya_sama", 2, is_taken),
+            "kaguya_sama_2_3"
+        );
+        assert_eq!(season_nickname("kaguya_sama", 3, is_taken), "kaguya_sama_3");
+    }
 
     #[test]
     fn nickname_generation() {
@@ -0,0 +1,616 @@
+use crate::config::ChapterSkipConfig;
+use crate::logging::STATUS_TARGET;
+use crate::mpv::{Connection, IpcSocket};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::process::ExitStatus;
+use std::sync::Arc;
+use std::time::{self, Instant};
+use tokio::process::Child;
+use tokio::sync::Notify;
+use tokio::time::{sleep, timeout};
+
+/// A running episode player.
+pub struct PlaybackHandle {
+    child: Child,
+    ipc: Option<IpcSocket>,
+    progress_time: DateTime<Utc>,
+    /// The fraction of the episode that must be watched for it to count.
+    must_watch: f64,
+    /// The length of the episode in seconds, for when the player cannot tell us.
+    episode_secs: f64,
+    already_watched_secs: f64,
+    /// How long the player can sit paused before it's exited for the episode to no longer count, if there's a limit.
+    max_idle_secs: Option<f64>,
+    skipper: Option<ChapterSkipper>,
+    /// Notified when the user has finished watching a stream.
+    stream_finished: Option<Arc<Notify>>,
+    started: Instant,
+}
+
+impl PlaybackHandle {
+    /// How often the player is asked for its playback position.
+    const POLL_INTERVAL: time::Duration = time::Duration::from_secs(1);
+
+    /// How many times to try connecting to the player before falling back to wall-clock tracking.
+    const MAX_CONNECT_ATTEMPTS: u32 = 10;
+
+    /// The minimum number of seconds the estimated progress time has to change by before it is reported again.
+    const REPORT_THRESHOLD_SECS: i64 = 30;
+
+    /// How soon the player has to exit with an error after starting for it to be considered a crash.
+    const CRASH_WINDOW: time::Duration = time::Duration::from_secs(5);
+
+    pub(super) fn new(
+        child: Child,
+        ipc: Option<IpcSocket>,
+        progress_time: DateTime<Utc>,
+        must_watch: f32,
+        episode_secs: f64,
+    ) -> Self {
+        Self {
+            child,
+            ipc,
+            progress_time,
+            must_watch: f64::from(must_watch),
+            episode_secs,
+            already_watched_secs: 0.0,
+            max_idle_secs: None,
+            skipper: None,
+            stream_finished: None,
+            started: Instant::now(),
+        }
+    }
+
+    /// Treat the player as something that opens a stream of the episode, such as a web browser.
+    ///
+    /// Since these usually exit right away, playback lasts until the episode counts as watched, or until the
+    /// [`Self::stream_finished`] signal is notified.
+    pub(super) fn streaming(mut self) -> Self {
+        self.stream_finished = Some(Arc::new(Notify::new()));
+        self
+    }
+
+    /// Returns the signal to notify once the user has finished watching the stream, if the episode is being streamed.
+    #[inline(always)]
+    pub fn stream_finished(&self) -> Option<Arc<Notify>> {
+        self.stream_finished.clone()
+    }
+
+    /// Skip past chapters that are openings or endings when asked to, or as soon as they start if `config` says to.
+    ///
+    /// This only works when the player can be tracked.
+    pub(super) fn skip_chapters(mut self, config: &ChapterSkipConfig) -> Self {
+        if !config.key.is_empty() || config.auto_skip {
+            self.skipper = Some(ChapterSkipper::new(config));
+        }
+
+        self
+    }
+
+    /// Don't count the episode if the player was left paused for more than `mins` minutes before it exited,
+    /// such as when the user falls asleep. A limit of zero disables this.
+    ///
+    /// This only works when the player can be tracked.
+    pub(super) fn max_idle(mut self, mins: u32) -> Self {
+        self.max_idle_secs = Some(f64::from(mins) * 60.0).filter(|&secs| secs > 0.0);
+        self
+    }
+
+    /// Count `secs` of the episode as already watched, such as when resuming playback.
+    pub(super) fn already_watched(mut self, secs: f64) -> Self {
+        self.already_watched_secs = secs;
+        self.progress_time -= Duration::seconds(secs as i64);
+        self
+    }
+
+    /// Returns the time the episode is expected to count as watched, assuming it isn't paused or skipped through.
+    #[inline(always)]
+    pub fn progress_time(&self) -> DateTime<Utc> {
+        self.progress_time
+    }
+
+    /// Wait for the player to exit.
+    ///
+    /// If the player's real playback position can be tracked, `on_progress` will be called with a new
+    /// estimate of when the episode will count as watched whenever it changes significantly
+    /// (such as when the player is paused or seeked).
+    pub async fn wait<F>(self, on_progress: F) -> Result<PlaybackResult>
+    where
+        F: FnMut(DateTime<Utc>),
+    {
+        let started = self.started;
+        let mut result = self.wait_for_exit(on_progress).await?;
+
+        result.crashed = !result.status.success() && started.elapsed() < Self::CRASH_WINDOW;
+
+        Ok(result)
+    }
+
+    async fn wait_for_exit<F>(mut self, mut on_progress: F) -> Result<PlaybackResult>
+    where
+        F: FnMut(DateTime<Utc>),
+    {
+        let ipc = match self.ipc.take() {
+            Some(ipc) => ipc,
+            None => {
+                let status = self
+                    .child
+                    .wait()
+                    .await
+                    .context("waiting for player to exit")?;
+
+                if let Some(finished) = &self.stream_finished {
+                    let remaining = (self.progress_time - Utc::now())
+                        .to_std()
+                        .unwrap_or_default();
+
+                    timeout(remaining, finished.notified()).await.ok();
+                }
+
+                return Ok(PlaybackResult::new(
+                    status,
+                    Utc::now() >= self.progress_time,
+                ));
+            }
+        };
+
+        let mut conn: Option<Connection> = None;
+        let mut connect_attempts = 0;
+        let mut tracker: Option<WatchTracker> = None;
+        let mut last_reported = self.progress_time;
+        let mut last_poll = Instant::now();
+
+        loop {
+            if let Some(status) = self.child.try_wait().context("checking player status")? {
+                let result = match &tracker {
+                    Some(tracker) => PlaybackResult {
+                        status,
+                        reached_progress: tracker
+                            .remaining_secs(self.must_watch, self.episode_secs)
+                            <= 0.0,
+                        position: tracker.last_position.map(|pos| (pos, tracker.watched_secs)),
+                        idle: self
+                            .max_idle_secs
+                            .map_or(false, |max| tracker.paused_secs > max),
+                        crashed: false,
+                    },
+                    None => PlaybackResult::new(status, Utc::now() >= self.progress_time),
+                };
+
+                return Ok(result);
+            }
+
+            sleep(Self::POLL_INTERVAL).await;
+
+            if conn.is_none() && connect_attempts < Self::MAX_CONNECT_ATTEMPTS {
+                connect_attempts += 1;
+                conn = ipc.connect().await.ok();
+
+                if let (Some(connection), Some(skipper)) = (&mut conn, &self.skipper) {
+                    skipper.bind_key(connection).await;
+                }
+            }
+
+            let connection = match &mut conn {
+                Some(connection) => connection,
+                None => continue,
+            };
+
+            let status = match PlayerStatus::poll(connection).await {
+                Ok(Some(status)) => status,
+                Ok(None) => continue,
+                // The player is most likely exiting
+                Err(_) => {
+                    conn = None;
+                    continue;
+                }
+            };
+
+            let elapsed = last_poll.elapsed().as_secs_f64();
+            last_poll = Instant::now();
+
+            let tracker =
+                tracker.get_or_insert_with(|| WatchTracker::new(self.already_watched_secs));
+            tracker.update(&status, elapsed);
+
+            if let Some(skipper) = &mut self.skipper {
+                if let Err(err) = skipper.update(connection).await {
+                    tracing::warn!("failed to skip chapter: {:#}", err);
+                }
+            }
+
+            let remaining = tracker.remaining_secs(self.must_watch, self.episode_secs);
+            let estimate = Utc::now() + Duration::seconds(remaining.max(0.0).ceil() as i64);
+
+            let changed_significantly =
+                (estimate - last_reported).num_seconds().abs() >= Self::REPORT_THRESHOLD_SECS;
+            let just_finished = remaining <= 0.0 && last_reported > Utc::now();
+
+            if changed_significantly || just_finished {
+                last_reported = estimate;
+                on_progress(estimate);
+            }
+        }
+    }
+}
+
+pub struct PlaybackResult {
+    pub status: ExitStatus,
+    /// Indicates whether or not enough of the episode was watched for it to count.
+    pub reached_progress: bool,
+    /// The last playback position of the player and the total number of seconds watched, if the player could be tracked.
+    pub position: Option<(f64, f64)>,
+    /// Indicates whether the player was left paused for too long before it exited for the episode to count.
+    pub idle: bool,
+    /// Indicates whether the player exited with an error right after it started.
+    pub crashed: bool,
+}
+
+impl PlaybackResult {
+    #[inline(always)]
+    pub fn new(status: ExitStatus, reached_progress: bool) -> Self {
+        Self {
+            status,
+            reached_progress,
+            position: None,
+            idle: false,
+            crashed: false,
+        }
+    }
+}
+
+struct PlayerStatus {
+    position: f64,
+    paused: bool,
+    duration: Option<f64>,
+}
+
+impl PlayerStatus {
+    /// Get the current status of the player.
+    ///
+    /// Returns None if the player hasn't started playing the episode yet.
+    async fn poll(conn: &mut Connection) -> Result<Option<Self>> {
+        let position = match conn.get_property("playback-time").await? {
+            Some(position) => position,
+            None => return Ok(None),
+        };
+
+        let paused = conn.get_property("pause").await?.unwrap_or(false);
+        let duration = conn.get_property("duration").await?;
+
+        Ok(Some(Self {
+            position,
+            paused,
+            duration,
+        }))
+    }
+}
+
+/// Seeks past chapters with a title that marks them as an opening or ending.
+struct ChapterSkipper {
+    key: String,
+    auto_skip: bool,
+    patterns: Vec<String>,
+    /// The chapters of the episode, once the player has loaded them.
+    chapters: Option<Vec<Chapter>>,
+    /// The index of every chapter that has already been skipped, so seeking back into one doesn't skip it again.
+    skipped: HashSet<usize>,
+}
+
+impl ChapterSkipper {
+    /// The `script-message` sent by the player when the skip key is pressed.
+    const SKIP_MESSAGE: &'static str = "anup-skip-chapter";
+
+    fn new(config: &ChapterSkipConfig) -> Self {
+        Self {
+            key: config.key.clone(),
+            auto_skip: config.auto_skip,
+            patterns: config.chapters.clone(),
+            chapters: None,
+            skipped: HashSet::new(),
+        }
+    }
+
+    /// Have the player tell us when the skip key is pressed.
+    async fn bind_key(&self, conn: &mut Connection) {
+        if self.key.is_empty() {
+            return;
+        }
+
+        let command = format!("script-message {}", Self::SKIP_MESSAGE);
+
+        // The keybind command needs a recent version of mpv, so auto-skipping can still work without it
+        if let Err(err) = conn.command(json!(["keybind", self.key, command])).await {
+            tracing::debug!("failed to bind chapter skip key: {:#}", err);
+        }
+    }
+
+    async fn update(&mut self, conn: &mut Connection) -> Result<()> {
+        let requested = conn
+            .take_messages()
+            .iter()
+            .any(|args| args.first().map(String::as_str) == Some(Self::SKIP_MESSAGE));
+
+        if !requested && !self.auto_skip {
+            return Ok(());
+        }
+
+        if self.chapters.is_none() {
+            self.chapters = conn.get_property("chapter-list").await?;
+        }
+
+        let chapters = match &self.chapters {
+            Some(chapters) => chapters,
+            None => return Ok(()),
+        };
+
+        // The current chapter is -1 when the episode has chapters that don't start at the beginning
+        let current = match conn.get_property::<i64>("chapter").await? {
+            Some(current) if current >= 0 => current as usize,
+            _ => return Ok(()),
+        };
+
+        if !requested && self.skipped.contains(&current) {
+            return Ok(());
+        }
+
+        let skip = match ChapterSkip::find(chapters, current, &self.patterns) {
+            Some(skip) => skip,
+            None if requested => {
+                tracing::debug!("no opening or ending to skip in chapter {}", current);
+                return Ok(());
+            }
+            None => return Ok(()),
+        };
+
+        self.skipped.extend(current..skip.next_chapter);
+
+        let seek = match skip.target_secs {
+            Some(secs) => json!(["seek", secs, "absolute"]),
+            None => json!(["seek", 100, "absolute-percent"]),
+        };
+
+        conn.command(seek).await?;
+
+        let message = format!("skipped {}", skip.title);
+        conn.command(json!(["show-text", message])).await.ok();
+        tracing::info!(target: STATUS_TARGET, "{}", message);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Chapter {
+    #[serde(default)]
+    title: String,
+    time: f64,
+}
+
+impl Chapter {
+    /// Returns true if one of `patterns` appears in the title of the chapter as a whole word, ignoring case.
+    fn matches(&self, patterns: &[String]) -> bool {
+        let words = Self::words(&self.title);
+
+        patterns.iter().any(|pattern| {
+            let pattern = Self::words(pattern);
+
+            !pattern.is_empty()
+                && words
+                    .windows(pattern.len())
+                    .any(|window| window == pattern.as_slice())
+        })
+    }
+
+    fn words(text: &str) -> Vec<String> {
+        text.split(|ch: char| !ch.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    }
+}
+
+/// Where to seek to in order to skip the opening or ending being played.
+struct ChapterSkip<'a> {
+    title: &'a str,
+    /// The index of the first chapter after the skipped ones.
+    next_chapter: usize,
+    /// The time to seek to, or None to seek to the end of the episode.
+    target_secs: Option<f64>,
+}
+
+impl<'a> ChapterSkip<'a> {
+    /// Returns where to seek to in order to skip the chapter at `current` in `chapters`, along with any chapters
+    /// right after it that also match `patterns`.
+    ///
+    /// Returns None if the current chapter doesn't match `patterns`.
+    fn find(chapters: &'a [Chapter], current: usize, patterns: &[String]) -> Option<Self> {
+        let chapter = chapters.get(current)?;
+
+        if !chapter.matches(patterns) {
+            return None;
+        }
+
+        let next_chapter = chapters[current..]
+            .iter()
+            .position(|chapter| !chapter.matches(patterns))
+            .map_or(chapters.len(), |offset| current + offset);
+
+        Some(Self {
+            title: &chapter.title,
+            next_chapter,
+            target_secs: chapters.get(next_chapter).map(|chapter| chapter.time),
+        })
+    }
+}
+
+/// Keeps track of how much of an episode has actually been played.
+#[derive(Default)]
+struct WatchTracker {
+    watched_secs: f64,
+    /// How long the player has been paused for without being unpaused.
+    paused_secs: f64,
+    last_position: Option<f64>,
+    duration: Option<f64>,
+}
+
+impl WatchTracker {
+    /// The fastest playback speed that is considered watching rather than skipping.
+    const MAX_PLAYBACK_SPEED: f64 = 2.0;
+
+    fn new(watched_secs: f64) -> Self {
+        Self {
+            watched_secs,
+            ..Self::default()
+        }
+    }
+
+    fn update(&mut self, status: &PlayerStatus, elapsed_secs: f64) {
+        if let (Some(last_position), false) = (self.last_position, status.paused) {
+            let delta = status.position - last_position;
+
+            // Anything further than what could have been played since the last update must have been seeked past
+            if delta > 0.0 && delta <= elapsed_secs * Self::MAX_PLAYBACK_SPEED + 1.0 {
+                self.watched_secs += delta;
+            }
+        }
+
+        if status.paused {
+            self.paused_secs += elapsed_secs;
+        } else {
+            self.paused_secs = 0.0;
+        }
+
+        self.last_position = Some(status.position);
+
+        if status.duration.is_some() {
+            self.duration = status.duration;
+        }
+    }
+
+    /// Returns the number of seconds that still need to be watched for the episode to count.
+    fn remaining_secs(&self, must_watch: f64, episode_secs: f64) -> f64 {
+        let duration = self.duration.unwrap_or(episode_secs);
+        duration * must_watch - self.watched_secs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(position: f64, paused: bool) -> PlayerStatus {
+        PlayerStatus {
+            position,
+            paused,
+            duration: Some(100.0),
+        }
+    }
+
+    #[test]
+    fn tracker_counts_playback() {
+        let mut tracker = WatchTracker::default();
+
+        for pos in 0..=50 {
+            tracker.update(&status(f64::from(pos), false), 1.0);
+        }
+
+        assert!((tracker.watched_secs - 50.0).abs() < f64::EPSILON);
+        assert!(tracker.remaining_secs(0.5, 0.0) <= 0.0);
+    }
+
+    #[test]
+    fn tracker_ignores_seeks_and_pauses() {
+        let mut tracker = WatchTracker::default();
+
+        tracker.update(&status(0.0, false), 1.0);
+        tracker.update(&status(1.0, false), 1.0);
+        // Seek forward
+        tracker.update(&status(80.0, false), 1.0);
+        tracker.update(&status(81.0, false), 1.0);
+        // Seek while paused
+        tracker.update(&status(82.0, true), 1.0);
+        tracker.update(&status(83.0, false), 1.0);
+        // Seek backwards
+        tracker.update(&status(10.0, false), 1.0);
+
+        assert!((tracker.watched_secs - 3.0).abs() < f64::EPSILON);
+        assert!((tracker.remaining_secs(0.5, 0.0) - 47.0).abs() < f64::EPSILON);
+    }
+
+    fn chapters(titles: &[&str]) -> Vec<Chapter> {
+        titles
+            .iter()
+            .enumerate()
+            .map(|(i, title)| Chapter {
+                title: title.to_string(),
+                time: i as f64 * 60.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn skip_opening_and_ending_chapters() {
+        let patterns = ChapterSkipConfig::default().chapters;
+        let chapters = chapters(&["Prologue", "Opening", "OP 2", "Part A", "Ending"]);
+
+        assert!(ChapterSkip::find(&chapters, 0, &patterns).is_none());
+        assert!(ChapterSkip::find(&chapters, 3, &patterns).is_none());
+
+        let skip = ChapterSkip::find(&chapters, 1, &patterns).unwrap();
+        assert_eq!(skip.title, "Opening");
+        assert_eq!(skip.next_chapter, 3);
+        assert_eq!(skip.target_secs, Some(180.0));
+
+        let skip = ChapterSkip::find(&chapters, 4, &patterns).unwrap();
+        assert_eq!(skip.next_chapter, 5);
+        assert_eq!(skip.target_secs, None);
+    }
+
+    #[test]
+    fn chapter_patterns_match_whole_words() {
+        let patterns = vec!["ED".into(), "Opening Song".into()];
+        let matches = |title: &str| {
+            Chapter {
+                title: title.into(),
+                time: 0.0,
+            }
+            .matches(&patterns)
+        };
+
+        assert!(matches("ed"));
+        assert!(matches("Episode 5 - ED"));
+        assert!(matches("opening song"));
+        assert!(!matches("Edited"));
+        assert!(!matches("Opening"));
+    }
+
+    #[test]
+    fn tracker_measures_latest_pause() {
+        let mut tracker = WatchTracker::default();
+
+        tracker.update(&status(0.0, false), 1.0);
+        tracker.update(&status(0.0, true), 60.0);
+        tracker.update(&status(0.0, true), 60.0);
+        assert!((tracker.paused_secs - 120.0).abs() < f64::EPSILON);
+
+        // Unpausing means someone is still watching
+        tracker.update(&status(1.0, false), 1.0);
+        tracker.update(&status(1.0, true), 30.0);
+        assert!((tracker.paused_secs - 30.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn tracker_counts_resumed_time() {
+        let mut tracker = WatchTracker::new(40.0);
+
+        tracker.update(&status(60.0, false), 1.0);
+        tracker.update(&status(70.0, false), 10.0);
+
+        assert!(tracker.remaining_secs(0.5, 0.0) <= 0.0);
+    }
+}
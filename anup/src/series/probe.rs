@@ -0,0 +1,51 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Returns how many seconds the media file at `path` lasts according to ffprobe.
+///
+/// None is returned when ffprobe can't be run or doesn't know the duration of the file.
+pub fn duration_secs(path: &Path) -> Option<f64> {
+    let output = Command::new("ffprobe")
+        .args(&["-v", "error", "-show_entries", "format=duration"])
+        .args(&["-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(path)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output();
+
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            tracing::warn!(path = %path.display(), status = %output.status, "ffprobe failed");
+            return None;
+        }
+        Err(err) => {
+            tracing::warn!(%err, "failed to run ffprobe");
+            return None;
+        }
+    };
+
+    parse_duration(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse the duration printed by ffprobe, which is "N/A" for files without one.
+fn parse_duration(output: &str) -> Option<f64> {
+    output
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ffprobe_duration() {
+        assert_eq!(parse_duration("1420.565000\n"), Some(1420.565));
+        assert_eq!(parse_duration("N/A\n"), None);
+        assert_eq!(parse_duration("0.000000"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+}
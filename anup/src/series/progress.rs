@@ -0,0 +1,69 @@
+use crate::database::schema::episode_progress;
+use crate::database::Database;
+use diesel::prelude::*;
+
+/// The last playback position of an episode that was not finished.
+#[derive(Queryable, Insertable)]
+#[table_name = "episode_progress"]
+pub struct EpisodeProgress {
+    pub series_id: i32,
+    pub episode: i16,
+    pub position_secs: i32,
+    /// The number of seconds of the episode that were actually watched, not including anything that was skipped.
+    pub watched_secs: i32,
+}
+
+impl EpisodeProgress {
+    /// Positions closer than this to the start of an episode are not worth resuming from.
+    pub const MIN_RESUME_SECS: i32 = 30;
+
+    /// How many seconds before the saved position playback should resume from, to make it easier to
+    /// remember what was happening.
+    const REWIND_SECS: i32 = 5;
+
+    pub fn load(db: &Database, sid: i32, ep: i16) -> diesel::QueryResult<Option<Self>> {
+        use crate::database::schema::episode_progress::dsl::{
+            episode, episode_progress, series_id,
+        };
+
+        episode_progress
+            .filter(series_id.eq(sid))
+            .filter(episode.eq(ep))
+            .get_result(db.conn())
+            .optional()
+    }
+
+    pub fn load_all(db: &Database, sid: i32) -> diesel::QueryResult<Vec<Self>> {
+        use crate::database::schema::episode_progress::dsl::{
+            episode, episode_progress, series_id,
+        };
+
+        episode_progress
+            .filter(series_id.eq(sid))
+            .order(episode.asc())
+            .load(db.conn())
+    }
+
+    pub fn save(&self, db: &Database) -> diesel::QueryResult<usize> {
+        use crate::database::schema::episode_progress::dsl::episode_progress;
+
+        diesel::replace_into(episode_progress)
+            .values(self)
+            .execute(db.conn())
+    }
+
+    pub fn clear(db: &Database, sid: i32, ep: i16) -> diesel::QueryResult<usize> {
+        use crate::database::schema::episode_progress::dsl::{
+            episode, episode_progress, series_id,
+        };
+
+        diesel::delete(episode_progress.filter(series_id.eq(sid).and(episode.eq(ep))))
+            .execute(db.conn())
+    }
+
+    /// Returns the position in seconds that playback should resume from.
+    #[inline(always)]
+    pub fn resume_secs(&self) -> i32 {
+        (self.position_secs - Self::REWIND_SECS).max(0)
+    }
+}
@@ -0,0 +1,132 @@
+use super::LoadedSeries;
+use anime::remote::anilist::Recommendation;
+use anime::remote::{SeriesID, SeriesInfo, Status};
+use std::collections::{HashMap, HashSet};
+
+/// How many of the highest scored completed series recommendations are based on.
+pub const MAX_LIKED: usize = 10;
+
+/// A series recommended for one or more of the series the user liked.
+#[derive(Clone, Debug)]
+pub struct Recommended {
+    pub info: SeriesInfo,
+    /// The IDs of the liked series it was recommended for.
+    pub sources: Vec<SeriesID>,
+    /// The combined rating of every recommendation of the series.
+    pub rating: i32,
+}
+
+/// Returns the IDs of the completed series with the highest scores, up to [`MAX_LIKED`] of them.
+///
+/// Completed series that haven't been scored are only used when there aren't enough scored ones.
+#[allow(clippy::cast_sign_loss)]
+pub fn liked_series(series: &[LoadedSeries]) -> Vec<SeriesID> {
+    let mut completed = series
+        .iter()
+        .filter_map(LoadedSeries::data)
+        .filter(|data| data.entry.status() == Status::Completed)
+        .map(|data| (data.entry.score(), data.info.id as SeriesID))
+        .collect::<Vec<_>>();
+
+    // None is ordered before Some, so sorting in reverse places series without a score last
+    completed.sort_unstable_by(|x, y| y.cmp(x));
+
+    completed
+        .into_iter()
+        .take(MAX_LIKED)
+        .map(|(_, id)| id)
+        .collect()
+}
+
+/// Combine the `recommendations` made for each liked series, with the highest rated first.
+///
+/// Series in `exclude` and those that more users disagree with than agree with are left out.
+pub fn merge(
+    recommendations: Vec<Recommendation>,
+    exclude: &HashSet<SeriesID>,
+) -> Vec<Recommended> {
+    let mut merged: HashMap<SeriesID, Recommended> = HashMap::new();
+
+    for Recommendation {
+        source,
+        info,
+        rating,
+    } in recommendations
+    {
+        if rating <= 0 || exclude.contains(&info.id) {
+            continue;
+        }
+
+        let existing = merged.entry(info.id).or_insert_with(|| Recommended {
+            info,
+            sources: Vec::new(),
+            rating: 0,
+        });
+
+        existing.rating += rating;
+        existing.sources.push(source);
+    }
+
+    let mut merged = merged.into_values().collect::<Vec<_>>();
+
+    merged.sort_unstable_by(|x, y| {
+        y.rating
+            .cmp(&x.rating)
+            .then_with(|| x.info.title.preferred.cmp(&y.info.title.preferred))
+    });
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anime::remote::{AiringStatus, SeriesTitle};
+    use anime::SeriesKind;
+
+    fn recommendation(source: SeriesID, id: SeriesID, rating: i32) -> Recommendation {
+        let title = format!("Series {}", id);
+
+        Recommendation {
+            source,
+            info: SeriesInfo {
+                id,
+                title: SeriesTitle {
+                    romaji: title.clone(),
+                    english: None,
+                    native: None,
+                    preferred: title,
+                    synonyms: Vec::new(),
+                },
+                episodes: 12,
+                episode_length: 24,
+                kind: SeriesKind::Season,
+                sequels: Vec::new(),
+                airing: AiringStatus::Finished,
+            },
+            rating,
+        }
+    }
+
+    #[test]
+    fn merge_recommendations() {
+        let recommendations = vec![
+            recommendation(1, 10, 20),
+            recommendation(2, 10, 5),
+            recommendation(1, 11, 30),
+            // Already in the list
+            recommendation(1, 2, 50),
+            // More users disagree than agree
+            recommendation(2, 12, -3),
+        ];
+
+        let exclude = [1, 2].iter().copied().collect();
+        let merged = merge(recommendations, &exclude);
+
+        let ids = merged.iter().map(|rec| rec.info.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![11, 10]);
+
+        assert_eq!(merged[1].rating, 25);
+        assert_eq!(merged[1].sources, vec![1, 2]);
+    }
+}
@@ -0,0 +1,558 @@
+use super::Series;
+use crate::config::{Config, FilenameConfig};
+use crate::database::Database;
+use crate::sanitize;
+use anime::local::{Episode, EpisodeParser};
+use anyhow::{anyhow, Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A filename template made up of text and placeholders like `{title}` or `{episode:02}`.
+///
+/// The available placeholders are `title`, `season`, `episode`, `group`, and `ext`.
+/// A number after a colon pads the value with zeros to that many digits, and `{{` / `}}` insert literal braces.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template(Vec<Part>);
+
+impl Template {
+    /// Render the filename of the episode described by `values`.
+    ///
+    /// Brackets left empty by a missing release group are removed along with any extra whitespace.
+    pub fn render(&self, values: &EpisodeValues) -> String {
+        let mut name = String::new();
+
+        for part in &self.0 {
+            match part {
+                Part::Text(text) => name.push_str(text),
+                Part::Field(field, width) => name.push_str(&field.value(values, *width)),
+            }
+        }
+
+        let mut name = name.replace("[]", "").replace("()", "");
+
+        while name.contains("  ") {
+            name = name.replace("  ", " ");
+        }
+
+        let mut name = name.trim().to_string();
+
+        if !values.ext.is_empty() {
+            if let Some(stem) = name.strip_suffix(values.ext) {
+                name = format!("{}{}", stem.trim_end(), values.ext);
+            }
+        }
+
+        name
+    }
+
+    /// Returns a custom episode pattern that matches filenames rendered with this template for a series with `title` and `season`.
+    ///
+    /// The pattern ends right after the episode number, and release groups are matched with a wildcard.
+    fn pattern(&self, title: &str, season: u32) -> String {
+        let values = EpisodeValues {
+            title,
+            season,
+            ..EpisodeValues::default()
+        };
+
+        let mut pattern = String::new();
+
+        for part in &self.0 {
+            match part {
+                Part::Text(text) => pattern.push_str(text),
+                Part::Field(Field::Episode, _) => {
+                    pattern.push('#');
+                    break;
+                }
+                Part::Field(Field::Group, _) => pattern.push('*'),
+                Part::Field(field, width) => pattern.push_str(&field.value(&values, *width)),
+            }
+        }
+
+        pattern
+    }
+}
+
+impl FromStr for Template {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = value.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => placeholder.push(ch),
+                            None => return Err(anyhow!("unclosed placeholder in template")),
+                        }
+                    }
+
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+
+                    parts.push(Part::parse_field(&placeholder)?);
+                }
+                '}' => return Err(anyhow!("unmatched }} in template")),
+                '/' => return Err(anyhow!("templates cannot contain a /")),
+                ch => text.push(ch),
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        if !parts
+            .iter()
+            .any(|part| matches!(part, Part::Field(Field::Episode, _)))
+        {
+            return Err(anyhow!("templates must contain an {{episode}} placeholder"));
+        }
+
+        Ok(Self(parts))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Part {
+    Text(String),
+    /// A placeholder along with the number of digits to pad it to.
+    Field(Field, usize),
+}
+
+impl Part {
+    fn parse_field(placeholder: &str) -> Result<Self> {
+        let (name, width) = match placeholder.split_once(':') {
+            Some((name, width)) => {
+                let width = width
+                    .parse()
+                    .map_err(|_| anyhow!("invalid padding in template: {}", placeholder))?;
+
+                (name, width)
+            }
+            None => (placeholder, 0),
+        };
+
+        let field = match name.trim() {
+            "title" => Field::Title,
+            "season" => Field::Season,
+            "episode" => Field::Episode,
+            "group" => Field::Group,
+            "ext" => Field::Ext,
+            _ => return Err(anyhow!("unknown placeholder in template: {{{}}}", name)),
+        };
+
+        Ok(Self::Field(field, width))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Field {
+    Title,
+    Season,
+    Episode,
+    Group,
+    Ext,
+}
+
+impl Field {
+    fn value(self, values: &EpisodeValues, width: usize) -> String {
+        let padded = |num: u32| format!("{:0width$}", num, width = width);
+
+        match self {
+            Self::Title => values.title.replace('/', " "),
+            Self::Season => padded(values.season),
+            Self::Episode => {
+                let mut episode = padded(values.episode);
+
+                if let Some(fraction) = values.fraction {
+                    write!(episode, ".{}", fraction).ok();
+                } else if values.last_episode > values.episode {
+                    write!(episode, "-{}", padded(values.last_episode)).ok();
+                }
+
+                episode
+            }
+            Self::Group => values.group.unwrap_or_default().replace('/', " "),
+            Self::Ext => values.ext.into(),
+        }
+    }
+}
+
+/// Everything a [`Template`] can insert into the filename of an episode.
+#[derive(Debug, Default)]
+pub struct EpisodeValues<'a> {
+    pub title: &'a str,
+    pub season: u32,
+    pub episode: u32,
+    pub last_episode: u32,
+    pub fraction: Option<u8>,
+    pub group: Option<&'a str>,
+    /// The extension of the episode file, including the leading dot.
+    pub ext: &'a str,
+}
+
+/// A single episode file to rename.
+#[derive(Debug, PartialEq)]
+pub struct Rename {
+    /// The path of the file relative to the series folder.
+    pub old_name: String,
+    /// The new path of the file relative to the series folder.
+    pub new_name: String,
+    episode: u32,
+    last_episode: u32,
+}
+
+/// Every episode file of a series that would be renamed by a [`Template`].
+pub struct RenamePlan {
+    pub renames: Vec<Rename>,
+    pattern: String,
+}
+
+impl RenamePlan {
+    /// Work out the new filename of every episode of `series` with `template`.
+    ///
+    /// Episodes that already have the right name are left out.
+    /// The title and release group are made safe to use in a filename with `filenames`.
+    pub fn new(
+        series: &Series,
+        template: &Template,
+        season: u32,
+        filenames: &FilenameConfig,
+    ) -> Result<Self> {
+        let title = sanitize::filename(&series.data.info.title_preferred, filenames);
+
+        let renames = series
+            .episodes
+            .iter()
+            .filter_map(|episode| Self::rename(episode, template, &title, season, filenames))
+            .filter(|rename| rename.old_name != rename.new_name)
+            .collect::<Vec<_>>();
+
+        let mut new_names = HashSet::with_capacity(renames.len());
+
+        for rename in &renames {
+            if !new_names.insert(&rename.new_name) {
+                return Err(anyhow!(
+                    "more than one episode would be renamed to {}",
+                    rename.new_name
+                ));
+            }
+        }
+
+        Ok(Self {
+            renames,
+            pattern: template.pattern(&title, season),
+        })
+    }
+
+    fn rename(
+        episode: &Episode,
+        template: &Template,
+        title: &str,
+        season: u32,
+        filenames: &FilenameConfig,
+    ) -> Option<Rename> {
+        let path = Path::new(&episode.filename);
+        let filename = path.file_name()?.to_string_lossy();
+
+        let ext = path
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+
+        let group = EpisodeParser::Tokenizer
+            .parse(filename.as_ref())
+            .ok()
+            .and_then(|parsed| parsed.release_group)
+            .map(|group| sanitize::filename(&group, filenames));
+
+        let values = EpisodeValues {
+            title,
+            season,
+            episode: episode.number,
+            last_episode: episode.last_number,
+            fraction: episode.fraction,
+            group: group.as_deref(),
+            ext: &ext,
+        };
+
+        let new_name = template.render(&values);
+
+        let new_name = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => {
+                parent.join(new_name).to_string_lossy().into_owned()
+            }
+            Some(_) | None => new_name,
+        };
+
+        Some(Rename {
+            old_name: episode.filename.clone(),
+            new_name,
+            episode: episode.number,
+            last_episode: episode.last_number,
+        })
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty()
+    }
+
+    /// Rename every episode file of `series` in the plan, and switch the series to an episode parser that can read the new filenames.
+    ///
+    /// The episode offset and absolute numbering of the series are turned off, as the new filenames already use the numbers they were shifted to.
+    /// Every file is renamed back if no parser can read the new filenames.
+    pub fn apply(&self, series: &mut Series, config: &Config, db: &Database) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let dir = series.data.config.path.absolute(config).into_owned();
+        self.rename_files(&dir)?;
+
+        match self.find_parser(series, config) {
+            Some(parser) => {
+                series.data.config.episode_parser = parser;
+                series.data.config.episode_offset = 0;
+                series.data.config.absolute_numbering = false;
+                series.episodes = Series::scan_episodes(&series.data, config)?;
+                series.save(db)?;
+                Ok(())
+            }
+            None => {
+                self.undo_rename_files(&dir)
+                    .context("failed to rename episodes back")?;
+
+                Err(anyhow!(
+                    "no episode parser can read the new filenames, so nothing was renamed"
+                ))
+            }
+        }
+    }
+
+    /// Returns the first parser that reads every renamed episode as the episode it was renamed to.
+    fn find_parser(&self, series: &Series, config: &Config) -> Option<EpisodeParser> {
+        let mut candidates = vec![
+            series.data.config.episode_parser.clone(),
+            EpisodeParser::default(),
+            EpisodeParser::custom(self.pattern.as_str()),
+            EpisodeParser::Tokenizer,
+        ];
+
+        candidates.dedup();
+
+        let expected = self
+            .renames
+            .iter()
+            .map(|rename| (rename.new_name.as_str(), rename))
+            .collect::<HashMap<_, _>>();
+
+        let titles = series.data.titles();
+
+        candidates.into_iter().find(|parser| {
+            let mut data = series.data.config.clone();
+            data.episode_parser = parser.clone();
+            data.episode_offset = 0;
+            data.absolute_numbering = false;
+
+            let dir = data.path.absolute(config);
+
+            let episodes = match super::parse_episode_dir(&dir, parser, &titles, config) {
+                Ok(episodes) => episodes,
+                Err(_) => return false,
+            };
+
+            let episodes = match data.episode_category {
+                Some(category) => episodes.take_category(category),
+                None => episodes.take_season_episodes_or_present(),
+            };
+
+            let episodes = match episodes {
+                Some(episodes) => episodes,
+                None => return false,
+            };
+
+            let mut found = 0;
+
+            for episode in episodes.iter() {
+                match expected.get(episode.filename.as_str()) {
+                    Some(rename)
+                        if rename.episode == episode.number
+                            && rename.last_episode == episode.last_number =>
+                    {
+                        found += 1;
+                    }
+                    Some(_) => return false,
+                    // Another file claiming a renamed episode would be played instead of it
+                    None if self
+                        .renames
+                        .iter()
+                        .any(|rename| rename.episode == episode.number) =>
+                    {
+                        return false
+                    }
+                    None => (),
+                }
+            }
+
+            found == self.renames.len()
+        })
+    }
+
+    /// Rename every file in the plan inside of `dir`.
+    ///
+    /// Files are moved to temporary names first, so episodes can safely trade names with each other.
+    fn rename_files(&self, dir: &Path) -> Result<()> {
+        let old_names = self
+            .renames
+            .iter()
+            .map(|rename| rename.old_name.as_str())
+            .collect::<HashSet<_>>();
+
+        for rename in &self.renames {
+            if dir.join(&rename.new_name).exists() && !old_names.contains(rename.new_name.as_str())
+            {
+                return Err(anyhow!(
+                    "{} already exists, so nothing was renamed",
+                    rename.new_name
+                ));
+            }
+        }
+
+        let temp_path = |rename: &Rename| dir.join(format!("{}.anup-rename", rename.old_name));
+        let mut moved = Vec::with_capacity(self.renames.len());
+
+        for rename in &self.renames {
+            if let Err(err) = fs::rename(dir.join(&rename.old_name), temp_path(rename)) {
+                for rename in moved {
+                    fs::rename(temp_path(rename), dir.join(&rename.old_name)).ok();
+                }
+
+                return Err(err).with_context(|| format!("failed to rename {}", rename.old_name));
+            }
+
+            moved.push(rename);
+        }
+
+        for rename in &self.renames {
+            fs::rename(temp_path(rename), dir.join(&rename.new_name))
+                .with_context(|| format!("failed to rename {}", rename.old_name))?;
+        }
+
+        Ok(())
+    }
+
+    fn undo_rename_files(&self, dir: &Path) -> Result<()> {
+        let undo = Self {
+            renames: self
+                .renames
+                .iter()
+                .map(|rename| Rename {
+                    old_name: rename.new_name.clone(),
+                    new_name: rename.old_name.clone(),
+                    episode: rename.episode,
+                    last_episode: rename.last_episode,
+                })
+                .collect(),
+            pattern: String::new(),
+        };
+
+        undo.rename_files(dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(episode: u32, group: Option<&str>) -> EpisodeValues<'_> {
+        EpisodeValues {
+            title: "Series Title",
+            season: 2,
+            episode,
+            last_episode: episode,
+            fraction: None,
+            group,
+            ext: ".mkv",
+        }
+    }
+
+    #[test]
+    fn template_rendering() {
+        let template: Template = "{title} - S{season:02}E{episode:02} [{group}]{ext}"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            template.render(&values(5, Some("Group"))),
+            "Series Title - S02E05 [Group].mkv"
+        );
+
+        // Brackets around a missing group shouldn't be left behind
+        assert_eq!(
+            template.render(&values(5, None)),
+            "Series Title - S02E05.mkv"
+        );
+
+        let span = EpisodeValues {
+            last_episode: 6,
+            ..values(5, None)
+        };
+
+        assert_eq!(template.render(&span), "Series Title - S02E05-06.mkv");
+
+        let recap = EpisodeValues {
+            fraction: Some(5),
+            ..values(12, None)
+        };
+
+        assert_eq!(template.render(&recap), "Series Title - S02E12.5.mkv");
+    }
+
+    #[test]
+    fn template_parsing() {
+        let template: Template = "{{{episode}}}".parse().unwrap();
+        assert_eq!(template.render(&values(3, None)), "{3}");
+
+        assert!("{title}".parse::<Template>().is_err());
+        assert!("{episode".parse::<Template>().is_err());
+        assert!("{episode:ab}".parse::<Template>().is_err());
+        assert!("{unknown} {episode}".parse::<Template>().is_err());
+        assert!("dir/{episode}".parse::<Template>().is_err());
+    }
+
+    #[test]
+    fn template_pattern() {
+        let template: Template = "[{group}] {title} - S{season:02}E{episode:02}{ext}"
+            .parse()
+            .unwrap();
+
+        let pattern = template.pattern("Series Title", 1);
+        assert_eq!(pattern, "[*] Series Title - S01E#");
+
+        let parser = EpisodeParser::custom(pattern);
+        let parsed = parser.parse("[Group] Series Title - S01E07.mkv").unwrap();
+
+        assert_eq!(parsed.episode, 7);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::database::Database;
+use anime::remote::Status;
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
+use diesel::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Statistics about everything that has been watched, computed entirely from the local database.
+#[derive(Debug, Default)]
+pub struct WatchStats {
+    /// The total number of episodes watched across every series, including rewatches.
+    pub episodes_watched: u32,
+    pub watch_time_mins: u32,
+    /// The mean score of every series that has been scored, out of 100.
+    pub mean_score: Option<f32>,
+    /// The number of series with each status, in the same order as `WatchStats::STATUSES`.
+    pub status_counts: Vec<(Status, u32)>,
+    /// The episodes recorded in the watch history for each year, starting with the most recent.
+    pub years: Vec<YearStats>,
+    /// The most consecutive days that had at least one episode recorded in the watch history.
+    pub longest_streak_days: u32,
+}
+
+impl WatchStats {
+    /// Every status a series can have, in the order they are displayed.
+    pub const STATUSES: [Status; 6] = [
+        Status::Watching,
+        Status::Rewatching,
+        Status::Completed,
+        Status::OnHold,
+        Status::Dropped,
+        Status::PlanToWatch,
+    ];
+
+    #[allow(clippy::cast_sign_loss)]
+    pub fn load(db: &Database) -> diesel::QueryResult<Self> {
+        use crate::database::schema::series_entries::dsl as entries;
+        use crate::database::schema::series_info::dsl as info;
+        use crate::database::schema::watch_history::dsl as history;
+
+        let episode_info = info::series_info
+            .select((info::id, info::episodes, info::episode_length_mins))
+            .load::<(i32, i16, i16)>(db.conn())?
+            .into_iter()
+            .map(|(id, episodes, length)| (id, (episodes, length)))
+            .collect::<HashMap<_, _>>();
+
+        let series = entries::series_entries
+            .select((
+                entries::id,
+                entries::watched_episodes,
+                entries::times_rewatched,
+                entries::score,
+                entries::status,
+            ))
+            .load::<(i32, i16, i16, Option<i16>, Status)>(db.conn())?;
+
+        let mut stats = Self::default();
+        let mut statuses = Vec::with_capacity(series.len());
+        let mut scores = Vec::new();
+
+        for (id, watched, rewatched, score, status) in series {
+            let (episodes, length) = episode_info.get(&id).copied().unwrap_or((0, 0));
+            let watched = watched.max(0) as u32 + rewatched.max(0) as u32 * episodes.max(0) as u32;
+
+            stats.episodes_watched += watched;
+            stats.watch_time_mins += watched * length.max(0) as u32;
+
+            if let Some(score) = score {
+                scores.push(f32::from(score));
+            }
+
+            statuses.push(status);
+        }
+
+        if !scores.is_empty() {
+            stats.mean_score = Some(scores.iter().sum::<f32>() / scores.len() as f32);
+        }
+
+        stats.status_counts = Self::STATUSES
+            .iter()
+            .map(|&status| {
+                let count = statuses.iter().filter(|&&other| other == status).count();
+                (status, count as u32)
+            })
+            .collect();
+
+        let watched = history::watch_history
+            .select((history::started_at, history::finished_at))
+            .load::<(i64, i64)>(db.conn())?
+            .into_iter()
+            .filter_map(|(started_at, finished_at)| {
+                let date = Local
+                    .timestamp_opt(finished_at, 0)
+                    .single()?
+                    .naive_local()
+                    .date();
+                Some((date, finished_at - started_at))
+            })
+            .collect::<Vec<_>>();
+
+        stats.years = year_breakdown(&watched);
+        stats.longest_streak_days = longest_streak(watched.iter().map(|&(date, _)| date));
+
+        Ok(stats)
+    }
+}
+
+/// The episodes recorded in the watch history during a single year.
+#[derive(Debug, PartialEq)]
+pub struct YearStats {
+    pub year: i32,
+    pub episodes: u32,
+    pub watch_time_mins: u32,
+}
+
+/// Groups every watched episode by the year it was watched in, starting with the most recent year.
+///
+/// `watched` should contain the date each episode finished along with how many seconds it was watched for.
+#[allow(clippy::cast_sign_loss)]
+fn year_breakdown(watched: &[(NaiveDate, i64)]) -> Vec<YearStats> {
+    let mut years = BTreeMap::new();
+
+    for &(date, watch_secs) in watched {
+        let (episodes, secs) = years.entry(date.year()).or_insert((0, 0));
+        *episodes += 1;
+        *secs += watch_secs.max(0) as u64;
+    }
+
+    years
+        .into_iter()
+        .rev()
+        .map(|(year, (episodes, secs))| YearStats {
+            year,
+            episodes,
+            watch_time_mins: (secs / 60) as u32,
+        })
+        .collect()
+}
+
+/// Returns the most consecutive days found in `dates`.
+fn longest_streak<I>(dates: I) -> u32
+where
+    I: IntoIterator<Item = NaiveDate>,
+{
+    let dates = dates.into_iter().collect::<BTreeSet<_>>();
+
+    let mut longest = 0;
+    let mut current = 0;
+    let mut last: Option<NaiveDate> = None;
+
+    for date in dates {
+        let continues = last.map_or(false, |last| last.succ_opt() == Some(date));
+        current = if continues { current + 1 } else { 1 };
+        longest = longest.max(current);
+        last = Some(date);
+    }
+
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn streak_detection() {
+        assert_eq!(longest_streak(Vec::new()), 0);
+        assert_eq!(longest_streak(vec![date(2021, 3, 1)]), 1);
+
+        let dates = vec![
+            date(2020, 12, 30),
+            date(2020, 12, 31),
+            date(2021, 1, 1),
+            // Multiple episodes on the same day only count once
+            date(2021, 1, 1),
+            date(2021, 1, 5),
+            date(2021, 1, 6),
+        ];
+
+        assert_eq!(longest_streak(dates), 3);
+    }
+
+    #[test]
+    fn episodes_grouped_by_year() {
+        let watched = vec![
+            (date(2020, 5, 1), 24 * 60),
+            (date(2021, 1, 1), 20 * 60),
+            (date(2021, 6, 1), 25 * 60),
+        ];
+
+        let years = year_breakdown(&watched);
+
+        assert_eq!(
+            years,
+            vec![
+                YearStats {
+                    year: 2021,
+                    episodes: 2,
+                    watch_time_mins: 45,
+                },
+                YearStats {
+                    year: 2020,
+                    episodes: 1,
+                    watch_time_mins: 24,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::{anyhow, Result};
+
+/// Returns the URL to stream `episode` of the series with `id` from, by filling in the placeholders of `template`.
+///
+/// `{episode}` is replaced with the episode number, which is padded with zeros when given a width like `{episode:02}`,
+/// and `{id}` is replaced with the ID of the series. `{{` and `}}` insert literal braces.
+pub fn url(template: &str, id: i32, episode: u32) -> Result<String> {
+    let mut url = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                url.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                url.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => placeholder.push(ch),
+                        None => return Err(anyhow!("unclosed placeholder in stream URL")),
+                    }
+                }
+
+                let (name, width) = match placeholder.split_once(':') {
+                    Some((name, width)) => {
+                        let width = width.parse().map_err(|_| {
+                            anyhow!("invalid padding in stream URL: {}", placeholder)
+                        })?;
+
+                        (name, width)
+                    }
+                    None => (placeholder.as_str(), 0),
+                };
+
+                let value = match name.trim() {
+                    "episode" => format!("{:0width$}", episode, width = width),
+                    "id" => format!("{:0width$}", id, width = width),
+                    _ => return Err(anyhow!("unknown placeholder in stream URL: {{{}}}", name)),
+                };
+
+                url.push_str(&value);
+            }
+            '}' => return Err(anyhow!("unmatched }} in stream URL")),
+            ch => url.push(ch),
+        }
+    }
+
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_filled_in() {
+        assert_eq!(
+            url("https://example.com/{id}/episode-{episode}", 1234, 5).unwrap(),
+            "https://example.com/1234/episode-5"
+        );
+
+        assert_eq!(
+            url("https://example.com/watch?ep={episode:03}&x={{y}}", 1, 7).unwrap(),
+            "https://example.com/watch?ep=007&x={y}"
+        );
+
+        assert!(url("https://example.com/{title}", 1, 1).is_err());
+        assert!(url("https://example.com/{episode", 1, 1).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use anime::local::EpisodeParser;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The extensions of subtitle files that can be loaded alongside an episode.
+const EXTENSIONS: [&str; 3] = ["ass", "ssa", "srt"];
+
+/// Returns the path of every external subtitle file in `dir`, or directly inside one of its subdirectories, that belongs to
+/// the episode numbered `episode`.
+///
+/// Subtitle files are matched to episodes by parsing their name with `parser`, and have their episode number shifted by `offset`
+/// like the episodes of the series.
+pub fn find_external(
+    dir: &Path,
+    episode: u32,
+    parser: &EpisodeParser,
+    offset: i32,
+) -> Vec<PathBuf> {
+    let belongs_to_episode = |filename: &str| match parser.parse(filename) {
+        Ok(parsed) if parsed.fraction.is_none() => parsed
+            .episodes()
+            .any(|number| i64::from(number) + i64::from(offset) == i64::from(episode)),
+        Ok(_) | Err(_) => false,
+    };
+
+    let mut found = subtitle_files(dir)
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .map_or(false, |name| belongs_to_episode(&name.to_string_lossy()))
+        })
+        .collect::<Vec<_>>();
+
+    found.sort_unstable();
+    found
+}
+
+/// Returns every subtitle file in `dir` and the folders directly inside of it, such as a `Subs` folder.
+///
+/// Folders that can't be read are skipped, as external subtitles are only ever a nice extra.
+fn subtitle_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut subdirs = Vec::new();
+
+    for path in dir_entries(dir) {
+        if path.is_dir() {
+            subdirs.push(path);
+        } else if is_subtitle(&path) {
+            files.push(path);
+        }
+    }
+
+    for subdir in subdirs {
+        files.extend(dir_entries(&subdir).filter(|path| is_subtitle(path)));
+    }
+
+    files
+}
+
+fn dir_entries(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+}
+
+fn is_subtitle(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| {
+        EXTENSIONS
+            .iter()
+            .any(|valid| ext.to_string_lossy().eq_ignore_ascii_case(valid))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn external_subtitles_are_matched_to_episodes() {
+        let dir = env::temp_dir().join(format!("anup_subtitle_{}", std::process::id()));
+
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        fs::create_dir_all(dir.join("Subs")).unwrap();
+
+        for file in [
+            "Series Title - 02.mkv",
+            "Series Title - 02.en.ass",
+            "Series Title - 03.en.ass",
+            "Subs/Series Title - 02.srt",
+            "Subs/Series Title - 03.srt",
+            "Subs/Series Title - 02.txt",
+        ] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let parser = EpisodeParser::custom("Series Title - #");
+        let found = find_external(&dir, 3, &parser, 0);
+
+        assert_eq!(
+            found,
+            [
+                dir.join("Series Title - 03.en.ass"),
+                dir.join("Subs/Series Title - 03.srt")
+            ]
+        );
+
+        // Episode 2 is played as episode 1 with an offset of -1
+        let found = find_external(&dir, 1, &parser, -1);
+
+        assert_eq!(
+            found,
+            [
+                dir.join("Series Title - 02.en.ass"),
+                dir.join("Subs/Series Title - 02.srt")
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+use super::{LoadedSeries, Series};
+use anime::remote::{SeriesID, Status};
+use std::collections::HashMap;
+
+/// What is known about a series being watched when deciding whether it should be watched next.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Candidate {
+    /// How many episodes have aired that haven't been watched yet.
+    pub episodes_behind: u32,
+    /// The UTC timestamp an episode of the series was last watched at, if it ever was.
+    pub last_watched: Option<i64>,
+    /// How many minutes it would take to watch every remaining episode of the series.
+    pub remaining_mins: u32,
+}
+
+impl Candidate {
+    /// How many episodes behind a series can be before it stops scoring any higher.
+    const MAX_COUNTED_BEHIND: u32 = 6;
+    /// How many days it takes for the score a series gets from being watched recently to halve.
+    const RECENCY_HALF_LIFE_DAYS: f32 = 7.0;
+
+    const BEHIND_WEIGHT: f32 = 1.0;
+    const RECENCY_WEIGHT: f32 = 1.5;
+    const REMAINING_WEIGHT: f32 = 0.5;
+
+    /// Create a candidate from `series`, or return None if it isn't being watched or its next episode isn't on disk.
+    ///
+    /// `latest_aired` is the latest episode of the series known to have aired, which is used over its total episode count to tell how far behind it is.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn from_series(
+        series: &Series,
+        latest_aired: Option<u32>,
+        last_watched: Option<i64>,
+    ) -> Option<Self> {
+        let entry = &series.data.entry;
+
+        if !matches!(entry.status(), Status::Watching | Status::Rewatching) {
+            return None;
+        }
+
+        let watched = entry.watched_episodes().max(0) as u32;
+        series.episodes.find(watched + 1)?;
+
+        let total = series.data.info.episodes.max(0) as u32;
+        let last_on_disk = series.episodes.highest_episode_number();
+
+        let aired = match latest_aired {
+            Some(aired) => aired,
+            None if total > 0 => total,
+            None => last_on_disk,
+        };
+
+        let remaining = if total > 0 {
+            total.saturating_sub(watched)
+        } else {
+            aired.saturating_sub(watched)
+        };
+
+        Some(Self {
+            episodes_behind: aired.saturating_sub(watched),
+            last_watched,
+            remaining_mins: remaining * series.data.episode_length_mins().max(0) as u32,
+        })
+    }
+
+    /// Returns how strongly the series should be suggested as of the `now` UTC timestamp.
+    ///
+    /// Series score higher the more episodes they're behind, the more recently they were watched, and the less time is left to finish them.
+    pub fn score(&self, now: i64) -> f32 {
+        let behind = self.episodes_behind.min(Self::MAX_COUNTED_BEHIND) as f32
+            / Self::MAX_COUNTED_BEHIND as f32;
+
+        let recency = self.last_watched.map_or(0.0, |watched| {
+            let days_since = (now - watched).max(0) as f32 / (24.0 * 60.0 * 60.0);
+            0.5_f32.powf(days_since / Self::RECENCY_HALF_LIFE_DAYS)
+        });
+
+        let remaining = 1.0 / (1.0 + self.remaining_mins as f32 / 60.0);
+
+        behind * Self::BEHIND_WEIGHT
+            + recency * Self::RECENCY_WEIGHT
+            + remaining * Self::REMAINING_WEIGHT
+    }
+}
+
+/// Rank every series in `series` that can be watched next, starting with the one that should be watched first.
+///
+/// Each result contains the index of the series in `series` along with what its ranking was based on.
+/// Series without any aired episodes left to watch are left out.
+#[allow(clippy::cast_sign_loss)]
+pub fn rank<'a, I>(
+    series: I,
+    latest_aired: &HashMap<SeriesID, u32>,
+    last_watched: &HashMap<i32, i64>,
+    now: i64,
+) -> Vec<(usize, Candidate)>
+where
+    I: IntoIterator<Item = &'a LoadedSeries>,
+{
+    let mut ranked = series
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, series)| {
+            let series = series.complete()?;
+            let id = series.data.config.id;
+            let aired = latest_aired.get(&(id as SeriesID)).copied();
+
+            Candidate::from_series(series, aired, last_watched.get(&id).copied())
+                .map(|candidate| (index, candidate))
+        })
+        .filter(|(_, candidate)| candidate.episodes_behind > 0)
+        .map(|(index, candidate)| (index, candidate, candidate.score(now)))
+        .collect::<Vec<_>>();
+
+    ranked.sort_by(|(_, _, x), (_, _, y)| y.partial_cmp(x).unwrap_or(std::cmp::Ordering::Equal));
+
+    ranked
+        .into_iter()
+        .map(|(index, candidate, _)| (index, candidate))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_SECS: i64 = 24 * 60 * 60;
+    const NOW: i64 = 100 * DAY_SECS;
+
+    fn candidate(
+        episodes_behind: u32,
+        days_since_watched: Option<i64>,
+        remaining_mins: u32,
+    ) -> Candidate {
+        Candidate {
+            episodes_behind,
+            last_watched: days_since_watched.map(|days| NOW - days * DAY_SECS),
+            remaining_mins,
+        }
+    }
+
+    #[test]
+    fn score_prefers_more_episodes_behind() {
+        let few = candidate(1, Some(3), 240);
+        let many = candidate(4, Some(3), 240);
+
+        assert!(many.score(NOW) > few.score(NOW));
+
+        // Being further behind than the cap shouldn't make a difference
+        let capped = candidate(Candidate::MAX_COUNTED_BEHIND, Some(3), 240);
+        let beyond = candidate(Candidate::MAX_COUNTED_BEHIND * 4, Some(3), 240);
+
+        assert!((capped.score(NOW) - beyond.score(NOW)).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn score_prefers_recently_watched() {
+        let recent = candidate(2, Some(1), 240);
+        let old = candidate(2, Some(30), 240);
+        let never = candidate(2, None, 240);
+
+        assert!(recent.score(NOW) > old.score(NOW));
+        assert!(old.score(NOW) > never.score(NOW));
+    }
+
+    #[test]
+    fn score_prefers_less_remaining() {
+        let short = candidate(2, Some(3), 24);
+        let long = candidate(2, Some(3), 24 * 24);
+
+        assert!(short.score(NOW) > long.score(NOW));
+    }
+}
@@ -0,0 +1,43 @@
+use crate::database::schema::series_synonyms;
+use crate::database::Database;
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[table_name = "series_synonyms"]
+struct SeriesSynonym<'a> {
+    series_id: i32,
+    synonym: &'a str,
+}
+
+/// Returns every custom synonym of the series with `series_id`.
+pub fn load(db: &Database, series_id: i32) -> diesel::QueryResult<Vec<String>> {
+    use crate::database::schema::series_synonyms::dsl;
+
+    dsl::series_synonyms
+        .filter(dsl::series_id.eq(series_id))
+        .select(dsl::synonym)
+        .order(dsl::synonym)
+        .load(db.conn())
+}
+
+/// Replace every custom synonym of the series with `series_id` with `synonyms`.
+pub fn replace(db: &Database, series_id: i32, synonyms: &[String]) -> diesel::QueryResult<()> {
+    use crate::database::schema::series_synonyms::dsl;
+
+    diesel::delete(dsl::series_synonyms.filter(dsl::series_id.eq(series_id))).execute(db.conn())?;
+
+    if synonyms.is_empty() {
+        return Ok(());
+    }
+
+    let rows = synonyms
+        .iter()
+        .map(|synonym| SeriesSynonym { series_id, synonym })
+        .collect::<Vec<_>>();
+
+    diesel::insert_into(dsl::series_synonyms)
+        .values(&rows)
+        .execute(db.conn())?;
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::database::schema::series_tags;
+use crate::database::Database;
+use anyhow::{anyhow, Result};
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[table_name = "series_tags"]
+struct SeriesTag<'a> {
+    series_id: i32,
+    tag: &'a str,
+}
+
+/// Returns every tag of the series with `series_id`.
+pub fn load(db: &Database, series_id: i32) -> diesel::QueryResult<Vec<String>> {
+    use crate::database::schema::series_tags::dsl;
+
+    dsl::series_tags
+        .filter(dsl::series_id.eq(series_id))
+        .select(dsl::tag)
+        .order(dsl::tag)
+        .load(db.conn())
+}
+
+/// Replace every tag of the series with `series_id` with `tags`.
+pub fn replace(db: &Database, series_id: i32, tags: &[String]) -> diesel::QueryResult<()> {
+    use crate::database::schema::series_tags::dsl;
+
+    diesel::delete(dsl::series_tags.filter(dsl::series_id.eq(series_id))).execute(db.conn())?;
+
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let rows = tags
+        .iter()
+        .map(|tag| SeriesTag { series_id, tag })
+        .collect::<Vec<_>>();
+
+    diesel::insert_into(dsl::series_tags)
+        .values(&rows)
+        .execute(db.conn())?;
+
+    Ok(())
+}
+
+/// Returns `tag` with surrounding whitespace removed, as long as it isn't empty and doesn't contain any other whitespace.
+///
+/// Tags are kept to a single word so they can be filtered by from the command prompt.
+pub fn validate(tag: &str) -> Result<&str> {
+    let tag = tag.trim();
+
+    if tag.is_empty() {
+        return Err(anyhow!("tag can't be empty"));
+    }
+
+    if tag.contains(char::is_whitespace) {
+        return Err(anyhow!(
+            "tags can't contain spaces, try {}",
+            tag.replace(char::is_whitespace, "-")
+        ));
+    }
+
+    Ok(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_validation() {
+        assert_eq!(validate(" seasonal-2024 ").unwrap(), "seasonal-2024");
+        assert!(validate("  ").is_err());
+        assert!(validate("rewatch queue").is_err());
+    }
+}
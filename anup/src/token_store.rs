@@ -0,0 +1,48 @@
+//! Keeps the access token of each user in the secret service of the operating system,
+//! such as the Secret Service on Linux, so they don't have to be saved in the users file.
+
+use crate::file;
+use crate::user::UserInfo;
+use anime::remote::AccessToken;
+use anyhow::{Context, Result};
+use keyring::{Entry, Error as KeyringError};
+
+/// Returns the keyring entry that holds the token of `user`.
+///
+/// Each profile stores its tokens under its own service name, as two profiles can have a user with the same name.
+fn entry(user: &UserInfo) -> Result<Entry> {
+    let service = match file::profile() {
+        Some(profile) => format!("{}-{}", env!("CARGO_PKG_NAME"), profile),
+        None => env!("CARGO_PKG_NAME").into(),
+    };
+
+    let account = format!("{}:{}", user.service.as_str(), user.username);
+
+    Entry::new(&service, &account).context("opening keyring entry")
+}
+
+/// Returns the token of `user` from the keyring, or None if it doesn't have one.
+pub fn load(user: &UserInfo) -> Result<Option<AccessToken>> {
+    match entry(user)?.get_password() {
+        Ok(token) => Ok(Some(AccessToken::encode(token))),
+        Err(KeyringError::NoEntry) => Ok(None),
+        Err(err) => Err(err).context("reading token from keyring"),
+    }
+}
+
+/// Store `token` as the token of `user` in the keyring, replacing any token it already had.
+pub fn store(user: &UserInfo, token: &AccessToken) -> Result<()> {
+    let token = token.decode().context("decoding token")?;
+
+    entry(user)?
+        .set_password(&token)
+        .context("writing token to keyring")
+}
+
+/// Remove the token of `user` from the keyring.
+pub fn remove(user: &UserInfo) -> Result<()> {
+    match entry(user)?.delete_password() {
+        Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
+        Err(err) => Err(err).context("removing token from keyring"),
+    }
+}
@@ -1,4 +1,5 @@
 use crate::series::SeriesPath;
+use crate::tui::theme;
 use crate::{config::Config, key::Key};
 use anime::local::detect::CustomPattern;
 use anime::local::EpisodeParser;
@@ -6,9 +7,8 @@ use anime::remote::SeriesID;
 use bitflags::bitflags;
 use crossterm::event::KeyCode;
 use std::borrow::Cow;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::{backend::Backend, text::Span};
 use tui_utils::{
@@ -103,15 +103,15 @@ impl Input {
 
     pub fn draw<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
         let is_disabled = self.flags.contains(InputFlags::DISABLED);
+        let theme = theme::get();
 
         let block_color = if is_disabled {
-            Some(Color::DarkGray)
+            theme.inactive
         } else {
             match (self.is_selected(), self.has_error()) {
-                (true, true) => Some(Color::LightRed),
-                (true, false) => Some(Color::Blue),
-                (false, true) => Some(Color::Red),
-                (false, false) => None,
+                (_, true) => theme.error,
+                (true, false) => theme.focus,
+                (false, false) => theme.border,
             }
         };
 
@@ -122,7 +122,7 @@ impl Input {
 
         let label_widget = {
             let style = if is_disabled {
-                style::bold().fg(Color::DarkGray)
+                style::bold().fg(theme.inactive)
             } else {
                 style::bold()
             };
@@ -134,11 +134,7 @@ impl Input {
 
         frame.render_widget(label_widget, layout[0]);
 
-        let mut block = block::with_borders(None);
-
-        if let Some(color) = block_color {
-            block = block.border_style(style::fg(color));
-        }
+        let block = block::with_borders(None).border_style(style::fg(block_color));
 
         let content_area = block.inner(layout[1]);
 
@@ -147,7 +143,7 @@ impl Input {
         let text: Span = match (self.caret.is_empty(), &self.placeholder) {
             (true, Some(placeholder)) if !self.flags.contains(InputFlags::IGNORE_PLACEHOLDER) => {
                 let slice = &placeholder[self.caret.pos()..];
-                Span::styled(slice, style::fg(Color::DarkGray))
+                Span::styled(slice, style::fg(theme.inactive))
             }
             _ => {
                 let visible_offset = self.get_visible_offset(content_area.width);
@@ -657,9 +653,29 @@ impl ValidatedInput for ParserInput {
             return;
         }
 
+        if text.eq_ignore_ascii_case(EpisodeParser::TOKENIZER_KEYWORD) {
+            self.parser = EpisodeParser::Tokenizer;
+            self.input.set_error(false);
+            return;
+        }
+
+        if let Some(program) = text.strip_prefix(EpisodeParser::EXTERNAL_PREFIX) {
+            let program = Path::new(program.trim());
+
+            // Relative paths would be looked up from wherever anup happens to be started
+            if !program.is_absolute() || !program.is_file() {
+                self.reset(true);
+                return;
+            }
+
+            self.parser = EpisodeParser::External(program.into());
+            self.input.set_error(false);
+            return;
+        }
+
         let pattern = CustomPattern::new(text);
 
-        if !pattern.has_episode_marker() {
+        if !pattern.can_detect_episodes() {
             self.reset(true);
             return;
         }
@@ -669,10 +685,19 @@ impl ValidatedInput for ParserInput {
     }
 
     fn error_message(&self) -> Cow<'static, str> {
+        if self
+            .input
+            .text()
+            .starts_with(EpisodeParser::EXTERNAL_PREFIX)
+        {
+            return "External parser must be the absolute path of an executable".into();
+        }
+
         // TODO: use concat! macro if/when it can accept constants, or when a similiar crate doesn't require nightly
         format!(
-            "Must mark episode location with {}",
+            "Must mark episode location with {} or not end with {}",
             CustomPattern::EPISODE_MARKER,
+            CustomPattern::WILDCARD,
         )
         .into()
     }
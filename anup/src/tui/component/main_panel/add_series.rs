@@ -4,11 +4,12 @@ use crate::tui::component::input::{
     ValidatedInput,
 };
 use crate::tui::component::Component;
+use crate::tui::theme;
 use crate::tui::UIState;
 use crate::{config::Config, key::Key};
 use crate::{file, tui::state::SharedState};
 use crate::{
-    series::info::{InfoSelector, SeriesInfo},
+    series::info::{InfoSelector, MatchOptions, SeriesInfo},
     util::ArcMutex,
 };
 use crate::{
@@ -16,8 +17,8 @@ use crate::{
     util::arc_mutex,
 };
 use crate::{try_opt_ret, util::ScopedTask};
-use anime::local::{CategorizedEpisodes, EpisodeParser, SortedEpisodes};
-use anime::remote::SeriesID;
+use anime::local::{EpisodeParser, SortedEpisodes};
+use anime::remote::{Remote, SeriesID};
 use anyhow::{Context, Result};
 use crossterm::event::KeyCode;
 use std::mem;
@@ -26,10 +27,9 @@ use std::{borrow::Cow, sync::Arc, time::Duration};
 use tokio::task;
 use tui::backend::Backend;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui_utils::{
-    helpers::{block, text},
+    helpers::text,
     layout::{BasicConstraint, RectExt, SimpleLayout},
     widgets::{Fragment, OverflowMode, SimpleText, SpanOptions, TextFragments},
 };
@@ -89,6 +89,12 @@ impl PanelInputs {
         let parser_pattern = match series.parser() {
             EpisodeParser::Default => Cow::Borrowed(""),
             EpisodeParser::Custom(cus) => cus.inner().into(),
+            EpisodeParser::Tokenizer => Cow::Borrowed(EpisodeParser::TOKENIZER_KEYWORD),
+            EpisodeParser::External(program) => Cow::Owned(format!(
+                "{}{}",
+                EpisodeParser::EXTERNAL_PREFIX,
+                program.display()
+            )),
         };
 
         Self {
@@ -122,6 +128,7 @@ struct SharedPanelState {
     selected_input: usize,
     error: Option<Cow<'static, str>>,
     mode: Mode,
+    lookup: Lookup,
 }
 
 impl SharedPanelState {
@@ -142,7 +149,7 @@ impl SharedPanelState {
         self.error = None;
     }
 
-    fn build_series(&mut self, state: &UIState) -> Result<AddSeriesResult> {
+    fn build_series(&mut self, state: &UIState) -> Result<BuiltSeries> {
         self.series_builder.build(&self.inputs, state, self.mode)
     }
 
@@ -153,8 +160,11 @@ impl SharedPanelState {
 
 pub struct AddSeriesPanel {
     state: ArcMutex<SharedPanelState>,
+    shared_state: SharedState,
     #[allow(dead_code)]
     update_monitor_task: ScopedTask<()>,
+    #[allow(dead_code)]
+    lookup_task: Option<ScopedTask<()>>,
 }
 
 impl AddSeriesPanel {
@@ -186,16 +196,56 @@ impl AddSeriesPanel {
             selected_input: 0,
             error: None,
             mode,
+            lookup: Lookup::Idle,
         });
 
         let update_monitor_task = Self::spawn_update_monitor(&state, shared_state).into();
 
         Ok(Self {
             state,
+            shared_state: shared_state.clone(),
             update_monitor_task,
+            lookup_task: None,
         })
     }
 
+    fn spawn_lookup(
+        &self,
+        lookup: SeriesLookup,
+        remote: Arc<Remote>,
+        options: MatchOptions,
+    ) -> task::JoinHandle<()> {
+        let panel_state = Arc::clone(&self.state);
+        let state = self.shared_state.clone();
+
+        task::spawn(async move {
+            let SeriesLookup {
+                sel,
+                params,
+                episodes,
+            } = lookup;
+
+            let info = SeriesInfo::from_remote(sel, &remote, options).await;
+            let found = info.map(|info| PartialSeries::new(info, params, episodes));
+
+            panel_state.lock().lookup = Lookup::Done(found);
+            state.lock().mark_dirty();
+        })
+    }
+
+    /// Returns the result of looking up the series to add once it has finished.
+    pub fn take_finished_lookup(&mut self) -> Option<Result<PartialSeries>> {
+        let mut panel_state = self.state.lock();
+
+        match mem::replace(&mut panel_state.lookup, Lookup::Idle) {
+            Lookup::Done(result) => Some(result),
+            other => {
+                panel_state.lookup = other;
+                None
+            }
+        }
+    }
+
     fn spawn_update_monitor(
         panel_state: &ArcMutex<SharedPanelState>,
         state: &SharedState,
@@ -269,14 +319,19 @@ impl AddSeriesPanel {
             }};
         }
 
+        let theme = theme::get();
+
         let (header_text, has_error) =
             match (&panel_state.error, &panel_state.series_builder.params) {
+                _ if matches!(panel_state.lookup, Lookup::Pending) => {
+                    (text::bold("Looking up series.."), false)
+                }
                 (Some(err), Some(_)) | (Some(err), None) => {
-                    (text::bold_with(err.as_ref(), |s| s.fg(Color::Red)), true)
+                    (text::bold_with(err.as_ref(), |s| s.fg(theme.error)), true)
                 }
                 (None, Some(_)) => (text::bold("Detected"), false),
                 (None, None) => (
-                    text::bold_with("Nothing Detected", |s| s.fg(Color::Red)),
+                    text::bold_with("Nothing Detected", |s| s.fg(theme.error)),
                     false,
                 ),
             };
@@ -315,9 +370,9 @@ impl AddSeriesPanel {
 
         let episodes_text = match &built.episodes {
             ParsedEpisodes::Parsed(_, range_str) => text::italic(range_str),
-            ParsedEpisodes::NoneFound => text::italic_with("none", |s| s.fg(Color::Yellow)),
+            ParsedEpisodes::NoneFound => text::italic_with("none", |s| s.fg(theme.warning)),
             ParsedEpisodes::NeedsSplitting => {
-                text::italic_with("needs splitting", |s| s.fg(Color::Yellow))
+                text::italic_with("needs splitting", |s| s.fg(theme.warning))
             }
         };
 
@@ -332,7 +387,7 @@ impl AddSeriesPanel {
             Mode::UpdateSeries => "Update Selected Series",
         };
 
-        let block = block::with_borders(title);
+        let block = theme::block(title);
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -362,13 +417,31 @@ impl Component for AddSeriesPanel {
             KeyCode::Enter => {
                 let mut panel_state = self.state.lock();
 
+                if matches!(panel_state.lookup, Lookup::Pending) {
+                    return Ok(AddSeriesResult::Ok);
+                }
+
                 panel_state.validate_selected();
 
                 if panel_state.error.is_some() {
                     return Ok(AddSeriesResult::Ok);
                 }
 
-                panel_state.build_series(state)
+                match panel_state.build_series(state)? {
+                    BuiltSeries::LookUp(lookup) => {
+                        let remote = state.remote.get_logged_in_shared()?;
+
+                        panel_state.lookup = Lookup::Pending;
+                        drop(panel_state);
+
+                        let options = MatchOptions::from(&state.config);
+                        let task = self.spawn_lookup(lookup, remote, options);
+                        self.lookup_task = Some(task.into());
+
+                        Ok(AddSeriesResult::Ok)
+                    }
+                    BuiltSeries::Update(params) => Ok(AddSeriesResult::UpdateSeries(params.into())),
+                }
             }
             KeyCode::Tab => {
                 let mut panel_state = self.state.lock();
@@ -408,10 +481,29 @@ impl Component for AddSeriesPanel {
 pub enum AddSeriesResult {
     Ok,
     Reset,
-    AddSeries(Box<PartialSeries>),
     UpdateSeries(Box<UpdateParams>),
 }
 
+/// What the user's input was built into.
+enum BuiltSeries {
+    /// A new series that still needs to be found on the remote service.
+    LookUp(SeriesLookup),
+    Update(UpdateParams),
+}
+
+struct SeriesLookup {
+    sel: InfoSelector,
+    params: SeriesParams,
+    episodes: SortedEpisodes,
+}
+
+/// The state of the remote lookup of a series being added.
+enum Lookup {
+    Idle,
+    Pending,
+    Done(Result<PartialSeries>),
+}
+
 #[derive(Copy, Clone)]
 pub enum Mode {
     AddSeries,
@@ -467,12 +559,7 @@ impl SeriesBuilder {
         }
     }
 
-    fn build(
-        &mut self,
-        inputs: &PanelInputs,
-        state: &UIState,
-        mode: Mode,
-    ) -> Result<AddSeriesResult> {
+    fn build(&mut self, inputs: &PanelInputs, state: &UIState, mode: Mode) -> Result<BuiltSeries> {
         let built = match self.update(inputs, state) {
             Ok(_) => mem::take(&mut self.params).unwrap(),
             Err(err) => return Err(err),
@@ -484,21 +571,17 @@ impl SeriesBuilder {
 
         match mode {
             Mode::AddSeries => {
-                let remote = state.remote.get_logged_in()?;
-
-                let info = {
-                    let id = inputs.id.parsed_value();
-                    let sel = id.map_or_else(
-                        || InfoSelector::from_path_or_name(&params.path, &params.name),
-                        InfoSelector::ID,
-                    );
-
-                    SeriesInfo::from_remote(sel, remote)?
-                };
-
-                let partial = PartialSeries::new(info, params, episodes);
-
-                Ok(AddSeriesResult::AddSeries(partial.into()))
+                let id = inputs.id.parsed_value();
+                let sel = id.map_or_else(
+                    || InfoSelector::from_path_or_name(&params.path, &params.name),
+                    InfoSelector::ID,
+                );
+
+                Ok(BuiltSeries::LookUp(SeriesLookup {
+                    sel,
+                    params,
+                    episodes,
+                }))
             }
             Mode::UpdateSeries => {
                 let params = UpdateParams {
@@ -506,9 +589,10 @@ impl SeriesBuilder {
                     path: Some(params.path),
                     parser: Some(params.parser),
                     episodes: Some(episodes),
+                    fetched: None,
                 };
 
-                Ok(AddSeriesResult::UpdateSeries(params.into()))
+                Ok(BuiltSeries::Update(params))
             }
         }
     }
@@ -522,7 +606,7 @@ enum ParsedEpisodes {
 
 impl ParsedEpisodes {
     fn parse(path: &SeriesPath, config: &Config, parser: &EpisodeParser) -> Result<Self> {
-        let episodes = CategorizedEpisodes::parse(path.absolute(config), parser)?;
+        let episodes = series::parse_episode_dir(path.absolute(config), parser, &[], config)?;
 
         if episodes.is_empty() {
             return Ok(Self::NoneFound);
@@ -554,6 +638,8 @@ impl ParsedEpisodes {
 
     /// Build a string that displays ranges and holes within a set of episodes.
     /// A hole is considered to be an episode that is not sequential.
+    ///
+    /// Fractional episodes are not included.
     fn episode_range_str(episodes: &SortedEpisodes) -> Option<String> {
         use std::ops::Range;
 
@@ -569,25 +655,22 @@ impl ParsedEpisodes {
             }
         }
 
-        if episodes.len() < 2 {
-            return episodes.get(0).map(|ep| ep.number.to_string());
-        }
+        let mut whole = episodes.whole();
+        let first = whole.next()?;
 
         let mut result = String::new();
-        let mut range = episodes[0].number..episodes[0].number;
+        let mut range = first.number..first.last_number;
 
-        for episode in &episodes[1..] {
-            let ep_num = episode.number;
-
-            if ep_num - range.end > 1 {
+        for episode in whole {
+            if episode.number.saturating_sub(range.end) > 1 {
                 push_range(&mut result, range);
                 result.push(HOLE_SEPARATOR);
-                range = ep_num..ep_num;
+                range = episode.number..episode.last_number;
 
                 continue;
             }
 
-            range.end = ep_num;
+            range.end = range.end.max(episode.last_number);
         }
 
         push_range(&mut result, range);
@@ -650,6 +733,23 @@ mod tests {
             ),
             (episodes!(1..=12, 16..=16, 24..=32), Some("1-12|16|24-32")),
             (episodes!(2..=2, 6..=6, 12..=12), Some("2|6|12")),
+            (
+                SortedEpisodes::with_episodes(vec![
+                    Episode::with_span(1, 2, String::new()),
+                    Episode::new(3, String::new()),
+                    Episode::with_span(5, 6, String::new()),
+                ]),
+                Some("1-3|5-6"),
+            ),
+            (
+                SortedEpisodes::with_episodes(vec![
+                    Episode::new(1, String::new()),
+                    Episode::fractional(1, 5, String::new()),
+                    Episode::new(2, String::new()),
+                    Episode::fractional(4, 5, String::new()),
+                ]),
+                Some("1-2"),
+            ),
         ];
 
         for (episodes, expected) in test_sets {
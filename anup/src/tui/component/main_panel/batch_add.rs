@@ -0,0 +1,326 @@
+use super::ShouldReset;
+use crate::config::TitleLanguage;
+use crate::series::batch::{self, FolderMatch};
+use crate::series::config::SeriesConfig;
+use crate::series::info::MatchOptions;
+use crate::tui::component::prompt::log::LogKind;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::{SharedState, UIState};
+use crate::tui::theme;
+use crate::util::{arc_mutex, ArcMutex, ScopedTask};
+use crate::{key::Key, tui::component::Component};
+use anyhow::{Context, Result};
+use crossterm::event::KeyCode;
+use std::mem;
+use std::sync::Arc;
+use tokio::task;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, SimpleLayout},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// Matches every folder in the series directory that hasn't been added yet against the remote service,
+/// and lets the user review the matches before adding them all at once.
+pub struct BatchAddPanel {
+    state: ArcMutex<PanelState>,
+    shared_state: SharedState,
+    selected: WrappingIndex,
+    /// The task looking up each folder, which is cancelled when the panel is closed.
+    #[allow(dead_code)]
+    lookup_task: ScopedTask<()>,
+}
+
+impl BatchAddPanel {
+    pub fn new(state: &SharedState, positions: &ListPositions) -> Self {
+        let panel_state = arc_mutex(PanelState::Loading {
+            looked_up: 0,
+            total: 0,
+        });
+
+        let lookup_task = Self::spawn_lookup_task(&panel_state, state).into();
+
+        // The number of matches isn't known until every folder is looked up, so the position is kept in bounds once they are
+        let selected = positions.get(&SavedList::BatchAdd, usize::MAX);
+
+        Self {
+            state: panel_state,
+            shared_state: state.clone(),
+            selected: WrappingIndex::new(selected),
+            lookup_task,
+        }
+    }
+
+    /// Remember the selected folder so it can be restored the next time the panel is opened.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        if let PanelState::Reviewing(_) = &*self.state.lock() {
+            positions.save(SavedList::BatchAdd, self.selected.get());
+        }
+    }
+
+    fn spawn_lookup_task(
+        panel_state: &ArcMutex<PanelState>,
+        state: &SharedState,
+    ) -> task::JoinHandle<()> {
+        let panel_state = Arc::clone(panel_state);
+        let state = state.clone();
+
+        task::spawn(async move {
+            // The state is only locked while finding the folders, so the UI isn't frozen while each one is looked up
+            let (folders, remote, options) = {
+                let mut state = state.lock();
+
+                let remote = match state.remote.get_logged_in_shared() {
+                    Ok(remote) => remote,
+                    Err(_) => return,
+                };
+
+                let folders = SeriesConfig::load_all(&state.db)
+                    .map_err(Into::into)
+                    .and_then(|sconfigs| batch::new_folders(&sconfigs, &state.config));
+
+                match folders {
+                    Ok(folders) if folders.is_empty() => {
+                        state.get_mut().log.push(
+                            LogKind::Info,
+                            "no new folders found in the series directory",
+                        );
+
+                        *panel_state.lock() = PanelState::Reviewing(Vec::new());
+                        return;
+                    }
+                    Ok(folders) => (folders, remote, MatchOptions::from(&state.config)),
+                    Err(err) => {
+                        state.get_mut().log.push_error(&err);
+                        return;
+                    }
+                }
+            };
+
+            let total = folders.len();
+            let mut matches = Vec::with_capacity(total);
+
+            for (looked_up, path) in folders.into_iter().enumerate() {
+                *panel_state.lock() = PanelState::Loading { looked_up, total };
+                state.lock().mark_dirty();
+
+                let display = path.display().to_string();
+                let found = FolderMatch::find(path, &remote, options)
+                    .await
+                    .with_context(|| format!("failed to look up {}", display));
+
+                match found {
+                    Ok(found) => matches.push(found),
+                    Err(err) => state.lock().get_mut().log.push_error(&err),
+                }
+            }
+
+            *panel_state.lock() = PanelState::Reviewing(matches);
+            state.lock().mark_dirty();
+        })
+    }
+
+    /// Add every folder that has a series selected.
+    fn add_selected(&mut self, matches: Vec<FolderMatch>, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in_shared()?;
+        let mut added = 0;
+
+        for result in batch::into_configs(matches, &state.db) {
+            match result {
+                Ok((sconfig, info)) => {
+                    self.shared_state
+                        .add_series_async(Arc::clone(&remote), sconfig, info, None);
+
+                    added += 1;
+                }
+                Err(err) => state.log.push_error(&err),
+            }
+        }
+
+        state
+            .log
+            .push(LogKind::Info, format!("adding {} series", added));
+
+        Ok(())
+    }
+
+    fn draw_loading_panel<B>(looked_up: usize, total: usize, rect: Rect, frame: &mut Frame<B>)
+    where
+        B: Backend,
+    {
+        let outline = theme::block("Add New Folders");
+        let area = outline.inner(rect);
+
+        frame.render_widget(outline, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical).split(
+            area,
+            [
+                BasicConstraint::Percentage(40),
+                BasicConstraint::Length(1),
+                BasicConstraint::Length(1),
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let text = if total > 0 {
+            text::bold(format!(
+                "Looking up folder {} of {}..",
+                looked_up + 1,
+                total
+            ))
+        } else {
+            text::bold("Finding new folders..")
+        };
+
+        let widget = SimpleText::new(text).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[1]);
+
+        let widget = SimpleText::new(text::hint("Esc - Cancel")).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[3]);
+    }
+
+    fn draw_review_panel<B>(
+        matches: &[FolderMatch],
+        selected: &WrappingIndex,
+        language: TitleLanguage,
+        rect: Rect,
+        frame: &mut Frame<B>,
+    ) where
+        B: Backend,
+    {
+        let block = theme::block("Add New Folders");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                BasicConstraint::MinLenRemaining(4, 4),
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let rows = matches.iter().map(|found| {
+            let (title, confidence) = match (found.selected, found.selected()) {
+                (Some(index), Some(candidate)) => (
+                    format!(
+                        "{} ({}/{})",
+                        candidate.info.title(language),
+                        index + 1,
+                        found.candidates.len()
+                    ),
+                    format!("{:.0}%", candidate.confidence * 100.0),
+                ),
+                _ => ("Skip".into(), String::new()),
+            };
+
+            [
+                Span::raw(found.path.display().to_string()),
+                Span::raw(title),
+                Span::raw(confidence),
+            ]
+        });
+
+        let header = [
+            Span::raw("Folder"),
+            Span::raw("Match"),
+            Span::raw("Confidence"),
+        ];
+
+        let widths = [
+            BasicConstraint::Percentage(40),
+            BasicConstraint::Percentage(45),
+            BasicConstraint::Percentage(15),
+        ];
+
+        let widget = SimpleTable::new(rows, widths)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(selected.get() as u16));
+
+        frame.render_widget(widget, layout[0]);
+
+        let hint = text::hint("Left/Right - Change Match | Enter - Add Selected | Esc - Cancel");
+        let hint_widget = SimpleText::new(hint).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, layout[1]);
+    }
+
+    pub fn draw<B: Backend>(&mut self, ui_state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let state = self.state.lock();
+
+        match &*state {
+            PanelState::Loading { looked_up, total } => {
+                Self::draw_loading_panel(*looked_up, *total, rect, frame)
+            }
+            PanelState::Reviewing(matches) => {
+                self.selected.update_bounds(matches.len());
+
+                Self::draw_review_panel(
+                    matches,
+                    &self.selected,
+                    ui_state.config.title_language,
+                    rect,
+                    frame,
+                );
+            }
+        }
+    }
+}
+
+impl Component for BatchAddPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        // Closing the panel drops the task looking up folders, which cancels it
+        if *key == KeyCode::Esc {
+            return Ok(ShouldReset::Yes);
+        }
+
+        let mut panel_state = self.state.lock();
+
+        let matches = match &mut *panel_state {
+            PanelState::Loading { .. } => return Ok(ShouldReset::No),
+            PanelState::Reviewing(matches) => matches,
+        };
+
+        self.selected.update_bounds(matches.len());
+
+        match *key {
+            KeyCode::Up => self.selected.decrement(matches.len()),
+            KeyCode::Down => self.selected.increment(matches.len()),
+            KeyCode::Left => {
+                if let Some(found) = matches.get_mut(self.selected.get()) {
+                    found.select_prev();
+                }
+            }
+            KeyCode::Right => {
+                if let Some(found) = matches.get_mut(self.selected.get()) {
+                    found.select_next();
+                }
+            }
+            KeyCode::Enter => {
+                let matches = mem::take(matches);
+                drop(panel_state);
+
+                self.add_selected(matches, state)?;
+                return Ok(ShouldReset::Yes);
+            }
+            _ => (),
+        }
+
+        Ok(ShouldReset::No)
+    }
+}
+
+enum PanelState {
+    Loading { looked_up: usize, total: usize },
+    Reviewing(Vec<FolderMatch>),
+}
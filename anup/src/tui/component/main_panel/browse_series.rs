@@ -0,0 +1,224 @@
+use crate::key::Key;
+use crate::series::info::SeriesInfo;
+use crate::tui::component::input::{Input, InputFlags};
+use crate::tui::component::Component;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::{SharedState, UIState};
+use crate::tui::theme;
+use crate::util::{arc_mutex, ArcMutex, ScopedTask};
+use anime::remote::RemoteService;
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use std::mem;
+use std::sync::Arc;
+use tokio::task;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappedSelection,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// A panel to search the remote service for a series and add it without any episodes on disk.
+pub struct BrowseSeriesPanel {
+    input: Input,
+    results: WrappedSelection<Vec<SeriesInfo>, SeriesInfo>,
+    /// The name used to get the current results.
+    searched: Option<String>,
+    search: ArcMutex<Search>,
+    #[allow(dead_code)]
+    search_task: Option<ScopedTask<()>>,
+    state: SharedState,
+}
+
+impl BrowseSeriesPanel {
+    pub fn new(state: &SharedState) -> Self {
+        Self {
+            input: Input::new(InputFlags::SELECTED, "Name"),
+            results: WrappedSelection::new(Vec::new()),
+            searched: None,
+            search: arc_mutex(Search::Idle),
+            search_task: None,
+            state: state.clone(),
+        }
+    }
+
+    fn search(&mut self, state: &UIState) -> Result<()> {
+        let name = self.input.text().trim().to_string();
+
+        if name.is_empty() {
+            return Err(anyhow!("must enter a name to search for"));
+        }
+
+        let remote = state.remote.get_logged_in_shared()?;
+        let search = Arc::clone(&self.search);
+        let shared_state = self.state.clone();
+
+        *search.lock() = Search::Pending;
+
+        let task = task::spawn(async move {
+            let found = match remote.search_info_by_name(&name).await {
+                Ok(found) => Search::Done(name, found.into_iter().map(SeriesInfo::from).collect()),
+                Err(err) => {
+                    let err = anyhow!(err).context(format!("failed to search for {}", name));
+                    shared_state.lock().get_mut().log.push_error(&err);
+                    Search::Idle
+                }
+            };
+
+            *search.lock() = found;
+            shared_state.lock().mark_dirty();
+        });
+
+        self.search_task = Some(task.into());
+        Ok(())
+    }
+
+    /// Show the results of the last search if it has finished.
+    ///
+    /// The selected result is restored from the last time the same name was searched for.
+    fn take_search_results(&mut self, positions: &ListPositions) {
+        let mut search = self.search.lock();
+
+        if let Search::Done(_, _) = &*search {
+            if let Search::Done(name, results) = mem::replace(&mut *search, Search::Idle) {
+                let selected = positions.get(&SavedList::BrowseSeries(name.clone()), results.len());
+
+                self.results = WrappedSelection::new(results);
+                self.results.set_selected(selected);
+                self.searched = Some(name);
+            }
+        }
+    }
+
+    /// Remember the selected result so it can be restored the next time the same name is searched for.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        if let Some(name) = &self.searched {
+            positions.save(SavedList::BrowseSeries(name.clone()), self.results.index());
+        }
+    }
+
+    #[inline(always)]
+    fn is_searching(&self) -> bool {
+        matches!(*self.search.lock(), Search::Pending)
+    }
+
+    /// Returns true if the current results were found with what is currently entered in the input.
+    fn results_are_current(&self) -> bool {
+        self.searched.as_deref() == Some(self.input.text().trim())
+    }
+
+    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Browse Series");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .split(
+                block_area,
+                [
+                    BasicConstraint::Length(Input::DRAW_LINES_REQUIRED),
+                    BasicConstraint::Length(1),
+                    BasicConstraint::MinLenRemaining(1, 1),
+                ],
+            );
+
+        self.take_search_results(&state.list_positions);
+        self.input.draw(layout[0], frame);
+
+        let hint = match &self.searched {
+            _ if self.is_searching() => "Searching..",
+            None => "Press Enter to search for the entered name",
+            Some(_) if self.results.is_empty() => "No results found",
+            Some(_) if self.results_are_current() => {
+                "Press Enter to add the selected series to your list"
+            }
+            Some(_) => "Press Enter to search again",
+        };
+
+        let widget = SimpleText::new(text::hint(hint)).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[1].lines_from_top(1));
+
+        if self.results.is_empty() {
+            return;
+        }
+
+        let rows = self.results.iter().map(|info| {
+            [
+                Span::raw(info.title(state.config.title_language)),
+                Span::raw(info.episodes.to_string()),
+            ]
+        });
+
+        let header = [Span::raw("Title"), Span::raw("Episodes")];
+
+        let constraints = [BasicConstraint::Percentage(80), BasicConstraint::Length(8)];
+
+        let widget = SimpleTable::new(rows, constraints)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(self.results.index() as u16));
+
+        frame.render_widget(widget, layout[2]);
+    }
+}
+
+impl Component for BrowseSeriesPanel {
+    type State = UIState;
+    type KeyResult = Result<BrowseSeriesResult>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(BrowseSeriesResult::Reset),
+            KeyCode::Enter => {
+                self.take_search_results(&state.list_positions);
+
+                if self.is_searching() {
+                    return Ok(BrowseSeriesResult::Ok);
+                }
+
+                if !self.results_are_current() || !self.results.is_valid_index() {
+                    self.save_list_position(&mut state.list_positions);
+                    self.search(state)?;
+                    return Ok(BrowseSeriesResult::Ok);
+                }
+
+                let selected = self.results.index();
+                let info = self.results.swap_remove(selected);
+
+                Ok(BrowseSeriesResult::AddSeries(info))
+            }
+            KeyCode::Up => {
+                self.results.dec_selected();
+                Ok(BrowseSeriesResult::Ok)
+            }
+            KeyCode::Down => {
+                self.results.inc_selected();
+                Ok(BrowseSeriesResult::Ok)
+            }
+            _ => {
+                self.input.process_key(key);
+                Ok(BrowseSeriesResult::Ok)
+            }
+        }
+    }
+}
+
+enum Search {
+    Idle,
+    Pending,
+    /// The name that was searched for along with its results.
+    Done(String, Vec<SeriesInfo>),
+}
+
+pub enum BrowseSeriesResult {
+    Ok,
+    AddSeries(SeriesInfo),
+    Reset,
+}
@@ -0,0 +1,197 @@
+use super::ShouldReset;
+use crate::series::clean::{CleanAction, MissingSeries};
+use crate::tui::component::prompt::log::LogKind;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use std::borrow::Cow;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, SimpleLayout},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// Lists every series whose folder no longer exists so each one can be deleted, moved to another folder, or kept without one.
+pub struct CleanPanel {
+    missing: Vec<MissingSeries>,
+    selected: WrappingIndex,
+}
+
+impl CleanPanel {
+    pub fn new(missing: Vec<MissingSeries>, positions: &ListPositions) -> Self {
+        let selected = positions.get(&SavedList::Clean, missing.len());
+
+        Self {
+            missing,
+            selected: WrappingIndex::new(selected),
+        }
+    }
+
+    /// Remember the selected series so it can be restored the next time the panel is opened.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        positions.save(SavedList::Clean, self.selected.get());
+    }
+
+    fn resolve_selected(
+        &mut self,
+        action: CleanAction,
+        state: &mut UIState,
+    ) -> Result<ShouldReset> {
+        let index = self.selected.get();
+        let missing = match self.missing.get(index) {
+            Some(missing) => missing,
+            None => return Ok(ShouldReset::Yes),
+        };
+
+        state.resolve_missing_series(missing, action)?;
+
+        let message = match action {
+            CleanAction::Delete => format!("deleted {}", missing.config.nickname),
+            CleanAction::Repath => format!("moved {} to a new folder", missing.config.nickname),
+            CleanAction::Keep => format!("removed the folder of {}", missing.config.nickname),
+        };
+
+        state.log.push(LogKind::Info, message);
+        self.missing.remove(index);
+
+        if self.missing.is_empty() {
+            return Ok(ShouldReset::Yes);
+        }
+
+        self.selected.update_bounds(self.missing.len());
+        Ok(ShouldReset::No)
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Missing Series Folders");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                BasicConstraint::MinLenRemaining(4, 4),
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let rows = self.missing.iter().map(|missing| {
+            let closest = match &missing.closest {
+                Some(closest) => closest.inner().to_string_lossy(),
+                None => Cow::Borrowed("none"),
+            };
+
+            [
+                Span::raw(missing.config.nickname.as_str()),
+                Span::raw(missing.config.path.inner().to_string_lossy()),
+                Span::raw(closest),
+            ]
+        });
+
+        let header = [
+            Span::raw("Series"),
+            Span::raw("Missing Folder"),
+            Span::raw("Closest Match"),
+        ];
+
+        let widths = [
+            BasicConstraint::Percentage(20),
+            BasicConstraint::Percentage(40),
+            BasicConstraint::Percentage(40),
+        ];
+
+        let widget = SimpleTable::new(rows, widths)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(self.selected.get() as u16));
+
+        frame.render_widget(widget, layout[0]);
+
+        let hint =
+            text::hint("D - Delete | R - Use Closest Match | K - Keep Without Folder | Esc - Skip");
+        let hint_widget = SimpleText::new(hint).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, layout[1]);
+    }
+}
+
+impl Component for CleanPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(ShouldReset::Yes),
+            KeyCode::Up => {
+                self.selected.decrement(self.missing.len());
+                Ok(ShouldReset::No)
+            }
+            KeyCode::Down => {
+                self.selected.increment(self.missing.len());
+                Ok(ShouldReset::No)
+            }
+            KeyCode::Char('d') => self.resolve_selected(CleanAction::Delete, state),
+            KeyCode::Char('r') => self.resolve_selected(CleanAction::Repath, state),
+            KeyCode::Char('k') => self.resolve_selected(CleanAction::Keep, state),
+            _ => Ok(ShouldReset::No),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::series::config::SeriesConfig;
+    use crate::series::SeriesPath;
+    use anime::local::EpisodeParser;
+
+    fn missing(count: i32) -> Vec<MissingSeries> {
+        (1..=count)
+            .map(|id| MissingSeries {
+                config: SeriesConfig {
+                    id,
+                    nickname: format!("series{}", id),
+                    path: SeriesPath::unset(),
+                    episode_parser: EpisodeParser::default(),
+                    player_args: Default::default(),
+                    episode_offset: 0,
+                    episode_category: None,
+                    absolute_numbering: false,
+                    feed_url: None,
+                    local_rewatch: None,
+                    sub_lang: None,
+                    audio_lang: None,
+                    sub_file_pattern: None,
+                    stream_url_template: None,
+                    episode_length_mins: None,
+                },
+                closest: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reopened_panel_restores_selection() {
+        let mut positions = ListPositions::default();
+
+        let mut panel = CleanPanel::new(missing(3), &positions);
+        panel.selected.increment(panel.missing.len());
+        panel.selected.increment(panel.missing.len());
+        panel.save_list_position(&mut positions);
+
+        let panel = CleanPanel::new(missing(3), &positions);
+        assert_eq!(panel.selected.get(), 2);
+
+        // A series was resolved since the panel was last opened
+        let panel = CleanPanel::new(missing(2), &positions);
+        assert_eq!(panel.selected.get(), 1);
+    }
+}
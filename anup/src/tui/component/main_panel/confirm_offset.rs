@@ -0,0 +1,102 @@
+use crate::tui::state::OffsetPrompt;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui_utils::{
+    helpers::text,
+    layout::{RectExt, SimpleLayout},
+    widgets::{OverflowMode, SimpleText},
+};
+
+/// A panel to offer shifting the episodes of a series that appears to number its creditless opening as the first episode.
+pub struct ConfirmOffsetPanel {
+    prompt: OffsetPrompt,
+    found_text: String,
+    question_text: String,
+}
+
+impl ConfirmOffsetPanel {
+    pub fn new(prompt: OffsetPrompt) -> Self {
+        let found_text = format!(
+            "The first episode of {} appears to be a creditless opening or ending: {}",
+            prompt.nickname, prompt.filename
+        );
+
+        let question_text =
+            "Shift every episode back by one so they line up with your list?".into();
+
+        Self {
+            prompt,
+            found_text,
+            question_text,
+        }
+    }
+
+    #[inline(always)]
+    pub fn prompt(&self) -> &OffsetPrompt {
+        &self.prompt
+    }
+
+    fn draw_hints<B: Backend>(rect: Rect, frame: &mut Frame<B>) {
+        let horiz_layout =
+            SimpleLayout::new(Direction::Horizontal).split_evenly(rect.lines_from_bottom(1));
+
+        let hint_text = text::hint("Esc - Keep Numbering");
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.left);
+
+        let hint_text = text::hint("Enter - Shift Episodes");
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.right);
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Numbered Opening Found");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_fields = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .split_quarters(block_area);
+
+        let found_text = text::bold(&self.found_text);
+        let found_widget = SimpleText::new(found_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(found_widget, vert_fields.first);
+
+        let question_text = text::bold(&self.question_text);
+        let question_widget = SimpleText::new(question_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(question_widget, vert_fields.second);
+
+        Self::draw_hints(vert_fields.fourth, frame);
+    }
+}
+
+impl Component for ConfirmOffsetPanel {
+    type State = ();
+    type KeyResult = ConfirmOffsetResult;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc | KeyCode::Char('n') => ConfirmOffsetResult::Reset,
+            KeyCode::Enter | KeyCode::Char('y') => ConfirmOffsetResult::Apply,
+            _ => ConfirmOffsetResult::Ok,
+        }
+    }
+}
+
+pub enum ConfirmOffsetResult {
+    Ok,
+    Reset,
+    Apply,
+}
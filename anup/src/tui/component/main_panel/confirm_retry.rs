@@ -0,0 +1,115 @@
+use crate::tui::state::RetryPrompt;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui_utils::{
+    helpers::text,
+    layout::{RectExt, SimpleLayout},
+    widgets::{OverflowMode, SimpleText},
+};
+
+/// A panel to offer playing an episode again after its player crashed, optionally with a fallback player.
+pub struct ConfirmRetryPanel {
+    prompt: RetryPrompt,
+    crashed_text: String,
+    question_text: String,
+    fallback_hint: Option<String>,
+}
+
+impl ConfirmRetryPanel {
+    pub fn new(prompt: RetryPrompt) -> Self {
+        let crashed_text = format!("{} exited with {}", prompt.player_name, prompt.status);
+        let question_text = format!("Play episode {} again?", prompt.episode);
+
+        let fallback_hint = prompt
+            .fallback
+            .as_ref()
+            .map(|(_, name)| format!("F - Try {}", name));
+
+        Self {
+            prompt,
+            crashed_text,
+            question_text,
+            fallback_hint,
+        }
+    }
+
+    #[inline(always)]
+    pub fn prompt(&self) -> &RetryPrompt {
+        &self.prompt
+    }
+
+    fn draw_hints<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
+        let horiz_layout =
+            SimpleLayout::new(Direction::Horizontal).split_evenly(rect.lines_from_bottom(1));
+
+        let hint_text = text::hint("Esc - Cancel");
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.left);
+
+        let hint_text = text::hint("Enter - Retry");
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.right);
+
+        if let Some(fallback_hint) = &self.fallback_hint {
+            let hint_text = text::hint(fallback_hint);
+            let hint_widget = SimpleText::new(hint_text)
+                .alignment(Alignment::Center)
+                .overflow(OverflowMode::Truncate);
+
+            frame.render_widget(hint_widget, rect.lines_from_bottom(2).lines_from_top(1));
+        }
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Player Crashed");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_fields = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .split_quarters(block_area);
+
+        let crashed_text = text::bold_with(&self.crashed_text, |s| s.fg(theme::get().error));
+        let crashed_widget = SimpleText::new(crashed_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(crashed_widget, vert_fields.first);
+
+        let question_text = text::bold(&self.question_text);
+        let question_widget = SimpleText::new(question_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(question_widget, vert_fields.second);
+
+        self.draw_hints(vert_fields.fourth, frame);
+    }
+}
+
+impl Component for ConfirmRetryPanel {
+    type State = ();
+    type KeyResult = ConfirmRetryResult;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc | KeyCode::Char('n') => ConfirmRetryResult::Reset,
+            KeyCode::Enter | KeyCode::Char('y') => ConfirmRetryResult::Retry,
+            KeyCode::Char('f') if self.prompt.fallback.is_some() => ConfirmRetryResult::Fallback,
+            _ => ConfirmRetryResult::Ok,
+        }
+    }
+}
+
+pub enum ConfirmRetryResult {
+    Ok,
+    Reset,
+    Retry,
+    Fallback,
+}
@@ -0,0 +1,98 @@
+use crate::tui::state::SequelPrompt;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui_utils::{
+    helpers::text,
+    layout::{RectExt, SimpleLayout},
+    widgets::{OverflowMode, SimpleText},
+};
+
+/// A panel to offer continuing with the sequel of a series that was just completed.
+pub struct ConfirmSequelPanel {
+    prompt: SequelPrompt,
+    completed_text: String,
+    question_text: String,
+}
+
+impl ConfirmSequelPanel {
+    pub fn new(prompt: SequelPrompt) -> Self {
+        let completed_text = format!("{} is complete", prompt.completed);
+        let question_text = format!("Continue with {}?", prompt.sequel);
+
+        Self {
+            prompt,
+            completed_text,
+            question_text,
+        }
+    }
+
+    #[inline(always)]
+    pub fn prompt(&self) -> &SequelPrompt {
+        &self.prompt
+    }
+
+    fn draw_hints<B: Backend>(rect: Rect, frame: &mut Frame<B>) {
+        let horiz_layout =
+            SimpleLayout::new(Direction::Horizontal).split_evenly(rect.lines_from_bottom(1));
+
+        let hint_text = text::hint("Esc - Not Now");
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.left);
+
+        let hint_text = text::hint("Enter - Continue");
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.right);
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Continue With Sequel");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_fields = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .split_quarters(block_area);
+
+        let completed_text =
+            text::bold_with(&self.completed_text, |s| s.fg(theme::get().completed));
+        let completed_widget = SimpleText::new(completed_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(completed_widget, vert_fields.first);
+
+        let question_text = text::bold(&self.question_text);
+        let question_widget = SimpleText::new(question_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(question_widget, vert_fields.second);
+
+        Self::draw_hints(vert_fields.fourth, frame);
+    }
+}
+
+impl Component for ConfirmSequelPanel {
+    type State = ();
+    type KeyResult = ConfirmSequelResult;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc | KeyCode::Char('n') => ConfirmSequelResult::Reset,
+            KeyCode::Enter | KeyCode::Char('y') => ConfirmSequelResult::Continue,
+            _ => ConfirmSequelResult::Ok,
+        }
+    }
+}
+
+pub enum ConfirmSequelResult {
+    Ok,
+    Reset,
+    Continue,
+}
@@ -0,0 +1,95 @@
+use crate::series::WatchStart;
+use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui_utils::{
+    helpers::text,
+    layout::{RectExt, SimpleLayout},
+    widgets::{OverflowMode, SimpleText},
+};
+
+/// A panel to confirm that a series should be moved out of the plan to watch status before it is played.
+pub struct ConfirmWatchPanel {
+    plan_status_text: String,
+}
+
+impl ConfirmWatchPanel {
+    pub fn init(state: &UIState) -> Result<Self> {
+        let series = match state.series.selected() {
+            Some(series) => series,
+            None => return Err(anyhow!("must select a series to watch")),
+        };
+
+        let plan_status_text = format!("{} is planned to be watched", series.nickname());
+
+        Ok(Self { plan_status_text })
+    }
+
+    fn draw_hints<B: Backend>(rect: Rect, frame: &mut Frame<B>) {
+        let horiz_layout =
+            SimpleLayout::new(Direction::Horizontal).split_evenly(rect.lines_from_bottom(1));
+
+        let hint_text = text::hint("N - Play Without Changing");
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.left);
+
+        let hint_text = text::hint("Enter - Start Watching");
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.right);
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Start Watching Series");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_fields = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .split_quarters(block_area);
+
+        let status_text = text::bold_with(&self.plan_status_text, |s| s.fg(theme::get().warning));
+        let status_widget = SimpleText::new(status_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(status_widget, vert_fields.first);
+
+        let question_text = text::bold("Set its status to watching?");
+        let question_widget = SimpleText::new(question_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(question_widget, vert_fields.second);
+
+        Self::draw_hints(vert_fields.fourth, frame);
+    }
+}
+
+impl Component for ConfirmWatchPanel {
+    type State = ();
+    type KeyResult = ConfirmWatchResult;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => ConfirmWatchResult::Reset,
+            KeyCode::Enter | KeyCode::Char('y') => {
+                ConfirmWatchResult::Play(WatchStart::UpdateStatus)
+            }
+            KeyCode::Char('n') => ConfirmWatchResult::Play(WatchStart::KeepStatus),
+            _ => ConfirmWatchResult::Ok,
+        }
+    }
+}
+
+pub enum ConfirmWatchResult {
+    Ok,
+    Reset,
+    Play(WatchStart),
+}
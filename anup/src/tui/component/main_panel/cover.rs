@@ -0,0 +1,584 @@
+//! Draws the cover art of a series with the image protocol of the terminal, for terminals that support one.
+//!
+//! Images can't be drawn as part of a frame, so the info panel only reserves the area a cover should be drawn in.
+//! The cover is then written to the terminal after the frame has been drawn.
+
+use super::anilist;
+use crate::config::CoverArt;
+use crate::file::SaveDir;
+use crate::remote::RemoteStatus;
+use crate::tui::state::SharedState;
+use crate::util::{arc_mutex, ArcMutex, ScopedTask};
+use anime::remote::{Remote, RemoteService};
+use anyhow::{anyhow, Context, Result};
+use crossterm::{cursor, QueueableCommand};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageOutputFormat};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task;
+use tui::layout::Rect;
+
+/// The size of a terminal cell in pixels that images are scaled with.
+///
+/// The real size can't be queried from every terminal, but most monospace fonts are about twice as tall as they are wide.
+const CELL_SIZE: (u32, u32) = (10, 20);
+
+/// An image protocol a terminal can support.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Protocol {
+    Kitty,
+    ITerm,
+    Sixel,
+}
+
+impl Protocol {
+    /// Returns the protocol to draw covers with for `setting`, or None if they shouldn't be drawn.
+    pub fn from_setting(setting: CoverArt) -> Option<Self> {
+        static DETECTED: Lazy<Option<Protocol>> =
+            Lazy::new(|| Protocol::detect(|name| std::env::var(name).ok()));
+
+        match setting {
+            CoverArt::Auto => *DETECTED,
+            CoverArt::Off => None,
+            CoverArt::Kitty => Some(Self::Kitty),
+            CoverArt::ITerm => Some(Self::ITerm),
+            CoverArt::Sixel => Some(Self::Sixel),
+        }
+    }
+
+    /// Detect the protocol the terminal supports from the environment variables returned by `var`.
+    ///
+    /// Images are never detected inside of tmux or screen, as they need to be wrapped in order to pass through them.
+    fn detect<F>(var: F) -> Option<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if var("TMUX").is_some() || var("STY").is_some() {
+            return None;
+        }
+
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+
+        if var("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" {
+            Some(Self::Kitty)
+        } else if program == "iTerm.app" || program == "WezTerm" {
+            Some(Self::ITerm)
+        } else if term.contains("sixel") || term.starts_with("foot") || term == "mlterm" {
+            Some(Self::Sixel)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `image` encoded with the protocol so that it fills `cols` columns and `rows` rows.
+    fn encode(self, image: &DynamicImage, cols: u16, rows: u16) -> Result<String> {
+        let (width, height) = (u32::from(cols) * CELL_SIZE.0, u32::from(rows) * CELL_SIZE.1);
+
+        let image = image.resize(width, height, FilterType::Triangle);
+
+        match self {
+            Self::Kitty => encode_kitty(&image, cols, rows),
+            Self::ITerm => encode_iterm(&image, cols, rows),
+            Self::Sixel => Ok(encode_sixel(&image)),
+        }
+    }
+}
+
+/// Returns the number of columns and rows an image of `width` by `height` pixels takes up when scaled to fit within `cols` and `rows`.
+#[allow(clippy::cast_possible_truncation)]
+fn fit(width: u32, height: u32, cols: u16, rows: u16) -> (u16, u16) {
+    if width == 0 || height == 0 {
+        return (0, 0);
+    }
+
+    let max_width = u64::from(cols) * u64::from(CELL_SIZE.0);
+    let max_height = u64::from(rows) * u64::from(CELL_SIZE.1);
+
+    let (width, height) = (u64::from(width), u64::from(height));
+
+    // Scale by whichever side runs out of room first
+    let (width, height) = if width * max_height > height * max_width {
+        (max_width, height * max_width / width)
+    } else {
+        (width * max_height / height, max_height)
+    };
+
+    let fit_cols = (width / u64::from(CELL_SIZE.0)).max(1).min(u64::from(cols));
+    let fit_rows = (height / u64::from(CELL_SIZE.1))
+        .max(1)
+        .min(u64::from(rows));
+
+    (fit_cols as u16, fit_rows as u16)
+}
+
+fn encode_kitty(image: &DynamicImage, cols: u16, rows: u16) -> Result<String> {
+    /// The most base64 data the protocol allows in a single escape sequence.
+    const CHUNK_SIZE: usize = 4096;
+
+    let rgba = image.to_rgba8();
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(rgba.as_raw())?;
+    let data = base64::encode(encoder.finish()?);
+
+    let chunks = data.as_bytes().chunks(CHUNK_SIZE).collect::<Vec<_>>();
+    let mut output = String::with_capacity(data.len() + chunks.len() * 16);
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+
+        // The chunks are base64, so they're always valid UTF-8
+        let chunk = std::str::from_utf8(chunk)?;
+
+        if i == 0 {
+            // q=2 keeps the terminal from replying, as the reply would be read as key presses
+            write!(
+                output,
+                "\x1b_Ga=T,f=32,s={},v={},c={},r={},o=z,C=1,q=2,m={};{}\x1b\\",
+                rgba.width(),
+                rgba.height(),
+                cols,
+                rows,
+                more,
+                chunk
+            )?;
+        } else {
+            write!(output, "\x1b_Gm={};{}\x1b\\", more, chunk)?;
+        }
+    }
+
+    Ok(output)
+}
+
+fn encode_iterm(image: &DynamicImage, cols: u16, rows: u16) -> Result<String> {
+    let mut png = Cursor::new(Vec::new());
+    image.write_to(&mut png, ImageOutputFormat::Png)?;
+    let png = png.into_inner();
+
+    Ok(format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        cols,
+        rows,
+        base64::encode(&png)
+    ))
+}
+
+/// Encode `image` as sixels, with its colors reduced to a palette of 252 evenly spaced colors.
+fn encode_sixel(image: &DynamicImage) -> String {
+    /// The number of shades of red, green, and blue in the palette.
+    const SHADES: (u32, u32, u32) = (6, 7, 6);
+
+    let rgb = image.to_rgb8();
+    let (width, height) = rgb.dimensions();
+
+    let shade = |value: u8, shades: u32| u32::from(value) * (shades - 1) / 255;
+
+    let indices = rgb
+        .pixels()
+        .map(|pixel| {
+            let [r, g, b] = pixel.0;
+            (shade(r, SHADES.0) * SHADES.1 + shade(g, SHADES.1)) * SHADES.2 + shade(b, SHADES.2)
+        })
+        .collect::<Vec<_>>();
+
+    let mut output = format!("\x1bPq\"1;1;{};{}", width, height);
+
+    for index in 0..SHADES.0 * SHADES.1 * SHADES.2 {
+        let r = index / (SHADES.1 * SHADES.2);
+        let g = index / SHADES.2 % SHADES.1;
+        let b = index % SHADES.2;
+
+        // Sixel colors are given as percentages
+        let pcnt = |value: u32, shades: u32| value * 100 / (shades - 1);
+
+        let _ = write!(
+            output,
+            "#{};2;{};{};{}",
+            index,
+            pcnt(r, SHADES.0),
+            pcnt(g, SHADES.1),
+            pcnt(b, SHADES.2)
+        );
+    }
+
+    // Each sixel is a column of 6 pixels, so the image is drawn in bands of 6 rows per color
+    for band_start in (0..height).step_by(6) {
+        let band_rows = band_start..(band_start + 6).min(height);
+
+        let mut colors = band_rows
+            .clone()
+            .flat_map(|y| (0..width).map(move |x| (y * width + x) as usize))
+            .map(|i| indices[i])
+            .collect::<Vec<_>>();
+
+        colors.sort_unstable();
+        colors.dedup();
+
+        for (i, &color) in colors.iter().enumerate() {
+            if i > 0 {
+                // Return to the start of the band to draw the next color over it
+                output.push('$');
+            }
+
+            let _ = write!(output, "#{}", color);
+
+            let sixels = (0..width).map(|x| {
+                let bits = band_rows
+                    .clone()
+                    .enumerate()
+                    .filter(|&(_, y)| indices[(y * width + x) as usize] == color)
+                    .fold(0, |bits, (bit, _)| bits | (1 << bit));
+
+                char::from(b'?' + bits)
+            });
+
+            push_run_length(&mut output, sixels);
+        }
+
+        output.push('-');
+    }
+
+    output.push_str("\x1b\\");
+    output
+}
+
+/// Push `sixels` to `output`, replacing runs of the same sixel with a repeat count.
+fn push_run_length<I>(output: &mut String, sixels: I)
+where
+    I: Iterator<Item = char>,
+{
+    let mut push = |sixel: char, count: usize| {
+        if count > 3 {
+            let _ = write!(output, "!{}{}", count, sixel);
+        } else {
+            output.extend(std::iter::repeat(sixel).take(count));
+        }
+    };
+
+    let mut run: Option<(char, usize)> = None;
+
+    for sixel in sixels {
+        run = match run {
+            Some((cur, count)) if cur == sixel => Some((cur, count + 1)),
+            Some((cur, count)) => {
+                push(cur, count);
+                Some((sixel, 1))
+            }
+            None => Some((sixel, 1)),
+        };
+    }
+
+    if let Some((sixel, count)) = run {
+        push(sixel, count);
+    }
+}
+
+/// Where a cover is drawn on the screen.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Placement {
+    series_id: i32,
+    protocol: Protocol,
+    rect: Rect,
+}
+
+enum Cover {
+    Loading,
+    /// The series has no cover, or it couldn't be loaded.
+    Missing,
+    Loaded(Arc<DynamicImage>),
+}
+
+/// The cover art of each series that has been shown, along with where the current one should be drawn.
+pub struct Covers {
+    images: ArcMutex<HashMap<i32, Cover>>,
+    load_tasks: Vec<ScopedTask<()>>,
+    /// Where the cover should be drawn after the current frame.
+    wanted: Option<Placement>,
+    /// Where the cover was last drawn, along with its encoded image.
+    drawn: Option<(Placement, String)>,
+    state: SharedState,
+}
+
+impl Covers {
+    pub fn new(state: &SharedState) -> Self {
+        Self {
+            images: arc_mutex(HashMap::new()),
+            load_tasks: Vec::new(),
+            wanted: None,
+            drawn: None,
+            state: state.clone(),
+        }
+    }
+
+    /// Forget where the cover should be drawn, which should be done at the start of every frame.
+    pub fn clear_wanted(&mut self) {
+        self.wanted = None;
+    }
+
+    /// Forget the last drawn cover, which should be done whenever the terminal is cleared.
+    pub fn forget_drawn(&mut self) {
+        self.drawn = None;
+    }
+
+    /// Reserve space for the cover of the series with `series_id` in the top left corner of `rect` and return the area it will be drawn in.
+    ///
+    /// None is returned when covers are turned off, the terminal doesn't support them, or the cover hasn't been loaded yet.
+    /// The cover will be loaded in the background with `http` if it hasn't been.
+    pub fn reserve(
+        &mut self,
+        series_id: i32,
+        setting: CoverArt,
+        remote: &RemoteStatus,
+        http: &reqwest::Client,
+        rect: Rect,
+    ) -> Option<Rect> {
+        let protocol = Protocol::from_setting(setting)?;
+
+        let (width, height) = {
+            let mut images = self.images.lock();
+
+            match images.get(&series_id) {
+                Some(Cover::Loaded(image)) => image.dimensions(),
+                Some(Cover::Loading | Cover::Missing) => return None,
+                None => {
+                    images.insert(series_id, Cover::Loading);
+                    drop(images);
+
+                    self.load_async(series_id, remote, http);
+                    return None;
+                }
+            }
+        };
+
+        let (cols, rows) = fit(width, height, rect.width, rect.height);
+
+        if cols == 0 || rows == 0 {
+            return None;
+        }
+
+        let rect = Rect {
+            width: cols,
+            height: rows,
+            ..rect
+        };
+
+        self.wanted = Some(Placement {
+            series_id,
+            protocol,
+            rect,
+        });
+
+        Some(rect)
+    }
+
+    /// Load the cover of the series with `series_id` from disk, or download it from AniList if it hasn't been yet.
+    fn load_async(&mut self, series_id: i32, remote: &RemoteStatus, http: &reqwest::Client) {
+        let remote = match remote {
+            RemoteStatus::LoggedIn(remote) if !remote.is_offline() => Some(Arc::clone(remote)),
+            _ => None,
+        };
+
+        let images = Arc::clone(&self.images);
+        let state = self.state.clone();
+        let http = http.clone();
+
+        let task = task::spawn(async move {
+            let cover = match load_cover(series_id, remote, &http).await {
+                Ok(Some(image)) => Cover::Loaded(Arc::new(image)),
+                Ok(None) => Cover::Missing,
+                Err(err) => {
+                    // Covers are only decoration, so a failure shouldn't interrupt the user
+                    tracing::warn!("failed to load cover of series {}: {:#}", series_id, err);
+                    Cover::Missing
+                }
+            };
+
+            images.lock().insert(series_id, cover);
+            state.lock().mark_dirty();
+        });
+
+        self.load_tasks.retain(|task| !task.is_finished());
+        self.load_tasks.push(task.into());
+    }
+
+    /// Returns true if the screen needs to be cleared and redrawn before the wanted cover can be drawn.
+    ///
+    /// This is only needed to erase a cover that was drawn as text, which the frame won't draw over when its cells don't change.
+    pub fn needs_clear(&self) -> bool {
+        match &self.drawn {
+            Some((drawn, _)) if Some(*drawn) != self.wanted => drawn.protocol != Protocol::Kitty,
+            _ => false,
+        }
+    }
+
+    /// Draw the wanted cover to `out` if it isn't already on the screen, and erase the previous one.
+    pub fn draw<W>(&mut self, out: &mut W) -> Result<()>
+    where
+        W: Write,
+    {
+        let drawn = self.drawn.as_ref().map(|(placement, _)| *placement);
+
+        if drawn == self.wanted {
+            return Ok(());
+        }
+
+        if let Some(Placement {
+            protocol: Protocol::Kitty,
+            ..
+        }) = drawn
+        {
+            write!(out, "\x1b_Ga=d,d=a,q=2\x1b\\")?;
+        }
+
+        let wanted = match self.wanted {
+            Some(wanted) => wanted,
+            None => {
+                self.drawn = None;
+                return out.flush().map_err(Into::into);
+            }
+        };
+
+        let image = match self.images.lock().get(&wanted.series_id) {
+            Some(Cover::Loaded(image)) => Arc::clone(image),
+            _ => return Ok(()),
+        };
+
+        // Covers are encoded again when they move, as they may need to be scaled differently
+        let encoded = match self.drawn.take() {
+            Some((placement, encoded))
+                if placement.series_id == wanted.series_id
+                    && placement.protocol == wanted.protocol
+                    && (placement.rect.width, placement.rect.height)
+                        == (wanted.rect.width, wanted.rect.height) =>
+            {
+                encoded
+            }
+            _ => wanted
+                .protocol
+                .encode(&image, wanted.rect.width, wanted.rect.height)
+                .context("encoding cover")?,
+        };
+
+        out.queue(cursor::MoveTo(wanted.rect.x, wanted.rect.y))?;
+        out.write_all(encoded.as_bytes())?;
+        out.flush()?;
+
+        self.drawn = Some((wanted, encoded));
+        Ok(())
+    }
+}
+
+/// Returns the cover of the series with `series_id` from the cover directory, or downloads it with `remote` and `http` if it isn't there.
+async fn load_cover(
+    series_id: i32,
+    remote: Option<Arc<Remote>>,
+    http: &reqwest::Client,
+) -> Result<Option<DynamicImage>> {
+    let path = cover_path(series_id)?;
+
+    let bytes = if path.exists() {
+        fs::read(&path).with_context(|| anyhow!("failed to read {}", path.display()))?
+    } else {
+        let anilist = match remote.as_deref().and_then(anilist) {
+            Some(anilist) => anilist,
+            None => return Ok(None),
+        };
+
+        #[allow(clippy::cast_sign_loss)]
+        let url = match anilist.cover_image_url(series_id as u32).await? {
+            Some(url) => url,
+            None => return Ok(None),
+        };
+
+        let bytes = async {
+            http.get(&url)
+                .timeout(Duration::from_secs(15))
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await
+        }
+        .await
+        .with_context(|| anyhow!("failed to download cover at {}", url))?
+        .to_vec();
+
+        fs::write(&path, &bytes).with_context(|| anyhow!("failed to save {}", path.display()))?;
+        bytes
+    };
+
+    let image = image::load_from_memory(&bytes).context("decoding cover")?;
+    Ok(Some(image))
+}
+
+fn cover_path(series_id: i32) -> Result<PathBuf> {
+    let mut path = SaveDir::LocalData.validated_dir_path()?.join("covers");
+
+    if !path.exists() {
+        fs::create_dir_all(&path).context("creating cover directory")?;
+    }
+
+    path.push(series_id.to_string());
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn detect_protocol() {
+        let detect = |vars: &[(&str, &str)]| {
+            Protocol::detect(|name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| (*value).to_string())
+            })
+        };
+
+        assert_eq!(detect(&[("TERM", "xterm-kitty")]), Some(Protocol::Kitty));
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "iTerm.app")]),
+            Some(Protocol::ITerm)
+        );
+        assert_eq!(detect(&[("TERM", "foot")]), Some(Protocol::Sixel));
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), None);
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/tmux-1000/default")]),
+            None
+        );
+    }
+
+    #[test]
+    fn fit_cover() {
+        // Covers are roughly 2:3, which is about 4:3 in cells that are twice as tall as they are wide
+        assert_eq!(fit(460, 690, 40, 10), (13, 10));
+        // Limited by width rather than height
+        assert_eq!(fit(460, 690, 6, 10), (6, 4));
+        assert_eq!(fit(0, 690, 40, 10), (0, 0));
+    }
+
+    #[test]
+    fn encode_sixels() {
+        let mut image = RgbImage::from_pixel(5, 7, Rgb([0, 0, 0]));
+        image.put_pixel(0, 6, Rgb([255, 255, 255]));
+
+        let encoded = encode_sixel(&DynamicImage::ImageRgb8(image));
+
+        assert!(encoded.starts_with("\x1bPq\"1;1;5;7#0;2;0;0;0"));
+        // The first band is entirely black, while the white pixel starts the second one
+        assert!(encoded.ends_with("#0!5~-#0?!4@$#251@!4?-\x1b\\"));
+    }
+}
@@ -1,15 +1,19 @@
 use super::ShouldReset;
+use crate::config::Config;
+use crate::series::deleted::DeletedSeries;
+use crate::tui::component::prompt::log::LogKind;
 use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::tui::undo::UndoAction;
 use crate::{key::Key, tui::component::Component};
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 use crossterm::event::KeyCode;
 use std::fs;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::{backend::Backend, text::Span};
 use tui_utils::{
-    helpers::{block, text},
+    helpers::text,
     layout::{RectExt, SimpleLayout},
     widgets::{Fragment, OverflowMode, SimpleText, SpanOptions, TextFragments},
     wrap,
@@ -23,6 +27,10 @@ pub struct DeleteSeriesPanel {
 
 impl DeleteSeriesPanel {
     pub fn init(state: &UIState) -> Result<Self> {
+        if !state.marked_series.is_empty() {
+            return Ok(Self::init_marked(state));
+        }
+
         let series = match state.series.selected() {
             Some(series) => series,
             None => return Err(anyhow!("must select a series to delete")),
@@ -38,15 +46,91 @@ impl DeleteSeriesPanel {
         })
     }
 
+    fn init_marked(state: &UIState) -> Self {
+        let marked = state
+            .series
+            .iter()
+            .filter(|series| state.marked_series.contains(&series.config().id))
+            .collect::<Vec<_>>();
+
+        let removal_warning_text = format!("{} marked series will be removed", marked.len());
+
+        let series_path_text = marked
+            .iter()
+            .map(|series| series.path().inner().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Self {
+            remove_files: RemoveFiles::default(),
+            removal_warning_text,
+            series_path_text,
+        }
+    }
+
+    fn delete_marked_series(&self, state: &mut UIState) {
+        let deleted = state.delete_marked_series();
+        let num_deleted = deleted.len();
+
+        if num_deleted == 0 {
+            return;
+        }
+
+        let (undoable, errors) = remove_series_files(deleted, self.remove_files, &state.config);
+
+        for err in &errors {
+            state.log.push_error(err);
+        }
+
+        state
+            .log
+            .push(LogKind::Info, format!("deleted {} series", num_deleted));
+
+        let num_removed = num_deleted - undoable.len();
+
+        if num_removed > 0 {
+            state.log.push(
+                LogKind::Info,
+                format!(
+                    "the folders of {} series were removed, so deleting them can't be undone",
+                    num_removed
+                ),
+            );
+        }
+
+        if !undoable.is_empty() {
+            let undo = undoable.into_iter().map(UndoAction::Delete).collect();
+            state.undo.push(UndoAction::Batch(undo));
+        }
+    }
+
     fn delete_selected_series(&self, state: &mut UIState) -> Result<()> {
-        let series = state.delete_selected_series()?;
+        if !state.marked_series.is_empty() {
+            self.delete_marked_series(state);
+            return Ok(());
+        }
+
+        let deleted = state.delete_selected_series()?;
+        let nickname = deleted.series.nickname().to_string();
 
-        if let RemoveFiles::Yes = self.remove_files {
-            let path = series.config().path.absolute(&state.config);
-            fs::remove_dir_all(path).context("failed to remove directory")?;
+        let (undoable, mut errors) =
+            remove_series_files(vec![deleted], self.remove_files, &state.config);
+
+        match undoable.into_iter().next() {
+            Some(deleted) => state.undo.push(UndoAction::Delete(deleted)),
+            None => state.log.push(
+                LogKind::Info,
+                format!(
+                    "the folder of {} was removed, so deleting it can't be undone",
+                    nickname
+                ),
+            ),
         }
 
-        Ok(())
+        match errors.pop() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
     }
 
     fn draw_remove_files_warning<B: Backend>(
@@ -70,7 +154,7 @@ impl DeleteSeriesPanel {
         frame.render_widget(path_widget, path_rect);
 
         let delete_status_text = match self.remove_files {
-            RemoveFiles::Yes => text::bold_with("will be deleted.", |s| s.fg(Color::Red)),
+            RemoveFiles::Yes => text::bold_with("will be deleted.", |s| s.fg(theme::get().error)),
             RemoveFiles::No => text::bold("will not be deleted."),
         };
 
@@ -104,7 +188,7 @@ impl DeleteSeriesPanel {
     }
 
     pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
-        let block = block::with_borders("Delete Series");
+        let block = theme::block("Delete Series");
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -114,7 +198,8 @@ impl DeleteSeriesPanel {
             .vertical_margin(1)
             .split_quarters(block_area);
 
-        let warning_text = text::bold_with(&self.removal_warning_text, |s| s.fg(Color::Red));
+        let warning_text =
+            text::bold_with(&self.removal_warning_text, |s| s.fg(theme::get().error));
         let warning_widget = SimpleText::new(warning_text)
             .alignment(Alignment::Center)
             .overflow(OverflowMode::Truncate);
@@ -151,6 +236,39 @@ impl Component for DeleteSeriesPanel {
     }
 }
 
+/// Remove the folder of each of the `deleted` series if `remove_files` says to.
+///
+/// Returns the series whose deletion can still be undone, which are those whose folders weren't removed,
+/// along with an error for each folder that couldn't be removed.
+fn remove_series_files(
+    deleted: Vec<DeletedSeries>,
+    remove_files: RemoveFiles,
+    config: &Config,
+) -> (Vec<DeletedSeries>, Vec<anyhow::Error>) {
+    if let RemoveFiles::No = remove_files {
+        return (deleted, Vec::new());
+    }
+
+    let mut undoable = Vec::new();
+    let mut errors = Vec::new();
+
+    for deleted in deleted {
+        let path = deleted.series.config().path.absolute(config);
+
+        if let Err(err) = fs::remove_dir_all(&path) {
+            errors.push(anyhow!(err).context(format!(
+                "failed to remove directory of {}",
+                deleted.series.nickname()
+            )));
+
+            // The folder is still there, so the series can be brought back
+            undoable.push(deleted);
+        }
+    }
+
+    (undoable, errors)
+}
+
 #[derive(Copy, Clone)]
 enum RemoveFiles {
     Yes,
@@ -176,3 +294,68 @@ impl Default for RemoveFiles {
         Self::No
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::series::config::SeriesConfig;
+    use crate::series::{LoadedSeries, SeriesPath};
+    use anime::local::EpisodeParser;
+    use std::env;
+
+    fn deleted_series(id: i32, path: SeriesPath) -> DeletedSeries {
+        let sconfig = SeriesConfig {
+            id,
+            nickname: format!("series{}", id),
+            path,
+            episode_parser: EpisodeParser::default(),
+            player_args: Default::default(),
+            episode_offset: 0,
+            episode_category: None,
+            absolute_numbering: false,
+            feed_url: None,
+            local_rewatch: None,
+            sub_lang: None,
+            audio_lang: None,
+            sub_file_pattern: None,
+            stream_url_template: None,
+            episode_length_mins: None,
+        };
+
+        DeletedSeries {
+            series: LoadedSeries::None(sconfig, anyhow!("not loaded")),
+            rows: Default::default(),
+        }
+    }
+
+    #[test]
+    fn no_undo_after_removing_files() {
+        let series_dir = env::temp_dir().join(format!("anup_delete_{}", std::process::id()));
+        let kept_dir = series_dir.join("Kept");
+        let removed_dir = series_dir.join("Removed");
+
+        fs::create_dir_all(&kept_dir).unwrap();
+        fs::create_dir_all(&removed_dir).unwrap();
+
+        let config = Config {
+            series_dir: series_dir.clone(),
+            ..Config::default()
+        };
+
+        let kept = deleted_series(1, SeriesPath::new(kept_dir.clone(), &config));
+        let (undoable, errors) = remove_series_files(vec![kept], RemoveFiles::No, &config);
+
+        assert_eq!(undoable.len(), 1);
+        assert!(errors.is_empty());
+        assert!(kept_dir.exists());
+
+        let removed = deleted_series(2, SeriesPath::new(removed_dir.clone(), &config));
+        let (undoable, errors) = remove_series_files(vec![removed], RemoveFiles::Yes, &config);
+
+        assert!(undoable.is_empty());
+        assert!(errors.is_empty());
+        assert!(!removed_dir.exists());
+
+        fs::remove_dir_all(&series_dir).unwrap();
+    }
+}
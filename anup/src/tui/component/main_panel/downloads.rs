@@ -0,0 +1,117 @@
+use super::ShouldReset;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, RectExt},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+pub struct DownloadsPanel {
+    selected: WrappingIndex,
+}
+
+impl DownloadsPanel {
+    pub fn new(state: &UIState) -> Self {
+        let selected = state
+            .list_positions
+            .get(&SavedList::Downloads, state.downloads.len());
+
+        Self {
+            selected: WrappingIndex::new(selected),
+        }
+    }
+
+    /// Remember the selected download so it can be restored the next time the panel is opened.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        positions.save(SavedList::Downloads, self.selected.get());
+    }
+
+    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Downloads");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let hint = if state.config.downloads.client.is_none() {
+            Some("No download client is set in your config")
+        } else if state.downloads.is_empty() {
+            Some("Nothing is being downloaded")
+        } else {
+            None
+        };
+
+        if let Some(hint) = hint {
+            let widget = SimpleText::new(text::hint(hint)).alignment(Alignment::Center);
+            frame.render_widget(widget, block_area.lines_from_top(1));
+            return;
+        }
+
+        let progress = state
+            .downloads
+            .iter()
+            .map(|download| format!("{:.0}%", download.torrent.progress * 100.0))
+            .collect::<Vec<_>>();
+
+        let rows = state
+            .downloads
+            .iter()
+            .zip(&progress)
+            .map(|(download, progress)| {
+                [
+                    Span::raw(download.nickname.as_str()),
+                    Span::raw(download.torrent.name.as_str()),
+                    Span::raw(progress.as_str()),
+                ]
+            });
+
+        let header = [
+            Span::raw("Series"),
+            Span::raw("Name"),
+            Span::raw("Progress"),
+        ];
+
+        let layout = [
+            BasicConstraint::Percentage(25),
+            BasicConstraint::Percentage(65),
+            BasicConstraint::Length(9),
+        ];
+
+        let selected = self.selected.get().min(state.downloads.len() - 1);
+
+        let widget = SimpleTable::new(rows, layout)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(selected as u16));
+
+        frame.render_widget(widget, block_area);
+    }
+}
+
+impl Component for DownloadsPanel {
+    type State = UIState;
+    type KeyResult = ShouldReset;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => ShouldReset::Yes,
+            KeyCode::Up => {
+                self.selected.decrement(state.downloads.len());
+                ShouldReset::No
+            }
+            KeyCode::Down => {
+                self.selected.increment(state.downloads.len());
+                ShouldReset::No
+            }
+            _ => ShouldReset::No,
+        }
+    }
+}
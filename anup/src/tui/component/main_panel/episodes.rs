@@ -0,0 +1,276 @@
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::tui::undo::UndoAction;
+use crate::{key::Key, tui::component::Component};
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::{style, text},
+    layout::{BasicConstraint, SimpleLayout},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// Lists every episode of the selected series so any of them can be played, or marked as watched or unwatched.
+pub struct EpisodesPanel {
+    series_id: i32,
+    nickname: String,
+    title: String,
+    episodes: Vec<EpisodeRow>,
+    watched: u32,
+    selected: WrappingIndex,
+    /// The index of the episode that the range of episodes being marked starts at, if there is one.
+    range_start: Option<usize>,
+}
+
+impl EpisodesPanel {
+    pub fn init(state: &UIState) -> Result<Self> {
+        let series = state
+            .series
+            .get_valid_sel_series()
+            .ok_or_else(|| anyhow!("must select a series with episodes to list"))?;
+
+        let last_episode = series
+            .episodes
+            .highest_episode_number()
+            .max(series.data.info.episodes.max(0) as u32);
+
+        if last_episode == 0 {
+            return Err(anyhow!(
+                "no episodes of {} were found",
+                series.data.config.nickname
+            ));
+        }
+
+        let episodes = (1..=last_episode)
+            .map(|number| EpisodeRow {
+                number,
+                filename: series
+                    .episodes
+                    .find(number)
+                    .map(|episode| episode.filename.clone()),
+            })
+            .collect::<Vec<_>>();
+
+        let watched = series.watched_episodes().max(0) as u32;
+
+        // Start on the next episode to watch when the panel hasn't been opened before, since that's where changes are most likely to be made
+        let next_index = (watched as usize).min(episodes.len() - 1);
+
+        let selected = state
+            .list_positions
+            .find(&SavedList::Episodes(series.data.config.id), episodes.len())
+            .unwrap_or(next_index);
+
+        Ok(Self {
+            series_id: series.data.config.id,
+            nickname: series.data.config.nickname.clone(),
+            title: format!("Episodes of {}", series.data.config.nickname),
+            episodes,
+            watched,
+            selected: WrappingIndex::new(selected),
+            range_start: None,
+        })
+    }
+
+    /// Remember the selected episode so it can be restored the next time the panel is opened for the same series.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        positions.save(SavedList::Episodes(self.series_id), self.selected.get());
+    }
+
+    /// Returns the first and last episode number of the episodes being marked, or the selected episode's number twice
+    /// when no range is being marked.
+    fn selected_range(&self) -> (u32, u32) {
+        let selected = self.selected.get();
+        let start = self.range_start.unwrap_or(selected);
+
+        let first = self.episodes[start.min(selected)].number;
+        let last = self.episodes[start.max(selected)].number;
+
+        (first, last)
+    }
+
+    fn is_in_range(&self, index: usize) -> bool {
+        match self.range_start {
+            Some(start) => {
+                let selected = self.selected.get();
+                (start.min(selected)..=start.max(selected)).contains(&index)
+            }
+            None => false,
+        }
+    }
+
+    /// Mark every selected episode as `watched` or unwatched.
+    ///
+    /// Progress is tracked as the number of episodes watched from the start, so marking episodes as watched also marks every
+    /// episode before them, and marking them as unwatched also unmarks every episode after them.
+    fn mark_selected(&mut self, watched: bool, state: &mut UIState) -> Result<()> {
+        let (first, last) = self.selected_range();
+
+        let progress = if watched {
+            self.watched.max(last)
+        } else {
+            self.watched.min(first - 1)
+        };
+
+        self.range_start = None;
+
+        if progress == self.watched {
+            return Ok(());
+        }
+
+        let series = state
+            .series
+            .get_valid_sel_series_mut()
+            .filter(|series| series.data.config.id == self.series_id)
+            .ok_or_else(|| anyhow!("{} is no longer selected", self.nickname))?;
+
+        // The progress of a local rewatch is part of the config of the series
+        let previous = if series.data.config.local_rewatch.is_some() {
+            UndoAction::Config(series.data.config.clone())
+        } else {
+            UndoAction::Entry(series.data.entry.clone())
+        };
+
+        series.set_progress(progress as i16, &state.config, &state.db)?;
+        self.watched = series.watched_episodes().max(0) as u32;
+
+        state.undo.push(previous);
+        state.request_entry_sync();
+
+        Ok(())
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block(self.title.as_str());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                BasicConstraint::MinLenRemaining(4, 4),
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let theme = theme::get();
+
+        let rows = self.episodes.iter().enumerate().map(|(index, episode)| {
+            let number = if self.is_in_range(index) {
+                Span::styled(format!("{}*", episode.number), style::fg(theme.selection))
+            } else {
+                Span::raw(episode.number.to_string())
+            };
+
+            let watched = if episode.number <= self.watched {
+                Span::styled("Watched", style::fg(theme.completed))
+            } else {
+                Span::raw("")
+            };
+
+            let filename = match &episode.filename {
+                Some(filename) => Span::raw(filename.as_str()),
+                None => Span::styled("Missing", style::fg(theme.error)),
+            };
+
+            [number, watched, filename]
+        });
+
+        let header = [Span::raw("Episode"), Span::raw("Status"), Span::raw("File")];
+
+        let widths = [
+            BasicConstraint::Length(8),
+            BasicConstraint::Length(8),
+            BasicConstraint::Percentage(100),
+        ];
+
+        let widget = SimpleTable::new(rows, widths)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(self.selected.get() as u16));
+
+        frame.render_widget(widget, layout[0]);
+
+        let hint = text::hint(
+            "Enter - Play | Space - Mark Range | W - Set Watched | U - Set Unwatched | Esc - Exit",
+        );
+
+        let hint_widget = SimpleText::new(hint).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, layout[1]);
+    }
+}
+
+impl Component for EpisodesPanel {
+    type State = UIState;
+    type KeyResult = Result<EpisodesResult>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc if self.range_start.is_some() => {
+                self.range_start = None;
+                Ok(EpisodesResult::Ok)
+            }
+            KeyCode::Esc => Ok(EpisodesResult::Reset),
+            KeyCode::Up => {
+                self.selected.decrement(self.episodes.len());
+                Ok(EpisodesResult::Ok)
+            }
+            KeyCode::Down => {
+                self.selected.increment(self.episodes.len());
+                Ok(EpisodesResult::Ok)
+            }
+            KeyCode::Char(' ') => {
+                self.range_start = match self.range_start {
+                    Some(_) => None,
+                    None => Some(self.selected.get()),
+                };
+
+                Ok(EpisodesResult::Ok)
+            }
+            KeyCode::Char('w') => {
+                self.mark_selected(true, state)?;
+                Ok(EpisodesResult::Ok)
+            }
+            KeyCode::Char('u') => {
+                self.mark_selected(false, state)?;
+                Ok(EpisodesResult::Ok)
+            }
+            KeyCode::Enter => {
+                let episode = &self.episodes[self.selected.get()];
+
+                if episode.filename.is_none() {
+                    return Err(anyhow!("episode {} is missing", episode.number));
+                }
+
+                if episode.number == self.watched + 1 {
+                    Ok(EpisodesResult::PlayNext)
+                } else {
+                    Ok(EpisodesResult::Play(episode.number))
+                }
+            }
+            _ => Ok(EpisodesResult::Ok),
+        }
+    }
+}
+
+pub enum EpisodesResult {
+    Ok,
+    Reset,
+    /// Play the next episode to watch, which counts towards the progress of the series.
+    PlayNext,
+    /// Play an episode without changing the status or progress of the series.
+    Play(u32),
+}
+
+struct EpisodeRow {
+    number: u32,
+    /// The name of the file the episode is in, or None if it couldn't be found.
+    filename: Option<String>,
+}
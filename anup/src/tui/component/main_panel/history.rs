@@ -0,0 +1,135 @@
+use super::ShouldReset;
+use crate::series::history::WatchRecord;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use anyhow::{Context, Result};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, RectExt},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+pub struct HistoryPanel {
+    records: Vec<HistoryRow>,
+    selected: WrappingIndex,
+}
+
+impl HistoryPanel {
+    const MAX_RECORDS: i64 = 200;
+
+    pub fn init(state: &UIState) -> Result<Self> {
+        let records = WatchRecord::recent(&state.db, Self::MAX_RECORDS)
+            .context("loading watch history")?
+            .into_iter()
+            .map(HistoryRow::new)
+            .collect::<Vec<_>>();
+
+        let selected = state.list_positions.get(&SavedList::History, records.len());
+
+        Ok(Self {
+            records,
+            selected: WrappingIndex::new(selected),
+        })
+    }
+
+    /// Remember the selected record so it can be restored the next time the panel is opened.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        positions.save(SavedList::History, self.selected.get());
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Watch History");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        if self.records.is_empty() {
+            let text = text::hint("No episodes have been watched yet");
+            let widget = SimpleText::new(text).alignment(Alignment::Center);
+            frame.render_widget(widget, block_area.lines_from_top(1));
+            return;
+        }
+
+        let rows = self.records.iter().map(|row| {
+            [
+                Span::raw(row.finished_at.as_str()),
+                Span::raw(row.record.title.as_str()),
+                Span::raw(row.episode.as_str()),
+                Span::raw(row.watch_time.as_str()),
+                Span::raw(row.exit_status.as_str()),
+            ]
+        });
+
+        let header = [
+            Span::raw("Finished"),
+            Span::raw("Series"),
+            Span::raw("Episode"),
+            Span::raw("Watched For"),
+            Span::raw("Player Exit"),
+        ];
+
+        let layout = [
+            BasicConstraint::Length(17),
+            BasicConstraint::Percentage(50),
+            BasicConstraint::Length(8),
+            BasicConstraint::Length(12),
+            BasicConstraint::Length(12),
+        ];
+
+        let widget = SimpleTable::new(rows, layout)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(self.selected.get() as u16));
+
+        frame.render_widget(widget, block_area);
+    }
+}
+
+impl Component for HistoryPanel {
+    type State = UIState;
+    type KeyResult = ShouldReset;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => ShouldReset::Yes,
+            KeyCode::Up => {
+                self.selected.decrement(self.records.len());
+                ShouldReset::No
+            }
+            KeyCode::Down => {
+                self.selected.increment(self.records.len());
+                ShouldReset::No
+            }
+            _ => ShouldReset::No,
+        }
+    }
+}
+
+/// A watch record with all of its displayed fields formatted ahead of time.
+struct HistoryRow {
+    record: WatchRecord,
+    finished_at: String,
+    episode: String,
+    watch_time: String,
+    exit_status: String,
+}
+
+impl HistoryRow {
+    fn new(record: WatchRecord) -> Self {
+        Self {
+            finished_at: record.finished_at_str(),
+            episode: record.episode.to_string(),
+            watch_time: record.watch_time_str(),
+            exit_status: record.exit_status_str(),
+            record,
+        }
+    }
+}
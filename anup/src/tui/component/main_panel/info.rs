@@ -1,14 +1,18 @@
+use super::cover::Covers;
 use crate::remote::RemoteStatus;
+use crate::series::history::WatchRecord;
 use crate::tui::state::ProgressTime;
 use crate::tui::state::SharedState;
+use crate::tui::theme;
+use crate::tui::widget_util;
 use crate::tui::{state::StateEvent, UIState};
 use crate::util;
 use crate::{
-    series::{LoadedSeries, Series},
+    series::{EpisodeScanError, LoadedSeries, Series},
     tui::component::Component,
 };
-use anime::remote::{ScoreParser, SeriesDate};
-use chrono::Utc;
+use anime::remote::{ScoreParser, SeriesDate, Status};
+use chrono::{Local, Utc};
 use smallvec::{smallvec, SmallVec};
 use std::{
     borrow::Cow,
@@ -19,11 +23,11 @@ use std::{fmt, sync::atomic::AtomicU32};
 use tokio::task;
 use tui::backend::Backend;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::text::Span;
+use tui::widgets::Gauge;
 use tui_utils::{
-    helpers::{block, text},
+    helpers::text,
     layout::{BasicConstraint, RectExt, SimpleLayout},
     widgets::{Fragment, OverflowMode, SimpleText, SpanOptions, TextFragments},
     wrap,
@@ -32,6 +36,8 @@ use util::ScopedTask;
 
 pub struct InfoPanel {
     progress_remaining_secs: Arc<AtomicU32>,
+    /// The recent watch activity of the last series drawn, which is reloaded when a different series is selected or its progress changes.
+    activity: Option<Activity>,
     #[allow(dead_code)]
     event_monitor_task: ScopedTask<()>,
 }
@@ -44,6 +50,7 @@ impl InfoPanel {
 
         Self {
             progress_remaining_secs,
+            activity: None,
             event_monitor_task,
         }
     }
@@ -183,6 +190,11 @@ impl InfoPanel {
             span("Ctrl + Shift + V or Ctrl + V."),
             Fragment::Line,
             Fragment::Line,
+            span("Series can also be added with 'a' without an account,"),
+            Fragment::Line,
+            span("but your list will only be kept locally."),
+            Fragment::Line,
+            Fragment::Line,
             span("More detailed instructions here:"),
             Fragment::Line,
             span("https://github.com/Acizza/anup#adding-an-account"),
@@ -228,10 +240,10 @@ impl InfoPanel {
         B: Backend,
         E: fmt::Display,
     {
-        let header = text::bold_with("Error Loading Series", |s| s.fg(Color::Red));
+        let header = text::bold_with("Error Loading Series", |s| s.fg(theme::get().error));
 
         let body = [Fragment::Span(
-            text::with_color(err.to_string(), Color::Red),
+            text::with_color(err.to_string(), theme::get().error),
             SpanOptions::new().overflow(OverflowMode::Truncate),
         )];
 
@@ -241,11 +253,41 @@ impl InfoPanel {
         Self::draw_text_panel(header, &wrapped, h_pos, b_pos, frame);
     }
 
+    /// Returns the number of episodes of `series` watched on each of the last [`Activity::DAYS`] days.
+    fn recent_activity(&mut self, state: &UIState, series: &Series) -> &[u64] {
+        let id = series.data.config.id;
+        let watched = series.watched_episodes();
+
+        let is_current = matches!(&self.activity, Some(activity) if activity.series_id == id && activity.watched == watched);
+
+        if !is_current {
+            let since = Utc::now() - chrono::Duration::days(Activity::DAYS as i64);
+
+            // The activity is only decoration, so a failed query shouldn't prevent the rest of the panel from being drawn
+            let dates = WatchRecord::finished_dates(&state.db, id, since).unwrap_or_default();
+
+            self.activity = Some(Activity {
+                series_id: id,
+                watched,
+                counts: widget_util::daily_counts(
+                    dates,
+                    Activity::DAYS,
+                    Local::today().naive_local(),
+                ),
+            });
+        }
+
+        self.activity
+            .as_ref()
+            .map_or(&[], |activity| &activity.counts)
+    }
+
     #[allow(clippy::too_many_lines)]
     fn draw_series_info<B>(
-        &self,
+        &mut self,
         state: &UIState,
         series: &Series,
+        covers: &mut Covers,
         rect: Rect,
         frame: &mut Frame<B>,
     ) where
@@ -256,6 +298,7 @@ impl InfoPanel {
             [
                 BasicConstraint::Length(4),
                 BasicConstraint::Percentage(70),
+                BasicConstraint::Length(3),
                 BasicConstraint::Length(4),
             ],
         );
@@ -266,7 +309,7 @@ impl InfoPanel {
         // Series title
         {
             let mut fragments: SmallVec<[Fragment; 2]> = smallvec![Fragment::Span(
-                text::bold(&info.title_preferred),
+                text::bold(info.title(state.config.title_language)),
                 SpanOptions::new().overflow(OverflowMode::Truncate)
             )];
 
@@ -274,45 +317,115 @@ impl InfoPanel {
                 fragments.push(Fragment::span(text::italic(" [*]")));
             }
 
+            if !series.data.tags.is_empty() {
+                fragments.push(Fragment::Line);
+
+                for (i, tag) in series.data.tags.iter().enumerate() {
+                    if i > 0 {
+                        fragments.push(Fragment::span(" "));
+                    }
+
+                    fragments.push(Fragment::span(widget_util::tag_chip(tag)));
+                }
+            }
+
             let title_widget = TextFragments::new(&fragments).alignment(Alignment::Center);
             frame.render_widget(title_widget, layout[0]);
         }
 
-        // Items in panel
+        // Cover art, which is drawn to the left of the items when the terminal can show it
+        let content = {
+            let area = layout[1];
+            let max_area = Rect {
+                width: area.width / 3,
+                ..area
+            };
 
-        macro_rules! draw_stat {
-            ($x_column:expr, $y_column:expr => $header:expr, $value:expr) => {{
-                let content = layout[1];
+            let cover = covers.reserve(
+                series.data.config.id,
+                state.config.tui.cover_art,
+                &state.remote,
+                &state.http,
+                max_area,
+            );
+
+            match cover {
+                Some(cover) => {
+                    let offset = cover.width + 2;
+
+                    Rect {
+                        x: area.x + offset,
+                        width: area.width.saturating_sub(offset),
+                        ..area
+                    }
+                }
+                None => area,
+            }
+        };
 
-                let pos = content.grid_pos(Rect {
+        // Items in panel
+
+        macro_rules! grid_pos {
+            ($x_column:expr, $y_column:expr) => {{
+                content.grid_pos(Rect {
                     x: $x_column,
                     y: $y_column,
                     width: content.width / 3,
                     height: content.height / 3,
-                });
+                })
+            }};
+        }
 
-                Self::draw_stat($header, $value, pos, frame);
+        macro_rules! draw_stat {
+            ($x_column:expr, $y_column:expr => $header:expr, $value:expr) => {{
+                Self::draw_stat($header, $value, grid_pos!($x_column, $y_column), frame);
             }};
         }
 
         // Left panel items
 
+        let episode_length_mins = series.data.episode_length_mins();
+
         draw_stat!(0, 0 => "Watch Time", {
-            let watch_time_mins = info.episodes * info.episode_length_mins;
+            let watch_time_mins = info.episodes * episode_length_mins;
             util::hm_from_mins(f32::from(watch_time_mins))
         });
 
         draw_stat!(0, 1 => "Time Left", {
-            let eps_left = info.episodes - entry.watched_episodes().min(info.episodes);
-            let time_left_mins = eps_left * info.episode_length_mins;
+            let eps_left = info.episodes - series.watched_episodes().min(info.episodes);
+            let time_left_mins = eps_left * episode_length_mins;
             util::hm_from_mins(f32::from(time_left_mins))
         });
 
-        draw_stat!(0, 2 => "Episode Length", format!("{}M", info.episode_length_mins));
+        draw_stat!(0, 2 => "Episode Length", format!("{}M", episode_length_mins));
 
         // Middle panel items
 
-        draw_stat!(1, 0 => "Progress", format!("{}|{}", entry.watched_episodes(), info.episodes));
+        let status = if series.data.config.local_rewatch.is_some() {
+            Status::Rewatching
+        } else {
+            entry.status()
+        };
+
+        {
+            let watched = series.watched_episodes();
+            let progress = format!("{}|{}", watched, info.episodes);
+
+            // Fractional episodes (such as recaps numbered 12.5) are never counted towards progress
+            let label = match series.episodes.fractional().count() {
+                0 => progress,
+                extras => format!("{} (+{})", progress, extras),
+            };
+
+            let gauge = widget_util::progress_gauge(
+                watched.max(0) as u16,
+                info.episodes.max(0) as u16,
+                status,
+                label,
+            );
+
+            Self::draw_gauge_stat("Progress", gauge, grid_pos!(1, 0), frame);
+        }
 
         draw_stat!(1, 1 => "Score", {
             match (entry.score(), &state.remote) {
@@ -322,10 +435,16 @@ impl InfoPanel {
             }
         });
 
-        draw_stat!(1, 2 => "Status", {
-            let status: &'static str = entry.status().into();
-            status
-        });
+        {
+            let text: &'static str = if series.data.config.local_rewatch.is_some() {
+                "Rewatching Locally"
+            } else {
+                status.into()
+            };
+
+            let badge = widget_util::status_badge(text, status);
+            Self::draw_span_stat("Status", badge, grid_pos!(1, 2), frame);
+        }
 
         // Right panel items
 
@@ -341,24 +460,86 @@ impl InfoPanel {
         draw_stat!(2, 1 => "Finish Date", format_date(entry.end_date()));
         draw_stat!(2, 2 => "Rewatched", entry.times_rewatched().to_string());
 
-        self.draw_status_text(state, layout[2], frame);
+        self.draw_activity(state, series, layout[2], frame);
+        self.draw_status_text(state, layout[3], frame);
     }
 
     fn draw_stat<B, S>(header: &str, value: S, rect: Rect, frame: &mut Frame<B>)
     where
         B: Backend,
         S: AsRef<str>,
+    {
+        Self::draw_span_stat(header, text::italic(value.as_ref()), rect, frame);
+    }
+
+    fn draw_span_stat<B>(header: &str, value: Span, rect: Rect, frame: &mut Frame<B>)
+    where
+        B: Backend,
     {
         let fragments = [
             Fragment::span(text::bold(header)),
             Fragment::Line,
-            Fragment::span(text::italic(value.as_ref())),
+            Fragment::span(value),
         ];
 
         let widget = TextFragments::new(&fragments).alignment(Alignment::Center);
         frame.render_widget(widget, rect);
     }
 
+    fn draw_gauge_stat<B>(header: &str, gauge: Gauge, rect: Rect, frame: &mut Frame<B>)
+    where
+        B: Backend,
+    {
+        let layout = SimpleLayout::new(Direction::Vertical).split(
+            rect,
+            [BasicConstraint::Length(1), BasicConstraint::Length(1)],
+        );
+
+        let header_widget = SimpleText::new(text::bold(header)).alignment(Alignment::Center);
+        frame.render_widget(header_widget, layout[0]);
+
+        // Leave some space on either side so the gauges of neighboring stats don't run together
+        let gauge_width = rect.width.saturating_sub(4).min(30);
+        let gauge_pos = Rect {
+            x: layout[1].x + (layout[1].width - gauge_width) / 2,
+            width: gauge_width,
+            ..layout[1]
+        };
+
+        frame.render_widget(gauge, gauge_pos);
+    }
+
+    fn draw_activity<B>(
+        &mut self,
+        state: &UIState,
+        series: &Series,
+        rect: Rect,
+        frame: &mut Frame<B>,
+    ) where
+        B: Backend,
+    {
+        let layout = SimpleLayout::new(Direction::Vertical).split(
+            rect,
+            [BasicConstraint::Length(1), BasicConstraint::Length(2)],
+        );
+
+        let header = format!("Activity (Last {} Days)", Activity::DAYS);
+        let header_widget = SimpleText::new(text::bold(header)).alignment(Alignment::Center);
+        frame.render_widget(header_widget, layout[0]);
+
+        let counts = self.recent_activity(state, series);
+
+        // One column is drawn for each day, so the sparkline is centered at that width
+        let width = (counts.len() as u16).min(layout[1].width);
+        let pos = Rect {
+            x: layout[1].x + (layout[1].width - width) / 2,
+            width,
+            ..layout[1]
+        };
+
+        frame.render_widget(widget_util::activity_sparkline(counts), pos);
+    }
+
     fn draw_status_text<B: Backend>(&self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
         let progress_remaining_secs = self.progress_remaining_secs.load(Ordering::SeqCst);
 
@@ -379,7 +560,7 @@ impl InfoPanel {
             let fragments = [
                 Fragment::span(text::bold("Logging In As ")),
                 Fragment::Span(
-                    text::bold_with(username, |s| s.fg(Color::Blue)),
+                    text::bold_with(username, |s| s.fg(theme::get().info)),
                     SpanOptions::new().overflow(OverflowMode::Truncate),
                 ),
             ];
@@ -389,20 +570,33 @@ impl InfoPanel {
         }
     }
 
-    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
-        let info_block = block::with_borders("Info");
+    pub fn draw<B: Backend>(
+        &mut self,
+        state: &UIState,
+        covers: &mut Covers,
+        rect: Rect,
+        frame: &mut Frame<B>,
+    ) {
+        let info_block = theme::block("Info");
         frame.render_widget(info_block, rect);
 
-        if state.users.get().is_empty() {
-            return Self::draw_no_users_info(rect, frame);
-        }
-
         match state.series.selected() {
             Some(LoadedSeries::Complete(series)) => {
-                self.draw_series_info(state, series, rect, frame)
+                self.draw_series_info(state, series, covers, rect, frame)
+            }
+            Some(LoadedSeries::Partial(_, err @ EpisodeScanError::FolderMoved { .. })) => {
+                let err = format!(
+                    "{}\npress {} to relink the series to it",
+                    err, state.config.tui.keys.relink_series
+                );
+
+                Self::draw_series_error(err, rect, frame)
             }
             Some(LoadedSeries::Partial(_, err)) => Self::draw_series_error(err, rect, frame),
             Some(LoadedSeries::None(_, err)) => Self::draw_series_error(err, rect, frame),
+            // The selected series is always scanned, so this is only seen for a moment
+            Some(LoadedSeries::Unscanned(_)) => (),
+            None if state.users.get().is_empty() => Self::draw_no_users_info(rect, frame),
             None => Self::draw_no_series_found(rect, frame),
         }
     }
@@ -414,3 +608,16 @@ impl Component for InfoPanel {
 
     fn process_key(&mut self, _: crate::key::Key, _: &mut Self::State) -> Self::KeyResult {}
 }
+
+/// The number of episodes of a series watched on each recent day.
+struct Activity {
+    series_id: i32,
+    /// The number of watched episodes of the series when the activity was loaded.
+    watched: i16,
+    counts: Vec<u64>,
+}
+
+impl Activity {
+    /// How many days of activity to show.
+    const DAYS: usize = 30;
+}
@@ -0,0 +1,277 @@
+use super::ShouldReset;
+use crate::clipboard;
+use crate::logging::StatusEvent;
+use crate::tui::component::prompt::log::LogKind;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use crossterm::event::KeyCode;
+use std::fmt::Write;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappingIndex,
+    widgets::{Fragment, SimpleTable, SimpleText, TextFragments},
+    wrap,
+};
+
+/// Lists recent log entries with the full context of their errors, so they can be read in full or copied into a bug report.
+pub struct LogPanel {
+    records: Vec<LogRecord>,
+    selected: WrappingIndex,
+}
+
+impl LogPanel {
+    pub fn init(state: &UIState) -> Self {
+        let records = LogRecord::from_events(state.log.events().lock().iter());
+        let selected = state.list_positions.get(&SavedList::Log, records.len());
+
+        Self {
+            records,
+            selected: WrappingIndex::new(selected),
+        }
+    }
+
+    /// Remember the selected entry so it can be restored the next time the panel is opened.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        positions.save(SavedList::Log, self.selected.get());
+    }
+
+    fn copy_selected(&self, state: &mut UIState) {
+        let record = match self.records.get(self.selected.get()) {
+            Some(record) => record,
+            None => return,
+        };
+
+        match clipboard::copy(&record.report()) {
+            Ok(()) => state
+                .log
+                .push(LogKind::Info, "copied log entry to clipboard"),
+            Err(err) => state
+                .log
+                .push_error(&err.context("failed to copy log entry")),
+        }
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Log");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        if self.records.is_empty() {
+            let text = text::hint("Nothing has been logged yet");
+            let widget = SimpleText::new(text).alignment(Alignment::Center);
+            frame.render_widget(widget, block_area.lines_from_top(1));
+            return;
+        }
+
+        let layout = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                BasicConstraint::Percentage(50),
+                BasicConstraint::Percentage(50),
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let rows = self.records.iter().map(|record| {
+            [
+                Span::raw(record.time.as_str()),
+                record.kind.into(),
+                Span::raw(record.message.as_str()),
+            ]
+        });
+
+        let header = [Span::raw("Time"), Span::raw("Kind"), Span::raw("Message")];
+
+        let widths = [
+            BasicConstraint::Length(9),
+            BasicConstraint::Length(7),
+            BasicConstraint::Percentage(100),
+        ];
+
+        let widget = SimpleTable::new(rows, widths)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(self.selected.get() as u16));
+
+        frame.render_widget(widget, layout[0]);
+
+        if let Some(record) = self.records.get(self.selected.get()) {
+            let details = theme::block("Details");
+            let details_area = details.inner(layout[1]);
+
+            frame.render_widget(details, layout[1]);
+
+            let fragments = wrap::by_letters(record.fragments(), details_area.width);
+            frame.render_widget(TextFragments::new(&fragments), details_area);
+        }
+
+        let hint = text::hint("C - Copy Selected Entry | Esc - Exit");
+        let hint_widget = SimpleText::new(hint).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, layout[2]);
+    }
+}
+
+impl Component for LogPanel {
+    type State = UIState;
+    type KeyResult = ShouldReset;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => ShouldReset::Yes,
+            KeyCode::Up => {
+                self.selected.decrement(self.records.len());
+                ShouldReset::No
+            }
+            KeyCode::Down => {
+                self.selected.increment(self.records.len());
+                ShouldReset::No
+            }
+            KeyCode::Char('c') => {
+                self.copy_selected(state);
+                ShouldReset::No
+            }
+            _ => ShouldReset::No,
+        }
+    }
+}
+
+/// A message from the log, along with the context of its error if it's one.
+struct LogRecord {
+    time: String,
+    date: String,
+    kind: LogKind,
+    message: String,
+    causes: Vec<String>,
+}
+
+impl LogRecord {
+    fn new(event: &StatusEvent) -> Self {
+        Self {
+            time: event.time.format("%H:%M:%S").to_string(),
+            date: event.time.format("%Y-%m-%d").to_string(),
+            kind: LogKind::from_event(event),
+            message: event.message.clone(),
+            causes: Vec::new(),
+        }
+    }
+
+    /// Returns a record for every message in `events`, from newest to oldest.
+    ///
+    /// Errors are followed by an event for each cause in their chain, so those are joined with the error they belong to.
+    fn from_events<'a, I>(events: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = &'a StatusEvent>,
+    {
+        let mut records: Vec<Self> = Vec::new();
+
+        for event in events {
+            match (LogKind::from_event(event), records.last_mut()) {
+                (LogKind::Context, Some(last)) if !matches!(last.kind, LogKind::Info) => {
+                    last.causes.push(event.message.clone());
+                }
+                _ => records.push(Self::new(event)),
+            }
+        }
+
+        records.reverse();
+        records
+    }
+
+    fn fragments(&self) -> Vec<Fragment<'_>> {
+        let mut fragments = vec![
+            Fragment::span(self.kind),
+            Fragment::span(Span::raw(self.message.as_str())),
+        ];
+
+        for cause in &self.causes {
+            fragments.push(Fragment::Line);
+            fragments.push(Fragment::span(LogKind::Context));
+            fragments.push(Fragment::span(Span::raw(cause.as_str())));
+        }
+
+        fragments
+    }
+
+    /// Returns the record as plain text, in a form that's suitable for a bug report.
+    fn report(&self) -> String {
+        let mut report = format!("[{} {}] {}", self.date, self.time, self.message);
+
+        for cause in &self.causes {
+            write!(report, "\ncaused by: {}", cause).ok();
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+    use tracing::Level;
+
+    fn event(level: Level, is_context: bool, message: &str) -> StatusEvent {
+        StatusEvent {
+            level,
+            is_context,
+            message: message.into(),
+            time: Local::now(),
+        }
+    }
+
+    #[test]
+    fn errors_are_joined_with_their_causes() {
+        let events = [
+            event(Level::INFO, false, "synced"),
+            event(Level::ERROR, false, "failed to sync"),
+            event(Level::ERROR, true, "sending request"),
+            event(Level::ERROR, true, "connection refused"),
+            event(Level::ERROR, false, "no series selected"),
+        ];
+
+        let records = LogRecord::from_events(&events);
+        assert_eq!(records.len(), 3);
+
+        assert_eq!(records[0].message, "no series selected");
+        assert!(records[0].causes.is_empty());
+
+        assert_eq!(records[1].message, "failed to sync");
+        assert_eq!(records[1].causes, ["sending request", "connection refused"]);
+
+        assert_eq!(records[2].message, "synced");
+    }
+
+    #[test]
+    fn causes_without_an_error_are_kept() {
+        let events = [
+            event(Level::ERROR, true, "connection refused"),
+            event(Level::INFO, false, "synced"),
+            event(Level::ERROR, true, "stray cause"),
+        ];
+
+        let records = LogRecord::from_events(&events);
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|record| record.causes.is_empty()));
+    }
+
+    #[test]
+    fn report_includes_every_cause() {
+        let events = [
+            event(Level::ERROR, false, "failed to sync"),
+            event(Level::ERROR, true, "connection refused"),
+        ];
+
+        let records = LogRecord::from_events(&events);
+        let report = records[0].report();
+
+        assert!(report.ends_with("] failed to sync\ncaused by: connection refused"));
+    }
+}
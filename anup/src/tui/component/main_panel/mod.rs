@@ -1,32 +1,78 @@
 mod add_series;
+mod batch_add;
+mod browse_series;
+mod clean;
+mod confirm_offset;
+mod confirm_retry;
+mod confirm_sequel;
+mod confirm_watch;
+mod cover;
 mod delete_series;
+mod downloads;
+mod episodes;
+mod history;
 mod info;
+mod log_panel;
+mod recommendations;
+mod relations;
+mod rename;
+mod search;
+mod seasonal;
 mod select_series;
 mod split_series;
+mod stats;
+mod sync_conflicts;
 mod user_panel;
 
 use super::Component;
-use crate::series::info::InfoResult;
-use crate::try_opt_r;
+use crate::series::clean::MissingSeries;
+use crate::series::info::{InfoResult, SeriesInfo};
+use crate::series::rename::Template;
+use crate::series::{self, SeriesPath, WatchStart};
 use crate::tui::state::{InputState, UIState};
 use crate::{key::Key, series::config::SeriesConfig};
 use crate::{series::SeriesParams, tui::state::SharedState};
+use crate::{try_opt_r, try_opt_ret};
 use add_series::{AddSeriesPanel, AddSeriesResult};
-use anime::local::SortedEpisodes;
-use anime::remote::RemoteService;
+use anime::local::{EpisodeParser, SortedEpisodes};
+use anime::remote::anilist::AniList;
+use anime::remote::{Remote, RemoteService};
 use anyhow::{anyhow, Result};
+use batch_add::BatchAddPanel;
+use browse_series::{BrowseSeriesPanel, BrowseSeriesResult};
+use clean::CleanPanel;
+use confirm_offset::{ConfirmOffsetPanel, ConfirmOffsetResult};
+use confirm_retry::{ConfirmRetryPanel, ConfirmRetryResult};
+use confirm_sequel::{ConfirmSequelPanel, ConfirmSequelResult};
+use confirm_watch::{ConfirmWatchPanel, ConfirmWatchResult};
+use cover::Covers;
 use delete_series::DeleteSeriesPanel;
+use downloads::DownloadsPanel;
+use episodes::{EpisodesPanel, EpisodesResult};
+use history::HistoryPanel;
 use info::InfoPanel;
+use log_panel::LogPanel;
+use recommendations::{RecommendationsPanel, RecommendationsResult};
+use relations::{RelationsPanel, RelationsResult};
+use rename::RenamePanel;
+use search::SearchPanel;
+use seasonal::{SeasonalPanel, SeasonalResult};
 use select_series::{SelectSeriesPanel, SelectSeriesResult, SelectState};
 use split_series::{SplitPanelResult, SplitSeriesPanel};
+use stats::StatsPanel;
+use std::io::Write;
 use std::mem;
+use sync_conflicts::SyncConflictsPanel;
 use tui::backend::Backend;
 use tui::layout::Rect;
 use tui::terminal::Frame;
 use user_panel::UserPanel;
 
+pub use split_series::{find_merged_seasons, relink_merged_seasons, MergedBase, RelinkedSeason};
+
 pub struct MainPanel {
     current: Panel,
+    covers: Covers,
     state: SharedState,
 }
 
@@ -34,6 +80,7 @@ impl MainPanel {
     pub fn new(state: SharedState) -> Self {
         Self {
             current: Panel::info(&state),
+            covers: Covers::new(&state),
             state,
         }
     }
@@ -55,6 +102,19 @@ impl MainPanel {
         Ok(())
     }
 
+    pub fn switch_to_browse_series(&mut self, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in()?;
+
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to browse series"));
+        }
+
+        self.current = Panel::browse_series(&self.state);
+        state.input_state = InputState::FocusedOnMainPanel;
+
+        Ok(())
+    }
+
     pub fn switch_to_update_series(&mut self, state: &mut UIState) -> Result<()> {
         self.current = Panel::update_series(state, &self.state)?;
         state.input_state = InputState::FocusedOnMainPanel;
@@ -67,13 +127,52 @@ impl MainPanel {
         Ok(())
     }
 
+    pub fn switch_to_confirm_watch(&mut self, state: &mut UIState) -> Result<()> {
+        self.current = Panel::confirm_watch(state)?;
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_episodes(&mut self, state: &mut UIState) -> Result<()> {
+        self.current = Panel::episodes(state)?;
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_history(&mut self, state: &mut UIState) -> Result<()> {
+        self.current = Panel::history(state)?;
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_log(&mut self, state: &mut UIState) {
+        self.current = Panel::log(state);
+        state.input_state = InputState::FocusedOnMainPanel;
+    }
+
+    pub fn switch_to_stats(&mut self, state: &mut UIState) -> Result<()> {
+        self.current = Panel::stats(state)?;
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_downloads(&mut self, state: &mut UIState) {
+        self.current = Panel::downloads(state);
+        state.input_state = InputState::FocusedOnMainPanel;
+    }
+
+    pub fn switch_to_search(&mut self, state: &mut UIState) {
+        self.current = Panel::search(state);
+        state.input_state = InputState::FocusedOnMainPanel;
+    }
+
     fn switch_to_select_series(&mut self, select: SelectState, state: &mut UIState) {
-        self.current = Panel::select_series(select);
+        self.current = Panel::select_series(select, state);
         state.input_state = InputState::FocusedOnMainPanel;
     }
 
     pub fn switch_to_user_panel(&mut self, state: &mut UIState) {
-        self.current = Panel::user(self.state.clone());
+        self.current = Panel::user(state, self.state.clone());
         state.input_state = InputState::FocusedOnMainPanel;
     }
 
@@ -91,13 +190,79 @@ impl MainPanel {
         Ok(())
     }
 
+    /// Open the panel for adding every folder in the series directory that no series has been added for.
+    pub fn switch_to_batch_add(&mut self, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in()?;
+
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to add series"));
+        }
+
+        self.current = Panel::BatchAdd(BatchAddPanel::new(&self.state, &state.list_positions));
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_relations(&mut self, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in()?;
+
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to view relations"));
+        }
+
+        self.current = Panel::relations(state, &self.state)?;
+        state.input_state = InputState::FocusedOnMainPanel;
+
+        Ok(())
+    }
+
+    pub fn switch_to_seasonal(&mut self, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in()?;
+
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to browse seasons"));
+        }
+
+        self.current = Panel::Seasonal(SeasonalPanel::init(state, &self.state)?);
+        state.input_state = InputState::FocusedOnMainPanel;
+
+        Ok(())
+    }
+
+    pub fn switch_to_recommendations(&mut self, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in()?;
+
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to get recommendations"));
+        }
+
+        self.current = Panel::Recommendations(RecommendationsPanel::init(state, &self.state)?);
+        state.input_state = InputState::FocusedOnMainPanel;
+
+        Ok(())
+    }
+
+    pub fn switch_to_rename(&mut self, template: Template, state: &mut UIState) -> Result<()> {
+        self.current = Panel::rename(&template, state)?;
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    /// Open the panel for resolving series whose folders no longer exist.
+    pub fn switch_to_clean(&mut self, missing: Vec<MissingSeries>, state: &mut UIState) {
+        self.current = Panel::Clean(CleanPanel::new(missing, &state.list_positions));
+        state.input_state = InputState::FocusedOnMainPanel;
+    }
+
     fn add_partial_series(&mut self, series: PartialSeries, state: &mut UIState) -> Result<()> {
         match series.info {
             InfoResult::Confident(info) => {
+                let remote = state.remote.get_logged_in_shared()?;
                 self.reset(state);
 
                 let config = SeriesConfig::new(info.id, series.params, &state.db)?;
-                state.add_series(config, info, series.episodes)?;
+                self.state
+                    .add_series_async(remote, config, info, series.episodes);
 
                 Ok(())
             }
@@ -109,21 +274,166 @@ impl MainPanel {
         }
     }
 
+    fn add_planned_series(&mut self, info: SeriesInfo, state: &mut UIState) -> Result<()> {
+        let nickname = series::generate_nickname(&info.title_romaji)
+            .ok_or_else(|| anyhow!("failed to generate a nickname for {}", info.title_romaji))?;
+
+        let params = SeriesParams::new(nickname, SeriesPath::unset(), EpisodeParser::default());
+        let config = SeriesConfig::new(info.id, params, &state.db)?;
+        let remote = state.remote.get_logged_in_shared()?;
+
+        self.reset(state);
+        self.state.add_planned_series_async(remote, config, info);
+
+        Ok(())
+    }
+
+    /// Finish adding the series looked up by the add series panel, if there is one.
+    pub fn add_looked_up_series(&mut self, state: &mut UIState) -> Result<()> {
+        let found = match &mut self.current {
+            Panel::AddSeries(add) => add.take_finished_lookup(),
+            _ => None,
+        };
+
+        match found {
+            Some(series) => self.add_partial_series(series?, state),
+            None => Ok(()),
+        }
+    }
+
+    /// Open the user panel to log back in as the user whose login was rejected by their remote service, if there is one.
+    ///
+    /// This waits until nothing else has focus so it doesn't interrupt the user.
+    pub fn reopen_expired_login(&mut self, state: &mut UIState) -> Result<()> {
+        if state.input_state != InputState::Idle || !matches!(self.current, Panel::Info(_)) {
+            return Ok(());
+        }
+
+        let user = match state.expired_login.take() {
+            Some(user) => user,
+            None => return Ok(()),
+        };
+
+        self.switch_to_user_panel(state);
+
+        match &mut self.current {
+            Panel::User(panel) => panel.renew_login(&user, state),
+            _ => Ok(()),
+        }
+    }
+
+    /// Open the panel for resolving list entries whose offline changes conflict with changes made on the remote service,
+    /// if there are any.
+    ///
+    /// Like [`Self::reopen_expired_login`], this waits until nothing else has focus.
+    pub fn open_sync_conflicts(&mut self, state: &mut UIState) {
+        if state.input_state != InputState::Idle
+            || !matches!(self.current, Panel::Info(_))
+            || state.sync_conflicts.is_empty()
+        {
+            return;
+        }
+
+        self.current = Panel::SyncConflicts(SyncConflictsPanel::init(state));
+        state.input_state = InputState::FocusedOnMainPanel;
+    }
+
+    /// Open the panel offering to continue with the sequel of a series that was just completed, if there is one.
+    ///
+    /// Like [`Self::reopen_expired_login`], this waits until nothing else has focus.
+    pub fn open_sequel_prompt(&mut self, state: &mut UIState) {
+        if state.input_state != InputState::Idle || !matches!(self.current, Panel::Info(_)) {
+            return;
+        }
+
+        let prompt = try_opt_ret!(state.sequel_prompt.take());
+
+        self.current = Panel::ConfirmSequel(ConfirmSequelPanel::new(prompt));
+        state.input_state = InputState::FocusedOnMainPanel;
+    }
+
+    /// Open the panel offering to play an episode again after its player crashed, if there is one.
+    ///
+    /// Like [`Self::reopen_expired_login`], this waits until nothing else has focus.
+    pub fn open_retry_prompt(&mut self, state: &mut UIState) {
+        if state.input_state != InputState::Idle || !matches!(self.current, Panel::Info(_)) {
+            return;
+        }
+
+        let prompt = try_opt_ret!(state.retry_prompt.take());
+
+        self.current = Panel::ConfirmRetry(ConfirmRetryPanel::new(prompt));
+        state.input_state = InputState::FocusedOnMainPanel;
+    }
+
+    /// Open the panel offering to shift the episodes of a series that numbers its creditless opening as the first episode, if there is one.
+    ///
+    /// Like [`Self::reopen_expired_login`], this waits until nothing else has focus.
+    pub fn open_offset_prompt(&mut self, state: &mut UIState) {
+        if state.input_state != InputState::Idle || !matches!(self.current, Panel::Info(_)) {
+            return;
+        }
+
+        let prompt = try_opt_ret!(state.offset_prompt.take());
+
+        self.current = Panel::ConfirmOffset(ConfirmOffsetPanel::new(prompt));
+        state.input_state = InputState::FocusedOnMainPanel;
+    }
+
     fn reset(&mut self, state: &mut UIState) {
+        self.current.save_list_position(state);
         self.current = self.default_panel();
+        state.series_filter = None;
         state.input_state.reset();
     }
 
     pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        self.covers.clear_wanted();
+
         match &mut self.current {
-            Panel::Info(info) => info.draw(state, rect, frame),
+            Panel::Info(info) => info.draw(state, &mut self.covers, rect, frame),
             Panel::AddSeries(add) => add.draw(rect, frame),
-            Panel::SelectSeries(panel) => panel.draw(rect, frame),
+            Panel::BrowseSeries(panel) => panel.draw(state, rect, frame),
+            Panel::SelectSeries(panel) => panel.draw(state, rect, frame),
             Panel::DeleteSeries(panel) => panel.draw(rect, frame),
+            Panel::ConfirmWatch(panel) => panel.draw(rect, frame),
+            Panel::ConfirmSequel(panel) => panel.draw(rect, frame),
+            Panel::ConfirmRetry(panel) => panel.draw(rect, frame),
+            Panel::ConfirmOffset(panel) => panel.draw(rect, frame),
+            Panel::Episodes(panel) => panel.draw(rect, frame),
+            Panel::History(panel) => panel.draw(rect, frame),
+            Panel::Log(panel) => panel.draw(rect, frame),
+            Panel::Stats(panel) => panel.draw(rect, frame),
+            Panel::Downloads(panel) => panel.draw(state, rect, frame),
+            Panel::Search(panel) => panel.draw(state, rect, frame),
             Panel::User(user) => user.draw(state, rect, frame),
             Panel::SplitSeries(split) => split.draw(rect, frame),
+            Panel::Relations(panel) => panel.draw(state, rect, frame),
+            Panel::Seasonal(panel) => panel.draw(state, rect, frame),
+            Panel::Recommendations(panel) => panel.draw(state, rect, frame),
+            Panel::Rename(panel) => panel.draw(rect, frame),
+            Panel::Clean(panel) => panel.draw(rect, frame),
+            Panel::SyncConflicts(panel) => panel.draw(rect, frame),
+            Panel::BatchAdd(panel) => panel.draw(state, rect, frame),
         }
     }
+
+    /// Returns true if the terminal has to be cleared and drawn again to erase the cover of the last frame.
+    pub fn cover_needs_clear(&self) -> bool {
+        self.covers.needs_clear()
+    }
+
+    /// Draw the cover reserved by the last frame to `out`.
+    ///
+    /// This must be called after the frame has been drawn to the terminal, as the cover is drawn over it.
+    pub fn draw_cover<W: Write>(&mut self, out: &mut W) -> Result<()> {
+        self.covers.draw(out)
+    }
+
+    /// Draw the cover again on the next frame, which should be done whenever the terminal is cleared.
+    pub fn redraw_cover(&mut self) {
+        self.covers.forget_drawn();
+    }
 }
 
 impl Component for MainPanel {
@@ -139,11 +449,21 @@ impl Component for MainPanel {
                     self.reset(state);
                     Ok(())
                 }
-                Ok(AddSeriesResult::AddSeries(partial)) => self.add_partial_series(*partial, state),
                 Ok(AddSeriesResult::UpdateSeries(params)) => {
                     let selected = try_opt_r!(state.series.selected_mut());
-                    let remote = state.remote.get_logged_in()?;
+                    let id = selected.config().id;
 
+                    // A new ID means the series has to be looked up again, which shouldn't block the UI
+                    if params.changes_id(id) {
+                        let remote = state.remote.get_logged_in_shared()?;
+
+                        self.reset(state);
+                        self.state.update_series_async(id, *params, remote);
+
+                        return Ok(());
+                    }
+
+                    let remote = state.remote.get_logged_in()?;
                     selected.update(*params, &state.config, &state.db, remote)?;
 
                     self.reset(state);
@@ -151,9 +471,19 @@ impl Component for MainPanel {
                 }
                 Err(err) => Err(err),
             },
+            Panel::BrowseSeries(panel) => match panel.process_key(key, state) {
+                Ok(BrowseSeriesResult::Ok) => Ok(()),
+                Ok(BrowseSeriesResult::AddSeries(info)) => self.add_planned_series(info, state),
+                Ok(BrowseSeriesResult::Reset) => {
+                    self.reset(state);
+                    Ok(())
+                }
+                Err(err) => Err(err),
+            },
             Panel::SelectSeries(panel) => match panel.process_key(key, &mut ()) {
                 SelectSeriesResult::Ok => Ok(()),
                 SelectSeriesResult::AddSeries(info) => {
+                    panel.save_list_position(&mut state.list_positions);
                     let default_panel = self.default_panel();
 
                     let params = match mem::replace(&mut self.current, default_panel) {
@@ -179,6 +509,129 @@ impl Component for MainPanel {
                 Ok(ShouldReset::No) => Ok(()),
                 Err(err) => Err(err),
             },
+            Panel::ConfirmWatch(panel) => match panel.process_key(key, &mut ()) {
+                ConfirmWatchResult::Ok => Ok(()),
+                ConfirmWatchResult::Reset => {
+                    self.reset(state);
+                    Ok(())
+                }
+                ConfirmWatchResult::Play(start) => {
+                    self.reset(state);
+                    state.play_next_series_episode(start, &self.state)
+                }
+            },
+            Panel::ConfirmSequel(panel) => match panel.process_key(key, &mut ()) {
+                ConfirmSequelResult::Ok => Ok(()),
+                ConfirmSequelResult::Reset => {
+                    self.reset(state);
+                    Ok(())
+                }
+                ConfirmSequelResult::Continue => {
+                    let default_panel = self.default_panel();
+
+                    let panel = match mem::replace(&mut self.current, default_panel) {
+                        Panel::ConfirmSequel(panel) => panel,
+                        _ => unreachable!(),
+                    };
+
+                    self.reset(state);
+                    state.continue_with_sequel(panel.prompt())
+                }
+            },
+            Panel::ConfirmRetry(panel) => match panel.process_key(key, &mut ()) {
+                ConfirmRetryResult::Ok => Ok(()),
+                ConfirmRetryResult::Reset => {
+                    self.reset(state);
+                    Ok(())
+                }
+                result @ (ConfirmRetryResult::Retry | ConfirmRetryResult::Fallback) => {
+                    let default_panel = self.default_panel();
+
+                    let panel = match mem::replace(&mut self.current, default_panel) {
+                        Panel::ConfirmRetry(panel) => panel,
+                        _ => unreachable!(),
+                    };
+
+                    let use_fallback = matches!(result, ConfirmRetryResult::Fallback);
+
+                    self.reset(state);
+                    state.retry_episode(panel.prompt(), use_fallback, &self.state)
+                }
+            },
+            Panel::ConfirmOffset(panel) => match panel.process_key(key, &mut ()) {
+                ConfirmOffsetResult::Ok => Ok(()),
+                ConfirmOffsetResult::Reset => {
+                    self.reset(state);
+                    Ok(())
+                }
+                ConfirmOffsetResult::Apply => {
+                    let default_panel = self.default_panel();
+
+                    let panel = match mem::replace(&mut self.current, default_panel) {
+                        Panel::ConfirmOffset(panel) => panel,
+                        _ => unreachable!(),
+                    };
+
+                    self.reset(state);
+                    state.apply_opening_offset(panel.prompt())
+                }
+            },
+            Panel::Episodes(panel) => match panel.process_key(key, state) {
+                Ok(EpisodesResult::Ok) => Ok(()),
+                Ok(EpisodesResult::Reset) => {
+                    self.reset(state);
+                    Ok(())
+                }
+                Ok(EpisodesResult::PlayNext) => {
+                    self.reset(state);
+
+                    if state.selected_needs_watch_confirmation() {
+                        self.switch_to_confirm_watch(state)
+                    } else {
+                        state.play_next_series_episode(WatchStart::UpdateStatus, &self.state)
+                    }
+                }
+                Ok(EpisodesResult::Play(episode)) => {
+                    self.reset(state);
+                    state.play_series_episode(episode, &self.state)
+                }
+                Err(err) => Err(err),
+            },
+            Panel::History(panel) => {
+                if let ShouldReset::Yes = panel.process_key(key, state) {
+                    self.reset(state);
+                }
+
+                Ok(())
+            }
+            Panel::Log(panel) => {
+                if let ShouldReset::Yes = panel.process_key(key, state) {
+                    self.reset(state);
+                }
+
+                Ok(())
+            }
+            Panel::Stats(panel) => {
+                if let ShouldReset::Yes = panel.process_key(key, state) {
+                    self.reset(state);
+                }
+
+                Ok(())
+            }
+            Panel::Downloads(panel) => {
+                if let ShouldReset::Yes = panel.process_key(key, state) {
+                    self.reset(state);
+                }
+
+                Ok(())
+            }
+            Panel::Search(panel) => {
+                if let ShouldReset::Yes = panel.process_key(key, state) {
+                    self.reset(state);
+                }
+
+                Ok(())
+            }
             Panel::User(user) => match user.process_key(key, state) {
                 Ok(ShouldReset::Yes) => {
                     self.reset(state);
@@ -194,21 +647,111 @@ impl Component for MainPanel {
                     Ok(())
                 }
                 Ok(SplitPanelResult::AddSeries(info, cfg)) => {
-                    state.add_series(*cfg, (*info).into(), None)
+                    let remote = state.remote.get_logged_in_shared()?;
+                    self.state
+                        .add_series_async(remote, *cfg, (*info).into(), None);
+
+                    Ok(())
+                }
+                Err(err) => Err(err),
+            },
+            Panel::Relations(panel) => match panel.process_key(key, state) {
+                Ok(RelationsResult::Ok) => Ok(()),
+                Ok(RelationsResult::AddSeries(info)) => self.add_planned_series(info, state),
+                Ok(RelationsResult::Reset) => {
+                    self.reset(state);
+                    Ok(())
+                }
+                Err(err) => Err(err),
+            },
+            Panel::Seasonal(panel) => match panel.process_key(key, state) {
+                Ok(SeasonalResult::Ok) => Ok(()),
+                Ok(SeasonalResult::AddSeries(info)) => self.add_planned_series(info, state),
+                Ok(SeasonalResult::Reset) => {
+                    self.reset(state);
+                    Ok(())
+                }
+                Err(err) => Err(err),
+            },
+            Panel::Recommendations(panel) => match panel.process_key(key, state) {
+                Ok(RecommendationsResult::Ok) => Ok(()),
+                Ok(RecommendationsResult::AddSeries(info)) => self.add_planned_series(info, state),
+                Ok(RecommendationsResult::Reset) => {
+                    self.reset(state);
+                    Ok(())
+                }
+                Err(err) => Err(err),
+            },
+            Panel::Rename(panel) => match panel.process_key(key, state) {
+                Ok(ShouldReset::Yes) => {
+                    self.reset(state);
+                    Ok(())
+                }
+                Ok(ShouldReset::No) => Ok(()),
+                Err(err) => Err(err),
+            },
+            Panel::Clean(panel) => match panel.process_key(key, state) {
+                Ok(ShouldReset::Yes) => {
+                    self.reset(state);
+                    Ok(())
+                }
+                Ok(ShouldReset::No) => Ok(()),
+                Err(err) => Err(err),
+            },
+            Panel::SyncConflicts(panel) => match panel.process_key(key, state) {
+                Ok(ShouldReset::Yes) => {
+                    self.reset(state);
+                    Ok(())
                 }
+                Ok(ShouldReset::No) => Ok(()),
+                Err(err) => Err(err),
+            },
+            Panel::BatchAdd(panel) => match panel.process_key(key, state) {
+                Ok(ShouldReset::Yes) => {
+                    self.reset(state);
+                    Ok(())
+                }
+                Ok(ShouldReset::No) => Ok(()),
                 Err(err) => Err(err),
             },
         }
     }
 }
 
+/// Returns the AniList connection of `remote`, if AniList is the main service.
+fn anilist(remote: &Remote) -> Option<&AniList> {
+    match remote {
+        Remote::AniList(anilist) => Some(anilist),
+        Remote::MultiRemote(multi) => anilist(multi.primary()),
+        _ => None,
+    }
+}
+
 enum Panel {
     Info(InfoPanel),
     AddSeries(Box<AddSeriesPanel>),
+    BrowseSeries(BrowseSeriesPanel),
     SelectSeries(SelectSeriesPanel),
     DeleteSeries(DeleteSeriesPanel),
+    ConfirmWatch(ConfirmWatchPanel),
+    ConfirmSequel(ConfirmSequelPanel),
+    ConfirmRetry(ConfirmRetryPanel),
+    ConfirmOffset(ConfirmOffsetPanel),
+    Episodes(EpisodesPanel),
+    History(HistoryPanel),
+    Log(LogPanel),
+    Stats(StatsPanel),
+    Downloads(DownloadsPanel),
+    Search(SearchPanel),
     User(UserPanel),
     SplitSeries(SplitSeriesPanel),
+    Relations(RelationsPanel),
+    Seasonal(SeasonalPanel),
+    Recommendations(RecommendationsPanel),
+    Rename(RenamePanel),
+    Clean(CleanPanel),
+    SyncConflicts(SyncConflictsPanel),
+    BatchAdd(BatchAddPanel),
 }
 
 impl Panel {
@@ -223,6 +766,11 @@ impl Panel {
         Ok(Self::AddSeries(panel.into()))
     }
 
+    #[inline(always)]
+    fn browse_series(state: &SharedState) -> Self {
+        Self::BrowseSeries(BrowseSeriesPanel::new(state))
+    }
+
     fn update_series(state: &UIState, shared_state: &SharedState) -> Result<Self> {
         use add_series::Mode;
         let panel = AddSeriesPanel::init(state, shared_state, Mode::UpdateSeries)?;
@@ -234,18 +782,96 @@ impl Panel {
         Ok(Self::DeleteSeries(panel))
     }
 
-    fn select_series(select: SelectState) -> Self {
-        Self::SelectSeries(SelectSeriesPanel::new(select))
+    fn confirm_watch(state: &UIState) -> Result<Self> {
+        let panel = ConfirmWatchPanel::init(state)?;
+        Ok(Self::ConfirmWatch(panel))
+    }
+
+    fn episodes(state: &UIState) -> Result<Self> {
+        let panel = EpisodesPanel::init(state)?;
+        Ok(Self::Episodes(panel))
+    }
+
+    fn history(state: &UIState) -> Result<Self> {
+        let panel = HistoryPanel::init(state)?;
+        Ok(Self::History(panel))
     }
 
-    fn user(state: SharedState) -> Self {
-        Self::User(UserPanel::new(state))
+    fn log(state: &UIState) -> Self {
+        Self::Log(LogPanel::init(state))
+    }
+
+    fn stats(state: &UIState) -> Result<Self> {
+        let panel = StatsPanel::init(state)?;
+        Ok(Self::Stats(panel))
+    }
+
+    #[inline(always)]
+    fn downloads(state: &UIState) -> Self {
+        Self::Downloads(DownloadsPanel::new(state))
+    }
+
+    fn search(state: &mut UIState) -> Self {
+        Self::Search(SearchPanel::new(state))
+    }
+
+    fn select_series(select: SelectState, state: &UIState) -> Self {
+        Self::SelectSeries(SelectSeriesPanel::new(select, &state.list_positions))
+    }
+
+    fn user(state: &mut UIState, shared_state: SharedState) -> Self {
+        Self::User(UserPanel::new(state, shared_state))
     }
 
     fn split_series(state: &SharedState) -> Self {
         let panel = SplitSeriesPanel::new(state);
         Self::SplitSeries(panel)
     }
+
+    fn relations(state: &UIState, shared_state: &SharedState) -> Result<Self> {
+        let panel = RelationsPanel::init(state, shared_state)?;
+        Ok(Self::Relations(panel))
+    }
+
+    fn rename(template: &Template, state: &UIState) -> Result<Self> {
+        let panel = RenamePanel::init(template, state)?;
+        Ok(Self::Rename(panel))
+    }
+
+    /// Remember the selected row of the panel's list so it's restored when the panel is opened again.
+    ///
+    /// Every panel is matched so a new panel with a list can't be added without deciding whether its position is saved.
+    fn save_list_position(&self, state: &mut UIState) {
+        let positions = &mut state.list_positions;
+
+        match self {
+            Self::Episodes(panel) => panel.save_list_position(positions),
+            Self::History(panel) => panel.save_list_position(positions),
+            Self::Log(panel) => panel.save_list_position(positions),
+            Self::Downloads(panel) => panel.save_list_position(positions),
+            Self::Search(_) => SearchPanel::save_list_position(state),
+            Self::User(panel) => panel.save_list_position(positions),
+            Self::BrowseSeries(panel) => panel.save_list_position(positions),
+            Self::SelectSeries(panel) => panel.save_list_position(positions),
+            Self::SplitSeries(panel) => panel.save_list_position(positions),
+            Self::Relations(panel) => panel.save_list_position(positions),
+            Self::Seasonal(panel) => panel.save_list_position(positions),
+            Self::Recommendations(panel) => panel.save_list_position(positions),
+            Self::Rename(panel) => panel.save_list_position(positions),
+            Self::Clean(panel) => panel.save_list_position(positions),
+            Self::SyncConflicts(panel) => panel.save_list_position(positions),
+            Self::BatchAdd(panel) => panel.save_list_position(positions),
+            // These panels don't have a list to select from
+            Self::Info(_)
+            | Self::AddSeries(_)
+            | Self::DeleteSeries(_)
+            | Self::ConfirmWatch(_)
+            | Self::ConfirmSequel(_)
+            | Self::ConfirmRetry(_)
+            | Self::ConfirmOffset(_)
+            | Self::Stats(_) => (),
+        }
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -0,0 +1,274 @@
+use super::anilist;
+use crate::key::Key;
+use crate::series::info::SeriesInfo;
+use crate::series::recommend::{self, Recommended};
+use crate::tui::component::Component;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::{SharedState, UIState};
+use crate::tui::theme;
+use crate::util::{arc_mutex, ArcMutex, ScopedTask};
+use anime::remote::SeriesID;
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use std::collections::HashSet;
+use std::mem;
+use std::sync::Arc;
+use tokio::task;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappedSelection,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// A panel that shows the series AniList users recommend for the highest scored completed series in the list.
+pub struct RecommendationsPanel {
+    series: WrappedSelection<Vec<RecommendedEntry>, RecommendedEntry>,
+    lookup: ArcMutex<Lookup>,
+    #[allow(dead_code)]
+    lookup_task: Option<ScopedTask<()>>,
+}
+
+impl RecommendationsPanel {
+    /// Create the panel and start looking up recommendations.
+    pub fn init(state: &UIState, shared_state: &SharedState) -> Result<Self> {
+        let remote = state.remote.get_logged_in_shared()?;
+
+        if anilist(&remote).is_none() {
+            return Err(anyhow!(
+                "recommendations are only available when AniList is your main service"
+            ));
+        }
+
+        let liked = recommend::liked_series(&state.series);
+
+        if liked.is_empty() {
+            return Err(anyhow!(
+                "complete a series first to get recommendations based on it"
+            ));
+        }
+
+        #[allow(clippy::cast_sign_loss)]
+        let in_list = state
+            .series
+            .iter()
+            .map(|series| series.config().id as SeriesID)
+            .collect::<HashSet<_>>();
+
+        let lookup = arc_mutex(Lookup::Pending);
+        let task_lookup = Arc::clone(&lookup);
+        let shared_state = shared_state.clone();
+
+        let task = task::spawn(async move {
+            let result = match anilist(&remote) {
+                Some(anilist) => anilist.recommendations(&liked).await,
+                None => Ok(Vec::new()),
+            };
+
+            let finished = match result {
+                Ok(recommendations) => {
+                    let merged = recommend::merge(recommendations, &in_list);
+                    Lookup::Done(merged.into_iter().map(Into::into).collect())
+                }
+                Err(err) => {
+                    let err = anyhow!(err).context("failed to get recommendations");
+                    shared_state.lock().get_mut().push_remote_error(&err);
+                    Lookup::Idle
+                }
+            };
+
+            *task_lookup.lock() = finished;
+            shared_state.lock().mark_dirty();
+        });
+
+        Ok(Self {
+            series: WrappedSelection::new(Vec::new()),
+            lookup,
+            lookup_task: Some(task.into()),
+        })
+    }
+
+    /// Show the recommendations from the lookup if it has finished.
+    ///
+    /// The selected series is restored from the last time the panel was opened.
+    fn take_finished_lookup(&mut self, positions: &ListPositions) {
+        let mut lookup = self.lookup.lock();
+
+        if let Lookup::Done(_) = &*lookup {
+            if let Lookup::Done(series) = mem::replace(&mut *lookup, Lookup::Idle) {
+                let selected = positions.get(&SavedList::Recommendations, series.len());
+
+                self.series = WrappedSelection::new(series);
+                self.series.set_selected(selected);
+            }
+        }
+    }
+
+    /// Remember the selected series so it can be restored the next time the panel is opened.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        if !self.series.is_empty() {
+            positions.save(SavedList::Recommendations, self.series.index());
+        }
+    }
+
+    #[inline(always)]
+    fn is_looking_up(&self) -> bool {
+        matches!(*self.lookup.lock(), Lookup::Pending)
+    }
+
+    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("You Might Like");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .split(
+                block_area,
+                [
+                    BasicConstraint::Length(1),
+                    BasicConstraint::MinLenRemaining(1, 1),
+                ],
+            );
+
+        self.take_finished_lookup(&state.list_positions);
+
+        let hint = if self.is_looking_up() {
+            "Looking up recommendations.."
+        } else if self.series.is_empty() {
+            "No recommendations found"
+        } else {
+            "A - add to list"
+        };
+
+        let widget = SimpleText::new(text::hint(hint)).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[0].lines_from_top(1));
+
+        if self.series.is_empty() {
+            return;
+        }
+
+        let rows = self.series.iter().map(|series| {
+            [
+                Span::raw(series.info.title(state.config.title_language)),
+                Span::raw(series.info.episodes.to_string()),
+                Span::raw(liked_names(&series.sources, state)),
+                Span::raw(series.rating.to_string()),
+            ]
+        });
+
+        let header = [
+            Span::raw("Title"),
+            Span::raw("Episodes"),
+            Span::raw("Because You Liked"),
+            Span::raw("Rating"),
+        ];
+
+        let constraints = [
+            BasicConstraint::Percentage(50),
+            BasicConstraint::Length(8),
+            BasicConstraint::Percentage(35),
+            BasicConstraint::Length(6),
+        ];
+
+        let widget = SimpleTable::new(rows, constraints)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(self.series.index() as u16));
+
+        frame.render_widget(widget, layout[1]);
+    }
+}
+
+impl Component for RecommendationsPanel {
+    type State = UIState;
+    type KeyResult = Result<RecommendationsResult>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(RecommendationsResult::Reset),
+            _ if self.is_looking_up() => Ok(RecommendationsResult::Ok),
+            KeyCode::Char('a') => {
+                self.take_finished_lookup(&state.list_positions);
+
+                let selected = match self.series.selected() {
+                    Some(selected) => selected,
+                    None => return Ok(RecommendationsResult::Ok),
+                };
+
+                if state
+                    .series
+                    .iter()
+                    .any(|series| series.config().id == selected.info.id)
+                {
+                    return Err(anyhow!(
+                        "{} is already in your list",
+                        selected.info.title_preferred
+                    ));
+                }
+
+                Ok(RecommendationsResult::AddSeries(selected.info.clone()))
+            }
+            KeyCode::Up => {
+                self.series.dec_selected();
+                Ok(RecommendationsResult::Ok)
+            }
+            KeyCode::Down => {
+                self.series.inc_selected();
+                Ok(RecommendationsResult::Ok)
+            }
+            _ => Ok(RecommendationsResult::Ok),
+        }
+    }
+}
+
+/// Returns the nickname of the first series in `sources`, along with how many others there are.
+#[allow(clippy::cast_possible_wrap)]
+fn liked_names(sources: &[SeriesID], state: &UIState) -> String {
+    let first = sources.first().and_then(|&id| {
+        state
+            .series
+            .iter()
+            .find(|series| series.config().id == id as i32)
+            .map(|series| series.config().nickname.as_str())
+    });
+
+    match (first, sources.len()) {
+        (Some(name), 1) => name.into(),
+        (Some(name), len) => format!("{} +{}", name, len - 1),
+        (None, _) => String::new(),
+    }
+}
+
+struct RecommendedEntry {
+    info: SeriesInfo,
+    sources: Vec<SeriesID>,
+    rating: i32,
+}
+
+impl From<Recommended> for RecommendedEntry {
+    fn from(rec: Recommended) -> Self {
+        Self {
+            info: rec.info.into(),
+            sources: rec.sources,
+            rating: rec.rating,
+        }
+    }
+}
+
+enum Lookup {
+    Idle,
+    Pending,
+    Done(Vec<RecommendedEntry>),
+}
+
+pub enum RecommendationsResult {
+    Ok,
+    AddSeries(SeriesInfo),
+    Reset,
+}
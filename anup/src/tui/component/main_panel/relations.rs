@@ -0,0 +1,309 @@
+use crate::key::Key;
+use crate::series::cache;
+use crate::series::info::SeriesInfo;
+use crate::tui::component::Component;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::{SharedState, UIState};
+use crate::tui::theme;
+use crate::util::{arc_mutex, ArcMutex, ScopedTask};
+use anime::remote::{RelationKind, RemoteService};
+use anyhow::{anyhow, Result};
+use chrono::Duration;
+use crossterm::event::KeyCode;
+use std::mem;
+use std::sync::Arc;
+use tokio::task;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappedSelection,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// A panel to walk through the series related to the selected one and add any of them to the list.
+pub struct RelationsPanel {
+    /// Every series whose relations were viewed to get to the current one, ending with the current one.
+    trail: Vec<SeriesInfo>,
+    relations: WrappedSelection<Vec<Related>, Related>,
+    lookup: ArcMutex<Lookup>,
+    #[allow(dead_code)]
+    lookup_task: Option<ScopedTask<()>>,
+    state: SharedState,
+}
+
+impl RelationsPanel {
+    pub fn init(state: &UIState, shared_state: &SharedState) -> Result<Self> {
+        let info = state
+            .series
+            .selected()
+            .and_then(|series| series.info())
+            .cloned()
+            .ok_or_else(|| anyhow!("must select a series with info to view its relations"))?;
+
+        let mut panel = Self {
+            trail: vec![info],
+            relations: WrappedSelection::new(Vec::new()),
+            lookup: arc_mutex(Lookup::Idle),
+            lookup_task: None,
+            state: shared_state.clone(),
+        };
+
+        panel.look_up_relations(state)?;
+        Ok(panel)
+    }
+
+    /// Look up the relations of the last series in the trail in the background.
+    fn look_up_relations(&mut self, state: &UIState) -> Result<()> {
+        let current = match self.trail.last() {
+            Some(current) => current,
+            None => return Ok(()),
+        };
+
+        let id = current.id as u32;
+        let title = current.title_preferred.clone();
+        let remote = state.remote.get_logged_in_shared()?;
+        let max_age = Duration::days(state.config.offline.cache_max_age_days.into());
+        let lookup = Arc::clone(&self.lookup);
+        let shared_state = self.state.clone();
+
+        self.relations = WrappedSelection::new(Vec::new());
+        *lookup.lock() = Lookup::Pending;
+
+        let task = task::spawn(async move {
+            let result: Result<Vec<Related>> = async {
+                let relations = remote.search_relations(id).await?;
+                let ids = relations.iter().map(|rel| rel.id).collect::<Vec<_>>();
+                let infos =
+                    cache::lookup(|| shared_state.lock_db(), &remote, &ids, max_age).await?;
+
+                let related = relations
+                    .into_iter()
+                    .filter_map(|rel| {
+                        let info = infos.get(&rel.id)?.clone();
+
+                        Some(Related {
+                            kind: rel.kind,
+                            info: info.into(),
+                        })
+                    })
+                    .collect();
+
+                Ok(related)
+            }
+            .await;
+
+            let finished = match result {
+                Ok(related) => Lookup::Done(related),
+                Err(err) => {
+                    let err = err.context(format!("failed to get the relations of {}", title));
+                    shared_state.lock().get_mut().push_remote_error(&err);
+                    Lookup::Idle
+                }
+            };
+
+            *lookup.lock() = finished;
+            shared_state.lock().mark_dirty();
+        });
+
+        self.lookup_task = Some(task.into());
+        Ok(())
+    }
+
+    /// Show the relations from the last lookup if it has finished.
+    ///
+    /// The selected relation is restored from the last time the relations of the same series were viewed.
+    fn take_finished_lookup(&mut self, positions: &ListPositions) {
+        let mut lookup = self.lookup.lock();
+
+        if let Lookup::Done(_) = &*lookup {
+            if let Lookup::Done(related) = mem::replace(&mut *lookup, Lookup::Idle) {
+                let selected = match self.trail.last() {
+                    Some(current) => {
+                        positions.get(&SavedList::Relations(current.id), related.len())
+                    }
+                    None => 0,
+                };
+
+                self.relations = WrappedSelection::new(related);
+                self.relations.set_selected(selected);
+            }
+        }
+    }
+
+    /// Remember the selected relation of the current series so it can be restored the next time its relations are viewed.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        if self.relations.is_empty() {
+            return;
+        }
+
+        if let Some(current) = self.trail.last() {
+            positions.save(SavedList::Relations(current.id), self.relations.index());
+        }
+    }
+
+    #[inline(always)]
+    fn is_looking_up(&self) -> bool {
+        matches!(*self.lookup.lock(), Lookup::Pending)
+    }
+
+    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Relations");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .split(
+                block_area,
+                [
+                    BasicConstraint::Length(1),
+                    BasicConstraint::Length(1),
+                    BasicConstraint::MinLenRemaining(1, 1),
+                ],
+            );
+
+        self.take_finished_lookup(&state.list_positions);
+
+        let trail = self
+            .trail
+            .iter()
+            .map(|info| info.title_preferred.as_str())
+            .collect::<Vec<_>>()
+            .join(" > ");
+
+        let widget = SimpleText::new(Span::raw(trail)).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[0].lines_from_top(1));
+
+        let hint = if self.is_looking_up() {
+            "Looking up relations.."
+        } else if self.relations.is_empty() {
+            "No related series found"
+        } else {
+            "Enter - view relations | Backspace - go back | A - add to list"
+        };
+
+        let widget = SimpleText::new(text::hint(hint)).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[1].lines_from_top(1));
+
+        if self.relations.is_empty() {
+            return;
+        }
+
+        let rows = self.relations.iter().map(|related| {
+            let in_list = is_in_list(related.info.id, state);
+
+            [
+                Span::raw(related.kind.as_str()),
+                Span::raw(related.info.title(state.config.title_language)),
+                Span::raw(related.info.episodes.to_string()),
+                Span::raw(if in_list { "Yes" } else { "No" }),
+            ]
+        });
+
+        let header = [
+            Span::raw("Relation"),
+            Span::raw("Title"),
+            Span::raw("Episodes"),
+            Span::raw("In List"),
+        ];
+
+        let constraints = [
+            BasicConstraint::Length(12),
+            BasicConstraint::Percentage(70),
+            BasicConstraint::Length(8),
+            BasicConstraint::Length(7),
+        ];
+
+        let widget = SimpleTable::new(rows, constraints)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(self.relations.index() as u16));
+
+        frame.render_widget(widget, layout[2]);
+    }
+}
+
+impl Component for RelationsPanel {
+    type State = UIState;
+    type KeyResult = Result<RelationsResult>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(RelationsResult::Reset),
+            _ if self.is_looking_up() => Ok(RelationsResult::Ok),
+            KeyCode::Enter => {
+                self.take_finished_lookup(&state.list_positions);
+                self.save_list_position(&mut state.list_positions);
+
+                if let Some(selected) = self.relations.selected() {
+                    self.trail.push(selected.info.clone());
+                    self.look_up_relations(state)?;
+                }
+
+                Ok(RelationsResult::Ok)
+            }
+            KeyCode::Backspace => {
+                if self.trail.len() > 1 {
+                    self.take_finished_lookup(&state.list_positions);
+                    self.save_list_position(&mut state.list_positions);
+                    self.trail.pop();
+                    self.look_up_relations(state)?;
+                }
+
+                Ok(RelationsResult::Ok)
+            }
+            KeyCode::Char('a') => {
+                self.take_finished_lookup(&state.list_positions);
+
+                let selected = match self.relations.selected() {
+                    Some(selected) => selected,
+                    None => return Ok(RelationsResult::Ok),
+                };
+
+                if is_in_list(selected.info.id, state) {
+                    return Err(anyhow!(
+                        "{} is already in your list",
+                        selected.info.title_preferred
+                    ));
+                }
+
+                Ok(RelationsResult::AddSeries(selected.info.clone()))
+            }
+            KeyCode::Up => {
+                self.relations.dec_selected();
+                Ok(RelationsResult::Ok)
+            }
+            KeyCode::Down => {
+                self.relations.inc_selected();
+                Ok(RelationsResult::Ok)
+            }
+            _ => Ok(RelationsResult::Ok),
+        }
+    }
+}
+
+fn is_in_list(id: i32, state: &UIState) -> bool {
+    state.series.iter().any(|series| series.config().id == id)
+}
+
+struct Related {
+    kind: RelationKind,
+    info: SeriesInfo,
+}
+
+enum Lookup {
+    Idle,
+    Pending,
+    Done(Vec<Related>),
+}
+
+pub enum RelationsResult {
+    Ok,
+    AddSeries(SeriesInfo),
+    Reset,
+}
@@ -0,0 +1,147 @@
+use super::ShouldReset;
+use crate::series::rename::{RenamePlan, Template};
+use crate::tui::component::prompt::log::LogKind;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, SimpleLayout},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+pub struct RenamePanel {
+    series_id: i32,
+    nickname: String,
+    plan: RenamePlan,
+    selected: WrappingIndex,
+}
+
+impl RenamePanel {
+    pub fn init(template: &Template, state: &UIState) -> Result<Self> {
+        let series = state
+            .series
+            .get_valid_sel_series()
+            .ok_or_else(|| anyhow!("must select a series with episodes to rename"))?;
+
+        // The season number is only known by the user, so it's left at the first season like the CLI default
+        let plan = RenamePlan::new(series, template, 1, &state.config.filenames)?;
+
+        if plan.is_empty() {
+            return Err(anyhow!(
+                "every episode of {} is already named correctly",
+                series.data.config.nickname
+            ));
+        }
+
+        let selected = state.list_positions.get(
+            &SavedList::Rename(series.data.config.id),
+            plan.renames.len(),
+        );
+
+        Ok(Self {
+            series_id: series.data.config.id,
+            nickname: series.data.config.nickname.clone(),
+            plan,
+            selected: WrappingIndex::new(selected),
+        })
+    }
+
+    /// Remember the selected file so it can be restored the next time the series' files are renamed.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        positions.save(SavedList::Rename(self.series_id), self.selected.get());
+    }
+
+    fn apply(&self, state: &mut UIState) -> Result<()> {
+        let series = state
+            .series
+            .get_valid_sel_series_mut()
+            .filter(|series| series.data.config.id == self.series_id)
+            .ok_or_else(|| anyhow!("{} is no longer selected", self.nickname))?;
+
+        self.plan.apply(series, &state.config, &state.db)?;
+
+        state.log.push(
+            LogKind::Info,
+            format!(
+                "renamed {} episodes of {}",
+                self.plan.renames.len(),
+                self.nickname
+            ),
+        );
+
+        Ok(())
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Rename Episodes");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                BasicConstraint::MinLenRemaining(4, 4),
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let rows = self.plan.renames.iter().map(|rename| {
+            [
+                Span::raw(rename.old_name.as_str()),
+                Span::raw(rename.new_name.as_str()),
+            ]
+        });
+
+        let header = [Span::raw("Old Name"), Span::raw("New Name")];
+
+        let widths = [
+            BasicConstraint::Percentage(50),
+            BasicConstraint::Percentage(50),
+        ];
+
+        let widget = SimpleTable::new(rows, widths)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(self.selected.get() as u16));
+
+        frame.render_widget(widget, layout[0]);
+
+        let hint = text::hint("Enter - Rename | Esc - Cancel");
+        let hint_widget = SimpleText::new(hint).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, layout[1]);
+    }
+}
+
+impl Component for RenamePanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(ShouldReset::Yes),
+            KeyCode::Up => {
+                self.selected.decrement(self.plan.renames.len());
+                Ok(ShouldReset::No)
+            }
+            KeyCode::Down => {
+                self.selected.increment(self.plan.renames.len());
+                Ok(ShouldReset::No)
+            }
+            KeyCode::Enter => {
+                self.apply(state)?;
+                Ok(ShouldReset::Yes)
+            }
+            _ => Ok(ShouldReset::No),
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use super::ShouldReset;
+use crate::series::LoadedSeries;
+use crate::tui::component::input::{Input, InputFlags};
+use crate::tui::list_positions::SavedList;
+use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use crossterm::event::KeyCode;
+use std::cmp::Ordering;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappedSelection,
+    widgets::SimpleText,
+};
+
+/// The minimum confidence a series needs to have to be shown in the search results.
+const MIN_CONFIDENCE: f32 = 0.7;
+
+pub struct SearchPanel {
+    input: Input,
+}
+
+impl SearchPanel {
+    pub fn new(state: &mut UIState) -> Self {
+        let panel = Self {
+            input: Input::new(InputFlags::SELECTED, "Name"),
+        };
+
+        panel.update_matches(state);
+
+        // Every series matches the empty query, so the saved series is at the same index in the filter
+        if let Some(filter) = &mut state.series_filter {
+            let selected = state.list_positions.get(&SavedList::Search, filter.len());
+            filter.set_selected(selected);
+        }
+
+        panel
+    }
+
+    /// Remember the selected series so it's selected again the next time the panel is opened.
+    pub fn save_list_position(state: &mut UIState) {
+        let selected = state
+            .series_filter
+            .as_ref()
+            .and_then(|filter| filter.selected().copied());
+
+        if let Some(index) = selected {
+            state.list_positions.save(SavedList::Search, index);
+        }
+    }
+
+    fn update_matches(&self, state: &mut UIState) {
+        let matches = matching_series(self.input.text(), state.series.iter());
+        state.series_filter = Some(WrappedSelection::new(matches));
+    }
+
+    fn jump_to_selected(state: &mut UIState) {
+        let selected = state
+            .series_filter
+            .as_ref()
+            .and_then(|filter| filter.selected().copied());
+
+        if let Some(index) = selected {
+            state.series.set_selected(index);
+            state.init_selected_series();
+        }
+    }
+
+    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Search");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical).margin(1).split(
+            block_area,
+            [
+                BasicConstraint::Length(Input::DRAW_LINES_REQUIRED),
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        self.input.draw(layout[0], frame);
+
+        let num_matches = match &state.series_filter {
+            Some(filter) => filter.len(),
+            None => 0,
+        };
+
+        let hint = match num_matches {
+            0 => text::hint("No matching series"),
+            1 => text::hint("1 match, press Enter to jump to it"),
+            num => text::hint(format!(
+                "{} matches, use the arrow keys to select one and press Enter to jump to it",
+                num
+            )),
+        };
+
+        let widget = SimpleText::new(hint).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[1].lines_from_top(1));
+    }
+}
+
+impl Component for SearchPanel {
+    type State = UIState;
+    type KeyResult = ShouldReset;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => ShouldReset::Yes,
+            KeyCode::Enter => {
+                Self::jump_to_selected(state);
+                ShouldReset::Yes
+            }
+            KeyCode::Up | KeyCode::Down => {
+                if let Some(filter) = &mut state.series_filter {
+                    match *key {
+                        KeyCode::Up => filter.dec_selected(),
+                        KeyCode::Down => filter.inc_selected(),
+                        _ => unreachable!(),
+                    }
+                }
+
+                ShouldReset::No
+            }
+            _ => {
+                self.input.process_key(key);
+                self.update_matches(state);
+                ShouldReset::No
+            }
+        }
+    }
+}
+
+/// Returns the index of every series in `series` whose nickname, title, or alias is similar to `query`, ordered from the closest match to the furthest.
+///
+/// Every series is returned in its original order when `query` is empty.
+fn matching_series<'a, I>(query: &str, series: I) -> Vec<usize>
+where
+    I: Iterator<Item = &'a LoadedSeries>,
+{
+    let query = query.trim().to_lowercase();
+
+    if query.is_empty() {
+        return series.enumerate().map(|(i, _)| i).collect();
+    }
+
+    let mut matches = series
+        .enumerate()
+        .filter_map(|(i, series)| {
+            let nickname = match_confidence(&query, series.nickname());
+
+            let title = match series {
+                LoadedSeries::Complete(series) => {
+                    match_confidence(&query, &series.data.info.title_preferred)
+                }
+                LoadedSeries::Unscanned(data) => {
+                    match_confidence(&query, &data.info.title_preferred)
+                }
+                LoadedSeries::Partial(..) | LoadedSeries::None(..) => None,
+            };
+
+            let aliases = series
+                .aliases()
+                .iter()
+                .filter_map(|alias| match_confidence(&query, alias));
+
+            let confidence = nickname
+                .into_iter()
+                .chain(title)
+                .chain(aliases)
+                .reduce(f32::max)?;
+
+            Some((i, confidence))
+        })
+        .collect::<Vec<_>>();
+
+    // Sorting is stable, so series with the same confidence will stay in the same order as the series list
+    matches.sort_by(|(_, x), (_, y)| y.partial_cmp(x).unwrap_or(Ordering::Equal));
+    matches.into_iter().map(|(i, _)| i).collect()
+}
+
+/// Returns how similar `name` is to `query`, if it is similar enough to be considered a match.
+///
+/// `query` should be in lowercase.
+fn match_confidence(query: &str, name: &str) -> Option<f32> {
+    /// The confidence given to a name that contains every character of the query in order.
+    const SUBSEQUENCE_CONFIDENCE: f32 = 0.85;
+
+    let name = name.to_lowercase();
+
+    if name.starts_with(query) {
+        return Some(1.0);
+    }
+
+    if name.contains(query) {
+        return Some(0.95);
+    }
+
+    let mut name_chars = name.chars();
+
+    if query
+        .chars()
+        .all(|query_ch| name_chars.any(|name_ch| name_ch == query_ch))
+    {
+        return Some(SUBSEQUENCE_CONFIDENCE);
+    }
+
+    let confidence = strsim::jaro_winkler(&name, query) as f32;
+
+    if confidence >= MIN_CONFIDENCE {
+        Some(confidence)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_confidence_ordering() {
+        let prefix = match_confidence("kaguya", "Kaguya-sama").unwrap();
+        let substring = match_confidence("sama", "Kaguya-sama").unwrap();
+        let subsequence = match_confidence("kgys", "Kaguya-sama").unwrap();
+        let typo = match_confidence("kaguay-sama", "Kaguya-sama").unwrap();
+
+        assert!(prefix > substring);
+        assert!(substring > subsequence);
+        assert!(typo >= MIN_CONFIDENCE);
+
+        assert_eq!(match_confidence("monogatari", "Kaguya-sama"), None);
+    }
+}
@@ -0,0 +1,301 @@
+use super::anilist;
+use crate::key::Key;
+use crate::series::info::SeriesInfo;
+use crate::tui::component::Component;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::{SharedState, UIState};
+use crate::tui::theme;
+use crate::util::{arc_mutex, ArcMutex, ScopedTask};
+use anime::remote::anilist::{AiringSeason, SeasonalSeries};
+use anime::SeriesKind;
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Local};
+use crossterm::event::KeyCode;
+use std::mem;
+use std::sync::Arc;
+use tokio::task;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappedSelection,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// A panel to browse the anime that premiered in a season on AniList and add any of them to the list.
+pub struct SeasonalPanel {
+    season: AiringSeason,
+    series: WrappedSelection<Vec<SeasonEntry>, SeasonEntry>,
+    lookup: ArcMutex<Lookup>,
+    #[allow(dead_code)]
+    lookup_task: Option<ScopedTask<()>>,
+    state: SharedState,
+}
+
+impl SeasonalPanel {
+    /// Create the panel and start looking up the current season.
+    pub fn init(state: &UIState, shared_state: &SharedState) -> Result<Self> {
+        let today = Local::today();
+
+        let mut panel = Self {
+            season: AiringSeason::containing(today.year(), today.month()),
+            series: WrappedSelection::new(Vec::new()),
+            lookup: arc_mutex(Lookup::Idle),
+            lookup_task: None,
+            state: shared_state.clone(),
+        };
+
+        panel.look_up_season(state)?;
+        Ok(panel)
+    }
+
+    /// Look up the series of the current season in the background.
+    fn look_up_season(&mut self, state: &UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in_shared()?;
+
+        if anilist(&remote).is_none() {
+            return Err(anyhow!(
+                "seasons can only be browsed when AniList is your main service"
+            ));
+        }
+
+        let season = self.season;
+        let lookup = Arc::clone(&self.lookup);
+        let shared_state = self.state.clone();
+
+        self.series = WrappedSelection::new(Vec::new());
+        *lookup.lock() = Lookup::Pending;
+
+        let task = task::spawn(async move {
+            let result = match anilist(&remote) {
+                Some(anilist) => anilist.seasonal_series(season).await,
+                None => Ok(Vec::new()),
+            };
+
+            let finished = match result {
+                Ok(series) => Lookup::Done(series.into_iter().map(Into::into).collect()),
+                Err(err) => {
+                    let err =
+                        anyhow!(err).context(format!("failed to get the series of {}", season));
+                    shared_state.lock().get_mut().push_remote_error(&err);
+                    Lookup::Idle
+                }
+            };
+
+            *lookup.lock() = finished;
+            shared_state.lock().mark_dirty();
+        });
+
+        self.lookup_task = Some(task.into());
+        Ok(())
+    }
+
+    /// Show the series from the last lookup if it has finished.
+    ///
+    /// The selected series is restored from the last time the same season was viewed.
+    fn take_finished_lookup(&mut self, positions: &ListPositions) {
+        let mut lookup = self.lookup.lock();
+
+        if let Lookup::Done(_) = &*lookup {
+            if let Lookup::Done(series) = mem::replace(&mut *lookup, Lookup::Idle) {
+                let selected = positions.get(&SavedList::Seasonal(self.season), series.len());
+
+                self.series = WrappedSelection::new(series);
+                self.series.set_selected(selected);
+            }
+        }
+    }
+
+    /// Remember the selected series of the current season so it can be restored the next time the season is viewed.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        if !self.series.is_empty() {
+            positions.save(SavedList::Seasonal(self.season), self.series.index());
+        }
+    }
+
+    /// Switch to `season` and look up its series.
+    fn change_season(&mut self, season: AiringSeason, state: &mut UIState) -> Result<()> {
+        self.take_finished_lookup(&state.list_positions);
+        self.save_list_position(&mut state.list_positions);
+
+        self.season = season;
+        self.look_up_season(state)
+    }
+
+    #[inline(always)]
+    fn is_looking_up(&self) -> bool {
+        matches!(*self.lookup.lock(), Lookup::Pending)
+    }
+
+    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Seasonal");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .split(
+                block_area,
+                [
+                    BasicConstraint::Length(1),
+                    BasicConstraint::Length(1),
+                    BasicConstraint::MinLenRemaining(1, 1),
+                ],
+            );
+
+        self.take_finished_lookup(&state.list_positions);
+
+        let season = text::bold(format!("< {} >", self.season));
+        let widget = SimpleText::new(season).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[0].lines_from_top(1));
+
+        let hint = if self.is_looking_up() {
+            "Looking up the season.."
+        } else if self.series.is_empty() {
+            "No series found | Left/Right - change season"
+        } else {
+            "Left/Right - change season | Enter - add to list"
+        };
+
+        let widget = SimpleText::new(text::hint(hint)).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[1].lines_from_top(1));
+
+        if self.series.is_empty() {
+            return;
+        }
+
+        let rows = self.series.iter().map(|series| {
+            let in_list = is_in_list(series.info.id, state);
+
+            let score = series
+                .average_score
+                .map_or_else(String::new, |score| format!("{}%", score));
+
+            [
+                Span::raw(series.info.title(state.config.title_language)),
+                Span::raw(format_name(series.kind)),
+                Span::raw(series.info.episodes.to_string()),
+                Span::raw(score),
+                Span::raw(series.studio.as_deref().unwrap_or_default()),
+                Span::raw(if in_list { "Yes" } else { "No" }),
+            ]
+        });
+
+        let header = [
+            Span::raw("Title"),
+            Span::raw("Format"),
+            Span::raw("Episodes"),
+            Span::raw("Score"),
+            Span::raw("Studio"),
+            Span::raw("In List"),
+        ];
+
+        let constraints = [
+            BasicConstraint::Percentage(50),
+            BasicConstraint::Length(7),
+            BasicConstraint::Length(8),
+            BasicConstraint::Length(5),
+            BasicConstraint::Percentage(20),
+            BasicConstraint::Length(7),
+        ];
+
+        let widget = SimpleTable::new(rows, constraints)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(self.series.index() as u16));
+
+        frame.render_widget(widget, layout[2]);
+    }
+}
+
+impl Component for SeasonalPanel {
+    type State = UIState;
+    type KeyResult = Result<SeasonalResult>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(SeasonalResult::Reset),
+            KeyCode::Left => {
+                self.change_season(self.season.prev(), state)?;
+                Ok(SeasonalResult::Ok)
+            }
+            KeyCode::Right => {
+                self.change_season(self.season.next(), state)?;
+                Ok(SeasonalResult::Ok)
+            }
+            _ if self.is_looking_up() => Ok(SeasonalResult::Ok),
+            KeyCode::Enter => {
+                self.take_finished_lookup(&state.list_positions);
+
+                let selected = match self.series.selected() {
+                    Some(selected) => selected,
+                    None => return Ok(SeasonalResult::Ok),
+                };
+
+                if is_in_list(selected.info.id, state) {
+                    return Err(anyhow!(
+                        "{} is already in your list",
+                        selected.info.title_preferred
+                    ));
+                }
+
+                Ok(SeasonalResult::AddSeries(selected.info.clone()))
+            }
+            KeyCode::Up => {
+                self.series.dec_selected();
+                Ok(SeasonalResult::Ok)
+            }
+            KeyCode::Down => {
+                self.series.inc_selected();
+                Ok(SeasonalResult::Ok)
+            }
+            _ => Ok(SeasonalResult::Ok),
+        }
+    }
+}
+
+fn is_in_list(id: i32, state: &UIState) -> bool {
+    state.series.iter().any(|series| series.config().id == id)
+}
+
+/// Returns the name AniList gives to the format of a series of `kind`.
+fn format_name(kind: SeriesKind) -> &'static str {
+    match kind {
+        SeriesKind::Season => "TV",
+        other => other.into(),
+    }
+}
+
+struct SeasonEntry {
+    info: SeriesInfo,
+    kind: SeriesKind,
+    average_score: Option<u8>,
+    studio: Option<String>,
+}
+
+impl From<SeasonalSeries> for SeasonEntry {
+    fn from(series: SeasonalSeries) -> Self {
+        Self {
+            kind: series.info.kind,
+            info: series.info.into(),
+            average_score: series.average_score,
+            studio: series.studio,
+        }
+    }
+}
+
+enum Lookup {
+    Idle,
+    Pending,
+    Done(Vec<SeasonEntry>),
+}
+
+pub enum SeasonalResult {
+    Ok,
+    AddSeries(SeriesInfo),
+    Reset,
+}
@@ -1,46 +1,56 @@
+use crate::key::Key;
+use crate::series::info::{Candidate, SeriesInfo};
 use crate::series::SeriesParams;
 use crate::tui::component::Component;
-use crate::{key::Key, series::info::SeriesInfo};
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::theme;
+use crate::tui::UIState;
 use crossterm::event::KeyCode;
 use tui::layout::Rect;
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::{backend::Backend, text::Span};
-use tui_utils::{
-    helpers::{block, text},
-    list::WrappedSelection,
-    widgets::SimpleList,
-};
+use tui_utils::{list::WrappedSelection, widgets::SimpleList};
 
 pub struct SelectSeriesPanel {
     state: SelectState,
 }
 
 impl SelectSeriesPanel {
-    #[inline(always)]
-    pub fn new(state: SelectState) -> Self {
+    pub fn new(mut state: SelectState, positions: &ListPositions) -> Self {
+        let list = SavedList::SelectSeries(state.params.name.clone());
+        let selected = positions.get(&list, state.series_list.len());
+
+        state.series_list.set_selected(selected);
         Self { state }
     }
 
+    /// Remember the selected candidate so it can be restored if the same series is added again.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        let list = SavedList::SelectSeries(self.state.params.name.clone());
+        positions.save(list, self.state.series_list.index());
+    }
+
     #[inline(always)]
     pub fn take_params(self) -> SeriesParams {
         self.state.params
     }
 
-    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
-        let block = block::with_borders("Select a series from the list");
+    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("No confident match found, select a series from the list");
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
 
-        let names = self
-            .state
-            .series_list
-            .iter()
-            .map(|info| Span::raw(info.title_preferred.as_str()));
+        let names = self.state.series_list.iter().map(|candidate| {
+            Span::raw(format!(
+                "{} ({:.0}%)",
+                candidate.info.title(state.config.title_language),
+                candidate.confidence * 100.0
+            ))
+        });
 
         let items = SimpleList::new(names)
-            .highlight_symbol(text::italic_with(">", |s| s.fg(Color::Green)))
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
             .select(Some(self.state.series_list.index() as u16));
 
         frame.render_widget(items, block_area);
@@ -67,9 +77,9 @@ impl Component for SelectSeriesPanel {
                 }
 
                 let selected = self.state.series_list.index();
-                let info = self.state.series_list.swap_remove(selected);
+                let candidate = self.state.series_list.swap_remove(selected);
 
-                SelectSeriesResult::AddSeries(info)
+                SelectSeriesResult::AddSeries(candidate.info)
             }
             KeyCode::Esc => SelectSeriesResult::Reset,
             _ => SelectSeriesResult::Ok,
@@ -78,12 +88,13 @@ impl Component for SelectSeriesPanel {
 }
 
 pub struct SelectState {
-    pub series_list: WrappedSelection<Vec<SeriesInfo>, SeriesInfo>,
+    /// The series found for the lookup, from the closest match to the furthest.
+    pub series_list: WrappedSelection<Vec<Candidate>, Candidate>,
     pub params: SeriesParams,
 }
 
 impl SelectState {
-    pub fn new(series_list: Vec<SeriesInfo>, params: SeriesParams) -> Self {
+    pub fn new(series_list: Vec<Candidate>, params: SeriesParams) -> Self {
         Self {
             series_list: WrappedSelection::new(series_list),
             params,
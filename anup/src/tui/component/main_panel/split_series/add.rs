@@ -1,10 +1,11 @@
 use super::SplitPanelResult;
-use crate::series::{self, SeriesParams, SeriesPath};
+use crate::series::{SeriesParams, SeriesPath};
 use crate::try_opt_ret;
 use crate::tui::component::input::{
     DrawInput, Input, InputFlags, NameInput, ParsedValue, ValidatedInput,
 };
 use crate::tui::component::Component;
+use crate::tui::theme;
 use crate::tui::UIState;
 use crate::{key::Key, series::config::SeriesConfig};
 use anime::local::EpisodeParser;
@@ -13,10 +14,9 @@ use anyhow::Result;
 use crossterm::event::KeyCode;
 use tui::backend::Backend;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui_utils::{
-    helpers::{block, text},
+    helpers::text,
     layout::{BasicConstraint, SimpleLayout},
     widgets::{OverflowMode, SimpleText},
 };
@@ -27,8 +27,8 @@ pub struct AddPanel {
 }
 
 impl AddPanel {
-    pub fn new(info: RemoteInfo, path: SeriesPath) -> Self {
-        let name_input = series::generate_nickname(&info.title.preferred).map_or_else(
+    pub fn new(info: RemoteInfo, path: SeriesPath, nickname: Option<String>) -> Self {
+        let name_input = nickname.map_or_else(
             || NameInput::new(InputFlags::SELECTED),
             |nickname| NameInput::with_placeholder(InputFlags::SELECTED, nickname),
         );
@@ -42,7 +42,7 @@ impl AddPanel {
     pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
         let data = try_opt_ret!(&self.data);
 
-        let block = block::with_borders("Enter Name For Series");
+        let block = theme::block("Enter Name For Series");
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -83,7 +83,7 @@ impl AddPanel {
         self.name_input.draw(name_layout[1], frame);
 
         if let Some(error) = self.name_input.error() {
-            let error_text = text::bold_with(error, |s| s.fg(Color::Red));
+            let error_text = text::bold_with(error, |s| s.fg(theme::get().error));
             let error_widget = SimpleText::new(error_text)
                 .alignment(Alignment::Center)
                 .overflow(OverflowMode::Truncate);
@@ -1,40 +1,47 @@
 mod add;
 mod split;
 
+use crate::config::{Config, FilenameConfig};
+use crate::key::Key;
+use crate::sanitize;
+use crate::tui::list_positions::ListPositions;
+use crate::tui::theme;
 use crate::tui::UIState;
 use crate::util::arc_mutex;
-use crate::{config::Config, key::Key};
+use crate::{series::cache, series::merged::MergedSeason, series::SeriesData, util::ScopedTask};
 use crate::{series::config::SeriesConfig, tui::component::prompt::log::LogKind};
-use crate::{series::SeriesData, util::ScopedTask};
 use crate::{
-    series::{LoadedSeries, SeriesPath},
+    series::{self, LoadedSeries, SeriesPath},
     tui::state::SharedState,
 };
 use crate::{tui::component::Component, util::ArcMutex};
 use add::AddPanel;
+use anime::local::split::{LinkMethod, SplitAction, SplitPlan, SplitReport};
 use anime::local::{CategorizedEpisodes, SortedEpisodes};
-use anime::remote::{Remote, RemoteService, SeriesInfo as RemoteInfo};
+use anime::remote::{Remote, SeriesInfo as RemoteInfo};
 use anime::SeriesKind;
 use anyhow::{anyhow, Context, Result};
+use crossterm::event::KeyCode;
 use split::{SplitPanel, SplitResult};
 use std::mem;
-use std::path::PathBuf;
-use std::thread;
-use std::time::Duration;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::{borrow::Cow, sync::Arc};
-use std::{fs, io};
 use tokio::task;
 use tui::backend::Backend;
 use tui::layout::{Alignment, Direction, Rect};
 use tui::terminal::Frame;
+use tui::widgets::Gauge;
 use tui_utils::{
-    helpers::{block, text},
-    layout::SimpleLayout,
+    helpers::{style, text},
+    layout::{BasicConstraint, SimpleLayout},
     widgets::SimpleText,
 };
 
 pub struct SplitSeriesPanel {
     state: ArcMutex<PanelState>,
+    progress: Arc<ResolveProgress>,
+    /// The task looking up the sequels of the series, which is cancelled when the panel is closed.
     #[allow(dead_code)]
     split_task: ScopedTask<()>,
 }
@@ -42,79 +49,140 @@ pub struct SplitSeriesPanel {
 impl SplitSeriesPanel {
     pub fn new(state: &SharedState) -> Self {
         let panel_state = arc_mutex(PanelState::Loading);
-        let split_task = Self::spawn_split_series_task(&panel_state, state).into();
+        let progress = Arc::new(ResolveProgress::default());
+        let split_task = Self::spawn_split_series_task(&panel_state, &progress, state).into();
 
         Self {
             state: panel_state,
+            progress,
             split_task,
         }
     }
 
     fn spawn_split_series_task(
         panel_state: &ArcMutex<PanelState>,
+        progress: &Arc<ResolveProgress>,
         state: &SharedState,
     ) -> task::JoinHandle<()> {
         let panel_state = Arc::clone(panel_state);
+        let progress = Arc::clone(progress);
         let state = state.clone();
 
         task::spawn(async move {
-            let mut state = state.lock();
+            // The state only stays locked while gathering what's needed, so the UI isn't frozen while every sequel is looked up
+            let (base, remote, config_id) = {
+                let mut state = state.lock();
+
+                let data = match state.series.selected() {
+                    Some(LoadedSeries::Complete(series)) => &series.data,
+                    Some(LoadedSeries::Partial(data, _) | LoadedSeries::Unscanned(data)) => data,
+                    Some(LoadedSeries::None(_, _)) | None => {
+                        state
+                            .get_mut()
+                            .log
+                            .push(LogKind::Error, "cannot split a series with errors");
+
+                        return;
+                    }
+                };
 
-            let series = match state.series.selected() {
-                Some(LoadedSeries::Complete(series)) => &series.data,
-                Some(LoadedSeries::Partial(data, _)) => data,
-                Some(LoadedSeries::None(_, _)) | None => {
-                    state
-                        .get_mut()
-                        .log
-                        .push(LogKind::Error, "cannot split a series with errors");
+                let config_id = data.config.id;
+                let base = MergedBase::new(data, &state.config);
 
-                    return;
-                }
-            };
+                let remote = match state.remote.get_logged_in_shared() {
+                    Ok(remote) => remote,
+                    Err(_) => return,
+                };
 
-            let remote = match state.remote.get_logged_in() {
-                Ok(remote) => remote,
-                Err(_) => return,
+                match base {
+                    Ok(base) => (base, remote, config_id),
+                    Err(err) => {
+                        state.get_mut().log.push_error(&err);
+                        return;
+                    }
+                }
             };
 
-            let merged_series = match MergedSeries::resolve(series, remote, &state.config) {
+            let merged_series = match MergedSeries::resolve(&base, &remote, &state, &progress).await
+            {
                 Ok(merged) => merged,
                 Err(err) => {
-                    state.get_mut().log.push_error(&err);
+                    state.lock().get_mut().log.push_error(&err);
                     return;
                 }
             };
 
-            let mut panel_state = panel_state.lock();
+            let split_panel = SplitPanel::new(
+                merged_series,
+                config_id,
+                base.nickname,
+                &state.lock().list_positions,
+            );
+
+            *panel_state.lock() = PanelState::Splitting(split_panel.into());
 
-            *panel_state = PanelState::Splitting(SplitPanel::new(merged_series).into());
-            state.mark_dirty();
+            state.lock().mark_dirty();
         })
     }
 
-    fn draw_loading_panel<B>(rect: Rect, frame: &mut Frame<B>)
+    /// Remember the selected season so it's restored the next time the series is split.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        match &*self.state.lock() {
+            PanelState::Splitting(split_panel) | PanelState::AddingSeries(_, split_panel) => {
+                split_panel.save_list_position(positions);
+            }
+            PanelState::Loading => (),
+        }
+    }
+
+    fn draw_loading_panel<B>(progress: &ResolveProgress, rect: Rect, frame: &mut Frame<B>)
     where
         B: Backend,
     {
-        let outline = block::with_borders("Split Series");
+        let outline = theme::block("Split Series");
+        let area = outline.inner(rect);
+
         frame.render_widget(outline, rect);
 
         let layout = SimpleLayout::new(Direction::Vertical)
-            .margin(1)
-            .split_evenly(rect);
+            .horizontal_margin(4)
+            .split(
+                area,
+                [
+                    BasicConstraint::Percentage(40),
+                    BasicConstraint::Length(1),
+                    BasicConstraint::Length(1),
+                    BasicConstraint::Length(1),
+                    BasicConstraint::Length(1),
+                ],
+            );
 
-        let text = text::bold("Loading..");
+        let text = text::bold("Looking up sequels..");
         let widget = SimpleText::new(text).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[1]);
+
+        let (resolved, total) = progress.get();
+
+        if total > 0 {
+            let label = format!("{} of {}", resolved, total);
+
+            let widget = Gauge::default()
+                .gauge_style(style::fg(theme::get().info))
+                .ratio(f64::from(resolved.min(total)) / f64::from(total))
+                .label(label);
 
-        frame.render_widget(widget, layout.right);
+            frame.render_widget(widget, layout[2]);
+        }
+
+        let widget = SimpleText::new(text::hint("Esc - cancel")).alignment(Alignment::Center);
+        frame.render_widget(widget, layout[4]);
     }
 
     pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
         let mut state = self.state.lock();
 
         match &mut *state {
-            PanelState::Loading => Self::draw_loading_panel(rect, frame),
+            PanelState::Loading => Self::draw_loading_panel(&self.progress, rect, frame),
             PanelState::Splitting(split_panel) => split_panel.draw(rect, frame),
             PanelState::AddingSeries(add_panel, _) => add_panel.draw(rect, frame),
         }
@@ -129,12 +197,14 @@ impl Component for SplitSeriesPanel {
         let mut panel_state = self.state.lock();
 
         match &mut *panel_state {
+            // Closing the panel drops the task looking up sequels, which cancels it
+            PanelState::Loading if *key == KeyCode::Esc => Ok(SplitPanelResult::Reset),
             PanelState::Loading => Ok(SplitPanelResult::Ok),
             PanelState::Splitting(split_panel) => match split_panel.process_key(key, state) {
                 Ok(SplitResult::Ok) => Ok(SplitPanelResult::Ok),
                 Ok(SplitResult::Reset) => Ok(SplitPanelResult::Reset),
-                Ok(SplitResult::AddSeries(info, path)) => {
-                    let add_panel = AddPanel::new(info, path);
+                Ok(SplitResult::AddSeries(info, path, nickname)) => {
+                    let add_panel = AddPanel::new(info, path, nickname);
                     let split_panel = mem::take(split_panel);
 
                     *panel_state = PanelState::AddingSeries(add_panel.into(), split_panel);
@@ -181,6 +251,44 @@ impl SplitPanelResult {
     }
 }
 
+/// The parts of a series needed to resolve the seasons merged into it.
+///
+/// This lets the sequels of a series be looked up without keeping the state locked.
+pub struct MergedBase {
+    id: u32,
+    path: SeriesPath,
+    nickname: String,
+    episodes: CategorizedEpisodes,
+    filenames: FilenameConfig,
+    /// How old cached series info can be before it needs to be fetched again.
+    cache_max_age: chrono::Duration,
+}
+
+impl MergedBase {
+    pub fn new(data: &SeriesData, config: &Config) -> Result<Self> {
+        let episodes = series::parse_episode_dir(
+            data.config.path.absolute(config),
+            &data.config.episode_parser,
+            &data.titles(),
+            config,
+        )?;
+
+        Ok(Self {
+            id: data.info.id as u32,
+            path: data.config.path.clone(),
+            nickname: data.config.nickname.clone(),
+            episodes,
+            filenames: config.filenames.clone(),
+            cache_max_age: chrono::Duration::days(config.offline.cache_max_age_days.into()),
+        })
+    }
+
+    #[inline(always)]
+    pub fn nickname(&self) -> &str {
+        &self.nickname
+    }
+}
+
 #[allow(variant_size_differences)]
 enum MergedSeries {
     Resolved(Box<ResolvedSeries>),
@@ -193,21 +301,42 @@ impl MergedSeries {
         Self::Resolved(Box::new(resolved))
     }
 
-    fn resolve(data: &SeriesData, remote: &Remote, config: &Config) -> Result<Vec<Self>> {
-        let episodes = CategorizedEpisodes::parse(
-            data.config.path.absolute(config),
-            &data.config.episode_parser,
-        )?;
+    async fn resolve(
+        base: &MergedBase,
+        remote: &Remote,
+        state: &SharedState,
+        progress: &ResolveProgress,
+    ) -> Result<Vec<Self>> {
+        let lock_db = || state.lock_db();
 
-        let base_info = remote.search_info_by_id(data.info.id as u32)?;
+        progress.add_pending(1);
+        let base_info = cache::lookup_by_id(lock_db, remote, base.id, base.cache_max_age).await?;
+        progress.add_resolved(1, state);
 
         if base_info.sequels.is_empty() {
             return Ok(Vec::new());
         }
 
+        // Every sequel that isn't a season can be looked up at once
+        let sequel_ids = base
+            .episodes
+            .iter()
+            .filter_map(|(cat, _)| base_info.sequel_by_kind(*cat))
+            .filter(|sequel| sequel.kind != SeriesKind::Season)
+            .map(|sequel| sequel.id)
+            .collect::<Vec<_>>();
+
+        progress.add_pending(sequel_ids.len() as u32);
+
+        let mut sequel_infos = cache::lookup(lock_db, remote, &sequel_ids, base.cache_max_age)
+            .await
+            .unwrap_or_default();
+
+        progress.add_resolved(sequel_ids.len() as u32, state);
+
         let mut results = Vec::with_capacity(1);
 
-        for (cat, eps) in episodes.iter() {
+        for (cat, eps) in base.episodes.iter() {
             let sequel = match base_info.sequel_by_kind(*cat) {
                 Some(sequel) => sequel,
                 None => continue,
@@ -217,27 +346,26 @@ impl MergedSeries {
             if let SeriesKind::Season = sequel.kind {
                 Self::resolve_merged_season(
                     &base_info,
-                    &data.config.path,
+                    base,
                     remote,
+                    state,
+                    progress,
                     eps,
-                    config,
                     &mut results,
-                );
+                )
+                .await;
 
                 continue;
             }
 
-            thread::sleep(Duration::from_millis(250));
-
-            let sequel_info = if let Ok(info) = remote.search_info_by_id(sequel.id) {
+            let sequel_info = if let Some(info) = sequel_infos.remove(&sequel.id) {
                 info
             } else {
                 results.push(Self::Failed(sequel.kind));
                 continue;
             };
 
-            let resolved =
-                ResolvedSeries::new(sequel_info, data.config.path.clone(), eps, 0, config);
+            let resolved = ResolvedSeries::new(sequel_info, base, eps, 0, None, true);
 
             results.push(Self::resolved(resolved));
         }
@@ -245,53 +373,75 @@ impl MergedSeries {
         Ok(results)
     }
 
-    fn resolve_merged_season(
+    async fn resolve_merged_season(
         base_info: &RemoteInfo,
-        base_path: &SeriesPath,
+        base: &MergedBase,
         remote: &Remote,
+        state: &SharedState,
+        progress: &ResolveProgress,
         episodes: &SortedEpisodes,
-        config: &Config,
         results: &mut Vec<Self>,
     ) {
-        let highest_episode = episodes.highest_episode_number();
         let mut info = Cow::Borrowed(base_info);
 
         // Exit early if we don't have enough episodes locally to have any merged seasons
-        if info.episodes > highest_episode {
-            return;
-        }
-
-        let mut episode_offset = info.episodes;
+        let mut offsets =
+            match MergedSeasonOffsets::new(info.episodes, episodes.highest_episode_number()) {
+                Some(offsets) => offsets,
+                None => return,
+            };
 
         while let Some(sequel) = info.direct_sequel() {
-            info = if let Ok(info) = remote.search_info_by_id(sequel.id) {
+            progress.add_pending(1);
+
+            // Every season after one that failed would have the wrong offset, so there's no point in continuing
+            let found =
+                cache::lookup_by_id(|| state.lock_db(), remote, sequel.id, base.cache_max_age)
+                    .await;
+
+            progress.add_resolved(1, state);
+
+            info = if let Ok(info) = found {
                 info.into()
             } else {
                 results.push(Self::Failed(sequel.kind));
-                continue;
+                break;
             };
 
+            let offset = offsets.next(info.episodes);
+
+            // Any episodes past the end of the last season don't belong to anything
+            let is_last = info.direct_sequel().is_none();
+
             let resolved = ResolvedSeries::new(
                 info.clone().into_owned(),
-                base_path.clone(),
+                base,
                 episodes,
-                episode_offset,
-                config,
+                offset,
+                Some(offsets.season()),
+                is_last,
             );
 
             results.push(Self::resolved(resolved));
 
-            episode_offset += info.episodes;
-
             // We can stop if we don't have anymore sequels or if we don't have enough episodes locally to have another merged season
-            if episode_offset > highest_episode || info.direct_sequel().is_none() {
+            if !offsets.has_room() || info.direct_sequel().is_none() {
                 break;
             }
-
-            thread::sleep(Duration::from_millis(250));
         }
     }
 
+    /// Returns the number of resolved series whose episodes don't line up with the files they would be split from.
+    fn num_mismatched(merged: &[Self]) -> usize {
+        merged
+            .iter()
+            .filter(|series| match series {
+                Self::Resolved(series) => !series.report.is_clean(),
+                Self::Failed(_) => false,
+            })
+            .count()
+    }
+
     fn split_all(merged: &[Self], config: &Config) -> Result<()> {
         for series in merged {
             let series = match series {
@@ -308,126 +458,343 @@ impl MergedSeries {
     }
 }
 
+/// How many of the series needed to split a series have been looked up.
+///
+/// The total grows as sequels are found, since merged seasons can only be discovered one at a time.
+#[derive(Default)]
+pub struct ResolveProgress {
+    resolved: AtomicU32,
+    total: AtomicU32,
+}
+
+impl ResolveProgress {
+    fn add_pending(&self, count: u32) {
+        self.total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Count `count` more series as looked up and redraw the UI to show it.
+    fn add_resolved(&self, count: u32, state: &SharedState) {
+        self.resolved.fetch_add(count, Ordering::Relaxed);
+        state.lock().mark_dirty();
+    }
+
+    /// Returns the number of series that have been looked up, along with the number known to need looking up.
+    fn get(&self) -> (u32, u32) {
+        (
+            self.resolved.load(Ordering::Relaxed),
+            self.total.load(Ordering::Relaxed),
+        )
+    }
+}
+
 pub type EpisodeOffset = u32;
 
+/// Accumulates the episode offsets of seasons that have been merged together into one folder.
+struct MergedSeasonOffsets {
+    next_offset: EpisodeOffset,
+    highest_episode: u32,
+    season: u32,
+}
+
+impl MergedSeasonOffsets {
+    /// Create a new `MergedSeasonOffsets` starting after a base season with `base_episodes` episodes.
+    ///
+    /// Returns `None` if `highest_episode` shows that there aren't enough episodes locally to have any merged seasons.
+    fn new(base_episodes: u32, highest_episode: u32) -> Option<Self> {
+        if base_episodes > highest_episode {
+            return None;
+        }
+
+        Some(Self {
+            next_offset: base_episodes,
+            highest_episode,
+            season: 1,
+        })
+    }
+
+    /// Returns the episode offset of the next merged season, which has the specified number of `episodes`.
+    fn next(&mut self, episodes: u32) -> EpisodeOffset {
+        let offset = self.next_offset;
+        self.next_offset += episodes;
+        self.season += 1;
+        offset
+    }
+
+    /// Returns the season number of the last season returned by `next`, where the base season is season 1.
+    #[inline(always)]
+    fn season(&self) -> u32 {
+        self.season
+    }
+
+    /// Returns true if there are enough episodes locally to have another merged season.
+    fn has_room(&self) -> bool {
+        self.next_offset <= self.highest_episode
+    }
+}
+
+/// A season whose episode links were recreated by [`relink_merged_seasons`].
+pub struct RelinkedSeason {
+    pub title: String,
+    pub path: SeriesPath,
+    pub offset: EpisodeOffset,
+    pub episodes: u32,
+}
+
+/// Re-walks the sequel chain of the series described by `base` and recomputes the episode offset of each season merged into it.
+///
+/// Every season that has already been split off will have its episode links recreated to match the new offsets.
+/// Seasons that haven't been split yet are left alone.
+/// The `state` is only locked once every season has been looked up.
+pub async fn relink_merged_seasons(
+    mut base: MergedBase,
+    remote: &Remote,
+    state: &SharedState,
+) -> Result<Vec<RelinkedSeason>> {
+    let episodes = mem::take(&mut base.episodes)
+        .take_category(SeriesKind::Season)
+        .ok_or_else(|| anyhow!("no season episodes found"))?;
+
+    let base_info =
+        cache::lookup_by_id(|| state.lock_db(), remote, base.id, base.cache_max_age).await?;
+
+    let mut merged = Vec::new();
+
+    MergedSeries::resolve_merged_season(
+        &base_info,
+        &base,
+        remote,
+        state,
+        &ResolveProgress::default(),
+        &episodes,
+        &mut merged,
+    )
+    .await;
+
+    let state = state.lock();
+    let config = &state.config;
+    let mut relinked = Vec::with_capacity(merged.len());
+
+    for series in merged {
+        let mut series = match series {
+            MergedSeries::Resolved(series) => series,
+            // Every offset after a failed season would be wrong
+            MergedSeries::Failed(kind) => {
+                return Err(anyhow!("failed to get info for {} sequel", kind))
+            }
+        };
+
+        if !series.out_dir.absolute(config).exists() {
+            // Seasons split before their titles were sanitized are in a folder with the exact title
+            let unsanitized = SeriesPath::new(Path::new(&series.info.title.preferred), config);
+
+            if !unsanitized.absolute(config).exists() {
+                continue;
+            }
+
+            series.out_dir = unsanitized;
+        }
+
+        series
+            .relink(config)
+            .with_context(|| format!("relinking {}", series.info.title.preferred))?;
+
+        relinked.push(RelinkedSeason {
+            title: series.info.title.preferred,
+            path: series.out_dir,
+            offset: series.offset,
+            episodes: series.info.episodes,
+        });
+    }
+
+    Ok(relinked)
+}
+
+/// Walks the sequel chain of the series described by `base` to find every season merged into its folder, so the folder can be watched as one series.
+///
+/// The returned seasons start with the series itself.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+pub async fn find_merged_seasons(
+    mut base: MergedBase,
+    remote: &Remote,
+    state: &SharedState,
+) -> Result<Vec<MergedSeason>> {
+    let episodes = mem::take(&mut base.episodes)
+        .take_category(SeriesKind::Season)
+        .ok_or_else(|| anyhow!("no season episodes found"))?;
+
+    let base_info =
+        cache::lookup_by_id(|| state.lock_db(), remote, base.id, base.cache_max_age).await?;
+
+    let mut merged = Vec::new();
+
+    MergedSeries::resolve_merged_season(
+        &base_info,
+        &base,
+        remote,
+        state,
+        &ResolveProgress::default(),
+        &episodes,
+        &mut merged,
+    )
+    .await;
+
+    if merged.is_empty() {
+        return Err(anyhow!(
+            "no seasons appear to be merged into the folder of {}",
+            base.nickname
+        ));
+    }
+
+    let series_id = base.id as i32;
+    let mut seasons = Vec::with_capacity(merged.len() + 1);
+
+    seasons.push(MergedSeason {
+        series_id,
+        season_id: series_id,
+        episode_offset: 0,
+        episodes: base_info.episodes as i16,
+    });
+
+    for series in merged {
+        let series = match series {
+            MergedSeries::Resolved(series) => series,
+            // Every offset after a failed season would be wrong
+            MergedSeries::Failed(kind) => {
+                return Err(anyhow!("failed to get info for {} sequel", kind))
+            }
+        };
+
+        seasons.push(MergedSeason {
+            series_id,
+            season_id: series.info.id as i32,
+            episode_offset: series.offset as i16,
+            episodes: series.info.episodes as i16,
+        });
+    }
+
+    Ok(seasons)
+}
+
 struct ResolvedSeries {
     info: RemoteInfo,
     base_dir: SeriesPath,
     out_dir: SeriesPath,
+    offset: EpisodeOffset,
+    /// The season number of the series when it was merged with other seasons.
+    season: Option<u32>,
     actions: Vec<SplitAction>,
+    /// The episodes that are missing from the series or won't be linked into it.
+    report: SplitReport,
 }
 
 impl ResolvedSeries {
     fn new(
         info: RemoteInfo,
-        base_dir: SeriesPath,
+        base: &MergedBase,
         episodes: &SortedEpisodes,
         offset: EpisodeOffset,
-        config: &Config,
+        season: Option<u32>,
+        is_last: bool,
     ) -> Self {
-        let actions = SplitAction::from_merged_seasons(&info, episodes, offset);
-        let out_dir = PathBuf::from(&info.title.preferred);
-        let out_dir = SeriesPath::new(out_dir, config);
+        let out_dir = SeriesPath::with_name(&info.title.preferred, &base.filenames);
+        let title = sanitize::filename(&info.title.preferred, &base.filenames);
+        let actions = SplitAction::from_merged_season(&title, info.episodes, episodes, offset);
+        let report = SplitReport::from_merged_season(info.episodes, episodes, offset, is_last);
 
         Self {
             info,
-            base_dir,
+            base_dir: base.path.clone(),
             out_dir,
+            offset,
+            season,
             actions,
+            report,
         }
     }
 
-    fn perform_split_actions(&self, config: &Config) -> Result<()> {
-        use std::os::unix::fs::symlink;
+    /// Returns the plan to link the episodes of the series into its own folder.
+    fn plan(&self, config: &Config) -> SplitPlan {
+        SplitPlan::new(
+            self.base_dir.absolute(config),
+            self.out_dir.absolute(config),
+            &self.actions,
+        )
+    }
 
-        if self.actions.is_empty() {
-            return Ok(());
-        }
+    /// Recreates the episode links of a series that has already been split.
+    fn relink(&self, config: &Config) -> Result<()> {
+        let plan = self.plan(config);
 
-        let base_dir = self.base_dir.absolute(config);
+        // Links from a previous split may point to the wrong episodes, so they all need to go
+        plan.remove_links().context("removing old episode links")?;
 
-        if !base_dir.exists() {
-            fs::create_dir_all(&base_dir).context("dir creation")?;
-        }
+        plan.execute(LinkMethod::Symlink)?;
+        Ok(())
+    }
 
-        let out_dir = self.out_dir.absolute(config);
+    fn perform_split_actions(&self, config: &Config) -> Result<()> {
+        self.plan(config).execute(LinkMethod::Symlink)?;
+        Ok(())
+    }
+}
 
-        if !out_dir.exists() {
-            fs::create_dir_all(&out_dir).context("dir creation")?;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anime::local::Episode;
 
-        for action in &self.actions {
-            let from_path = base_dir.join(&action.old_name);
-            let to_path = out_dir.join(&action.new_name);
+    fn season_offsets(season_episodes: &[u32], highest_episode: u32) -> Vec<EpisodeOffset> {
+        let (base, sequels) = season_episodes.split_first().unwrap();
+        let mut offsets = MergedSeasonOffsets::new(*base, highest_episode).unwrap();
+        let mut results = Vec::new();
 
-            if let Err(err) = symlink(&from_path, &to_path) {
-                if err.kind() == io::ErrorKind::AlreadyExists {
-                    continue;
-                }
+        for &episodes in sequels {
+            results.push(offsets.next(episodes));
 
-                return Err(anyhow!(
-                    "failed to symlink files:\nfrom: {}\nto: {}\nreason: {}",
-                    from_path.display(),
-                    to_path.display(),
-                    err
-                ));
+            if !offsets.has_room() {
+                break;
             }
         }
 
-        Ok(())
+        results
     }
-}
 
-struct SplitAction {
-    old_name: String,
-    new_name: String,
-}
-
-impl SplitAction {
-    fn new<S, N>(old_name: S, new_name: N) -> Self
-    where
-        S: Into<String>,
-        N: Into<String>,
-    {
-        Self {
-            old_name: old_name.into(),
-            new_name: new_name.into(),
-        }
-    }
+    #[test]
+    fn merged_season_numbers() {
+        let mut offsets = MergedSeasonOffsets::new(12, 48).unwrap();
 
-    fn from_merged_seasons(
-        info: &RemoteInfo,
-        episodes: &SortedEpisodes,
-        offset: EpisodeOffset,
-    ) -> Vec<Self> {
-        let mut actions = Vec::new();
+        offsets.next(12);
+        assert_eq!(offsets.season(), 2);
 
-        let sequel_start = 1 + offset;
-        let sequel_end = offset + info.episodes;
+        offsets.next(12);
+        assert_eq!(offsets.season(), 3);
+    }
 
-        for real_ep_num in sequel_start..=sequel_end {
-            let episode = match episodes.find(real_ep_num) {
-                Some(episode) => episode,
-                None => continue,
-            };
+    #[test]
+    fn merged_season_offsets() {
+        assert_eq!(season_offsets(&[12, 12, 12], 36), vec![12, 24]);
+        assert!(MergedSeasonOffsets::new(12, 11).is_none());
+        assert_eq!(season_offsets(&[12, 12, 12], 20), vec![12]);
+    }
 
-            let extension = PathBuf::from(&episode.filename).extension().map_or_else(
-                || Cow::Borrowed(""),
-                |e| format!(".{}", e.to_string_lossy()).into(),
-            );
+    #[test]
+    fn changed_episode_count_shifts_offsets() {
+        let episodes = SortedEpisodes::with_episodes(
+            (1..=37)
+                .map(|num| Episode::new(num, format!("Merged - {:02}.mkv", num)))
+                .collect(),
+        );
 
-            let new_filename = format!(
-                "{} - {:02}{}",
-                info.title.preferred,
-                real_ep_num - offset,
-                extension
-            );
+        let highest = episodes.highest_episode_number();
 
-            let action = Self::new(&episode.filename, new_filename);
+        assert_eq!(season_offsets(&[12, 12, 12], highest), vec![12, 24]);
+        // The first season turned out to have an extra episode
+        assert_eq!(season_offsets(&[13, 12, 12], highest), vec![13, 25]);
 
-            actions.push(action);
-        }
+        let old_actions = SplitAction::from_merged_season("Season 2", 12, &episodes, 12);
+        let new_actions = SplitAction::from_merged_season("Season 2", 12, &episodes, 13);
 
-        actions
+        assert_eq!(old_actions[0].old_name, "Merged - 13.mkv");
+        assert_eq!(new_actions[0].old_name, "Merged - 14.mkv");
     }
 }
@@ -1,16 +1,21 @@
 use super::MergedSeries;
+use crate::tui::component::prompt::log::LogKind;
 use crate::tui::component::Component;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::theme;
 use crate::tui::UIState;
-use crate::{key::Key, series::SeriesPath};
+use crate::{
+    key::Key,
+    series::{self, SeriesPath},
+};
 use anime::remote::SeriesInfo as RemoteInfo;
 use anyhow::Result;
 use crossterm::event::KeyCode;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::{backend::Backend, text::Span};
 use tui_utils::{
-    helpers::{block, color, style, text},
+    helpers::{color, text},
     layout::{BasicConstraint, SimpleLayout},
     list::WrappingIndex,
     widgets::{SimpleTable, SimpleText},
@@ -20,51 +25,86 @@ use tui_utils::{
 pub struct SplitPanel {
     selected_series: WrappingIndex,
     merged_series: Vec<MergedSeries>,
+    /// The config ID of the series the seasons are merged into.
+    base_id: i32,
+    base_nickname: String,
     has_split_series: bool,
+    /// Set when the user has been warned about mismatched episodes, and has to confirm the split again.
+    confirming_split: bool,
 }
 
 impl SplitPanel {
-    pub(super) fn new(merged_series: Vec<MergedSeries>) -> Self {
+    pub(super) fn new(
+        merged_series: Vec<MergedSeries>,
+        base_id: i32,
+        base_nickname: String,
+        positions: &ListPositions,
+    ) -> Self {
+        let selected = positions.get(&SavedList::SplitSeries(base_id), merged_series.len());
+
         Self {
-            selected_series: WrappingIndex::new(0),
+            selected_series: WrappingIndex::new(selected),
             merged_series,
+            base_id,
+            base_nickname,
             has_split_series: false,
+            confirming_split: false,
         }
     }
 
+    /// Remember the selected season so it can be restored the next time the series is split.
+    pub(super) fn save_list_position(&self, positions: &mut ListPositions) {
+        positions.save(
+            SavedList::SplitSeries(self.base_id),
+            self.selected_series.get(),
+        );
+    }
+
     fn draw_merged_series_table<B>(&self, rect: Rect, frame: &mut Frame<B>)
     where
         B: Backend,
     {
-        let row_color = color::either(self.has_split_series, Color::Blue, Color::Yellow);
+        let theme = theme::get();
+        let row_color = color::either(self.has_split_series, theme.info, theme.warning);
 
         let rows = self.merged_series.iter().map(|merged| match merged {
             &MergedSeries::Failed(kind) => {
                 let kind: &'static str = kind.into();
 
                 [
-                    text::with_color(kind, Color::Red),
-                    text::with_color("Failed..", Color::Red),
+                    text::with_color(kind, theme.error),
+                    text::with_color("Failed..", theme.error),
+                    text::with_color("", theme.error),
                 ]
             }
             MergedSeries::Resolved(series) => {
                 let kind: &'static str = series.info.kind.into();
+                let report_color = color::either(series.report.is_clean(), row_color, theme.error);
 
                 [
                     text::with_color(kind, row_color),
                     text::with_color(series.info.title.preferred.as_str(), row_color),
+                    text::with_color(series.report.to_string(), report_color),
                 ]
             }
         });
 
-        let header = [Span::raw("Type"), Span::raw("Series")];
-        let layout = [BasicConstraint::Length(8), BasicConstraint::Percentage(100)];
+        let header = [
+            Span::raw("Type"),
+            Span::raw("Series"),
+            Span::raw("Episodes"),
+        ];
+        let layout = [
+            BasicConstraint::Length(8),
+            BasicConstraint::Percentage(60),
+            BasicConstraint::Percentage(40),
+        ];
 
         let table = SimpleTable::new(rows, layout)
             .header(&header)
             .highlight_symbol(Span::styled(
                 ">",
-                style::list_selector(self.has_split_series),
+                theme::list_selector(self.has_split_series),
             ));
 
         frame.render_widget(table, rect);
@@ -80,7 +120,7 @@ impl SplitPanel {
     }
 
     pub fn draw<B: Backend>(&mut self, area: Rect, frame: &mut Frame<B>) {
-        let block = block::with_borders(None);
+        let block = theme::block(None);
         let block_area = block.inner(area);
 
         frame.render_widget(block, area);
@@ -102,7 +142,13 @@ impl SplitPanel {
 
         let hint_layout = SimpleLayout::new(Direction::Horizontal).split_evenly(vert_split[1]);
 
-        let hint = SimpleText::new(text::hint("S - Split All")).alignment(Alignment::Center);
+        let split_hint = if self.confirming_split {
+            "S - Split Anyway"
+        } else {
+            "S - Split All"
+        };
+
+        let hint = SimpleText::new(text::hint(split_hint)).alignment(Alignment::Center);
         frame.render_widget(hint, hint_layout.left);
 
         let hint = SimpleText::new(text::hint("Enter - Add Series")).alignment(Alignment::Center);
@@ -118,6 +164,23 @@ impl Component for SplitPanel {
         match *key {
             KeyCode::Esc => Ok(SplitResult::Reset),
             KeyCode::Char('s') => {
+                let mismatched = MergedSeries::num_mismatched(&self.merged_series);
+
+                // Series with missing or extra episodes would be split with holes in them, so make sure that's intended
+                if mismatched > 0 && !self.confirming_split && !self.has_split_series {
+                    self.confirming_split = true;
+
+                    state.log.push(
+                        LogKind::Info,
+                        format!(
+                            "{} series have missing or extra episodes, press S again to split anyway",
+                            mismatched
+                        ),
+                    );
+
+                    return Ok(SplitResult::Ok);
+                }
+
                 MergedSeries::split_all(&self.merged_series, &state.config)?;
 
                 self.has_split_series = true;
@@ -138,9 +201,20 @@ impl Component for SplitPanel {
                     Some(MergedSeries::Failed(_)) | None => return Ok(SplitResult::Ok),
                 };
 
+                // Merged seasons are named after the series they were split from so they stay recognizable
+                let nickname = match series.season {
+                    Some(season) => Some(series::season_nickname(
+                        &self.base_nickname,
+                        season,
+                        |nickname| state.series.iter().any(|s| s.nickname() == nickname),
+                    )),
+                    None => series::generate_nickname(&series.info.title.preferred),
+                };
+
                 Ok(SplitResult::AddSeries(
                     series.info.clone(),
                     series.out_dir.clone(),
+                    nickname,
                 ))
             }
             _ => {
@@ -163,5 +237,5 @@ impl Component for SplitPanel {
 pub enum SplitResult {
     Ok,
     Reset,
-    AddSeries(RemoteInfo, SeriesPath),
+    AddSeries(RemoteInfo, SeriesPath, Option<String>),
 }
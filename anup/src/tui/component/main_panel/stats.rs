@@ -0,0 +1,182 @@
+use super::ShouldReset;
+use crate::remote::RemoteStatus;
+use crate::series::goals::{self, GoalProgress, WeekStats};
+use crate::series::stats::WatchStats;
+use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::util;
+use crate::{key::Key, tui::component::Component};
+use anime::remote::ScoreParser;
+use anyhow::{Context, Result};
+use chrono::Local;
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    widgets::{SimpleTable, SimpleText},
+};
+
+pub struct StatsPanel {
+    overview: Vec<(&'static str, String)>,
+    years: Vec<[String; 3]>,
+}
+
+impl StatsPanel {
+    pub fn init(state: &UIState) -> Result<Self> {
+        let stats = WatchStats::load(&state.db).context("loading statistics")?;
+
+        let mean_score = match (stats.mean_score, &state.remote) {
+            (Some(score), RemoteStatus::LoggedIn(remote)) => {
+                remote.score_to_str(score.round() as u8).into_owned()
+            }
+            (Some(score), RemoteStatus::LoggingIn(_)) => format!("{:.0}", score),
+            (None, _) => "??".into(),
+        };
+
+        let mut overview = vec![
+            ("Episodes Watched", stats.episodes_watched.to_string()),
+            (
+                "Watch Time",
+                util::hours_mins_str(stats.watch_time_mins.into()),
+            ),
+            ("Mean Score", mean_score),
+            (
+                "Longest Streak",
+                format!("{} Days", stats.longest_streak_days),
+            ),
+        ];
+
+        let week_start = goals::week_start(Local::today().naive_local());
+        let week =
+            WeekStats::load(&state.db, week_start).context("loading this week's statistics")?;
+        let goals = &state.config.goals;
+
+        overview.push((
+            "Episodes This Week",
+            with_goal(week.episodes.to_string(), week.episode_goal(goals), |eps| {
+                eps.to_string()
+            }),
+        ));
+
+        overview.push((
+            "Watch Time This Week",
+            with_goal(
+                util::hours_mins_str(week.watch_time_mins.into()),
+                week.minutes_goal(goals),
+                |mins| util::hours_mins_str(mins.into()),
+            ),
+        ));
+
+        overview.extend(
+            stats
+                .status_counts
+                .into_iter()
+                .map(|(status, count)| (status.into(), count.to_string())),
+        );
+
+        let years = stats
+            .years
+            .into_iter()
+            .map(|year| {
+                [
+                    year.year.to_string(),
+                    year.episodes.to_string(),
+                    util::hours_mins_str(year.watch_time_mins.into()),
+                ]
+            })
+            .collect();
+
+        Ok(Self { overview, years })
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Statistics");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Horizontal).margin(1).split(
+            block_area,
+            [
+                BasicConstraint::Percentage(50),
+                BasicConstraint::Percentage(50),
+            ],
+        );
+
+        let overview = self
+            .overview
+            .iter()
+            .map(|(name, value)| [Span::raw(*name), Span::raw(value.as_str())]);
+
+        let header = [Span::raw("Overall"), Span::raw("")];
+        let constraints = [
+            BasicConstraint::Percentage(60),
+            BasicConstraint::Percentage(40),
+        ];
+
+        let widget = SimpleTable::new(overview, constraints).header(&header);
+        frame.render_widget(widget, layout[0]);
+
+        if self.years.is_empty() {
+            let text = text::hint("No episodes have been watched yet");
+            let widget = SimpleText::new(text).alignment(Alignment::Center);
+            frame.render_widget(widget, layout[1].lines_from_top(1));
+            return;
+        }
+
+        let years = self.years.iter().map(|[year, episodes, watch_time]| {
+            [
+                Span::raw(year.as_str()),
+                Span::raw(episodes.as_str()),
+                Span::raw(watch_time.as_str()),
+            ]
+        });
+
+        let header = [
+            Span::raw("Year"),
+            Span::raw("Episodes"),
+            Span::raw("Watch Time"),
+        ];
+
+        let constraints = [
+            BasicConstraint::Length(6),
+            BasicConstraint::Length(10),
+            BasicConstraint::Length(12),
+        ];
+
+        let widget = SimpleTable::new(years, constraints).header(&header);
+        frame.render_widget(widget, layout[1]);
+    }
+}
+
+/// Returns `value` followed by the target of `goal` formatted with `format_target` and how much of it has been reached, if there is a goal.
+fn with_goal<F>(value: String, goal: Option<GoalProgress>, format_target: F) -> String
+where
+    F: Fn(u32) -> String,
+{
+    match goal {
+        Some(goal) => format!(
+            "{}/{} ({}%)",
+            value,
+            format_target(goal.target),
+            goal.percent()
+        ),
+        None => value,
+    }
+}
+
+impl Component for StatsPanel {
+    type State = UIState;
+    type KeyResult = ShouldReset;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => ShouldReset::Yes,
+            _ => ShouldReset::No,
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use super::ShouldReset;
+use crate::series::changes::{EntryMerge, Resolution};
+use crate::tui::component::prompt::log::LogKind;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::state::UIState;
+use crate::tui::theme;
+use crate::{key::Key, tui::component::Component};
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use std::mem;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::text,
+    layout::{BasicConstraint, SimpleLayout},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// Lists every field of a list entry that was changed both offline and on the remote service,
+/// so the user can pick which value to keep for each one.
+pub struct SyncConflictsPanel {
+    merges: Vec<(String, EntryMerge)>,
+    selected: WrappingIndex,
+}
+
+impl SyncConflictsPanel {
+    /// Take the unresolved merges out of `state` to be resolved.
+    pub fn init(state: &mut UIState) -> Self {
+        let merges = mem::take(&mut state.sync_conflicts)
+            .into_iter()
+            .map(|merge| {
+                let name = state
+                    .series
+                    .iter()
+                    .find(|series| series.config().id == merge.id())
+                    .map_or_else(
+                        || format!("ID {}", merge.id()),
+                        |series| series.config().nickname.clone(),
+                    );
+
+                (name, merge)
+            })
+            .collect();
+
+        let mut panel = Self {
+            merges,
+            selected: WrappingIndex::new(0),
+        };
+
+        let selected = state
+            .list_positions
+            .get(&SavedList::SyncConflicts, panel.num_conflicts());
+
+        panel.selected = WrappingIndex::new(selected);
+        panel
+    }
+
+    /// Remember the selected conflict so it can be restored the next time the panel is opened.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        positions.save(SavedList::SyncConflicts, self.selected.get());
+    }
+
+    fn num_conflicts(&self) -> usize {
+        self.merges
+            .iter()
+            .map(|(_, merge)| merge.conflicts().len())
+            .sum()
+    }
+
+    /// Returns the index of the merge the conflict at `index` belongs to, and the index of the conflict within it.
+    fn locate(&self, mut index: usize) -> Option<(usize, usize)> {
+        for (merge_index, (_, merge)) in self.merges.iter().enumerate() {
+            let len = merge.conflicts().len();
+
+            if index < len {
+                return Some((merge_index, index));
+            }
+
+            index -= len;
+        }
+
+        None
+    }
+
+    fn resolve_selected(
+        &mut self,
+        resolution: Resolution,
+        state: &mut UIState,
+    ) -> Result<ShouldReset> {
+        let (merge_index, conflict_index) = match self.locate(self.selected.get()) {
+            Some(location) => location,
+            None => return Ok(ShouldReset::Yes),
+        };
+
+        let (name, merge) = &mut self.merges[merge_index];
+        let field = merge.conflicts()[conflict_index].field;
+
+        merge.resolve(field, resolution);
+
+        let kept = match resolution {
+            Resolution::KeepLocal => "local",
+            Resolution::KeepRemote => "remote",
+        };
+
+        state.log.push(
+            LogKind::Info,
+            format!("kept the {} {} of {}", kept, field.name(), name),
+        );
+
+        if merge.is_resolved() {
+            let (_, merge) = self.merges.remove(merge_index);
+            state.apply_resolved_merge(merge)?;
+        }
+
+        if self.merges.is_empty() {
+            return Ok(ShouldReset::Yes);
+        }
+
+        self.selected.update_bounds(self.num_conflicts());
+        Ok(ShouldReset::No)
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = theme::block("Sync Conflicts");
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                BasicConstraint::MinLenRemaining(4, 4),
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let rows = self.merges.iter().flat_map(|(name, merge)| {
+            merge.conflicts().iter().map(move |conflict| {
+                let changed_last = if conflict.local_is_latest() {
+                    "Local"
+                } else {
+                    "Remote"
+                };
+
+                [
+                    Span::raw(name.as_str()),
+                    Span::raw(conflict.field.name()),
+                    Span::raw(conflict.local.as_deref().unwrap_or("none")),
+                    Span::raw(conflict.remote.as_deref().unwrap_or("none")),
+                    Span::raw(changed_last),
+                ]
+            })
+        });
+
+        let header = [
+            Span::raw("Series"),
+            Span::raw("Field"),
+            Span::raw("Local"),
+            Span::raw("Remote"),
+            Span::raw("Changed Last"),
+        ];
+
+        let widths = [
+            BasicConstraint::Percentage(25),
+            BasicConstraint::Percentage(15),
+            BasicConstraint::Percentage(25),
+            BasicConstraint::Percentage(20),
+            BasicConstraint::Percentage(15),
+        ];
+
+        let widget = SimpleTable::new(rows, widths)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", theme::list_selector(true)))
+            .select(Some(self.selected.get() as u16));
+
+        frame.render_widget(widget, layout[0]);
+
+        let hint = text::hint("L - Keep Local | R - Keep Remote | Esc - Decide Later");
+        let hint_widget = SimpleText::new(hint).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, layout[1]);
+    }
+}
+
+impl Component for SyncConflictsPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(ShouldReset::Yes),
+            KeyCode::Up => {
+                self.selected.decrement(self.num_conflicts());
+                Ok(ShouldReset::No)
+            }
+            KeyCode::Down => {
+                self.selected.increment(self.num_conflicts());
+                Ok(ShouldReset::No)
+            }
+            KeyCode::Char('l') => self.resolve_selected(Resolution::KeepLocal, state),
+            KeyCode::Char('r') => self.resolve_selected(Resolution::KeepRemote, state),
+            _ => Ok(ShouldReset::No),
+        }
+    }
+}
@@ -1,25 +1,33 @@
 use super::{Component, ShouldReset};
 use crate::try_opt_r;
 use crate::tui::component::input::{Input, InputFlags};
+use crate::tui::component::prompt::log::LogKind;
+use crate::tui::list_positions::{ListPositions, SavedList};
+use crate::tui::theme;
 use crate::tui::UIState;
-use crate::user::{RemoteType, UserInfo};
-use crate::{file::SerializedFile, key::Key};
+use crate::user::{RemoteType, UserInfo, EXPIRY_WARNING_DAYS};
+use crate::util;
 use crate::{
-    remote::{RemoteLogin, RemoteStatus},
-    tui::state::SharedState,
+    file::{self, SerializedFile},
+    key::Key,
 };
-use anime::remote::anilist::AniList;
-use anime::remote::{AccessToken, Remote, RemoteService};
+use crate::{remote::RemoteStatus, tui::state::SharedState};
+use anime::remote::anilist::{self, AniList};
+use anime::remote::kitsu::{self, Kitsu};
+use anime::remote::mal::{self, AuthState, CodeVerifier, Mal};
+use anime::remote::{AccessToken, IssuedToken, Remote, RemoteService};
 use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
 use crossterm::event::KeyCode;
 use std::process::Command;
+use std::sync::Arc;
+use tokio::task;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::text::Span;
 use tui::{backend::Backend, style::Style};
 use tui_utils::{
-    helpers::{block, style, text},
+    helpers::{style, text},
     layout::{BasicConstraint, SimpleLayout},
     list::{EnumListItems, SelectableEnum, WrappingIndex},
     widgets::{Fragment, SimpleList, SimpleTable, SimpleText, TextFragments},
@@ -27,47 +35,122 @@ use tui_utils::{
 
 pub struct UserPanel {
     selected_user: WrappingIndex,
+    /// Every profile that can be switched to, where None is the default profile.
+    profiles: Vec<Option<String>>,
+    selected_profile: WrappingIndex,
     selected_service: SelectableEnum<RemoteType>,
     token_input: Input,
+    /// The verifier and state of the last MyAnimeList auth URL that was opened.
+    mal_auth: Option<(CodeVerifier, AuthState)>,
     current_panel: SelectedPanel,
     state: SharedState,
 }
 
 impl UserPanel {
-    pub fn new(state: SharedState) -> Self {
+    pub fn new(state: &mut UIState, shared_state: SharedState) -> Self {
+        let selected_user = state
+            .list_positions
+            .get(&SavedList::Users, state.users.len());
+
+        let named_profiles = file::profiles().unwrap_or_else(|err| {
+            state.log.push_error(&err);
+            Vec::new()
+        });
+
+        let profiles = std::iter::once(None)
+            .chain(named_profiles.into_iter().map(Some))
+            .collect::<Vec<_>>();
+
+        let current_profile = file::profile();
+        let selected_profile = profiles
+            .iter()
+            .position(|profile| profile.as_deref() == current_profile)
+            .unwrap_or(0);
+
         Self {
-            selected_user: WrappingIndex::new(0),
+            selected_user: WrappingIndex::new(selected_user),
+            profiles,
+            selected_profile: WrappingIndex::new(selected_profile),
             selected_service: SelectableEnum::new(),
             token_input: Input::new(InputFlags::empty(), "Paste Token"),
+            mal_auth: None,
             current_panel: SelectedPanel::SelectUser,
-            state,
+            state: shared_state,
         }
     }
 
-    fn add_user_from_inputs(&mut self, state: &mut UIState) -> Result<()> {
-        use anime::remote::anilist::Auth;
+    /// Remember the selected user so it can be restored the next time the panel is opened.
+    pub fn save_list_position(&self, positions: &mut ListPositions) {
+        positions.save(SavedList::Users, self.selected_user.get());
+    }
 
-        let token_text = self.token_input.text();
+    fn add_user_from_inputs(&mut self, state: &mut UIState) -> Result<()> {
+        let token_text = self.token_input.text().to_string();
 
         if token_text.is_empty() {
             return Ok(());
         }
 
-        match self.selected_service.selected() {
-            service @ RemoteType::AniList => {
-                let token = AccessToken::encode(token_text);
-                let auth = Auth::retrieve(token.clone()).context("failed to get new user auth")?;
+        let service = self.selected_service.selected();
+
+        let login = match service {
+            RemoteType::AniList => {
+                let mut client = state.config.anilist_client()?;
+                client.set_rate_limit_hook(self.state.rate_limit_hook());
+                NewUserLogin::AniList(client)
+            }
+            RemoteType::Kitsu => NewUserLogin::Kitsu(state.config.kitsu_client()?),
+            RemoteType::Mal => {
+                let (verifier, auth_state) = self.mal_auth.as_ref().ok_or_else(|| {
+                    anyhow!("the auth URL must be opened before adding a MyAnimeList user")
+                })?;
+
+                let code = mal::code_from_redirect(&token_text, auth_state)?;
+
+                NewUserLogin::Mal {
+                    client: state.config.mal_client()?,
+                    code,
+                    redirect_uri: state.config.mal_redirect_uri()?.to_string(),
+                    verifier: verifier.clone(),
+                }
+            }
+        };
+
+        let shared_state = self.state.clone();
+
+        task::spawn(async move {
+            let result = login.login(&token_text).await;
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
 
-                let info = UserInfo::new(service, &auth.user.name);
+            let result = result.and_then(|user| {
+                let info = UserInfo::new(service, user.username);
 
-                state.remote = RemoteStatus::LoggedIn(AniList::Authenticated(auth).into());
-                state.users.add_and_set_last(info, token);
-                state.users.save().context("failed to save new user")?;
+                state.remote = RemoteStatus::LoggedIn(Arc::new(user.remote));
+                state
+                    .users
+                    .add_and_set_last(info, user.token, user.expires_at);
+                state.users.save().context("failed to save new user")
+            });
 
-                self.token_input.clear();
-                Ok(())
+            match result {
+                Ok(()) => {
+                    if service == RemoteType::AniList && state.series.is_empty() {
+                        state.log.push(
+                            LogKind::Info,
+                            "press I in the user panel to import the series on your list",
+                        );
+                    }
+
+                    state.request_entry_sync();
+                }
+                Err(err) => state.log.push_error(&err),
             }
-        }
+        });
+
+        self.token_input.clear();
+        Ok(())
     }
 
     fn selected_user<'a>(&'a self, state: &'a UIState) -> Option<(&'a UserInfo, &'a AccessToken)> {
@@ -84,10 +167,12 @@ impl UserPanel {
         let remote = state.remote.get_logged_in()?;
 
         if user.is_logged_in(remote) {
-            state.remote = RemoteStatus::LoggedIn(Remote::offline());
+            state.go_offline();
         }
 
         state.users.remove(&user);
+        state.user_links.remove(&user);
+        state.user_links.save()?;
 
         // Since our user table has been changed, we should make sure our selected user is still valid
         self.selected_user.update_bounds(state.users.len());
@@ -96,39 +181,116 @@ impl UserPanel {
     }
 
     fn login_as_selected_user(&mut self, state: &mut UIState) -> Result<()> {
-        let (info, token) = try_opt_r!(self.selected_user(state));
+        let (info, _) = try_opt_r!(self.selected_user(state));
+        let (login, linked) = try_opt_r!(state.users.logins(info, &state.user_links));
 
-        match info.service {
-            RemoteType::AniList => {
-                self.state.login_to_remote_async(RemoteLogin::AniList(
-                    info.username.clone(),
-                    token.clone(),
-                ));
+        self.state.login_to_remote_async(login, linked);
 
-                state.users.last_used = Some(info.to_owned());
-                state.users.save()?;
-            }
+        state.users.last_used = Some(info.to_owned());
+        state.users.save()
+    }
+
+    /// Import every series on the list of the logged in user that hasn't been added yet.
+    fn import_list(&self, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in_shared()?;
+
+        if !remote.has_list() {
+            return Err(anyhow!("must be logged in to import your list"));
         }
 
+        state.log.push(
+            LogKind::Info,
+            "importing your list, which may take a moment",
+        );
+
+        self.state.import_list_async(remote);
         Ok(())
     }
 
-    fn open_auth_url(&self) -> Result<()> {
+    /// Link the selected user to the current user, or unlink it if it already is.
+    ///
+    /// List updates made while logged in as the current user will also be sent to all of its linked users.
+    fn toggle_selected_user_link(&mut self, state: &mut UIState) -> Result<()> {
+        let (selected, _) = try_opt_r!(self.selected_user(state));
+        let selected = selected.to_owned();
+
+        let current = state
+            .users
+            .last_used
+            .clone()
+            .ok_or_else(|| anyhow!("must be logged in to link accounts"))?;
+
+        if selected == current {
+            return Err(anyhow!("cannot link an account to itself"));
+        }
+
+        state.user_links.toggle(&current, selected);
+        state.user_links.save()?;
+
+        // Logging in again will make the change take effect
+        if let Some((login, linked)) = state.users.logins(&current, &state.user_links) {
+            self.state.login_to_remote_async(login, linked);
+        }
+
+        Ok(())
+    }
+
+    /// Restart the program with the selected profile, unless it's the one already in use.
+    fn switch_to_selected_profile(&self, state: &mut UIState) -> bool {
+        let selected = match self.profiles.get(self.selected_profile.get()) {
+            Some(selected) => selected,
+            None => return false,
+        };
+
+        if selected.as_deref() == file::profile() {
+            return false;
+        }
+
+        state.switch_profile = Some(selected.clone());
+        true
+    }
+
+    /// Get ready to add `user` again with a new token, opening the auth URL of their service in the browser.
+    pub fn renew_login(&mut self, user: &UserInfo, state: &UIState) -> Result<()> {
+        while self.selected_service.selected() != user.service {
+            self.selected_service.increment();
+        }
+
+        self.current_panel = SelectedPanel::AddUser;
+        self.token_input.clear();
+
+        match user.service {
+            // Kitsu tokens are retrieved with the user's credentials, so there's nothing to open
+            RemoteType::Kitsu => Ok(()),
+            RemoteType::AniList | RemoteType::Mal => self.open_auth_url(state),
+        }
+    }
+
+    fn open_auth_url(&mut self, state: &UIState) -> Result<()> {
         let url = match self.selected_service.selected() {
             RemoteType::AniList => anime::remote::anilist::auth_url(crate::ANILIST_CLIENT_ID),
-        };
+            RemoteType::Kitsu => {
+                return Err(anyhow!(
+                    "Kitsu accounts are added with your email and password instead of an auth URL"
+                ))
+            }
+            RemoteType::Mal => {
+                let client_id = state.config.mal_client_id()?;
+                let redirect_uri = state.config.mal_redirect_uri()?;
 
-        #[cfg(target_os = "linux")]
-        let opener = "xdg-open";
-        #[cfg(target_os = "macos")]
-        let opener = "open";
-        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
-        compile_error!("must specify URL opener for this platform");
+                // These need to be kept around to check the redirect the user enters and exchange its code for a token
+                let (verifier, auth_state) = self
+                    .mal_auth
+                    .insert((CodeVerifier::generate()?, AuthState::generate()?));
+
+                mal::auth_url(client_id, redirect_uri, verifier, auth_state)
+            }
+        };
 
-        Command::new(opener)
+        Command::new(util::URL_OPENER)
             .arg(url)
             .spawn()
-            .with_context(|| anyhow!("failed to open URL in browser with {}", opener))
+            .with_context(|| anyhow!("failed to open URL in browser with {}", util::URL_OPENER))
             .map(|_| ())
     }
 
@@ -138,7 +300,7 @@ impl UserPanel {
     {
         let is_panel_selected = self.current_panel == SelectedPanel::AddUser;
 
-        let block = block::selectable("Add User", is_panel_selected);
+        let block = theme::selectable_block("Add User", is_panel_selected);
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -165,7 +327,7 @@ impl UserPanel {
         self.token_input.set_selected(is_panel_selected);
         self.token_input.draw(vert_split[0], frame);
 
-        let services_block = block::selectable("Service", is_panel_selected);
+        let services_block = theme::selectable_block("Service", is_panel_selected);
         let services_block_area = services_block.inner(vert_split[2]);
 
         frame.render_widget(services_block, vert_split[2]);
@@ -177,7 +339,7 @@ impl UserPanel {
             .map(Span::raw);
 
         let services_widget = SimpleList::new(services)
-            .highlight_symbol(Span::styled(">", style::list_selector(is_panel_selected)))
+            .highlight_symbol(Span::styled(">", theme::list_selector(is_panel_selected)))
             .select(Some(self.selected_service.index() as u16));
 
         frame.render_widget(services_widget, services_block_area);
@@ -200,7 +362,7 @@ impl UserPanel {
     {
         let is_panel_selected = self.current_panel == SelectedPanel::SelectUser;
 
-        let block = block::selectable(None, is_panel_selected);
+        let block = theme::selectable_block(None, is_panel_selected);
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -213,7 +375,7 @@ impl UserPanel {
                 // Spacer
                 BasicConstraint::Length(1),
                 // Hints
-                BasicConstraint::Length(5),
+                BasicConstraint::Length(9),
                 // Status Text
                 BasicConstraint::Length(2),
             ],
@@ -226,6 +388,10 @@ impl UserPanel {
             Fragment::Line,
             Fragment::span(text::hint("D - Remove account")),
             Fragment::Line,
+            Fragment::span(text::hint("L - Link to current account")),
+            Fragment::Line,
+            Fragment::span(text::hint("I - Import list from current account")),
+            Fragment::Line,
             Fragment::span(text::hint("Enter - Login as selected")),
         ];
 
@@ -234,7 +400,7 @@ impl UserPanel {
 
         frame.render_widget(key_hints_widget, layout[2]);
 
-        let yellow_text = |value| text::with_color(value, Color::Yellow);
+        let yellow_text = |value| text::with_color(value, theme::get().warning);
 
         match &state.remote {
             RemoteStatus::LoggingIn(username) => {
@@ -266,6 +432,13 @@ impl UserPanel {
     {
         let remote = state.remote.get_logged_in();
 
+        let linked_users = match &state.users.last_used {
+            Some(current) => state.user_links.linked_to(current),
+            None => &[],
+        };
+
+        let now = Utc::now().timestamp();
+
         let users = state.users.get().keys().map(|user| {
             let is_logged_in = remote
                 .as_ref()
@@ -273,33 +446,114 @@ impl UserPanel {
                 .unwrap_or(false);
 
             let style = if is_logged_in {
-                style::fg(Color::Blue)
+                style::fg(theme::get().info)
             } else {
                 Style::default()
             };
 
+            let linked = if linked_users.contains(user) {
+                "Yes"
+            } else {
+                ""
+            };
+
+            let expires = match state.users.days_until_expiry(user, now) {
+                Some(days) if days < 0 => Span::styled("Expired", style::fg(theme::get().error)),
+                Some(days) => {
+                    let style = if days < EXPIRY_WARNING_DAYS {
+                        style::fg(theme::get().warning)
+                    } else {
+                        style
+                    };
+
+                    let text = match days {
+                        0 => "Today".into(),
+                        1 => "1 day".into(),
+                        days => format!("{} days", days),
+                    };
+
+                    Span::styled(text, style)
+                }
+                None => Span::raw(""),
+            };
+
             [
                 Span::styled(user.username.as_str(), style),
                 Span::styled(user.service.as_str(), style),
+                Span::styled(linked, style),
+                expires,
             ]
         });
 
-        let header = [Span::raw("Username"), Span::raw("Service")];
+        let header = [
+            Span::raw("Username"),
+            Span::raw("Service"),
+            Span::raw("Linked"),
+            Span::raw("Expires"),
+        ];
 
         let layout = [
-            BasicConstraint::Percentage(50),
-            BasicConstraint::Percentage(50),
+            BasicConstraint::Percentage(35),
+            BasicConstraint::Percentage(25),
+            BasicConstraint::Percentage(15),
+            BasicConstraint::Percentage(25),
         ];
 
         let users_widget = SimpleTable::new(users, layout)
             .header(&header)
-            .highlight_symbol(Span::styled(">", style::list_selector(is_selected)))
+            .highlight_symbol(Span::styled(">", theme::list_selector(is_selected)))
             .select(Some(self.selected_user.get() as u16));
 
         frame.render_widget(users_widget, rect);
     }
 
+    fn draw_profile_panel<B>(&self, rect: Rect, frame: &mut Frame<B>)
+    where
+        B: Backend,
+    {
+        let is_panel_selected = self.current_panel == SelectedPanel::SelectProfile;
+
+        let block = theme::selectable_block("Profiles", is_panel_selected);
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                BasicConstraint::MinLenRemaining(1, 1),
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let current_profile = file::profile();
+
+        let profiles = self.profiles.iter().map(|profile| {
+            let name = profile.as_deref().unwrap_or("Default");
+
+            if profile.as_deref() == current_profile {
+                Span::styled(name, style::fg(theme::get().info))
+            } else {
+                Span::raw(name)
+            }
+        });
+
+        let profiles_widget = SimpleList::new(profiles)
+            .highlight_symbol(Span::styled(">", theme::list_selector(is_panel_selected)))
+            .select(Some(self.selected_profile.get() as u16));
+
+        frame.render_widget(profiles_widget, layout[0]);
+
+        let hint = SimpleText::new(text::hint("Enter - Restart with selected profile"))
+            .alignment(Alignment::Center);
+
+        frame.render_widget(hint, layout[1]);
+    }
+
     pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        /// The most profiles that will be shown before the list has to scroll.
+        const MAX_PROFILES_SHOWN: usize = 4;
+
         let horiz_split = SimpleLayout::new(Direction::Horizontal).split(
             rect,
             [
@@ -308,7 +562,19 @@ impl UserPanel {
             ],
         );
 
-        self.draw_user_selection_panel(state, horiz_split[0], frame);
+        // The profile list needs room for its borders and hint
+        let profiles_height = self.profiles.len().min(MAX_PROFILES_SHOWN) as u16 + 3;
+
+        let left_split = SimpleLayout::new(Direction::Vertical).split(
+            horiz_split[0],
+            [
+                BasicConstraint::MinLenRemaining(16, profiles_height),
+                BasicConstraint::Length(profiles_height),
+            ],
+        );
+
+        self.draw_user_selection_panel(state, left_split[0], frame);
+        self.draw_profile_panel(left_split[1], frame);
         self.draw_add_user_panel(horiz_split[1], frame);
     }
 }
@@ -343,8 +609,30 @@ impl Component for UserPanel {
                         self.remove_selected_user(state)?;
                         Ok(ShouldReset::No)
                     }
+                    KeyCode::Char('l') => {
+                        self.toggle_selected_user_link(state)?;
+                        Ok(ShouldReset::No)
+                    }
+                    KeyCode::Char('i') => {
+                        self.import_list(state)?;
+                        Ok(ShouldReset::No)
+                    }
                     KeyCode::Char('o') => {
-                        state.remote = RemoteStatus::LoggedIn(Remote::offline());
+                        state.go_offline();
+                        Ok(ShouldReset::Yes)
+                    }
+                    _ => Ok(ShouldReset::No),
+                },
+                SelectedPanel::SelectProfile => match *key {
+                    KeyCode::Up => {
+                        self.selected_profile.decrement(self.profiles.len());
+                        Ok(ShouldReset::No)
+                    }
+                    KeyCode::Down => {
+                        self.selected_profile.increment(self.profiles.len());
+                        Ok(ShouldReset::No)
+                    }
+                    KeyCode::Enter if self.switch_to_selected_profile(state) => {
                         Ok(ShouldReset::Yes)
                     }
                     _ => Ok(ShouldReset::No),
@@ -360,7 +648,7 @@ impl Component for UserPanel {
                         Ok(ShouldReset::No)
                     }
                     KeyCode::Char('o') if key.ctrl_pressed() => {
-                        self.open_auth_url()?;
+                        self.open_auth_url(state)?;
                         Ok(ShouldReset::No)
                     }
                     KeyCode::Enter => {
@@ -377,16 +665,120 @@ impl Component for UserPanel {
     }
 }
 
+/// The client needed to log in a new user.
+///
+/// These depend on the config, so they need to be created before logging in off the UI thread.
+enum NewUserLogin {
+    AniList(anilist::Client),
+    Kitsu(kitsu::Client),
+    /// MyAnimeList logins exchange the authorization code from the redirect the user entered instead of the entered text.
+    Mal {
+        client: mal::Client,
+        code: String,
+        redirect_uri: String,
+        verifier: CodeVerifier,
+    },
+}
+
+/// A user that was just logged in to for the first time.
+struct NewUser {
+    remote: Remote,
+    username: String,
+    token: AccessToken,
+    /// When `token` expires, in seconds since the Unix epoch.
+    expires_at: Option<i64>,
+}
+
+impl NewUser {
+    fn new<R>(remote: R, username: String, issued: IssuedToken) -> Self
+    where
+        R: Into<Remote>,
+    {
+        let expires_at = issued
+            .expires_in
+            .map(|expires_in| Utc::now().timestamp() + expires_in.as_secs() as i64);
+
+        Self {
+            remote: remote.into(),
+            username,
+            token: issued.token,
+            expires_at,
+        }
+    }
+}
+
+impl NewUserLogin {
+    /// Log in with the `token_text` entered by the user.
+    async fn login(self, token_text: &str) -> Result<NewUser> {
+        match self {
+            Self::AniList(client) => {
+                use anime::remote::anilist::Auth;
+
+                let token = AccessToken::encode(token_text);
+                let auth = Auth::retrieve(token.clone(), client)
+                    .await
+                    .context("failed to get new user auth")?;
+                let username = auth.user.name.clone();
+
+                Ok(NewUser {
+                    remote: AniList::Authenticated(auth).into(),
+                    username,
+                    expires_at: anilist::token_expiry(&token).map(|expiry| expiry as i64),
+                    token,
+                })
+            }
+            Self::Kitsu(client) => {
+                use anime::remote::kitsu::Auth;
+
+                // Kitsu has no way to authorize applications through the browser, so we need the user's credentials instead
+                let (email, password) = token_text.split_once(':').ok_or_else(|| {
+                    anyhow!("Kitsu accounts must be entered as <email>:<password>")
+                })?;
+
+                let issued = kitsu::login(&client, email, password)
+                    .await
+                    .context("failed to log in")?;
+                let auth = Auth::retrieve(issued.token.clone(), client)
+                    .await
+                    .context("failed to get new user auth")?;
+                let username = auth.user.name.clone();
+
+                Ok(NewUser::new(Kitsu::Authenticated(auth), username, issued))
+            }
+            Self::Mal {
+                client,
+                code,
+                redirect_uri,
+                verifier,
+            } => {
+                use anime::remote::mal::Auth;
+
+                let issued = mal::exchange_code(&client, &code, &redirect_uri, &verifier)
+                    .await
+                    .context("failed to exchange authorization code")?;
+                let auth = Auth::retrieve(issued.token.clone(), client)
+                    .await
+                    .context("failed to get new user auth")?;
+                let username = auth.user.name.clone();
+
+                Ok(NewUser::new(Mal::Authenticated(auth), username, issued))
+            }
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq)]
 enum SelectedPanel {
     SelectUser,
+    SelectProfile,
     AddUser,
 }
 
 impl SelectedPanel {
     fn next(self) -> Self {
         match self {
-            Self::SelectUser => Self::AddUser,
+            Self::SelectUser => Self::SelectProfile,
+            Self::SelectProfile => Self::AddUser,
             Self::AddUser => Self::SelectUser,
         }
     }
@@ -1,7 +1,13 @@
+use crate::series::entry;
+use crate::series::rename::Template;
+use crate::series::stream;
 use crate::tui::component::input::Input;
 use crate::tui::component::Component;
+use crate::tui::list_view::{ListFilter, SeriesSort};
+use crate::tui::theme;
 use crate::tui::UIState;
 use crate::{config::Config, key::Key};
+use anime::SeriesKind;
 use anyhow::{anyhow, Result};
 use crossterm::event::KeyCode;
 use smallvec::{smallvec, SmallVec};
@@ -9,11 +15,10 @@ use std::convert::TryFrom;
 use std::result;
 use tui::backend::Backend;
 use tui::layout::Rect;
-use tui::style::Color;
 use tui::text::Span;
 use tui::Frame;
 use tui_utils::{
-    helpers::{block, style},
+    helpers::style,
     widgets::{Fragment, TextFragments},
     wrap,
 };
@@ -85,7 +90,7 @@ impl CommandPrompt {
     }
 
     pub fn draw<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
-        let block = block::with_borders("Enter Command");
+        let block = theme::block("Enter Command");
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -119,7 +124,7 @@ impl CommandPrompt {
         if let Some(hint_cmd) = &self.hint_cmd {
             let span = Span::styled(
                 hint_cmd.remaining_name_and_usage(),
-                style::fg(Color::DarkGray),
+                style::fg(theme::get().inactive),
             );
 
             items.push(Fragment::span(span));
@@ -289,9 +294,53 @@ pub enum Command {
     Score(String),
     /// Set the watch status of the selected season.
     Status(anime::remote::Status),
+    /// Shift the episode numbers of the selected season.
+    EpisodeOffset(i16),
+    /// Set which episode category of the selected season to watch.
+    EpisodeCategory(Option<SeriesKind>),
+    /// Set whether the episodes of the selected season are numbered from the first season of the franchise.
+    AbsoluteNumbering(bool),
+    /// Recompute the episode offsets of the seasons merged into the selected season and relink their episodes.
+    RelinkSeasons,
+    /// Set whether the folder of the selected season is watched through as one series, moving on to each season merged into it.
+    MergedSeasons(bool),
+    /// Store the info of every series so it can be looked up while offline.
+    Prefetch,
+    /// Revert the most recent change made to a series.
+    Undo,
+    /// Set the RSS feed to check for new releases of the selected season.
+    Feed(Option<String>),
+    /// Select the watched series that should be watched next.
+    Next,
+    /// Preview renaming the episode files of the selected season with a template.
+    Rename(Template),
+    /// Add or remove another name the selected season can be selected by.
+    Alias(NameAction),
+    /// Add or remove a custom title of the selected season, used to find its folder and episodes.
+    Synonym(NameAction),
+    /// Start or stop rewatching the selected season without changing its list entry.
+    LocalRewatch(bool),
+    /// Set the subtitle languages to prefer when playing the selected season.
+    SubLang(Option<String>),
+    /// Set the audio languages to prefer when playing the selected season.
+    AudioLang(Option<String>),
+    /// Set the pattern used to find external subtitle files for the episodes of the selected season.
+    SubFiles(Option<String>),
+    /// Set the URL template to stream episodes of the selected season from when they aren't on disk.
+    Stream(Option<String>),
+    /// Set the length of each episode of the selected season in minutes, or use the one from the remote service when None.
+    EpisodeLength(Option<i16>),
+    /// Review and add every folder in the series directory that no series has been added for.
+    AddAll,
+    /// Change how the series list is ordered.
+    Sort(SeriesSort),
+    /// Only show series that pass a filter in the series list, or every series when None.
+    Filter(Option<ListFilter>),
+    /// Add or remove a tag of the selected series.
+    Tag(NameAction),
 }
 
-impl_command_matching!(Command, 6,
+impl_command_matching!(Command, 28,
     PlayerArgs(_) => {
         name: "args",
         usage: "<player args>",
@@ -339,21 +388,292 @@ impl_command_matching!(Command, 6,
         usage: "<w, watching | c, completed | h, hold | d, drop | p, plan | r, rewatch>",
         min_args: 1,
         fn: |args: &[&str], _| {
-            use anime::remote::Status;
-
-            let status = match args[0].to_ascii_lowercase().as_ref() {
-                "w" | "watching" => Status::Watching,
-                "c" | "completed" => Status::Completed,
-                "h" | "hold" => Status::OnHold,
-                "d" | "drop" => Status::Dropped,
-                "p" | "plan" => Status::PlanToWatch,
-                "r" | "rewatch" => Status::Rewatching,
+            let status = entry::parse_status(args[0])
+                .ok_or_else(|| anyhow!("unknown argument: {}", args[0]))?;
+
+            Ok(Command::Status(status))
+        },
+    },
+    EpisodeOffset(_) => {
+        name: "offset",
+        usage: "<episode offset>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let offset = args[0]
+                .parse()
+                .map_err(|_| anyhow!("invalid episode offset: {}", args[0]))?;
+
+            Ok(Command::EpisodeOffset(offset))
+        },
+    },
+    EpisodeCategory(_) => {
+        name: "category",
+        usage: "<s, season | m, movie | sp, special | ova | ona | mu, music | d, default>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let category = match args[0].to_ascii_lowercase().as_ref() {
+                "s" | "season" => Some(SeriesKind::Season),
+                "m" | "movie" => Some(SeriesKind::Movie),
+                "sp" | "special" => Some(SeriesKind::Special),
+                "ova" => Some(SeriesKind::OVA),
+                "ona" => Some(SeriesKind::ONA),
+                "mu" | "music" => Some(SeriesKind::Music),
+                "d" | "default" => None,
                 _ => {
                     return Err(anyhow!("unknown argument: {}", args[0]))
                 }
             };
 
-            Ok(Command::Status(status))
+            Ok(Command::EpisodeCategory(category))
+        },
+    },
+    AbsoluteNumbering(_) => {
+        name: "absolute",
+        usage: "<on | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let enabled = match args[0].to_ascii_lowercase().as_ref() {
+                "on" => true,
+                "off" => false,
+                _ => {
+                    return Err(anyhow!("unknown argument: {}", args[0]))
+                }
+            };
+
+            Ok(Command::AbsoluteNumbering(enabled))
+        },
+    },
+    RelinkSeasons => {
+        name: "relinkseasons",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::RelinkSeasons),
+    },
+    MergedSeasons(_) => {
+        name: "merged",
+        usage: "<on | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let enabled = match args[0].to_ascii_lowercase().as_ref() {
+                "on" => true,
+                "off" => false,
+                _ => {
+                    return Err(anyhow!("unknown argument: {}", args[0]))
+                }
+            };
+
+            Ok(Command::MergedSeasons(enabled))
+        },
+    },
+    Prefetch => {
+        name: "prefetch",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::Prefetch),
+    },
+    Undo => {
+        name: "undo",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::Undo),
+    },
+    Feed(_) => {
+        name: "feed",
+        usage: "<url | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let url = match args[0] {
+                "off" => None,
+                url => Some(url.to_string()),
+            };
+
+            Ok(Command::Feed(url))
+        },
+    },
+    Next => {
+        name: "next",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::Next),
+    },
+    Rename(_) => {
+        name: "rename",
+        usage: "[template]",
+        min_args: 0,
+        fn: |args: &[&str], config: &Config| {
+            let template = if args.is_empty() {
+                config.episode.rename_template.parse()
+            } else {
+                args.join(" ").parse()
+            };
+
+            Ok(Command::Rename(template?))
+        },
+    },
+    Alias(_) => {
+        name: "alias",
+        usage: "<add | rm> <name>",
+        min_args: 2,
+        fn: |args: &[&str], _| {
+            let alias = args[1..].join(" ");
+
+            let action = match args[0].to_ascii_lowercase().as_ref() {
+                "add" => NameAction::Add(alias),
+                "rm" | "remove" => NameAction::Remove(alias),
+                _ => return Err(anyhow!("unknown argument: {}", args[0])),
+            };
+
+            Ok(Command::Alias(action))
+        },
+    },
+    Synonym(_) => {
+        name: "synonym",
+        usage: "<add | rm> <title>",
+        min_args: 2,
+        fn: |args: &[&str], _| {
+            let synonym = args[1..].join(" ");
+
+            let action = match args[0].to_ascii_lowercase().as_ref() {
+                "add" => NameAction::Add(synonym),
+                "rm" | "remove" => NameAction::Remove(synonym),
+                _ => return Err(anyhow!("unknown argument: {}", args[0])),
+            };
+
+            Ok(Command::Synonym(action))
+        },
+    },
+    LocalRewatch(_) => {
+        name: "rewatch",
+        usage: "<local | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let enabled = match args[0].to_ascii_lowercase().as_ref() {
+                "local" => true,
+                "off" => false,
+                _ => {
+                    return Err(anyhow!("unknown argument: {}", args[0]))
+                }
+            };
+
+            Ok(Command::LocalRewatch(enabled))
+        },
+    },
+    SubLang(_) => {
+        name: "sublang",
+        usage: "<languages | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let langs = match args[0] {
+                "off" => None,
+                langs => Some(langs.to_string()),
+            };
+
+            Ok(Command::SubLang(langs))
+        },
+    },
+    AudioLang(_) => {
+        name: "audiolang",
+        usage: "<languages | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let langs = match args[0] {
+                "off" => None,
+                langs => Some(langs.to_string()),
+            };
+
+            Ok(Command::AudioLang(langs))
+        },
+    },
+    SubFiles(_) => {
+        name: "subfiles",
+        usage: "<pattern | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let pattern = match args {
+                ["off"] => None,
+                pattern => Some(pattern.join(" ")),
+            };
+
+            Ok(Command::SubFiles(pattern))
+        },
+    },
+    Stream(_) => {
+        name: "stream",
+        usage: "<url template | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let template = match args[0] {
+                "off" => None,
+                template => {
+                    // Catch mistakes in the template now rather than when an episode is played
+                    stream::url(template, 0, 1)?;
+                    Some(template.to_string())
+                }
+            };
+
+            Ok(Command::Stream(template))
+        },
+    },
+    EpisodeLength(_) => {
+        name: "eplength",
+        usage: "<minutes | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            if args[0].eq_ignore_ascii_case("off") {
+                return Ok(Command::EpisodeLength(None));
+            }
+
+            let mins = args[0]
+                .parse()
+                .ok()
+                .filter(|&mins: &i16| mins > 0)
+                .ok_or_else(|| anyhow!("invalid episode length: {}", args[0]))?;
+
+            Ok(Command::EpisodeLength(Some(mins)))
+        },
+    },
+    AddAll => {
+        name: "addall",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::AddAll),
+    },
+    Sort(_) => {
+        name: "sort",
+        usage: "<name | progress | score | last-watched | airing>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let sort = args[0].parse().map_err(|err: String| anyhow!(err))?;
+            Ok(Command::Sort(sort))
+        },
+    },
+    Filter(_) => {
+        name: "filter",
+        usage: "<status:<watching | completed | hold | drop | plan | rewatch> | tag:<tag> | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            if args[0].eq_ignore_ascii_case("off") {
+                return Ok(Command::Filter(None));
+            }
+
+            let filter = args[0].parse().map_err(|err: String| anyhow!(err))?;
+            Ok(Command::Filter(Some(filter)))
+        },
+    },
+    Tag(_) => {
+        name: "tag",
+        usage: "<add | rm> <tag>",
+        min_args: 2,
+        fn: |args: &[&str], _| {
+            let tag = args[1..].join(" ");
+
+            let action = match args[0].to_ascii_lowercase().as_ref() {
+                "add" => NameAction::Add(tag),
+                "rm" | "remove" => NameAction::Remove(tag),
+                _ => return Err(anyhow!("unknown argument: {}", args[0])),
+            };
+
+            Ok(Command::Tag(action))
         },
     },
 );
@@ -371,6 +691,11 @@ impl Command {
         })
         .map(|(_, cmd)| cmd)
     }
+
+    /// Returns true if the command should be applied to every marked series when there are any.
+    pub fn applies_to_marked(&self) -> bool {
+        matches!(self, Self::Score(_) | Self::Status(_))
+    }
 }
 
 /// Indicates which way to advance the episode count of a season.
@@ -395,6 +720,13 @@ impl TryFrom<&str> for ProgressDirection {
     }
 }
 
+/// A name to add to or remove from a season, such as an alias or synonym.
+#[cfg_attr(test, derive(Debug))]
+pub enum NameAction {
+    Add(String),
+    Remove(String),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -470,6 +802,49 @@ mod tests {
         );
 
         test_command!("status watching", Command::Status(Status::Watching));
+        test_command!("offset -1", Command::EpisodeOffset(-1));
+        test_command!(
+            "category ova",
+            Command::EpisodeCategory(Some(SeriesKind::OVA))
+        );
+        test_command!("category default", Command::EpisodeCategory(None));
+        test_command!("absolute on", Command::AbsoluteNumbering(true));
+        test_command!("merged off", Command::MergedSeasons(false));
+        test_command!("undo", Command::Undo);
+        test_command!("feed off", Command::Feed(None));
+        test_command!("next", Command::Next);
+        test_command!("rename", Command::Rename(_));
+        test_command!("rename {title} E{episode}{ext}", Command::Rename(_));
+        test_command!("alias add kaguya", Command::Alias(NameAction::Add(_)));
+        test_command!("alias rm kaguya", Command::Alias(NameAction::Remove(_)));
+        test_command!(
+            "synonym add frieren s2",
+            Command::Synonym(NameAction::Add(_))
+        );
+        test_command!("rewatch local", Command::LocalRewatch(true));
+        test_command!("sort last-watched", Command::Sort(SeriesSort::LastWatched));
+        test_command!(
+            "filter status:watching",
+            Command::Filter(Some(ListFilter::Status(Status::Watching)))
+        );
+        test_command!(
+            "filter tag:rewatch-queue",
+            Command::Filter(Some(ListFilter::Tag(_)))
+        );
+        test_command!("tag add seasonal-2024", Command::Tag(NameAction::Add(_)));
+        test_command!("filter off", Command::Filter(None));
+        test_command!("sublang off", Command::SubLang(None));
+        test_command!(
+            "stream https://example.com/{id}/{episode}",
+            Command::Stream(Some(_))
+        );
+        test_command!("audiolang ja,jpn", Command::AudioLang(Some(_)));
+        test_command!(
+            "subfiles \"[*] Series Title - #\"",
+            Command::SubFiles(Some(_))
+        );
+        test_command!("eplength 45", Command::EpisodeLength(Some(45)));
+        test_command!("eplength off", Command::EpisodeLength(None));
     }
 
     #[test]
@@ -1,113 +1,113 @@
-use std::collections::VecDeque;
-
+use crate::key::Key;
+use crate::logging::{StatusEvent, StatusEvents, STATUS_TARGET};
+use crate::tui::theme;
 use anyhow::Error;
+use tracing::Level;
 use tui::backend::Backend;
 use tui::layout::Rect;
-use tui::style::Color;
 use tui::text::Span;
 use tui::Frame;
-use tui_utils::{
-    helpers::{block, style},
-    widgets::Fragment,
-    wrap,
-};
+use tui_utils::{helpers::style, widgets::Fragment, wrap};
 
 #[derive(Copy, Clone)]
 pub enum LogKind {
     Error,
     Context,
+    Info,
 }
 
 impl<'a> Into<Span<'a>> for LogKind {
     fn into(self) -> Span<'a> {
         match self {
-            Self::Error => Span::styled("error: ", style::fg(Color::Red)),
-            Self::Context => Span::styled("^ ", style::fg(Color::Yellow)),
+            Self::Error => Span::styled("error: ", style::fg(theme::get().error)),
+            Self::Context => Span::styled("^ ", style::fg(theme::get().warning)),
+            Self::Info => Span::styled("info: ", style::fg(theme::get().info)),
         }
     }
 }
 
-pub struct LogEntry<'a> {
-    kind: LogKind,
-    message: Span<'a>,
-}
-
-impl<'a> LogEntry<'a> {
-    fn new<S>(kind: LogKind, message: S) -> Self
-    where
-        S: Into<Span<'a>>,
-    {
-        Self {
-            kind,
-            message: message.into(),
+impl LogKind {
+    pub fn from_event(event: &StatusEvent) -> Self {
+        match event.level {
+            Level::ERROR if event.is_context => Self::Context,
+            Level::ERROR | Level::WARN => Self::Error,
+            _ => Self::Info,
         }
     }
-
-    fn as_fragments(&self) -> [Fragment<'a>; 2] {
-        [
-            Fragment::span(self.kind),
-            Fragment::span(self.message.clone()),
-        ]
-    }
-}
-
-impl<'a> Into<[Fragment<'a>; 2]> for &'a LogEntry<'a> {
-    fn into(self) -> [Fragment<'a>; 2] {
-        self.as_fragments()
-    }
 }
 
 /// A scrolling status log.
-pub struct Log<'a> {
-    items: VecDeque<LogEntry<'a>>,
-    max_items: u8,
+///
+/// Messages are sent as events to [`STATUS_TARGET`] so they're also written to the log file, and are shown once the logger
+/// collects them.
+pub struct Log {
+    events: StatusEvents,
     title: String,
 }
 
-impl<'a> Log<'a> {
-    pub fn new(max_items: u8) -> Self {
-        let title = format!(
-            "Error Log [press '{}' for command entry]",
-            super::COMMAND_KEY
-        );
+impl Log {
+    /// The number of the most recent events that are shown.
+    const MAX_SHOWN: usize = 15;
 
+    pub fn new(events: StatusEvents, command_key: Key) -> Self {
         Self {
-            items: VecDeque::with_capacity(max_items as usize),
-            max_items,
-            title,
+            events,
+            title: Self::title(command_key),
         }
     }
 
+    fn title(command_key: Key) -> String {
+        format!("Error Log [press '{}' for command entry]", command_key)
+    }
+
+    /// Returns every event that has been sent to the log and not yet removed, including those that aren't shown.
+    pub fn events(&self) -> &StatusEvents {
+        &self.events
+    }
+
+    /// Update the key shown in the title for entering commands.
+    pub fn set_command_key(&mut self, command_key: Key) {
+        self.title = Self::title(command_key);
+    }
+
     pub fn push<S>(&mut self, kind: LogKind, msg: S)
     where
-        S: Into<Span<'a>>,
+        S: AsRef<str>,
     {
-        while self.items.len() >= self.max_items as usize {
-            self.items.pop_front();
-        }
+        let msg = msg.as_ref();
 
-        let entry = LogEntry::new(kind, msg);
-        self.items.push_back(entry);
+        match kind {
+            LogKind::Error => tracing::error!(target: STATUS_TARGET, "{}", msg),
+            LogKind::Context => tracing::error!(target: STATUS_TARGET, context = true, "{}", msg),
+            LogKind::Info => tracing::info!(target: STATUS_TARGET, "{}", msg),
+        }
     }
 
     pub fn push_error(&mut self, err: &Error) {
-        self.push(LogKind::Error, format!("{}", err));
+        self.push(LogKind::Error, err.to_string());
 
         for cause in err.chain().skip(1) {
-            self.push(LogKind::Context, format!("{}", cause));
+            self.push(LogKind::Context, cause.to_string());
         }
     }
 
     pub fn draw<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
-        let block = block::with_borders(self.title.as_str());
+        let block = theme::block(self.title.as_str());
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
 
-        let items = self
-            .items
+        let events = self.events.lock();
+
+        let items = events
             .iter()
-            .map(LogEntry::as_fragments)
+            .skip(events.len().saturating_sub(Self::MAX_SHOWN))
+            .map(|event| {
+                [
+                    Fragment::span(LogKind::from_event(event)),
+                    Fragment::span(Span::raw(event.message.as_str())),
+                ]
+            })
             .map(wrap::by_newlines)
             .map(|fragments| wrap::by_letters(fragments, block_area.width));
 
@@ -1,4 +1,2 @@
 pub mod command;
 pub mod log;
-
-pub const COMMAND_KEY: char = ':';
@@ -1,70 +1,113 @@
 use crate::tui::state::{InputState, UIState};
+use crate::tui::theme;
 use crate::{key::Key, series::LoadedSeries};
-use anime::remote::Status;
-use crossterm::event::KeyCode;
+use smallvec::SmallVec;
+use std::borrow::Cow;
 use tui::backend::Backend;
 use tui::layout::Rect;
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::text::Span;
-use tui_utils::{
-    helpers::{block, style, text},
-    widgets::SimpleList,
-};
+use tui_utils::{helpers::text, widgets::SimpleList};
 
 pub struct SeriesList;
 
 impl SeriesList {
-    fn series_text(series: &LoadedSeries) -> Span {
-        match series {
-            LoadedSeries::Complete(series) => {
-                let color = match series.data.entry.status() {
-                    Status::Watching | Status::Rewatching => Color::Blue,
-                    Status::Completed => Color::Green,
-                    Status::OnHold => Color::Yellow,
-                    Status::Dropped => Color::Red,
-                    Status::PlanToWatch => Color::Gray,
-                };
-
-                text::with_color(series.data.config.nickname.as_str(), color)
-            }
-            LoadedSeries::Partial(data, _) => {
-                text::with_color(data.config.nickname.as_str(), Color::LightRed)
-            }
-            LoadedSeries::None(cfg, _) => text::with_color(cfg.nickname.as_str(), Color::LightRed),
+    fn series_text<'a>(series: &'a LoadedSeries, state: &UIState) -> Span<'a> {
+        let color = match series {
+            LoadedSeries::Complete(series) => theme::get().status(series.data.entry.status()),
+            LoadedSeries::Unscanned(_) => theme::get().inactive,
+            LoadedSeries::Partial(..) | LoadedSeries::None(..) => theme::get().error,
+        };
+
+        let nickname = series.nickname();
+
+        if state.marked_series.contains(&series.config().id) {
+            text::bold_with(format!("* {}", nickname), |style| style.fg(color))
+        } else {
+            text::with_color(nickname, color)
         }
     }
 
     pub fn process_key(key: Key, state: &mut UIState) {
-        if !matches!(*key, KeyCode::Up | KeyCode::Down) {
-            return;
-        }
+        let keys = &state.config.tui.keys;
 
-        match *key {
-            KeyCode::Up => state.series.dec_selected(),
-            KeyCode::Down => state.series.inc_selected(),
-            _ => (),
+        if key == keys.list_up {
+            state.select_shown_series(false);
+        } else if key == keys.list_down {
+            state.select_shown_series(true);
         }
-
-        state.init_selected_series();
     }
 
     pub fn draw<B: Backend>(state: &UIState, rect: Rect, frame: &mut Frame<B>) {
-        let highlight_style = match state.input_state {
-            InputState::Idle => style::italic().fg(Color::Green),
-            _ => style::italic().fg(Color::DarkGray),
+        let highlight_style = theme::list_selector(state.input_state == InputState::Idle);
+
+        let unscanned = state
+            .series
+            .iter()
+            .filter(|series| series.is_unscanned())
+            .count();
+
+        // Series are drawn as inactive until their episodes are scanned, so the title explains why
+        let mut details = SmallVec::<[String; 3]>::new();
+
+        if let Some(status) = state.list_view.status {
+            details.push(status.to_string());
+        }
+
+        if let Some(tag) = &state.list_view.tag {
+            details.push(format!("#{}", tag));
+        }
+
+        if unscanned > 0 {
+            details.push(format!("scanning {}", unscanned));
+        }
+
+        let title = if details.is_empty() {
+            Cow::Borrowed("Series")
+        } else {
+            Cow::Owned(format!("Series ({})", details.join(", ")))
         };
 
-        let block = block::with_borders("Series");
+        let block = theme::block(title.as_ref());
         let list_area = block.inner(rect);
 
-        let series_names = state.series.iter().map(Self::series_text);
+        frame.render_widget(block, rect);
+
+        // Only show the series matching the current search if there is one
+        if let Some(filter) = &state.series_filter {
+            let series_names = filter
+                .iter()
+                .filter_map(|&index| state.series.get(index))
+                .map(|series| Self::series_text(series, state));
+
+            let list = SimpleList::new(series_names)
+                .select(filter.index() as u16)
+                .highlight_symbol(Span::styled(">", highlight_style));
+
+            frame.render_widget(list, list_area);
+            return;
+        }
+
+        let selected = state.series.index();
+
+        // The selected series is always shown, as it can be selected by other means than the series list
+        let shown = state
+            .series
+            .iter()
+            .enumerate()
+            .filter(|&(index, series)| index == selected || state.list_view.shows(series))
+            .collect::<Vec<_>>();
+
+        let selected_row = shown.iter().position(|&(index, _)| index == selected);
+
+        let series_names = shown
+            .into_iter()
+            .map(|(_, series)| Self::series_text(series, state));
 
         let list = SimpleList::new(series_names)
-            .select(state.series.index() as u16)
+            .select(selected_row.map(|row| row as u16))
             .highlight_symbol(Span::styled(">", highlight_style));
 
-        frame.render_widget(block, rect);
         frame.render_widget(list, list_area);
     }
 }
@@ -0,0 +1,50 @@
+use crate::file::SaveDir;
+use anyhow::{Context, Result};
+use std::fs;
+use std::io;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+
+/// A socket the TUI listens on while it's open, so another TUI opened with the same profile can tell it isn't the only one.
+///
+/// Each TUI only sees the changes it made itself, so two of them can overwrite what the other saved.
+/// Other commands, like `anup watch`, don't keep anything loaded and are safe to run alongside the TUI.
+pub struct InstanceSocket {
+    path: PathBuf,
+    #[allow(dead_code)]
+    listener: UnixListener,
+}
+
+impl InstanceSocket {
+    /// Start listening on the socket, or return None if another TUI already is.
+    pub fn bind() -> Result<Option<Self>> {
+        let path = Self::validated_path().context("getting path")?;
+
+        if UnixStream::connect(&path).is_ok() {
+            return Ok(None);
+        }
+
+        // A TUI that didn't exit cleanly leaves its socket behind, which has to be removed before it can be bound again
+        match fs::remove_file(&path) {
+            Ok(()) => (),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+            Err(err) => return Err(err).context("removing old socket"),
+        }
+
+        let listener = UnixListener::bind(&path).context("binding socket")?;
+
+        Ok(Some(Self { path, listener }))
+    }
+
+    fn validated_path() -> Result<PathBuf> {
+        let mut path = SaveDir::LocalData.validated_dir_path()?.to_path_buf();
+        path.push("tui.sock");
+        Ok(path)
+    }
+}
+
+impl Drop for InstanceSocket {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
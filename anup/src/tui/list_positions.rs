@@ -0,0 +1,102 @@
+use anime::remote::anilist::AiringSeason;
+use std::collections::HashMap;
+
+/// A list in a panel that is recreated every time it's opened.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SavedList {
+    Users,
+    History,
+    Log,
+    Downloads,
+    Search,
+    Clean,
+    BatchAdd,
+    SyncConflicts,
+    Recommendations,
+    /// The episodes of the series with the given config ID.
+    Episodes(i32),
+    /// The files of the series with the given config ID that would be renamed.
+    Rename(i32),
+    /// The seasons merged into the series with the given config ID.
+    SplitSeries(i32),
+    /// The series related to the one with the given remote ID.
+    Relations(i32),
+    /// The series that premiered in a season.
+    Seasonal(AiringSeason),
+    /// The results of browsing for the given name.
+    BrowseSeries(String),
+    /// The candidates for the series being added with the given nickname.
+    SelectSeries(String),
+}
+
+/// The selected rows of lists in panels that are recreated every time they're opened.
+///
+/// This allows a panel to return to where it was left the next time it's opened. Positions only last for the current session.
+#[derive(Default)]
+pub struct ListPositions(HashMap<SavedList, usize>);
+
+impl ListPositions {
+    /// Returns the last selected row of `list`, which now has `len` items.
+    #[inline(always)]
+    pub fn get(&self, list: &SavedList, len: usize) -> usize {
+        self.find(list, len).unwrap_or(0)
+    }
+
+    /// Returns the last selected row of `list`, which now has `len` items, or None if it hasn't been opened before.
+    #[inline(always)]
+    pub fn find(&self, list: &SavedList, len: usize) -> Option<usize> {
+        self.0.get(list).map(|&index| Self::clamp(index, len))
+    }
+
+    #[inline(always)]
+    pub fn save(&mut self, list: SavedList, index: usize) {
+        self.0.insert(list, index);
+    }
+
+    /// Keeps `index` within a list of `len` items, in case items were removed since it was saved.
+    #[inline(always)]
+    fn clamp(index: usize, len: usize) -> usize {
+        index.min(len.saturating_sub(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reopened_list_retains_selection() {
+        let mut positions = ListPositions::default();
+        assert_eq!(positions.get(&SavedList::Users, 5), 0);
+        assert_eq!(positions.find(&SavedList::Users, 5), None);
+
+        positions.save(SavedList::Users, 3);
+
+        assert_eq!(positions.get(&SavedList::Users, 5), 3);
+        assert_eq!(positions.get(&SavedList::History, 5), 0);
+    }
+
+    #[test]
+    fn shrunk_list_clamps_selection() {
+        let mut positions = ListPositions::default();
+        positions.save(SavedList::Users, 3);
+
+        assert_eq!(positions.get(&SavedList::Users, 2), 1);
+        assert_eq!(positions.get(&SavedList::Users, 0), 0);
+    }
+
+    #[test]
+    fn keyed_positions_are_separate() {
+        let mut positions = ListPositions::default();
+        positions.save(SavedList::SplitSeries(1), 2);
+        positions.save(SavedList::BrowseSeries("Kaguya".into()), 4);
+
+        assert_eq!(positions.get(&SavedList::SplitSeries(1), 4), 2);
+        assert_eq!(positions.get(&SavedList::SplitSeries(2), 4), 0);
+        assert_eq!(
+            positions.get(&SavedList::BrowseSeries("Kaguya".into()), 8),
+            4
+        );
+        assert_eq!(positions.get(&SavedList::BrowseSeries("Mob".into()), 8), 0);
+    }
+}
@@ -0,0 +1,290 @@
+use crate::database::schema::series_list_view;
+use crate::database::Database;
+use crate::series::{cache, entry, history::WatchRecord, LoadedSeries};
+use anime::remote::Status;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// How the series list is ordered.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SeriesSort {
+    /// Alphabetically by nickname.
+    Name,
+    /// The series with the most of their episodes watched first.
+    Progress,
+    /// The highest scored series first.
+    Score,
+    /// The series with an episode watched most recently first.
+    LastWatched,
+    /// The series with an episode that aired most recently first, as of the last time their airing schedule was checked.
+    Airing,
+}
+
+impl SeriesSort {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Progress => "progress",
+            Self::Score => "score",
+            Self::LastWatched => "last-watched",
+            Self::Airing => "airing",
+        }
+    }
+
+    /// Sort `series` in this order.
+    ///
+    /// Series that have nothing to sort by, such as those that have never been watched, are placed last in alphabetical order.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn sort(self, series: &mut [LoadedSeries], db: &Database) -> diesel::QueryResult<()> {
+        match self {
+            Self::Name => series.sort_unstable(),
+            Self::Progress => sort_descending_by(series, |series| {
+                let data = series.data()?;
+                let total = data.info.episodes;
+
+                if total <= 0 {
+                    return None;
+                }
+
+                // Kept as an integer so it can be ordered
+                let watched = data.entry.watched_episodes().clamp(0, total);
+                Some((u32::from(watched as u16) * 1000) / u32::from(total as u16))
+            }),
+            Self::Score => sort_descending_by(series, |series| series.data()?.entry.score()),
+            Self::LastWatched => {
+                let times = WatchRecord::last_watched_times(db)?;
+                sort_descending_by(series, |series| times.get(&series.config().id).copied());
+            }
+            Self::Airing => {
+                let times = cache::aired_times(db)?;
+                sort_descending_by(series, |series| times.get(&series.config().id).copied());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for SeriesSort {
+    fn default() -> Self {
+        Self::Name
+    }
+}
+
+impl FromStr for SeriesSort {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_ref() {
+            "name" => Ok(Self::Name),
+            "progress" => Ok(Self::Progress),
+            "score" => Ok(Self::Score),
+            "last-watched" => Ok(Self::LastWatched),
+            "airing" => Ok(Self::Airing),
+            _ => Err(format!(
+                "unknown sort {}, expected name, progress, score, last-watched, or airing",
+                value
+            )),
+        }
+    }
+}
+
+/// Sort `series` from the highest `key` to the lowest, with series that don't have one placed last.
+fn sort_descending_by<K, F>(series: &mut [LoadedSeries], key: F)
+where
+    K: Ord,
+    F: Fn(&LoadedSeries) -> Option<K>,
+{
+    let keys = series
+        .iter()
+        .map(|series| (series.config().id, key(series)))
+        .collect::<HashMap<_, _>>();
+
+    series.sort_unstable_by(|x, y| {
+        let x_key = &keys[&x.config().id];
+        let y_key = &keys[&y.config().id];
+
+        // None is ordered before Some, so comparing in reverse places series without a key last
+        y_key.cmp(x_key).then_with(|| x.cmp(y))
+    });
+}
+
+/// A restriction on which series the series list shows.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListFilter {
+    Status(Status),
+    Tag(String),
+}
+
+impl FromStr for ListFilter {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(status) = value.strip_prefix("status:") {
+            return entry::parse_status(status)
+                .map(Self::Status)
+                .ok_or_else(|| format!("unknown status: {}", status));
+        }
+
+        match value.strip_prefix("tag:").map(str::trim) {
+            Some(tag) if !tag.is_empty() => Ok(Self::Tag(tag.into())),
+            Some(_) | None => Err(format!(
+                "unknown filter {}, expected status:<status> or tag:<tag>",
+                value
+            )),
+        }
+    }
+}
+
+/// The order and filter of the series list, which is saved so the list looks the same the next time the program is opened.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SeriesListView {
+    pub sort: SeriesSort,
+    /// The only status of series to show, or None to show series with any status.
+    pub status: Option<Status>,
+    /// The tag series must have to be shown, or None to show series regardless of their tags.
+    pub tag: Option<String>,
+}
+
+impl SeriesListView {
+    /// The ID of the only row the view is stored in.
+    const ROW_ID: i32 = 1;
+
+    pub fn load(db: &Database) -> diesel::QueryResult<Self> {
+        use crate::database::schema::series_list_view::dsl;
+
+        let row = dsl::series_list_view
+            .filter(dsl::id.eq(Self::ROW_ID))
+            .select((dsl::sort, dsl::status_filter, dsl::tag_filter))
+            .get_result::<(String, Option<Status>, Option<String>)>(db.conn())
+            .optional()?;
+
+        let view = match row {
+            Some((sort, status, tag)) => Self {
+                // A sort that no longer exists shouldn't prevent the program from starting
+                sort: sort.parse().unwrap_or_default(),
+                status,
+                tag,
+            },
+            None => Self::default(),
+        };
+
+        Ok(view)
+    }
+
+    pub fn save(&self, db: &Database) -> diesel::QueryResult<()> {
+        use crate::database::schema::series_list_view::dsl;
+
+        diesel::replace_into(series_list_view::table)
+            .values((
+                dsl::id.eq(Self::ROW_ID),
+                dsl::sort.eq(self.sort.name()),
+                dsl::status_filter.eq(self.status),
+                dsl::tag_filter.eq(&self.tag),
+            ))
+            .execute(db.conn())?;
+
+        Ok(())
+    }
+
+    /// Apply `filter` alongside the current filters, or remove every filter when it's None.
+    pub fn set_filter(&mut self, filter: Option<ListFilter>) {
+        match filter {
+            Some(ListFilter::Status(status)) => self.status = Some(status),
+            Some(ListFilter::Tag(tag)) => self.tag = Some(tag),
+            None => {
+                self.status = None;
+                self.tag = None;
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_filtered(&self) -> bool {
+        self.status.is_some() || self.tag.is_some()
+    }
+
+    /// Returns true if `series` passes every filter of the view.
+    ///
+    /// Series that couldn't be loaded don't have a status or tags, so they're only shown when there isn't a filter.
+    pub fn shows(&self, series: &LoadedSeries) -> bool {
+        if !self.is_filtered() {
+            return true;
+        }
+
+        let data = match series.data() {
+            Some(data) => data,
+            None => return false,
+        };
+
+        let status_matches = self
+            .status
+            .map_or(true, |status| data.entry.status() == status);
+
+        let tag_matches = self
+            .tag
+            .as_ref()
+            .map_or(true, |tag| data.tags.contains(tag));
+
+        status_matches && tag_matches
+    }
+}
+
+/// Returns the index of the first series `view` shows after `from`, or before it when `forwards` is false, wrapping around either end of the list.
+///
+/// None is returned if the view doesn't show any series.
+pub fn next_shown(
+    series: &[LoadedSeries],
+    view: &SeriesListView,
+    from: usize,
+    forwards: bool,
+) -> Option<usize> {
+    let len = series.len();
+
+    (1..=len)
+        .map(|offset| {
+            if forwards {
+                (from + offset) % len
+            } else {
+                (from + len - offset) % len
+            }
+        })
+        .find(|&index| view.shows(&series[index]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_names_round_trip() {
+        let sorts = [
+            SeriesSort::Name,
+            SeriesSort::Progress,
+            SeriesSort::Score,
+            SeriesSort::LastWatched,
+            SeriesSort::Airing,
+        ];
+
+        for &sort in &sorts {
+            assert_eq!(sort.name().parse::<SeriesSort>(), Ok(sort));
+        }
+
+        assert!("newest".parse::<SeriesSort>().is_err());
+    }
+
+    #[test]
+    fn filter_parsing() {
+        assert_eq!(
+            "status:w".parse::<ListFilter>(),
+            Ok(ListFilter::Status(Status::Watching))
+        );
+        assert_eq!(
+            "tag:seasonal-2024".parse::<ListFilter>(),
+            Ok(ListFilter::Tag("seasonal-2024".into()))
+        );
+        assert!("tag:".parse::<ListFilter>().is_err());
+        assert!("watching".parse::<ListFilter>().is_err());
+    }
+}
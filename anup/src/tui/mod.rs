@@ -1,35 +1,84 @@
 mod component;
+mod instance;
+mod list_positions;
+mod list_view;
 mod state;
+mod theme;
+mod undo;
+mod widget_util;
 
 use self::state::{InputState, Reactive, UIEvents, UIState};
 use crate::key::Key;
+use crate::logging::{Logger, StatusEvents};
+use crate::series::cache::{self, Refresh};
+use crate::series::clean::MissingSeries;
+use crate::series::info::SeriesInfo;
+use crate::series::merged::MergedSeason;
+use crate::series::{LoadedSeries, Series, SeriesData, WatchStart};
+use crate::try_opt_r;
 use crate::Args;
-use crate::{file::SerializedFile, remote::RemoteLogin, try_opt_r, user::Users};
-use anime::remote::ScoreParser;
+use anime::remote::{Remote, RemoteService, ScoreParser, SeriesID};
 use anyhow::{anyhow, Context, Result};
+use component::main_panel::{
+    find_merged_seasons, relink_merged_seasons, MergedBase, RelinkedSeason,
+};
 use component::prompt::command::Command;
 use component::prompt::command::InputResult;
-use component::prompt::COMMAND_KEY;
+use component::prompt::log::LogKind;
 use component::series_list::SeriesList;
 use component::Component;
 use component::{main_panel::MainPanel, prompt::command::CommandPrompt};
-use crossterm::{event::KeyCode, terminal};
-use state::{SharedState, UIErrorKind, UIEvent};
+use crossterm::terminal;
+use instance::InstanceSocket;
+use state::{SharedState, StateEvent, UIErrorKind, UIEvent};
 use std::{
-    io,
+    env, io,
     ops::{Deref, DerefMut},
+    process,
     sync::Arc,
 };
+use tokio::sync::broadcast::{self, error::RecvError};
 use tokio::sync::Notify;
+use tokio::task;
 use tui::{backend::CrosstermBackend, layout::Direction, Terminal};
 use tui_utils::layout::{BasicConstraint, SimpleLayout};
+use undo::UndoAction;
 
-pub async fn run(args: &Args) -> Result<()> {
-    let mut ui = UI::init(&args).context("failed to init UI")?;
+pub async fn run(args: &Args, logger: Logger) -> Result<()> {
+    let mut ui = UI::init(&args, logger.status.clone()).context("failed to init UI")?;
     let result = ui.run().await;
+    let switch_profile = ui.state.lock().get_mut().switch_profile.take();
 
     ui.exit()?;
-    result
+    result?;
+
+    // Restarting replaces the process, so anything still waiting to be written to the log file has to be written first
+    drop(logger);
+
+    match switch_profile {
+        Some(profile) => restart_with_profile(profile.as_deref(), args),
+        None => Ok(()),
+    }
+}
+
+/// Replace the running program with a new instance of it that uses `profile`, where None is the default profile.
+fn restart_with_profile(profile: Option<&str>, args: &Args) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let exe = env::current_exe().context("failed to get the path of the program")?;
+    let mut cmd = process::Command::new(exe);
+
+    if let Some(profile) = profile {
+        cmd.args(&["--profile", profile]);
+    }
+
+    if args.offline {
+        cmd.arg("--offline");
+    }
+
+    // exec only returns if it failed
+    let err = cmd.exec();
+    Err(anyhow!(err).context("failed to restart the program with the new profile"))
 }
 
 struct UI {
@@ -38,26 +87,63 @@ struct UI {
     state: SharedState,
     dirty_state_notify: Arc<Notify>,
     panels: Panels,
+    /// The socket that tells other TUIs this one is open, or None if another one was already open.
+    #[allow(dead_code)]
+    instance: Option<InstanceSocket>,
 }
 
 impl UI {
-    fn init(args: &Args) -> Result<UI> {
+    fn init(args: &Args, status: StatusEvents) -> Result<UI> {
         let events = UIEvents::new().context("UI events init")?;
 
-        let mut state = UIState::init().context("UI state init")?;
+        let mut state = UIState::init(status).context("UI state init")?;
 
         state
             .select_initial_series(args)
             .context("selecting initial series")?;
 
+        let instance = InstanceSocket::bind().context("checking for other open instances")?;
+
+        if instance.is_none() {
+            state.log.push(
+                LogKind::Error,
+                "anup is already open with this profile\nchanges made in one will be overwritten by the other, so close one of them",
+            );
+        }
+
+        let logins = state.users.last_used_logins(&state.user_links);
+
+        if logins.is_none() && !args.offline {
+            if let Err(err) = state.go_anonymous() {
+                state.log.push_error(&err);
+            }
+        }
+        let missing =
+            MissingSeries::find_all(state.series.iter().map(LoadedSeries::config), &state.config);
+
         let dirty_state_notify = Arc::new(Notify::const_new());
         let shared_state = SharedState::new(Reactive::new(state, Arc::clone(&dirty_state_notify)));
 
-        let panels = Panels::init(&shared_state);
+        let mut panels = Panels::init(&shared_state);
+
+        if !missing.is_empty() {
+            let mut state = shared_state.lock();
+            panels.main_panel.switch_to_clean(missing, state.get_mut());
+        }
+
+        shared_state.scan_series_async();
+        shared_state.watch_config_async();
+        shared_state.watch_airing_async();
+        shared_state.watch_weekly_summary_async();
+        shared_state.watch_feeds_async();
+        shared_state.watch_downloads_async();
+        shared_state.watch_folders_async();
+        shared_state.watch_media_server_async();
+        shared_state.watch_entry_sync_async();
 
         if !args.offline {
-            if let Some((user, token)) = Users::load_or_create()?.take_last_used_user() {
-                shared_state.login_to_remote_async(RemoteLogin::AniList(user.username, token));
+            if let Some((login, linked)) = logins {
+                shared_state.login_to_remote_async(login, linked);
             }
         }
 
@@ -69,6 +155,7 @@ impl UI {
             state: shared_state,
             dirty_state_notify,
             panels,
+            instance,
         })
     }
 
@@ -98,21 +185,80 @@ impl UI {
             Err(UIErrorKind::Other(err)) => return CycleResult::Error(err),
         };
 
-        let mut state = self.state.lock();
-        let state = state.get_mut();
+        let (result, player_events) = {
+            let mut state = self.state.lock();
+            let state = state.get_mut();
+
+            let result = match event {
+                UIEvent::Key(key) => self.panels.process_key(key, state),
+                UIEvent::StateChange | UIEvent::Resize => CycleResult::Ok,
+            };
+
+            if let Err(err) = self.panels.main_panel.add_looked_up_series(state) {
+                state.log.push_error(&err);
+            }
+
+            if let Err(err) = self.panels.main_panel.reopen_expired_login(state) {
+                state.log.push_error(&err);
+            }
+
+            self.panels.main_panel.open_sync_conflicts(state);
+            self.panels.main_panel.open_sequel_prompt(state);
+            self.panels.main_panel.open_retry_prompt(state);
+            self.panels.main_panel.open_offset_prompt(state);
 
-        let result = match event {
-            UIEvent::Key(key) => self.panels.process_key(key, state).await,
-            UIEvent::StateChange | UIEvent::Resize => CycleResult::Ok,
+            if state.switch_profile.is_some() {
+                return CycleResult::Exit;
+            }
+
+            if state.input_state == InputState::PlayerAttached {
+                (result, Some(state.events.subscribe()))
+            } else {
+                if let Err(err) = self.panels.draw(state, &mut self.terminal) {
+                    return CycleResult::Error(err);
+                }
+
+                (result, None)
+            }
         };
 
-        if let Err(err) = self.panels.draw(state, &mut self.terminal) {
-            return CycleResult::Error(err);
+        if let Some(player_events) = player_events {
+            if let Err(err) = self.attach_player(player_events).await {
+                return CycleResult::Error(err);
+            }
         }
 
         result
     }
 
+    /// Hands the terminal over to a player that inherits our stdio until it exits, and restores the TUI afterwards.
+    ///
+    /// Input events are not read while the player is running, as they would otherwise be stolen from it.
+    async fn attach_player(&mut self, mut events: broadcast::Receiver<StateEvent>) -> Result<()> {
+        self.terminal.clear().context("failed to clear terminal")?;
+        self.terminal
+            .show_cursor()
+            .context("failed to show mouse cursor")?;
+        terminal::disable_raw_mode().context("failed to disable raw mode")?;
+
+        loop {
+            match events.recv().await {
+                Ok(StateEvent::FinishedEpisode) | Err(RecvError::Closed) => break,
+                Ok(StateEvent::StartedEpisode(_)) | Err(RecvError::Lagged(_)) => (),
+            }
+        }
+
+        terminal::enable_raw_mode().context("failed to enable raw mode")?;
+        self.terminal.clear().context("failed to clear terminal")?;
+        self.panels.main_panel.redraw_cover();
+        self.terminal
+            .hide_cursor()
+            .context("failed to hide mouse cursor")?;
+
+        let mut state = self.state.lock();
+        self.panels.draw(state.get_mut(), &mut self.terminal)
+    }
+
     pub fn exit(mut self) -> Result<()> {
         self.terminal.clear().ok();
         terminal::disable_raw_mode().map_err(Into::into)
@@ -140,7 +286,7 @@ impl Panels {
         }
     }
 
-    async fn process_key(&mut self, key: Key, state: &mut UIState) -> CycleResult {
+    fn process_key(&mut self, key: Key, state: &mut UIState) -> CycleResult {
         macro_rules! capture {
             ($result:expr) => {
                 match $result {
@@ -160,28 +306,72 @@ impl Panels {
         }
 
         match state.input_state {
-            InputState::Idle => match *key {
-                KeyCode::Char('q') => return CycleResult::Exit,
-                _ if key == state.config.tui.keys.play_next_episode => {
-                    capture!(state.play_next_series_episode(&self.state).await)
-                }
-                KeyCode::Char('a') => {
-                    capture!(self.main_panel.switch_to_add_series(state))
-                }
-                KeyCode::Char('e') => {
-                    capture!(self.main_panel.switch_to_update_series(state))
-                }
-                KeyCode::Char('D') => {
-                    capture!(self.main_panel.switch_to_delete_series(state))
+            InputState::Idle => {
+                let keys = state.config.tui.keys;
+
+                match key {
+                    _ if key == keys.quit => return CycleResult::Exit,
+                    _ if key == keys.play_next_episode => {
+                        if state.selected_needs_watch_confirmation() {
+                            capture!(self.main_panel.switch_to_confirm_watch(state))
+                        } else {
+                            capture!(state
+                                .play_next_series_episode(WatchStart::UpdateStatus, &self.state))
+                        }
+                    }
+                    _ if key == keys.add_series => {
+                        capture!(self.main_panel.switch_to_add_series(state))
+                    }
+                    _ if key == keys.browse_series => {
+                        capture!(self.main_panel.switch_to_browse_series(state))
+                    }
+                    _ if key == keys.update_series => {
+                        capture!(self.main_panel.switch_to_update_series(state))
+                    }
+                    _ if key == keys.delete_series => {
+                        capture!(self.main_panel.switch_to_delete_series(state))
+                    }
+                    _ if key == keys.user_panel => self.main_panel.switch_to_user_panel(state),
+                    _ if key == keys.episodes_panel => {
+                        capture!(self.main_panel.switch_to_episodes(state))
+                    }
+                    _ if key == keys.history_panel => {
+                        capture!(self.main_panel.switch_to_history(state))
+                    }
+                    _ if key == keys.log_panel => self.main_panel.switch_to_log(state),
+                    _ if key == keys.relink_series => capture!(state.relink_selected()),
+                    _ if key == keys.stats_panel => {
+                        capture!(self.main_panel.switch_to_stats(state))
+                    }
+                    _ if key == keys.downloads_panel => self.main_panel.switch_to_downloads(state),
+                    _ if key == keys.search => self.main_panel.switch_to_search(state),
+                    _ if key == keys.mark_series => state.toggle_selected_mark(),
+                    _ if key == keys.split_series => {
+                        capture!(self.main_panel.switch_to_split_series(state))
+                    }
+                    _ if key == keys.relations_panel => {
+                        capture!(self.main_panel.switch_to_relations(state))
+                    }
+                    _ if key == keys.seasonal_panel => {
+                        capture!(self.main_panel.switch_to_seasonal(state))
+                    }
+                    _ if key == keys.recommendations_panel => {
+                        capture!(self.main_panel.switch_to_recommendations(state))
+                    }
+                    _ if key == keys.undo => capture!(state.undo_last_action()),
+                    _ if key == keys.command_prompt => {
+                        state.input_state = InputState::EnteringCommand
+                    }
+                    _ => SeriesList::process_key(key, state),
                 }
-                KeyCode::Char('u') => self.main_panel.switch_to_user_panel(state),
-                KeyCode::Char('s') => {
-                    capture!(self.main_panel.switch_to_split_series(state))
+            }
+            // Streams can't be tracked, so the play key is pressed again once they've been watched
+            InputState::Locked if key == state.config.tui.keys.play_next_episode => {
+                if let Some(finished) = state.stream_finished.take() {
+                    finished.notify_one();
                 }
-                KeyCode::Char(COMMAND_KEY) => state.input_state = InputState::EnteringCommand,
-                _ => SeriesList::process_key(key, state),
-            },
-            InputState::Locked => (),
+            }
+            InputState::Locked | InputState::PlayerAttached => (),
             InputState::FocusedOnMainPanel => process_key!(main_panel),
             InputState::EnteringCommand => {
                 let result = self.command_prompt.process_key(key, state);
@@ -192,8 +382,14 @@ impl Panels {
                 }
 
                 match capture!(result) {
+                    InputResult::Command(Command::Rename(template)) => {
+                        capture!(self.main_panel.switch_to_rename(template, state))
+                    }
+                    InputResult::Command(Command::AddAll) => {
+                        capture!(self.main_panel.switch_to_batch_add(state))
+                    }
                     InputResult::Command(cmd) => {
-                        capture!(Self::process_command(cmd, state))
+                        capture!(Self::process_command(cmd, state, &self.state))
                     }
                     InputResult::Done | InputResult::Continue => (),
                 }
@@ -204,6 +400,17 @@ impl Panels {
     }
 
     fn draw(&mut self, state: &UIState, terminal: &mut CrosstermTerminal) -> Result<()> {
+        self.draw_frame(state, terminal)?;
+
+        if self.main_panel.cover_needs_clear() {
+            terminal.clear()?;
+            self.draw_frame(state, terminal)?;
+        }
+
+        self.main_panel.draw_cover(terminal.backend_mut())
+    }
+
+    fn draw_frame(&mut self, state: &UIState, terminal: &mut CrosstermTerminal) -> Result<()> {
         terminal.draw(|mut frame| {
             let horiz_splitter = SimpleLayout::new(Direction::Horizontal).split(
                 frame.size(),
@@ -238,7 +445,194 @@ impl Panels {
         Ok(())
     }
 
-    fn process_command(command: Command, state: &mut UIState) -> Result<()> {
+    fn process_command(command: Command, state: &mut UIState, shared: &SharedState) -> Result<()> {
+        if !state.marked_series.is_empty() && command.applies_to_marked() {
+            return Self::run_marked_command(&command, state);
+        }
+
+        let undo = state
+            .series
+            .selected()
+            .and_then(|series| UndoAction::before_command(&command, series));
+
+        Self::run_command(command, state, shared)?;
+
+        if let Some(undo) = undo {
+            state.undo.push(undo);
+        }
+
+        Ok(())
+    }
+
+    /// Applies `command` to every marked series, logging the error of each series it fails for.
+    fn run_marked_command(command: &Command, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in()?;
+        let config = &state.config;
+        let db = &state.db;
+        let log = &mut state.log;
+
+        let score = match command {
+            Command::Score(raw_score) => parse_score(remote, raw_score)?,
+            _ => None,
+        };
+
+        let mut undo = Vec::new();
+
+        for series in state.series.items_mut() {
+            if !state.marked_series.contains(&series.config().id) {
+                continue;
+            }
+
+            let nickname = series.nickname().to_string();
+
+            if series.is_unscanned() {
+                series.try_load(config, db);
+            }
+
+            let series = match series.complete_mut() {
+                Some(series) => series,
+                None => {
+                    log.push(
+                        LogKind::Error,
+                        format!("{} must be fully loaded to be updated", nickname),
+                    );
+                    continue;
+                }
+            };
+
+            let previous = series.data.entry.clone();
+
+            match command {
+                Command::Status(status) => series.data.entry.set_status(*status, config),
+                Command::Score(_) => series.data.entry.set_score(score),
+                _ => unreachable!(),
+            }
+
+            match series.save(db) {
+                Ok(()) => undo.push(UndoAction::Entry(previous)),
+                Err(err) => {
+                    series.data.entry.restore(previous);
+
+                    let err = anyhow!(err).context(format!("failed to update {}", nickname));
+                    log.push_error(&err);
+                }
+            }
+        }
+
+        if undo.is_empty() {
+            return Ok(());
+        }
+
+        log.push(LogKind::Info, format!("updated {} series", undo.len()));
+        state.undo.push(UndoAction::Batch(undo));
+        state.request_entry_sync();
+
+        Ok(())
+    }
+
+    /// Replace the info and list entry of `data` with the latest versions from `remote` in the background.
+    fn sync_from_remote_async(data: &SeriesData, remote: Arc<Remote>, shared: &SharedState) {
+        let id = data.info.id;
+        let mut entry = data.entry.clone();
+        let shared = shared.clone();
+
+        task::spawn(async move {
+            // The new info shouldn't be set if the entry sync fails
+            let fetched: Result<_> = async {
+                let info = SeriesInfo::from_remote_by_id(id as SeriesID, &remote).await?;
+                entry.force_sync_from_remote(&remote).await?;
+                Ok((info, entry))
+            }
+            .await;
+
+            let mut state = shared.lock();
+            let state = state.get_mut();
+
+            let result = fetched.and_then(|(info, entry)| {
+                let series = state
+                    .series
+                    .items_mut()
+                    .iter_mut()
+                    .filter_map(LoadedSeries::complete_mut)
+                    .find(|series| series.data.info.id == id)
+                    .ok_or_else(|| anyhow!("series was removed before it could be synced"))?;
+
+                series.data.info = info;
+                series.data.entry = entry;
+                series.save(&state.db)?;
+                Ok(())
+            });
+
+            if let Err(err) = result {
+                state
+                    .log
+                    .push_error(&err.context("failed to sync from the remote service"));
+            }
+        });
+    }
+
+    fn finish_merge(id: i32, seasons: Vec<MergedSeason>, state: &mut UIState) -> Result<()> {
+        let series = state
+            .series
+            .items_mut()
+            .iter_mut()
+            .filter_map(LoadedSeries::complete_mut)
+            .find(|series| series.data.config.id == id)
+            .ok_or_else(|| anyhow!("series was removed before its seasons could be found"))?;
+
+        let count = seasons.len();
+
+        series.set_merged_seasons(seasons, &state.config)?;
+        series.save(&state.db)?;
+
+        state.log.push(
+            LogKind::Info,
+            format!(
+                "{} will move through the {} seasons in its folder",
+                series.data.config.nickname, count
+            ),
+        );
+
+        Ok(())
+    }
+
+    fn finish_relink(relinked: &[RelinkedSeason], state: &mut UIState) {
+        if relinked.is_empty() {
+            state
+                .log
+                .push(LogKind::Error, "no split seasons found to relink");
+
+            return;
+        }
+
+        for season in relinked {
+            state.log.push(
+                LogKind::Info,
+                format!(
+                    "{} now uses episodes {}-{} (offset {})",
+                    season.title,
+                    season.offset + 1,
+                    season.offset + season.episodes,
+                    season.offset
+                ),
+            );
+        }
+
+        // The episodes of the relinked seasons may have changed, so they need to be rescanned
+        for series in state.series.items_mut() {
+            let was_relinked = relinked
+                .iter()
+                .any(|season| season.path.inner() == series.path().inner());
+
+            if was_relinked {
+                let sconfig = series.config().clone();
+                *series = Series::load_from_config(sconfig, &state.config, &state.db);
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn run_command(command: Command, state: &mut UIState, shared: &SharedState) -> Result<()> {
         let remote = &mut state.remote;
         let config = &state.config;
         let db = &state.db;
@@ -255,56 +649,301 @@ impl Panels {
                 use component::prompt::command::ProgressDirection;
 
                 let series = try_opt_r!(state.series.get_valid_sel_series_mut());
-                let remote = remote.get_logged_in()?;
 
                 match direction {
-                    ProgressDirection::Forwards => series.episode_completed(remote, config, db),
-                    ProgressDirection::Backwards => series.episode_regressed(remote, config, db),
+                    ProgressDirection::Forwards => {
+                        series.episode_completed(config, db)?;
+
+                        if series.is_completed() {
+                            state.queue_sequel_prompt()?;
+                        }
+                    }
+                    ProgressDirection::Backwards => series.episode_regressed(config, db)?,
                 }
+
+                state.request_entry_sync();
+                Ok(())
             }
-            cmd @ Command::SyncFromRemote | cmd @ Command::SyncToRemote => {
-                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
-                let remote = remote.get_logged_in()?;
+            Command::SyncFromRemote => {
+                let series = try_opt_r!(state.series.get_valid_sel_series());
+                let remote = remote.get_logged_in_shared()?;
 
-                match cmd {
-                    Command::SyncFromRemote => series.data.force_sync_from_remote(remote)?,
-                    Command::SyncToRemote => series.data.entry.force_sync_to_remote(remote)?,
-                    _ => unreachable!(),
-                }
+                Self::sync_from_remote_async(&series.data, remote, shared);
+                Ok(())
+            }
+            Command::SyncToRemote => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
 
+                series.data.entry.set_needs_sync();
                 series.save(db)?;
+
+                state.request_entry_sync();
                 Ok(())
             }
             Command::Score(raw_score) => {
                 let series = try_opt_r!(state.series.get_valid_sel_series_mut());
                 let remote = remote.get_logged_in()?;
+                let score = parse_score(remote, &raw_score)?;
+
+                series.data.entry.set_score(score);
+                series.save(db)?;
+
+                state.request_entry_sync();
+                Ok(())
+            }
+            Command::EpisodeOffset(offset) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.set_episode_offset(offset, config)?;
+                series.save(db)?;
 
-                let score = match remote.parse_score(&raw_score) {
-                    Some(score) if score == 0 => None,
-                    Some(score) => Some(score),
-                    None => return Err(anyhow!("invalid score")),
+                Ok(())
+            }
+            Command::EpisodeCategory(category) => {
+                let series = try_opt_r!(state.series.selected_mut());
+                series.set_episode_category(category, config, db)
+            }
+            Command::AbsoluteNumbering(enabled) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+                let prequel_episodes =
+                    cache::prequel_episodes(db, series.data.info.id as SeriesID)?;
+
+                if enabled && prequel_episodes == 0 {
+                    return Err(anyhow!(
+                        "no earlier seasons of {} are cached, add one and use the prefetch command first",
+                        series.data.info.title_preferred
+                    ));
+                }
+
+                series.set_absolute_numbering(enabled, prequel_episodes, config)?;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::RelinkSeasons => {
+                let data = match try_opt_r!(state.series.selected()) {
+                    LoadedSeries::Complete(series) => &series.data,
+                    LoadedSeries::Partial(data, _) | LoadedSeries::Unscanned(data) => data,
+                    LoadedSeries::None(_, _) => {
+                        return Err(anyhow!("cannot relink the seasons of a series with errors"))
+                    }
                 };
 
-                series.data.entry.set_score(score.map(i16::from));
-                series.data.entry.sync_to_remote(remote)?;
+                let base = MergedBase::new(data, config)?;
+                let remote = remote.get_logged_in_shared()?;
+                let shared = shared.clone();
+
+                state.log.push(
+                    LogKind::Info,
+                    format!("looking up the seasons of {}..", base.nickname()),
+                );
+
+                task::spawn(async move {
+                    let relinked = relink_merged_seasons(base, &remote, &shared).await;
+
+                    let mut state = shared.lock();
+                    let state = state.get_mut();
+
+                    match relinked {
+                        Ok(relinked) => Self::finish_relink(&relinked, state),
+                        Err(err) => state.log.push_error(&err),
+                    }
+                });
+
+                Ok(())
+            }
+            Command::MergedSeasons(false) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.set_merged_seasons(Vec::new(), config)?;
                 series.save(db)?;
 
                 Ok(())
             }
+            Command::MergedSeasons(true) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series());
+
+                if !series.data.merged_seasons.is_empty() {
+                    return Err(anyhow!(
+                        "{} is already watched as merged seasons",
+                        series.data.config.nickname
+                    ));
+                }
+
+                let id = series.data.config.id;
+                let base = MergedBase::new(&series.data, config)?;
+                let remote = remote.get_logged_in_shared()?;
+                let shared = shared.clone();
+
+                state.log.push(
+                    LogKind::Info,
+                    format!("looking up the seasons of {}..", base.nickname()),
+                );
+
+                task::spawn(async move {
+                    let found = find_merged_seasons(base, &remote, &shared).await;
+
+                    let mut state = shared.lock();
+                    let state = state.get_mut();
+
+                    if let Err(err) =
+                        found.and_then(|seasons| Self::finish_merge(id, seasons, state))
+                    {
+                        state.log.push_error(&err);
+                    }
+                });
+
+                Ok(())
+            }
             Command::Status(status) => {
                 let series = try_opt_r!(state.series.get_valid_sel_series_mut());
-                let remote = remote.get_logged_in()?;
 
                 series.data.entry.set_status(status, config);
-                series.data.entry.sync_to_remote(remote)?;
+                series.save(db)?;
+
+                state.request_entry_sync();
+                Ok(())
+            }
+            Command::Prefetch => {
+                let remote = remote.get_logged_in_shared()?;
+
+                if remote.is_offline() {
+                    return Err(anyhow!("must be online to prefetch series info"));
+                }
+
+                state.log.push(
+                    LogKind::Info,
+                    "fetching info for every series in the background",
+                );
+
+                shared.refresh_cache_async(remote, Refresh::All);
+                Ok(())
+            }
+            Command::Undo => state.undo_last_action(),
+            Command::Feed(url) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.data.config.feed_url = url;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::Stream(template) => {
+                let series = try_opt_r!(state.series.selected_mut());
+                let data = series
+                    .data_mut()
+                    .ok_or_else(|| anyhow!("series must be loaded to set its stream URL"))?;
+
+                data.config.stream_url_template = template;
+                data.config.save(db)?;
+
+                // Whether the series can be watched without any episodes on disk may have changed
+                let sconfig = data.config.clone();
+                *series = Series::load_from_config(sconfig, &state.config, db);
+
+                Ok(())
+            }
+            Command::SubLang(langs) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.data.config.sub_lang = langs;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::AudioLang(langs) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.data.config.audio_lang = langs;
                 series.save(db)?;
 
                 Ok(())
             }
+            Command::SubFiles(pattern) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.data.config.sub_file_pattern = pattern;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::EpisodeLength(mins) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.data.config.episode_length_mins = mins;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::Next => {
+                let remote = remote.get_logged_in_shared()?;
+                shared.suggest_next_async(remote);
+                Ok(())
+            }
+            // Renames are previewed in the main panel before anything is run
+            Command::Rename(_) => Ok(()),
+            // New folders are reviewed in the main panel before any are added
+            Command::AddAll => Ok(()),
+            Command::Sort(sort) => state.set_series_sort(sort),
+            Command::Filter(filter) => state.set_series_filter(filter),
+            Command::Tag(action) => {
+                use component::prompt::command::NameAction;
+
+                let series = try_opt_r!(state.series.selected_mut());
+                let data = series
+                    .data_mut()
+                    .ok_or_else(|| anyhow!("series must be loaded to change its tags"))?;
+
+                match action {
+                    NameAction::Add(tag) => data.add_tag(&tag, db),
+                    NameAction::Remove(tag) => data.remove_tag(&tag, db),
+                }
+            }
+            Command::Alias(action) => {
+                use component::prompt::command::NameAction;
+
+                let series = try_opt_r!(state.series.selected_mut());
+                let data = series
+                    .data_mut()
+                    .ok_or_else(|| anyhow!("series must be loaded to change its aliases"))?;
+
+                match action {
+                    NameAction::Add(alias) => data.add_alias(&alias, db),
+                    NameAction::Remove(alias) => data.remove_alias(&alias, db),
+                }
+            }
+            Command::Synonym(action) => {
+                use component::prompt::command::NameAction;
+
+                let series = try_opt_r!(state.series.selected_mut());
+
+                match action {
+                    NameAction::Add(synonym) => series.add_synonym(&synonym, config, db),
+                    NameAction::Remove(synonym) => series.remove_synonym(&synonym, config, db),
+                }
+            }
+            Command::LocalRewatch(enabled) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                if enabled {
+                    series.begin_local_rewatch(db)
+                } else {
+                    series.end_local_rewatch(db)
+                }
+            }
         }
     }
 }
 
+/// Parses `raw_score` with the scoring system of `remote`, where a score of zero removes the current score.
+fn parse_score(remote: &Remote, raw_score: &str) -> Result<Option<i16>> {
+    match remote.parse_score(raw_score) {
+        Some(0) => Ok(None),
+        Some(score) => Ok(Some(score.into())),
+        None => Err(anyhow!("invalid score")),
+    }
+}
+
 struct CrosstermTerminal(Terminal<CrosstermBackend<io::Stdout>>);
 
 impl CrosstermTerminal {
@@ -1,32 +1,68 @@
-use super::component::prompt::log::Log;
-use crate::user::Users;
-use crate::{config::Config, util::ArcMutex};
+use super::component::prompt::log::{Log, LogKind};
+use super::list_positions::ListPositions;
+use super::theme;
+use super::undo::{UndoAction, UndoStack};
+use crate::download::{Downloader, SeriesDownload};
+use crate::logging::StatusEvents;
+use crate::media_server;
+use crate::tui::list_view::{self, ListFilter, SeriesListView, SeriesSort};
+use crate::user::{UserInfo, UserLinks, Users, EXPIRY_WARNING_DAYS};
+use crate::{config::Config, config::DownloadConfig, util::ArcMutex};
 use crate::{database::Database, series::LastWatched};
 use crate::{file::SerializedFile, key::Key};
-use crate::{remote::RemoteLogin, series::info::SeriesInfo};
 use crate::{
     remote::RemoteStatus,
-    series::{LoadedSeries, Series, SeriesData},
+    series::{
+        self,
+        changes::{EntryChange, EntryMerge},
+        entry::SeriesEntry,
+        episode_counts_as_watched, EpisodeScanError, LoadedSeries, Series, SeriesData,
+        UpdateParams, WatchStart,
+    },
+};
+use crate::{
+    remote::{self, RemoteLogin},
+    series::cache::{self, Refresh},
+    series::clean::{CleanAction, MissingSeries, Resolved},
+    series::deleted::{DeletedRows, DeletedSeries},
+    series::suggest,
+    series::{
+        feed,
+        goals::{LastWeeklySummary, WeekStats},
+        history::WatchRecord,
+        hook::Hook,
+        import,
+        info::SeriesInfo,
+        playback::PlaybackHandle,
+    },
 };
 use crate::{series::config::SeriesConfig, Args};
-use crate::{try_opt_ret, util::arc_mutex};
-use anime::remote::{anilist::AniList, Remote};
-use anime::{local::SortedEpisodes, remote::anilist::Auth};
+use crate::{try_opt_r, try_opt_ret, util::arc_mutex};
+use anime::local::{EpisodeParser, SortedEpisodes};
+use anime::remote::anilist::AniList;
+use anime::remote::{Remote, RemoteService, SeriesID, Status};
 use anyhow::{anyhow, Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Local, Utc};
 use crossterm::event::{Event, EventStream};
 use futures::{select, FutureExt, StreamExt};
-use parking_lot::MutexGuard;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify_rust::Notification;
+use parking_lot::{MappedMutexGuard, MutexGuard};
 use std::{
     borrow::Cow,
-    mem,
-    ops::{Deref, DerefMut},
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fs, mem,
+    ops::{Deref, DerefMut, RangeInclusive},
+    path::{Path, PathBuf},
+    process::ExitStatus,
     sync::Arc,
+    time::{self, SystemTime},
 };
 use tokio::{
-    process::Child,
     signal::unix::{signal, Signal, SignalKind},
-    sync::{broadcast, Notify},
+    sync::{broadcast, mpsc, Notify},
     task,
 };
 use tui_utils::list::WrappedSelection;
@@ -38,6 +74,10 @@ impl WrappedSeriesSelection {
         Self(WrappedSelection::new(series))
     }
 
+    pub fn get_valid_sel_series(&self) -> Option<&Series> {
+        self.selected().and_then(LoadedSeries::complete)
+    }
+
     pub fn get_valid_sel_series_mut(&mut self) -> Option<&mut Series> {
         self.selected_mut().and_then(LoadedSeries::complete_mut)
     }
@@ -59,46 +99,231 @@ impl DerefMut for WrappedSeriesSelection {
 
 pub struct UIState {
     pub series: WrappedSeriesSelection,
+    /// The indices of the series that match the current search, if one is in progress.
+    pub series_filter: Option<WrappedSelection<Vec<usize>, usize>>,
+    /// How the series list is ordered and filtered.
+    pub list_view: SeriesListView,
+    /// The IDs of the series that have been marked to have commands applied to all of them at once.
+    pub marked_series: HashSet<i32>,
+    /// The torrents the configured download client is downloading to the folder of a series.
+    pub downloads: Vec<SeriesDownload>,
     pub last_watched: LastWatched,
     pub input_state: InputState,
     pub events: broadcast::Sender<StateEvent>,
-    pub log: Log<'static>,
+    pub log: Log,
     pub config: Config,
+    /// The client used for HTTP requests that don't go to the remote service, which goes through the configured proxy.
+    pub http: reqwest::Client,
     pub users: Users,
+    pub user_links: UserLinks,
     pub remote: RemoteStatus,
+    pub undo: UndoStack,
+    pub list_positions: ListPositions,
+    /// The user whose login was rejected by their remote service, so they can be asked for a new token.
+    pub expired_login: Option<UserInfo>,
+    /// List entries whose offline changes conflict with changes made on the remote service, waiting for the user to resolve them.
+    pub sync_conflicts: Vec<EntryMerge>,
+    /// The sequel of a series that was just completed, waiting for the user to decide whether to continue with it.
+    pub sequel_prompt: Option<SequelPrompt>,
+    /// An episode whose player crashed right after it started, waiting for the user to decide whether to play it again.
+    pub retry_prompt: Option<RetryPrompt>,
+    /// A series that appears to number its creditless opening as the first episode, waiting for the user to decide whether to shift its episodes.
+    pub offset_prompt: Option<OffsetPrompt>,
+    /// The index of the fallback player the playing episode was started with, or None if it's the main player.
+    fallback_player: Option<usize>,
+    /// The profile to restart the program under once the current cycle finishes, where `Some(None)` is the default profile.
+    pub switch_profile: Option<Option<String>>,
+    /// Notified when the user has finished watching the episode being streamed, if there is one.
+    pub stream_finished: Option<Arc<Notify>>,
+    /// Notified whenever list entries have changes that need to be sent to the remote service.
+    entry_sync: Arc<Notify>,
     pub db: Database,
 }
 
 impl UIState {
-    pub fn init() -> Result<Self> {
+    pub fn init(status: StatusEvents) -> Result<Self> {
         let config = Config::load_or_create().context("failed to load / create config")?;
+        let http = config.http_client()?;
         let users = Users::load_or_create().context("failed to load / create users")?;
+        let user_links =
+            UserLinks::load_or_create().context("failed to load / create user links")?;
         let db = Database::open().context("failed to open database")?;
         let last_watched = LastWatched::load().context("last watched series")?;
+        let list_view = SeriesListView::load(&db).context("failed to load series list view")?;
 
         let mut series = SeriesConfig::load_all(&db)
             .context("failed to load series configs")?
             .into_iter()
-            .map(|sconfig| Series::load_from_config(sconfig, &config, &db))
+            .map(|sconfig| Series::load_unscanned(sconfig, &db))
             .collect::<Vec<_>>();
 
-        series.sort_unstable();
+        list_view
+            .sort
+            .sort(&mut series, &db)
+            .context("failed to sort series")?;
 
         let (events_tx, _) = broadcast::channel(8);
+        let remote = cache::offline_remote(&db);
+
+        theme::apply(&config.tui.theme);
+
+        let mut log = Log::new(status, config.tui.keys.command_prompt);
+        Self::report_key_conflicts(&config, &mut log);
 
         Ok(Self {
             series: WrappedSeriesSelection::new(series),
+            series_filter: None,
+            list_view,
+            marked_series: HashSet::new(),
+            downloads: Vec::new(),
             last_watched,
             input_state: InputState::default(),
             events: events_tx,
-            log: Log::new(15),
+            log,
             config,
+            http,
             users,
-            remote: RemoteStatus::LoggedIn(Remote::offline()),
+            user_links,
+            remote: RemoteStatus::LoggedIn(Arc::new(remote)),
+            undo: UndoStack::default(),
+            list_positions: ListPositions::default(),
+            expired_login: None,
+            sync_conflicts: Vec::new(),
+            sequel_prompt: None,
+            retry_prompt: None,
+            offset_prompt: None,
+            fallback_player: None,
+            switch_profile: None,
+            stream_finished: None,
+            entry_sync: Arc::new(Notify::new()),
             db,
         })
     }
 
+    fn report_key_conflicts(config: &Config, log: &mut Log) {
+        for conflict in config.tui.keys.conflicts() {
+            log.push(LogKind::Error, conflict);
+        }
+    }
+
+    /// Replace the current config with `config` and reload every series, as their paths may point somewhere else now.
+    ///
+    /// Returns the nicknames of any series whose episode folder no longer exists.
+    pub fn reload_config(&mut self, config: Config) -> Vec<String> {
+        self.config = config;
+
+        // The old client is kept when the new proxy is invalid, so requests don't stop working altogether
+        match self.config.http_client() {
+            Ok(http) => self.http = http,
+            Err(err) => self.log.push_error(&err),
+        }
+
+        theme::apply(&self.config.tui.theme);
+        self.log
+            .set_command_key(self.config.tui.keys.command_prompt);
+        Self::report_key_conflicts(&self.config, &mut self.log);
+
+        for series in self.series.items_mut() {
+            *series = Series::load_from_config(series.config().clone(), &self.config, &self.db);
+        }
+
+        self.series
+            .iter()
+            .filter(|series| {
+                let path = series.path();
+                path.is_set() && !path.absolute(&self.config).exists()
+            })
+            .map(|series| series.nickname().to_string())
+            .collect()
+    }
+
+    /// Have every list entry with unsynced changes sent to the remote service in the background.
+    pub fn request_entry_sync(&self) {
+        self.entry_sync.notify_one();
+    }
+
+    /// Switch to offline mode, using whatever series info has been cached.
+    pub fn go_offline(&mut self) {
+        self.remote = RemoteStatus::LoggedIn(Arc::new(cache::offline_remote(&self.db)));
+    }
+
+    /// Connect to AniList without an account, so series can still be searched for and added.
+    ///
+    /// List changes can't be sent without an account, so they're kept locally until one is logged in to.
+    pub fn go_anonymous(&mut self) -> Result<()> {
+        let client = self.config.anilist_client()?;
+        self.remote = RemoteStatus::LoggedIn(Arc::new(AniList::Unauthenticated(client).into()));
+
+        self.log.push(
+            LogKind::Info,
+            "not logged in to an account, so list changes will only be kept locally\nlog in from the user panel to sync them",
+        );
+
+        Ok(())
+    }
+
+    /// Log an error from a request to the remote service.
+    ///
+    /// If the remote service rejected the current user's token, we go offline so every request after it doesn't fail as well,
+    /// and the user is asked to log in again.
+    pub fn push_remote_error(&mut self, err: &anyhow::Error) {
+        self.log.push_error(err);
+
+        let unauthorized = err.chain().any(|cause| {
+            matches!(cause.downcast_ref::<anime::Error>(), Some(cause) if cause.is_http_code(401))
+        });
+
+        if !unauthorized || self.expired_login.is_some() {
+            return;
+        }
+
+        let user = match &self.users.last_used {
+            Some(user) => user.clone(),
+            None => return,
+        };
+
+        self.log.push(
+            LogKind::Info,
+            format!(
+                "the login for {} on {} has expired, paste a new token in the user panel to log back in",
+                user.username,
+                user.service.as_str()
+            ),
+        );
+
+        self.expired_login = Some(user);
+        self.go_offline();
+    }
+
+    /// Warn about the current user's token if it has expired or is about to.
+    fn warn_about_token_expiry(&mut self) {
+        let user = match &self.users.last_used {
+            Some(user) => user,
+            None => return,
+        };
+
+        let days = match self.users.days_until_expiry(user, Utc::now().timestamp()) {
+            Some(days) if days < EXPIRY_WARNING_DAYS => days,
+            _ => return,
+        };
+
+        let when = match days {
+            days if days < 0 => "has expired".into(),
+            0 => "expires today".into(),
+            1 => "expires in 1 day".into(),
+            days => format!("expires in {} days", days),
+        };
+
+        let msg = format!(
+            "the login for {} on {} {}, add the account again in the user panel to renew it",
+            user.username,
+            user.service.as_str(),
+            when
+        );
+
+        self.log.push(LogKind::Info, msg);
+    }
+
     pub fn select_initial_series(&mut self, args: &Args) -> Result<()> {
         let mut desired_series = args.series.as_ref().map(Cow::Borrowed);
 
@@ -111,7 +336,7 @@ impl UIState {
             Some(desired) => self
                 .series
                 .iter()
-                .position(|series| series.nickname() == desired.as_ref())
+                .position(|series| series.has_name(&desired))
                 .unwrap_or(0),
             None => 0,
         };
@@ -122,29 +347,38 @@ impl UIState {
         Ok(())
     }
 
-    pub fn add_series<E>(
+    /// Add a series whose list entry has been retrieved from the remote service.
+    fn insert_new_series(
         &mut self,
-        config: SeriesConfig,
-        info: SeriesInfo,
-        episodes: E,
-    ) -> Result<()>
-    where
-        E: Into<Option<SortedEpisodes>>,
-    {
-        let remote = self.remote.get_logged_in()?;
-        let data = SeriesData::from_remote(config, info, remote)?;
-
-        let series = match episodes.into() {
+        data: SeriesData,
+        episodes: Option<SortedEpisodes>,
+    ) -> Result<()> {
+        let series = match episodes {
             Some(episodes) => LoadedSeries::Complete(Series::with_episodes(data, episodes)),
             None => Series::init(data, &self.config),
         };
 
         series.save(&self.db)?;
 
+        if let LoadedSeries::Complete(series) = &series {
+            if let Some(opening) = series.numbered_opening() {
+                self.offset_prompt = Some(OffsetPrompt {
+                    series_id: series.data.config.id,
+                    nickname: series.data.config.nickname.clone(),
+                    filename: opening.filename.clone(),
+                });
+            }
+        }
+
+        self.insert_and_select_series(series);
+        Ok(())
+    }
+
+    fn insert_and_select_series(&mut self, series: LoadedSeries) {
         let nickname = series.nickname().to_string();
 
         self.series.push(series);
-        self.series.items_mut().sort_unstable();
+        self.sort_series();
 
         let selected = self
             .series
@@ -153,194 +387,2298 @@ impl UIState {
             .unwrap_or(0);
 
         self.series.set_selected(selected);
+    }
+
+    /// Add every series in the database that isn't in the series list yet, such as those that were just imported.
+    fn load_new_series(&mut self) -> Result<()> {
+        let new = SeriesConfig::load_all(&self.db)?
+            .into_iter()
+            .filter(|sconfig| {
+                !self
+                    .series
+                    .iter()
+                    .any(|series| series.config().id == sconfig.id)
+            })
+            .map(|sconfig| Series::load_unscanned(sconfig, &self.db))
+            .collect::<Vec<_>>();
+
+        for series in new {
+            self.series.push(series);
+        }
+
+        self.sort_series();
+        Ok(())
+    }
+
+    /// Sort the series list with the current view, keeping the same series selected.
+    fn sort_series(&mut self) {
+        let selected = self.series.selected().map(|series| series.config().id);
+
+        if let Err(err) = self.list_view.sort.sort(self.series.items_mut(), &self.db) {
+            self.log
+                .push_error(&anyhow!(err).context("failed to sort series"));
+        }
+
+        let index = selected.and_then(|id| {
+            self.series
+                .iter()
+                .position(|series| series.config().id == id)
+        });
+
+        if let Some(index) = index {
+            self.series.set_selected(index);
+        }
+    }
+
+    /// Change how the series list is ordered, and save it for the next time the program is opened.
+    pub fn set_series_sort(&mut self, sort: SeriesSort) -> Result<()> {
+        self.list_view.sort = sort;
+        self.list_view.save(&self.db)?;
+        self.sort_series();
+        Ok(())
+    }
+
+    /// Only show series that pass `filter` in the series list, or every series if it's None, and save it for the next time the program is opened.
+    ///
+    /// If the selected series is hidden by the filter, the first series shown after it is selected instead.
+    pub fn set_series_filter(&mut self, filter: Option<ListFilter>) -> Result<()> {
+        self.list_view.set_filter(filter);
+        self.list_view.save(&self.db)?;
+
+        let hidden = self
+            .series
+            .selected()
+            .map_or(false, |series| !self.list_view.shows(series));
+
+        if hidden {
+            self.select_shown_series(true);
+        }
+
         Ok(())
     }
 
+    /// Select the next series the series list shows, or the previous one when `forwards` is false.
+    pub fn select_shown_series(&mut self, forwards: bool) {
+        let index = list_view::next_shown(
+            self.series.items(),
+            &self.list_view,
+            self.series.index(),
+            forwards,
+        );
+
+        if let Some(index) = index {
+            self.series.set_selected(index);
+            self.init_selected_series();
+        }
+    }
+
     pub fn init_selected_series(&mut self) {
         let selected = try_opt_ret!(self.series.selected_mut());
         selected.try_load(&self.config, &self.db)
     }
 
-    pub fn delete_selected_series(&mut self) -> Result<LoadedSeries> {
+    /// Mark the selected series if it isn't marked already, or unmark it otherwise.
+    pub fn toggle_selected_mark(&mut self) {
+        let id = try_opt_ret!(self.series.selected()).config().id;
+
+        if !self.marked_series.remove(&id) {
+            self.marked_series.insert(id);
+        }
+    }
+
+    pub fn delete_selected_series(&mut self) -> Result<DeletedSeries> {
         if !self.series.is_valid_index() {
             return Err(anyhow!("must select series to delete"));
         }
 
         let index = self.series.index();
         let series = self.series.items_mut().remove(index);
+        self.marked_series.remove(&series.config().id);
 
         self.series.update_bounds();
         // Since we changed our selected series, we need to make sure the new one is initialized
         self.init_selected_series();
 
-        series.config().delete(&self.db)?;
-        Ok(series)
+        let rows = DeletedRows::delete_series(series.config(), &self.db)?;
+        Ok(DeletedSeries { series, rows })
     }
 
-    async fn start_next_series_episode(&mut self) -> Result<(Child, ProgressTime)> {
-        let series = match self.series.get_valid_sel_series_mut() {
-            Some(series) => series,
-            None => return Err(anyhow!("no series selected")),
-        };
-
-        let is_diff_series = self.last_watched.set(&series.data.config.nickname);
+    /// Delete every marked series and clear the marks.
+    ///
+    /// Series that fail to be deleted are kept in the series list and have their error logged.
+    pub fn delete_marked_series(&mut self) -> Vec<DeletedSeries> {
+        let marked = mem::take(&mut self.marked_series);
+        let mut deleted = Vec::with_capacity(marked.len());
+
+        for series in mem::take(self.series.items_mut()) {
+            if !marked.contains(&series.config().id) {
+                self.series.push(series);
+                continue;
+            }
 
-        if is_diff_series {
-            self.last_watched
-                .save()
-                .context("setting last watched series")?;
+            match DeletedRows::delete_series(series.config(), &self.db) {
+                Ok(rows) => deleted.push(DeletedSeries { series, rows }),
+                Err(err) => {
+                    let err =
+                        anyhow!(err).context(format!("failed to delete {}", series.nickname()));
+                    self.log.push_error(&err);
+                    self.series.push(series);
+                }
+            }
         }
 
-        let remote = self.remote.get_logged_in()?;
+        self.series.update_bounds();
+        self.init_selected_series();
 
-        series
-            .begin_watching(remote, &self.config, &self.db)
-            .context("updating series status")?;
+        deleted
+    }
 
-        let next_ep = series.data.entry.watched_episodes() + 1;
+    /// Resolve the missing folder of the series in `missing` with `action`, updating it in the series list.
+    ///
+    /// Deleted series can be restored with the undo key.
+    pub fn resolve_missing_series(
+        &mut self,
+        missing: &MissingSeries,
+        action: CleanAction,
+    ) -> Result<()> {
+        let id = missing.config.id;
 
-        let child = series
-            .play_episode(next_ep as u32, &self.config)
-            .context("playing episode")?;
+        let index = self
+            .series
+            .iter()
+            .position(|series| series.config().id == id)
+            .ok_or_else(|| anyhow!("{} was already removed", missing.config.nickname))?;
 
-        let progress_time = series.data.next_watch_progress_time(&self.config);
+        match missing.resolve(action, &self.db)? {
+            Resolved::Kept(sconfig) => {
+                self.series.items_mut()[index] =
+                    Series::load_from_config(sconfig, &self.config, &self.db);
+            }
+            Resolved::Deleted(rows) => {
+                let series = self.series.items_mut().remove(index);
+                self.marked_series.remove(&id);
+                self.series.update_bounds();
+                self.undo
+                    .push(UndoAction::Delete(DeletedSeries { series, rows }));
+            }
+        }
 
-        Ok((child, progress_time))
+        self.init_selected_series();
+        Ok(())
     }
 
-    pub async fn play_next_series_episode(&mut self, shared_state: &SharedState) -> Result<()> {
-        let (ep_process, progress_time) = self.start_next_series_episode().await?;
+    /// Save the entry of a `merge` whose conflicts have all been resolved, and have it synced to the remote service.
+    ///
+    /// The recorded changes of the entry are forgotten so the resolved values are sent as they are on the next sync.
+    pub fn apply_resolved_merge(&mut self, merge: EntryMerge) -> Result<()> {
+        let entry = merge.into_merged();
+        let db = &self.db;
 
-        self.events
-            .send(StateEvent::StartedEpisode(progress_time))
-            .ok();
+        db.transaction("apply resolved merge", || {
+            entry.save(db)?;
+            EntryChange::clear(db, entry.id())?;
+            Ok::<_, anyhow::Error>(())
+        })?;
 
-        self.input_state = InputState::Locked;
-
-        let shared_state = shared_state.clone();
+        let data = self
+            .series
+            .items_mut()
+            .iter_mut()
+            .filter_map(LoadedSeries::data_mut)
+            .find(|data| data.entry.id() == entry.id());
 
-        task::spawn(async move {
-            let result = shared_state
-                .track_episode_finish(ep_process, progress_time)
-                .await;
+        if let Some(data) = data {
+            data.entry = entry;
+        }
 
-            let mut state = shared_state.lock();
-            let state = state.get_mut();
+        self.request_entry_sync();
+        Ok(())
+    }
 
-            if let Err(err) = result {
-                state.log.push_error(&err);
+    /// Point the selected series at the folder its old one looks to have been renamed to.
+    pub fn relink_selected(&mut self) -> Result<()> {
+        let (data, moved_to) = match self.series.selected() {
+            Some(LoadedSeries::Partial(data, EpisodeScanError::FolderMoved { moved_to, .. })) => {
+                (data, moved_to)
             }
+            _ => {
+                return Err(anyhow!(
+                    "the selected series doesn't have a moved folder to relink"
+                ))
+            }
+        };
 
-            state.input_state.reset();
-            state.events.send(StateEvent::FinishedEpisode).ok();
-        });
+        let mut sconfig = data.config.clone();
+        sconfig.path = moved_to.clone();
+        sconfig.save(&self.db)?;
+
+        self.log.push(
+            LogKind::Info,
+            format!(
+                "relinked {} to {}",
+                sconfig.nickname,
+                sconfig.path.display()
+            ),
+        );
 
+        let index = self.series.index();
+        self.series.items_mut()[index] = Series::load_from_config(sconfig, &self.config, &self.db);
+
+        self.init_selected_series();
         Ok(())
     }
-}
 
-pub type ReactiveState = Reactive<UIState>;
+    /// Reverts the most recent change made to a series.
+    pub fn undo_last_action(&mut self) -> Result<()> {
+        let action = match self.undo.pop() {
+            Some(action) => action,
+            None => return Err(anyhow!("nothing to undo")),
+        };
 
-#[derive(Clone)]
-pub struct SharedState(ArcMutex<ReactiveState>);
+        let reverted = self.revert_action(action)?;
 
-impl SharedState {
-    pub fn new(state: ReactiveState) -> Self {
-        Self(arc_mutex(state))
-    }
+        self.request_entry_sync();
+        self.init_selected_series();
+        self.log.push(
+            LogKind::Info,
+            format!("reverted the last change made to {}", reverted),
+        );
 
-    pub fn login_to_remote_async(&self, login: RemoteLogin) {
-        let shared_state = self.clone();
+        Ok(())
+    }
 
-        task::spawn_blocking(move || match login {
-            RemoteLogin::AniList(username, token) => {
-                {
-                    let mut state = shared_state.lock();
-                    state.get_mut().remote = RemoteStatus::LoggingIn(username);
+    /// Reverts the change described by `action` and returns a description of the series it was made to.
+    fn revert_action(&mut self, action: UndoAction) -> Result<String> {
+        let nickname = match action {
+            UndoAction::Entry(entry) => {
+                let index = self.series_index_by_id(entry.id())?;
+                let series = self.series.items_mut()[index]
+                    .complete_mut()
+                    .ok_or_else(|| anyhow!("series must be fully loaded to undo changes"))?;
+
+                series.data.entry.restore(entry);
+                series.save(&self.db)?;
+
+                let nickname = series.data.config.nickname.clone();
+                self.series.set_selected(index);
+                nickname
+            }
+            UndoAction::Sync(entry, info) => {
+                let index = self.series_index_by_id(entry.id())?;
+                let series = self.series.items_mut()[index]
+                    .complete_mut()
+                    .ok_or_else(|| anyhow!("series must be fully loaded to undo changes"))?;
+
+                series.data.entry.restore(entry);
+                series.data.info = info;
+                series.save(&self.db)?;
+
+                let nickname = series.data.config.nickname.clone();
+                self.series.set_selected(index);
+                nickname
+            }
+            UndoAction::Config(sconfig) => {
+                let index = self.series_index_by_id(sconfig.id)?;
+                let series = self.series.items_mut()[index]
+                    .complete_mut()
+                    .ok_or_else(|| anyhow!("series must be fully loaded to undo changes"))?;
+
+                series.replace_config(sconfig, &self.config)?;
+                series.save(&self.db)?;
+
+                let nickname = series.data.config.nickname.clone();
+                self.series.set_selected(index);
+                nickname
+            }
+            UndoAction::Delete(deleted) => {
+                let sconfig = deleted.series.config();
+
+                let exists = self
+                    .series
+                    .iter()
+                    .any(|s| s.config().id == sconfig.id || s.nickname() == sconfig.nickname);
+
+                if exists {
+                    return Err(anyhow!(
+                        "{} can't be restored as a series with the same ID or name has been added",
+                        sconfig.nickname
+                    ));
                 }
 
-                let auth = Auth::retrieve(token);
-                let mut state = shared_state.lock();
-                let state = state.get_mut();
+                deleted.restore(&self.db)?;
 
-                let remote = match auth {
-                    Ok(auth) => {
-                        let anilist = AniList::Authenticated(auth);
-                        RemoteStatus::LoggedIn(anilist.into())
-                    }
-                    Err(err) => {
-                        state.log.push_error(&err.into());
-                        RemoteStatus::LoggedIn(Remote::offline())
+                let nickname = sconfig.nickname.clone();
+                let restored = Series::load_from_config(sconfig, &self.config, &self.db);
+
+                self.insert_and_select_series(restored);
+                nickname
+            }
+            UndoAction::Batch(actions) => {
+                let num_series = actions.len();
+
+                for action in actions {
+                    if let Err(err) = self.revert_action(action) {
+                        self.log.push_error(&err);
                     }
-                };
+                }
 
-                state.remote = remote;
+                format!("{} series", num_series)
             }
-        });
+        };
+
+        Ok(nickname)
     }
 
-    async fn track_episode_finish(
-        &self,
-        mut ep_process: Child,
-        progress_time: ProgressTime,
-    ) -> Result<()> {
-        ep_process
-            .wait()
-            .await
-            .context("waiting for episode to finish")?;
+    fn series_index_by_id(&self, id: i32) -> Result<usize> {
+        self.series
+            .iter()
+            .position(|series| series.config().id == id)
+            .ok_or_else(|| anyhow!("series to undo changes for no longer exists"))
+    }
 
-        let mut state = self.lock();
-        let state = state.get_mut();
+    /// Offer to continue with the sequel of the selected series if it was just completed and its sequel is in the series list.
+    ///
+    /// Sequels that have already been completed aren't offered.
+    pub fn queue_sequel_prompt(&mut self) -> Result<()> {
+        let completed = match self.series.selected().and_then(LoadedSeries::data) {
+            Some(data) if data.entry.status() == Status::Completed => data,
+            Some(_) | None => return Ok(()),
+        };
 
-        state.input_state.reset();
+        let sequel_id = match cache::direct_sequel(&self.db, completed.info.id as SeriesID)? {
+            Some(sequel_id) => sequel_id as i32,
+            None => return Ok(()),
+        };
 
-        if Utc::now() < progress_time {
-            return Ok(());
-        }
+        let sequel = self
+            .series
+            .iter()
+            .filter_map(LoadedSeries::data)
+            .find(|data| data.info.id == sequel_id);
 
-        let series = if let Some(series) = state.series.get_valid_sel_series_mut() {
-            series
-        } else {
-            return Ok(());
+        let sequel = match sequel {
+            Some(sequel) if sequel.entry.status() != Status::Completed => sequel,
+            Some(_) | None => return Ok(()),
         };
 
-        let remote = state.remote.get_logged_in()?;
+        self.sequel_prompt = Some(SequelPrompt {
+            completed: completed.config.nickname.clone(),
+            sequel_id: sequel.config.id,
+            sequel: sequel.config.nickname.clone(),
+        });
 
-        series
-            .episode_completed(remote, &state.config, &state.db)
-            .context("marking episode as completed")
+        Ok(())
     }
 
-    #[inline(always)]
-    pub fn lock(&self) -> MutexGuard<'_, ReactiveState> {
-        self.0.lock()
+    /// Select the sequel offered by `prompt` and set it to watching, unless it's already being watched.
+    pub fn continue_with_sequel(&mut self, prompt: &SequelPrompt) -> Result<()> {
+        let index = self
+            .series
+            .iter()
+            .position(|series| series.config().id == prompt.sequel_id)
+            .ok_or_else(|| anyhow!("{} is no longer in the series list", prompt.sequel))?;
+
+        self.series.set_selected(index);
+        self.init_selected_series();
+
+        let data = try_opt_r!(self.series.selected_mut().and_then(LoadedSeries::data_mut));
+
+        if !matches!(data.entry.status(), Status::Watching | Status::Rewatching) {
+            data.entry.set_status(Status::Watching, &self.config);
+            data.save(&self.db)?;
+            self.request_entry_sync();
+        }
+
+        self.log
+            .push(LogKind::Info, format!("continuing with {}", prompt.sequel));
+
+        Ok(())
     }
-}
 
-#[derive(Clone, Copy)]
-pub enum InputState {
-    Idle,
-    Locked,
-    FocusedOnMainPanel,
-    EnteringCommand,
-}
+    /// Shift the episodes of the series offered by `prompt` back by one, so its numbered creditless opening is skipped.
+    ///
+    /// This can be undone like the offset command.
+    pub fn apply_opening_offset(&mut self, prompt: &OffsetPrompt) -> Result<()> {
+        let index = self
+            .series
+            .iter()
+            .position(|series| series.config().id == prompt.series_id)
+            .ok_or_else(|| anyhow!("{} is no longer in the series list", prompt.nickname))?;
 
-impl InputState {
-    #[inline(always)]
-    pub fn reset(&mut self) {
-        *self = Self::default();
+        let series = self.series.items_mut()[index]
+            .complete_mut()
+            .ok_or_else(|| anyhow!("the episodes of {} aren't loaded", prompt.nickname))?;
+
+        let previous = series.data.config.clone();
+
+        series.set_episode_offset(-1, &self.config)?;
+        series.save(&self.db)?;
+
+        self.undo.push(UndoAction::Config(previous));
+        self.log.push(
+            LogKind::Info,
+            format!("shifted the episodes of {} back by one", prompt.nickname),
+        );
+
+        Ok(())
     }
-}
 
-impl Default for InputState {
-    fn default() -> Self {
-        Self::Idle
+    /// Returns true if the selected series should have its change out of the plan to watch status confirmed before being played.
+    pub fn selected_needs_watch_confirmation(&self) -> bool {
+        match self.series.selected() {
+            Some(LoadedSeries::Complete(series)) => {
+                series.data.entry.needs_watch_confirmation(&self.config)
+            }
+            Some(LoadedSeries::Partial(_, _))
+            | Some(LoadedSeries::Unscanned(_))
+            | Some(LoadedSeries::None(_, _))
+            | None => false,
+        }
     }
-}
 
-impl PartialEq for InputState {
-    fn eq(&self, other: &Self) -> bool {
-        mem::discriminant(self) == mem::discriminant(other)
+    /// Start playing the next episode of the selected series.
+    ///
+    /// `pulled` is the list entry of the series as it was on the remote service just before, if it was retrieved.
+    /// Returns the playing episode along with the number of it.
+    fn start_next_series_episode(
+        &mut self,
+        start: WatchStart,
+        pulled: Option<SeriesEntry>,
+    ) -> Result<(PlaybackHandle, u32)> {
+        let series = match self.series.get_valid_sel_series_mut() {
+            Some(series) => series,
+            None => return Err(anyhow!("no series selected")),
+        };
+
+        if let Some(pulled) = pulled {
+            // Changes made to the entry while it was being retrieved shouldn't be overwritten
+            if pulled.id() == series.data.entry.id() && !series.data.entry.needs_sync() {
+                series.data.entry = pulled;
+            }
+        }
+
+        series
+            .begin_watching(start, &self.config, &self.db)
+            .context("updating series status")?;
+
+        let next_ep = series.next_episode();
+        let playback = self.start_series_episode(next_ep, None)?;
+
+        self.request_entry_sync();
+        Ok((playback, next_ep))
+    }
+
+    /// Start playing `episode` of the selected series without changing its status or progress.
+    ///
+    /// The episode is played with the fallback player at index `fallback` when one is given.
+    fn start_series_episode(
+        &mut self,
+        episode: u32,
+        fallback: Option<usize>,
+    ) -> Result<PlaybackHandle> {
+        let series = match self.series.get_valid_sel_series_mut() {
+            Some(series) => series,
+            None => return Err(anyhow!("no series selected")),
+        };
+
+        let player = match fallback {
+            Some(index) => self
+                .config
+                .episode
+                .fallback_players
+                .get(index)
+                .ok_or_else(|| anyhow!("fallback player {} no longer exists", index + 1))?,
+            None => &self.config.episode.player,
+        };
+
+        let is_diff_series = self.last_watched.set(&series.data.config.nickname);
+
+        if is_diff_series {
+            self.last_watched
+                .save()
+                .context("setting last watched series")?;
+        }
+
+        if let Err(err) = Hook::BeforePlay.run(series, episode, &self.config) {
+            self.log.push_error(&err);
+        }
+
+        let playback = series
+            .play_episode_with(episode, player, &self.config, &self.db)
+            .context("playing episode")?;
+
+        self.fallback_player = fallback;
+        self.stream_finished = playback.stream_finished();
+
+        if self.stream_finished.is_some() {
+            self.log.push(
+                LogKind::Info,
+                format!(
+                    "streaming episode {}, press {} once you've finished watching",
+                    episode, self.config.tui.keys.play_next_episode
+                ),
+            );
+        }
+
+        Ok(playback)
+    }
+
+    /// Returns the input state to use while `playback` is running.
+    ///
+    /// Streams are finished with a key press, so they never hand the terminal over to the player.
+    fn playback_input_state(&self, playback: &PlaybackHandle) -> InputState {
+        if self.config.episode.player_inherit_stdio && playback.stream_finished().is_none() {
+            InputState::PlayerAttached
+        } else {
+            InputState::Locked
+        }
+    }
+
+    pub fn play_next_series_episode(
+        &mut self,
+        start: WatchStart,
+        shared_state: &SharedState,
+    ) -> Result<()> {
+        let series = match self.series.get_valid_sel_series() {
+            Some(series) => series,
+            None => return Err(anyhow!("no series selected")),
+        };
+
+        let remote = self.remote.get_logged_in_shared()?;
+
+        // Changes made on the remote service are only pulled in when we don't have any of our own to send
+        let entry = (!series.data.entry.needs_sync()).then(|| series.data.entry.clone());
+
+        self.input_state = InputState::Locked;
+
+        let shared_state = shared_state.clone();
+
+        task::spawn(async move {
+            let result = shared_state.play_next_episode(entry, &remote, start).await;
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            if let Err(err) = result {
+                state.push_remote_error(&err);
+            }
+
+            state.input_state.reset();
+            state.events.send(StateEvent::FinishedEpisode).ok();
+        });
+
+        Ok(())
+    }
+
+    /// Play `episode` of the selected series without changing its status or progress, such as when rewatching an earlier episode.
+    pub fn play_series_episode(&mut self, episode: u32, shared_state: &SharedState) -> Result<()> {
+        self.play_and_track_episode(episode, WatchStart::KeepStatus, None, shared_state)
+    }
+
+    /// Offer to play `episode` of the series with `series_id` again after its player crashed with `status`,
+    /// along with the next fallback player to try if there are any left.
+    fn queue_retry_prompt(
+        &mut self,
+        series_id: i32,
+        episode: u32,
+        start: WatchStart,
+        status: ExitStatus,
+    ) {
+        let player = self.fallback_player;
+
+        let player_name = player
+            .and_then(|index| self.config.episode.fallback_players.get(index))
+            .unwrap_or(&self.config.episode.player)
+            .clone();
+
+        let next = player.map_or(0, |index| index + 1);
+
+        let fallback = self
+            .config
+            .episode
+            .fallback_players
+            .get(next)
+            .map(|name| (next, name.clone()));
+
+        tracing::warn!(player = %player_name, %status, episode, "player crashed");
+
+        self.log.push(
+            LogKind::Error,
+            format!(
+                "{} exited with {} right after starting, so episode {} was not counted",
+                player_name, status, episode
+            ),
+        );
+
+        self.retry_prompt = Some(RetryPrompt {
+            series_id,
+            episode,
+            start,
+            player,
+            player_name,
+            status,
+            fallback,
+        });
+    }
+
+    /// Play the episode offered by `prompt` again, with its next fallback player if `use_fallback` is set.
+    pub fn retry_episode(
+        &mut self,
+        prompt: &RetryPrompt,
+        use_fallback: bool,
+        shared_state: &SharedState,
+    ) -> Result<()> {
+        let index = self
+            .series
+            .iter()
+            .position(|series| series.config().id == prompt.series_id)
+            .ok_or_else(|| anyhow!("series to play again no longer exists"))?;
+
+        self.series.set_selected(index);
+        self.init_selected_series();
+
+        let player = match &prompt.fallback {
+            Some((fallback, _)) if use_fallback => Some(*fallback),
+            Some(_) | None => prompt.player,
+        };
+
+        self.play_and_track_episode(prompt.episode, prompt.start, player, shared_state)
+    }
+
+    /// Play `episode` of the selected series with the fallback player at index `fallback` if one is given,
+    /// and handle it finishing according to `start`.
+    ///
+    /// The status of the series is never changed here, so it should already be updated when `start` says to.
+    fn play_and_track_episode(
+        &mut self,
+        episode: u32,
+        start: WatchStart,
+        fallback: Option<usize>,
+        shared_state: &SharedState,
+    ) -> Result<()> {
+        let playback = self.start_series_episode(episode, fallback)?;
+
+        if start == WatchStart::UpdateStatus {
+            self.events
+                .send(StateEvent::StartedEpisode(playback.progress_time()))
+                .ok();
+        }
+
+        self.input_state = self.playback_input_state(&playback);
+
+        let shared_state = shared_state.clone();
+
+        task::spawn(async move {
+            let result = shared_state
+                .track_episode_finish(playback, start, episode)
+                .await;
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            if let Err(err) = result {
+                state.log.push_error(&err);
+            }
+
+            state.input_state.reset();
+            state.events.send(StateEvent::FinishedEpisode).ok();
+        });
+
+        Ok(())
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn show_desktop_notification(summary: &str, msg: &str) -> Result<()> {
+    Notification::new()
+        .appname("anup")
+        .summary(summary)
+        .body(msg)
+        .show()
+        .context("failed to show desktop notification")?;
+
+    Ok(())
+}
+
+pub type ReactiveState = Reactive<UIState>;
+
+#[derive(Clone)]
+pub struct SharedState(ArcMutex<ReactiveState>);
+
+impl SharedState {
+    pub fn new(state: ReactiveState) -> Self {
+        Self(arc_mutex(state))
+    }
+
+    pub fn login_to_remote_async(&self, login: RemoteLogin, linked: Vec<RemoteLogin>) {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let (primary, linked) = {
+                let mut state = shared_state.lock();
+                let state = state.get_mut();
+
+                state.remote = RemoteStatus::LoggingIn(login.username().to_owned());
+
+                let prepare = |login: RemoteLogin| {
+                    login.prepare(&state.config).map(|mut prepared| {
+                        prepared.set_rate_limit_hook(shared_state.rate_limit_hook());
+                        prepared
+                    })
+                };
+
+                let linked = linked.into_iter().map(prepare).collect();
+
+                (prepare(login), linked)
+            };
+
+            let result = match primary {
+                Ok(primary) => remote::login_linked(primary, linked).await,
+                Err(err) => Err(err),
+            };
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            match result {
+                Ok((remote, linked_errors)) => {
+                    for err in linked_errors {
+                        state.log.push_error(&err);
+                    }
+
+                    let remote = Arc::new(remote);
+                    state.remote = RemoteStatus::LoggedIn(Arc::clone(&remote));
+                    state.expired_login = None;
+                    state.warn_about_token_expiry();
+
+                    // Changes made while offline can finally be sent
+                    state.request_entry_sync();
+
+                    let max_age = Duration::days(state.config.offline.cache_max_age_days.into());
+                    shared_state.refresh_cache_async(remote, Refresh::OlderThan(max_age));
+                }
+                Err(err) => {
+                    state.log.push_error(&err);
+                    state.go_offline();
+                }
+            }
+        });
+    }
+
+    /// Returns a hook for remote clients that logs whenever a request has to wait for the rate limit.
+    pub fn rate_limit_hook(&self) -> impl Fn(time::Duration) + Send + Sync + 'static {
+        let shared_state = self.clone();
+
+        move |wait| {
+            shared_state.lock().get_mut().log.push(
+                LogKind::Info,
+                format!("waiting for rate limit ({}s)", wait.as_secs_f32().ceil()),
+            );
+        }
+    }
+
+    /// Look up the list entry of a new series on `remote` in the background, and add the series once it's found.
+    pub fn add_series_async(
+        &self,
+        remote: Arc<Remote>,
+        config: SeriesConfig,
+        info: SeriesInfo,
+        episodes: Option<SortedEpisodes>,
+    ) {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let nickname = config.nickname.clone();
+            let result = SeriesData::from_remote(config, info, &remote).await;
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            if let Err(err) = result.and_then(|data| state.insert_new_series(data, episodes)) {
+                state.push_remote_error(&err.context(format!("failed to add {}", nickname)));
+            }
+        });
+    }
+
+    /// Add every series on the user's list on `remote` that hasn't been added yet in the background.
+    ///
+    /// The imported series don't have a folder, so their path has to be set by editing them before they can be played.
+    pub fn import_list_async(&self, remote: Arc<Remote>) {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let list = remote
+                .list_entries()
+                .await
+                .context("failed to get your list");
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            let result = list
+                .and_then(|list| import::import_remote(list, &state.db))
+                .and_then(|summary| state.load_new_series().map(|()| summary));
+
+            match result {
+                Ok(summary) => state.log.push(
+                    LogKind::Info,
+                    format!(
+                        "imported {} series from your list and skipped {} that were already added\nedit a series to set its path before watching it",
+                        summary.imported,
+                        summary.skipped.len()
+                    ),
+                ),
+                Err(err) => state.push_remote_error(&err.context("failed to import your list")),
+            }
+        });
+    }
+
+    /// Put a series without any episodes on disk on the user's list on `remote` in the background, and add it once that succeeds.
+    ///
+    /// The path and episode parser of the series can be set later by editing it.
+    pub fn add_planned_series_async(
+        &self,
+        remote: Arc<Remote>,
+        config: SeriesConfig,
+        info: SeriesInfo,
+    ) {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let nickname = config.nickname.clone();
+
+            let result = async {
+                let mut data = SeriesData::from_remote(config, info, &remote).await?;
+
+                data.entry
+                    .force_sync_to_remote(&remote)
+                    .await
+                    .context("adding series to list")?;
+
+                Ok(data)
+            }
+            .await;
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            if let Err(err) = result.and_then(|data| state.insert_new_series(data, None)) {
+                state.push_remote_error(&err.context(format!("failed to add {}", nickname)));
+            }
+        });
+    }
+
+    /// Look up the new ID of the series with `id` on `remote` in the background, then apply the rest of the update `params`.
+    pub fn update_series_async(&self, id: i32, mut params: UpdateParams, remote: Arc<Remote>) {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let fetched = params.fetch(&remote).await;
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            let result = fetched.and_then(|()| {
+                let series = state
+                    .series
+                    .items_mut()
+                    .iter_mut()
+                    .find(|series| series.config().id == id)
+                    .ok_or_else(|| anyhow!("series was removed before it could be updated"))?;
+
+                series.update(params, &state.config, &state.db, &remote)
+            });
+
+            if let Err(err) = result {
+                state.push_remote_error(&err.context("failed to update series"));
+            }
+        });
+    }
+
+    /// Send every list entry with unsynced changes to the remote service whenever it's requested with [`UIState::request_entry_sync`].
+    ///
+    /// Entries are only sent while logged in to a service with a list, so changes made otherwise are kept until the next request.
+    pub fn watch_entry_sync_async(&self) {
+        let shared_state = self.clone();
+        let requested = Arc::clone(&self.lock().entry_sync);
+
+        task::spawn(async move {
+            loop {
+                requested.notified().await;
+                shared_state.sync_pending_entries().await;
+            }
+        });
+    }
+
+    async fn sync_pending_entries(&self) {
+        let (remote, pending) = {
+            let mut state = self.lock();
+
+            let remote = match &state.remote {
+                RemoteStatus::LoggedIn(remote) if remote.has_list() => Arc::clone(remote),
+                RemoteStatus::LoggedIn(_) | RemoteStatus::LoggingIn(_) => return,
+            };
+
+            let pending = SeriesEntry::entries_that_need_sync(&state.db).and_then(|entries| {
+                entries
+                    .into_iter()
+                    // Entries with unresolved conflicts are left alone until the user decides what to keep
+                    .filter(|entry| {
+                        !state
+                            .sync_conflicts
+                            .iter()
+                            .any(|merge| merge.id() == entry.id())
+                    })
+                    .map(|entry| {
+                        let changes = EntryChange::load(&state.db, entry.id())?;
+                        Ok((entry, changes))
+                    })
+                    .collect::<diesel::QueryResult<Vec<_>>>()
+            });
+
+            match pending {
+                Ok(pending) => (remote, pending),
+                Err(err) => {
+                    let err = anyhow!(err).context("failed to load list entries to sync");
+                    state.get_mut().log.push_error(&err);
+                    return;
+                }
+            }
+        };
+
+        for (entry, changes) in pending {
+            let result: Result<std::result::Result<_, EntryMerge>> = async {
+                let latest = if changes.is_empty() {
+                    None
+                } else {
+                    remote.get_list_entry(entry.id() as u32).await?
+                };
+
+                let merge = EntryMerge::new(&entry, latest, &changes);
+
+                if !merge.is_resolved() {
+                    return Ok(Err(merge));
+                }
+
+                let mut synced = merge.into_merged();
+                synced.force_sync_to_remote(&remote).await?;
+                Ok(Ok(synced))
+            }
+            .await;
+
+            let mut state = self.lock();
+            let state = state.get_mut();
+
+            let data = state
+                .series
+                .items_mut()
+                .iter_mut()
+                .filter_map(LoadedSeries::data_mut)
+                .find(|data| data.entry.id() == entry.id());
+
+            let synced = match result {
+                Ok(Ok(synced)) => synced,
+                Ok(Err(merge)) => {
+                    // The entry was changed again while the remote one was being fetched, so the merge is already stale
+                    if data.map_or(true, |data| data.entry == entry) {
+                        state.sync_conflicts.push(merge);
+                    }
+
+                    continue;
+                }
+                Err(err) => {
+                    let name = data.map_or_else(
+                        || format!("series with ID {}", entry.id()),
+                        |data| data.config.nickname.clone(),
+                    );
+
+                    state.push_remote_error(&err.context(format!("failed to sync {}", name)));
+                    continue;
+                }
+            };
+
+            let saved = match data {
+                // The entry was changed again while it was being sent, so it still needs to be synced
+                Some(data) if data.entry != entry => continue,
+                Some(data) => {
+                    data.entry = synced;
+                    data.entry.save(&state.db)
+                }
+                None => synced.save(&state.db),
+            };
+
+            if let Err(err) = saved {
+                let err = anyhow!(err).context("failed to save synced list entry");
+                state.log.push_error(&err);
+            }
+        }
+
+        self.advance_merged_seasons(&remote);
+    }
+
+    /// Move every series watched through a folder with merged seasons on to its next season, once its current one has been completed and synced.
+    fn advance_merged_seasons(&self, remote: &Arc<Remote>) {
+        let mut state = self.lock();
+        let state = state.get_mut();
+
+        let advancing = state
+            .series
+            .iter()
+            .filter_map(LoadedSeries::data)
+            .filter_map(|data| {
+                let season = data.next_merged_season()?;
+                Some((
+                    data.config.id,
+                    data.config.nickname.clone(),
+                    season.season_id,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        for (id, nickname, season_id) in advancing {
+            state.log.push(
+                LogKind::Info,
+                format!("moving {} on to its next season", nickname),
+            );
+
+            let params = UpdateParams {
+                id: Some(season_id as SeriesID),
+                path: None,
+                parser: None,
+                episodes: None,
+                fetched: None,
+            };
+
+            self.update_series_async(id, params, Arc::clone(remote));
+        }
+    }
+
+    /// Pull in the latest version of the selected series' list `entry` from `remote` if one is given,
+    /// then play its next episode and wait for it to finish.
+    async fn play_next_episode(
+        &self,
+        entry: Option<SeriesEntry>,
+        remote: &Remote,
+        start: WatchStart,
+    ) -> Result<()> {
+        let pulled = match entry {
+            Some(mut entry) => {
+                entry
+                    .sync_from_remote(remote)
+                    .await
+                    .context("updating series status")?;
+
+                Some(entry)
+            }
+            None => None,
+        };
+
+        let (playback, episode) = {
+            let mut state = self.lock();
+            let state = state.get_mut();
+            let (playback, episode) = state.start_next_series_episode(start, pulled)?;
+
+            if start == WatchStart::UpdateStatus {
+                state
+                    .events
+                    .send(StateEvent::StartedEpisode(playback.progress_time()))
+                    .ok();
+            }
+
+            state.input_state = state.playback_input_state(&playback);
+
+            (playback, episode)
+        };
+
+        self.track_episode_finish(playback, start, episode).await
+    }
+
+    /// Refresh the cached series info selected by `refresh` in the background.
+    ///
+    /// The state is only locked while accessing the database, so the UI can still be used while series info is being fetched.
+    pub fn refresh_cache_async(&self, remote: Arc<Remote>, refresh: Refresh) {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let result = cache::refresh(|| shared_state.lock_db(), &remote, refresh).await;
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    state.push_remote_error(&err.context("failed to refresh offline info"));
+                    return;
+                }
+            };
+
+            for (id, err) in result.failed {
+                let err = err.context(format!("failed to get info for anime with ID {}", id));
+                state.log.push_error(&err);
+            }
+
+            if result.refreshed > 0 {
+                state.log.push(
+                    LogKind::Info,
+                    format!("stored offline info for {} series", result.refreshed),
+                );
+            }
+        });
+    }
+
+    /// Periodically check the airing schedule of every series being watched or planned and report newly aired episodes that aren't on disk yet.
+    pub fn watch_airing_async(&self) {
+        /// How long to wait before the first check, so logging in has a chance to finish.
+        const STARTUP_DELAY: time::Duration = time::Duration::from_secs(30);
+
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let mut reported = HashSet::new();
+            let mut delay = STARTUP_DELAY;
+
+            loop {
+                tokio::time::sleep(delay).await;
+
+                reported = shared_state.report_aired_episodes(reported).await;
+
+                let interval_mins = shared_state.lock().config.notifications.check_interval_mins;
+                delay = time::Duration::from_secs(u64::from(interval_mins.max(1)) * 60);
+            }
+        });
+    }
+
+    /// Log a summary of the previous week once a new week starts.
+    pub fn watch_weekly_summary_async(&self) {
+        /// How often to check if a new week has started.
+        const CHECK_INTERVAL: time::Duration = time::Duration::from_secs(60 * 60);
+
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            loop {
+                if let Err(err) = shared_state.report_weekly_summary().await {
+                    shared_state.lock().get_mut().log.push_error(&err);
+                }
+
+                tokio::time::sleep(CHECK_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Log what was watched last week and how it compared to the weekly goals, unless it already has been.
+    async fn report_weekly_summary(&self) -> Result<()> {
+        let (msg, desktop) = {
+            let state = self.lock();
+
+            if !state.config.goals.weekly_summary {
+                return Ok(());
+            }
+
+            let mut last =
+                LastWeeklySummary::load_or_create().context("loading the last weekly summary")?;
+
+            let before = last.clone();
+            let due = last.take_due(Local::today().naive_local());
+
+            if last != before {
+                last.save().context("saving the last weekly summary")?;
+            }
+
+            let week = match due {
+                Some(week) => week,
+                None => return Ok(()),
+            };
+
+            let stats =
+                WeekStats::load(&state.db, week).context("loading last week's watch history")?;
+
+            (
+                stats.summary(&state.config.goals),
+                state.config.notifications.desktop,
+            )
+        };
+
+        if desktop {
+            let body = msg.clone();
+
+            if let Ok(Err(err)) =
+                task::spawn_blocking(move || show_desktop_notification("Weekly Summary", &body))
+                    .await
+            {
+                self.lock().get_mut().log.push_error(&err);
+            }
+        }
+
+        self.lock().get_mut().log.push(LogKind::Info, msg);
+        Ok(())
+    }
+
+    /// Log every episode that aired within the last day and isn't on disk, unless it's in `reported` already.
+    ///
+    /// Returns `reported` with the newly reported episodes added to it.
+    async fn report_aired_episodes(
+        &self,
+        mut reported: HashSet<(SeriesID, u32)>,
+    ) -> HashSet<(SeriesID, u32)> {
+        /// How far back to look for aired episodes.
+        const LOOKBACK_SECS: i64 = 24 * 60 * 60;
+
+        let (remote, series, desktop) = {
+            let state = self.lock();
+
+            if !state.config.notifications.airing {
+                return reported;
+            }
+
+            let remote = match &state.remote {
+                RemoteStatus::LoggedIn(remote) if !remote.is_offline() => Arc::clone(remote),
+                RemoteStatus::LoggedIn(_) | RemoteStatus::LoggingIn(_) => return reported,
+            };
+
+            let series = state
+                .series
+                .iter()
+                .filter_map(|series| match series {
+                    LoadedSeries::Complete(series) => Some(series),
+                    LoadedSeries::Partial(..)
+                    | LoadedSeries::Unscanned(..)
+                    | LoadedSeries::None(..) => None,
+                })
+                .filter(|series| {
+                    matches!(
+                        series.data.entry.status(),
+                        Status::Watching | Status::Rewatching | Status::PlanToWatch
+                    )
+                })
+                .map(|series| {
+                    let id = series.data.info.id as SeriesID;
+                    let title = series.data.info.title_preferred.clone();
+                    let on_disk = series
+                        .episodes
+                        .iter()
+                        .map(|episode| episode.number..=episode.last_number)
+                        .collect::<Vec<_>>();
+
+                    (id, (title, on_disk))
+                })
+                .collect::<HashMap<_, _>>();
+
+            (remote, series, state.config.notifications.desktop)
+        };
+
+        if series.is_empty() {
+            return reported;
+        }
+
+        let ids = series.keys().copied().collect::<Vec<_>>();
+        let now = Utc::now().timestamp();
+
+        let aired = match remote.aired_episodes(&ids, now - LOOKBACK_SECS, now).await {
+            Ok(aired) => aired,
+            Err(err) => {
+                let err = anyhow!(err).context("failed to check for newly aired episodes");
+                self.lock().get_mut().push_remote_error(&err);
+                return reported;
+            }
+        };
+
+        if let Err(err) = cache::store_aired(&self.lock_db(), &ids, &aired, now) {
+            let err = anyhow!(err).context("failed to store aired episodes");
+            self.lock().get_mut().log.push_error(&err);
+        }
+
+        for aired in aired {
+            let (title, on_disk) = match series.get(&aired.id) {
+                Some(series) => series,
+                None => continue,
+            };
+
+            let have_episode = on_disk.iter().any(|range| range.contains(&aired.episode));
+
+            if have_episode || !reported.insert((aired.id, aired.episode)) {
+                continue;
+            }
+
+            let msg = format!("episode {} of {} has aired", aired.episode, title);
+
+            if desktop {
+                let body = msg.clone();
+
+                if let Ok(Err(err)) =
+                    task::spawn_blocking(move || show_desktop_notification("New Episode", &body))
+                        .await
+                {
+                    self.lock().get_mut().log.push_error(&err);
+                }
+            }
+
+            self.lock().get_mut().log.push(LogKind::Info, msg);
+        }
+
+        reported
+    }
+
+    /// Rank every series being watched by how much it should be watched next, and select the best one.
+    ///
+    /// The airing schedule of each series is checked with `remote` if it hasn't been checked recently.
+    pub fn suggest_next_async(&self, remote: Arc<Remote>) {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let (ids, max_age) = {
+                let state = shared_state.lock();
+
+                let ids = state
+                    .series
+                    .iter()
+                    .filter_map(LoadedSeries::complete)
+                    .filter(|series| {
+                        matches!(
+                            series.data.entry.status(),
+                            Status::Watching | Status::Rewatching
+                        )
+                    })
+                    .map(|series| series.data.info.id as SeriesID)
+                    .collect::<Vec<_>>();
+
+                let interval_mins = state.config.notifications.check_interval_mins;
+                (ids, Duration::minutes(interval_mins.into()))
+            };
+
+            let latest_aired =
+                cache::latest_aired(|| shared_state.lock_db(), &remote, &ids, max_age).await;
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            let latest_aired = match latest_aired {
+                Ok(latest_aired) => latest_aired,
+                Err(err) => {
+                    state.push_remote_error(&err.context("failed to check airing schedules"));
+                    return;
+                }
+            };
+
+            let last_watched = match WatchRecord::last_watched_times(&state.db) {
+                Ok(last_watched) => last_watched,
+                Err(err) => {
+                    state
+                        .log
+                        .push_error(&anyhow!(err).context("failed to load watch history"));
+                    return;
+                }
+            };
+
+            // Series can only be suggested once it's known which of their episodes are on disk
+            for series in state.series.items_mut() {
+                let watching = series.is_unscanned()
+                    && series.data().map_or(false, |data| {
+                        matches!(data.entry.status(), Status::Watching | Status::Rewatching)
+                    });
+
+                if watching {
+                    series.try_load(&state.config, &state.db);
+                }
+            }
+
+            let now = Utc::now().timestamp();
+            let ranked = suggest::rank(state.series.iter(), &latest_aired, &last_watched, now);
+
+            let (index, candidate) = match ranked.first() {
+                Some(&best) => best,
+                None => {
+                    state.log.push(
+                        LogKind::Info,
+                        "nothing to suggest, as no watched series has an unwatched episode",
+                    );
+                    return;
+                }
+            };
+
+            state.series.set_selected(index);
+            state.init_selected_series();
+
+            let title = state
+                .series
+                .selected()
+                .and_then(LoadedSeries::info)
+                .map_or("the selected series", |info| info.title_preferred.as_str());
+
+            let msg = format!(
+                "watch {} next ({} episode(s) behind)",
+                title, candidate.episodes_behind
+            );
+
+            state.log.push(LogKind::Info, msg);
+        });
+    }
+
+    /// Periodically check the RSS feed of every series that has one and report releases of episodes that aren't on disk or watched yet.
+    pub fn watch_feeds_async(&self) {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let mut reported = HashSet::new();
+
+            loop {
+                reported = shared_state.report_feed_releases(reported).await;
+
+                let interval_mins = shared_state.lock().config.feeds.check_interval_mins;
+                tokio::time::sleep(time::Duration::from_secs(
+                    u64::from(interval_mins.max(1)) * 60,
+                ))
+                .await;
+            }
+        });
+    }
+
+    /// Log every new release found in the feed of each series, unless it's in `reported` already.
+    ///
+    /// Returns `reported` with the newly reported releases added to it.
+    async fn report_feed_releases(&self, mut reported: HashSet<(i32, u32)>) -> HashSet<(i32, u32)> {
+        struct FeedSeries {
+            id: i32,
+            nickname: String,
+            url: String,
+            parser: EpisodeParser,
+            watched: u32,
+            on_disk: Vec<RangeInclusive<u32>>,
+            dir: Option<PathBuf>,
+        }
+
+        let (all_series, mut downloader, http) = {
+            let state = self.lock();
+            let downloader = Downloader::from_config(&state.config.downloads, &state.http);
+
+            let all_series = state
+                .series
+                .iter()
+                .filter_map(|series| match series {
+                    LoadedSeries::Complete(series) => Some(series),
+                    LoadedSeries::Partial(..)
+                    | LoadedSeries::Unscanned(..)
+                    | LoadedSeries::None(..) => None,
+                })
+                .filter_map(|series| {
+                    let config = &series.data.config;
+
+                    Some(FeedSeries {
+                        id: config.id,
+                        nickname: config.nickname.clone(),
+                        url: config.feed_url.clone()?,
+                        parser: config.episode_parser.clone(),
+                        watched: series.data.entry.watched_episodes() as u32,
+                        on_disk: series
+                            .episodes
+                            .iter()
+                            .map(|episode| episode.number..=episode.last_number)
+                            .collect(),
+                        dir: config
+                            .path
+                            .is_set()
+                            .then(|| config.path.absolute(&state.config).into_owned()),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            (all_series, downloader, state.http.clone())
+        };
+
+        for series in all_series {
+            let items = match feed::fetch(&http, &series.url).await {
+                Ok(items) => items,
+                Err(err) => {
+                    let err = err.context(format!("failed to check feed of {}", series.nickname));
+                    self.lock().get_mut().log.push_error(&err);
+                    continue;
+                }
+            };
+
+            for release in feed::releases(&items, &series.parser) {
+                let is_new = release.episodes.clone().any(|episode| {
+                    episode > series.watched
+                        && !series.on_disk.iter().any(|range| range.contains(&episode))
+                });
+
+                let first_episode = *release.episodes.start();
+
+                if !is_new || !reported.insert((series.id, first_episode)) {
+                    continue;
+                }
+
+                {
+                    let mut state = self.lock();
+                    let state = state.get_mut();
+
+                    state.log.push(
+                        LogKind::Info,
+                        format!(
+                            "episode {} of {} is available",
+                            first_episode, series.nickname
+                        ),
+                    );
+
+                    if let Err(err) = feed::hand_off(&release.item.link, &state.config) {
+                        state.log.push_error(&err);
+                    }
+                }
+
+                // Series without a folder yet have nowhere to download to
+                if let (Some(downloader), Some(dir)) = (&mut downloader, &series.dir) {
+                    if let Err(err) = downloader.add(&release.item.link, dir).await {
+                        let err = err.context(format!(
+                            "failed to send episode {} of {} to download client",
+                            first_episode, series.nickname
+                        ));
+
+                        self.lock().get_mut().log.push_error(&err);
+                    }
+                }
+            }
+        }
+
+        reported
+    }
+
+    /// Periodically refresh the torrents being downloaded by the configured download client and rescan each series that finishes downloading one.
+    pub fn watch_downloads_async(&self) {
+        /// How often the download client is checked for progress.
+        const CHECK_INTERVAL: time::Duration = time::Duration::from_secs(5);
+
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let mut tracker = DownloadTracker::default();
+
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+
+                tracker = shared_state.update_downloads(tracker).await;
+            }
+        });
+    }
+
+    async fn update_downloads(&self, mut tracker: DownloadTracker) -> DownloadTracker {
+        let (config, http) = {
+            let state = self.lock();
+            (state.config.downloads.clone(), state.http.clone())
+        };
+
+        if tracker.downloader.is_none() || tracker.config != config {
+            tracker.downloader = Downloader::from_config(&config, &http);
+            tracker.config = config;
+        }
+
+        let downloader = match &mut tracker.downloader {
+            Some(downloader) => downloader,
+            None => {
+                let mut state = self.lock();
+
+                if !state.downloads.is_empty() {
+                    state.get_mut().downloads.clear();
+                }
+
+                return tracker;
+            }
+        };
+
+        let torrents = match downloader.torrents().await {
+            Ok(torrents) => {
+                tracker.failing = false;
+                torrents
+            }
+            Err(err) => {
+                // The client is checked often, so only the first failure in a row is worth reporting
+                if !tracker.failing {
+                    let err = err.context("failed to check download progress");
+                    self.lock().get_mut().log.push_error(&err);
+                }
+
+                tracker.failing = true;
+                return tracker;
+            }
+        };
+
+        let mut state = self.lock();
+
+        // Rescanning a series shouldn't happen while an episode is being played
+        if matches!(
+            state.input_state,
+            InputState::Locked | InputState::PlayerAttached
+        ) {
+            return tracker;
+        }
+
+        let mut downloads = Vec::new();
+        let mut progress = HashMap::with_capacity(torrents.len());
+        let mut finished = Vec::new();
+
+        for torrent in torrents {
+            let series = state.series.iter().find(|series| {
+                let path = series.path();
+                path.is_set() && torrent.dir.starts_with(path.absolute(&state.config))
+            });
+
+            let series = match series {
+                Some(series) => series,
+                None => continue,
+            };
+
+            let was_downloading = matches!(
+                tracker.progress.get(&torrent.name),
+                Some(&progress) if progress < 1.0
+            );
+
+            if was_downloading && torrent.is_complete() {
+                finished.push((series.config().id, torrent.name.clone()));
+            }
+
+            progress.insert(torrent.name.clone(), torrent.progress);
+
+            downloads.push(SeriesDownload {
+                nickname: series.nickname().to_string(),
+                torrent,
+            });
+        }
+
+        tracker.progress = progress;
+
+        if finished.is_empty() && state.downloads == downloads {
+            return tracker;
+        }
+
+        let state = state.get_mut();
+        state.downloads = downloads;
+
+        for (id, name) in &finished {
+            state
+                .log
+                .push(LogKind::Info, format!("finished downloading {}", name));
+
+            let series = state
+                .series
+                .items_mut()
+                .iter_mut()
+                .find(|series| series.config().id == *id);
+
+            if let Some(series) = series {
+                *series =
+                    Series::load_from_config(series.config().clone(), &state.config, &state.db);
+            }
+        }
+
+        tracker
+    }
+
+    /// Scan the episodes of every series that was loaded without them, several at a time.
+    ///
+    /// Scanning every series one after another can take a while when their folders are on a network share.
+    /// Nothing is scanned if disabled in the config, in which case each series is only scanned once it's selected.
+    pub fn scan_series_async(&self) {
+        /// How many series can have their episodes scanned at once.
+        const MAX_CONCURRENT_SCANS: usize = 8;
+
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let (config, unscanned) = {
+                let state = shared_state.lock();
+
+                if !state.config.episode.scan_on_startup {
+                    return;
+                }
+
+                let unscanned = state
+                    .series
+                    .iter()
+                    .filter(|series| series.is_unscanned())
+                    .filter_map(|series| {
+                        let data = series.data()?;
+
+                        Some((data.config.clone(), data.titles(), data.episode_offset()))
+                    })
+                    .collect::<Vec<_>>();
+
+                (Arc::new(state.config.clone()), unscanned)
+            };
+
+            let mut scans = futures::stream::iter(unscanned)
+                .map(|(sconfig, titles, offset)| {
+                    let config = Arc::clone(&config);
+
+                    task::spawn_blocking(move || {
+                        let result = series::scan_episodes(&sconfig, &titles, offset, &config);
+                        (sconfig.id, result)
+                    })
+                })
+                .buffer_unordered(MAX_CONCURRENT_SCANS);
+
+            while let Some(scan) = scans.next().await {
+                let (id, result) = match scan {
+                    Ok(scan) => scan,
+                    Err(_) => return,
+                };
+
+                let mut state = shared_state.lock();
+                let state = state.get_mut();
+
+                let series = state
+                    .series
+                    .items_mut()
+                    .iter_mut()
+                    .find(|series| series.config().id == id);
+
+                if let Some(series) = series {
+                    series.finish_scan(result);
+                }
+            }
+        });
+    }
+
+    /// Watch the folder of every series for changes and rescan the series whose episodes were added, removed, or renamed.
+    pub fn watch_folders_async(&self) {
+        /// How long the folders have to go without changes before the changed series are rescanned.
+        ///
+        /// Downloading or moving an episode can cause a burst of changes, which should only cause one rescan.
+        const DEBOUNCE: time::Duration = time::Duration::from_secs(2);
+        /// How often the watched folders are matched up with the folders of the added series.
+        const SYNC_INTERVAL: time::Duration = time::Duration::from_secs(5);
+
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let (tx, mut rx) = mpsc::unbounded_channel();
+
+            let watcher =
+                notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(_) => return,
+                    };
+
+                    let changes_episodes = matches!(
+                        event.kind,
+                        EventKind::Create(_)
+                            | EventKind::Remove(_)
+                            | EventKind::Modify(ModifyKind::Name(_))
+                            | EventKind::Any
+                    );
+
+                    if changes_episodes {
+                        for path in event.paths {
+                            tx.send(path).ok();
+                        }
+                    }
+                });
+
+            let mut folders = match watcher {
+                Ok(watcher) => WatchedFolders::new(watcher),
+                Err(err) => {
+                    let err = anyhow!(err).context("failed to watch series folders");
+                    shared_state.lock().get_mut().log.push_error(&err);
+                    return;
+                }
+            };
+
+            let mut changed = HashSet::new();
+            shared_state.sync_watched_folders(&mut folders);
+
+            loop {
+                match tokio::time::timeout(SYNC_INTERVAL, rx.recv()).await {
+                    Ok(Some(path)) => {
+                        changed.insert(path);
+
+                        while let Ok(Some(path)) = tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                            changed.insert(path);
+                        }
+                    }
+                    Ok(None) => return,
+                    Err(_) => (),
+                }
+
+                shared_state.sync_watched_folders(&mut folders);
+
+                if !changed.is_empty() {
+                    changed = shared_state.rescan_changed_series(changed);
+                }
+            }
+        });
+    }
+
+    /// Start watching the folder of every series that isn't being watched yet, and stop watching the folders of series that were removed.
+    fn sync_watched_folders(&self, folders: &mut WatchedFolders) {
+        let state = self.lock();
+        let recursive = state.config.episode.recursive_scan;
+
+        // Changing the recursion of a folder requires watching it again
+        if !state.config.episode.watch_folders || recursive != folders.recursive {
+            folders.unwatch_all();
+            folders.recursive = recursive;
+        }
+
+        if !state.config.episode.watch_folders {
+            return;
+        }
+
+        let paths = state
+            .series
+            .iter()
+            .map(LoadedSeries::path)
+            .filter(|path| path.is_set())
+            .map(|path| path.absolute(&state.config).into_owned())
+            .collect::<HashSet<_>>();
+
+        drop(state);
+
+        folders.sync(paths);
+    }
+
+    /// Rescan every series whose folder contains a path in `changed`, and log the episodes that were found.
+    ///
+    /// Series can't be rescanned while an episode is being played, so `changed` is returned to be tried again later when that's the case.
+    fn rescan_changed_series(&self, changed: HashSet<PathBuf>) -> HashSet<PathBuf> {
+        let mut state = self.lock();
+
+        if matches!(
+            state.input_state,
+            InputState::Locked | InputState::PlayerAttached
+        ) {
+            return changed;
+        }
+
+        let state = state.get_mut();
+
+        for series in state.series.items_mut() {
+            let path = series.path();
+
+            if !path.is_set() {
+                continue;
+            }
+
+            let dir = path.absolute(&state.config);
+
+            if !changed.iter().any(|path| path.starts_with(&dir)) {
+                continue;
+            }
+
+            let last_episode = |series: &LoadedSeries| {
+                series
+                    .complete()
+                    .map_or(0, |series| series.episodes.highest_episode_number())
+            };
+
+            let old_last = last_episode(series);
+            *series = Series::load_from_config(series.config().clone(), &state.config, &state.db);
+            let new_last = last_episode(series);
+
+            let msg = match new_last.cmp(&old_last) {
+                Ordering::Greater if new_last == old_last + 1 => {
+                    format!("found episode {} of {}", new_last, series.nickname())
+                }
+                Ordering::Greater => format!(
+                    "found episodes {}-{} of {}",
+                    old_last + 1,
+                    new_last,
+                    series.nickname()
+                ),
+                Ordering::Equal | Ordering::Less => continue,
+            };
+
+            state.log.push(LogKind::Info, msg);
+        }
+
+        HashSet::new()
+    }
+
+    /// Periodically mark episodes that have been played on the configured media server as watched.
+    pub fn watch_media_server_async(&self) {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            loop {
+                shared_state.sync_media_server().await;
+
+                let interval_mins = shared_state.lock().config.media_server.check_interval_mins;
+                tokio::time::sleep(time::Duration::from_secs(
+                    u64::from(interval_mins.max(1)) * 60,
+                ))
+                .await;
+            }
+        });
+    }
+
+    async fn sync_media_server(&self) {
+        let (server_config, series_dir, http) = {
+            let state = self.lock();
+
+            if state.config.media_server.server.is_none() {
+                return;
+            }
+
+            (
+                state.config.media_server.clone(),
+                state.config.series_dir.clone(),
+                state.http.clone(),
+            )
+        };
+
+        let played = match media_server::played_episodes(&http, &server_config, &series_dir).await {
+            Ok(played) => played,
+            Err(err) => {
+                self.lock().get_mut().log.push_error(&err);
+                return;
+            }
+        };
+
+        if played.is_empty() {
+            return;
+        }
+
+        let mut state = self.lock();
+
+        // The series being played shouldn't have its progress changed from under it
+        if matches!(
+            state.input_state,
+            InputState::Locked | InputState::PlayerAttached
+        ) {
+            return;
+        }
+
+        let state = state.get_mut();
+        let mut any_synced = false;
+
+        for series in state.series.items_mut() {
+            let series = match series.complete_mut() {
+                Some(series) => series,
+                None => continue,
+            };
+
+            match series.sync_played_episodes(&played, &state.config, &state.db) {
+                Ok(0) => (),
+                Ok(synced) => {
+                    any_synced = true;
+
+                    state.log.push(
+                        LogKind::Info,
+                        format!(
+                            "marked {} episode(s) of {} as watched from your media server",
+                            synced, series.data.config.nickname
+                        ),
+                    );
+                }
+                Err(err) => {
+                    let err = err.context(format!(
+                        "failed to sync played episodes of {}",
+                        series.data.config.nickname
+                    ));
+
+                    state.log.push_error(&err);
+                }
+            }
+        }
+
+        if any_synced {
+            state.request_entry_sync();
+        }
+    }
+
+    /// Periodically check the config file for changes and reload it when it's modified.
+    pub fn watch_config_async(&self) {
+        /// How often the config file is checked for changes.
+        const CHECK_INTERVAL: time::Duration = time::Duration::from_secs(2);
+
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let path = match Config::validated_save_path() {
+                Ok(path) => path,
+                Err(_) => return,
+            };
+
+            let mut last_modified = modified_time(&path);
+
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+
+                let modified = modified_time(&path);
+
+                if modified.is_none() || modified == last_modified {
+                    continue;
+                }
+
+                let mut state = shared_state.lock();
+
+                // Reloading rescans every series, which shouldn't happen while an episode is being played
+                if matches!(
+                    state.input_state,
+                    InputState::Locked | InputState::PlayerAttached
+                ) {
+                    continue;
+                }
+
+                last_modified = modified;
+
+                let config = match Config::load() {
+                    Ok(config) => config,
+                    Err(err) => {
+                        state
+                            .get_mut()
+                            .log
+                            .push_error(&err.context("failed to reload config"));
+                        continue;
+                    }
+                };
+
+                let state = state.get_mut();
+                let missing = state.reload_config(config);
+
+                state.log.push(LogKind::Info, "reloaded config");
+
+                if !missing.is_empty() {
+                    state.log.push(
+                        LogKind::Info,
+                        format!(
+                            "episode folders not found for the following series:\n{}",
+                            missing.join("\n")
+                        ),
+                    );
+                }
+            }
+        });
+    }
+
+    /// Wait for the `playback` of `episode` of the selected series to finish, and mark it as watched if `start` allows it.
+    #[tracing::instrument(name = "playback", skip(self, playback))]
+    async fn track_episode_finish(
+        &self,
+        playback: PlaybackHandle,
+        start: WatchStart,
+        episode: u32,
+    ) -> Result<()> {
+        let started_at = Utc::now();
+
+        let result = playback
+            .wait(|progress_time| {
+                // The progress timer is only shown when the status is being updated
+                if start == WatchStart::UpdateStatus {
+                    let state = self.lock();
+                    state
+                        .events
+                        .send(StateEvent::StartedEpisode(progress_time))
+                        .ok();
+                }
+            })
+            .await
+            .context("waiting for episode to finish")?;
+
+        tracing::info!(
+            status = %result.status,
+            reached_progress = result.reached_progress,
+            "playback finished"
+        );
+
+        let mut state = self.lock();
+        let state = state.get_mut();
+
+        state.input_state.reset();
+        state.stream_finished = None;
+
+        let series = if let Some(series) = state.series.get_valid_sel_series_mut() {
+            series
+        } else {
+            return Ok(());
+        };
+
+        if let Err(err) = Hook::AfterPlay.run(series, episode, &state.config) {
+            state.log.push_error(&err);
+        }
+
+        if result.crashed {
+            let series_id = series.data.config.id;
+            state.queue_retry_prompt(series_id, episode, start, result.status);
+            return Ok(());
+        }
+
+        // Episodes played without changing the series status should never count towards progress
+        let completed =
+            start == WatchStart::UpdateStatus && episode_counts_as_watched(&result, &state.config);
+
+        if start == WatchStart::UpdateStatus && result.reached_progress && result.idle {
+            state.log.push(LogKind::Info, "episode not counted (idle)");
+        }
+
+        series
+            .save_playback_progress(episode, &result, completed, &state.config, &state.db)
+            .context("saving playback position")?;
+
+        if !completed {
+            return Ok(());
+        }
+
+        series
+            .episode_completed(&state.config, &state.db)
+            .context("marking episode as completed")?;
+
+        WatchRecord::new(&series.data, episode as i16, started_at, result.status)
+            .save(&state.db)
+            .context("recording watch history")?;
+
+        let mut hooks = vec![Hook::EpisodeCompleted];
+        let series_completed = series.is_completed();
+
+        if series_completed {
+            hooks.push(Hook::SeriesCompleted);
+        }
+
+        for hook in hooks {
+            if let Err(err) = hook.run(series, episode, &state.config) {
+                state.log.push_error(&err);
+            }
+        }
+
+        if series_completed {
+            if let Err(err) = state.queue_sequel_prompt() {
+                state.log.push_error(&err);
+            }
+        }
+
+        state.request_entry_sync();
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn lock(&self) -> MutexGuard<'_, ReactiveState> {
+        self.0.lock()
+    }
+
+    /// Lock the state to only access the database.
+    ///
+    /// Unlike `lock`, this will not cause the UI to be redrawn.
+    pub fn lock_db(&self) -> MappedMutexGuard<'_, Database> {
+        MutexGuard::map(self.lock(), |state| &mut state.state.db)
+    }
+}
+
+/// A sequel to offer to continue with after finishing the series before it.
+pub struct SequelPrompt {
+    /// The nickname of the series that was completed.
+    pub completed: String,
+    /// The config ID of the sequel.
+    pub sequel_id: i32,
+    /// The nickname of the sequel.
+    pub sequel: String,
+}
+
+/// A series that appears to number its creditless opening or ending as the first episode, to offer shifting its episodes.
+pub struct OffsetPrompt {
+    /// The config ID of the series.
+    pub series_id: i32,
+    pub nickname: String,
+    /// The filename of the episode that appears to be an opening or ending.
+    pub filename: String,
+}
+
+/// An episode whose player exited with an error right after it started, to offer playing it again.
+pub struct RetryPrompt {
+    /// The config ID of the series the episode belongs to.
+    pub series_id: i32,
+    pub episode: u32,
+    /// How the episode was started, so playing it again counts it the same way.
+    pub start: WatchStart,
+    /// The index of the fallback player that crashed, or None if it was the main player.
+    pub player: Option<usize>,
+    pub player_name: String,
+    pub status: ExitStatus,
+    /// The index and name of the next fallback player to try, if there are any left.
+    pub fallback: Option<(usize, String)>,
+}
+
+#[derive(Clone, Copy)]
+pub enum InputState {
+    Idle,
+    Locked,
+    /// The player has taken over the terminal, so the TUI should neither draw nor read input until it exits.
+    PlayerAttached,
+    FocusedOnMainPanel,
+    EnteringCommand,
+}
+
+impl InputState {
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::Idle
+    }
+}
+
+impl PartialEq for InputState {
+    fn eq(&self, other: &Self) -> bool {
+        mem::discriminant(self) == mem::discriminant(other)
+    }
+}
+
+/// The state carried between checks of the download client.
+#[derive(Default)]
+struct DownloadTracker {
+    config: DownloadConfig,
+    downloader: Option<Downloader>,
+    /// The last known progress of each torrent, by name.
+    progress: HashMap<String, f32>,
+    /// Whether the last check of the download client failed.
+    failing: bool,
+}
+
+/// The series folders being watched for changes.
+struct WatchedFolders {
+    watcher: RecommendedWatcher,
+    paths: HashSet<PathBuf>,
+    /// Whether the subfolders of each folder are being watched as well.
+    recursive: bool,
+}
+
+impl WatchedFolders {
+    fn new(watcher: RecommendedWatcher) -> Self {
+        Self {
+            watcher,
+            paths: HashSet::new(),
+            recursive: false,
+        }
+    }
+
+    /// Watch every folder in `paths`, and stop watching every folder that isn't in it.
+    ///
+    /// Folders that don't exist yet are tried again the next time this is called.
+    fn sync(&mut self, paths: HashSet<PathBuf>) {
+        for path in self.paths.difference(&paths) {
+            self.watcher.unwatch(path).ok();
+        }
+
+        let mode = if self.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+
+        self.paths = paths
+            .into_iter()
+            .filter(|path| self.paths.contains(path) || self.watcher.watch(path, mode).is_ok())
+            .collect();
+    }
+
+    fn unwatch_all(&mut self) {
+        for path in self.paths.drain() {
+            self.watcher.unwatch(&path).ok();
+        }
     }
 }
 
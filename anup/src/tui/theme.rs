@@ -0,0 +1,197 @@
+use crate::config::{ThemeConfig, ThemePreset};
+use anime::remote::Status;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use tui::style::{Color, Style};
+use tui::widgets::Block;
+use tui_utils::helpers::{block, style};
+
+static CURRENT: Lazy<RwLock<Theme>> =
+    Lazy::new(|| RwLock::new(Theme::preset(ThemePreset::Default)));
+
+/// The colors every component is drawn with.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// The color of the selection marker in lists.
+    pub selection: Color,
+    /// The color of the borders of selected inputs and panels.
+    pub focus: Color,
+    pub border: Color,
+    /// The color of disabled inputs, placeholders, and unfocused list selections.
+    pub inactive: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub info: Color,
+    pub watching: Color,
+    pub completed: Color,
+    pub on_hold: Color,
+    pub dropped: Color,
+    pub plan_to_watch: Color,
+}
+
+impl Theme {
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Default => Self {
+                selection: Color::Green,
+                focus: Color::Blue,
+                border: Color::Reset,
+                inactive: Color::DarkGray,
+                error: Color::Red,
+                warning: Color::Yellow,
+                info: Color::Blue,
+                watching: Color::Blue,
+                completed: Color::Green,
+                on_hold: Color::Yellow,
+                dropped: Color::Red,
+                plan_to_watch: Color::Gray,
+            },
+            ThemePreset::Solarized => {
+                let yellow = Color::Rgb(0xb5, 0x89, 0x00);
+                let orange = Color::Rgb(0xcb, 0x4b, 0x16);
+                let red = Color::Rgb(0xdc, 0x32, 0x2f);
+                let blue = Color::Rgb(0x26, 0x8b, 0xd2);
+                let cyan = Color::Rgb(0x2a, 0xa1, 0x98);
+                let green = Color::Rgb(0x85, 0x99, 0x00);
+                let base01 = Color::Rgb(0x58, 0x6e, 0x75);
+                let base0 = Color::Rgb(0x83, 0x94, 0x96);
+
+                Self {
+                    selection: green,
+                    focus: blue,
+                    border: base01,
+                    inactive: base01,
+                    error: red,
+                    warning: orange,
+                    info: cyan,
+                    watching: blue,
+                    completed: green,
+                    on_hold: yellow,
+                    dropped: red,
+                    plan_to_watch: base0,
+                }
+            }
+            ThemePreset::HighContrast => Self {
+                selection: Color::LightYellow,
+                focus: Color::LightCyan,
+                border: Color::White,
+                inactive: Color::Gray,
+                error: Color::LightRed,
+                warning: Color::LightYellow,
+                info: Color::LightCyan,
+                watching: Color::LightCyan,
+                completed: Color::LightGreen,
+                on_hold: Color::LightYellow,
+                dropped: Color::LightRed,
+                plan_to_watch: Color::White,
+            },
+        }
+    }
+
+    /// Create a theme from the preset in `config`, with any colors it overrides applied.
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        let mut theme = Self::preset(config.preset);
+
+        macro_rules! apply_overrides {
+            ($($field:ident)+) => {
+                $(
+                if let Some(color) = config.$field {
+                    theme.$field = color.0;
+                }
+                )+
+            };
+        }
+
+        apply_overrides!(
+            selection focus border inactive error warning info
+            watching completed on_hold dropped plan_to_watch
+        );
+
+        theme
+    }
+
+    pub fn status(&self, status: Status) -> Color {
+        match status {
+            Status::Watching | Status::Rewatching => self.watching,
+            Status::Completed => self.completed,
+            Status::OnHold => self.on_hold,
+            Status::Dropped => self.dropped,
+            Status::PlanToWatch => self.plan_to_watch,
+        }
+    }
+}
+
+/// Returns the theme currently in use.
+#[inline]
+pub fn get() -> Theme {
+    *CURRENT.read()
+}
+
+/// Set the theme every component will be drawn with from `config`.
+pub fn apply(config: &ThemeConfig) {
+    *CURRENT.write() = Theme::from_config(config);
+}
+
+/// Returns a block with borders drawn in the theme's border color.
+pub fn block<'a, T>(title: T) -> Block<'a>
+where
+    T: Into<Option<&'a str>>,
+{
+    block::with_borders(title).border_style(style::fg(get().border))
+}
+
+/// Returns a block with borders that use the theme's focus color when `selected` is true.
+pub fn selectable_block<'a, T>(title: T, selected: bool) -> Block<'a>
+where
+    T: Into<Option<&'a str>>,
+{
+    let theme = get();
+    let color = if selected { theme.focus } else { theme.border };
+
+    block::with_borders(title).border_style(style::fg(color))
+}
+
+/// Returns the style for the selection marker of a list that may not be focused.
+pub fn list_selector(focused: bool) -> Style {
+    let theme = get();
+    let color = if focused {
+        theme.selection
+    } else {
+        theme.inactive
+    };
+
+    style::italic().fg(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ThemeColor;
+    use std::convert::TryInto;
+
+    #[test]
+    fn overrides_replace_preset_colors() {
+        let config = ThemeConfig {
+            preset: ThemePreset::HighContrast,
+            error: Some(ThemeColor(Color::Magenta)),
+            ..ThemeConfig::default()
+        };
+
+        let theme = Theme::from_config(&config);
+        let preset = Theme::preset(ThemePreset::HighContrast);
+
+        assert_eq!(theme.error, Color::Magenta);
+        assert_eq!(theme.selection, preset.selection);
+    }
+
+    #[test]
+    fn color_parsing() {
+        let parse = |value: &str| value.try_into().ok().map(|color: ThemeColor| color.0);
+
+        assert_eq!(parse("LightBlue"), Some(Color::LightBlue));
+        assert_eq!(parse("#268bd2"), Some(Color::Rgb(0x26, 0x8b, 0xd2)));
+        assert_eq!(parse("208"), Some(Color::Indexed(208)));
+        assert_eq!(parse("#12345"), None);
+        assert_eq!(parse("blurple"), None);
+    }
+}
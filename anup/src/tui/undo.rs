@@ -0,0 +1,174 @@
+use super::component::prompt::command::Command;
+use crate::series::config::SeriesConfig;
+use crate::series::deleted::DeletedSeries;
+use crate::series::entry::SeriesEntry;
+use crate::series::info::SeriesInfo;
+use crate::series::LoadedSeries;
+use std::collections::VecDeque;
+
+/// A change made to a series that can be reverted.
+pub enum UndoAction {
+    /// The list entry of a series was modified. Holds the entry as it was before the change.
+    Entry(SeriesEntry),
+    /// The config of a series was modified. Holds the config as it was before the change.
+    Config(SeriesConfig),
+    /// The list entry and info of a series were replaced with the ones from the remote service. Holds both as they were before the sync.
+    Sync(SeriesEntry, SeriesInfo),
+    /// A series was deleted. Holds it along with every row that was deleted with it.
+    Delete(DeletedSeries),
+    /// The same change was made to several marked series at once.
+    Batch(Vec<UndoAction>),
+}
+
+impl UndoAction {
+    /// Takes a snapshot of the part of `series` that `command` is about to modify.
+    ///
+    /// Returns `None` if the command doesn't change anything that can be reverted.
+    pub fn before_command(command: &Command, series: &LoadedSeries) -> Option<Self> {
+        let series = match series {
+            LoadedSeries::Complete(series) => series,
+            LoadedSeries::Partial(_, _) | LoadedSeries::Unscanned(_) | LoadedSeries::None(_, _) => {
+                return None
+            }
+        };
+
+        match command {
+            Command::PlayerArgs(_)
+            | Command::EpisodeOffset(_)
+            | Command::EpisodeCategory(_)
+            | Command::AbsoluteNumbering(_)
+            | Command::Feed(_)
+            | Command::LocalRewatch(_)
+            | Command::SubLang(_)
+            | Command::AudioLang(_)
+            | Command::SubFiles(_)
+            | Command::EpisodeLength(_) => Some(Self::Config(series.data.config.clone())),
+            // The progress of a local rewatch is part of the config of the series
+            Command::Progress(_) if series.data.config.local_rewatch.is_some() => {
+                Some(Self::Config(series.data.config.clone()))
+            }
+            Command::Progress(_) | Command::Score(_) | Command::Status(_) => {
+                Some(Self::Entry(series.data.entry.clone()))
+            }
+            Command::SyncFromRemote => Some(Self::Sync(
+                series.data.entry.clone(),
+                series.data.info.clone(),
+            )),
+            Command::SyncToRemote
+            | Command::RelinkSeasons
+            | Command::MergedSeasons(_)
+            | Command::Prefetch
+            | Command::Undo
+            | Command::Next
+            | Command::Rename(_)
+            | Command::Alias(_)
+            | Command::Synonym(_)
+            | Command::Stream(_)
+            | Command::AddAll
+            | Command::Sort(_)
+            | Command::Filter(_)
+            | Command::Tag(_) => None,
+        }
+    }
+}
+
+/// A bounded stack of changes that can be reverted, with the most recent change on top.
+#[derive(Default)]
+pub struct UndoStack(VecDeque<UndoAction>);
+
+impl UndoStack {
+    /// The maximum number of changes that can be reverted.
+    pub const MAX_ACTIONS: usize = 20;
+
+    /// Push a new action onto the stack, forgetting the oldest one if the stack is full.
+    pub fn push(&mut self, action: UndoAction) {
+        if self.0.len() >= Self::MAX_ACTIONS {
+            self.0.pop_front();
+        }
+
+        self.0.push_back(action);
+    }
+
+    /// Take the most recent action off of the stack.
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<UndoAction> {
+        self.0.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use anime::remote::Status;
+
+    fn pop_entry(stack: &mut UndoStack) -> SeriesEntry {
+        match stack.pop() {
+            Some(UndoAction::Entry(entry)) => entry,
+            Some(_) => panic!("expected entry undo action"),
+            None => panic!("undo stack is empty"),
+        }
+    }
+
+    #[test]
+    fn undo_status_change() {
+        let config = Config::default();
+        let mut stack = UndoStack::default();
+        let mut entry = SeriesEntry::from(1);
+
+        stack.push(UndoAction::Entry(entry.clone()));
+        entry.set_status(Status::Dropped, &config);
+
+        assert_eq!(entry.status(), Status::Dropped);
+        assert!(entry.end_date().is_some());
+
+        entry.restore(pop_entry(&mut stack));
+
+        assert_eq!(entry.status(), Status::PlanToWatch);
+        assert!(entry.end_date().is_none());
+        assert!(entry.needs_sync());
+        assert!(stack.pop().is_none());
+    }
+
+    #[test]
+    fn undo_progress_bump() {
+        let mut stack = UndoStack::default();
+        let mut entry = SeriesEntry::from(1);
+
+        for _ in 0..2 {
+            stack.push(UndoAction::Entry(entry.clone()));
+            entry.set_watched_episodes(entry.watched_episodes() + 1);
+        }
+
+        assert_eq!(entry.watched_episodes(), 2);
+
+        entry.restore(pop_entry(&mut stack));
+        assert_eq!(entry.watched_episodes(), 1);
+
+        entry.restore(pop_entry(&mut stack));
+        assert_eq!(entry.watched_episodes(), 0);
+        assert!(entry.needs_sync());
+    }
+
+    #[test]
+    fn undo_stack_is_bounded() {
+        let mut stack = UndoStack::default();
+        let mut entry = SeriesEntry::from(1);
+
+        for _ in 0..UndoStack::MAX_ACTIONS + 5 {
+            stack.push(UndoAction::Entry(entry.clone()));
+            entry.set_watched_episodes(entry.watched_episodes() + 1);
+        }
+
+        let mut remaining = 0;
+        let mut oldest = None;
+
+        while let Some(UndoAction::Entry(entry)) = stack.pop() {
+            remaining += 1;
+            oldest = Some(entry);
+        }
+
+        assert_eq!(remaining, UndoStack::MAX_ACTIONS);
+        assert_eq!(oldest.map(|entry| entry.watched_episodes()), Some(5));
+    }
+}
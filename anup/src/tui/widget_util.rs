@@ -0,0 +1,102 @@
+use crate::tui::theme;
+use anime::remote::Status;
+use chrono::NaiveDate;
+use tui::style::{Color, Modifier, Style};
+use tui::text::Span;
+use tui::widgets::{Gauge, Sparkline};
+use tui_utils::helpers::style;
+
+/// Returns a gauge filled to the portion of `total` episodes that have been `watched`, in the color of `status`.
+///
+/// Series with an unknown number of episodes are shown as empty.
+pub fn progress_gauge<'a, S>(watched: u16, total: u16, status: Status, label: S) -> Gauge<'a>
+where
+    S: Into<Span<'a>>,
+{
+    let ratio = if total > 0 {
+        (f64::from(watched) / f64::from(total)).min(1.0)
+    } else {
+        0.0
+    };
+
+    Gauge::default()
+        .gauge_style(style::fg(theme::get().status(status)))
+        .ratio(ratio)
+        .label(label)
+        .use_unicode(true)
+}
+
+/// Returns `text` drawn as a badge with the color of `status` as its background.
+pub fn status_badge<'a, S>(text: S, status: Status) -> Span<'a>
+where
+    S: Into<String>,
+{
+    badge(text.into(), theme::get().status(status))
+}
+
+/// Returns `tag` drawn as a chip in the theme's info color.
+pub fn tag_chip<'a>(tag: &str) -> Span<'a> {
+    badge(format!("#{}", tag), theme::get().info)
+}
+
+fn badge<'a>(text: String, color: Color) -> Span<'a> {
+    let style = Style::default()
+        .fg(Color::Black)
+        .bg(color)
+        .add_modifier(Modifier::BOLD);
+
+    Span::styled(format!(" {} ", text), style)
+}
+
+/// Returns a sparkline of `counts` in the theme's info color.
+pub fn activity_sparkline(counts: &[u64]) -> Sparkline {
+    Sparkline::default()
+        .data(counts)
+        .style(style::fg(theme::get().info))
+}
+
+/// Count how many of `dates` fall on each of the `days` days ending with `today`.
+///
+/// The first count is the oldest day, which makes the result suitable for [`activity_sparkline`].
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn daily_counts<I>(dates: I, days: usize, today: NaiveDate) -> Vec<u64>
+where
+    I: IntoIterator<Item = NaiveDate>,
+{
+    let mut counts = vec![0; days];
+
+    for date in dates {
+        let days_ago = (today - date).num_days();
+
+        if days_ago < 0 || days_ago >= days as i64 {
+            continue;
+        }
+
+        counts[days - 1 - days_ago as usize] += 1;
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn daily_counts_by_day() {
+        let today = NaiveDate::from_ymd(2021, 3, 10);
+
+        let dates = [
+            today,
+            today,
+            NaiveDate::from_ymd(2021, 3, 9),
+            NaiveDate::from_ymd(2021, 3, 7),
+            // Too old to be counted
+            NaiveDate::from_ymd(2021, 3, 6),
+            // After today
+            NaiveDate::from_ymd(2021, 3, 11),
+        ];
+
+        assert_eq!(daily_counts(dates, 4, today), vec![1, 0, 1, 2]);
+    }
+}
@@ -1,5 +1,10 @@
+use crate::err;
 use crate::file::{FileFormat, SaveDir, SerializedFile};
+use crate::remote::RemoteLogin;
+#[cfg(feature = "keyring")]
+use crate::token_store;
 use anime::remote::{AccessToken, Remote};
+use anyhow::{Context, Result};
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tui_utils::list::EnumListItems;
@@ -13,6 +18,8 @@ use tui_utils::list::EnumListItems;
 #[serde(rename_all = "lowercase")]
 pub enum RemoteType {
     AniList,
+    Kitsu,
+    Mal,
 }
 
 impl RemoteType {
@@ -20,13 +27,15 @@ impl RemoteType {
     pub fn as_str(self) -> &'static str {
         match self {
             Self::AniList => "AniList",
+            Self::Kitsu => "Kitsu",
+            Self::Mal => "MyAnimeList",
         }
     }
 }
 
 impl EnumListItems for RemoteType {
     fn items<'a>() -> &'a [Self] {
-        &[Self::AniList]
+        &[Self::AniList, Self::Kitsu, Self::Mal]
     }
 }
 
@@ -52,17 +61,33 @@ impl UserInfo {
 
     pub fn is_logged_in(&self, remote: &Remote) -> bool {
         use anime::remote::anilist::AniList;
+        use anime::remote::kitsu::Kitsu;
+        use anime::remote::mal::Mal;
 
         match (self.service, remote) {
+            (_, Remote::MultiRemote(multi)) => {
+                multi.remotes().any(|remote| self.is_logged_in(remote))
+            }
             (RemoteType::AniList, Remote::AniList(anilist)) => match anilist {
                 AniList::Authenticated(auth) => auth.user.name == self.username,
-                AniList::Unauthenticated => false,
+                AniList::Unauthenticated(_) => false,
+            },
+            (RemoteType::Kitsu, Remote::Kitsu(kitsu)) => match kitsu {
+                Kitsu::Authenticated(auth) => auth.user.name == self.username,
+                Kitsu::Unauthenticated(_) => false,
             },
-            (RemoteType::AniList, Remote::Offline(_)) => false,
+            (RemoteType::Mal, Remote::Mal(mal)) => match mal {
+                Mal::Authenticated(auth) => auth.user.name == self.username,
+                Mal::Unauthenticated(_) => false,
+            },
+            _ => false,
         }
     }
 }
 
+/// How many days before a user's token expires to start warning about it.
+pub const EXPIRY_WARNING_DAYS: i64 = 7;
+
 pub type UserMap = HashMap<UserInfo, AccessToken>;
 
 /// A map containing all users along with the last used one.
@@ -70,28 +95,70 @@ pub type UserMap = HashMap<UserInfo, AccessToken>;
 pub struct Users {
     users: UserMap,
     pub last_used: Option<UserInfo>,
+    /// When the token of each user expires, in seconds since the Unix epoch.
+    ///
+    /// Users whose remote service didn't say when their token expires aren't in here.
+    expiries: HashMap<UserInfo, i64>,
 }
 
 impl Users {
     #[cfg(test)]
     pub fn new() -> Self {
-        Self {
-            users: UserMap::new(),
-            last_used: None,
-        }
+        Self::default()
     }
 
     /// Adds a new (unique) `user` to the user map and sets the last used user to `user`.
-    pub fn add_and_set_last(&mut self, user: UserInfo, token: AccessToken) {
+    ///
+    /// `expires_at` is when `token` expires, in seconds since the Unix epoch.
+    pub fn add_and_set_last(
+        &mut self,
+        user: UserInfo,
+        token: AccessToken,
+        expires_at: Option<i64>,
+    ) {
+        match expires_at {
+            Some(expires_at) => self.expiries.insert(user.clone(), expires_at),
+            None => self.expiries.remove(&user),
+        };
+
         self.last_used = Some(user.clone());
         self.users.insert(user, token);
     }
 
+    /// Returns when the token of `user` expires, in seconds since the Unix epoch.
+    #[inline(always)]
+    pub fn expiry(&self, user: &UserInfo) -> Option<i64> {
+        self.expiries.get(user).copied()
+    }
+
+    /// Returns how many whole days are left until the token of `user` expires, relative to `now`.
+    ///
+    /// A negative number of days means the token has already expired.
+    pub fn days_until_expiry(&self, user: &UserInfo, now: i64) -> Option<i64> {
+        const SECS_PER_DAY: i64 = 60 * 60 * 24;
+
+        let expiry = self.expiry(user)?;
+        let secs_left = expiry - now;
+
+        if secs_left < 0 {
+            Some(-1)
+        } else {
+            Some(secs_left / SECS_PER_DAY)
+        }
+    }
+
     /// Removes the specified `user` from the user map.
     ///
     /// This also unsets the last used user if it was set to `user`.
     pub fn remove(&mut self, user: &UserInfo) {
         self.users.remove(user);
+        self.expiries.remove(user);
+
+        // The user is already gone, so a token left behind in the keyring isn't worth failing over
+        #[cfg(feature = "keyring")]
+        if let Err(err) = token_store::remove(user) {
+            tracing::warn!("failed to remove token of {}: {:#}", user.username, err);
+        }
 
         if let Some(last) = &self.last_used {
             if user == last {
@@ -100,16 +167,28 @@ impl Users {
         }
     }
 
-    /// Returns the last used user's access token if it was set.
-    pub fn take_last_used_token(mut self) -> Option<AccessToken> {
-        let last = self.last_used?;
-        self.users.remove(&last)
+    /// Returns the login for `user`, along with the logins of every user linked to it in `links`.
+    ///
+    /// Linked users that no longer exist are skipped.
+    pub fn logins(
+        &self,
+        user: &UserInfo,
+        links: &UserLinks,
+    ) -> Option<(RemoteLogin, Vec<RemoteLogin>)> {
+        let login = |user: &UserInfo| {
+            let token = self.users.get(user)?;
+            Some(RemoteLogin::new(user.to_owned(), token.to_owned()))
+        };
+
+        let linked = links.linked_to(user).iter().filter_map(login).collect();
+
+        Some((login(user)?, linked))
     }
 
-    /// Consumes the `Users` struct and returns the last used user.
-    pub fn take_last_used_user(mut self) -> Option<(UserInfo, AccessToken)> {
-        let last = self.last_used?;
-        self.users.remove_entry(&last)
+    /// Returns the logins for the last used user, if it was set.
+    pub fn last_used_logins(&self, links: &UserLinks) -> Option<(RemoteLogin, Vec<RemoteLogin>)> {
+        let last = self.last_used.as_ref()?;
+        self.logins(last, links)
     }
 
     #[inline(always)]
@@ -121,6 +200,50 @@ impl Users {
     pub fn len(&self) -> usize {
         self.users.len()
     }
+
+    /// Fill in the token of each user from the keyring.
+    ///
+    /// Tokens that are still in the users file were saved before the keyring was used,
+    /// so they're moved into the keyring and removed from the file.
+    #[cfg(feature = "keyring")]
+    fn with_stored_tokens(mut self) -> Result<Self> {
+        let mut needs_migration = false;
+
+        for (user, token) in &mut self.users {
+            let in_file = token.decode().map_or(false, |token| !token.is_empty());
+
+            if in_file {
+                needs_migration = true;
+                continue;
+            }
+
+            if let Some(stored) = token_store::load(user)? {
+                *token = stored;
+            }
+        }
+
+        if needs_migration {
+            self.save().context("moving tokens into keyring")?;
+        }
+
+        Ok(self)
+    }
+
+    /// Returns a copy of the users with every token left out, for saving alongside a keyring.
+    #[cfg(feature = "keyring")]
+    fn without_tokens(&self) -> Self {
+        let users = self
+            .users
+            .keys()
+            .map(|user| (user.clone(), AccessToken::default()))
+            .collect();
+
+        Self {
+            users,
+            last_used: self.last_used.clone(),
+            expiries: self.expiries.clone(),
+        }
+    }
 }
 
 impl SerializedFile for Users {
@@ -135,6 +258,112 @@ impl SerializedFile for Users {
     fn format() -> FileFormat {
         FileFormat::Binary
     }
+
+    fn load() -> Result<Self> {
+        let path = Self::validated_save_path().context("getting path")?;
+
+        let users: Self = match Self::format().deserialize(&path) {
+            Ok(users) => users,
+            Err(err) if err::is_file_nonexistant(&err) => {
+                return Err(err).context("deserializing file")
+            }
+            // Users saved before token expiry times were stored have to be read without them
+            Err(err) => Self::format()
+                .deserialize::<_, LegacyUsers>(&path)
+                .map(Into::into)
+                .map_err(|_| err)
+                .context("deserializing file")?,
+        };
+
+        #[cfg(feature = "keyring")]
+        let users = users.with_stored_tokens()?;
+
+        Ok(users)
+    }
+
+    #[cfg(feature = "keyring")]
+    fn save(&self) -> Result<()> {
+        for (user, token) in &self.users {
+            token_store::store(user, token)
+                .with_context(|| format!("storing token of {}", user.username))?;
+        }
+
+        let path = Self::validated_save_path()?;
+        Self::format().serialize(&self.without_tokens(), path)
+    }
+}
+
+/// The layout users were saved in before token expiry times were stored.
+#[derive(Deserialize, Serialize)]
+struct LegacyUsers {
+    users: UserMap,
+    last_used: Option<UserInfo>,
+}
+
+impl From<LegacyUsers> for Users {
+    fn from(legacy: LegacyUsers) -> Self {
+        Self {
+            users: legacy.users,
+            last_used: legacy.last_used,
+            expiries: HashMap::new(),
+        }
+    }
+}
+
+/// Users that have other users linked to them, so list updates can be sent to all of them at once.
+#[derive(Default, Deserialize, Serialize)]
+pub struct UserLinks(HashMap<UserInfo, Vec<UserInfo>>);
+
+impl UserLinks {
+    /// Returns all users that are linked to `user`.
+    pub fn linked_to(&self, user: &UserInfo) -> &[UserInfo] {
+        self.0.get(user).map_or(&[], Vec::as_slice)
+    }
+
+    /// Links `other` to `user`, or unlinks it if it is already linked.
+    ///
+    /// Returns true if `other` is now linked to `user`.
+    pub fn toggle(&mut self, user: &UserInfo, other: UserInfo) -> bool {
+        let linked = self.0.entry(user.to_owned()).or_default();
+
+        if let Some(pos) = linked.iter().position(|linked| *linked == other) {
+            linked.remove(pos);
+
+            if linked.is_empty() {
+                self.0.remove(user);
+            }
+
+            false
+        } else {
+            linked.push(other);
+            true
+        }
+    }
+
+    /// Removes all links to and from the specified `user`.
+    pub fn remove(&mut self, user: &UserInfo) {
+        self.0.remove(user);
+
+        for linked in self.0.values_mut() {
+            linked.retain(|linked| linked != user);
+        }
+
+        self.0.retain(|_, linked| !linked.is_empty());
+    }
+}
+
+impl SerializedFile for UserLinks {
+    fn filename() -> &'static str {
+        "user_links"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Binary
+    }
 }
 
 #[cfg(test)]
@@ -148,14 +377,74 @@ mod tests {
         let user1 = UserInfo::new(RemoteType::AniList, "User 1");
         let user1_duplicate = user1.clone();
 
-        users.add_and_set_last(user1, AccessToken::encode("token1"));
-        users.add_and_set_last(user1_duplicate, AccessToken::encode("token2"));
+        users.add_and_set_last(user1, AccessToken::encode("token1"), None);
+        users.add_and_set_last(user1_duplicate, AccessToken::encode("token2"), None);
 
         assert_eq!(users.len(), 1);
 
         let user2 = UserInfo::new(RemoteType::AniList, "User 2");
-        users.add_and_set_last(user2, AccessToken::encode("token3"));
+        users.add_and_set_last(user2, AccessToken::encode("token3"), None);
 
         assert_eq!(users.len(), 2);
     }
+
+    #[test]
+    fn token_expiries() {
+        let mut users = Users::new();
+        let user = UserInfo::new(RemoteType::Mal, "User 1");
+
+        users.add_and_set_last(user.clone(), AccessToken::encode("token1"), Some(200_000));
+        assert_eq!(users.expiry(&user), Some(200_000));
+        assert_eq!(users.days_until_expiry(&user, 0), Some(2));
+        assert_eq!(users.days_until_expiry(&user, 199_999), Some(0));
+        assert_eq!(users.days_until_expiry(&user, 200_001), Some(-1));
+
+        // A new token without an expiry shouldn't keep the old token's expiry around
+        users.add_and_set_last(user.clone(), AccessToken::encode("token2"), None);
+        assert_eq!(users.expiry(&user), None);
+
+        users.add_and_set_last(user.clone(), AccessToken::encode("token3"), Some(2000));
+        users.remove(&user);
+        assert_eq!(users.expiry(&user), None);
+    }
+
+    #[test]
+    fn decode_legacy_users() {
+        let user = UserInfo::new(RemoteType::AniList, "User 1");
+        let mut legacy_users = UserMap::new();
+        legacy_users.insert(user.clone(), AccessToken::encode("token1"));
+
+        let legacy = LegacyUsers {
+            users: legacy_users,
+            last_used: Some(user.clone()),
+        };
+
+        let bytes = bincode::serialize(&legacy).unwrap();
+        assert!(bincode::deserialize::<Users>(&bytes).is_err());
+
+        let users: Users = bincode::deserialize::<LegacyUsers>(&bytes).unwrap().into();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users.expiry(&user), None);
+        assert!(users.last_used == Some(user));
+    }
+
+    #[test]
+    fn toggle_and_remove_user_links() {
+        let mut links = UserLinks::default();
+
+        let primary = UserInfo::new(RemoteType::AniList, "User 1");
+        let mal = UserInfo::new(RemoteType::Mal, "User 1");
+        let kitsu = UserInfo::new(RemoteType::Kitsu, "User 1");
+
+        assert!(links.toggle(&primary, mal.clone()));
+        assert!(links.toggle(&primary, kitsu.clone()));
+        assert_eq!(links.linked_to(&primary).len(), 2);
+
+        assert!(!links.toggle(&primary, kitsu));
+        assert_eq!(links.linked_to(&primary).len(), 1);
+
+        links.remove(&mal);
+        assert!(links.linked_to(&primary).is_empty());
+        assert!(links.0.is_empty());
+    }
 }
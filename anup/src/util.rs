@@ -5,6 +5,14 @@ use std::{
 };
 use tokio::task;
 
+/// The program that opens URLs in the default browser.
+#[cfg(target_os = "linux")]
+pub const URL_OPENER: &str = "xdg-open";
+#[cfg(target_os = "macos")]
+pub const URL_OPENER: &str = "open";
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+compile_error!("must specify URL opener for this platform");
+
 #[macro_export]
 macro_rules! try_opt_r {
     ($x:expr) => {
@@ -37,6 +45,15 @@ where
     format!("{:02}:{:02}H", hours, minutes)
 }
 
+/// Formats `total_mins` as hours and minutes, such as `1h 24m`, or just minutes if it's less than an hour.
+pub fn hours_mins_str(total_mins: i64) -> String {
+    if total_mins >= 60 {
+        format!("{}h {}m", total_mins / 60, total_mins % 60)
+    } else {
+        format!("{}m", total_mins)
+    }
+}
+
 pub type ArcMutex<T> = Arc<Mutex<T>>;
 
 pub fn arc_mutex<T>(value: T) -> ArcMutex<T> {